@@ -0,0 +1,13 @@
+//! Feeds arbitrary source code into the lexer, which has to report errors for invalid input instead of panicking.
+
+#![no_main]
+
+use std::sync::Arc;
+
+use fortytwolang::{lexer::Lexer, source::Source};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source_code: &str| {
+	let source = Arc::new(Source::new("fuzz.ftl".to_owned(), source_code.to_owned()));
+	for _token in Lexer::new(source.iter()) {}
+});
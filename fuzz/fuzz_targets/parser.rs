@@ -0,0 +1,16 @@
+//! Feeds the tokens of arbitrary source code into the parser, which has to report errors for invalid input instead of
+//! panicking. Tokens the lexer rejects are skipped, so that the parser also sees token sequences that cannot be lexed
+//! from any source code.
+
+#![no_main]
+
+use std::sync::Arc;
+
+use fortytwolang::{lexer::Lexer, parser::Parser, source::Source};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source_code: &str| {
+	let source = Arc::new(Source::new("fuzz.ftl".to_owned(), source_code.to_owned()));
+	let tokens = Lexer::new(source.iter()).filter_map(Result::ok);
+	let _ = Parser::new(tokens).parse();
+});
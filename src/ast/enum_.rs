@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+
+use crate::{
+	ast::{
+		expression::{Number, NumberKind},
+		statement::{ConstDeclaration, DataType, LValue},
+		visit::{self, VisitorMut},
+		Expression, Node,
+	},
+	module::{qualified_name, qualify},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// A type whose values are one of the named variants.
+///
+/// # Example
+///
+/// ```text
+/// enum Color { Red, Green, Blue }
+/// ```
+///
+/// A variant is referred to by its qualified name like `Color.Red`, which is a constant of the type `Color`. Enums are
+/// not integers, but backends represent the variants by their index, i.e. `Color.Green` by 1.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Enum {
+	/// The name of the enum.
+	pub name: PositionContainer<Symbol>,
+	/// The variants in the order of their declaration.
+	pub variants: Vec<Variant>,
+	/// The position from `enum` to the closing curly brace.
+	pub position: SourcePositionRange,
+}
+
+/// A variant of an [`Enum`].
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Variant {
+	/// The name of the variant, without the name of the enum.
+	pub name: PositionContainer<Symbol>,
+	/// The comment in the line(s) before the variant, if any.
+	pub comment: Option<PositionContainer<String>>,
+}
+
+impl Enum {
+	/// The qualified name of the `variant`, like `Color.Red`.
+	pub fn variant_name(&self, variant: &Variant) -> PositionContainer<Symbol> {
+		PositionContainer::new(qualified_name(&self.name, &variant.name), variant.name.position.clone())
+	}
+
+	/// The variants as constants like `const Color.Green: Color = 1`, for backends which represent enums by integers.
+	pub fn constants(&self) -> Vec<ConstDeclaration> {
+		let data_type = PositionContainer::new(DataType::Enum(self.name.value), self.name.position.clone());
+		self.variants
+			.iter()
+			.enumerate()
+			.map(|(index, variant)| {
+				let position = variant.name.position.clone();
+				ConstDeclaration {
+					name: self.variant_name(variant),
+					data_type: Some(data_type.clone()),
+					value: Expression::Number(Number::new(NumberKind::Int(index as i64), position.clone())),
+					position,
+				}
+			})
+			.collect()
+	}
+}
+
+/// Resolves references to the `enums`, which the parser cannot distinguish from other names: A type like `Color`,
+/// which is parsed as struct, becomes [`DataType::Enum`], and a variant like `Color.Red`, which is parsed as field
+/// access, becomes a variable with the qualified name `Color.Red`.
+///
+/// Enums of imported modules are referred to by qualified names, too, like `math.Sign.Negative`.
+pub(crate) struct EnumResolver<'a> {
+	pub enums: &'a HashSet<Symbol>,
+}
+
+impl EnumResolver<'_> {
+	/// The names of all enums declared by the `nodes`.
+	pub fn declared(nodes: &[Node]) -> HashSet<Symbol> {
+		nodes
+			.iter()
+			.filter_map(|node| match node {
+				Node::Enum(enum_) => Some(enum_.name.value),
+				_ => None,
+			})
+			.collect()
+	}
+
+	fn data_type(&self, data_type: &mut DataType) {
+		match data_type {
+			DataType::Struct(name) if self.enums.contains(name) => *data_type = DataType::Enum(*name),
+			DataType::Generic { type_arguments, .. } => {
+				type_arguments.iter_mut().for_each(|type_argument| self.data_type(type_argument))
+			},
+			DataType::Pointer(pointee) => self.data_type(&mut pointee.value),
+			DataType::Array { element, .. } => self.data_type(element),
+			DataType::Basic(_) | DataType::Struct(_) | DataType::Enum(_) | DataType::Null => (),
+		}
+	}
+
+	/// The name of the enum that the `base` of a field access refers to, if any.
+	fn enum_name(&self, base: &Expression) -> Option<PositionContainer<Symbol>> {
+		let name = match base {
+			Expression::Variable(name) => name.clone(),
+			// A module like `math` in `math.Sign.Negative`
+			Expression::FieldAccess(field_access) => match &*field_access.base {
+				Expression::Variable(module) => qualify(module.clone(), field_access.field.clone()),
+				_ => return None,
+			},
+			_ => return None,
+		};
+		self.enums.contains(&name.value).then_some(name)
+	}
+
+	/// The same as [`Self::enum_name`], for the base of an assignment target.
+	fn lvalue_enum_name(&self, base: &LValue) -> Option<PositionContainer<Symbol>> {
+		let name = match base {
+			LValue::Variable(name) => name.clone(),
+			LValue::FieldAccess { base, field } => match &**base {
+				LValue::Variable(module) => qualify(module.clone(), field.clone()),
+				_ => return None,
+			},
+			LValue::Index { .. } | LValue::Dereference { .. } => return None,
+		};
+		self.enums.contains(&name.value).then_some(name)
+	}
+}
+
+impl VisitorMut for EnumResolver<'_> {
+	fn visit_lvalue(&mut self, lvalue: &mut LValue) {
+		// Assigning to a variant is an error, which should mention the variant
+		if let LValue::FieldAccess { base, field } = lvalue {
+			if let Some(enum_name) = self.lvalue_enum_name(base) {
+				*lvalue = LValue::Variable(qualify(enum_name, field.clone()));
+				return;
+			}
+		}
+		visit::walk_lvalue_mut(self, lvalue);
+	}
+
+	fn visit_expression(&mut self, expression: &mut Expression) {
+		if let Expression::FieldAccess(field_access) = expression {
+			if let Some(enum_name) = self.enum_name(&field_access.base) {
+				*expression = Expression::Variable(qualify(enum_name, field_access.field.clone()));
+				return;
+			}
+		}
+		visit::walk_expression_mut(self, expression);
+	}
+
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		self.data_type(&mut data_type.value);
+	}
+}
@@ -0,0 +1,11 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// Creation of an array from its elements, like `[1, 2, 3]`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArrayLiteral {
+	/// The elements of the array.
+	pub elements: Vec<Expression>,
+	/// The position from the opening to the closing square bracket.
+	pub position: SourcePositionRange,
+}
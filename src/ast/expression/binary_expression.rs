@@ -6,6 +6,7 @@ use crate::{
 
 /// A binary expression of the form `lhs op rhs` like `40 + 2`.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BinaryExpression {
 	/// The left hand side.
 	pub lhs: Box<Expression>,
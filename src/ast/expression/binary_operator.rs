@@ -4,11 +4,16 @@ use std::{cmp::Ordering, collections::HashMap};
 //  operator binds stronger with rhs than with current, let it go with rhs`
 /// A binary operator connecting a lhs and a rhs.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
 	/// Comparison if lhs is smaller/less than rhs (`<`).
 	Less,
 	/// Comparison if lhs is bigger/greater than rhs (`>`).
 	Greater,
+	/// Comparison if lhs is smaller/less than or equal to rhs (`<=`).
+	LessEqual,
+	/// Comparison if lhs is bigger/greater than or equal to rhs (`>=`).
+	GreaterEqual,
 	/// Addition (`+`).
 	Add,
 	/// Subtraction (`-`).
@@ -17,8 +22,60 @@ pub enum BinaryOperator {
 	Multiply,
 	/// Division (`/`)
 	Divide,
+	/// Exponentiation (`**`), which is right-associative, i.e. `a ** b ** c` is `a ** (b ** c)`.
+	Power,
+	/// Bitwise OR (`|`).
+	BitOr,
+	/// Bitwise AND (`&`).
+	BitAnd,
+	/// Bitwise XOR (`^`).
+	BitXor,
+	/// Shift lhs to the left by rhs bits (`<<`).
+	ShiftLeft,
+	/// Shift lhs to the right by rhs bits, preserving the sign (`>>`).
+	ShiftRight,
 	Equal,
 	NotEqual,
+	/// Logical AND (`and`). The rhs is only evaluated if the lhs is true.
+	LogicalAnd,
+	/// Logical OR (`or`). The rhs is only evaluated if the lhs is false.
+	LogicalOr,
+}
+
+impl BinaryOperator {
+	/// Whether this operator manipulates the bits of integers, i.e. `|`, `&`, `^`, `<<` and `>>`.
+	pub fn is_bitwise(&self) -> bool {
+		matches!(
+			self,
+			BinaryOperator::BitOr
+				| BinaryOperator::BitAnd
+				| BinaryOperator::BitXor
+				| BinaryOperator::ShiftLeft
+				| BinaryOperator::ShiftRight
+		)
+	}
+}
+
+impl BinaryOperator {
+	/// Whether operators of the same precedence group from the right, which is only the case for `**`.
+	pub fn is_right_associative(&self) -> bool {
+		matches!(self, BinaryOperator::Power)
+	}
+}
+
+impl BinaryOperator {
+	/// Whether this operator compares its operands, i.e. `<`, `>`, `<=`, `>=`, `=` and `=/=`.
+	pub fn is_comparison(&self) -> bool {
+		matches!(
+			self,
+			BinaryOperator::Less
+				| BinaryOperator::Greater
+				| BinaryOperator::LessEqual
+				| BinaryOperator::GreaterEqual
+				| BinaryOperator::Equal
+				| BinaryOperator::NotEqual
+		)
+	}
 }
 
 impl PartialOrd for BinaryOperator {
@@ -29,12 +86,22 @@ impl PartialOrd for BinaryOperator {
 		let mut precedence = HashMap::new();
 		precedence.insert(BinaryOperator::Less, 10);
 		precedence.insert(BinaryOperator::Greater, 10);
+		precedence.insert(BinaryOperator::LessEqual, 10);
+		precedence.insert(BinaryOperator::GreaterEqual, 10);
+		precedence.insert(BinaryOperator::BitOr, 12);
+		precedence.insert(BinaryOperator::BitXor, 13);
+		precedence.insert(BinaryOperator::BitAnd, 14);
+		precedence.insert(BinaryOperator::ShiftLeft, 15);
+		precedence.insert(BinaryOperator::ShiftRight, 15);
 		precedence.insert(BinaryOperator::Add, 20);
 		precedence.insert(BinaryOperator::Subtract, 20);
 		precedence.insert(BinaryOperator::Multiply, 30);
 		precedence.insert(BinaryOperator::Divide, 30);
+		precedence.insert(BinaryOperator::Power, 40);
 		precedence.insert(BinaryOperator::Equal, 5);
 		precedence.insert(BinaryOperator::NotEqual, 5);
+		precedence.insert(BinaryOperator::LogicalAnd, 2);
+		precedence.insert(BinaryOperator::LogicalOr, 1);
 
 		precedence[self].partial_cmp(&precedence[other])
 	}
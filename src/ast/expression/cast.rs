@@ -0,0 +1,25 @@
+use crate::{
+	ast::{statement::DataType, Expression},
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// A cast of the form `expression as type` like `x as float`, which converts the value of `expression` to `data_type`.
+///
+/// Casts bind stronger than all binary operators, but weaker than unary ones, so `-x as float * y` is
+/// `((-x) as float) * y`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cast {
+	/// The expression whose value is converted.
+	pub expression: Box<Expression>,
+	/// The type the value is converted to.
+	pub data_type: PositionContainer<DataType>,
+}
+
+impl Cast {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.expression.source_position();
+		position.position.end = self.data_type.position.position.end;
+		position
+	}
+}
@@ -0,0 +1,25 @@
+use crate::{
+	ast::Expression,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Access of a field of a struct like `point.x`.
+///
+/// Accesses can be chained, so `a.b.c` is the field `c` of the field access `a.b`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldAccess {
+	/// The struct whose field is accessed.
+	pub base: Box<Expression>,
+	/// The name of the accessed field.
+	pub field: PositionContainer<Symbol>,
+}
+
+impl FieldAccess {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.base.source_position();
+		position.position.end = self.field.position.position.end;
+		position
+	}
+}
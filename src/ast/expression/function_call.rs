@@ -1,15 +1,41 @@
 use std::{fmt, ops::Deref};
 
 use super::Expression;
-use crate::source::PositionContainer;
+use crate::{
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
 
 /// A function call, i.e. the execution of a [`FunctionDefinition`](crate::ast::FunctionDefinition) with concrete parameters.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionCall {
 	/// The name of the function to be called.
-	pub name: PositionContainer<String>,
+	pub name: PositionContainer<Symbol>,
+	/// The value before the dot of a method call like `line.start.length()`, for which the method of its struct is
+	/// called. It is [`None`] for calls of functions, and also for method calls like `point.length()` on a variable,
+	/// which are parsed with the name `point.length` like calls of functions of modules.
+	pub receiver: Option<Box<Expression>>,
 	/// The parameters to invoke the called function with.
 	pub params: Vec<Expression>,
+	/// The position from the name, or the receiver of a method call, to the closing parenthesis.
+	pub position: SourcePositionRange,
+}
+
+impl FunctionCall {
+	/// Replaces the method call by a call of the `method`, which gets the receiver as first parameter, like
+	/// `Point.length(point)` for `point.length()`.
+	pub fn desugar_method(&mut self, method: Symbol) {
+		let receiver = match self.receiver.take() {
+			Some(receiver) => *receiver,
+			None => {
+				let (variable, _) = self.name.split_once('.').expect("Method call without receiver");
+				Expression::Variable(PositionContainer::new(Symbol::intern(variable), self.name.position.clone()))
+			},
+		};
+		self.params.insert(0, receiver);
+		self.name.value = method;
+	}
 }
 
 impl fmt::Display for FunctionCall {
@@ -0,0 +1,18 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// A conditional expression like `if a < b { a } else { b }`, which evaluates to the value of one of its branches.
+///
+/// Unlike the [`IfElse`](crate::ast::IfElse) instruction, each branch is a single expression and the `else` branch is
+/// required, so that there is always a value. Both branches have the same type. An `else if` chain is represented by
+/// an `if_false` expression that is the next [`IfExpression`].
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IfExpression {
+	pub condition: Box<Expression>,
+	/// The value if the condition is true.
+	pub if_true: Box<Expression>,
+	/// The value if the condition is false.
+	pub if_false: Box<Expression>,
+	/// The position from `if` to the closing curly brace of the last branch.
+	pub position: SourcePositionRange,
+}
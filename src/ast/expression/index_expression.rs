@@ -0,0 +1,19 @@
+use crate::{ast::Expression, source::SourcePositionRange};
+
+/// Access of an element of an array like `a[i]`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexExpression {
+	/// The array whose element is accessed.
+	pub base: Box<Expression>,
+	/// The index of the accessed element, starting at zero.
+	pub index: Box<Expression>,
+}
+
+impl IndexExpression {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.base.source_position();
+		position.position.end = self.index.source_position().position.end;
+		position
+	}
+}
@@ -1,30 +1,76 @@
+mod array_literal;
 mod binary_expression;
 mod binary_operator;
+mod cast;
+mod field_access;
 mod function_call;
+mod if_expression;
+mod index_expression;
+mod struct_literal;
+mod unary_expression;
+mod unary_operator;
 
+pub use array_literal::ArrayLiteral;
 pub use binary_expression::BinaryExpression;
 pub use binary_operator::BinaryOperator;
+pub use cast::Cast;
+pub use field_access::FieldAccess;
 pub use function_call::FunctionCall;
+pub use if_expression::IfExpression;
+pub use index_expression::IndexExpression;
+pub use struct_literal::{FieldValue, StructLiteral};
+pub use unary_expression::UnaryExpression;
+pub use unary_operator::UnaryOperator;
 
-use crate::source::{PositionContainer, SourcePositionRange};
+use crate::{
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
 
-pub type Variable = PositionContainer<String>;
+pub type Variable = PositionContainer<Symbol>;
 
 /// An expression produces a value.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
 	BinaryExpression(BinaryExpression),
+	UnaryExpression(UnaryExpression),
+	Cast(Cast),
 	FunctionCall(FunctionCall),
+	FieldAccess(FieldAccess),
+	StructLiteral(StructLiteral),
+	ArrayLiteral(ArrayLiteral),
+	IndexExpression(IndexExpression),
+	If(IfExpression),
 	Number(Number),
-	Variable(PositionContainer<String>),
+	/// A boolean literal, i.e. `true` or `false`.
+	Bool(PositionContainer<bool>),
+	/// A string literal like `"hello"`, with escape sequences already replaced.
+	StringLiteral(PositionContainer<String>),
+	/// A character literal like `'a'`, with an escape sequence already replaced.
+	Char(PositionContainer<char>),
+	/// The null pointer literal `null`, which is a value of every pointer type.
+	Null(SourcePositionRange),
+	Variable(PositionContainer<Symbol>),
 }
 
 impl Expression {
 	pub fn source_position(&self) -> SourcePositionRange {
 		match self {
 			Expression::BinaryExpression(binary_expression) => binary_expression.source_position(),
-			Expression::FunctionCall(function_call) => function_call.name.position.clone(),
+			Expression::UnaryExpression(unary_expression) => unary_expression.source_position(),
+			Expression::Cast(cast) => cast.source_position(),
+			Expression::FunctionCall(function_call) => function_call.position.clone(),
+			Expression::FieldAccess(field_access) => field_access.source_position(),
+			Expression::StructLiteral(struct_literal) => struct_literal.position.clone(),
+			Expression::ArrayLiteral(array_literal) => array_literal.position.clone(),
+			Expression::IndexExpression(index_expression) => index_expression.source_position(),
+			Expression::If(if_expression) => if_expression.position.clone(),
 			Expression::Number(number) => number.position.clone(),
+			Expression::Bool(bool) => bool.position.clone(),
+			Expression::StringLiteral(string) => string.position.clone(),
+			Expression::Char(char) => char.position.clone(),
+			Expression::Null(position) => position.clone(),
 			Expression::Variable(variable) => variable.position.clone(),
 		}
 	}
@@ -33,6 +79,7 @@ impl Expression {
 pub type Number = PositionContainer<NumberKind>;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NumberKind {
 	Int(i64),
 	Float(f64),
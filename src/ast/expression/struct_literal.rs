@@ -0,0 +1,35 @@
+use crate::{
+	ast::Expression,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Creation of a struct value by giving a value to each field, like `Point { x: 1, y: 2 }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructLiteral {
+	/// The name of the created struct.
+	pub name: PositionContainer<Symbol>,
+	/// The values of the fields in the order of the source code.
+	pub fields: Vec<FieldValue>,
+	/// The position from the name to the closing curly brace.
+	pub position: SourcePositionRange,
+}
+
+/// The value of a field in a [`StructLiteral`], like `x: 1`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldValue {
+	/// The name of the field.
+	pub name: PositionContainer<Symbol>,
+	/// The value of the field.
+	pub value: Expression,
+}
+
+impl FieldValue {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.name.position.clone();
+		position.position.end = self.value.source_position().position.end;
+		position
+	}
+}
@@ -0,0 +1,25 @@
+use super::UnaryOperator;
+use crate::{
+	ast::Expression,
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// A unary expression of the form `op operand` like `-x`, `!done` or `*pointer`.
+///
+/// Unary operators bind stronger than all binary operators, so `-a * b` is `(-a) * b`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnaryExpression {
+	/// The operator applied to `operand`.
+	pub operator: PositionContainer<UnaryOperator>,
+	/// The operand.
+	pub operand: Box<Expression>,
+}
+
+impl UnaryExpression {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.operator.position.clone();
+		position.position.end = self.operand.source_position().position.end;
+		position
+	}
+}
@@ -0,0 +1,11 @@
+/// A unary operator applied to a single operand.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOperator {
+	/// Arithmetic negation of a number (`-`).
+	Negate,
+	/// Logical negation of a boolean (`!`).
+	Not,
+	/// The value that a pointer points to (`*`).
+	Dereference,
+}
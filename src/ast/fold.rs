@@ -0,0 +1,397 @@
+//! Rewriting of the AST, for passes like desugaring and optimizations which replace some kinds of nodes.
+//!
+//! A [`Fold`] takes each node by value and returns the node replacing it. Like for a
+//! [`Visitor`](super::visit::Visitor), each `fold_*` method defaults to the `walk_*` function of the same name, which
+//! folds the children of the node and returns it with them. An instruction is replaced by a whole [`Block`], so that a
+//! pass can remove it or expand it to several instructions.
+//!
+//! Only the nodes which may contain instructions or expressions are folded. Names, data types and positions are
+//! changed in place with a [`VisitorMut`](super::visit::VisitorMut) instead.
+
+use crate::ast::{
+	expression::FieldValue,
+	statement::{Assignment, ConstDeclaration, LValue, VariableDeclaration},
+	Block, Expression, FunctionDefinition, Instruction, MatchArm, Node, Statement,
+};
+
+/// Replaces the nodes of an AST.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use fortytwolang::{
+/// 	ast::{
+/// 		fold::{self, Fold},
+/// 		Block, Instruction,
+/// 	},
+/// 	Lexer, Parser, Source,
+/// };
+///
+/// /// Removes all comments inside of functions.
+/// struct RemoveComments;
+///
+/// impl Fold for RemoveComments {
+/// 	fn fold_instruction(&mut self, instruction: Instruction) -> Block {
+/// 		match instruction {
+/// 			Instruction::Comment(_) => Vec::new(),
+/// 			instruction => fold::walk_instruction(self, instruction),
+/// 		}
+/// 	}
+/// }
+///
+/// let source_code = "def main() {\n\t# Loop forever\n\twhile true {\n\t\t# Nothing\n\t}\n}";
+/// let source = Arc::new(Source::new("example.ftl".to_owned(), source_code.to_owned()));
+/// let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+/// let ast_nodes = Parser::new(tokens.into_iter()).parse().unwrap();
+/// let ast_nodes = RemoveComments.fold_nodes(ast_nodes);
+/// let fortytwolang::ast::Node::Function(main) = &ast_nodes[0] else { panic!() };
+/// let [Instruction::WhileLoop(while_loop)] = main.body.as_slice() else { panic!() };
+/// assert!(while_loop.body.is_empty());
+/// ```
+pub trait Fold {
+	fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+		nodes.into_iter().map(|node| self.fold_node(node)).collect()
+	}
+
+	fn fold_node(&mut self, node: Node) -> Node {
+		walk_node(self, node)
+	}
+
+	fn fold_function(&mut self, function: FunctionDefinition) -> FunctionDefinition {
+		walk_function(self, function)
+	}
+
+	fn fold_block(&mut self, block: Block) -> Block {
+		walk_block(self, block)
+	}
+
+	/// Returns the instructions replacing the `instruction`, which may be none or several.
+	fn fold_instruction(&mut self, instruction: Instruction) -> Block {
+		walk_instruction(self, instruction)
+	}
+
+	fn fold_statement(&mut self, statement: Statement) -> Statement {
+		walk_statement(self, statement)
+	}
+
+	fn fold_variable_declaration(&mut self, variable_declaration: VariableDeclaration) -> VariableDeclaration {
+		walk_variable_declaration(self, variable_declaration)
+	}
+
+	/// Folds both global and local constants.
+	fn fold_const_declaration(&mut self, const_declaration: ConstDeclaration) -> ConstDeclaration {
+		walk_const_declaration(self, const_declaration)
+	}
+
+	fn fold_assignment(&mut self, assignment: Assignment) -> Assignment {
+		walk_assignment(self, assignment)
+	}
+
+	fn fold_lvalue(&mut self, lvalue: LValue) -> LValue {
+		walk_lvalue(self, lvalue)
+	}
+
+	fn fold_expression(&mut self, expression: Expression) -> Expression {
+		walk_expression(self, expression)
+	}
+}
+
+pub fn walk_node<F: Fold + ?Sized>(folder: &mut F, node: Node) -> Node {
+	match node {
+		Node::Function(function) => Node::Function(folder.fold_function(function)),
+		Node::Const(const_declaration) => Node::Const(folder.fold_const_declaration(const_declaration)),
+		node @ (Node::FunctionPrototype(_)
+		| Node::Struct(_)
+		| Node::Enum(_)
+		| Node::TypeAlias(_)
+		| Node::Import(_)
+		| Node::Comment(_)) => node,
+	}
+}
+
+pub fn walk_function<F: Fold + ?Sized>(folder: &mut F, function: FunctionDefinition) -> FunctionDefinition {
+	FunctionDefinition { body: folder.fold_block(function.body), ..function }
+}
+
+pub fn walk_block<F: Fold + ?Sized>(folder: &mut F, block: Block) -> Block {
+	block.into_iter().flat_map(|instruction| folder.fold_instruction(instruction)).collect()
+}
+
+pub fn walk_instruction<F: Fold + ?Sized>(folder: &mut F, instruction: Instruction) -> Block {
+	let instruction = match instruction {
+		Instruction::Expression(expression) => Instruction::Expression(folder.fold_expression(expression)),
+		Instruction::Statement(statement) => Instruction::Statement(folder.fold_statement(statement)),
+		Instruction::IfElse(mut if_else) => {
+			if_else.condition = folder.fold_expression(if_else.condition);
+			if_else.if_true = folder.fold_block(if_else.if_true);
+			if_else.if_false = folder.fold_block(if_else.if_false);
+			Instruction::IfElse(if_else)
+		},
+		Instruction::WhileLoop(mut while_loop) => {
+			while_loop.condition = folder.fold_expression(while_loop.condition);
+			while_loop.body = folder.fold_block(while_loop.body);
+			Instruction::WhileLoop(while_loop)
+		},
+		Instruction::ForLoop(mut for_loop) => {
+			for_loop.initialization = folder.fold_statement(for_loop.initialization);
+			for_loop.condition = folder.fold_expression(for_loop.condition);
+			for_loop.step = folder.fold_assignment(for_loop.step);
+			for_loop.body = folder.fold_block(for_loop.body);
+			Instruction::ForLoop(for_loop)
+		},
+		Instruction::Match(mut match_) => {
+			match_.value = folder.fold_expression(match_.value);
+			let mut fold_arm = |arm: MatchArm| MatchArm {
+				patterns: arm.patterns.into_iter().map(|pattern| folder.fold_expression(pattern)).collect(),
+				body: folder.fold_block(arm.body),
+				comment: arm.comment,
+			};
+			match_.arms = match_.arms.into_iter().map(&mut fold_arm).collect();
+			match_.default = match_.default.map(fold_arm);
+			Instruction::Match(match_)
+		},
+		instruction @ Instruction::Comment(_) => instruction,
+	};
+	vec![instruction]
+}
+
+pub fn walk_statement<F: Fold + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+	match statement {
+		Statement::VariableDeclaration(variable_declaration) => {
+			Statement::VariableDeclaration(Box::new(folder.fold_variable_declaration(*variable_declaration)))
+		},
+		Statement::ConstDeclaration(const_declaration) => {
+			Statement::ConstDeclaration(Box::new(folder.fold_const_declaration(*const_declaration)))
+		},
+		Statement::Assignment(assignment) => Statement::Assignment(folder.fold_assignment(assignment)),
+		Statement::Return(expression, position) => {
+			Statement::Return(expression.map(|expression| folder.fold_expression(expression)), position)
+		},
+		statement @ (Statement::Break(_) | Statement::Continue(_)) => statement,
+	}
+}
+
+pub fn walk_variable_declaration<F: Fold + ?Sized>(
+	folder: &mut F,
+	variable_declaration: VariableDeclaration,
+) -> VariableDeclaration {
+	VariableDeclaration { value: folder.fold_expression(variable_declaration.value), ..variable_declaration }
+}
+
+pub fn walk_const_declaration<F: Fold + ?Sized>(
+	folder: &mut F,
+	const_declaration: ConstDeclaration,
+) -> ConstDeclaration {
+	ConstDeclaration { value: folder.fold_expression(const_declaration.value), ..const_declaration }
+}
+
+pub fn walk_assignment<F: Fold + ?Sized>(folder: &mut F, assignment: Assignment) -> Assignment {
+	Assignment {
+		target: folder.fold_lvalue(assignment.target),
+		operator: assignment.operator,
+		value: folder.fold_expression(assignment.value),
+	}
+}
+
+pub fn walk_lvalue<F: Fold + ?Sized>(folder: &mut F, lvalue: LValue) -> LValue {
+	match lvalue {
+		LValue::Variable(_) => lvalue,
+		LValue::FieldAccess { base, field } => LValue::FieldAccess { base: Box::new(folder.fold_lvalue(*base)), field },
+		LValue::Index { base, index } => {
+			LValue::Index { base: Box::new(folder.fold_lvalue(*base)), index: Box::new(folder.fold_expression(*index)) }
+		},
+		LValue::Dereference { position, base } => {
+			LValue::Dereference { position, base: Box::new(folder.fold_lvalue(*base)) }
+		},
+	}
+}
+
+pub fn walk_expression<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+	match expression {
+		Expression::BinaryExpression(mut binary_expression) => {
+			*binary_expression.lhs = folder.fold_expression(*binary_expression.lhs);
+			*binary_expression.rhs = folder.fold_expression(*binary_expression.rhs);
+			Expression::BinaryExpression(binary_expression)
+		},
+		Expression::UnaryExpression(mut unary_expression) => {
+			*unary_expression.operand = folder.fold_expression(*unary_expression.operand);
+			Expression::UnaryExpression(unary_expression)
+		},
+		Expression::Cast(mut cast) => {
+			*cast.expression = folder.fold_expression(*cast.expression);
+			Expression::Cast(cast)
+		},
+		Expression::FunctionCall(mut function_call) => {
+			function_call.receiver = function_call.receiver.map(|receiver| Box::new(folder.fold_expression(*receiver)));
+			function_call.params =
+				function_call.params.into_iter().map(|param| folder.fold_expression(param)).collect();
+			Expression::FunctionCall(function_call)
+		},
+		Expression::FieldAccess(mut field_access) => {
+			*field_access.base = folder.fold_expression(*field_access.base);
+			Expression::FieldAccess(field_access)
+		},
+		Expression::StructLiteral(mut struct_literal) => {
+			struct_literal.fields = struct_literal
+				.fields
+				.into_iter()
+				.map(|field| FieldValue { value: folder.fold_expression(field.value), ..field })
+				.collect();
+			Expression::StructLiteral(struct_literal)
+		},
+		Expression::ArrayLiteral(mut array_literal) => {
+			array_literal.elements =
+				array_literal.elements.into_iter().map(|element| folder.fold_expression(element)).collect();
+			Expression::ArrayLiteral(array_literal)
+		},
+		Expression::IndexExpression(mut index_expression) => {
+			*index_expression.base = folder.fold_expression(*index_expression.base);
+			*index_expression.index = folder.fold_expression(*index_expression.index);
+			Expression::IndexExpression(index_expression)
+		},
+		Expression::If(mut if_expression) => {
+			*if_expression.condition = folder.fold_expression(*if_expression.condition);
+			*if_expression.if_true = folder.fold_expression(*if_expression.if_true);
+			*if_expression.if_false = folder.fold_expression(*if_expression.if_false);
+			Expression::If(if_expression)
+		},
+		expression @ (Expression::Number(_)
+		| Expression::Bool(_)
+		| Expression::StringLiteral(_)
+		| Expression::Char(_)
+		| Expression::Null(_)
+		| Expression::Variable(_)) => expression,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use super::Fold;
+	use crate::{
+		ast::{
+			expression::{BinaryOperator, Number, NumberKind},
+			pretty,
+			statement::Statement,
+			Block, Expression, Instruction, WhileLoop,
+		},
+		Lexer, Parser, Source,
+	};
+
+	fn fold(folder: &mut impl Fold, source_code: &str) -> String {
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+		let ast_nodes = Parser::new(tokens.into_iter()).parse().unwrap();
+		pretty::nodes(&folder.fold_nodes(ast_nodes))
+	}
+
+	/// Desugars `for` loops without `continue` into a `while` loop preceded by the initialization.
+	struct ForToWhile;
+
+	impl Fold for ForToWhile {
+		fn fold_instruction(&mut self, instruction: Instruction) -> Block {
+			let Instruction::ForLoop(for_loop) = instruction else {
+				return super::walk_instruction(self, instruction);
+			};
+			let mut body = self.fold_block(for_loop.body);
+			body.push(Instruction::Statement(Statement::Assignment(self.fold_assignment(for_loop.step))));
+			vec![
+				Instruction::Statement(self.fold_statement(for_loop.initialization)),
+				Instruction::WhileLoop(Box::new(WhileLoop {
+					condition: self.fold_expression(for_loop.condition),
+					body,
+					position: for_loop.position,
+				})),
+			]
+		}
+	}
+
+	/// Tests that an instruction can be replaced by several ones, also in nested blocks.
+	#[test]
+	fn test_replace_instruction() {
+		let source_code = "
+def main() {
+	if true {
+		for var i = 0; i < 2; i += 1 {
+			print(i)
+		}
+	}
+}";
+		let expected = "\
+Function main [2:5-2:8]
+  If [3:2-7:2]
+    Bool true [3:5-3:8]
+    Then
+      Var i [4:11-4:11]
+        Number 0 [4:15-4:15]
+      While [4:3-6:3]
+        BinaryExpression Less [4:18-4:22]
+          Variable i [4:18-4:18]
+          Number 2 [4:22-4:22]
+        Body
+          FunctionCall print [5:4-5:11]
+            Variable i [5:10-5:10]
+          Assignment Add [4:25-4:30]
+            Variable i [4:25-4:25]
+            Number 1 [4:30-4:30]
+";
+		assert_eq!(fold(&mut ForToWhile, source_code), expected);
+	}
+
+	/// Replaces multiplications by two with additions.
+	struct DoubleToAdd;
+
+	impl Fold for DoubleToAdd {
+		fn fold_expression(&mut self, expression: Expression) -> Expression {
+			let mut expression = super::walk_expression(self, expression);
+			if let Expression::BinaryExpression(binary_expression) = &mut expression {
+				if let Expression::Number(Number { value: NumberKind::Int(2), .. }) = *binary_expression.rhs {
+					if binary_expression.operator.value == BinaryOperator::Multiply {
+						binary_expression.operator.value = BinaryOperator::Add;
+						*binary_expression.rhs = (*binary_expression.lhs).clone();
+					}
+				}
+			}
+			expression
+		}
+	}
+
+	/// Tests that expressions are folded in all nodes containing them, after folding their operands.
+	#[test]
+	fn test_replace_expression() {
+		let source_code = "
+const four: int = (2 * 2)
+def main() {
+	a[i * 2] = i * 2 * 2
+	return f((i * 2))
+}";
+		// The added copies of the left operands keep their positions
+		let expected = "\
+Const four: int [2:7-2:10]
+  BinaryExpression Add [2:20-2:20]
+    Number 2 [2:20-2:20]
+    Number 2 [2:20-2:20]
+Function main [3:5-3:8]
+  Assignment [4:2-4:13]
+    IndexExpression [4:2-4:4]
+      Variable a [4:2-4:2]
+      BinaryExpression Add [4:4-4:4]
+        Variable i [4:4-4:4]
+        Variable i [4:4-4:4]
+    BinaryExpression Add [4:13-4:13]
+      BinaryExpression Add [4:13-4:13]
+        Variable i [4:13-4:13]
+        Variable i [4:13-4:13]
+      BinaryExpression Add [4:13-4:13]
+        Variable i [4:13-4:13]
+        Variable i [4:13-4:13]
+  Return [5:2-5:18]
+    FunctionCall f [5:9-5:18]
+      BinaryExpression Add [5:12-5:12]
+        Variable i [5:12-5:12]
+        Variable i [5:12-5:12]
+";
+		assert_eq!(fold(&mut DoubleToAdd, source_code), expected);
+	}
+}
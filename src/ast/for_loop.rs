@@ -0,0 +1,25 @@
+use super::{statement::Assignment, Expression, Statement};
+use crate::{ast::Block, source::SourcePositionRange};
+
+/// Execute the `initialization` once, then the `body` followed by the `step` *while* the `condition` is true.
+///
+/// # Example
+///
+/// ```text
+/// for var i: int = 0; i < 10; i = i + 1 {
+///     sum = sum + i
+/// }
+/// ```
+///
+/// The `initialization` is a variable declaration or assignment and the `step` an assignment. A variable declared in
+/// the `initialization` is only visible inside the loop.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForLoop {
+	pub initialization: Statement,
+	pub condition: Expression,
+	pub step: Assignment,
+	pub body: Block,
+	/// The position from `for` to the closing curly brace of the body.
+	pub position: SourcePositionRange,
+}
@@ -1,10 +1,23 @@
-use crate::{ast::statement::DataType, source::PositionContainer};
+use crate::{
+	ast::statement::DataType,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
 
 /// Name and a type that specify an argument of a function in its function prototype.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionArgument {
 	/// The name of the function argument.
-	pub name: PositionContainer<String>,
+	pub name: PositionContainer<Symbol>,
 	/// The type of the argument, e.g. a int, a struct or a pointer.
 	pub data_type: PositionContainer<DataType>,
 }
+
+impl FunctionArgument {
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.name.position.clone();
+		position.position.end = self.data_type.position.position.end;
+		position
+	}
+}
@@ -1,10 +1,16 @@
-use crate::ast::{function_prototype::FunctionPrototype, Block};
+use crate::{
+	ast::{function_prototype::FunctionPrototype, Block},
+	source::SourcePositionRange,
+};
 
 /// Name, arguments and body define a function.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionDefinition {
 	/// Name and arguments of the function.
 	pub prototype: FunctionPrototype,
 	/// The body of the function.
 	pub body: Block,
+	/// The position from `def` to the closing curly brace of the body.
+	pub position: SourcePositionRange,
 }
@@ -1,15 +1,31 @@
 use crate::{
 	ast::{function_argument::FunctionArgument, statement::DataType},
-	source::PositionContainer,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 };
 
 /// The header of the function i.e. function name and arguments, but not the body.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionPrototype {
-	/// The name of the function.
-	pub name: PositionContainer<String>,
+	/// The name of the function. The name of a method is qualified with the name of its struct, like `Point.length`.
+	pub name: PositionContainer<Symbol>,
+	/// The type parameters like `T` in `def max<T>(a: T, b: T): T`, which are empty unless the function is generic.
+	/// The semantic analysis replaces a generic function by a specialized copy for each type argument it is called
+	/// with.
+	pub type_parameters: Vec<PositionContainer<Symbol>>,
 	/// The arguments for the function.
 	pub args: Vec<FunctionArgument>,
 	/// Return type is what this function returns.
 	pub return_type: Option<PositionContainer<DataType>>,
+	/// The position from `def` or `extern` to the end of the return type, or to the closing parenthesis of the
+	/// arguments if there is no return type.
+	pub position: SourcePositionRange,
+}
+
+impl FunctionPrototype {
+	/// Whether the function has type parameters.
+	pub fn is_generic(&self) -> bool {
+		!self.type_parameters.is_empty()
+	}
 }
@@ -1,5 +1,5 @@
 use super::Expression;
-use crate::ast::Block;
+use crate::{ast::Block, source::SourcePositionRange};
 
 /// An if-then-or-else-then expression.
 ///
@@ -16,9 +16,14 @@ use crate::ast::Block;
 /// * The `condition` is `answer == 42`.
 /// * The `if_true` expression is `42`.
 /// * The `if_false` expression is `0´.
+///
+/// An `else if` chain is represented by an `if_false` block that only contains the next [`IfElse`].
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IfElse {
 	pub condition: Expression,
 	pub if_true: Block,
 	pub if_false: Block,
+	/// The position from `if` to the closing curly brace of the last block.
+	pub position: SourcePositionRange,
 }
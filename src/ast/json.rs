@@ -0,0 +1,421 @@
+//! Serialization of the AST as JSON, e.g. for external tools like linters and editors.
+//!
+//! Enums are represented like by serde_json, i.e. a variant with data is an object with the name of the variant as only
+//! key, like `{"Import": {...}}`. Positions contain the name of the source file, but not its text.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use fortytwolang::{ast, Lexer, Parser, Source};
+//!
+//! let source = Arc::new(Source::new("example.ftl".to_owned(), "import math".to_owned()));
+//! let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+//! let ast_nodes = Parser::new(tokens.into_iter()).collect::<Result<Vec<_>, _>>().unwrap();
+//! let json = ast::json::to_string(&ast_nodes).unwrap();
+//! assert!(json.starts_with(r#"[{"Import":{"position":{"source":"example.ftl","position":"#));
+//! ```
+
+use std::fmt::Write;
+
+use serde::{ser, Serialize};
+use thiserror::Error;
+
+/// Error raised by a [`Serialize`] implementation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0}")]
+pub struct Error(String);
+
+impl ser::Error for Error {
+	fn custom<T: std::fmt::Display>(msg: T) -> Self {
+		Self(msg.to_string())
+	}
+}
+
+/// Serializes the `value` as compact JSON.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+	let mut serializer = Serializer { output: String::new() };
+	value.serialize(&mut serializer)?;
+	Ok(serializer.output)
+}
+
+/// Serializer writing JSON to a string.
+struct Serializer {
+	output: String,
+}
+
+impl Serializer {
+	fn string(&mut self, string: &str) {
+		self.output.push('"');
+		for char in string.chars() {
+			match char {
+				'"' => self.output.push_str("\\\""),
+				'\\' => self.output.push_str("\\\\"),
+				'\n' => self.output.push_str("\\n"),
+				'\r' => self.output.push_str("\\r"),
+				'\t' => self.output.push_str("\\t"),
+				char if (char as u32) < 0x20 => {
+					let _ = write!(self.output, "\\u{:04x}", char as u32);
+				},
+				char => self.output.push(char),
+			}
+		}
+		self.output.push('"');
+	}
+
+	/// Starts an object, array or object of an enum variant. The `variant` is the name of the enum variant, whose data
+	/// is enclosed in an object with the name as key.
+	fn compound(&mut self, variant: Option<&'static str>, opening: char, closing: char) -> Compound<'_> {
+		if let Some(variant) = variant {
+			self.output.push('{');
+			self.string(variant);
+			self.output.push(':');
+		}
+		self.output.push(opening);
+		Compound { serializer: self, first: true, closing, variant: variant.is_some() }
+	}
+}
+
+/// Object or array whose elements are being serialized.
+struct Compound<'a> {
+	serializer: &'a mut Serializer,
+	/// Whether no element was serialized yet, so no comma is needed before the next one.
+	first: bool,
+	/// Character closing the object or array.
+	closing: char,
+	/// Whether the compound is the data of an enum variant, which is enclosed in another object.
+	variant: bool,
+}
+
+impl Compound<'_> {
+	fn separator(&mut self) {
+		if !self.first {
+			self.serializer.output.push(',');
+		}
+		self.first = false;
+	}
+
+	fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		self.separator();
+		value.serialize(&mut *self.serializer)
+	}
+
+	fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+		self.separator();
+		self.serializer.string(key);
+		self.serializer.output.push(':');
+		value.serialize(&mut *self.serializer)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.serializer.output.push(self.closing);
+		if self.variant {
+			self.serializer.output.push('}');
+		}
+		Ok(())
+	}
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = Compound<'a>;
+	type SerializeTuple = Compound<'a>;
+	type SerializeTupleStruct = Compound<'a>;
+	type SerializeTupleVariant = Compound<'a>;
+	type SerializeMap = Compound<'a>;
+	type SerializeStruct = Compound<'a>;
+	type SerializeStructVariant = Compound<'a>;
+
+	fn serialize_bool(self, v: bool) -> Result<(), Error> {
+		self.output.push_str(if v { "true" } else { "false" });
+		Ok(())
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<(), Error> {
+		self.serialize_i64(v.into())
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<(), Error> {
+		self.serialize_i64(v.into())
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<(), Error> {
+		self.serialize_i64(v.into())
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<(), Error> {
+		let _ = write!(self.output, "{}", v);
+		Ok(())
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<(), Error> {
+		self.serialize_u64(v.into())
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<(), Error> {
+		self.serialize_u64(v.into())
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<(), Error> {
+		self.serialize_u64(v.into())
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<(), Error> {
+		let _ = write!(self.output, "{}", v);
+		Ok(())
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<(), Error> {
+		self.serialize_f64(v.into())
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<(), Error> {
+		// JSON has no representation for NaN and infinity. The debug representation uses an exponent for very large
+		// and small numbers, which is valid JSON, too.
+		match v.is_finite() {
+			true => {
+				let _ = write!(self.output, "{:?}", v);
+			},
+			false => self.output.push_str("null"),
+		}
+		Ok(())
+	}
+
+	fn serialize_char(self, v: char) -> Result<(), Error> {
+		self.string(v.encode_utf8(&mut [0; 4]));
+		Ok(())
+	}
+
+	fn serialize_str(self, v: &str) -> Result<(), Error> {
+		self.string(v);
+		Ok(())
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+		let mut compound = self.compound(None, '[', ']');
+		for byte in v {
+			compound.element(byte)?;
+		}
+		compound.end()
+	}
+
+	fn serialize_none(self) -> Result<(), Error> {
+		self.serialize_unit()
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), Error> {
+		self.output.push_str("null");
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+		self.serialize_unit()
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+		self.string(variant);
+		Ok(())
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		let mut compound = self.compound(None, '{', '}');
+		compound.field(variant, value)?;
+		compound.end()
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(None, '[', ']'))
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(None, '[', ']'))
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(None, '[', ']'))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(Some(variant), '[', ']'))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(None, '{', '}'))
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(None, '{', '}'))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Compound<'a>, Error> {
+		Ok(self.compound(Some(variant), '{', '}'))
+	}
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		self.element(value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		self.element(value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		self.element(value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		self.element(value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeMap for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+		self.separator();
+		// JSON only allows strings as keys, so other keys are quoted
+		let key = to_string(key)?;
+		match key.starts_with('"') {
+			true => self.serializer.output.push_str(&key),
+			false => self.serializer.string(&key),
+		}
+		self.serializer.output.push(':');
+		Ok(())
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut *self.serializer)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+		self.field(key, value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+		self.field(key, value)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		Compound::end(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use super::*;
+	use crate::{
+		ast::{self, expression::NumberKind, Expression},
+		source::{Position, PositionContainer, PositionRange, Source, SourcePositionRange},
+		symbol::Symbol,
+	};
+
+	/// Tests that structs, enum variants, options and escaped strings are serialized like by serde_json.
+	#[test]
+	fn test_to_string() {
+		let position = SourcePositionRange {
+			source: Arc::new(Source::new("file.ftl".to_owned(), "text".to_owned())),
+			position: PositionRange { start: Position::default(), end: Position { line: 1, column: 2, offset: 1 } },
+		};
+		let contain = |value| PositionContainer::new(value, position.clone());
+		let ast_nodes = vec![
+			ast::Node::Comment(contain("\"quoted\"\n\u{1}".to_owned())),
+			ast::Node::Const(ast::statement::ConstDeclaration {
+				name: PositionContainer::new(Symbol::intern("PI"), position.clone()),
+				data_type: None,
+				value: Expression::Number(PositionContainer::new(NumberKind::Float(3.5), position.clone())),
+				position: position.clone(),
+			}),
+		];
+		let position_json = r#"{"source":"file.ftl","position":{"start":{"line":1,"column":1,"offset":0},"end":{"line":1,"column":2,"offset":1}}}"#;
+		let expected = format!(
+			r#"[{{"Comment":{{"position":{0},"value":"\"quoted\"\n\u0001"}}}},{{"Const":{{"name":{{"position":{0},"value":"PI"}},"data_type":null,"value":{{"Number":{{"position":{0},"value":{{"Float":3.5}}}}}},"position":{0}}}}}]"#,
+			position_json
+		);
+		assert_eq!(to_string(&ast_nodes), Ok(expected));
+	}
+}
@@ -0,0 +1,112 @@
+use super::{
+	expression::{BinaryExpression, BinaryOperator},
+	statement::{DataType, VariableDeclaration},
+	Expression, IfElse, Instruction, Statement,
+};
+use crate::{
+	ast::Block,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// A multi-way branch over an integer.
+///
+/// # Example
+///
+/// ```text
+/// match day {
+///     0, 6 => {
+///         println("weekend")
+///     }
+///     _ => {
+///         println("workday")
+///     }
+/// }
+/// ```
+///
+/// * The `value` is `day`.
+/// * The only arm of `arms` has the patterns `0` and `6`.
+/// * The `default` arm `_` is executed if no pattern is equal to the value.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Match {
+	pub value: Expression,
+	/// The type of the value, which the semantic analysis annotates, so that [`Match::into_if_else`] can declare the
+	/// variable holding it.
+	pub value_type: Option<PositionContainer<DataType>>,
+	pub arms: Vec<MatchArm>,
+	/// The `_` arm, whose patterns are empty. It is the last arm and required, unless the other arms cover all variants
+	/// of an enum.
+	pub default: Option<MatchArm>,
+	/// The position from `match` to the closing curly brace.
+	pub position: SourcePositionRange,
+}
+
+/// An arm of a [`Match`], whose body is executed if one of its patterns is equal to the value.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchArm {
+	/// The constants compared to the value.
+	pub patterns: Vec<Expression>,
+	pub body: Block,
+	/// The comment in the line(s) before the arm, if any.
+	pub comment: Option<PositionContainer<String>>,
+}
+
+impl Match {
+	/// Converts the match into an if-else chain with the same behavior, for backends without a multi-way branch.
+	///
+	/// The value is only evaluated once: Unless it is a variable, it is stored in a new variable like `_match42`, which
+	/// is named after the offset of the `match`, so that it is unique in the function.
+	pub fn into_if_else(self) -> Vec<Instruction> {
+		let mut instructions = Vec::new();
+		let value = match self.value {
+			value @ Expression::Variable(_) => value,
+			value => {
+				let name = format!("_match{}", self.position.position.start.offset);
+				let name = PositionContainer::new(Symbol::from(name), value.source_position());
+				instructions.push(Instruction::Statement(Statement::VariableDeclaration(Box::new(
+					VariableDeclaration {
+						name: name.clone(),
+						data_type: self.value_type,
+						value,
+						position: self.position.clone(),
+					},
+				))));
+				Expression::Variable(name)
+			},
+		};
+		let default = self.default.map(|default| default.body).unwrap_or_default();
+		let if_else = self.arms.into_iter().rev().fold(default, |if_false, arm| {
+			let condition = arm
+				.patterns
+				.into_iter()
+				.map(|pattern| {
+					let position = pattern.source_position();
+					binary_expression(value.clone(), BinaryOperator::Equal, pattern, position)
+				})
+				.reduce(|lhs, rhs| {
+					let position = rhs.source_position();
+					binary_expression(lhs, BinaryOperator::LogicalOr, rhs, position)
+				})
+				.expect("Match arm without patterns");
+			let position = self.position.clone();
+			vec![Instruction::IfElse(Box::new(IfElse { condition, if_true: arm.body, if_false, position }))]
+		});
+		instructions.extend(if_else);
+		instructions
+	}
+}
+
+fn binary_expression(
+	lhs: Expression,
+	operator: BinaryOperator,
+	rhs: Expression,
+	position: SourcePositionRange,
+) -> Expression {
+	Expression::BinaryExpression(BinaryExpression {
+		lhs: Box::new(lhs),
+		operator: PositionContainer::new(operator, position),
+		rhs: Box::new(rhs),
+	})
+}
@@ -1,39 +1,104 @@
 //! The abstract syntax tree, which is produced by the [`Parser`](crate::parser::Parser).
 //!
 //! The AST is a tree representation of the source code, which is used for [semantic analysis](crate::semantic_analyzer) and [code generation](crate::emitter).
+//!
+//! The [`pretty`] module prints the AST as compact tree for humans. With the `serde` feature, all AST types implement `Serialize` and `Deserialize`, and the `json` module serializes them as JSON.
+//!
+//! Passes which only handle some kinds of nodes traverse the AST with a [`visit::Visitor`], and rewrite it with a [`fold::Fold`].
 
+pub mod enum_;
 pub mod expression;
+pub mod fold;
+mod for_loop;
 mod function_argument;
 mod function_definition;
 mod function_prototype;
 mod if_else;
+#[cfg(feature = "serde")]
+pub mod json;
+mod match_;
+pub mod pretty;
 pub mod statement;
 pub mod struct_;
+mod type_alias;
+pub mod visit;
 mod while_loop;
 
+pub use enum_::Enum;
 pub use expression::Expression;
+pub use for_loop::ForLoop;
+pub use function_argument::FunctionArgument;
 pub use function_definition::FunctionDefinition;
 pub use function_prototype::FunctionPrototype;
 pub use if_else::IfElse;
+pub use match_::{Match, MatchArm};
 pub use statement::Statement;
 pub use struct_::Struct;
+pub use type_alias::TypeAlias;
 pub use while_loop::WhileLoop;
 
+use crate::source::{PositionContainer, SourcePositionRange};
+
 /// A "regular" line of code.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
 	Expression(Expression),
 	Statement(Statement),
 	IfElse(Box<IfElse>),
 	WhileLoop(Box<WhileLoop>),
+	ForLoop(Box<ForLoop>),
+	Match(Box<Match>),
+	/// A comment, which is only kept so that the formatter can emit it again.
+	Comment(PositionContainer<String>),
+}
+
+impl Instruction {
+	pub fn source_position(&self) -> SourcePositionRange {
+		match self {
+			Instruction::Expression(expression) => expression.source_position(),
+			Instruction::Statement(statement) => statement.source_position(),
+			Instruction::IfElse(if_else) => if_else.position.clone(),
+			Instruction::WhileLoop(while_loop) => while_loop.position.clone(),
+			Instruction::ForLoop(for_loop) => for_loop.position.clone(),
+			Instruction::Match(match_) => match_.position.clone(),
+			Instruction::Comment(comment) => comment.position.clone(),
+		}
+	}
 }
 
 /// The top-level element of an AST.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Node {
+	/// Declaration of an extern function without a body, like `extern putchar(c: int): int`, which calls the C function
+	/// of the same name.
 	FunctionPrototype(FunctionPrototype),
 	Function(FunctionDefinition),
 	Struct(Struct),
+	Enum(Enum),
+	TypeAlias(TypeAlias),
+	/// A constant visible in all functions.
+	Const(statement::ConstDeclaration),
+	/// Import of a module like `import math`, whose members are then available as `math.square`.
+	Import(PositionContainer<String>),
+	/// A comment between top-level nodes.
+	Comment(PositionContainer<String>),
+}
+
+impl Node {
+	/// The position of the node in the source code. For an import, this is the position of the module name only.
+	pub fn source_position(&self) -> SourcePositionRange {
+		match self {
+			Node::FunctionPrototype(prototype) => prototype.position.clone(),
+			Node::Function(function) => function.position.clone(),
+			Node::Struct(struct_) => struct_.position.clone(),
+			Node::Enum(enum_) => enum_.position.clone(),
+			Node::TypeAlias(type_alias) => type_alias.position.clone(),
+			Node::Const(const_declaration) => const_declaration.position.clone(),
+			Node::Import(name) | Node::Comment(name) => name.position.clone(),
+		}
+	}
 }
 
 /// A list of instructions.
@@ -0,0 +1,424 @@
+//! Compact tree representation of the AST for humans, which is much shorter than the debug representation.
+//!
+//! Each node is printed on its own line with its kind, its name or value and its span, and its children are indented
+//! below it:
+//!
+//! ```text
+//! Function main [1:5-1:8]
+//!   ReturnType int [1:13-1:15]
+//!   Return [2:2-2:13]
+//!     BinaryExpression Add [2:9-2:13]
+//!       Number 1 [2:9-2:9]
+//!       Number 2 [2:13-2:13]
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::{
+	ast::{
+		self,
+		expression::{NumberKind, StructLiteral},
+		statement::{DataType, LValue},
+		Expression, Instruction, Node, Statement,
+	},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Prints the top-level nodes of a program as tree.
+pub fn nodes(ast_nodes: &[Node]) -> String {
+	let mut printer = Printer::default();
+	for node in ast_nodes {
+		printer.node(node);
+	}
+	printer.output
+}
+
+/// Prints the instructions of a block as tree, e.g. for a snippet of code.
+pub fn block(block: &[Instruction]) -> String {
+	let mut printer = Printer::default();
+	printer.instructions(block);
+	printer.output
+}
+
+#[derive(Default)]
+struct Printer {
+	output: String,
+	/// Depth of the printed node in the tree, used for indentation.
+	depth: usize,
+}
+
+impl Printer {
+	/// Prints a line describing a node, with the `span` of its source code at the end if it has one.
+	fn line(&mut self, description: impl fmt::Display, span: Option<&SourcePositionRange>) {
+		// Writing to a string can't fail
+		let _ = write!(self.output, "{:indent$}{}", "", description, indent = 2 * self.depth);
+		if let Some(span) = span {
+			let _ = write!(self.output, " [{}]", span.position);
+		}
+		self.output.push('\n');
+	}
+
+	/// Prints the nodes printed by `print` as children of the previous line.
+	fn children(&mut self, print: impl FnOnce(&mut Self)) {
+		self.depth += 1;
+		print(self);
+		self.depth -= 1;
+	}
+
+	fn node(&mut self, node: &Node) {
+		match node {
+			Node::Function(function) => {
+				self.prototype("Function", &function.prototype);
+				self.children(|this| this.instructions(&function.body));
+			},
+			Node::FunctionPrototype(prototype) => self.prototype("Extern", prototype),
+			Node::Struct(struct_) => {
+				self.named("Struct", &struct_.name);
+				self.children(|this| {
+					for type_parameter in &struct_.type_parameters {
+						this.line(format_args!("TypeParameter {}", **type_parameter), Some(&type_parameter.position));
+					}
+					for field in &struct_.fields {
+						if let Some(comment) = &field.comment {
+							this.comment(comment);
+						}
+						this.line(
+							format_args!("Field {}: {}", *field.name, field.data_type.value),
+							Some(&field.name.position),
+						);
+					}
+				});
+			},
+			Node::Enum(enum_) => {
+				self.named("Enum", &enum_.name);
+				self.children(|this| {
+					for variant in &enum_.variants {
+						if let Some(comment) = &variant.comment {
+							this.comment(comment);
+						}
+						this.named("Variant", &variant.name);
+					}
+				});
+			},
+			Node::TypeAlias(type_alias) => {
+				self.named("TypeAlias", &type_alias.name);
+				self.children(|this| this.data_type("Type", &type_alias.data_type));
+			},
+			Node::Const(const_declaration) => {
+				self.declaration("Const", &const_declaration.name, &const_declaration.data_type);
+				self.children(|this| this.expression(&const_declaration.value));
+			},
+			Node::Import(import) => self.named("Import", import),
+			Node::Comment(comment) => self.comment(comment),
+		}
+	}
+
+	/// Prints a line with the `kind` and the name of a node.
+	fn named(&mut self, kind: &str, name: &PositionContainer<impl fmt::Display>) {
+		self.line(format_args!("{} {}", kind, name.value), Some(&name.position));
+	}
+
+	/// Prints the prototype of a function with its type parameters, arguments and return type as children.
+	fn prototype(&mut self, kind: &str, prototype: &ast::FunctionPrototype) {
+		self.named(kind, &prototype.name);
+		self.children(|this| {
+			for type_parameter in &prototype.type_parameters {
+				this.line(format_args!("TypeParameter {}", **type_parameter), Some(&type_parameter.position));
+			}
+			for arg in &prototype.args {
+				this.line(format_args!("Argument {}: {}", *arg.name, arg.data_type.value), Some(&arg.name.position));
+			}
+			if let Some(return_type) = &prototype.return_type {
+				this.data_type("ReturnType", return_type);
+			}
+		});
+	}
+
+	fn data_type(&mut self, kind: &str, data_type: &PositionContainer<DataType>) {
+		self.line(format_args!("{} {}", kind, data_type.value), Some(&data_type.position));
+	}
+
+	/// Prints a line with the `kind`, the name and the type of a variable or constant declaration.
+	fn declaration(
+		&mut self,
+		kind: &str,
+		name: &PositionContainer<Symbol>,
+		data_type: &Option<PositionContainer<DataType>>,
+	) {
+		match data_type {
+			Some(data_type) => {
+				self.line(format_args!("{} {}: {}", kind, **name, data_type.value), Some(&name.position))
+			},
+			None => self.named(kind, name),
+		}
+	}
+
+	fn comment(&mut self, comment: &PositionContainer<String>) {
+		self.line(format_args!("Comment {:?}", **comment), Some(&comment.position));
+	}
+
+	fn instructions(&mut self, block: &[Instruction]) {
+		for instruction in block {
+			self.instruction(instruction);
+		}
+	}
+
+	/// Prints a line with the `kind` of a block and its instructions as children. Blocks have no span, because their
+	/// instructions have.
+	fn block(&mut self, kind: &str, block: &[Instruction]) {
+		self.line(kind, None);
+		self.children(|this| this.instructions(block));
+	}
+
+	fn instruction(&mut self, instruction: &Instruction) {
+		match instruction {
+			Instruction::Expression(expression) => self.expression(expression),
+			Instruction::Statement(statement) => self.statement(statement),
+			Instruction::IfElse(if_else) => {
+				self.line("If", Some(&if_else.position));
+				self.children(|this| {
+					this.expression(&if_else.condition);
+					this.block("Then", &if_else.if_true);
+					if !if_else.if_false.is_empty() {
+						this.block("Else", &if_else.if_false);
+					}
+				});
+			},
+			Instruction::WhileLoop(while_loop) => {
+				self.line("While", Some(&while_loop.position));
+				self.children(|this| {
+					this.expression(&while_loop.condition);
+					this.block("Body", &while_loop.body);
+				});
+			},
+			Instruction::ForLoop(for_loop) => {
+				self.line("For", Some(&for_loop.position));
+				self.children(|this| {
+					this.statement(&for_loop.initialization);
+					this.expression(&for_loop.condition);
+					this.assignment(&for_loop.step);
+					this.block("Body", &for_loop.body);
+				});
+			},
+			Instruction::Match(match_) => {
+				self.line("Match", Some(&match_.position));
+				self.children(|this| {
+					this.expression(&match_.value);
+					for arm in &match_.arms {
+						this.line("Arm", None);
+						this.children(|this| {
+							for pattern in &arm.patterns {
+								this.expression(pattern);
+							}
+							this.block("Body", &arm.body);
+						});
+					}
+					if let Some(default) = &match_.default {
+						this.block("Default", &default.body);
+					}
+				});
+			},
+			Instruction::Comment(comment) => self.comment(comment),
+		}
+	}
+
+	fn statement(&mut self, statement: &Statement) {
+		match statement {
+			Statement::VariableDeclaration(variable_declaration) => {
+				self.declaration("Var", &variable_declaration.name, &variable_declaration.data_type);
+				self.children(|this| this.expression(&variable_declaration.value));
+			},
+			Statement::ConstDeclaration(const_declaration) => {
+				self.declaration("Const", &const_declaration.name, &const_declaration.data_type);
+				self.children(|this| this.expression(&const_declaration.value));
+			},
+			Statement::Assignment(assignment) => self.assignment(assignment),
+			Statement::Return(expression, position) => {
+				self.line("Return", Some(position));
+				if let Some(expression) = expression {
+					self.children(|this| this.expression(expression));
+				}
+			},
+			Statement::Break(position) => self.line("Break", Some(position)),
+			Statement::Continue(position) => self.line("Continue", Some(position)),
+		}
+	}
+
+	fn assignment(&mut self, assignment: &ast::statement::Assignment) {
+		let span = Some(&assignment.source_position());
+		match &assignment.operator {
+			Some(operator) => self.line(format_args!("Assignment {:?}", **operator), span),
+			None => self.line("Assignment", span),
+		}
+		self.children(|this| {
+			this.lvalue(&assignment.target);
+			this.expression(&assignment.value);
+		});
+	}
+
+	fn lvalue(&mut self, lvalue: &LValue) {
+		self.expression(&lvalue.clone().into());
+	}
+
+	fn expression(&mut self, expression: &Expression) {
+		let span = expression.source_position();
+		let span = Some(&span);
+		match expression {
+			Expression::BinaryExpression(binary_expression) => {
+				self.line(format_args!("BinaryExpression {:?}", *binary_expression.operator), span);
+				self.children(|this| {
+					this.expression(&binary_expression.lhs);
+					this.expression(&binary_expression.rhs);
+				});
+			},
+			Expression::UnaryExpression(unary_expression) => {
+				self.line(format_args!("UnaryExpression {:?}", *unary_expression.operator), span);
+				self.children(|this| this.expression(&unary_expression.operand));
+			},
+			Expression::Cast(cast) => {
+				self.line(format_args!("Cast {}", cast.data_type.value), span);
+				self.children(|this| this.expression(&cast.expression));
+			},
+			Expression::FunctionCall(function_call) => {
+				self.line(format_args!("FunctionCall {}", *function_call.name), span);
+				self.children(|this| {
+					function_call.receiver.iter().for_each(|receiver| this.expression(receiver));
+					function_call.params.iter().for_each(|param| this.expression(param))
+				});
+			},
+			Expression::FieldAccess(field_access) => {
+				self.line(format_args!("FieldAccess {}", *field_access.field), span);
+				self.children(|this| this.expression(&field_access.base));
+			},
+			Expression::StructLiteral(StructLiteral { name, fields, .. }) => {
+				self.line(format_args!("StructLiteral {}", **name), span);
+				self.children(|this| {
+					for field in fields {
+						this.named("FieldValue", &field.name);
+						this.children(|this| this.expression(&field.value));
+					}
+				});
+			},
+			Expression::ArrayLiteral(array_literal) => {
+				self.line("ArrayLiteral", span);
+				self.children(|this| array_literal.elements.iter().for_each(|element| this.expression(element)));
+			},
+			Expression::IndexExpression(index_expression) => {
+				self.line("IndexExpression", span);
+				self.children(|this| {
+					this.expression(&index_expression.base);
+					this.expression(&index_expression.index);
+				});
+			},
+			Expression::If(if_expression) => {
+				self.line("IfExpression", span);
+				self.children(|this| {
+					this.expression(&if_expression.condition);
+					this.expression(&if_expression.if_true);
+					this.expression(&if_expression.if_false);
+				});
+			},
+			Expression::Number(number) => match **number {
+				NumberKind::Int(int) => self.line(format_args!("Number {}", int), span),
+				NumberKind::Float(float) => self.line(format_args!("Number {:?}", float), span),
+			},
+			Expression::Bool(boolean) => self.line(format_args!("Bool {}", **boolean), span),
+			Expression::Char(char) => self.line(format_args!("Char {:?}", **char), span),
+			Expression::StringLiteral(string) => self.line(format_args!("StringLiteral {:?}", **string), span),
+			Expression::Null(_) => self.line("Null", span),
+			Expression::Variable(variable) => self.line(format_args!("Variable {}", **variable), span),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::{Lexer, Parser, Source};
+
+	/// Tests that each node is printed on its own line with its children indented below it.
+	#[test]
+	fn test_nodes() {
+		let source_code = "
+import math
+struct Point {
+	x: int
+}
+def main(a: int): int {
+	# Start
+	var p = Point { x: 1 }
+	if a < 2 {
+		return -p.x
+	}
+	return math.abs(2.5) as int
+}";
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+		let ast_nodes = Parser::new(tokens.into_iter()).collect::<Result<Vec<_>, _>>().unwrap();
+		let expected = "\
+Import math [2:8-2:11]
+Struct Point [3:8-3:12]
+  Field x: int [4:2-4:2]
+Function main [6:5-6:8]
+  Argument a: int [6:10-6:10]
+  ReturnType int [6:19-6:21]
+  Comment \"Start\" [7:2-7:8]
+  Var p [8:6-8:6]
+    StructLiteral Point [8:10-8:23]
+      FieldValue x [8:18-8:18]
+        Number 1 [8:21-8:21]
+  If [9:2-11:2]
+    BinaryExpression Less [9:5-9:9]
+      Variable a [9:5-9:5]
+      Number 2 [9:9-9:9]
+    Then
+      Return [10:3-10:13]
+        UnaryExpression Negate [10:10-10:13]
+          FieldAccess x [10:11-10:13]
+            Variable p [10:11-10:11]
+  Return [12:2-12:28]
+    Cast int [12:9-12:28]
+      FunctionCall math.abs [12:9-12:21]
+        Number 2.5 [12:18-12:20]
+";
+		assert_eq!(super::nodes(&ast_nodes), expected);
+	}
+
+	/// Tests that loops and the statements in them span from their keyword to their end.
+	#[test]
+	fn test_loop_spans() {
+		let source_code = "
+def main() {
+	for var i = 0; i < 3; i += 1 {
+		while true {
+			continue
+		}
+		break
+	}
+}";
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+		let ast_nodes = Parser::new(tokens.into_iter()).collect::<Result<Vec<_>, _>>().unwrap();
+		let expected = "\
+Function main [2:5-2:8]
+  For [3:2-8:2]
+    Var i [3:10-3:10]
+      Number 0 [3:14-3:14]
+    BinaryExpression Less [3:17-3:21]
+      Variable i [3:17-3:17]
+      Number 3 [3:21-3:21]
+    Assignment Add [3:24-3:29]
+      Variable i [3:24-3:24]
+      Number 1 [3:29-3:29]
+    Body
+      While [4:3-6:3]
+        Bool true [4:9-4:12]
+        Body
+          Continue [5:4-5:11]
+      Break [7:3-7:7]
+";
+		assert_eq!(super::nodes(&ast_nodes), expected);
+	}
+}
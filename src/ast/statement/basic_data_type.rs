@@ -2,11 +2,18 @@ use std::fmt;
 
 /// A basic data type is a type with hardware support like int and float.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BasicDataType {
 	/// A integer number, like 42
 	Int,
 	/// A floating point number like 4.2
 	Float,
+	/// A boolean, either `true` or `false`
+	Bool,
+	/// A string of characters, like "hello"
+	Str,
+	/// A single unicode character, like 'a'
+	Char,
 }
 
 impl TryFrom<&str> for BasicDataType {
@@ -18,6 +25,9 @@ impl TryFrom<&str> for BasicDataType {
 		match data_type {
 			"int" => Ok(BasicDataType::Int),
 			"float" => Ok(BasicDataType::Float),
+			"bool" => Ok(BasicDataType::Bool),
+			"str" => Ok(BasicDataType::Str),
+			"char" => Ok(BasicDataType::Char),
 			_ => Err(()), // No basic data type with this name
 		}
 	}
@@ -28,6 +38,9 @@ impl fmt::Display for BasicDataType {
 		match self {
 			BasicDataType::Int => write!(f, "int"),
 			BasicDataType::Float => write!(f, "float"),
+			BasicDataType::Bool => write!(f, "bool"),
+			BasicDataType::Str => write!(f, "str"),
+			BasicDataType::Char => write!(f, "char"),
 		}
 	}
 }
@@ -1,25 +1,97 @@
-use std::fmt;
+use std::{
+	fmt,
+	hash::{Hash, Hasher},
+	mem,
+};
 
 use super::basic_data_type::BasicDataType;
-use crate::source::PositionContainer;
+use crate::{source::PositionContainer, symbol::Symbol};
 
-/// A data type is either basic, a struct, or a pointer to a data type.
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+/// A data type is either basic, a struct, an enum, a pointer to a data type, or an array of a data type.
+///
+/// Two data types are equal if they describe the same type, regardless of where the pointee of a pointer type is
+/// written in the source code.
+#[derive(Debug, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
 	/// A basic data type like int and float.
 	Basic(BasicDataType),
 	/// A user defined struct with custom name.
-	Struct(String),
+	Struct(Symbol),
+	/// A generic struct with type arguments, like `Pair<int, float>`. The semantic analysis replaces it by a
+	/// [`DataType::Struct`] for the specialization of the struct for the type arguments.
+	Generic { name: Symbol, type_arguments: Vec<DataType> },
+	/// A user defined enum. The parser reads all named types as [`DataType::Struct`], which are resolved to enums
+	/// afterwards.
+	Enum(Symbol),
 	/// A Pointer to a data type.
 	Pointer(Box<PositionContainer<DataType>>),
+	/// An array with a fixed number of elements of the same data type, like `[int; 3]`.
+	Array { element: Box<DataType>, length: usize },
+	/// The type of the `null` literal, which converts to every pointer type. It cannot be written in the source code.
+	Null,
+}
+
+impl DataType {
+	/// Whether a value of type `value` can be stored where a value of this type is expected, i.e. whether the types
+	/// are equal or the value is `null` and this type is a pointer.
+	pub fn accepts(&self, value: &DataType) -> bool {
+		self == value || (*value == DataType::Null && matches!(self, DataType::Pointer(_)))
+	}
+}
+
+impl PartialEq for DataType {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(DataType::Basic(lhs), DataType::Basic(rhs)) => lhs == rhs,
+			(DataType::Struct(lhs), DataType::Struct(rhs)) | (DataType::Enum(lhs), DataType::Enum(rhs)) => lhs == rhs,
+			(
+				DataType::Generic { name: lhs_name, type_arguments: lhs_type_arguments },
+				DataType::Generic { name: rhs_name, type_arguments: rhs_type_arguments },
+			) => lhs_name == rhs_name && lhs_type_arguments == rhs_type_arguments,
+			(DataType::Pointer(lhs), DataType::Pointer(rhs)) => lhs.value == rhs.value,
+			(
+				DataType::Array { element: lhs_element, length: lhs_length },
+				DataType::Array { element: rhs_element, length: rhs_length },
+			) => lhs_element == rhs_element && lhs_length == rhs_length,
+			(DataType::Null, DataType::Null) => true,
+			_ => false,
+		}
+	}
+}
+
+impl Hash for DataType {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		mem::discriminant(self).hash(state);
+		match self {
+			DataType::Basic(basic_data_type) => basic_data_type.hash(state),
+			DataType::Struct(name) | DataType::Enum(name) => name.hash(state),
+			DataType::Generic { name, type_arguments } => {
+				name.hash(state);
+				type_arguments.hash(state);
+			},
+			DataType::Pointer(pointer) => pointer.value.hash(state),
+			DataType::Array { element, length } => {
+				element.hash(state);
+				length.hash(state);
+			},
+			DataType::Null => (),
+		}
+	}
 }
 
 impl fmt::Display for DataType {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			DataType::Basic(basic_data_type) => write!(f, "{}", basic_data_type),
-			DataType::Struct(struct_name) => write!(f, "{}", struct_name),
+			DataType::Struct(name) | DataType::Enum(name) => write!(f, "{}", name),
+			DataType::Generic { name, type_arguments } => {
+				let type_arguments: Vec<_> = type_arguments.iter().map(ToString::to_string).collect();
+				write!(f, "{}<{}>", name, type_arguments.join(", "))
+			},
 			DataType::Pointer(pointer) => write!(f, "ptr {}", pointer.value),
+			DataType::Array { element, length } => write!(f, "[{}; {}]", element, length),
+			DataType::Null => write!(f, "null"),
 		}
 	}
 }
@@ -10,12 +10,34 @@ pub use crate::ast::{
 	function_argument::FunctionArgument,
 	function_definition::FunctionDefinition,
 	function_prototype::FunctionPrototype,
-	statement::var_assignment::{VariableAssignment, VariableDeclaration},
+	statement::var_assignment::{Assignment, ConstDeclaration, LValue, VariableDeclaration},
 };
+use crate::source::SourcePositionRange;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
-	VariableDeclaration(VariableDeclaration),
-	VariableAssignment(VariableAssignment),
-	Return(Expression),
+	VariableDeclaration(Box<VariableDeclaration>),
+	ConstDeclaration(Box<ConstDeclaration>),
+	Assignment(Assignment),
+	/// Leaves the function, with a value unless the function has no return type. The position is the one from
+	/// `return` to the end of the value.
+	Return(Option<Expression>, SourcePositionRange),
+	/// Leaves the innermost loop.
+	Break(SourcePositionRange),
+	/// Skips the rest of the body of the innermost loop and continues with its next iteration.
+	Continue(SourcePositionRange),
+}
+
+impl Statement {
+	pub fn source_position(&self) -> SourcePositionRange {
+		match self {
+			Statement::VariableDeclaration(variable_declaration) => variable_declaration.position.clone(),
+			Statement::ConstDeclaration(const_declaration) => const_declaration.position.clone(),
+			Statement::Assignment(assignment) => assignment.source_position(),
+			Statement::Return(_, position) | Statement::Break(position) | Statement::Continue(position) => {
+				position.clone()
+			},
+		}
+	}
 }
@@ -1,17 +1,157 @@
 use crate::{
-	ast::{statement::DataType, Expression},
-	source::PositionContainer,
+	ast::{
+		expression::{BinaryExpression, BinaryOperator, FieldAccess, IndexExpression, UnaryExpression, UnaryOperator},
+		statement::DataType,
+		Expression,
+	},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 };
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariableDeclaration {
-	pub name: PositionContainer<String>,
-	pub data_type: PositionContainer<DataType>,
+	pub name: PositionContainer<Symbol>,
+	/// The annotated type like in `var x: int = 1`, or [`None`] if the type is inferred from the value.
+	pub data_type: Option<PositionContainer<DataType>>,
 	pub value: Expression,
+	/// The position from `var` to the end of the value.
+	pub position: SourcePositionRange,
 }
 
+/// Declaration of a constant like `const answer: int = 42`, which cannot be assigned to. Its value has to be known at
+/// compile time, so it may only consist of literals, other constants and operators.
 #[derive(Debug, PartialEq, Clone)]
-pub struct VariableAssignment {
-	pub name: PositionContainer<String>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstDeclaration {
+	pub name: PositionContainer<Symbol>,
+	/// The annotated type, or [`None`] if the type is inferred from the value.
+	pub data_type: Option<PositionContainer<DataType>>,
 	pub value: Expression,
+	/// The position from `const` to the end of the value.
+	pub position: SourcePositionRange,
+}
+
+impl From<ConstDeclaration> for VariableDeclaration {
+	/// Backends may store a local constant like a variable, because the semantic analyzer rejects assignments to it.
+	fn from(const_declaration: ConstDeclaration) -> Self {
+		Self {
+			name: const_declaration.name,
+			data_type: const_declaration.data_type,
+			value: const_declaration.value,
+			position: const_declaration.position,
+		}
+	}
+}
+
+/// Assignment like `x = 42`, `point.x = 2`, `a[i] = 3` or `*p = 1`, or a compound assignment like `a[i] += 1`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment {
+	/// The variable or the part of it that is assigned to.
+	pub target: LValue,
+	/// The operator of a compound assignment like `+=`, which combines the target with the value, or [`None`] for `=`.
+	pub operator: Option<PositionContainer<BinaryOperator>>,
+	pub value: Expression,
+}
+
+impl Assignment {
+	/// The position from the target to the end of the value.
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.target.source_position();
+		position.position.end = self.value.source_position().position.end;
+		position
+	}
+
+	/// The value the target has after the assignment, e.g. `a[i] + 1` for `a[i] += 1`. Evaluating it evaluates the
+	/// indices of the target again, so backends evaluate a compound assignment themselves if they could have effects.
+	pub fn assigned_value(&self) -> Expression {
+		match &self.operator {
+			None => self.value.clone(),
+			Some(operator) => Expression::BinaryExpression(BinaryExpression {
+				lhs: Box::new(self.target.clone().into()),
+				operator: operator.clone(),
+				rhs: Box::new(self.value.clone()),
+			}),
+		}
+	}
+}
+
+/// The target of an [`Assignment`], which is a variable or a part of it, like a field of a struct variable or an
+/// element of an array variable, or the value a pointer points to. Field accesses, indexing and dereferencing can be
+/// nested, like in `lines[i].start.x` or `*nodes[i].next`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LValue {
+	Variable(PositionContainer<Symbol>),
+	/// The field of a struct, like `point.x`.
+	FieldAccess {
+		base: Box<LValue>,
+		field: PositionContainer<Symbol>,
+	},
+	/// The element of an array, like `a[i]`.
+	Index {
+		base: Box<LValue>,
+		index: Box<Expression>,
+	},
+	/// The value that a pointer points to, like `*p`. The field of a pointer to a struct can also be assigned to
+	/// without dereferencing it, like `p.x`, which the semantic analysis desugars to `(*p).x`.
+	Dereference {
+		/// The position of the `*`.
+		position: SourcePositionRange,
+		base: Box<LValue>,
+	},
+}
+
+impl LValue {
+	/// The variable that is assigned to, or whose field or element is assigned to, like `a` in `a[i].x = 1`. For an
+	/// assignment through a pointer, this is the variable whose value leads to the pointer, like `p` in `*p = 1`.
+	pub fn variable(&self) -> &PositionContainer<Symbol> {
+		match self {
+			LValue::Variable(variable) => variable,
+			LValue::FieldAccess { base, .. } | LValue::Index { base, .. } | LValue::Dereference { base, .. } => {
+				base.variable()
+			},
+		}
+	}
+
+	/// Whether the assignment changes the memory a pointer points to instead of the variable itself.
+	pub fn is_dereference(&self) -> bool {
+		match self {
+			LValue::Variable(_) => false,
+			LValue::FieldAccess { base, .. } | LValue::Index { base, .. } => base.is_dereference(),
+			LValue::Dereference { .. } => true,
+		}
+	}
+
+	pub fn source_position(&self) -> SourcePositionRange {
+		let (base, end) = match self {
+			LValue::Variable(variable) => return variable.position.clone(),
+			LValue::FieldAccess { base, field } => (base, field.position.position.end),
+			LValue::Index { base, index } => (base, index.source_position().position.end),
+			LValue::Dereference { position, base } => return position.until(&base.source_position()),
+		};
+		let mut position = base.source_position();
+		position.position.end = end;
+		position
+	}
+}
+
+impl From<LValue> for Expression {
+	/// Converts the target to the expression reading its value, e.g. for compound assignments like `a[i] += 1`.
+	fn from(lvalue: LValue) -> Self {
+		match lvalue {
+			LValue::Variable(variable) => Expression::Variable(variable),
+			LValue::FieldAccess { base, field } => {
+				Expression::FieldAccess(FieldAccess { base: Box::new(Expression::from(*base)), field })
+			},
+			LValue::Index { base, index } => {
+				Expression::IndexExpression(IndexExpression { base: Box::new(Expression::from(*base)), index })
+			},
+			LValue::Dereference { position, base } => Expression::UnaryExpression(UnaryExpression {
+				operator: PositionContainer::new(UnaryOperator::Dereference, position),
+				operand: Box::new(Expression::from(*base)),
+			}),
+		}
+	}
 }
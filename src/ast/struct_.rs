@@ -1,19 +1,48 @@
-use crate::{ast::statement::DataType, source::PositionContainer};
+use crate::{
+	ast::statement::DataType,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
 
 /// Collection of fields.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Struct {
 	/// The name of the struct.
-	pub name: PositionContainer<String>,
+	pub name: PositionContainer<Symbol>,
+	/// The type parameters like `A` and `B` in `struct Pair<A, B>`, which are empty unless the struct is generic. The
+	/// semantic analysis replaces a generic struct by a specialized copy for each type argument it is used with.
+	pub type_parameters: Vec<PositionContainer<Symbol>>,
 	/// The fields of the struct.
 	pub fields: Vec<Field>,
+	/// The position from `struct` to the closing curly brace.
+	pub position: SourcePositionRange,
+}
+
+impl Struct {
+	/// Whether the struct has type parameters.
+	pub fn is_generic(&self) -> bool {
+		!self.type_parameters.is_empty()
+	}
 }
 
 /// A struct field consists of a name and a type that specify a field of a struct.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field {
 	/// The name of the struct field.
-	pub name: PositionContainer<String>,
+	pub name: PositionContainer<Symbol>,
 	/// The type of the field, e.g. a int, a struct or a pointer.
 	pub data_type: PositionContainer<DataType>,
+	/// The comment in the line(s) before the field, if any.
+	pub comment: Option<PositionContainer<String>>,
+}
+
+impl Field {
+	/// The position from the name to the end of the type, without the comment before the field.
+	pub fn source_position(&self) -> SourcePositionRange {
+		let mut position = self.name.position.clone();
+		position.position.end = self.data_type.position.position.end;
+		position
+	}
 }
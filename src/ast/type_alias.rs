@@ -0,0 +1,26 @@
+use crate::{
+	ast::statement::DataType,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Another name for a type.
+///
+/// # Example
+///
+/// ```text
+/// type Meters = float
+/// ```
+///
+/// `Meters` is the same type as `float`, so values of both can be mixed freely. Aliases are replaced by the type they
+/// stand for during [semantic analysis](crate::semantic_analyzer::resolve_aliases).
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeAlias {
+	/// The name of the alias.
+	pub name: PositionContainer<Symbol>,
+	/// The type the alias stands for, which may be another alias.
+	pub data_type: PositionContainer<DataType>,
+	/// The position from `type` to the end of the type.
+	pub position: SourcePositionRange,
+}
@@ -0,0 +1,668 @@
+//! Traversal of the AST, for passes which only handle some kinds of nodes.
+//!
+//! Each `visit_*` method of a [`Visitor`] defaults to the `walk_*` function of the same name, which visits the children
+//! of the node in source order. A visitor overrides the methods of the nodes it is interested in, and calls the
+//! `walk_*` function in them to continue with the children. [`VisitorMut`] and the `walk_*_mut` functions are the
+//! same for mutable access to the nodes, e.g. to rename them.
+//!
+//! [`Visitor::visit_position`] is called for every position in the AST, including those of names and operators.
+
+use crate::{
+	ast::{
+		statement::{Assignment, ConstDeclaration, DataType, LValue, VariableDeclaration},
+		Block, Enum, Expression, FunctionDefinition, FunctionPrototype, Instruction, Node, Statement, Struct,
+		TypeAlias,
+	},
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// Visits the nodes of an AST by reference.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use fortytwolang::{
+/// 	ast::{
+/// 		visit::{self, Visitor},
+/// 		Expression,
+/// 	},
+/// 	Lexer, Parser, Source,
+/// };
+///
+/// /// Collects the names of the called functions.
+/// struct Calls(Vec<String>);
+///
+/// impl Visitor for Calls {
+/// 	fn visit_expression(&mut self, expression: &Expression) {
+/// 		if let Expression::FunctionCall(function_call) = expression {
+/// 			self.0.push(function_call.name.value.to_string());
+/// 		}
+/// 		visit::walk_expression(self, expression);
+/// 	}
+/// }
+///
+/// let source_code = "def main() {\n\tif f(g(1)) {\n\t\th()\n\t}\n}";
+/// let source = Arc::new(Source::new("example.ftl".to_owned(), source_code.to_owned()));
+/// let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+/// let ast_nodes = Parser::new(tokens.into_iter()).parse().unwrap();
+/// let mut calls = Calls(Vec::new());
+/// for node in &ast_nodes {
+/// 	calls.visit_node(node);
+/// }
+/// assert_eq!(calls.0, ["f", "g", "h"]);
+/// ```
+pub trait Visitor {
+	fn visit_node(&mut self, node: &Node) {
+		walk_node(self, node);
+	}
+
+	fn visit_function(&mut self, function: &FunctionDefinition) {
+		walk_function(self, function);
+	}
+
+	fn visit_prototype(&mut self, prototype: &FunctionPrototype) {
+		walk_prototype(self, prototype);
+	}
+
+	fn visit_struct(&mut self, struct_: &Struct) {
+		walk_struct(self, struct_);
+	}
+
+	fn visit_enum(&mut self, enum_: &Enum) {
+		walk_enum(self, enum_);
+	}
+
+	fn visit_type_alias(&mut self, type_alias: &TypeAlias) {
+		walk_type_alias(self, type_alias);
+	}
+
+	fn visit_block(&mut self, block: &[Instruction]) {
+		walk_block(self, block);
+	}
+
+	fn visit_instruction(&mut self, instruction: &Instruction) {
+		walk_instruction(self, instruction);
+	}
+
+	fn visit_statement(&mut self, statement: &Statement) {
+		walk_statement(self, statement);
+	}
+
+	fn visit_variable_declaration(&mut self, variable_declaration: &VariableDeclaration) {
+		walk_variable_declaration(self, variable_declaration);
+	}
+
+	/// Visits both global and local constants.
+	fn visit_const_declaration(&mut self, const_declaration: &ConstDeclaration) {
+		walk_const_declaration(self, const_declaration);
+	}
+
+	fn visit_assignment(&mut self, assignment: &Assignment) {
+		walk_assignment(self, assignment);
+	}
+
+	fn visit_lvalue(&mut self, lvalue: &LValue) {
+		walk_lvalue(self, lvalue);
+	}
+
+	fn visit_expression(&mut self, expression: &Expression) {
+		walk_expression(self, expression);
+	}
+
+	fn visit_data_type(&mut self, data_type: &PositionContainer<DataType>) {
+		walk_data_type(self, data_type);
+	}
+
+	fn visit_position(&mut self, _position: &SourcePositionRange) {}
+}
+
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, node: &Node) {
+	match node {
+		Node::Function(function) => visitor.visit_function(function),
+		Node::FunctionPrototype(prototype) => visitor.visit_prototype(prototype),
+		Node::Struct(struct_) => visitor.visit_struct(struct_),
+		Node::Enum(enum_) => visitor.visit_enum(enum_),
+		Node::TypeAlias(type_alias) => visitor.visit_type_alias(type_alias),
+		Node::Const(const_declaration) => visitor.visit_const_declaration(const_declaration),
+		Node::Import(import) => visitor.visit_position(&import.position),
+		Node::Comment(comment) => visitor.visit_position(&comment.position),
+	}
+}
+
+pub fn walk_function<V: Visitor + ?Sized>(visitor: &mut V, function: &FunctionDefinition) {
+	visitor.visit_position(&function.position);
+	visitor.visit_prototype(&function.prototype);
+	visitor.visit_block(&function.body);
+}
+
+pub fn walk_prototype<V: Visitor + ?Sized>(visitor: &mut V, prototype: &FunctionPrototype) {
+	visitor.visit_position(&prototype.position);
+	visitor.visit_position(&prototype.name.position);
+	for type_parameter in &prototype.type_parameters {
+		visitor.visit_position(&type_parameter.position);
+	}
+	for arg in &prototype.args {
+		visitor.visit_position(&arg.name.position);
+		visitor.visit_data_type(&arg.data_type);
+	}
+	if let Some(return_type) = &prototype.return_type {
+		visitor.visit_data_type(return_type);
+	}
+}
+
+pub fn walk_struct<V: Visitor + ?Sized>(visitor: &mut V, struct_: &Struct) {
+	visitor.visit_position(&struct_.position);
+	visitor.visit_position(&struct_.name.position);
+	for type_parameter in &struct_.type_parameters {
+		visitor.visit_position(&type_parameter.position);
+	}
+	for field in &struct_.fields {
+		visitor.visit_position(&field.name.position);
+		visitor.visit_data_type(&field.data_type);
+		if let Some(comment) = &field.comment {
+			visitor.visit_position(&comment.position);
+		}
+	}
+}
+
+pub fn walk_enum<V: Visitor + ?Sized>(visitor: &mut V, enum_: &Enum) {
+	visitor.visit_position(&enum_.position);
+	visitor.visit_position(&enum_.name.position);
+	for variant in &enum_.variants {
+		visitor.visit_position(&variant.name.position);
+		if let Some(comment) = &variant.comment {
+			visitor.visit_position(&comment.position);
+		}
+	}
+}
+
+pub fn walk_type_alias<V: Visitor + ?Sized>(visitor: &mut V, type_alias: &TypeAlias) {
+	visitor.visit_position(&type_alias.position);
+	visitor.visit_position(&type_alias.name.position);
+	visitor.visit_data_type(&type_alias.data_type);
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &[Instruction]) {
+	for instruction in block {
+		visitor.visit_instruction(instruction);
+	}
+}
+
+pub fn walk_instruction<V: Visitor + ?Sized>(visitor: &mut V, instruction: &Instruction) {
+	match instruction {
+		Instruction::Expression(expression) => visitor.visit_expression(expression),
+		Instruction::Statement(statement) => visitor.visit_statement(statement),
+		Instruction::IfElse(if_else) => {
+			visitor.visit_position(&if_else.position);
+			visitor.visit_expression(&if_else.condition);
+			visitor.visit_block(&if_else.if_true);
+			visitor.visit_block(&if_else.if_false);
+		},
+		Instruction::WhileLoop(while_loop) => {
+			visitor.visit_position(&while_loop.position);
+			visitor.visit_expression(&while_loop.condition);
+			visitor.visit_block(&while_loop.body);
+		},
+		Instruction::ForLoop(for_loop) => {
+			visitor.visit_position(&for_loop.position);
+			visitor.visit_statement(&for_loop.initialization);
+			visitor.visit_expression(&for_loop.condition);
+			visitor.visit_assignment(&for_loop.step);
+			visitor.visit_block(&for_loop.body);
+		},
+		Instruction::Match(match_) => {
+			visitor.visit_position(&match_.position);
+			visitor.visit_expression(&match_.value);
+			if let Some(value_type) = &match_.value_type {
+				visitor.visit_data_type(value_type);
+			}
+			for arm in match_.arms.iter().chain(&match_.default) {
+				if let Some(comment) = &arm.comment {
+					visitor.visit_position(&comment.position);
+				}
+				for pattern in &arm.patterns {
+					visitor.visit_expression(pattern);
+				}
+				visitor.visit_block(&arm.body);
+			}
+		},
+		Instruction::Comment(comment) => visitor.visit_position(&comment.position),
+	}
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+	match statement {
+		Statement::VariableDeclaration(variable_declaration) => {
+			visitor.visit_variable_declaration(variable_declaration)
+		},
+		Statement::ConstDeclaration(const_declaration) => visitor.visit_const_declaration(const_declaration),
+		Statement::Assignment(assignment) => visitor.visit_assignment(assignment),
+		Statement::Return(expression, position) => {
+			visitor.visit_position(position);
+			if let Some(expression) = expression {
+				visitor.visit_expression(expression);
+			}
+		},
+		Statement::Break(position) | Statement::Continue(position) => visitor.visit_position(position),
+	}
+}
+
+pub fn walk_variable_declaration<V: Visitor + ?Sized>(visitor: &mut V, variable_declaration: &VariableDeclaration) {
+	visitor.visit_position(&variable_declaration.position);
+	visitor.visit_position(&variable_declaration.name.position);
+	if let Some(data_type) = &variable_declaration.data_type {
+		visitor.visit_data_type(data_type);
+	}
+	visitor.visit_expression(&variable_declaration.value);
+}
+
+pub fn walk_const_declaration<V: Visitor + ?Sized>(visitor: &mut V, const_declaration: &ConstDeclaration) {
+	visitor.visit_position(&const_declaration.position);
+	visitor.visit_position(&const_declaration.name.position);
+	if let Some(data_type) = &const_declaration.data_type {
+		visitor.visit_data_type(data_type);
+	}
+	visitor.visit_expression(&const_declaration.value);
+}
+
+pub fn walk_assignment<V: Visitor + ?Sized>(visitor: &mut V, assignment: &Assignment) {
+	visitor.visit_lvalue(&assignment.target);
+	visitor.visit_expression(&assignment.value);
+}
+
+pub fn walk_lvalue<V: Visitor + ?Sized>(visitor: &mut V, lvalue: &LValue) {
+	match lvalue {
+		LValue::Variable(name) => visitor.visit_position(&name.position),
+		LValue::FieldAccess { base, field } => {
+			visitor.visit_lvalue(base);
+			visitor.visit_position(&field.position);
+		},
+		LValue::Index { base, index } => {
+			visitor.visit_lvalue(base);
+			visitor.visit_expression(index);
+		},
+		LValue::Dereference { position, base } => {
+			visitor.visit_position(position);
+			visitor.visit_lvalue(base);
+		},
+	}
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+	match expression {
+		Expression::BinaryExpression(binary_expression) => {
+			visitor.visit_expression(&binary_expression.lhs);
+			visitor.visit_position(&binary_expression.operator.position);
+			visitor.visit_expression(&binary_expression.rhs);
+		},
+		Expression::UnaryExpression(unary_expression) => {
+			visitor.visit_position(&unary_expression.operator.position);
+			visitor.visit_expression(&unary_expression.operand);
+		},
+		Expression::Cast(cast) => {
+			visitor.visit_expression(&cast.expression);
+			visitor.visit_data_type(&cast.data_type);
+		},
+		Expression::FunctionCall(function_call) => {
+			visitor.visit_position(&function_call.position);
+			if let Some(receiver) = &function_call.receiver {
+				visitor.visit_expression(receiver);
+			}
+			visitor.visit_position(&function_call.name.position);
+			for param in &function_call.params {
+				visitor.visit_expression(param);
+			}
+		},
+		Expression::FieldAccess(field_access) => {
+			visitor.visit_expression(&field_access.base);
+			visitor.visit_position(&field_access.field.position);
+		},
+		Expression::StructLiteral(struct_literal) => {
+			visitor.visit_position(&struct_literal.position);
+			visitor.visit_position(&struct_literal.name.position);
+			for field in &struct_literal.fields {
+				visitor.visit_position(&field.name.position);
+				visitor.visit_expression(&field.value);
+			}
+		},
+		Expression::ArrayLiteral(array_literal) => {
+			visitor.visit_position(&array_literal.position);
+			for element in &array_literal.elements {
+				visitor.visit_expression(element);
+			}
+		},
+		Expression::IndexExpression(index_expression) => {
+			visitor.visit_expression(&index_expression.base);
+			visitor.visit_expression(&index_expression.index);
+		},
+		Expression::If(if_expression) => {
+			visitor.visit_position(&if_expression.position);
+			visitor.visit_expression(&if_expression.condition);
+			visitor.visit_expression(&if_expression.if_true);
+			visitor.visit_expression(&if_expression.if_false);
+		},
+		Expression::Number(number) => visitor.visit_position(&number.position),
+		Expression::Bool(boolean) => visitor.visit_position(&boolean.position),
+		Expression::StringLiteral(string) => visitor.visit_position(&string.position),
+		Expression::Char(char) => visitor.visit_position(&char.position),
+		Expression::Null(position) => visitor.visit_position(position),
+		Expression::Variable(variable) => visitor.visit_position(&variable.position),
+	}
+}
+
+pub fn walk_data_type<V: Visitor + ?Sized>(visitor: &mut V, data_type: &PositionContainer<DataType>) {
+	visitor.visit_position(&data_type.position);
+	if let DataType::Pointer(pointee) = &data_type.value {
+		visitor.visit_data_type(pointee);
+	}
+}
+
+/// Visits the nodes of an AST by mutable reference, like [`Visitor`].
+pub trait VisitorMut {
+	fn visit_node(&mut self, node: &mut Node) {
+		walk_node_mut(self, node);
+	}
+
+	fn visit_function(&mut self, function: &mut FunctionDefinition) {
+		walk_function_mut(self, function);
+	}
+
+	fn visit_prototype(&mut self, prototype: &mut FunctionPrototype) {
+		walk_prototype_mut(self, prototype);
+	}
+
+	fn visit_struct(&mut self, struct_: &mut Struct) {
+		walk_struct_mut(self, struct_);
+	}
+
+	fn visit_enum(&mut self, enum_: &mut Enum) {
+		walk_enum_mut(self, enum_);
+	}
+
+	fn visit_type_alias(&mut self, type_alias: &mut TypeAlias) {
+		walk_type_alias_mut(self, type_alias);
+	}
+
+	fn visit_block(&mut self, block: &mut Block) {
+		walk_block_mut(self, block);
+	}
+
+	fn visit_instruction(&mut self, instruction: &mut Instruction) {
+		walk_instruction_mut(self, instruction);
+	}
+
+	fn visit_statement(&mut self, statement: &mut Statement) {
+		walk_statement_mut(self, statement);
+	}
+
+	fn visit_variable_declaration(&mut self, variable_declaration: &mut VariableDeclaration) {
+		walk_variable_declaration_mut(self, variable_declaration);
+	}
+
+	/// Visits both global and local constants.
+	fn visit_const_declaration(&mut self, const_declaration: &mut ConstDeclaration) {
+		walk_const_declaration_mut(self, const_declaration);
+	}
+
+	fn visit_assignment(&mut self, assignment: &mut Assignment) {
+		walk_assignment_mut(self, assignment);
+	}
+
+	fn visit_lvalue(&mut self, lvalue: &mut LValue) {
+		walk_lvalue_mut(self, lvalue);
+	}
+
+	fn visit_expression(&mut self, expression: &mut Expression) {
+		walk_expression_mut(self, expression);
+	}
+
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		walk_data_type_mut(self, data_type);
+	}
+
+	fn visit_position(&mut self, _position: &mut SourcePositionRange) {}
+}
+
+pub fn walk_node_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Node) {
+	match node {
+		Node::Function(function) => visitor.visit_function(function),
+		Node::FunctionPrototype(prototype) => visitor.visit_prototype(prototype),
+		Node::Struct(struct_) => visitor.visit_struct(struct_),
+		Node::Enum(enum_) => visitor.visit_enum(enum_),
+		Node::TypeAlias(type_alias) => visitor.visit_type_alias(type_alias),
+		Node::Const(const_declaration) => visitor.visit_const_declaration(const_declaration),
+		Node::Import(import) => visitor.visit_position(&mut import.position),
+		Node::Comment(comment) => visitor.visit_position(&mut comment.position),
+	}
+}
+
+pub fn walk_function_mut<V: VisitorMut + ?Sized>(visitor: &mut V, function: &mut FunctionDefinition) {
+	visitor.visit_position(&mut function.position);
+	visitor.visit_prototype(&mut function.prototype);
+	visitor.visit_block(&mut function.body);
+}
+
+pub fn walk_prototype_mut<V: VisitorMut + ?Sized>(visitor: &mut V, prototype: &mut FunctionPrototype) {
+	visitor.visit_position(&mut prototype.position);
+	visitor.visit_position(&mut prototype.name.position);
+	for type_parameter in &mut prototype.type_parameters {
+		visitor.visit_position(&mut type_parameter.position);
+	}
+	for arg in &mut prototype.args {
+		visitor.visit_position(&mut arg.name.position);
+		visitor.visit_data_type(&mut arg.data_type);
+	}
+	if let Some(return_type) = &mut prototype.return_type {
+		visitor.visit_data_type(return_type);
+	}
+}
+
+pub fn walk_struct_mut<V: VisitorMut + ?Sized>(visitor: &mut V, struct_: &mut Struct) {
+	visitor.visit_position(&mut struct_.position);
+	visitor.visit_position(&mut struct_.name.position);
+	for type_parameter in &mut struct_.type_parameters {
+		visitor.visit_position(&mut type_parameter.position);
+	}
+	for field in &mut struct_.fields {
+		visitor.visit_position(&mut field.name.position);
+		visitor.visit_data_type(&mut field.data_type);
+		if let Some(comment) = &mut field.comment {
+			visitor.visit_position(&mut comment.position);
+		}
+	}
+}
+
+pub fn walk_enum_mut<V: VisitorMut + ?Sized>(visitor: &mut V, enum_: &mut Enum) {
+	visitor.visit_position(&mut enum_.position);
+	visitor.visit_position(&mut enum_.name.position);
+	for variant in &mut enum_.variants {
+		visitor.visit_position(&mut variant.name.position);
+		if let Some(comment) = &mut variant.comment {
+			visitor.visit_position(&mut comment.position);
+		}
+	}
+}
+
+pub fn walk_type_alias_mut<V: VisitorMut + ?Sized>(visitor: &mut V, type_alias: &mut TypeAlias) {
+	visitor.visit_position(&mut type_alias.position);
+	visitor.visit_position(&mut type_alias.name.position);
+	visitor.visit_data_type(&mut type_alias.data_type);
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut Block) {
+	for instruction in block {
+		visitor.visit_instruction(instruction);
+	}
+}
+
+pub fn walk_instruction_mut<V: VisitorMut + ?Sized>(visitor: &mut V, instruction: &mut Instruction) {
+	match instruction {
+		Instruction::Expression(expression) => visitor.visit_expression(expression),
+		Instruction::Statement(statement) => visitor.visit_statement(statement),
+		Instruction::IfElse(if_else) => {
+			visitor.visit_position(&mut if_else.position);
+			visitor.visit_expression(&mut if_else.condition);
+			visitor.visit_block(&mut if_else.if_true);
+			visitor.visit_block(&mut if_else.if_false);
+		},
+		Instruction::WhileLoop(while_loop) => {
+			visitor.visit_position(&mut while_loop.position);
+			visitor.visit_expression(&mut while_loop.condition);
+			visitor.visit_block(&mut while_loop.body);
+		},
+		Instruction::ForLoop(for_loop) => {
+			visitor.visit_position(&mut for_loop.position);
+			visitor.visit_statement(&mut for_loop.initialization);
+			visitor.visit_expression(&mut for_loop.condition);
+			visitor.visit_assignment(&mut for_loop.step);
+			visitor.visit_block(&mut for_loop.body);
+		},
+		Instruction::Match(match_) => {
+			visitor.visit_position(&mut match_.position);
+			visitor.visit_expression(&mut match_.value);
+			if let Some(value_type) = &mut match_.value_type {
+				visitor.visit_data_type(value_type);
+			}
+			for arm in match_.arms.iter_mut().chain(&mut match_.default) {
+				if let Some(comment) = &mut arm.comment {
+					visitor.visit_position(&mut comment.position);
+				}
+				for pattern in &mut arm.patterns {
+					visitor.visit_expression(pattern);
+				}
+				visitor.visit_block(&mut arm.body);
+			}
+		},
+		Instruction::Comment(comment) => visitor.visit_position(&mut comment.position),
+	}
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+	match statement {
+		Statement::VariableDeclaration(variable_declaration) => {
+			visitor.visit_variable_declaration(variable_declaration)
+		},
+		Statement::ConstDeclaration(const_declaration) => visitor.visit_const_declaration(const_declaration),
+		Statement::Assignment(assignment) => visitor.visit_assignment(assignment),
+		Statement::Return(expression, position) => {
+			visitor.visit_position(position);
+			if let Some(expression) = expression {
+				visitor.visit_expression(expression);
+			}
+		},
+		Statement::Break(position) | Statement::Continue(position) => visitor.visit_position(position),
+	}
+}
+
+pub fn walk_variable_declaration_mut<V: VisitorMut + ?Sized>(
+	visitor: &mut V,
+	variable_declaration: &mut VariableDeclaration,
+) {
+	visitor.visit_position(&mut variable_declaration.position);
+	visitor.visit_position(&mut variable_declaration.name.position);
+	if let Some(data_type) = &mut variable_declaration.data_type {
+		visitor.visit_data_type(data_type);
+	}
+	visitor.visit_expression(&mut variable_declaration.value);
+}
+
+pub fn walk_const_declaration_mut<V: VisitorMut + ?Sized>(visitor: &mut V, const_declaration: &mut ConstDeclaration) {
+	visitor.visit_position(&mut const_declaration.position);
+	visitor.visit_position(&mut const_declaration.name.position);
+	if let Some(data_type) = &mut const_declaration.data_type {
+		visitor.visit_data_type(data_type);
+	}
+	visitor.visit_expression(&mut const_declaration.value);
+}
+
+pub fn walk_assignment_mut<V: VisitorMut + ?Sized>(visitor: &mut V, assignment: &mut Assignment) {
+	visitor.visit_lvalue(&mut assignment.target);
+	visitor.visit_expression(&mut assignment.value);
+}
+
+pub fn walk_lvalue_mut<V: VisitorMut + ?Sized>(visitor: &mut V, lvalue: &mut LValue) {
+	match lvalue {
+		LValue::Variable(name) => visitor.visit_position(&mut name.position),
+		LValue::FieldAccess { base, field } => {
+			visitor.visit_lvalue(base);
+			visitor.visit_position(&mut field.position);
+		},
+		LValue::Index { base, index } => {
+			visitor.visit_lvalue(base);
+			visitor.visit_expression(index);
+		},
+		LValue::Dereference { position, base } => {
+			visitor.visit_position(position);
+			visitor.visit_lvalue(base);
+		},
+	}
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+	match expression {
+		Expression::BinaryExpression(binary_expression) => {
+			visitor.visit_expression(&mut binary_expression.lhs);
+			visitor.visit_position(&mut binary_expression.operator.position);
+			visitor.visit_expression(&mut binary_expression.rhs);
+		},
+		Expression::UnaryExpression(unary_expression) => {
+			visitor.visit_position(&mut unary_expression.operator.position);
+			visitor.visit_expression(&mut unary_expression.operand);
+		},
+		Expression::Cast(cast) => {
+			visitor.visit_expression(&mut cast.expression);
+			visitor.visit_data_type(&mut cast.data_type);
+		},
+		Expression::FunctionCall(function_call) => {
+			visitor.visit_position(&mut function_call.position);
+			if let Some(receiver) = &mut function_call.receiver {
+				visitor.visit_expression(receiver);
+			}
+			visitor.visit_position(&mut function_call.name.position);
+			for param in &mut function_call.params {
+				visitor.visit_expression(param);
+			}
+		},
+		Expression::FieldAccess(field_access) => {
+			visitor.visit_expression(&mut field_access.base);
+			visitor.visit_position(&mut field_access.field.position);
+		},
+		Expression::StructLiteral(struct_literal) => {
+			visitor.visit_position(&mut struct_literal.position);
+			visitor.visit_position(&mut struct_literal.name.position);
+			for field in &mut struct_literal.fields {
+				visitor.visit_position(&mut field.name.position);
+				visitor.visit_expression(&mut field.value);
+			}
+		},
+		Expression::ArrayLiteral(array_literal) => {
+			visitor.visit_position(&mut array_literal.position);
+			for element in &mut array_literal.elements {
+				visitor.visit_expression(element);
+			}
+		},
+		Expression::IndexExpression(index_expression) => {
+			visitor.visit_expression(&mut index_expression.base);
+			visitor.visit_expression(&mut index_expression.index);
+		},
+		Expression::If(if_expression) => {
+			visitor.visit_position(&mut if_expression.position);
+			visitor.visit_expression(&mut if_expression.condition);
+			visitor.visit_expression(&mut if_expression.if_true);
+			visitor.visit_expression(&mut if_expression.if_false);
+		},
+		Expression::Number(number) => visitor.visit_position(&mut number.position),
+		Expression::Bool(boolean) => visitor.visit_position(&mut boolean.position),
+		Expression::StringLiteral(string) => visitor.visit_position(&mut string.position),
+		Expression::Char(char) => visitor.visit_position(&mut char.position),
+		Expression::Null(position) => visitor.visit_position(position),
+		Expression::Variable(variable) => visitor.visit_position(&mut variable.position),
+	}
+}
+
+pub fn walk_data_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, data_type: &mut PositionContainer<DataType>) {
+	visitor.visit_position(&mut data_type.position);
+	if let DataType::Pointer(pointee) = &mut data_type.value {
+		visitor.visit_data_type(pointee);
+	}
+}
@@ -1,9 +1,12 @@
 use super::Expression;
-use crate::ast::Block;
+use crate::{ast::Block, source::SourcePositionRange};
 
 /// Execute the `body` *while* the `condition` is true.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhileLoop {
 	pub condition: Expression,
 	pub body: Block,
+	/// The position from `while` to the closing curly brace of the body.
+	pub position: SourcePositionRange,
 }
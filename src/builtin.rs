@@ -0,0 +1,202 @@
+//! Functions that are built into the language, like `print`, which can be called without declaring them.
+//!
+//! The builtins are recognized by their name, which is why no function may be named like one of them. The
+//! interpreters execute them natively, while the compiled backends lower them to C library calls, LLVM intrinsics or
+//! host imports.
+//!
+//! The math functions are members of the `math` module, which is available without importing it, e.g.
+//! `math.sqrt(2.0)`.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{
+	ast::statement::{BasicDataType, DataType},
+	interpreter::Value,
+};
+
+/// A function that is built into the language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Builtin {
+	/// `print(value)` writes a value of a basic type to stdout. Floats are written like by [`format_float`].
+	Print,
+	/// `println(value)` writes a value of a basic type to stdout, followed by a newline.
+	Println,
+	/// `read_int(): int` reads a line from stdin and parses it as integer, which is 0 if this fails.
+	ReadInt,
+	/// `math.sqrt(x: float): float` calculates the square root.
+	Sqrt,
+	/// `math.abs(x: float): float` calculates the absolute value.
+	Abs,
+	/// `math.pow(x: float, y: float): float` raises `x` to the power of `y`.
+	Pow,
+	/// `math.min(x: float, y: float): float` returns the smaller value, ignoring NaN.
+	Min,
+	/// `math.max(x: float, y: float): float` returns the greater value, ignoring NaN.
+	Max,
+}
+
+impl Builtin {
+	/// Looks up the builtin with the `name`.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"print" => Some(Builtin::Print),
+			"println" => Some(Builtin::Println),
+			"read_int" => Some(Builtin::ReadInt),
+			"math.sqrt" => Some(Builtin::Sqrt),
+			"math.abs" => Some(Builtin::Abs),
+			"math.pow" => Some(Builtin::Pow),
+			"math.min" => Some(Builtin::Min),
+			"math.max" => Some(Builtin::Max),
+			_ => None,
+		}
+	}
+
+	/// The name by which the builtin is called.
+	pub fn name(self) -> &'static str {
+		match self {
+			Builtin::Print => "print",
+			Builtin::Println => "println",
+			Builtin::ReadInt => "read_int",
+			Builtin::Sqrt => "math.sqrt",
+			Builtin::Abs => "math.abs",
+			Builtin::Pow => "math.pow",
+			Builtin::Min => "math.min",
+			Builtin::Max => "math.max",
+		}
+	}
+
+	/// The number of parameters the builtin expects.
+	pub fn arity(self) -> usize {
+		match self {
+			Builtin::Print | Builtin::Println | Builtin::Sqrt | Builtin::Abs => 1,
+			Builtin::ReadInt => 0,
+			Builtin::Pow | Builtin::Min | Builtin::Max => 2,
+		}
+	}
+
+	/// Whether the builtin accepts a parameter of type `data_type`.
+	pub fn accepts(self, data_type: &DataType) -> bool {
+		match self {
+			Builtin::Print | Builtin::Println => matches!(data_type, DataType::Basic(_)),
+			Builtin::ReadInt => false,
+			Builtin::Sqrt | Builtin::Abs | Builtin::Pow | Builtin::Min | Builtin::Max => {
+				*data_type == DataType::Basic(BasicDataType::Float)
+			},
+		}
+	}
+
+	/// The type of the value returned by the builtin, if any.
+	pub fn return_type(self) -> Option<DataType> {
+		match self {
+			Builtin::Print | Builtin::Println => None,
+			Builtin::ReadInt => Some(DataType::Basic(BasicDataType::Int)),
+			Builtin::Sqrt | Builtin::Abs | Builtin::Pow | Builtin::Min | Builtin::Max => {
+				Some(DataType::Basic(BasicDataType::Float))
+			},
+		}
+	}
+
+	/// Executes the builtin with the `args`, which have been type checked, and returns its return value.
+	pub fn call(self, args: &[Value]) -> Option<Value> {
+		let mut stdout = io::stdout();
+		// Like in C, output errors are ignored
+		match self {
+			Builtin::Print => {
+				let _ = write!(stdout, "{}", printed(&args[0]));
+				let _ = stdout.flush();
+				None
+			},
+			Builtin::Println => {
+				let _ = writeln!(stdout, "{}", printed(&args[0]));
+				None
+			},
+			Builtin::ReadInt => {
+				let mut line = String::new();
+				let _ = io::stdin().lock().read_line(&mut line);
+				Some(Value::Int(line.trim().parse().unwrap_or(0)))
+			},
+			Builtin::Sqrt => Some(Value::Float(float(&args[0]).sqrt())),
+			Builtin::Abs => Some(Value::Float(float(&args[0]).abs())),
+			Builtin::Pow => Some(Value::Float(float(&args[0]).powf(float(&args[1])))),
+			Builtin::Min => Some(Value::Float(float(&args[0]).min(float(&args[1])))),
+			Builtin::Max => Some(Value::Float(float(&args[0]).max(float(&args[1])))),
+		}
+	}
+}
+
+/// The text `print` writes for the `value`. Floats are formatted like by the compiled backends.
+fn printed(value: &Value) -> String {
+	match value {
+		Value::Float(float) => format_float(*float),
+		value => value.to_string(),
+	}
+}
+
+/// Formats the `float` like `printf("%g")` in C, which the compiled backends use to print floats: With 6 significant
+/// digits and without trailing zeros, in scientific notation if the exponent is less than -4 or at least 6, e.g.
+/// `0.0001`, `123457` and `1.5e+06`.
+///
+/// ```
+/// use fortytwolang::builtin::format_float;
+///
+/// assert_eq!(format_float(0.1 + 0.2), "0.3");
+/// assert_eq!(format_float(2.0), "2");
+/// assert_eq!(format_float(-1e100), "-1e+100");
+/// ```
+pub fn format_float(float: f64) -> String {
+	const PRECISION: i32 = 6;
+	if !float.is_finite() {
+		let sign = if float.is_sign_negative() { "-" } else { "" };
+		return format!("{}{}", sign, if float.is_nan() { "nan" } else { "inf" });
+	}
+	// The exponent after rounding to the precision, e.g. 999999.5 is rounded to 1.00000e6
+	let scientific = format!("{:.*e}", (PRECISION - 1) as usize, float);
+	let (mantissa, exponent) = scientific.split_once('e').expect("scientific notation has an exponent");
+	let exponent: i32 = exponent.parse().expect("exponent is an integer");
+	if (-4..PRECISION).contains(&exponent) {
+		let fixed = format!("{:.*}", (PRECISION - 1 - exponent) as usize, float);
+		return without_trailing_zeros(&fixed).to_owned();
+	}
+	let sign = if exponent < 0 { '-' } else { '+' };
+	format!("{}e{}{:02}", without_trailing_zeros(mantissa), sign, exponent.abs())
+}
+
+/// Removes the zeros at the end of the fractional part of `number`, and the decimal point if nothing is left of it.
+fn without_trailing_zeros(number: &str) -> &str {
+	match number.contains('.') {
+		true => number.trim_end_matches('0').trim_end_matches('.'),
+		false => number,
+	}
+}
+
+/// The float an argument of a math function is, because it has been type checked.
+fn float(value: &Value) -> f64 {
+	match value {
+		Value::Float(float) => *float,
+		_ => unreachable!("math functions are called with floats"),
+	}
+}
+
+/// Raises `base` to the power of `exponent` for the `**` operator on integers, wrapping around on overflow. A negative
+/// exponent yields the truncated quotient `1 / base ** -exponent`, which is 0 unless the base is 1 or -1 (and also 0
+/// for a base of 0). The compiled backends emit the same algorithm, exponentiation by squaring.
+pub fn int_power(mut base: i64, exponent: i64) -> i64 {
+	if exponent < 0 {
+		return match base {
+			1 => 1,
+			-1 if exponent % 2 == 0 => 1,
+			-1 => -1,
+			_ => 0,
+		};
+	}
+	let mut exponent = exponent as u64;
+	let mut power: i64 = 1;
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			power = power.wrapping_mul(base);
+		}
+		base = base.wrapping_mul(base);
+		exponent >>= 1;
+	}
+	power
+}
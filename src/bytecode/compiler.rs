@@ -0,0 +1,205 @@
+use super::{CompileError, Function, Instruction, Program};
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryOperator, UnaryOperator},
+		statement::BasicDataType,
+	},
+	ir::{self, BlockId, Callee, Operand, Projection, Terminator},
+	source::PositionContainer,
+};
+
+/// Compiles [AST nodes](ast::Node) to a bytecode [`Program`] by lowering them to the [IR](ir) first.
+#[derive(Debug, Default)]
+pub struct Compiler {
+	/// Instructions of the function currently being compiled.
+	code: Vec<Instruction>,
+	/// Addresses of the first instruction of each block of the function currently being compiled.
+	block_addresses: Vec<usize>,
+	/// Addresses of the jumps to blocks, which are patched once the addresses of all blocks are known.
+	jumps: Vec<(usize, BlockId)>,
+}
+
+impl Compiler {
+	/// Compiles all function definitions in `ast_nodes` to a [`Program`].
+	#[tracing::instrument(skip_all)]
+	pub fn compile(ast_nodes: impl Iterator<Item = ast::Node>) -> Result<Program, CompileError> {
+		Self::compile_ir(&ir::lower(ast_nodes)?)
+	}
+
+	/// Compiles a program in the [IR](ir) to a [`Program`].
+	pub fn compile_ir(program: &ir::Program) -> Result<Program, CompileError> {
+		let mut compiler = Self::default();
+		let functions =
+			program.functions.iter().map(|function| compiler.function(program, function)).collect::<Result<_, _>>()?;
+		Ok(Program { functions })
+	}
+
+	fn function(&mut self, program: &ir::Program, function: &ir::Function) -> Result<Function, CompileError> {
+		self.code = Vec::new();
+		self.block_addresses = Vec::new();
+		for (id, block) in function.blocks.iter().enumerate() {
+			self.block_addresses.push(self.code.len());
+			for (instruction, position) in block.instructions.iter().zip(&block.positions) {
+				if let ir::Instruction::Call { callee: Callee::Extern(index), .. } = instruction {
+					let name = program.externs[*index].name;
+					return Err(CompileError::UnsupportedExtern { name, position: position.clone() });
+				}
+				if let ir::Instruction::Unary { operator: UnaryOperator::Dereference, .. }
+				| ir::Instruction::StorePointer { .. } = instruction
+				{
+					return Err(CompileError::UnsupportedDereference { position: position.clone() });
+				}
+				self.instruction(instruction)?;
+			}
+			self.terminator(&block.terminator, BlockId(id + 1));
+		}
+		for (address, target) in std::mem::take(&mut self.jumps) {
+			match &mut self.code[address] {
+				Instruction::Jump(jump_target) | Instruction::JumpIfFalse(jump_target) => {
+					*jump_target = self.block_addresses[target.0]
+				},
+				instruction => unreachable!("Patching non-jump instruction {}", instruction),
+			}
+		}
+
+		Ok(Function {
+			name: function.name.clone(),
+			arity: function.arity,
+			locals: function.locals.len(),
+			returns_value: function.return_type.is_some(),
+			code: std::mem::take(&mut self.code),
+		})
+	}
+
+	fn instruction(&mut self, instruction: &ir::Instruction) -> Result<(), CompileError> {
+		match instruction {
+			ir::Instruction::Copy { dest, value } => {
+				self.operand(value);
+				self.code.push(Instruction::Store(dest.0));
+			},
+			ir::Instruction::Binary { dest, operator, lhs, rhs } => {
+				self.operand(lhs);
+				self.operand(rhs);
+				self.code.push(match operator {
+					BinaryOperator::Add => Instruction::Add,
+					BinaryOperator::Subtract => Instruction::Subtract,
+					BinaryOperator::Multiply => Instruction::Multiply,
+					BinaryOperator::Divide => Instruction::Divide,
+					BinaryOperator::Power => Instruction::Power,
+					BinaryOperator::BitOr => Instruction::BitOr,
+					BinaryOperator::BitAnd => Instruction::BitAnd,
+					BinaryOperator::BitXor => Instruction::BitXor,
+					BinaryOperator::ShiftLeft => Instruction::ShiftLeft,
+					BinaryOperator::ShiftRight => Instruction::ShiftRight,
+					BinaryOperator::Less => Instruction::Less,
+					BinaryOperator::Greater => Instruction::Greater,
+					BinaryOperator::LessEqual => Instruction::LessEqual,
+					BinaryOperator::GreaterEqual => Instruction::GreaterEqual,
+					BinaryOperator::Equal => Instruction::Equal,
+					BinaryOperator::NotEqual => Instruction::NotEqual,
+					BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+						unreachable!("Logical operators are lowered to branches")
+					},
+				});
+				self.code.push(Instruction::Store(dest.0));
+			},
+			ir::Instruction::Unary { dest, operator, operand } => {
+				self.operand(operand);
+				self.code.push(match operator {
+					UnaryOperator::Negate => Instruction::Negate,
+					UnaryOperator::Not => Instruction::Not,
+					UnaryOperator::Dereference => unreachable!("Dereferences are rejected before"),
+				});
+				self.code.push(Instruction::Store(dest.0));
+			},
+			ir::Instruction::Convert { dest, data_type, value } => {
+				self.operand(value);
+				self.code.push(match data_type {
+					BasicDataType::Float => Instruction::ToFloat,
+					_ => Instruction::ToInt,
+				});
+				self.code.push(Instruction::Store(dest.0));
+			},
+			ir::Instruction::Call { dest, callee, args } => {
+				for arg in args {
+					self.operand(arg);
+				}
+				self.code.push(match callee {
+					Callee::Function(index) => Instruction::Call(*index),
+					Callee::Builtin(builtin) => Instruction::CallBuiltin(*builtin),
+					Callee::Extern(_) => unreachable!("Calls of extern functions are rejected before"),
+				});
+				if let Some(dest) = dest {
+					self.code.push(Instruction::Store(dest.0));
+				}
+			},
+			ir::Instruction::MakeArray { dest, elements } => {
+				for element in elements {
+					self.operand(element);
+				}
+				self.code.push(Instruction::MakeArray(elements.len()));
+				self.code.push(Instruction::Store(dest.0));
+			},
+			ir::Instruction::Index { dest, base, index } => {
+				self.operand(base);
+				self.operand(index);
+				self.code.push(Instruction::Index);
+				self.code.push(Instruction::Store(dest.0));
+			},
+			// The indices are pushed before the value, starting with the outermost one
+			ir::Instruction::Store { target, projections, value } => {
+				for projection in projections {
+					match projection {
+						Projection::Index(index) => self.operand(index),
+						Projection::Field(field) => {
+							return Err(CompileError::UnsupportedStruct { position: field.position.clone() })
+						},
+					}
+				}
+				self.operand(value);
+				self.code.push(Instruction::StoreIndex(target.0, projections.len()));
+			},
+			ir::Instruction::StorePointer { .. } => unreachable!("Dereferences are rejected before"),
+			ir::Instruction::MakeStruct { name: PositionContainer { position, .. }, .. }
+			| ir::Instruction::Field { field: PositionContainer { position, .. }, .. } => {
+				return Err(CompileError::UnsupportedStruct { position: position.clone() })
+			},
+		}
+		Ok(())
+	}
+
+	/// Compiles the end of a block. Jumps to the `next` block, which directly follows it, are left out.
+	fn terminator(&mut self, terminator: &Terminator, next: BlockId) {
+		match terminator {
+			Terminator::Jump(target) => self.jump(*target, next),
+			Terminator::Branch { condition, if_true, if_false } => {
+				self.operand(condition);
+				self.jumps.push((self.code.len(), *if_false));
+				self.code.push(Instruction::JumpIfFalse(0));
+				self.jump(*if_true, next);
+			},
+			Terminator::Return(Some(value)) => {
+				self.operand(value);
+				self.code.push(Instruction::Return);
+			},
+			// Leaving a function that has to return a value this way is caught by the VM
+			Terminator::Return(None) => self.code.push(Instruction::ReturnVoid),
+		}
+	}
+
+	fn jump(&mut self, target: BlockId, next: BlockId) {
+		if target != next {
+			self.jumps.push((self.code.len(), target));
+			self.code.push(Instruction::Jump(0));
+		}
+	}
+
+	/// Pushes the value of the `operand`.
+	fn operand(&mut self, operand: &Operand) {
+		self.code.push(match operand {
+			Operand::Local(local) => Instruction::Load(local.0),
+			Operand::Constant(value) => Instruction::Push(value.clone()),
+		});
+	}
+}
@@ -0,0 +1,39 @@
+use crate::{interpreter::Value, ir, source::SourcePositionRange, symbol::Symbol};
+
+/// Errors that occur while [compiling](super::Compiler) a program to bytecode.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CompileError {
+	#[error(transparent)]
+	Lowering(#[from] ir::Error),
+
+	#[error("{}: UnsupportedStruct: Structs are not supported by the bytecode compiler.", position)]
+	UnsupportedStruct { position: SourcePositionRange },
+
+	#[error("{}: UnsupportedExtern: Extern function `{name}(...)` cannot be called by the bytecode VM.", position)]
+	UnsupportedExtern { name: Symbol, position: SourcePositionRange },
+
+	#[error("{}: UnsupportedDereference: Pointers cannot be dereferenced by the bytecode VM.", position)]
+	UnsupportedDereference { position: SourcePositionRange },
+}
+
+/// Errors that occur while [executing](super::Vm) bytecode.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RuntimeError {
+	#[error("MissingMainFunction: The program has no `main()` function to start with.")]
+	MissingMainFunction,
+
+	#[error("MissingReturnValue: Function `{function}` ended without returning a value.")]
+	MissingReturnValue { function: String },
+
+	#[error("TypeMismatch: Cannot apply `{instruction}` to {lhs} and {rhs}")]
+	TypeMismatch { instruction: String, lhs: Value, rhs: Value },
+
+	#[error("InvalidOperand: Cannot apply `{instruction}` to {operand}")]
+	InvalidOperand { instruction: String, operand: Value },
+
+	#[error("IndexOutOfBounds: Index {index} is out of bounds of an array with {length} elements.")]
+	IndexOutOfBounds { index: i64, length: usize },
+
+	#[error("DivisionByZero: Integer division by zero.")]
+	DivisionByZero,
+}
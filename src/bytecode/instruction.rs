@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::{builtin::Builtin, interpreter::Value};
+
+/// A single instruction of the [`Vm`](super::Vm).
+///
+/// The VM is stack-based: Instructions take their operands from the top of the stack and push their result onto it.
+/// Local variables of a function are addressed by their slot index, jumps by the index of the target instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+	/// Push a constant.
+	Push(Value),
+	/// Remove the top value.
+	Pop,
+	/// Push the value of a local variable.
+	Load(usize),
+	/// Pop the top value and store it in a local variable.
+	Store(usize),
+	/// Pop `rhs` and `lhs` and push `lhs + rhs`.
+	Add,
+	/// Pop `rhs` and `lhs` and push `lhs - rhs`.
+	Subtract,
+	/// Pop `rhs` and `lhs` and push `lhs * rhs`.
+	Multiply,
+	/// Pop `rhs` and `lhs` and push `lhs / rhs`.
+	Divide,
+	/// Pop `rhs` and `lhs` and push `lhs ** rhs`.
+	Power,
+	/// Pop `rhs` and `lhs` and push `lhs | rhs`.
+	BitOr,
+	/// Pop `rhs` and `lhs` and push `lhs & rhs`.
+	BitAnd,
+	/// Pop `rhs` and `lhs` and push `lhs ^ rhs`.
+	BitXor,
+	/// Pop `rhs` and `lhs` and push `lhs << rhs`.
+	ShiftLeft,
+	/// Pop `rhs` and `lhs` and push `lhs >> rhs`.
+	ShiftRight,
+	/// Pop `rhs` and `lhs` and push whether `lhs < rhs`.
+	Less,
+	/// Pop `rhs` and `lhs` and push whether `lhs > rhs`.
+	Greater,
+	/// Pop `rhs` and `lhs` and push whether `lhs <= rhs`.
+	LessEqual,
+	/// Pop `rhs` and `lhs` and push whether `lhs >= rhs`.
+	GreaterEqual,
+	/// Pop `rhs` and `lhs` and push whether `lhs == rhs`.
+	Equal,
+	/// Pop `rhs` and `lhs` and push whether `lhs =/= rhs`.
+	NotEqual,
+	/// Pop the given number of values and push an array of them. The value pushed first is the first element.
+	MakeArray(usize),
+	/// Pop `index` and `array` and push `array[index]`.
+	Index,
+	/// Pop `value` and the given number of indices and set the element at these indices of the (nested) array in the
+	/// given slot to `value`. The index pushed first is the one of the outermost array.
+	StoreIndex(usize, usize),
+	/// Pop a number and push its negation.
+	Negate,
+	/// Pop a boolean and push its logical negation.
+	Not,
+	/// Pop a number and push it converted to an int, rounding floats towards zero.
+	ToInt,
+	/// Pop a number and push it converted to a float.
+	ToFloat,
+	/// Continue at the given instruction.
+	Jump(usize),
+	/// Pop the top value and continue at the given instruction if it is zero.
+	JumpIfFalse(usize),
+	/// Call the function with the given index. Its arguments are the topmost values on the stack.
+	Call(usize),
+	/// Call the builtin with its arguments popped from the stack, and push its return value, if any.
+	CallBuiltin(Builtin),
+	/// Pop the top value and return it to the caller.
+	Return,
+	/// Return to the caller without a value.
+	ReturnVoid,
+}
+
+impl fmt::Display for Instruction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Instruction::Push(Value::Str(string)) => write!(f, "push {:?}", string),
+			Instruction::Push(value) => write!(f, "push {}", value),
+			Instruction::Pop => write!(f, "pop"),
+			Instruction::Load(slot) => write!(f, "load {}", slot),
+			Instruction::Store(slot) => write!(f, "store {}", slot),
+			Instruction::Add => write!(f, "add"),
+			Instruction::Subtract => write!(f, "sub"),
+			Instruction::Multiply => write!(f, "mul"),
+			Instruction::Divide => write!(f, "div"),
+			Instruction::Power => write!(f, "pow"),
+			Instruction::BitOr => write!(f, "or"),
+			Instruction::BitAnd => write!(f, "and"),
+			Instruction::BitXor => write!(f, "xor"),
+			Instruction::ShiftLeft => write!(f, "shl"),
+			Instruction::ShiftRight => write!(f, "shr"),
+			Instruction::Less => write!(f, "lt"),
+			Instruction::Greater => write!(f, "gt"),
+			Instruction::LessEqual => write!(f, "le"),
+			Instruction::GreaterEqual => write!(f, "ge"),
+			Instruction::Equal => write!(f, "eq"),
+			Instruction::NotEqual => write!(f, "ne"),
+			Instruction::MakeArray(length) => write!(f, "make_array {}", length),
+			Instruction::Index => write!(f, "index"),
+			Instruction::StoreIndex(slot, depth) => write!(f, "store_index {} {}", slot, depth),
+			Instruction::Negate => write!(f, "neg"),
+			Instruction::Not => write!(f, "not"),
+			Instruction::ToInt => write!(f, "to_int"),
+			Instruction::ToFloat => write!(f, "to_float"),
+			Instruction::Jump(target) => write!(f, "jump {}", target),
+			Instruction::JumpIfFalse(target) => write!(f, "jump_if_false {}", target),
+			Instruction::Call(function) => write!(f, "call {}", function),
+			Instruction::CallBuiltin(builtin) => write!(f, "call_builtin {}", builtin.name()),
+			Instruction::Return => write!(f, "ret"),
+			Instruction::ReturnVoid => write!(f, "ret_void"),
+		}
+	}
+}
@@ -0,0 +1,19 @@
+//! Compiling the [AST](crate::ast) to bytecode and executing it in a stack-based virtual machine.
+//!
+//! Compared to the tree-walking [interpreter](crate::interpreter), names of variables and functions are resolved
+//! once during compilation, so the [`Vm`] only works with slot and function indices. The [`Compiler`] translates the
+//! [IR](crate::ir) of the program, whose locals become the slots.
+
+mod compiler;
+mod error;
+mod instruction;
+mod program;
+#[cfg(test)]
+mod test;
+mod vm;
+
+pub use compiler::Compiler;
+pub use error::{CompileError, RuntimeError};
+pub use instruction::Instruction;
+pub use program::{Function, Program};
+pub use vm::Vm;
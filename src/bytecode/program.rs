@@ -0,0 +1,49 @@
+use std::fmt;
+
+use super::Instruction;
+
+/// A compiled program, consisting of all its functions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Program {
+	/// All functions of the program. [`Instruction::Call`] refers to a function by its index in this list.
+	pub functions: Vec<Function>,
+}
+
+impl Program {
+	/// Returns the index of the function with the given name.
+	pub fn function_index(&self, name: &str) -> Option<usize> {
+		self.functions.iter().position(|function| function.name == name)
+	}
+}
+
+/// A compiled function.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Function {
+	/// The name of the function.
+	pub name: String,
+	/// The number of arguments. Arguments occupy the first local slots.
+	pub arity: usize,
+	/// The number of local slots including the arguments.
+	pub locals: usize,
+	/// Whether the function has to return a value.
+	pub returns_value: bool,
+	/// The instructions of the function body.
+	pub code: Vec<Instruction>,
+}
+
+impl fmt::Display for Program {
+	/// Formats the program as human-readable listing of all instructions.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, function) in self.functions.iter().enumerate() {
+			writeln!(
+				f,
+				"function {} `{}` (arity {}, locals {}):",
+				index, function.name, function.arity, function.locals
+			)?;
+			for (address, instruction) in function.code.iter().enumerate() {
+				writeln!(f, "\t{:>4}  {}", address, instruction)?;
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,168 @@
+use crate::{
+	bytecode::{Compiler, RuntimeError, Vm},
+	interpreter::Value,
+	parser,
+};
+
+/// Tests that nested function calls and loops compute the right result.
+#[test]
+fn test_function_call_in_loop() {
+	let source_code = "
+		def square(x: int): int {
+			return x * x
+		}
+		def main(): int {
+			var i: int = 0
+			var sum: int = 0
+			while i < 4 {
+				sum = sum + square(i)
+				i = i + 1
+			}
+			return sum
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(14))));
+}
+
+/// Tests that a for loop can return from its body.
+#[test]
+fn test_for_loop() {
+	let source_code = "
+		def main(): int {
+			for var i: int = 1; i < 100; i = i * 2 {
+				if 20 < i {
+					return i
+				}
+			}
+			return 0
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(32))));
+}
+
+/// Tests that `continue` skips the rest of the loop body, but not the step, and that `break` leaves the loop.
+#[test]
+fn test_break_continue() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 100; i = i + 1 {
+				if i = 2 {
+					continue
+				}
+				if i = 5 {
+					break
+				}
+				sum = sum + i
+			}
+			var j: int = 0
+			while true {
+				j = j + 1
+				if j < 10 {
+					continue
+				}
+				break
+			}
+			return sum + j
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(18))));
+}
+
+/// Tests that the rhs of `and`/`or` is not evaluated if the lhs already determines the result.
+#[test]
+fn test_logical_short_circuit() {
+	let source_code = "
+		def main(): bool {
+			var zero: int = 0
+			var a: bool = (false and ((1 / zero) = 0))
+			var b: bool = (true or ((1 / zero) = 0))
+			return (a = false) and (b and ((1 < 2) or false))
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Bool(true))));
+}
+
+/// Tests that unary operators bind stronger than binary ones.
+#[test]
+fn test_unary_expression() {
+	let source_code = "
+		def main(): int {
+			var x: int = 3
+			var done: bool = !(x < 2)
+			if !done {
+				return 0
+			}
+			return -x * 2 - -x
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(-3))));
+}
+
+/// Tests that both branches of an if-else are reachable.
+#[test]
+fn test_if_else() {
+	let source_code = "
+		def sign(x: float): int {
+			if x < 0.0 {
+				return 0 - 1
+			} else {
+				if x = 0.0 {
+					return 0
+				}
+			}
+			return 1
+		}
+		def main(): int {
+			return sign(2.5) * 100 + sign(0.0) * 10 + sign((0.0 - 3.0))
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(99))));
+}
+
+/// Tests that `**` is compiled to an instruction for both integers and floats.
+#[test]
+fn test_power() {
+	let source_code = "
+		def main(): int {
+			var half: float = (0.25 ** 0.5)
+			return 3 ** 4 + (half * 2.0) as int
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(82))));
+}
+
+/// Tests that array elements can be written in a loop and read back, and that out of bounds indices are detected.
+#[test]
+fn test_array() {
+	let source_code = "
+		def main(): int {
+			var squares: [int; 4] = [0, 0, 0, 0]
+			for var i: int = 0; i < 4; i = i + 1 {
+				squares[i] = i * i
+			}
+			return squares[1] + squares[2] + squares[3]
+		}
+	";
+	assert_eq!(run(source_code), Ok(Some(Value::Int(14))));
+	assert_eq!(
+		run("def main(): int {\n\tvar a: [int; 1] = [1]\n\treturn a[(0 - 1)]\n}"),
+		Err(RuntimeError::IndexOutOfBounds { index: -1, length: 1 })
+	);
+}
+
+/// Tests that leaving a function with return type without `return` is detected.
+#[test]
+fn test_missing_return_value() {
+	assert_eq!(
+		run("def main(): int {\n\tvar x: int = 1\n}"),
+		Err(RuntimeError::MissingReturnValue { function: "main".to_owned() })
+	);
+}
+
+/// Boilerplate code for lexing, parsing, compiling and executing source code.
+fn run(source_code: &str) -> Result<Option<Value>, RuntimeError> {
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	let program = Compiler::compile(ast_nodes.into_iter()).unwrap();
+	Vm::new(&program).run()
+}
@@ -0,0 +1,217 @@
+use super::{Instruction, Program, RuntimeError};
+use crate::{builtin, interpreter::Value};
+
+/// State of one function call.
+#[derive(Debug)]
+struct Frame {
+	/// Index of the called function in [`Program::functions`].
+	function: usize,
+	/// Index of the next instruction to execute.
+	instruction_pointer: usize,
+	/// Position of the first local slot on the stack.
+	base: usize,
+}
+
+/// Stack-based virtual machine that executes a bytecode [`Program`].
+///
+/// The local variables of each function call live on the same stack as the operands, right below them.
+#[derive(Debug)]
+pub struct Vm<'a> {
+	program: &'a Program,
+	stack: Vec<Value>,
+	frames: Vec<Frame>,
+}
+
+impl<'a> Vm<'a> {
+	/// Creates a [`Vm`] for executing the given `program`.
+	pub fn new(program: &'a Program) -> Self {
+		Self { program, stack: Vec::new(), frames: Vec::new() }
+	}
+
+	/// Executes the program by calling its `main()` function and returns the value returned by it.
+	#[tracing::instrument(skip_all)]
+	pub fn run(&mut self) -> Result<Option<Value>, RuntimeError> {
+		let main = self.program.function_index("main").ok_or(RuntimeError::MissingMainFunction)?;
+		self.call(main);
+
+		loop {
+			let frame = self.frames.last_mut().expect("No function is being executed");
+			let instruction = &self.program.functions[frame.function].code[frame.instruction_pointer];
+			frame.instruction_pointer += 1;
+
+			match *instruction {
+				Instruction::Push(ref value) => self.stack.push(value.clone()),
+				Instruction::Pop => {
+					self.pop();
+				},
+				Instruction::Load(slot) => {
+					let value = self.stack[frame.base + slot].clone();
+					self.stack.push(value);
+				},
+				Instruction::Store(slot) => {
+					let slot = frame.base + slot;
+					self.stack[slot] = self.pop();
+				},
+				Instruction::MakeArray(length) => {
+					let elements = self.stack.split_off(self.stack.len() - length);
+					self.stack.push(Value::Array(elements.into_boxed_slice()));
+				},
+				Instruction::StoreIndex(slot, depth) => {
+					let address = frame.base + slot;
+					let value = self.pop();
+					let indices = self.stack.split_off(self.stack.len() - depth);
+					let mut element = &mut self.stack[address];
+					for index in indices {
+						element = match (element, index) {
+							(Value::Array(elements), Value::Int(index)) => {
+								let index = array_index(index, elements.len())?;
+								&mut elements[index]
+							},
+							(array, index) => {
+								return Err(RuntimeError::TypeMismatch {
+									instruction: Instruction::StoreIndex(slot, depth).to_string(),
+									lhs: array.clone(),
+									rhs: index,
+								})
+							},
+						};
+					}
+					*element = value;
+				},
+				Instruction::Jump(target) => frame.instruction_pointer = target,
+				Instruction::JumpIfFalse(target) => {
+					if !self.pop().is_truthy() {
+						self.frames.last_mut().unwrap().instruction_pointer = target;
+					}
+				},
+				Instruction::Call(function) => self.call(function),
+				Instruction::CallBuiltin(builtin) => {
+					let args = self.stack.split_off(self.stack.len() - builtin.arity());
+					if let Some(value) = builtin.call(&args) {
+						self.stack.push(value);
+					}
+				},
+				Instruction::Return => {
+					let value = self.pop();
+					if self.ret().is_none() {
+						return Ok(Some(value));
+					}
+					self.stack.push(value);
+				},
+				Instruction::ReturnVoid => {
+					let function = &self.program.functions[frame.function];
+					if function.returns_value {
+						return Err(RuntimeError::MissingReturnValue { function: function.name.clone() });
+					}
+					if self.ret().is_none() {
+						return Ok(None);
+					}
+				},
+				ref
+				operation @ (Instruction::Negate | Instruction::Not | Instruction::ToInt | Instruction::ToFloat) => {
+					let operand = self.pop();
+					let result = unary_operation(operation, operand)?;
+					self.stack.push(result);
+				},
+				ref operation => {
+					let rhs = self.pop();
+					let lhs = self.pop();
+					let result = binary_operation(operation, lhs, rhs)?;
+					self.stack.push(result);
+				},
+			}
+		}
+	}
+
+	/// Starts executing the function with the given index. Its arguments are the topmost values on the stack.
+	fn call(&mut self, function: usize) {
+		let function_data = &self.program.functions[function];
+		let base = self.stack.len() - function_data.arity;
+		// Initialize the remaining local slots, which are assigned by a variable declaration before they are read
+		self.stack.resize(base + function_data.locals, Value::Int(0));
+		self.frames.push(Frame { function, instruction_pointer: 0, base });
+	}
+
+	/// Leaves the current function and drops its locals. Returns [`None`] if no caller is left.
+	fn ret(&mut self) -> Option<()> {
+		let frame = self.frames.pop().expect("No function is being executed");
+		self.stack.truncate(frame.base);
+		self.frames.last().map(|_| ())
+	}
+
+	fn pop(&mut self) -> Value {
+		self.stack.pop().expect("Stack underflow")
+	}
+}
+
+/// Checks that the `index` points into an array with `length` elements.
+fn array_index(index: i64, length: usize) -> Result<usize, RuntimeError> {
+	match usize::try_from(index) {
+		Ok(index) if index < length => Ok(index),
+		_ => Err(RuntimeError::IndexOutOfBounds { index, length }),
+	}
+}
+
+/// Applies a negation instruction to a value.
+fn unary_operation(instruction: &Instruction, operand: Value) -> Result<Value, RuntimeError> {
+	Ok(match (instruction, &operand) {
+		(Instruction::Negate, Value::Int(int)) => Value::Int(int.wrapping_neg()),
+		(Instruction::Negate, Value::Float(float)) => Value::Float(-float),
+		(Instruction::Not, Value::Bool(boolean)) => Value::Bool(!boolean),
+		(Instruction::ToInt, Value::Int(int)) => Value::Int(*int),
+		(Instruction::ToInt, Value::Float(float)) => Value::Int(*float as i64),
+		(Instruction::ToFloat, Value::Int(int)) => Value::Float(*int as f64),
+		(Instruction::ToFloat, Value::Float(float)) => Value::Float(*float),
+		(instruction, _) => return Err(RuntimeError::InvalidOperand { instruction: instruction.to_string(), operand }),
+	})
+}
+
+/// Applies an arithmetic or comparison instruction to two values.
+fn binary_operation(instruction: &Instruction, lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
+	Ok(match (instruction, &lhs, &rhs) {
+		(Instruction::Add, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_add(*r)),
+		(Instruction::Subtract, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_sub(*r)),
+		(Instruction::Multiply, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_mul(*r)),
+		(Instruction::Divide, Value::Int(_), Value::Int(0)) => return Err(RuntimeError::DivisionByZero),
+		(Instruction::Divide, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_div(*r)),
+		(Instruction::Power, Value::Int(l), Value::Int(r)) => Value::Int(builtin::int_power(*l, *r)),
+		(Instruction::BitOr, Value::Int(l), Value::Int(r)) => Value::Int(l | r),
+		(Instruction::BitAnd, Value::Int(l), Value::Int(r)) => Value::Int(l & r),
+		(Instruction::BitXor, Value::Int(l), Value::Int(r)) => Value::Int(l ^ r),
+		(Instruction::ShiftLeft, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_shl(*r as u32)),
+		(Instruction::ShiftRight, Value::Int(l), Value::Int(r)) => Value::Int(l.wrapping_shr(*r as u32)),
+		(Instruction::Less, Value::Int(l), Value::Int(r)) => Value::Bool(l < r),
+		(Instruction::Greater, Value::Int(l), Value::Int(r)) => Value::Bool(l > r),
+		(Instruction::LessEqual, Value::Int(l), Value::Int(r)) => Value::Bool(l <= r),
+		(Instruction::GreaterEqual, Value::Int(l), Value::Int(r)) => Value::Bool(l >= r),
+		(Instruction::Equal, Value::Int(l), Value::Int(r)) => Value::Bool(l == r),
+		(Instruction::NotEqual, Value::Int(l), Value::Int(r)) => Value::Bool(l != r),
+		(Instruction::Add, Value::Float(l), Value::Float(r)) => Value::Float(l + r),
+		(Instruction::Subtract, Value::Float(l), Value::Float(r)) => Value::Float(l - r),
+		(Instruction::Multiply, Value::Float(l), Value::Float(r)) => Value::Float(l * r),
+		(Instruction::Divide, Value::Float(l), Value::Float(r)) => Value::Float(l / r),
+		(Instruction::Power, Value::Float(l), Value::Float(r)) => Value::Float(l.powf(*r)),
+		(Instruction::Less, Value::Float(l), Value::Float(r)) => Value::Bool(l < r),
+		(Instruction::Greater, Value::Float(l), Value::Float(r)) => Value::Bool(l > r),
+		(Instruction::LessEqual, Value::Float(l), Value::Float(r)) => Value::Bool(l <= r),
+		(Instruction::GreaterEqual, Value::Float(l), Value::Float(r)) => Value::Bool(l >= r),
+		(Instruction::Equal, Value::Float(l), Value::Float(r)) => Value::Bool(l == r),
+		(Instruction::NotEqual, Value::Float(l), Value::Float(r)) => Value::Bool(l != r),
+		(Instruction::Index, Value::Array(elements), Value::Int(index)) => {
+			elements[array_index(*index, elements.len())?].clone()
+		},
+		(Instruction::Less, Value::Char(l), Value::Char(r)) => Value::Bool(l < r),
+		(Instruction::Greater, Value::Char(l), Value::Char(r)) => Value::Bool(l > r),
+		(Instruction::LessEqual, Value::Char(l), Value::Char(r)) => Value::Bool(l <= r),
+		(Instruction::GreaterEqual, Value::Char(l), Value::Char(r)) => Value::Bool(l >= r),
+		(Instruction::Equal, Value::Char(l), Value::Char(r)) => Value::Bool(l == r),
+		(Instruction::NotEqual, Value::Char(l), Value::Char(r)) => Value::Bool(l != r),
+		(Instruction::Equal, Value::Bool(l), Value::Bool(r)) => Value::Bool(l == r),
+		(Instruction::NotEqual, Value::Bool(l), Value::Bool(r)) => Value::Bool(l != r),
+		(Instruction::Equal, Value::Null, Value::Null) => Value::Bool(true),
+		(Instruction::NotEqual, Value::Null, Value::Null) => Value::Bool(false),
+		(instruction, _, _) => {
+			return Err(RuntimeError::TypeMismatch { instruction: instruction.to_string(), lhs, rhs })
+		},
+	})
+}
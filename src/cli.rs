@@ -1,28 +1,175 @@
+use fortytwolang::passes::{OptLevel, Pass};
+
 /// FORTYTWO-LANG COMPILER
 #[derive(clap::Parser, Debug)]
 #[clap(author, version, about)]
+#[clap(
+	after_help = "Exit codes: 1 for other errors, 2 for usage errors, 3 for syntax errors, 4 for type errors and 5 \
+                     for I/O errors. Programs that are run exit with their own exit code."
+)]
 pub struct Args {
 	#[clap(subcommand)]
 	pub command: Command,
+	/// When to highlight error messages with colors.
+	#[clap(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+	pub color: ColorChoice,
+	/// Treat warnings as errors, so that the program is neither compiled nor executed if there are any.
+	#[clap(long, global = true)]
+	pub deny_warnings: bool,
 }
 
 #[derive(clap::Parser, Debug)]
 pub enum Command {
-	/// Format the code.
+	/// Format the code in the style configured by the nearest `.ftlfmt.toml`.
 	Fmt {
 		/// The file to format. Note that this file will be overwritten.
 		file: std::path::PathBuf,
+		/// Do not format, but print the changes the formatting would make as a diff, and fail if there are any.
+		#[clap(long)]
+		check: bool,
 	},
 
-	/// Compile to an executable.
-	Compile {
-		/// The file to compile.
-		file: std::path::PathBuf,
+	/// Compile to an executable, which is named after the first file.
+	#[clap(alias = "compile")]
+	Build {
+		/// The files to compile into a single program.
+		#[clap(required = true)]
+		files: Vec<std::path::PathBuf>,
+		/// The intermediate language to compile to before creating the executable.
+		#[clap(long, value_enum, default_value_t = Target::C)]
+		target: Target,
+		#[command(flatten)]
+		optimization: Optimization,
 	},
 
 	/// Compile and execute.
 	Run {
-		/// The file to run.
+		/// The files to compile into a single program and run.
+		#[clap(required = true)]
+		files: Vec<std::path::PathBuf>,
+		/// The intermediate language to compile to before creating the executable.
+		#[clap(long, value_enum, default_value_t = Target::C)]
+		target: Target,
+		#[command(flatten)]
+		optimization: Optimization,
+	},
+
+	/// Read and execute code interactively, line by line.
+	Repl,
+
+	/// Run a language server, which speaks the Language Server Protocol over stdin and stdout for editors like VS Code.
+	Lsp,
+
+	/// Check the program for errors and warnings, without compiling or executing it.
+	Check {
+		/// The files to check as a single program.
+		#[clap(required = true)]
+		files: Vec<std::path::PathBuf>,
+	},
+
+	/// Print the tokens the lexer produces for a file as a table with their kinds and positions.
+	Tokens {
+		/// The file to lex.
+		file: std::path::PathBuf,
+	},
+
+	/// Print the abstract syntax tree the parser produces for a file.
+	Ast {
+		/// The file to parse. Imported modules are not loaded.
+		file: std::path::PathBuf,
+		/// How to print the abstract syntax tree.
+		#[clap(long, value_enum, default_value_t = AstFormat::Tree)]
+		format: AstFormat,
+	},
+
+	/// Print a file as HTML document with syntax highlighting. Files with syntax errors are highlighted, too.
+	Highlight {
+		/// The file to highlight.
 		file: std::path::PathBuf,
 	},
+
+	/// Explain an error code, like `E0102`, in detail.
+	Explain {
+		/// The error code to explain.
+		code: String,
+	},
+
+	/// Execute without compiling, using the interpreter.
+	Interpret {
+		/// The files to interpret as a single program.
+		#[clap(required = true)]
+		files: Vec<std::path::PathBuf>,
+		/// Compile to bytecode and execute it in a virtual machine instead of walking the AST.
+		#[clap(long)]
+		bytecode: bool,
+		/// Compile to native code just in time and execute it in-process instead of walking the AST.
+		#[cfg(feature = "jit")]
+		#[clap(long, conflicts_with = "bytecode")]
+		jit: bool,
+		#[command(flatten)]
+		optimization: Optimization,
+	},
+}
+
+/// Options selecting the optimization passes of the commands which compile or interpret a program.
+#[derive(clap::Args, Debug)]
+pub struct Optimization {
+	/// The optimization level: 0 for none, 1 for constant folding and 2 to also inline small functions and remove dead
+	/// code, like branches whose condition is always false and unused variables. `-O` is `-O2`. Jump threading on the
+	/// IR only runs for the LLVM and WebAssembly targets and the bytecode interpreter.
+	#[clap(short = 'O', long, default_value = "1", default_missing_value = "2", num_args = 0..=1)]
+	pub opt_level: OptLevel,
+	/// Print the program to stderr after the optimization pass, one of `inline`, `const-fold`, `dce` and
+	/// `thread-jumps`. `thread-jumps` only runs on the IR, see `--opt-level`.
+	#[clap(long, value_name = "PASS", require_equals = true)]
+	pub print_after: Option<Pass>,
+}
+
+/// Intermediate languages an executable can be created from.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+	/// C code, compiled with `cc`.
+	C,
+	/// LLVM IR, compiled with `llc` and `cc`.
+	Llvm,
+	/// WebAssembly text format. Only the `.wat` file is created, which can be run in a WebAssembly runtime.
+	Wasm,
+}
+
+/// Output formats of the abstract syntax tree.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstFormat {
+	/// A compact tree with a line per node, showing its kind, name and span.
+	Tree,
+	/// Rust's debug representation, showing all fields of the nodes.
+	Debug,
+	/// A GraphViz graph, which can be rendered e.g. with `dot -Tpng`.
+	Dot,
+	/// JSON, which is meant to be consumed by other tools. Positions only contain the name of the file.
+	#[cfg(feature = "serde")]
+	Json,
+}
+
+/// When to highlight error messages with colors.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+	/// Always use colors.
+	Always,
+	/// Never use colors.
+	Never,
+	/// Use colors if stderr is a terminal and the `NO_COLOR` environment variable is not set.
+	Auto,
+}
+
+impl ColorChoice {
+	/// Whether error messages should be colored.
+	pub fn enabled(self) -> bool {
+		match self {
+			ColorChoice::Always => true,
+			ColorChoice::Never => false,
+			ColorChoice::Auto => {
+				std::io::IsTerminal::is_terminal(&std::io::stderr()) && std::env::var_os("NO_COLOR").is_none()
+			},
+		}
+	}
 }
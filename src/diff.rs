@@ -0,0 +1,182 @@
+//! Line-based differences between two texts, shown in the unified format of `diff -u`.
+
+use std::fmt::Write;
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// How a line of the old text was changed in the new text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+	/// The line is in both texts.
+	Equal,
+	/// The line is only in the old text.
+	Delete,
+	/// The line is only in the new text.
+	Insert,
+}
+
+/// Creates a unified diff from the `old` text named `old_name` to the `new` text named `new_name`. The diff is empty if
+/// both texts are equal.
+pub fn unified(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+	// Lines keep their newline, so that a missing newline at the end of the text is a difference, too
+	let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+	let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+	let changes = changes(&old_lines, &new_lines);
+
+	let mut diff = String::new();
+	if changes.iter().all(|(change, _)| *change == Change::Equal) {
+		return diff;
+	}
+	let _ = writeln!(diff, "--- {}\n+++ {}", old_name, new_name);
+	for hunk in hunks(&changes) {
+		let (old_start, new_start) = line_numbers(&changes[..hunk.start]);
+		let (old_count, new_count) = line_numbers(&changes[hunk.clone()]);
+		// An empty range starts at the line before it
+		let start = |start: usize, count: usize| if count == 0 { start } else { start + 1 };
+		let _ = writeln!(
+			diff,
+			"@@ -{},{} +{},{} @@",
+			start(old_start, old_count),
+			old_count,
+			start(new_start, new_count),
+			new_count
+		);
+		for (change, line) in &changes[hunk] {
+			let prefix = match change {
+				Change::Equal => ' ',
+				Change::Delete => '-',
+				Change::Insert => '+',
+			};
+			match line.strip_suffix('\n') {
+				Some(line) => {
+					let _ = writeln!(diff, "{}{}", prefix, line);
+				},
+				None => {
+					let _ = writeln!(diff, "{}{}\n\\ No newline at end of file", prefix, line);
+				},
+			}
+		}
+	}
+	diff
+}
+
+/// The changes that turn the `old` lines into the `new` lines, which are derived from their longest common
+/// subsequence.
+fn changes<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Change, &'a str)> {
+	// Lines at the start and end are usually unchanged, so they are not part of the quadratic table
+	let prefix = old.iter().zip(new).take_while(|(old, new)| old == new).count();
+	let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(old, new)| old == new).count();
+	let (old_changed, new_changed) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+	// `common[i][j]` is the length of the longest common subsequence of `old_changed[i..]` and `new_changed[j..]`
+	let mut common = vec![vec![0; new_changed.len() + 1]; old_changed.len() + 1];
+	for i in (0..old_changed.len()).rev() {
+		for j in (0..new_changed.len()).rev() {
+			common[i][j] = match old_changed[i] == new_changed[j] {
+				true => common[i + 1][j + 1] + 1,
+				false => common[i + 1][j].max(common[i][j + 1]),
+			};
+		}
+	}
+
+	let mut changes = old[..prefix].iter().map(|line| (Change::Equal, *line)).collect::<Vec<_>>();
+	let (mut i, mut j) = (0, 0);
+	while i < old_changed.len() || j < new_changed.len() {
+		if i < old_changed.len() && j < new_changed.len() && old_changed[i] == new_changed[j] {
+			changes.push((Change::Equal, old_changed[i]));
+			i += 1;
+			j += 1;
+		} else if i < old_changed.len() && (j == new_changed.len() || common[i + 1][j] >= common[i][j + 1]) {
+			// Like in `diff`, deleted lines are shown before the inserted lines replacing them
+			changes.push((Change::Delete, old_changed[i]));
+			i += 1;
+		} else {
+			changes.push((Change::Insert, new_changed[j]));
+			j += 1;
+		}
+	}
+	changes.extend(old[old.len() - suffix..].iter().map(|line| (Change::Equal, *line)));
+	changes
+}
+
+/// The ranges of the `changes` shown together, each of which contains changed lines with their context. Changes that
+/// are close to each other share a hunk.
+fn hunks(changes: &[(Change, &str)]) -> Vec<std::ops::Range<usize>> {
+	let mut hunks: Vec<std::ops::Range<usize>> = Vec::new();
+	for (index, _) in changes.iter().enumerate().filter(|(_, (change, _))| *change != Change::Equal) {
+		let start = index.saturating_sub(CONTEXT);
+		let end = (index + 1 + CONTEXT).min(changes.len());
+		match hunks.last_mut() {
+			Some(hunk) if hunk.end >= start => hunk.end = end,
+			_ => hunks.push(start..end),
+		}
+	}
+	hunks
+}
+
+/// The number of lines of the old and the new text the `changes` span.
+fn line_numbers(changes: &[(Change, &str)]) -> (usize, usize) {
+	let old = changes.iter().filter(|(change, _)| *change != Change::Insert).count();
+	let new = changes.iter().filter(|(change, _)| *change != Change::Delete).count();
+	(old, new)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Tests that the diff of equal texts is empty.
+	#[test]
+	fn test_identical() {
+		assert_eq!(unified("", "", "a", "b"), "");
+		assert_eq!(unified(&lines(1, 10), &lines(1, 10), "a", "b"), "");
+	}
+
+	/// Tests that an inserted line is shown with three lines of context before and after it.
+	#[test]
+	fn test_insert() {
+		let new = format!("{}new\n{}", lines(1, 5), lines(6, 10));
+		let expected = "--- a\n+++ b\n@@ -3,6 +3,7 @@\n 3\n 4\n 5\n+new\n 6\n 7\n 8\n";
+		assert_eq!(unified(&lines(1, 10), &new, "a", "b"), expected);
+		// An empty range starts at the line before it
+		assert_eq!(unified("", "x\ny\n", "a", "b"), "--- a\n+++ b\n@@ -0,0 +1,2 @@\n+x\n+y\n");
+	}
+
+	/// Tests that a line deleted at the start has context only after it.
+	#[test]
+	fn test_delete() {
+		let expected = "--- a\n+++ b\n@@ -1,4 +1,3 @@\n-1\n 2\n 3\n 4\n";
+		assert_eq!(unified(&lines(1, 10), &lines(2, 10), "a", "b"), expected);
+	}
+
+	/// Tests that a changed line is shown as deleted and inserted, that changes far apart are shown in separate hunks,
+	/// and that changes close to each other share a hunk.
+	#[test]
+	fn test_change() {
+		let new = lines(1, 20).replace("\n2\n", "\ntwo\n").replace("\n18\n", "\neighteen\n");
+		let expected = concat!(
+			"--- a\n+++ b\n",
+			"@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n",
+			"@@ -15,6 +15,6 @@\n 15\n 16\n 17\n-18\n+eighteen\n 19\n 20\n",
+		);
+		assert_eq!(unified(&lines(1, 20), &new, "a", "b"), expected);
+
+		let new = lines(1, 10).replace("\n3\n", "\nthree\n").replace("\n8\n", "\neight\n");
+		let diff = unified(&lines(1, 10), &new, "a", "b");
+		assert_eq!(diff.matches("@@ -").count(), 1);
+		assert!(diff.contains("@@ -1,10 +1,10 @@\n"));
+	}
+
+	/// Tests that a missing newline at the end of a text is a difference, which is marked like by `diff`.
+	#[test]
+	fn test_missing_newline() {
+		let expected = "--- a\n+++ b\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n";
+		assert_eq!(unified("a\nb", "a\nb\n", "a", "b"), expected);
+	}
+
+	/// The numbers from `start` to `end` (both inclusive), each in its own line.
+	fn lines(start: usize, end: usize) -> String {
+		(start..=end).map(|number| format!("{}\n", number)).collect()
+	}
+}
@@ -1,28 +1,62 @@
 //! C emitter.
+//!
+//! Each function and instruction is preceded by a `#line` directive with its FTL source position, so that compiler
+//! errors, debuggers and sanitizers refer to the FTL source instead of the generated C code.
 
-use std::io;
+#[cfg(test)]
+mod test;
+
+use std::{collections::HashMap, io};
 
 use crate::{
 	ast,
 	ast::{
-		expression::BinaryOperator,
+		expression::{BinaryOperator, UnaryOperator},
 		statement::{BasicDataType, DataType},
 		Expression,
 	},
-	source::PositionContainer,
+	builtin::Builtin,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 };
 
+/// Included headers and the implementation of the [builtin functions](crate::builtin).
+const PRELUDE: &str = include_str!("prelude.h");
+
 /// Emits C code.
-pub struct Emitter {
-	writer: Box<dyn io::Write>,
+pub struct Emitter<W> {
+	writer: W,
+	/// Values of the top-level constants. C only allows literals in initializers of globals, so the value of a global
+	/// constant is emitted inline wherever it is used.
+	constants: HashMap<Symbol, Expression>,
 }
 
-impl super::Emitter for Emitter {
-	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: Box<dyn io::Write>) -> io::Result<()> {
-		let mut this = Self { writer };
+impl<W: io::Write> super::Emitter<W> for Emitter<W> {
+	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: W) -> io::Result<()> {
+		let mut this = Self { writer, constants: HashMap::new() };
 
 		// Prelude
-		writeln!(this.writer, "#include <stdio.h>\n#include <stdlib.h>")?;
+		writeln!(this.writer, "{}", PRELUDE)?;
+
+		// Structs and functions may be used before their definition, so declare all of them first
+		let ast_nodes: Vec<ast::Node> = ast_nodes.collect();
+		for ast_node in &ast_nodes {
+			match ast_node {
+				ast::Node::Struct(struct_) => writeln!(this.writer, "typedef struct {0} {0};", c_name(&struct_.name))?,
+				ast::Node::Function(function) => {
+					this.function_prototype(function.prototype.clone())?;
+					writeln!(this.writer, ";")?;
+				},
+				ast::Node::FunctionPrototype(_) => (), // extern function, declared by the included headers
+				ast::Node::Const(const_declaration) => {
+					this.constants.insert(const_declaration.name.value, const_declaration.value.clone());
+				},
+				ast::Node::Enum(enum_) => this
+					.constants
+					.extend(enum_.constants().into_iter().map(|constant| (constant.name.value, constant.value))),
+				ast::Node::TypeAlias(_) | ast::Node::Import(_) | ast::Node::Comment(_) => (),
+			}
+		}
 
 		for ast_node in ast_nodes {
 			this.ast_node(ast_node)?;
@@ -32,127 +66,324 @@ impl super::Emitter for Emitter {
 }
 
 /// Each of the functions in this impl block is responsible for emitting the corresponding AST node.
-impl Emitter {
+impl<W: io::Write> Emitter<W> {
 	fn ast_node(&mut self, node: ast::Node) -> io::Result<()> {
 		match node {
 			ast::Node::Function(function) => self.function(function),
 			ast::Node::Struct(struct_) => self.struct_(struct_),
 			ast::Node::FunctionPrototype(_) => Ok(()), // extern function
-			_ => todo!(),
+			ast::Node::Const(_) => Ok(()),             // emitted inline where it is used
+			ast::Node::Enum(_) => Ok(()),              // variants are emitted inline as their index
+			ast::Node::TypeAlias(_) => Ok(()),         // resolved to the type it stands for
+			ast::Node::Import(_) | ast::Node::Comment(_) => Ok(()),
 		}
 	}
 
 	fn function(&mut self, function: ast::FunctionDefinition) -> io::Result<()> {
 		// Function header
+		self.line_directive(&function.position)?;
+		self.function_prototype(function.prototype)?;
+		writeln!(self.writer, " {{")?;
+
+		// Function body
+		for instruction in function.body {
+			self.instruction(instruction)?;
+		}
+		writeln!(self.writer)?;
+		writeln!(self.writer, "}}")?;
+		Ok(())
+	}
+
+	fn function_prototype(&mut self, prototype: ast::FunctionPrototype) -> io::Result<()> {
 		// Return type
-		match function.prototype.return_type {
+		match prototype.return_type {
+			Some(PositionContainer { value: DataType::Array { .. }, .. }) => {
+				return Err(io::Error::new(io::ErrorKind::Unsupported, "Arrays cannot be returned from functions in C"))
+			},
+			// C requires `main` to return an `int`, which is converted to the exit code anyway
+			Some(PositionContainer { value: DataType::Basic(BasicDataType::Int), .. }) if *prototype.name == "main" => {
+				write!(self.writer, "int")?
+			},
 			Some(return_type) => self.data_type(return_type)?,
 			None => write!(self.writer, "void")?,
 		}
 		write!(self.writer, " ")?;
 
 		// Function name
-		write!(self.writer, "{}(", *function.prototype.name)?;
+		write!(self.writer, "{}(", c_name(&prototype.name))?;
 
 		// Function arguments
-		for (i, arg) in function.prototype.args.into_iter().enumerate() {
+		for (i, arg) in prototype.args.into_iter().enumerate() {
 			if i != 0 {
 				write!(self.writer, ", ")?;
 			}
 			self.function_argument(arg)?;
 		}
-		writeln!(self.writer, ") {{")?;
-
-		// Function body
-		for instruction in function.body {
-			self.instruction(instruction)?;
-		}
-		writeln!(self.writer)?;
-		writeln!(self.writer, "}}")?;
+		write!(self.writer, ")")?;
 		Ok(())
 	}
 
 	fn struct_(&mut self, struct_: ast::Struct) -> io::Result<()> {
-		writeln!(self.writer, "typedef struct {{",)?;
+		// The typedef was already emitted before all definitions
+		writeln!(self.writer, "struct {} {{", c_name(&struct_.name))?;
 		for field in struct_.fields {
-			self.data_type(field.data_type)?;
-			write!(self.writer, " {};", *field.name)?;
+			write!(self.writer, "\t")?;
+			self.declarator(field.data_type, &field.name)?;
+			writeln!(self.writer, ";")?;
 		}
-		writeln!(self.writer, "}} {};", *struct_.name)?;
+		writeln!(self.writer, "}};")?;
 		Ok(())
 	}
 
 	fn instruction(&mut self, instruction: ast::Instruction) -> io::Result<()> {
+		if !matches!(instruction, ast::Instruction::Comment(_)) {
+			self.line_directive(&instruction.source_position())?;
+		}
 		match instruction {
-			ast::Instruction::Expression(expression) => self.expression(expression),
+			ast::Instruction::Expression(expression) => {
+				self.expression(expression)?;
+				writeln!(self.writer, ";")
+			},
 			ast::Instruction::Statement(statement) => self.statement(statement),
 			ast::Instruction::IfElse(if_else) => self.if_else(*if_else),
 			ast::Instruction::WhileLoop(while_loop) => self.while_loop(*while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(*for_loop),
+			// A `switch` only accepts literal cases, but the patterns may be constants
+			ast::Instruction::Match(match_) => {
+				match_.into_if_else().into_iter().try_for_each(|instruction| self.instruction(instruction))
+			},
+			ast::Instruction::Comment(_) => Ok(()),
 		}
 	}
 
+	/// Emits a `#line` directive, which makes the C compiler attribute the following line to the `position`.
+	fn line_directive(&mut self, position: &SourcePositionRange) -> io::Result<()> {
+		let name = position.source.name.replace('\\', "\\\\").replace('"', "\\\"");
+		writeln!(self.writer, "#line {} \"{}\"", position.position.start.line, name)
+	}
+
 	fn expression(&mut self, expression: ast::Expression) -> io::Result<()> {
 		match expression {
 			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::UnaryExpression(unary_expression) => self.unary_expression(unary_expression),
+			Expression::Cast(cast) => self.cast(cast),
 			Expression::FunctionCall(function_call) => self.function_call(function_call),
+			Expression::FieldAccess(field_access) => self.field_access(field_access),
+			Expression::StructLiteral(struct_literal) => self.struct_literal(struct_literal),
+			Expression::ArrayLiteral(_) => Err(io::Error::new(
+				io::ErrorKind::Unsupported,
+				"Arrays can only be initialized with array literals in C",
+			)),
+			Expression::IndexExpression(index_expression) => self.index_expression(index_expression),
+			Expression::If(if_expression) => self.if_expression(if_expression),
 			Expression::Number(number) => self.number(number),
+			Expression::Bool(boolean) => self.bool(boolean),
+			Expression::Char(char) => self.char(char),
+			Expression::StringLiteral(string) => self.string_literal(string),
+			Expression::Null(_) => write!(self.writer, "NULL"),
 			Expression::Variable(variable) => self.variable(variable),
 		}
 	}
 
 	fn binary_expression(&mut self, binary_expression: ast::expression::BinaryExpression) -> io::Result<()> {
+		// C has no exponentiation operator, so the prelude selects `pow` or its own function for integers by the type
+		if *binary_expression.operator == BinaryOperator::Power {
+			write!(self.writer, "ftl_power(")?;
+			self.expression(*binary_expression.lhs)?;
+			write!(self.writer, ", ")?;
+			self.expression(*binary_expression.rhs)?;
+			return write!(self.writer, ")");
+		}
+		// Comparisons are `int`s in C, so cast them to `bool` to print them like booleans
+		let is_bool = binary_expression.operator.is_comparison()
+			|| matches!(*binary_expression.operator, BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr);
+		if is_bool {
+			write!(self.writer, "((bool)")?;
+		}
+		// Parentheses of the FTL source code are not part of the AST, so always add them to keep the evaluation order
+		write!(self.writer, "(")?;
 		self.expression(*binary_expression.lhs)?;
 		let operator = match *binary_expression.operator {
 			ast::expression::BinaryOperator::Add => "+",
 			ast::expression::BinaryOperator::Subtract => "-",
 			ast::expression::BinaryOperator::Multiply => "*",
 			ast::expression::BinaryOperator::Divide => "/",
+			BinaryOperator::Power => unreachable!("`**` is emitted as call"),
+			BinaryOperator::BitOr => "|",
+			BinaryOperator::BitAnd => "&",
+			BinaryOperator::BitXor => "^",
+			BinaryOperator::ShiftLeft => "<<",
+			BinaryOperator::ShiftRight => ">>",
 			BinaryOperator::Less => "<",
 			BinaryOperator::Greater => ">",
+			BinaryOperator::LessEqual => "<=",
+			BinaryOperator::GreaterEqual => ">=",
 			BinaryOperator::Equal => "==",
-			BinaryOperator::NotEqual => "=/=",
+			BinaryOperator::NotEqual => "!=",
+			// Like in FTL, the rhs is only evaluated if the lhs does not already determine the result
+			BinaryOperator::LogicalAnd => "&&",
+			BinaryOperator::LogicalOr => "||",
 		};
 		write!(self.writer, " {} ", operator)?;
 		self.expression(*binary_expression.rhs)?;
+		write!(self.writer, ")")?;
+		if is_bool {
+			write!(self.writer, ")")?;
+		}
 		Ok(())
 	}
 
+	fn unary_expression(&mut self, unary_expression: ast::expression::UnaryExpression) -> io::Result<()> {
+		let operator = match *unary_expression.operator {
+			UnaryOperator::Negate => "-",
+			UnaryOperator::Not => "(bool)!",
+			UnaryOperator::Dereference => "*",
+		};
+		write!(self.writer, "({}", operator)?;
+		self.expression(*unary_expression.operand)?;
+		write!(self.writer, ")")
+	}
+
+	fn cast(&mut self, cast: ast::expression::Cast) -> io::Result<()> {
+		write!(self.writer, "((")?;
+		self.data_type(cast.data_type)?;
+		write!(self.writer, ") ")?;
+		self.expression(*cast.expression)?;
+		write!(self.writer, ")")
+	}
+
+	fn if_expression(&mut self, if_expression: ast::expression::IfExpression) -> io::Result<()> {
+		write!(self.writer, "(")?;
+		self.expression(*if_expression.condition)?;
+		write!(self.writer, " ? ")?;
+		self.expression(*if_expression.if_true)?;
+		write!(self.writer, " : ")?;
+		self.expression(*if_expression.if_false)?;
+		write!(self.writer, ")")
+	}
+
 	fn function_call(&mut self, function_call: ast::expression::FunctionCall) -> io::Result<()> {
-		write!(self.writer, "{}(", *function_call.name)?;
-		for param in function_call.params {
+		match Builtin::from_name(&function_call.name) {
+			// Implemented by the prelude
+			Some(builtin) => write!(self.writer, "ftl_{}(", c_name(builtin.name()))?,
+			None => write!(self.writer, "{}(", c_name(&function_call.name))?,
+		}
+		for (i, param) in function_call.params.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ", ")?;
+			}
 			self.expression(param)?;
 		}
 		write!(self.writer, ")")?;
 		Ok(())
 	}
 
+	fn field_access(&mut self, field_access: ast::expression::FieldAccess) -> io::Result<()> {
+		// Binary and unary expressions are already surrounded by parentheses
+		self.expression(*field_access.base)?;
+		write!(self.writer, ".{}", *field_access.field)
+	}
+
+	fn struct_literal(&mut self, struct_literal: ast::expression::StructLiteral) -> io::Result<()> {
+		// Compound literal with designated initializers
+		write!(self.writer, "(({}){{ ", c_name(&struct_literal.name))?;
+		for (i, field) in struct_literal.fields.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ", ")?;
+			}
+			write!(self.writer, ".{} = ", *field.name)?;
+			self.expression(field.value)?;
+		}
+		write!(self.writer, " }})")
+	}
+
+	fn index_expression(&mut self, index_expression: ast::expression::IndexExpression) -> io::Result<()> {
+		self.expression(*index_expression.base)?;
+		write!(self.writer, "[")?;
+		self.expression(*index_expression.index)?;
+		write!(self.writer, "]")
+	}
+
+	/// Emits the value of a variable declaration, which may be an initializer list for an array.
+	fn initializer(&mut self, expression: ast::Expression) -> io::Result<()> {
+		match expression {
+			Expression::ArrayLiteral(array_literal) => {
+				write!(self.writer, "{{ ")?;
+				for (i, element) in array_literal.elements.into_iter().enumerate() {
+					if i != 0 {
+						write!(self.writer, ", ")?;
+					}
+					self.initializer(element)?;
+				}
+				write!(self.writer, " }}")
+			},
+			expression => self.expression(expression),
+		}
+	}
+
 	fn statement(&mut self, statement: ast::Statement) -> io::Result<()> {
 		match statement {
 			ast::statement::Statement::VariableDeclaration(variable_declaration) => {
-				self.variable_declaration(variable_declaration)
+				self.variable_declaration(*variable_declaration)
+			},
+			ast::statement::Statement::ConstDeclaration(const_declaration) => {
+				self.const_declaration(*const_declaration)
 			},
-			ast::statement::Statement::VariableAssignment(assignment) => self.variable_assignment(assignment),
-			ast::statement::Statement::Return(expression) => self.return_(expression),
+			ast::statement::Statement::Assignment(assignment) => self.assignment(assignment),
+			ast::statement::Statement::Return(expression, _) => self.return_(expression),
+			ast::statement::Statement::Break(_) => writeln!(self.writer, "break;"),
+			ast::statement::Statement::Continue(_) => writeln!(self.writer, "continue;"),
 		}
 	}
 
 	fn variable_declaration(&mut self, variable_declaration: ast::statement::VariableDeclaration) -> io::Result<()> {
-		write!(self.writer, "{} {} = ", *variable_declaration.data_type, *variable_declaration.name)?;
-		self.expression(variable_declaration.value)?;
+		// The semantic analysis annotates the inferred type of variables declared without type
+		let Some(data_type) = variable_declaration.data_type else {
+			return Err(io::Error::other(format!(
+				"{}: Variable `{}` has no type, which the semantic analysis infers",
+				variable_declaration.name.position, *variable_declaration.name
+			)));
+		};
+		self.declarator(data_type, &variable_declaration.name)?;
+		write!(self.writer, " = ")?;
+		self.initializer(variable_declaration.value)?;
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
 
-	fn variable_assignment(&mut self, assignment: ast::statement::VariableAssignment) -> io::Result<()> {
-		write!(self.writer, "{} = ", *assignment.name)?;
-		self.expression(assignment.value)?;
+	fn const_declaration(&mut self, const_declaration: ast::statement::ConstDeclaration) -> io::Result<()> {
+		write!(self.writer, "const ")?;
+		self.variable_declaration(const_declaration.into())
+	}
+
+	fn assignment(&mut self, assignment: ast::statement::Assignment) -> io::Result<()> {
+		self.assignment_expression(assignment)?;
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
 
-	fn return_(&mut self, expression: ast::Expression) -> io::Result<()> {
-		write!(self.writer, "return ")?;
-		self.expression(expression)?;
+	/// Emits an assignment without the trailing semicolon, e.g. for the step of a for loop.
+	fn assignment_expression(&mut self, assignment: ast::statement::Assignment) -> io::Result<()> {
+		self.expression(assignment.target.into())?;
+		// C evaluates the target of a compound assignment only once, like FTL
+		let operator = match assignment.operator.map(|operator| operator.value) {
+			None => "=",
+			Some(BinaryOperator::Add) => "+=",
+			Some(BinaryOperator::Subtract) => "-=",
+			Some(BinaryOperator::Multiply) => "*=",
+			Some(BinaryOperator::Divide) => "/=",
+			Some(operator) => unreachable!("`{:?}` is no compound assignment", operator),
+		};
+		write!(self.writer, " {} ", operator)?;
+		self.expression(assignment.value)
+	}
+
+	fn return_(&mut self, expression: Option<ast::Expression>) -> io::Result<()> {
+		write!(self.writer, "return")?;
+		if let Some(expression) = expression {
+			write!(self.writer, " ")?;
+			self.expression(expression)?;
+		}
 		writeln!(self.writer, ";")?;
 		Ok(())
 	}
@@ -191,9 +422,45 @@ impl Emitter {
 		Ok(())
 	}
 
+	fn for_loop(&mut self, for_loop: ast::ForLoop) -> io::Result<()> {
+		// The initialization is emitted as a statement of its own, so enclose the loop in a block to limit its scope
+		writeln!(self.writer, "{{")?;
+		self.statement(for_loop.initialization)?;
+		write!(self.writer, "for (; ")?;
+		self.expression(for_loop.condition)?;
+		write!(self.writer, "; ")?;
+		self.assignment_expression(for_loop.step)?;
+		writeln!(self.writer, ") {{")?;
+		for instruction in for_loop.body {
+			self.instruction(instruction)?;
+		}
+		writeln!(self.writer, "}}")?;
+		writeln!(self.writer, "}}")?;
+		Ok(())
+	}
+
 	fn function_argument(&mut self, function_argument: ast::statement::FunctionArgument) -> io::Result<()> {
-		self.data_type(function_argument.data_type)?;
-		write!(self.writer, " {}", *function_argument.name)?;
+		if let DataType::Array { .. } = function_argument.data_type.value {
+			// Array parameters decay to pointers in C, which would not copy the array like FTL does
+			return Err(io::Error::new(io::ErrorKind::Unsupported, "Arrays cannot be passed to functions in C"));
+		}
+		self.declarator(function_argument.data_type, &function_argument.name)
+	}
+
+	/// Emits the declaration of `name` with the given data type. In C, the lengths of arrays follow the name, so
+	/// `[[int; 3]; 2]` becomes `int name[2][3]`.
+	fn declarator(&mut self, data_type: PositionContainer<ast::statement::DataType>, name: &str) -> io::Result<()> {
+		let mut lengths = Vec::new();
+		let mut element = data_type.value;
+		while let DataType::Array { element: inner, length } = element {
+			lengths.push(length);
+			element = *inner;
+		}
+		self.data_type(PositionContainer::new(element, data_type.position))?;
+		write!(self.writer, " {}", name)?;
+		for length in lengths {
+			write!(self.writer, "[{}]", length)?;
+		}
 		Ok(())
 	}
 
@@ -201,36 +468,91 @@ impl Emitter {
 		match data_type.value {
 			DataType::Basic(basic_data_type) => self.basic_data_type(basic_data_type),
 			DataType::Struct(struct_name) => self.struct_name(struct_name),
+			DataType::Enum(_) => self.basic_data_type(BasicDataType::Int),
 			DataType::Pointer(pointer) => self.pointer(*pointer),
+			DataType::Array { .. } => {
+				Err(io::Error::new(io::ErrorKind::Unsupported, "Arrays can only be declared by name in C"))
+			},
+			DataType::Null => write!(self.writer, "void*"),
+			DataType::Generic { .. } => unreachable!("Generic structs are specialized by the semantic analysis"),
 		}
 	}
 
 	fn basic_data_type(&mut self, basic_data_type: ast::statement::BasicDataType) -> io::Result<()> {
 		match basic_data_type {
-			BasicDataType::Int => write!(self.writer, "int"),
-			BasicDataType::Float => write!(self.writer, "float"),
+			// Like in the other backends, ints and floats have 64 bits
+			BasicDataType::Int => write!(self.writer, "int64_t"),
+			BasicDataType::Float => write!(self.writer, "double"),
+			BasicDataType::Bool => write!(self.writer, "bool"),
+			BasicDataType::Str => write!(self.writer, "char*"),
+			// A unicode code point does not fit into a C `char`
+			BasicDataType::Char => write!(self.writer, "char32_t"),
 		}
 	}
 
-	fn struct_name(&mut self, struct_name: String) -> io::Result<()> {
-		write!(self.writer, "{}", struct_name)
+	fn struct_name(&mut self, struct_name: Symbol) -> io::Result<()> {
+		write!(self.writer, "{}", c_name(&struct_name))
 	}
 
 	fn pointer(&mut self, pointer: PositionContainer<ast::statement::DataType>) -> io::Result<()> {
-		write!(self.writer, "*")?;
-		self.data_type(pointer)
+		self.data_type(pointer)?;
+		write!(self.writer, "*")
 	}
 
 	fn number(&mut self, number: ast::expression::Number) -> io::Result<()> {
 		match *number {
 			ast::expression::NumberKind::Int(int) => write!(self.writer, "{}", int)?,
-			ast::expression::NumberKind::Float(float) => write!(self.writer, "{}", float)?,
+			// Unlike the display representation, the debug one always contains a dot or an exponent, so that e.g. `1.0 /
+			// 2.0` is not an integer division
+			ast::expression::NumberKind::Float(float) => write!(self.writer, "{:?}", float)?,
 		}
 		Ok(())
 	}
 
+	fn bool(&mut self, boolean: PositionContainer<bool>) -> io::Result<()> {
+		// `true` and `false` are `int`s in C
+		write!(self.writer, "((bool){})", *boolean)
+	}
+
+	/// Emits printable ASCII characters as C character literal and all others as their code point, both of type
+	/// `char32_t`.
+	fn char(&mut self, char: PositionContainer<char>) -> io::Result<()> {
+		match *char {
+			'\'' | '\\' => write!(self.writer, "U'\\{}'", *char),
+			' '..='~' => write!(self.writer, "U'{}'", *char),
+			char => write!(self.writer, "((char32_t){})", char as u32),
+		}
+	}
+
+	fn string_literal(&mut self, string: PositionContainer<String>) -> io::Result<()> {
+		write!(self.writer, "\"")?;
+		for char in string.chars() {
+			match char {
+				'"' => write!(self.writer, "\\\"")?,
+				'\\' => write!(self.writer, "\\\\")?,
+				'\n' => write!(self.writer, "\\n")?,
+				'\r' => write!(self.writer, "\\r")?,
+				'\t' => write!(self.writer, "\\t")?,
+				'\0' => write!(self.writer, "\\000")?,
+				char => write!(self.writer, "{}", char)?,
+			}
+		}
+		write!(self.writer, "\"")
+	}
+
 	fn variable(&mut self, variable: ast::expression::Variable) -> io::Result<()> {
+		// Local variables cannot shadow constants, so the name always refers to the constant
+		if let Some(constant) = self.constants.get(&variable.value).cloned() {
+			write!(self.writer, "(")?;
+			self.expression(constant)?;
+			return write!(self.writer, ")");
+		}
 		write!(self.writer, "{}", *variable)?;
 		Ok(())
 	}
 }
+
+/// Converts the qualified name of a module member like `math.square` into a valid C identifier like `math__square`.
+fn c_name(name: &str) -> String {
+	name.replace('.', "__")
+}
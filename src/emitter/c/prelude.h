@@ -0,0 +1,92 @@
+#include <inttypes.h>
+#include <math.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <uchar.h>
+
+// Builtin functions of FTL
+
+static void ftl_print_int(int64_t value) {
+	printf("%" PRId64, value);
+}
+
+static void ftl_print_float(double value) {
+	printf("%g", value);
+}
+
+static void ftl_print_bool(bool value) {
+	fputs(value ? "true" : "false", stdout);
+}
+
+static void ftl_print_str(char* value) {
+	fputs(value, stdout);
+}
+
+// Writes the character encoded as UTF-8
+static void ftl_print_char(char32_t value) {
+	if (value < 0x80) {
+		putchar(value);
+	} else if (value < 0x800) {
+		putchar(0xC0 | value >> 6);
+		putchar(0x80 | (value & 0x3F));
+	} else if (value < 0x10000) {
+		putchar(0xE0 | value >> 12);
+		putchar(0x80 | (value >> 6 & 0x3F));
+		putchar(0x80 | (value & 0x3F));
+	} else {
+		putchar(0xF0 | value >> 18);
+		putchar(0x80 | (value >> 12 & 0x3F));
+		putchar(0x80 | (value >> 6 & 0x3F));
+		putchar(0x80 | (value & 0x3F));
+	}
+}
+
+// Integer literals are `int` or `long` in C, so all integer types are printed as `int64_t`
+#define ftl_print(value) \
+	_Generic((value), \
+		double: ftl_print_float, \
+		bool: ftl_print_bool, \
+		char32_t: ftl_print_char, \
+		char*: ftl_print_str, \
+		default: ftl_print_int)(value)
+
+#define ftl_println(value) (ftl_print(value), putchar('\n'))
+
+static int64_t ftl_read_int(void) {
+	char line[64];
+	int64_t value = 0;
+	if (fgets(line, sizeof(line), stdin) != NULL) {
+		sscanf(line, "%" SCNd64, &value);
+	}
+	return value;
+}
+
+// `**` on integers by exponentiation by squaring, which wraps around on overflow like the other backends. A negative
+// exponent yields the truncated quotient `1 / base ** -exponent`.
+static int64_t ftl_int_power(int64_t base, int64_t exponent) {
+	if (exponent < 0) {
+		return base == 1 ? 1 : base == -1 ? (exponent % 2 == 0 ? 1 : -1) : 0;
+	}
+	uint64_t power = 1;
+	uint64_t factor = base;
+	for (uint64_t rest = exponent; rest > 0; rest >>= 1) {
+		if (rest & 1) {
+			power *= factor;
+		}
+		factor *= factor;
+	}
+	return (int64_t)power;
+}
+
+#define ftl_power(base, exponent) \
+	_Generic((base), \
+		double: pow, \
+		default: ftl_int_power)(base, exponent)
+
+#define ftl_math__sqrt sqrt
+#define ftl_math__abs fabs
+#define ftl_math__pow pow
+#define ftl_math__min fmin
+#define ftl_math__max fmax
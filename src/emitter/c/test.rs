@@ -0,0 +1,180 @@
+use std::{fs, path::PathBuf, process};
+
+use crate::{
+	builtin,
+	emitter::Emitter as _,
+	error::DiagnosticSink,
+	interpreter::{Interpreter, Value},
+	parser,
+};
+
+/// Tests that the compiled C code computes the same results as the interpreter, also for values which only fit into 64
+/// bit ints and floats. Skipped if there is no C compiler.
+#[test]
+fn test_same_results_as_interpreter() {
+	if process::Command::new("cc").arg("--version").output().is_err() {
+		eprintln!("Skipping test, because there is no C compiler `cc`");
+		return;
+	}
+	let programs = [
+		("int64", "def main(): int {\n\tvar big: int = 4294967296\n\treturn big / 1073741824 + 1\n}"),
+		("int_power", "def main(): int {\n\treturn 2 ** 40 / 2 ** 38\n}"),
+		// 2^24 + 1 is the first integer which a 32 bit float cannot represent
+		("inferred_types", "def main(): int {\n\tvar a = [[1, 2], [3, 4]]\n\tvar x = a[1][0]\n\treturn x\n}"),
+		("float64", "def main(): int {\n\tvar x: float = 16777217.0\n\treturn (x - 16777216.0) as int\n}"),
+		// Unless the value of a match is a variable, it is stored in a variable of its type
+		(
+			"match_value",
+			concat!(
+				"def main(): int {\n\tvar a = [4, 5]\n\tmatch a[1] {\n",
+				"\t\t5 => {\n\t\t\treturn 1\n\t\t}\n\t\t_ => {\n\t\t\treturn 2\n\t\t}\n\t}\n}",
+			),
+		),
+	];
+	for (name, source_code) in programs {
+		let mut ast_nodes = parser::parse_str(source_code).unwrap();
+		crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+		let Some(Value::Int(expected)) = Interpreter::new(ast_nodes.clone().into_iter()).run().unwrap() else {
+			panic!("{name}: main() returned no int");
+		};
+		assert_eq!(run(name, ast_nodes), expected, "{name}");
+	}
+}
+
+/// Tests that values are assigned and read through pointers from `malloc()`, also to fields of structs and in methods
+/// with a pointer as receiver. Skipped if there is no C compiler.
+#[test]
+fn test_pointer() {
+	if process::Command::new("cc").arg("--version").output().is_err() {
+		eprintln!("Skipping test, because there is no C compiler `cc`");
+		return;
+	}
+	let source_code = "
+		extern malloc(size: int): ptr int
+		extern calloc(count: int, size: int): ptr Point
+		struct Point {
+			x: int
+			y: int
+		}
+		def Point.move(self: ptr Point, dx: int) {
+			self.x += dx
+		}
+		def Point.sum(self: ptr Point): int {
+			return self.x + self.y
+		}
+		def main(): int {
+			var n = malloc(8)
+			*n = 40
+			*n += 1
+			var p = calloc(1, 16)
+			p.y = *n
+			p.move(1)
+			return p.sum()
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	assert_eq!(run("pointer", ast_nodes), 42);
+}
+
+/// Tests that a compound assignment evaluates the index of its target only once, so the side effect of a call in it
+/// happens once. Skipped if there is no C compiler.
+#[test]
+fn test_compound_assignment() {
+	if process::Command::new("cc").arg("--version").output().is_err() {
+		eprintln!("Skipping test, because there is no C compiler `cc`");
+		return;
+	}
+	let source_code = "
+		def f(): int {
+			print(\"f\")
+			return 1
+		}
+		def main(): int {
+			var a = [1, 2, 3]
+			a[f()] *= 2 + 3
+			return a[1]
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	let output = process::Command::new(compile("compound_assignment", ast_nodes)).output().unwrap();
+	assert_eq!(String::from_utf8_lossy(&output.stdout), "f");
+	assert_eq!(output.status.code(), Some(10));
+}
+
+/// Tests that floats are printed like by the interpreters. Skipped if there is no C compiler.
+#[test]
+fn test_print_float() {
+	if process::Command::new("cc").arg("--version").output().is_err() {
+		eprintln!("Skipping test, because there is no C compiler `cc`");
+		return;
+	}
+	let (source_code, expected) = print_floats();
+	let mut ast_nodes = parser::parse_str(&source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	let output = process::Command::new(compile("print_float", ast_nodes)).output().unwrap();
+	assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+/// Tests that the `#line` directives map a line of the C code back to the line of its FTL statement.
+#[test]
+fn test_source_map() {
+	let source_code = "
+		def main(): int {
+			var x = 6
+			return x * 7
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	let mut c_code = Vec::new();
+	super::Emitter::codegen(ast_nodes.into_iter(), &mut c_code).unwrap();
+	let c_code = String::from_utf8(c_code).unwrap();
+
+	let lines: Vec<_> = c_code.lines().collect();
+	let multiplication = lines.iter().position(|line| line.contains("x * 7")).unwrap();
+	// The line after a directive has the line of the directive, and the following lines count up from it
+	let (directive, line) = lines[..multiplication]
+		.iter()
+		.enumerate()
+		.rev()
+		.find_map(|(index, line)| Some((index, line.strip_prefix("#line ")?)))
+		.unwrap();
+	let (line, name) = line.split_once(' ').unwrap();
+	assert_eq!(name, "\"<string>\"");
+	assert_eq!(line.parse::<usize>().unwrap() + (multiplication - directive - 1), 4);
+}
+
+/// Boilerplate code for compiling the AST nodes with the C compiler and running the executable. Returns the exit code.
+fn run(name: &str, ast_nodes: Vec<crate::ast::Node>) -> i64 {
+	process::Command::new(compile(name, ast_nodes)).status().unwrap().code().unwrap().into()
+}
+
+/// Boilerplate code for compiling the AST nodes with the C compiler. Returns the path of the executable.
+fn compile(name: &str, ast_nodes: Vec<crate::ast::Node>) -> PathBuf {
+	let directory = std::env::temp_dir().join(format!("fortytwolang_c_{}", name));
+	fs::create_dir_all(&directory).unwrap();
+	let mut c_code = Vec::new();
+	super::Emitter::codegen(ast_nodes.into_iter(), &mut c_code).unwrap();
+	fs::write(directory.join("main.c"), c_code).unwrap();
+	let executable = directory.join("main");
+	let output = process::Command::new("cc")
+		.arg(directory.join("main.c"))
+		.arg("-o")
+		.arg(&executable)
+		.arg("-lm")
+		.output()
+		.unwrap();
+	assert!(output.status.success(), "{name}: {}", String::from_utf8_lossy(&output.stderr));
+	executable
+}
+
+/// A program printing floats of different magnitudes, and what the interpreters print for it.
+fn print_floats() -> (String, String) {
+	let floats = [2.5, 1.0, -0.0, 0.1, 1.0 / 3.0, 100000.0, 999999.5, 1e6, 123456789.0, 0.0001, 0.00001234, 1e100];
+	let prints: Vec<_> = floats.iter().map(|float| format!("\tprintln({:?})\n", float)).collect();
+	let source_code = format!("def main() {{\n{}\tprintln((1e308 * 10.0))\n}}", prints.concat());
+	let expected = floats.iter().chain(&[f64::INFINITY]).map(|float| format!("{}\n", builtin::format_float(*float)));
+	(source_code, expected.collect())
+}
@@ -0,0 +1,367 @@
+//! GraphViz DOT emitter, visualizing the AST as graph, e.g. with `mylang ast --format=dot file.ftl | dot -Tpng`.
+//!
+//! Every function, instruction and expression is a node of the graph, which is connected to its children by edges.
+//! Edges to children with a specific role, like the condition of a loop, are labelled with it. All top-level nodes
+//! are children of a single `Program` node.
+
+use std::{fmt, io};
+
+use crate::{
+	ast::{self, expression::NumberKind, statement::DataType, Expression, Instruction, Statement},
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// Emits a DOT graph of the AST.
+pub struct Emitter<W> {
+	writer: W,
+	/// Counter for generating unique node ids.
+	next_id: usize,
+}
+
+/// Id of a node in the graph.
+#[derive(Debug, Clone, Copy)]
+struct Id(usize);
+
+impl fmt::Display for Id {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "n{}", self.0)
+	}
+}
+
+impl<W: io::Write> super::Emitter<W> for Emitter<W> {
+	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: W) -> io::Result<()> {
+		let mut this = Self { writer, next_id: 0 };
+		writeln!(this.writer, "digraph ast {{")?;
+		writeln!(this.writer, "\tnode [shape=box, fontname=\"monospace\"];")?;
+		let program = this.node("Program")?;
+		for ast_node in ast_nodes {
+			let child = this.ast_node(ast_node)?;
+			this.edge(program, child, None)?;
+		}
+		writeln!(this.writer, "}}")
+	}
+}
+
+impl<W: io::Write> Emitter<W> {
+	/// Writes a node with the `label` and returns its id.
+	fn node(&mut self, label: impl fmt::Display) -> io::Result<Id> {
+		let id = Id(self.next_id);
+		self.next_id += 1;
+		write!(self.writer, "\t{} [label=\"", id)?;
+		for char in label.to_string().chars() {
+			match char {
+				'"' => write!(self.writer, "\\\"")?,
+				'\\' => write!(self.writer, "\\\\")?,
+				'\n' => write!(self.writer, "\\n")?,
+				char => write!(self.writer, "{}", char)?,
+			}
+		}
+		writeln!(self.writer, "\"];")?;
+		Ok(id)
+	}
+
+	/// Writes an edge from the `parent` to the `child`, which is labelled with the role of the child, if any.
+	fn edge(&mut self, parent: Id, child: Id, role: Option<&str>) -> io::Result<()> {
+		match role {
+			Some(role) => writeln!(self.writer, "\t{} -> {} [label=\"{}\"];", parent, child, role),
+			None => writeln!(self.writer, "\t{} -> {};", parent, child),
+		}
+	}
+
+	/// Emits the instructions of a block as children of the `parent`, whose edges are labelled with the `role`.
+	fn block(&mut self, parent: Id, block: ast::Block, role: Option<&str>) -> io::Result<()> {
+		for instruction in block {
+			let child = self.instruction(instruction)?;
+			self.edge(parent, child, role)?;
+		}
+		Ok(())
+	}
+
+	/// Emits the `expression` as child of the `parent`.
+	fn child(&mut self, parent: Id, expression: Expression, role: Option<&str>) -> io::Result<()> {
+		let child = self.expression(expression)?;
+		self.edge(parent, child, role)
+	}
+}
+
+/// Each of the functions in this impl block emits the corresponding AST node with its children and returns its id.
+impl<W: io::Write> Emitter<W> {
+	fn ast_node(&mut self, node: ast::Node) -> io::Result<Id> {
+		match node {
+			ast::Node::Function(function) => {
+				let id = self.prototype("Function", function.prototype)?;
+				self.block(id, function.body, None)?;
+				Ok(id)
+			},
+			ast::Node::FunctionPrototype(prototype) => self.prototype("Extern", prototype),
+			ast::Node::Struct(struct_) => {
+				let id = self.node(format_args!("Struct {}", *struct_.name))?;
+				for field in struct_.fields {
+					let child = self.node(format_args!("Field {}: {}", *field.name, field.data_type.value))?;
+					self.edge(id, child, None)?;
+				}
+				Ok(id)
+			},
+			ast::Node::Enum(enum_) => {
+				let id = self.node(format_args!("Enum {}", *enum_.name))?;
+				for variant in enum_.variants {
+					let child = self.node(format_args!("Variant {}", *variant.name))?;
+					self.edge(id, child, None)?;
+				}
+				Ok(id)
+			},
+			ast::Node::TypeAlias(type_alias) => {
+				self.node(format_args!("TypeAlias {} = {}", *type_alias.name, type_alias.data_type.value))
+			},
+			ast::Node::Const(const_declaration) => {
+				self.declaration("Const", const_declaration.name, const_declaration.data_type, const_declaration.value)
+			},
+			ast::Node::Import(import) => self.node(format_args!("Import {}", *import)),
+			ast::Node::Comment(comment) => self.comment(comment),
+		}
+	}
+
+	fn prototype(&mut self, kind: &str, prototype: ast::FunctionPrototype) -> io::Result<Id> {
+		let mut label = format!("{} {}", kind, *prototype.name);
+		if let Some(return_type) = &prototype.return_type {
+			label = format!("{}: {}", label, return_type.value);
+		}
+		let id = self.node(label)?;
+		for arg in prototype.args {
+			let child = self.node(format_args!("Argument {}: {}", *arg.name, arg.data_type.value))?;
+			self.edge(id, child, Some("argument"))?;
+		}
+		Ok(id)
+	}
+
+	fn declaration(
+		&mut self,
+		kind: &str,
+		name: PositionContainer<Symbol>,
+		data_type: Option<PositionContainer<DataType>>,
+		value: Expression,
+	) -> io::Result<Id> {
+		let id = match data_type {
+			Some(data_type) => self.node(format_args!("{} {}: {}", kind, *name, data_type.value))?,
+			None => self.node(format_args!("{} {}", kind, *name))?,
+		};
+		self.child(id, value, None)?;
+		Ok(id)
+	}
+
+	fn comment(&mut self, comment: PositionContainer<String>) -> io::Result<Id> {
+		// The lines of the comment become lines of the label
+		self.node(format_args!("Comment \"{}\"", *comment))
+	}
+
+	fn instruction(&mut self, instruction: Instruction) -> io::Result<Id> {
+		match instruction {
+			Instruction::Expression(expression) => self.expression(expression),
+			Instruction::Statement(statement) => self.statement(statement),
+			Instruction::IfElse(if_else) => {
+				let id = self.node("If")?;
+				self.child(id, if_else.condition, Some("condition"))?;
+				self.block(id, if_else.if_true, Some("then"))?;
+				self.block(id, if_else.if_false, Some("else"))?;
+				Ok(id)
+			},
+			Instruction::WhileLoop(while_loop) => {
+				let id = self.node("While")?;
+				self.child(id, while_loop.condition, Some("condition"))?;
+				self.block(id, while_loop.body, Some("body"))?;
+				Ok(id)
+			},
+			Instruction::ForLoop(for_loop) => {
+				let id = self.node("For")?;
+				let initialization = self.statement(for_loop.initialization)?;
+				self.edge(id, initialization, Some("initialization"))?;
+				self.child(id, for_loop.condition, Some("condition"))?;
+				let step = self.assignment(for_loop.step)?;
+				self.edge(id, step, Some("step"))?;
+				self.block(id, for_loop.body, Some("body"))?;
+				Ok(id)
+			},
+			Instruction::Match(match_) => {
+				let id = self.node("Match")?;
+				self.child(id, match_.value, Some("value"))?;
+				for arm in match_.arms {
+					let arm_id = self.node("Arm")?;
+					self.edge(id, arm_id, None)?;
+					for pattern in arm.patterns {
+						self.child(arm_id, pattern, Some("pattern"))?;
+					}
+					self.block(arm_id, arm.body, Some("body"))?;
+				}
+				if let Some(default) = match_.default {
+					self.block(id, default.body, Some("default"))?;
+				}
+				Ok(id)
+			},
+			Instruction::Comment(comment) => self.comment(comment),
+		}
+	}
+
+	fn statement(&mut self, statement: Statement) -> io::Result<Id> {
+		match statement {
+			Statement::VariableDeclaration(variable_declaration) => self.declaration(
+				"Var",
+				variable_declaration.name,
+				variable_declaration.data_type,
+				variable_declaration.value,
+			),
+			Statement::ConstDeclaration(const_declaration) => {
+				self.declaration("Const", const_declaration.name, const_declaration.data_type, const_declaration.value)
+			},
+			Statement::Assignment(assignment) => self.assignment(assignment),
+			Statement::Return(expression, _) => {
+				let id = self.node("Return")?;
+				if let Some(expression) = expression {
+					self.child(id, expression, None)?;
+				}
+				Ok(id)
+			},
+			Statement::Break(_) => self.node("Break"),
+			Statement::Continue(_) => self.node("Continue"),
+		}
+	}
+
+	fn assignment(&mut self, assignment: ast::statement::Assignment) -> io::Result<Id> {
+		let id = match assignment.operator {
+			Some(operator) => self.node(format_args!("Assignment {:?}", *operator))?,
+			None => self.node("Assignment")?,
+		};
+		self.child(id, assignment.target.into(), Some("target"))?;
+		self.child(id, assignment.value, Some("value"))?;
+		Ok(id)
+	}
+
+	fn expression(&mut self, expression: Expression) -> io::Result<Id> {
+		match expression {
+			Expression::BinaryExpression(binary_expression) => {
+				let id = self.node(format_args!("BinaryExpression {:?}", *binary_expression.operator))?;
+				self.child(id, *binary_expression.lhs, Some("lhs"))?;
+				self.child(id, *binary_expression.rhs, Some("rhs"))?;
+				Ok(id)
+			},
+			Expression::UnaryExpression(unary_expression) => {
+				let id = self.node(format_args!("UnaryExpression {:?}", *unary_expression.operator))?;
+				self.child(id, *unary_expression.operand, None)?;
+				Ok(id)
+			},
+			Expression::Cast(cast) => {
+				let id = self.node(format_args!("Cast {}", cast.data_type.value))?;
+				self.child(id, *cast.expression, None)?;
+				Ok(id)
+			},
+			Expression::FunctionCall(function_call) => {
+				let id = self.node(format_args!("FunctionCall {}", *function_call.name))?;
+				if let Some(receiver) = function_call.receiver {
+					self.child(id, *receiver, Some("receiver"))?;
+				}
+				for param in function_call.params {
+					self.child(id, param, Some("param"))?;
+				}
+				Ok(id)
+			},
+			Expression::FieldAccess(field_access) => {
+				let id = self.node(format_args!("FieldAccess {}", *field_access.field))?;
+				self.child(id, *field_access.base, None)?;
+				Ok(id)
+			},
+			Expression::StructLiteral(struct_literal) => {
+				let id = self.node(format_args!("StructLiteral {}", *struct_literal.name))?;
+				for field in struct_literal.fields {
+					self.child(id, field.value, Some(&field.name))?;
+				}
+				Ok(id)
+			},
+			Expression::ArrayLiteral(array_literal) => {
+				let id = self.node("ArrayLiteral")?;
+				for element in array_literal.elements {
+					self.child(id, element, None)?;
+				}
+				Ok(id)
+			},
+			Expression::IndexExpression(index_expression) => {
+				let id = self.node("IndexExpression")?;
+				self.child(id, *index_expression.base, Some("base"))?;
+				self.child(id, *index_expression.index, Some("index"))?;
+				Ok(id)
+			},
+			Expression::If(if_expression) => {
+				let id = self.node("IfExpression")?;
+				self.child(id, *if_expression.condition, Some("condition"))?;
+				self.child(id, *if_expression.if_true, Some("then"))?;
+				self.child(id, *if_expression.if_false, Some("else"))?;
+				Ok(id)
+			},
+			Expression::Number(number) => match *number {
+				NumberKind::Int(int) => self.node(format_args!("Number {}", int)),
+				NumberKind::Float(float) => self.node(format_args!("Number {:?}", float)),
+			},
+			Expression::Bool(boolean) => self.node(format_args!("Bool {}", *boolean)),
+			Expression::Char(char) => self.node(format_args!("Char {:?}", *char)),
+			Expression::StringLiteral(string) => self.node(format_args!("StringLiteral {:?}", *string)),
+			Expression::Null(_) => self.node("Null"),
+			Expression::Variable(variable) => self.node(format_args!("Variable {}", *variable)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{emitter::Emitter as _, parser};
+
+	/// Tests that every node gets a unique id and its label, that the edges to children with a role are labelled with
+	/// it and that quotes in labels are escaped.
+	#[test]
+	fn test_graph() {
+		let source_code = "
+def main(): int {
+	var x = 1
+	while x < 3 {
+		x += 1
+	}
+	print(\"\\\"\")
+	return x
+}";
+		let ast_nodes = parser::parse_str(source_code).unwrap();
+		let mut dot = Vec::new();
+		super::Emitter::codegen(ast_nodes.into_iter(), &mut dot).unwrap();
+		let expected = r#"digraph ast {
+	node [shape=box, fontname="monospace"];
+	n0 [label="Program"];
+	n1 [label="Function main: int"];
+	n2 [label="Var x"];
+	n3 [label="Number 1"];
+	n2 -> n3;
+	n1 -> n2;
+	n4 [label="While"];
+	n5 [label="BinaryExpression Less"];
+	n6 [label="Variable x"];
+	n5 -> n6 [label="lhs"];
+	n7 [label="Number 3"];
+	n5 -> n7 [label="rhs"];
+	n4 -> n5 [label="condition"];
+	n8 [label="Assignment Add"];
+	n9 [label="Variable x"];
+	n8 -> n9 [label="target"];
+	n10 [label="Number 1"];
+	n8 -> n10 [label="value"];
+	n4 -> n8 [label="body"];
+	n1 -> n4;
+	n11 [label="FunctionCall print"];
+	n12 [label="StringLiteral \"\\\"\""];
+	n11 -> n12 [label="param"];
+	n1 -> n11;
+	n13 [label="Return"];
+	n14 [label="Variable x"];
+	n13 -> n14;
+	n1 -> n13;
+	n0 -> n1;
+}
+"#;
+		assert_eq!(String::from_utf8(dot).unwrap(), expected);
+	}
+}
@@ -0,0 +1,111 @@
+//! Style of the formatted code, which can be configured with a `.ftlfmt.toml` file.
+//!
+//! The file consists of `key = value` pairs, whose keys follow `rustfmt.toml`:
+//!
+//! ```toml
+//! max_width = 100
+//! hard_tabs = false
+//! tab_spaces = 2
+//! brace_style = "NextLine"
+//! ```
+
+use thiserror::Error;
+
+use super::Indent;
+
+/// Style of the code emitted by the FTL emitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+	/// Maximum width of a line.
+	pub max_width: usize,
+	/// What a single level of indentation consists of.
+	pub indent: Indent,
+	/// Where the opening braces of blocks are placed.
+	pub brace_style: BraceStyle,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self { max_width: 120, indent: Indent::default(), brace_style: BraceStyle::default() }
+	}
+}
+
+/// Where the opening brace of a block is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BraceStyle {
+	/// At the end of the line that starts the block, e.g. `while (true) {`.
+	#[default]
+	SameLine,
+	/// On its own line below the start of the block.
+	NextLine,
+}
+
+/// Errors in a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+	#[error("Line {line}: Expected `key = value`")]
+	Syntax { line: usize },
+	#[error("Line {line}: Unknown key `{key}`")]
+	UnknownKey { line: usize, key: String },
+	#[error("Line {line}: Invalid value `{value}` for `{key}`, expected {expected}")]
+	InvalidValue { line: usize, key: String, value: String, expected: &'static str },
+}
+
+impl Config {
+	/// Name of the file the configuration is read from.
+	pub const FILE_NAME: &'static str = ".ftlfmt.toml";
+
+	/// Parses the contents of a configuration file. Keys that are not set keep their default values.
+	///
+	/// Only the subset of TOML needed for the configuration is supported, i.e. no tables, arrays or escape sequences.
+	pub fn from_toml(toml: &str) -> Result<Self, Error> {
+		let mut config = Self::default();
+		let mut hard_tabs = true;
+		let mut tab_spaces = 4;
+		for (i, line) in toml.lines().enumerate() {
+			let line_number = i + 1;
+			let line = strip_comment(line).trim();
+			if line.is_empty() {
+				continue;
+			}
+			let Some((key, value)) = line.split_once('=') else {
+				return Err(Error::Syntax { line: line_number });
+			};
+			let (key, value) = (key.trim(), value.trim());
+			let invalid_value = |expected| Error::InvalidValue {
+				line: line_number,
+				key: key.to_owned(),
+				value: value.to_owned(),
+				expected,
+			};
+			match key {
+				"max_width" => config.max_width = value.parse().map_err(|_| invalid_value("an integer"))?,
+				"hard_tabs" => hard_tabs = value.parse().map_err(|_| invalid_value("`true` or `false`"))?,
+				"tab_spaces" => tab_spaces = value.parse().map_err(|_| invalid_value("an integer"))?,
+				"brace_style" => {
+					config.brace_style = match value {
+						"\"SameLine\"" => BraceStyle::SameLine,
+						"\"NextLine\"" => BraceStyle::NextLine,
+						_ => return Err(invalid_value("\"SameLine\" or \"NextLine\"")),
+					}
+				},
+				_ => return Err(Error::UnknownKey { line: line_number, key: key.to_owned() }),
+			}
+		}
+		config.indent = if hard_tabs { Indent::Tabs } else { Indent::Spaces(tab_spaces) };
+		Ok(config)
+	}
+}
+
+/// Removes a `#` comment from the end of the line, unless the `#` is part of a string.
+fn strip_comment(line: &str) -> &str {
+	let mut in_string = false;
+	for (i, char) in line.char_indices() {
+		match char {
+			'"' => in_string = !in_string,
+			'#' if !in_string => return &line[..i],
+			_ => (),
+		}
+	}
+	line
+}
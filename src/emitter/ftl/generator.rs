@@ -0,0 +1,393 @@
+//! Generates random, syntactically valid ASTs for testing that formatting them is lossless.
+//!
+//! The ASTs are not semantically valid, e.g. they call functions that don't exist, since only the parser has to
+//! accept the formatted code.
+
+use std::sync::Arc;
+
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryOperator, NumberKind, UnaryOperator},
+		statement::{BasicDataType, DataType, LValue},
+		Expression, Instruction, Node,
+	},
+	source::{PositionContainer, PositionRange, Source, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Names of variables, fields, functions and structs, none of which is a keyword.
+const NAMES: [&str; 8] = ["a", "b", "count", "point", "values", "x1", "snake_case", "Point"];
+
+/// Modules which qualify the names of called functions, struct literals and struct types.
+const MODULES: [&str; 2] = ["math", "geometry"];
+
+/// Comments, including multi-line line comments, block comments and an empty one.
+const COMMENTS: [&str; 5] = ["note", "# heading", "first line\n# second line", "block\ncomment /* nested */", ""];
+
+const STRINGS: [&str; 5] = ["", "text", "quote \" and \\ backslash", "tab\tand\nnewline", "nul\0"];
+
+const CHARS: [char; 7] = ['a', '\'', '\\', '"', '\n', '\t', '\0'];
+
+const BINARY_OPERATORS: [BinaryOperator; 18] = [
+	BinaryOperator::Less,
+	BinaryOperator::Greater,
+	BinaryOperator::LessEqual,
+	BinaryOperator::GreaterEqual,
+	BinaryOperator::Add,
+	BinaryOperator::Subtract,
+	BinaryOperator::Multiply,
+	BinaryOperator::Divide,
+	BinaryOperator::Power,
+	BinaryOperator::BitOr,
+	BinaryOperator::BitAnd,
+	BinaryOperator::BitXor,
+	BinaryOperator::ShiftLeft,
+	BinaryOperator::ShiftRight,
+	BinaryOperator::Equal,
+	BinaryOperator::NotEqual,
+	BinaryOperator::LogicalAnd,
+	BinaryOperator::LogicalOr,
+];
+
+/// Generator of random ASTs, which are determined by the seed.
+pub struct Generator {
+	/// State of the xorshift random number generator, which is never zero.
+	state: u64,
+}
+
+impl Generator {
+	pub fn new(seed: u64) -> Self {
+		// Spread the seed over all bits and avoid the state zero, in which the generator would get stuck
+		Self { state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1 }
+	}
+
+	/// Generates the top-level nodes of a program.
+	pub fn nodes(&mut self) -> Vec<Node> {
+		(0..self.below(6) + 1).map(|_| self.node()).collect()
+	}
+
+	fn node(&mut self) -> Node {
+		match self.below(10) {
+			0 => Node::FunctionPrototype(self.prototype()),
+			1 => Node::Struct(ast::Struct {
+				name: self.name(),
+				type_parameters: (0..self.below(3)).map(|_| self.name()).collect(),
+				fields: (0..self.below(3))
+					.map(|_| ast::struct_::Field {
+						name: self.name(),
+						data_type: self.data_type(),
+						comment: self.chance(30).then(|| self.comment()),
+					})
+					.collect(),
+				position: position(),
+			}),
+			2 => Node::Const(self.const_declaration()),
+			3 => Node::Import(contain(self.name().value.to_string())),
+			4 => Node::Comment(self.comment()),
+			5 => Node::Enum(ast::Enum {
+				name: self.name(),
+				variants: (0..self.below(3))
+					.map(|_| ast::enum_::Variant {
+						name: self.name(),
+						comment: self.chance(30).then(|| self.comment()),
+					})
+					.collect(),
+				position: position(),
+			}),
+			6 => {
+				Node::TypeAlias(ast::TypeAlias { name: self.name(), data_type: self.data_type(), position: position() })
+			},
+			_ => Node::Function(ast::FunctionDefinition {
+				prototype: ast::FunctionPrototype {
+					// A qualified name like `Point.length` is the name of a method
+					name: self.qualified_name(),
+					type_parameters: (0..self.below(3)).map(|_| self.name()).collect(),
+					..self.prototype()
+				},
+				body: self.block(3, false),
+				position: position(),
+			}),
+		}
+	}
+
+	fn prototype(&mut self) -> ast::FunctionPrototype {
+		ast::FunctionPrototype {
+			name: self.name(),
+			type_parameters: Vec::new(),
+			args: (0..self.below(3))
+				.map(|_| ast::FunctionArgument { name: self.name(), data_type: self.data_type() })
+				.collect(),
+			return_type: self.chance(50).then(|| self.data_type()),
+			position: position(),
+		}
+	}
+
+	/// Generates a block with instructions nested up to `depth` levels. `break` and `continue` are only allowed
+	/// `in_loop`.
+	fn block(&mut self, depth: usize, in_loop: bool) -> ast::Block {
+		(0..self.below(4)).map(|_| self.instruction(depth, in_loop)).collect()
+	}
+
+	fn instruction(&mut self, depth: usize, in_loop: bool) -> Instruction {
+		let kinds = if depth == 0 { 7 } else { 11 };
+		match self.below(kinds) {
+			// Only calls and assignable expressions are generated as instructions, because a parenthesized expression
+			// like `(a + b)` would be parsed as the parameters of a call of a preceding `var x = f`
+			0 => Instruction::Expression(match self.chance(50) {
+				true => Expression::FunctionCall(self.function_call(1)),
+				false => self.target().into(),
+			}),
+			1 => Instruction::Statement(ast::Statement::VariableDeclaration(Box::new(self.variable_declaration()))),
+			2 => Instruction::Statement(ast::Statement::ConstDeclaration(Box::new(self.const_declaration()))),
+			3 => Instruction::Statement(ast::Statement::Assignment(self.assignment())),
+			4 => {
+				Instruction::Statement(ast::Statement::Return(self.chance(50).then(|| self.expression(2)), position()))
+			},
+			5 if in_loop => match self.chance(50) {
+				true => Instruction::Statement(ast::Statement::Break(position())),
+				false => Instruction::Statement(ast::Statement::Continue(position())),
+			},
+			5 | 6 => Instruction::Comment(self.comment()),
+			7 => Instruction::IfElse(Box::new(self.if_else(depth, in_loop))),
+			8 => Instruction::WhileLoop(Box::new(ast::WhileLoop {
+				condition: self.expression(2),
+				body: self.block(depth - 1, true),
+				position: position(),
+			})),
+			9 => Instruction::Match(Box::new(self.match_(depth, in_loop))),
+			_ => Instruction::ForLoop(Box::new(ast::ForLoop {
+				initialization: match self.chance(50) {
+					true => ast::Statement::VariableDeclaration(Box::new(self.variable_declaration())),
+					false => ast::Statement::Assignment(self.assignment()),
+				},
+				condition: self.expression(2),
+				step: self.assignment(),
+				body: self.block(depth - 1, true),
+				position: position(),
+			})),
+		}
+	}
+
+	fn if_else(&mut self, depth: usize, in_loop: bool) -> ast::IfElse {
+		let if_false = match self.below(3) {
+			0 => Vec::new(),
+			// An `else if` chain
+			1 if depth > 1 => vec![Instruction::IfElse(Box::new(self.if_else(depth - 1, in_loop)))],
+			_ => self.block(depth - 1, in_loop),
+		};
+		ast::IfElse {
+			condition: self.expression(2),
+			if_true: self.block(depth - 1, in_loop),
+			if_false,
+			position: position(),
+		}
+	}
+
+	fn match_(&mut self, depth: usize, in_loop: bool) -> ast::Match {
+		let arms = (0..self.below(3)).map(|_| self.match_arm(depth, in_loop, false)).collect();
+		ast::Match {
+			value: self.expression(2),
+			value_type: None,
+			arms,
+			default: self.chance(50).then(|| self.match_arm(depth, in_loop, true)),
+			position: position(),
+		}
+	}
+
+	/// Generates an arm with up to three patterns, or none for the `default` arm.
+	fn match_arm(&mut self, depth: usize, in_loop: bool, default: bool) -> ast::MatchArm {
+		let patterns = if default { 0 } else { 1 + self.below(3) };
+		ast::MatchArm {
+			patterns: (0..patterns).map(|_| self.expression(2)).collect(),
+			body: self.block(depth - 1, in_loop),
+			comment: self.chance(20).then(|| self.comment()),
+		}
+	}
+
+	fn variable_declaration(&mut self) -> ast::statement::VariableDeclaration {
+		ast::statement::VariableDeclaration {
+			name: self.name(),
+			data_type: self.chance(50).then(|| self.data_type()),
+			value: self.expression(2),
+			position: position(),
+		}
+	}
+
+	fn const_declaration(&mut self) -> ast::statement::ConstDeclaration {
+		ast::statement::ConstDeclaration {
+			name: self.name(),
+			data_type: self.chance(50).then(|| self.data_type()),
+			value: self.expression(2),
+			position: position(),
+		}
+	}
+
+	fn assignment(&mut self) -> ast::statement::Assignment {
+		let operator = match self.below(8) {
+			0 => Some(BinaryOperator::Add),
+			1 => Some(BinaryOperator::Subtract),
+			2 => Some(BinaryOperator::Multiply),
+			3 => Some(BinaryOperator::Divide),
+			_ => None,
+		};
+		ast::statement::Assignment { target: self.target(), operator: operator.map(contain), value: self.expression(2) }
+	}
+
+	/// Generates an assignable expression, which may be dereferenced. Field accesses and indexing of a dereference
+	/// cannot be written, since `*p.x` dereferences `p.x`.
+	fn target(&mut self) -> LValue {
+		match self.below(4) {
+			0 => LValue::Dereference { position: position(), base: Box::new(self.target()) },
+			_ => self.lvalue(2),
+		}
+	}
+
+	/// Generates an assignable expression with up to `depth` field accesses and indexings.
+	fn lvalue(&mut self, depth: usize) -> LValue {
+		match self.below(3) {
+			_ if depth == 0 => LValue::Variable(self.name()),
+			0 => LValue::Variable(self.name()),
+			1 => LValue::FieldAccess { base: Box::new(self.lvalue(depth - 1)), field: self.name() },
+			_ => LValue::Index { base: Box::new(self.lvalue(depth - 1)), index: Box::new(self.expression(depth - 1)) },
+		}
+	}
+
+	/// Generates an expression, whose subexpressions are nested up to `depth` levels.
+	fn expression(&mut self, depth: usize) -> Expression {
+		let kinds = if depth == 0 { 7 } else { 16 };
+		match self.below(kinds) {
+			0 => Expression::Number(contain(NumberKind::Int(self.below(1000) as i64))),
+			1 => Expression::Number(contain(NumberKind::Float(self.below(100) as f64 / 4.0))),
+			2 => Expression::Bool(contain(self.chance(50))),
+			3 => Expression::Char(contain(CHARS[self.below(CHARS.len())])),
+			4 => Expression::StringLiteral(contain(STRINGS[self.below(STRINGS.len())].to_owned())),
+			5 => Expression::Null(position()),
+			6 => Expression::Variable(self.name()),
+			7 | 8 => Expression::BinaryExpression(ast::expression::BinaryExpression {
+				lhs: Box::new(self.expression(depth - 1)),
+				operator: contain(BINARY_OPERATORS[self.below(BINARY_OPERATORS.len())].clone()),
+				rhs: Box::new(self.expression(depth - 1)),
+			}),
+			9 => Expression::UnaryExpression(ast::expression::UnaryExpression {
+				operator: contain(match self.below(3) {
+					0 => UnaryOperator::Negate,
+					1 => UnaryOperator::Not,
+					_ => UnaryOperator::Dereference,
+				}),
+				operand: Box::new(self.expression(depth - 1)),
+			}),
+			10 => Expression::Cast(ast::expression::Cast {
+				expression: Box::new(self.expression(depth - 1)),
+				data_type: self.data_type(),
+			}),
+			11 => Expression::FunctionCall(self.function_call(depth)),
+			12 => Expression::FieldAccess(ast::expression::FieldAccess {
+				base: Box::new(self.expression(depth - 1)),
+				field: self.name(),
+			}),
+			13 => match self.chance(50) {
+				true => Expression::StructLiteral(ast::expression::StructLiteral {
+					name: self.qualified_name(),
+					fields: (0..self.below(3))
+						.map(|_| ast::expression::FieldValue { name: self.name(), value: self.expression(depth - 1) })
+						.collect(),
+					position: position(),
+				}),
+				false => Expression::ArrayLiteral(ast::expression::ArrayLiteral {
+					elements: (0..self.below(4)).map(|_| self.expression(depth - 1)).collect(),
+					position: position(),
+				}),
+			},
+			14 => Expression::IndexExpression(ast::expression::IndexExpression {
+				base: Box::new(self.expression(depth - 1)),
+				index: Box::new(self.expression(depth - 1)),
+			}),
+			_ => Expression::If(ast::expression::IfExpression {
+				condition: Box::new(self.expression(depth - 1)),
+				if_true: Box::new(self.expression(depth - 1)),
+				if_false: Box::new(self.expression(depth - 1)),
+				position: position(),
+			}),
+		}
+	}
+
+	fn function_call(&mut self, depth: usize) -> ast::expression::FunctionCall {
+		// A receiver at the start of an instruction would be parenthesized, see `Self::instruction`
+		let receiver = (depth > 1 && self.chance(20)).then(|| Box::new(self.expression(depth - 1)));
+		ast::expression::FunctionCall {
+			// Methods are called by their name without the struct
+			name: if receiver.is_some() { self.name() } else { self.qualified_name() },
+			receiver,
+			params: (0..self.below(3)).map(|_| self.expression(depth - 1)).collect(),
+			position: position(),
+		}
+	}
+
+	fn data_type(&mut self) -> PositionContainer<DataType> {
+		let data_type = self.data_type_value(2);
+		contain(data_type)
+	}
+
+	fn data_type_value(&mut self, depth: usize) -> DataType {
+		let kinds = if depth == 0 { 6 } else { 9 };
+		match self.below(kinds) {
+			0 => DataType::Basic(BasicDataType::Int),
+			1 => DataType::Basic(BasicDataType::Float),
+			2 => DataType::Basic(BasicDataType::Bool),
+			3 => DataType::Basic(BasicDataType::Str),
+			4 => DataType::Basic(BasicDataType::Char),
+			5 => DataType::Struct(self.qualified_name().value),
+			6 => {
+				let pointee = self.data_type_value(depth - 1);
+				DataType::Pointer(Box::new(contain(pointee)))
+			},
+			7 => DataType::Generic {
+				name: self.qualified_name().value,
+				type_arguments: (0..self.below(2) + 1).map(|_| self.data_type_value(depth - 1)).collect(),
+			},
+			_ => DataType::Array { element: Box::new(self.data_type_value(depth - 1)), length: self.below(4) },
+		}
+	}
+
+	fn comment(&mut self) -> PositionContainer<String> {
+		contain(COMMENTS[self.below(COMMENTS.len())].to_owned())
+	}
+
+	fn name(&mut self) -> PositionContainer<Symbol> {
+		contain(Symbol::intern(NAMES[self.below(NAMES.len())]))
+	}
+
+	/// Generates a name, which is qualified by a module sometimes.
+	fn qualified_name(&mut self) -> PositionContainer<Symbol> {
+		let name = self.name();
+		match self.chance(25) {
+			true => crate::module::qualify(contain(Symbol::intern(MODULES[self.below(MODULES.len())])), name),
+			false => name,
+		}
+	}
+
+	/// Returns true with a probability of `percent`.
+	fn chance(&mut self, percent: usize) -> bool {
+		self.below(100) < percent
+	}
+
+	/// Returns a random number less than `n`.
+	fn below(&mut self, n: usize) -> usize {
+		// xorshift64
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		(self.state % n as u64) as usize
+	}
+}
+
+/// Position of the generated nodes, which don't stem from source code.
+fn position() -> SourcePositionRange {
+	let source = Arc::new(Source::new("testfile".to_owned(), String::new()));
+	SourcePositionRange { source, position: PositionRange::default() }
+}
+
+fn contain<T>(value: T) -> PositionContainer<T> {
+	PositionContainer::new(value, position())
+}
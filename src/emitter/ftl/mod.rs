@@ -1,178 +1,798 @@
 //! FTL emitter used to format existing FTL code.
+//!
+//! Formatting is lossless: Parsing the emitted code results in the same AST, apart from the source positions, so
+//! formatting already formatted code changes nothing.
+//!
+//! Binary expressions and lists of parameters and arguments that don't fit into the configured maximum width are
+//! wrapped onto continuation lines, which are indented one level deeper than the line they continue.
 
-use std::io;
+use std::io::{self, Write};
 
 use crate::{
 	ast::{
 		self,
-		expression::BinaryOperator,
+		expression::{BinaryOperator, UnaryOperator},
 		statement::{BasicDataType, DataType},
 		Expression,
 	},
 	source::PositionContainer,
+	symbol::Symbol,
 };
 
+pub mod config;
+#[cfg(test)]
+mod generator;
+#[cfg(test)]
+mod test;
+
+pub use config::{BraceStyle, Config};
+
 /// Emits FTL code.
 ///
 /// This is mainly used to format existing FTL code.
-pub struct Emitter {
-	writer: Box<dyn io::Write>,
+pub struct Emitter<W> {
+	writer: ColumnWriter<W>,
+	/// Style of the emitted code.
+	config: Config,
+	/// Nesting depth of the current line, used for indentation.
+	depth: usize,
+	/// Whether struct literals can be emitted without parentheses, which is not the case directly before a block, e.g.
+	/// in the condition of a `for` loop, where the `{` of the block would be parsed as the start of the fields.
+	struct_literals: bool,
+	/// Whether the emitter only measures the width of code, which is never wrapped for that.
+	measuring: bool,
+}
+
+/// Width a tab counts as when measuring lines, like in rustfmt.
+const TAB_WIDTH: usize = 4;
+
+/// Writer keeping track of the width of the current line, based on which code is wrapped.
+struct ColumnWriter<W> {
+	/// The underlying writer, which is [`None`] while only measuring the width of code.
+	writer: Option<W>,
+	/// Width of the current line in characters, where a tab counts [`TAB_WIDTH`] times.
+	column: usize,
+}
+
+impl<W: io::Write> io::Write for ColumnWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let written = match &mut self.writer {
+			Some(writer) => writer.write(buf)?,
+			None => buf.len(),
+		};
+		for &byte in &buf[..written] {
+			match byte {
+				b'\n' => self.column = 0,
+				b'\t' => self.column += TAB_WIDTH,
+				// Continuation bytes of UTF-8 characters don't start a new character
+				byte if byte & 0xC0 != 0x80 => self.column += 1,
+				_ => (),
+			}
+		}
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.writer.as_mut().map_or(Ok(()), io::Write::flush)
+	}
+}
+
+/// The indentation of one nesting level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Indent {
+	/// One tab per level.
+	#[default]
+	Tabs,
+	/// The given number of spaces per level.
+	Spaces(usize),
+}
+
+impl<W: io::Write> super::Emitter<W> for Emitter<W> {
+	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: W) -> io::Result<()> {
+		Self::codegen_with_config(ast_nodes, writer, Config::default())
+	}
 }
 
-impl super::Emitter for Emitter {
-	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: Box<dyn io::Write>) -> io::Result<()> {
-		let mut this = Self { writer };
+impl<W: io::Write> Emitter<W> {
+	fn new(writer: W, config: Config) -> Self {
+		Self {
+			writer: ColumnWriter { writer: Some(writer), column: 0 },
+			config,
+			depth: 0,
+			struct_literals: true,
+			measuring: false,
+		}
+	}
+
+	/// Like [`super::Emitter::codegen`], but emits the code in the style of the `config` instead of the default one.
+	pub fn codegen_with_config(
+		ast_nodes: impl Iterator<Item = ast::Node>,
+		writer: W,
+		config: Config,
+	) -> io::Result<()> {
+		let mut this = Self::new(writer, config);
+		let mut after_comment = false;
 		for ast_node in ast_nodes {
-			this.ast_node(ast_node)?;
+			after_comment = match ast_node {
+				ast::Node::Comment(comment) => {
+					this.comment(comment, after_comment)?;
+					true
+				},
+				ast_node => {
+					this.ast_node(ast_node)?;
+					false
+				},
+			};
+		}
+		Ok(())
+	}
+
+	/// Emits the instructions of a block without surrounding braces, e.g. to format a snippet of code.
+	pub fn codegen_block(block: ast::Block, writer: W) -> io::Result<()> {
+		Self::new(writer, Config::default()).instructions(block)
+	}
+
+	/// Writes the indentation of the current nesting depth at the start of a line.
+	fn indent(&mut self) -> io::Result<()> {
+		for _ in 0..self.depth {
+			match self.config.indent {
+				Indent::Tabs => write!(self.writer, "\t")?,
+				Indent::Spaces(spaces) => write!(self.writer, "{:spaces$}", "")?,
+			}
+		}
+		Ok(())
+	}
+
+	/// Ends the line that starts a block with its opening brace, which is placed according to the brace style.
+	fn opening_brace(&mut self) -> io::Result<()> {
+		match self.config.brace_style {
+			BraceStyle::SameLine => writeln!(self.writer, " {{"),
+			BraceStyle::NextLine => {
+				writeln!(self.writer)?;
+				self.indent()?;
+				writeln!(self.writer, "{{")
+			},
+		}
+	}
+
+	/// Emits the `items` with `emit`, writing the `separator` between, but not after them.
+	fn write_separated<T>(
+		&mut self,
+		items: impl IntoIterator<Item = T>,
+		separator: &str,
+		mut emit: impl FnMut(&mut Self, T) -> io::Result<()>,
+	) -> io::Result<()> {
+		for (i, item) in items.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, "{}", separator)?;
+			}
+			emit(self, item)?;
 		}
 		Ok(())
 	}
+
+	/// Emits the `items` of a list that is too long for its line, each on a continuation line and separated by commas.
+	/// The closing delimiter written afterwards starts a new line at the current depth.
+	fn write_wrapped<T>(
+		&mut self,
+		items: impl IntoIterator<Item = T>,
+		mut emit: impl FnMut(&mut Self, T) -> io::Result<()>,
+	) -> io::Result<()> {
+		for (i, item) in items.into_iter().enumerate() {
+			if i != 0 {
+				write!(self.writer, ",")?;
+			}
+			self.continuation_line(|this| emit(this, item))?;
+		}
+		writeln!(self.writer)?;
+		self.indent()
+	}
+
+	/// Starts a continuation line one level deeper than the current one and runs `emit` at its depth.
+	fn continuation_line(&mut self, emit: impl FnOnce(&mut Self) -> io::Result<()>) -> io::Result<()> {
+		writeln!(self.writer)?;
+		self.depth += 1;
+		self.indent()?;
+		let result = emit(self);
+		self.depth -= 1;
+		result
+	}
+
+	/// Whether the code emitted by `emit` fits into the rest of the current line without exceeding the maximum width.
+	fn fits(&self, emit: impl FnOnce(&mut Self) -> io::Result<()>) -> io::Result<bool> {
+		if self.measuring {
+			return Ok(true);
+		}
+		let mut measurer = Self {
+			writer: ColumnWriter { writer: None, column: 0 },
+			config: self.config,
+			depth: self.depth,
+			struct_literals: self.struct_literals,
+			measuring: true,
+		};
+		emit(&mut measurer)?;
+		Ok(self.writer.column + measurer.writer.column <= self.config.max_width)
+	}
+
+	/// Emits the instructions of a block one level deeper than the surrounding code.
+	fn block(&mut self, block: ast::Block) -> io::Result<()> {
+		self.depth += 1;
+		self.instructions(block)?;
+		self.depth -= 1;
+		Ok(())
+	}
+
+	/// Emits the instructions of a block at the current nesting depth.
+	fn instructions(&mut self, block: ast::Block) -> io::Result<()> {
+		let mut after_comment = false;
+		for instruction in block {
+			after_comment = match instruction {
+				ast::Instruction::Comment(comment) => {
+					self.comment(comment, after_comment)?;
+					true
+				},
+				instruction => {
+					self.instruction(instruction)?;
+					false
+				},
+			};
+		}
+		Ok(())
+	}
+
+	/// Runs `emit` with struct literals being `allowed` without parentheses or not.
+	fn with_struct_literals(
+		&mut self,
+		allowed: bool,
+		emit: impl FnOnce(&mut Self) -> io::Result<()>,
+	) -> io::Result<()> {
+		let struct_literals = std::mem::replace(&mut self.struct_literals, allowed);
+		let result = emit(self);
+		self.struct_literals = struct_literals;
+		result
+	}
+
+	/// Emits the expression enclosed in parentheses, inside of which struct literals are unambiguous.
+	fn parenthesized(&mut self, expression: ast::Expression) -> io::Result<()> {
+		write!(self.writer, "(")?;
+		self.with_struct_literals(true, |this| this.expression(expression))?;
+		write!(self.writer, ")")
+	}
 }
 
 /// Each of the functions in this impl block is responsible for emitting the corresponding AST node.
-impl Emitter {
+impl<W: io::Write> Emitter<W> {
 	fn ast_node(&mut self, node: ast::Node) -> io::Result<()> {
 		match node {
 			ast::Node::Function(function) => self.function(function),
+			ast::Node::FunctionPrototype(prototype) => self.extern_function(prototype),
 			ast::Node::Struct(struct_) => self.struct_(struct_),
-			_ => todo!(),
+			ast::Node::Enum(enum_) => self.enum_(enum_),
+			ast::Node::TypeAlias(type_alias) => self.type_alias(type_alias),
+			ast::Node::Const(const_declaration) => self.const_declaration(const_declaration),
+			ast::Node::Import(import) => writeln!(self.writer, "import {}", *import),
+			ast::Node::Comment(comment) => self.comment(comment, false),
 		}
 	}
 
+	fn extern_function(&mut self, prototype: ast::FunctionPrototype) -> io::Result<()> {
+		self.prototype("extern", prototype)?;
+		writeln!(self.writer)
+	}
+
 	fn function(&mut self, function: ast::FunctionDefinition) -> io::Result<()> {
 		// Function header
-		write!(self.writer, "function {}(", *function.prototype.name)?;
-		for arg in function.prototype.args {
-			self.function_argument(arg)?;
-			write!(self.writer, ", ")?; // TODO: Remove trailing comma
-		}
-		writeln!(self.writer, ") {{")?;
+		self.prototype("def", function.prototype)?;
+		self.opening_brace()?;
 
 		// Function body
-		for instruction in function.body {
-			self.instruction(instruction)?;
-		}
+		self.block(function.body)?;
 		writeln!(self.writer)?;
+		self.indent()?;
 		writeln!(self.writer, "}}")?;
 		Ok(())
 	}
 
 	fn struct_(&mut self, struct_: ast::Struct) -> io::Result<()> {
-		writeln!(self.writer, "struct {} {{", *struct_.name)?;
-		for field in struct_.fields {
-			write!(self.writer, "{}: ", *field.name)?;
-			self.data_type(field.data_type)?;
-			writeln!(self.writer, ", ")?; // TODO: Remove trailing comma
+		write!(self.writer, "struct {}", *struct_.name)?;
+		if !struct_.type_parameters.is_empty() {
+			write!(self.writer, "<")?;
+			self.write_separated(struct_.type_parameters, ", ", |this, type_parameter| {
+				write!(this.writer, "{}", *type_parameter)
+			})?;
+			write!(self.writer, ">")?;
+		}
+		self.opening_brace()?;
+		self.depth += 1;
+		// Fields are only separated by line breaks
+		let has_fields = !struct_.fields.is_empty();
+		self.write_separated(struct_.fields, "\n", |this, field| {
+			if let Some(comment) = field.comment {
+				this.comment(comment, false)?;
+			}
+			this.indent()?;
+			write!(this.writer, "{}: ", *field.name)?;
+			this.data_type(field.data_type)
+		})?;
+		if has_fields {
+			writeln!(self.writer)?;
+		}
+		self.depth -= 1;
+		self.indent()?;
+		writeln!(self.writer, "}}")?;
+		Ok(())
+	}
+
+	fn enum_(&mut self, enum_: ast::Enum) -> io::Result<()> {
+		write!(self.writer, "enum {}", *enum_.name)?;
+		self.opening_brace()?;
+		self.depth += 1;
+		let has_variants = !enum_.variants.is_empty();
+		self.write_separated(enum_.variants, ",\n", |this, variant| {
+			if let Some(comment) = variant.comment {
+				this.comment(comment, false)?;
+			}
+			this.indent()?;
+			write!(this.writer, "{}", *variant.name)
+		})?;
+		if has_variants {
+			writeln!(self.writer)?;
 		}
+		self.depth -= 1;
+		self.indent()?;
 		writeln!(self.writer, "}}")?;
 		Ok(())
 	}
 
+	fn type_alias(&mut self, type_alias: ast::TypeAlias) -> io::Result<()> {
+		write!(self.writer, "type {} = ", *type_alias.name)?;
+		self.data_type(type_alias.data_type)?;
+		writeln!(self.writer)
+	}
+
+	/// Emits a comment as line comments. Following lines of a multi-line line comment already start with `#`.
+	///
+	/// Lines of a block comment don't, so it stays a block comment, because the lines of line comments would be lexed
+	/// differently. The same holds for a comment directly `after_comment`, since consecutive line comments are lexed as a
+	/// single comment.
+	/// Emits the prototype of a function, starting with the `keyword`, up to the return type.
+	fn prototype(&mut self, keyword: &str, prototype: ast::FunctionPrototype) -> io::Result<()> {
+		let wrap = !prototype.args.is_empty() && !self.fits(|this| this.prototype(keyword, prototype.clone()))?;
+		write!(self.writer, "{} {}", keyword, *prototype.name)?;
+		if !prototype.type_parameters.is_empty() {
+			write!(self.writer, "<")?;
+			self.write_separated(prototype.type_parameters, ", ", |this, type_parameter| {
+				write!(this.writer, "{}", *type_parameter)
+			})?;
+			write!(self.writer, ">")?;
+		}
+		write!(self.writer, "(")?;
+		match wrap {
+			true => self.write_wrapped(prototype.args, Self::function_argument)?,
+			false => self.write_separated(prototype.args, ", ", Self::function_argument)?,
+		}
+		write!(self.writer, ")")?;
+		if let Some(return_type) = prototype.return_type {
+			write!(self.writer, ": ")?;
+			self.data_type(return_type)?;
+		}
+		Ok(())
+	}
+
+	fn comment(&mut self, comment: PositionContainer<String>, after_comment: bool) -> io::Result<()> {
+		self.indent()?;
+		if after_comment || comment.lines().skip(1).any(|line| !line.starts_with('#')) {
+			return writeln!(self.writer, "/* {} */", *comment);
+		}
+		for (i, line) in comment.lines().enumerate() {
+			if i != 0 {
+				self.indent()?;
+				writeln!(self.writer, "{}", line)?;
+			} else {
+				writeln!(self.writer, "# {}", line)?;
+			}
+		}
+		// An empty comment has no lines
+		if comment.is_empty() {
+			writeln!(self.writer, "#")?;
+		}
+		Ok(())
+	}
+
 	fn instruction(&mut self, instruction: ast::Instruction) -> io::Result<()> {
+		// Comments indent each of their lines themselves
+		if !matches!(instruction, ast::Instruction::Comment(_)) {
+			self.indent()?;
+		}
 		match instruction {
-			ast::Instruction::Expression(expression) => self.expression(expression),
+			ast::Instruction::Expression(expression) => {
+				self.expression_instruction(expression)?;
+				writeln!(self.writer)
+			},
 			ast::Instruction::Statement(statement) => self.statement(statement),
 			ast::Instruction::IfElse(if_else) => self.if_else(*if_else),
 			ast::Instruction::WhileLoop(while_loop) => self.while_loop(*while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(*for_loop),
+			ast::Instruction::Match(match_) => self.match_(*match_),
+			ast::Instruction::Comment(comment) => self.comment(comment, false),
+		}
+	}
+
+	/// Emits an expression used as instruction. Only calls, numbers and expressions that can be assigned to, like
+	/// `point.x` or `*p`, can start an instruction, so other expressions need parentheses.
+	fn expression_instruction(&mut self, expression: ast::Expression) -> io::Result<()> {
+		match expression {
+			// Only receivers that can be assigned to start an instruction, like in `line.start.move(1)`
+			Expression::FunctionCall(function_call) if function_call.receiver.as_deref().is_none_or(is_assignable) => {
+				self.function_call(function_call)
+			},
+			expression @ Expression::Number(_) => self.expression(expression),
+			expression if is_assignable(&expression) => self.expression(expression),
+			Expression::UnaryExpression(unary_expression) if is_target(&unary_expression) => {
+				self.unary_expression(unary_expression)
+			},
+			expression => self.parenthesized(expression),
+		}
+	}
+
+	/// Emits an expression where a primary expression is expected, like the value of a variable declaration or a
+	/// parameter of a call, which is why a binary expression needs parentheses there.
+	fn primary(&mut self, expression: ast::Expression) -> io::Result<()> {
+		match expression {
+			expression @ Expression::BinaryExpression(_) => self.parenthesized(expression),
+			expression => self.expression(expression),
 		}
 	}
 
 	fn expression(&mut self, expression: ast::Expression) -> io::Result<()> {
 		match expression {
 			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::UnaryExpression(unary_expression) => self.unary_expression(unary_expression),
+			Expression::Cast(cast) => self.cast(cast),
 			Expression::FunctionCall(function_call) => self.function_call(function_call),
+			Expression::FieldAccess(field_access) => self.field_access(field_access),
+			Expression::StructLiteral(struct_literal) => self.struct_literal(struct_literal),
+			Expression::ArrayLiteral(array_literal) => self.array_literal(array_literal),
+			Expression::IndexExpression(index_expression) => self.index_expression(index_expression),
+			Expression::If(if_expression) => self.if_expression(if_expression),
 			Expression::Number(number) => self.number(number),
+			Expression::Bool(boolean) => self.bool(boolean),
+			Expression::Char(char) => self.char(char),
+			Expression::StringLiteral(string) => self.string_literal(string),
+			Expression::Null(_) => write!(self.writer, "null"),
 			Expression::Variable(variable) => self.variable(variable),
 		}
 	}
 
 	fn binary_expression(&mut self, binary_expression: ast::expression::BinaryExpression) -> io::Result<()> {
-		self.expression(*binary_expression.lhs)?;
-		let operator = match *binary_expression.operator {
-			ast::expression::BinaryOperator::Add => "+",
-			ast::expression::BinaryOperator::Subtract => "-",
-			ast::expression::BinaryOperator::Multiply => "*",
-			ast::expression::BinaryOperator::Divide => "/",
-			BinaryOperator::Less => "<",
-			BinaryOperator::Greater => ">",
-			BinaryOperator::Equal => "==",
-			BinaryOperator::NotEqual => "=/=",
+		// Operands binding weaker than the operator need parentheses. Operators of the same precedence are left
+		// associative, so this includes a rhs with an operator of the same precedence, or a lhs with the same
+		// operator if it is right-associative like `**`.
+		// A chain that is too long for the line is broken before its operators. The lhs decides on its own whether it
+		// has to be broken, too, which puts all operators of a chain on continuation lines of the same depth.
+		let wrap = !self.fits(|this| this.binary_expression(binary_expression.clone()))?;
+		let operator = binary_expression.operator.value;
+		match *binary_expression.lhs {
+			Expression::BinaryExpression(lhs)
+				if lhs.operator.value < operator
+					|| (lhs.operator.value == operator && operator.is_right_associative()) =>
+			{
+				self.parenthesized(Expression::BinaryExpression(lhs))?
+			},
+			lhs => self.expression(lhs)?,
+		}
+		let symbol = operator_symbol(&operator);
+		let rhs = |this: &mut Self| match *binary_expression.rhs {
+			Expression::BinaryExpression(rhs)
+				if rhs.operator.value <= operator
+					&& !(rhs.operator.value == operator && operator.is_right_associative()) =>
+			{
+				this.parenthesized(Expression::BinaryExpression(rhs))
+			},
+			rhs => this.expression(rhs),
 		};
-		write!(self.writer, " {} ", operator)?;
-		self.expression(*binary_expression.rhs)?;
-		Ok(())
+		match wrap {
+			true => self.continuation_line(|this| {
+				write!(this.writer, "{} ", symbol)?;
+				rhs(this)
+			}),
+			false => {
+				write!(self.writer, " {} ", symbol)?;
+				rhs(self)
+			},
+		}
+	}
+
+	fn unary_expression(&mut self, unary_expression: ast::expression::UnaryExpression) -> io::Result<()> {
+		let operator = match *unary_expression.operator {
+			UnaryOperator::Negate => "-",
+			UnaryOperator::Not => "!",
+			UnaryOperator::Dereference => "*",
+		};
+		write!(self.writer, "{}", operator)?;
+		// The operand is a primary expression without casts, so a binary expression or cast needs parentheses
+		match *unary_expression.operand {
+			operand @ (Expression::BinaryExpression(_) | Expression::Cast(_)) => self.parenthesized(operand),
+			operand => self.expression(operand),
+		}
+	}
+
+	fn cast(&mut self, cast: ast::expression::Cast) -> io::Result<()> {
+		// The cast binds stronger than binary operators
+		match *cast.expression {
+			expression @ Expression::BinaryExpression(_) => self.parenthesized(expression)?,
+			expression => self.expression(expression)?,
+		}
+		write!(self.writer, " as ")?;
+		self.data_type(cast.data_type)
+	}
+
+	fn field_access(&mut self, field_access: ast::expression::FieldAccess) -> io::Result<()> {
+		// The field access binds stronger than unary and binary operators and casts. The dot after a number would be
+		// lexed as part of the number.
+		match *field_access.base {
+			base @ (Expression::BinaryExpression(_)
+			| Expression::UnaryExpression(_)
+			| Expression::Cast(_)
+			| Expression::If(_)
+			| Expression::Number(_)) => self.parenthesized(base)?,
+			base => self.expression(base)?,
+		}
+		write!(self.writer, ".{}", *field_access.field)
+	}
+
+	fn struct_literal(&mut self, struct_literal: ast::expression::StructLiteral) -> io::Result<()> {
+		if !self.struct_literals {
+			return self.parenthesized(Expression::StructLiteral(struct_literal));
+		}
+		write!(self.writer, "{} {{", *struct_literal.name)?;
+		if struct_literal.fields.is_empty() {
+			return write!(self.writer, "}}");
+		}
+		write!(self.writer, " ")?;
+		self.write_separated(struct_literal.fields, ", ", |this, field| {
+			write!(this.writer, "{}: ", *field.name)?;
+			this.expression(field.value)
+		})?;
+		write!(self.writer, " }}")
+	}
+
+	fn array_literal(&mut self, array_literal: ast::expression::ArrayLiteral) -> io::Result<()> {
+		write!(self.writer, "[")?;
+		self.with_struct_literals(true, |this| this.write_separated(array_literal.elements, ", ", Self::expression))?;
+		write!(self.writer, "]")
+	}
+
+	fn index_expression(&mut self, index_expression: ast::expression::IndexExpression) -> io::Result<()> {
+		// Like the field access, the indexing binds stronger than unary and binary operators and casts
+		match *index_expression.base {
+			base @ (Expression::BinaryExpression(_)
+			| Expression::UnaryExpression(_)
+			| Expression::Cast(_)
+			| Expression::If(_)) => self.parenthesized(base)?,
+			base => self.expression(base)?,
+		}
+		write!(self.writer, "[")?;
+		self.with_struct_literals(true, |this| this.expression(*index_expression.index))?;
+		write!(self.writer, "]")
 	}
 
 	fn function_call(&mut self, function_call: ast::expression::FunctionCall) -> io::Result<()> {
-		write!(self.writer, "{}(", *function_call.name)?;
-		for param in function_call.params {
-			self.expression(param)?;
+		let wrap = !function_call.params.is_empty() && !self.fits(|this| this.function_call(function_call.clone()))?;
+		// Like the base of a field access. A variable as receiver needs parentheses, too, because `point.length()` is
+		// parsed as call of the function `length` of a module `point`.
+		match function_call.receiver.map(|receiver| *receiver) {
+			Some(
+				receiver @ (Expression::BinaryExpression(_)
+				| Expression::UnaryExpression(_)
+				| Expression::Cast(_)
+				| Expression::If(_)
+				| Expression::Number(_)
+				| Expression::Variable(_)),
+			) => {
+				self.parenthesized(receiver)?;
+				write!(self.writer, ".")?;
+			},
+			Some(receiver) => {
+				self.expression(receiver)?;
+				write!(self.writer, ".")?;
+			},
+			None => (),
 		}
-		writeln!(self.writer, ")")?;
-		Ok(())
+		write!(self.writer, "{}(", *function_call.name)?;
+		self.with_struct_literals(true, |this| match wrap {
+			true => this.write_wrapped(function_call.params, Self::primary),
+			false => this.write_separated(function_call.params, ", ", Self::primary),
+		})?;
+		write!(self.writer, ")")
 	}
 
 	fn statement(&mut self, statement: ast::Statement) -> io::Result<()> {
 		match statement {
 			ast::statement::Statement::VariableDeclaration(variable_declaration) => {
-				self.variable_declaration(variable_declaration)
+				self.variable_declaration(*variable_declaration)
+			},
+			ast::statement::Statement::ConstDeclaration(const_declaration) => {
+				self.const_declaration(*const_declaration)
 			},
-			ast::statement::Statement::VariableAssignment(assignment) => self.assignment(assignment),
-			ast::Statement::Return(expression) => self.return_(expression),
+			ast::statement::Statement::Assignment(assignment) => self.assignment(assignment),
+			ast::Statement::Return(expression, _) => self.return_(expression),
+			ast::Statement::Break(_) => writeln!(self.writer, "break"),
+			ast::Statement::Continue(_) => writeln!(self.writer, "continue"),
 		}
 	}
 
 	fn variable_declaration(&mut self, variable_declaration: ast::statement::VariableDeclaration) -> io::Result<()> {
-		write!(self.writer, "var {} = ", *variable_declaration.name)?;
-		self.expression(variable_declaration.value)?;
+		self.inline_variable_declaration(variable_declaration)?;
 		writeln!(self.writer)?;
 		Ok(())
 	}
 
-	fn assignment(&mut self, assignment: ast::statement::VariableAssignment) -> io::Result<()> {
-		write!(self.writer, "{} = ", *assignment.name)?;
-		self.expression(assignment.value)?;
+	/// Emits a variable declaration without the trailing newline, e.g. for the initialization of a for loop.
+	fn inline_variable_declaration(
+		&mut self,
+		variable_declaration: ast::statement::VariableDeclaration,
+	) -> io::Result<()> {
+		self.declaration("var", variable_declaration.name, variable_declaration.data_type)?;
+		// Unlike the value of a constant, the value of a variable is a primary expression
+		self.primary(variable_declaration.value)
+	}
+
+	fn const_declaration(&mut self, const_declaration: ast::statement::ConstDeclaration) -> io::Result<()> {
+		self.declaration("const", const_declaration.name, const_declaration.data_type)?;
+		self.expression(const_declaration.value)?;
 		writeln!(self.writer)?;
 		Ok(())
 	}
 
-	fn return_(&mut self, expression: ast::Expression) -> io::Result<()> {
-		write!(self.writer, "return ")?;
-		self.expression(expression)?;
+	/// Emits the start of a declaration like `var x: int = ` with the given keyword, up to the value.
+	fn declaration(
+		&mut self,
+		keyword: &str,
+		name: PositionContainer<Symbol>,
+		data_type: Option<PositionContainer<DataType>>,
+	) -> io::Result<()> {
+		write!(self.writer, "{} {}", keyword, *name)?;
+		if let Some(data_type) = data_type {
+			write!(self.writer, ": ")?;
+			self.data_type(data_type)?;
+		}
+		write!(self.writer, " = ")
+	}
+
+	fn assignment(&mut self, assignment: ast::statement::Assignment) -> io::Result<()> {
+		self.inline_assignment(assignment)?;
+		writeln!(self.writer)?;
+		Ok(())
+	}
+
+	/// Emits an assignment without the trailing newline, e.g. for the step of a for loop.
+	fn inline_assignment(&mut self, assignment: ast::statement::Assignment) -> io::Result<()> {
+		self.expression(assignment.target.into())?;
+		match assignment.operator {
+			Some(operator) => write!(self.writer, " {}= ", operator_symbol(&operator))?,
+			None => write!(self.writer, " = ")?,
+		}
+		self.expression(assignment.value)
+	}
+
+	fn return_(&mut self, expression: Option<ast::Expression>) -> io::Result<()> {
+		write!(self.writer, "return")?;
+		if let Some(expression) = expression {
+			write!(self.writer, " ")?;
+			self.expression(expression)?;
+		}
 		writeln!(self.writer)?;
 		Ok(())
 	}
 
 	fn if_else(&mut self, if_else: ast::IfElse) -> io::Result<()> {
 		// if block, always present
-		write!(self.writer, "if (")?;
-		self.expression(if_else.condition)?;
-		writeln!(self.writer, ") {{")?;
-		for instruction in if_else.if_true {
-			self.instruction(instruction)?;
-		}
+		write!(self.writer, "if ")?;
+		self.parenthesized(if_else.condition)?;
+		self.opening_brace()?;
+		self.block(if_else.if_true)?;
+		self.indent()?;
 		writeln!(self.writer, "}}")?;
 
-		// else block, optional
-		if if_else.if_false.is_empty() {
-			return Ok(());
+		// else block, optional. An else block only containing an if-else is printed as `else if` chain.
+		let mut if_false = if_else.if_false;
+		if let [ast::Instruction::IfElse(_)] = if_false.as_slice() {
+			if let Some(ast::Instruction::IfElse(if_else)) = if_false.pop() {
+				self.indent()?;
+				write!(self.writer, "else ")?;
+				return self.if_else(*if_else);
+			}
 		}
-		writeln!(self.writer, "else {{")?;
-		for instruction in if_else.if_false {
-			self.instruction(instruction)?;
+		if if_false.is_empty() {
+			return Ok(());
 		}
+		self.indent()?;
+		write!(self.writer, "else")?;
+		self.opening_brace()?;
+		self.block(if_false)?;
+		self.indent()?;
 		writeln!(self.writer, "}}")?;
 
 		Ok(())
 	}
 
+	fn if_expression(&mut self, if_expression: ast::expression::IfExpression) -> io::Result<()> {
+		// Like an if-else, but on a single line. An if-expression in the else branch is printed as `else if` chain.
+		write!(self.writer, "if ")?;
+		self.parenthesized(*if_expression.condition)?;
+		write!(self.writer, " {{ ")?;
+		self.with_struct_literals(true, |this| this.expression(*if_expression.if_true))?;
+		write!(self.writer, " }} else ")?;
+		match *if_expression.if_false {
+			Expression::If(if_false) => self.if_expression(if_false),
+			if_false => {
+				write!(self.writer, "{{ ")?;
+				self.with_struct_literals(true, |this| this.expression(if_false))?;
+				write!(self.writer, " }}")
+			},
+		}
+	}
+
+	fn match_(&mut self, match_: ast::Match) -> io::Result<()> {
+		write!(self.writer, "match ")?;
+		self.parenthesized(match_.value)?;
+		self.opening_brace()?;
+		self.depth += 1;
+		for arm in match_.arms {
+			self.match_arm(arm, false)?;
+		}
+		if let Some(default) = match_.default {
+			self.match_arm(default, true)?;
+		}
+		self.depth -= 1;
+		self.indent()?;
+		writeln!(self.writer, "}}")
+	}
+
+	/// Emits an arm of a match with its comment. The patterns of the `default` arm are replaced by `_`.
+	fn match_arm(&mut self, arm: ast::MatchArm, default: bool) -> io::Result<()> {
+		if let Some(comment) = arm.comment {
+			self.comment(comment, false)?;
+		}
+		self.indent()?;
+		match default {
+			true => write!(self.writer, "_")?,
+			false => self.write_separated(arm.patterns, ", ", Self::primary)?,
+		}
+		write!(self.writer, " =>")?;
+		self.opening_brace()?;
+		self.block(arm.body)?;
+		self.indent()?;
+		writeln!(self.writer, "}}")
+	}
+
 	fn while_loop(&mut self, while_loop: ast::WhileLoop) -> io::Result<()> {
-		write!(self.writer, "while (")?;
-		self.expression(while_loop.condition)?;
-		writeln!(self.writer, ") {{")?;
-		for instruction in while_loop.body {
-			self.instruction(instruction)?;
+		write!(self.writer, "while ")?;
+		self.parenthesized(while_loop.condition)?;
+		self.opening_brace()?;
+		self.block(while_loop.body)?;
+		self.indent()?;
+		writeln!(self.writer, "}}")?;
+		Ok(())
+	}
+
+	fn for_loop(&mut self, for_loop: ast::ForLoop) -> io::Result<()> {
+		write!(self.writer, "for ")?;
+		match for_loop.initialization {
+			ast::Statement::VariableDeclaration(variable_declaration) => {
+				self.inline_variable_declaration(*variable_declaration)?
+			},
+			ast::Statement::Assignment(assignment) => self.inline_assignment(assignment)?,
+			_ => unreachable!("the parser only accepts declarations and assignments"),
 		}
+		write!(self.writer, "; ")?;
+		self.with_struct_literals(false, |this| this.expression(for_loop.condition))?;
+		write!(self.writer, "; ")?;
+		self.with_struct_literals(false, |this| this.inline_assignment(for_loop.step))?;
+		self.opening_brace()?;
+		self.block(for_loop.body)?;
+		self.indent()?;
 		writeln!(self.writer, "}}")?;
 		Ok(())
 	}
@@ -186,8 +806,11 @@ impl Emitter {
 	fn data_type(&mut self, data_type: PositionContainer<ast::statement::DataType>) -> io::Result<()> {
 		match data_type.value {
 			DataType::Basic(basic_data_type) => self.basic_data_type(basic_data_type),
-			DataType::Struct(struct_name) => self.struct_name(struct_name),
+			DataType::Struct(struct_name) | DataType::Enum(struct_name) => self.struct_name(struct_name),
 			DataType::Pointer(pointer) => self.pointer(*pointer),
+			data_type @ (DataType::Generic { .. } | DataType::Array { .. } | DataType::Null) => {
+				write!(self.writer, "{}", data_type)
+			},
 		}
 	}
 
@@ -195,28 +818,109 @@ impl Emitter {
 		match basic_data_type {
 			BasicDataType::Int => write!(self.writer, "int"),
 			BasicDataType::Float => write!(self.writer, "float"),
+			BasicDataType::Bool => write!(self.writer, "bool"),
+			BasicDataType::Str => write!(self.writer, "str"),
+			BasicDataType::Char => write!(self.writer, "char"),
 		}
 	}
 
-	fn struct_name(&mut self, struct_name: String) -> io::Result<()> {
+	fn struct_name(&mut self, struct_name: Symbol) -> io::Result<()> {
 		write!(self.writer, "{}", struct_name)
 	}
 
 	fn pointer(&mut self, pointer: PositionContainer<ast::statement::DataType>) -> io::Result<()> {
-		write!(self.writer, "ptr")?;
+		write!(self.writer, "ptr ")?;
 		self.data_type(pointer)
 	}
 
 	fn number(&mut self, number: ast::expression::Number) -> io::Result<()> {
 		match *number {
 			ast::expression::NumberKind::Int(int) => write!(self.writer, "{}", int)?,
-			ast::expression::NumberKind::Float(float) => write!(self.writer, "{}", float)?,
+			// The debug representation round-trips, always contains a dot or an exponent and uses an exponent for very
+			// large and small numbers
+			ast::expression::NumberKind::Float(float) => write!(self.writer, "{:?}", float)?,
 		}
 		Ok(())
 	}
 
+	fn bool(&mut self, boolean: PositionContainer<bool>) -> io::Result<()> {
+		write!(self.writer, "{}", *boolean)
+	}
+
+	fn char(&mut self, char: PositionContainer<char>) -> io::Result<()> {
+		match *char {
+			'\'' => write!(self.writer, "'\\''"),
+			'\\' => write!(self.writer, "'\\\\'"),
+			'\n' => write!(self.writer, "'\\n'"),
+			'\r' => write!(self.writer, "'\\r'"),
+			'\t' => write!(self.writer, "'\\t'"),
+			'\0' => write!(self.writer, "'\\0'"),
+			char => write!(self.writer, "'{}'", char),
+		}
+	}
+
+	fn string_literal(&mut self, string: PositionContainer<String>) -> io::Result<()> {
+		write!(self.writer, "\"")?;
+		for char in string.chars() {
+			match char {
+				'"' => write!(self.writer, "\\\"")?,
+				'\\' => write!(self.writer, "\\\\")?,
+				'\n' => write!(self.writer, "\\n")?,
+				'\r' => write!(self.writer, "\\r")?,
+				'\t' => write!(self.writer, "\\t")?,
+				'\0' => write!(self.writer, "\\0")?,
+				char => write!(self.writer, "{}", char)?,
+			}
+		}
+		write!(self.writer, "\"")
+	}
+
 	fn variable(&mut self, variable: ast::expression::Variable) -> io::Result<()> {
 		write!(self.writer, "{}", *variable)?;
 		Ok(())
 	}
 }
+
+/// Whether the `expression` can be assigned to, i.e. is a variable or a field access or indexing of one.
+fn is_assignable(expression: &ast::Expression) -> bool {
+	match expression {
+		Expression::Variable(_) => true,
+		Expression::FieldAccess(field_access) => is_assignable(&field_access.base),
+		Expression::IndexExpression(index_expression) => is_assignable(&index_expression.base),
+		_ => false,
+	}
+}
+
+/// Whether the `unary_expression` dereferences an expression that can be assigned to or another such dereference,
+/// like `**p.next`, which can be assigned through.
+fn is_target(unary_expression: &ast::expression::UnaryExpression) -> bool {
+	unary_expression.operator.value == UnaryOperator::Dereference
+		&& match &*unary_expression.operand {
+			Expression::UnaryExpression(operand) => is_target(operand),
+			operand => is_assignable(operand),
+		}
+}
+
+/// Returns how `operator` is written in source code.
+fn operator_symbol(operator: &BinaryOperator) -> &'static str {
+	match operator {
+		BinaryOperator::Add => "+",
+		BinaryOperator::Subtract => "-",
+		BinaryOperator::Multiply => "*",
+		BinaryOperator::Divide => "/",
+		BinaryOperator::Power => "**",
+		BinaryOperator::BitOr => "|",
+		BinaryOperator::BitAnd => "&",
+		BinaryOperator::BitXor => "^",
+		BinaryOperator::ShiftLeft => "<<",
+		BinaryOperator::ShiftRight => ">>",
+		BinaryOperator::Less => "<",
+		BinaryOperator::Greater => ">",
+		BinaryOperator::LessEqual => "<=",
+		BinaryOperator::GreaterEqual => ">=",
+		BinaryOperator::Equal => "=",
+		BinaryOperator::NotEqual => "=/=",
+		BinaryOperator::LogicalAnd => "and",
+		BinaryOperator::LogicalOr => "or",
+	}
+}
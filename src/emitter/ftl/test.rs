@@ -0,0 +1,327 @@
+use std::sync::Arc;
+
+use super::{config, generator::Generator, BraceStyle, Config, Indent};
+use crate::{ast, lexer::Lexer, parser::Parser, source::Source};
+
+/// Tests that nested blocks are indented with a tab per level by default.
+#[test]
+fn test_indent_nested_blocks() {
+	let source_code = "
+		def main(): int {
+		var i = 0
+		while (i < 3) {
+		if (i = 1) {
+		# one
+		println(i)
+		}
+		else {
+		print(i)
+		}
+		i = i + 1
+		}
+		return i
+		}
+	";
+	let expected = concat!(
+		"def main(): int {\n",
+		"\tvar i = 0\n",
+		"\twhile (i < 3) {\n",
+		"\t\tif (i = 1) {\n",
+		"\t\t\t# one\n",
+		"\t\t\tprintln(i)\n",
+		"\t\t}\n",
+		"\t\telse {\n",
+		"\t\t\tprint(i)\n",
+		"\t\t}\n",
+		"\t\ti = i + 1\n",
+		"\t}\n",
+		"\treturn i\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(format(source_code, Config::default()), expected);
+}
+
+/// Tests that struct fields and `else if` chains can be indented with spaces.
+#[test]
+fn test_indent_spaces() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def main() {
+			if (true) {
+				return
+			} else if (false) {
+				for var i = 0; i < 2; i = i + 1 {
+					print(i)
+				}
+			}
+		}
+	";
+	let expected = concat!(
+		"struct Point {\n",
+		"  x: int\n",
+		"}\n",
+		"def main() {\n",
+		"  if (true) {\n",
+		"    return\n",
+		"  }\n",
+		"  else if (false) {\n",
+		"    for var i = 0; i < 2; i = i + 1 {\n",
+		"      print(i)\n",
+		"    }\n",
+		"  }\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(format(source_code, Config { indent: Indent::Spaces(2), ..Config::default() }), expected);
+}
+
+/// Tests that arguments, parameters, fields and elements are separated without a trailing separator.
+#[test]
+fn test_separators() {
+	let source_code = "
+		extern max(a: int, b: int): int
+		struct Pair {
+			# first
+			a: int
+			b: int
+		}
+		struct Empty {
+		}
+		def sum(a: int, b: int, c: int): int {
+			var pair = Pair { a: 1, b: 2 }
+			var array = [1, 2, 3]
+			return max(a, sum(b, c, 0))
+		}
+	";
+	let expected = concat!(
+		"extern max(a: int, b: int): int\n",
+		"struct Pair {\n",
+		"\t# first\n",
+		"\ta: int\n",
+		"\tb: int\n",
+		"}\n",
+		"struct Empty {\n",
+		"}\n",
+		"def sum(a: int, b: int, c: int): int {\n",
+		"\tvar pair = Pair { a: 1, b: 2 }\n",
+		"\tvar array = [1, 2, 3]\n",
+		"\treturn max(a, sum(b, c, 0))\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(format(source_code, Config::default()), expected);
+}
+
+/// Tests that opening braces can be placed on their own line.
+#[test]
+fn test_brace_style_next_line() {
+	let source_code = "
+		def main() {
+			while (true) {
+				break
+			}
+		}
+	";
+	let expected = concat!("def main()\n", "{\n", "\twhile (true)\n", "\t{\n", "\t\tbreak\n", "\t}\n", "\n", "}\n");
+	assert_eq!(format(source_code, Config { brace_style: BraceStyle::NextLine, ..Config::default() }), expected);
+}
+
+/// Tests that binary expressions, parameters and arguments exceeding the maximum width are wrapped.
+#[test]
+fn test_wrap_long_lines() {
+	let source_code = "
+		extern compare(first_value: int, second_value: int): bool
+		def main() {
+			var sum = (first + second * third + fourth)
+			result = calculate(first_value, (second + third))
+			if short = 1 {
+				print((a + b))
+			}
+		}
+	";
+	let expected = concat!(
+		"extern compare(\n",
+		"\tfirst_value: int,\n",
+		"\tsecond_value: int\n",
+		"): bool\n",
+		"def main() {\n",
+		"\tvar sum = (first\n",
+		"\t\t+ second * third\n",
+		"\t\t+ fourth)\n",
+		"\tresult = calculate(\n",
+		"\t\tfirst_value,\n",
+		"\t\t(second + third)\n",
+		"\t)\n",
+		"\tif (short = 1) {\n",
+		"\t\tprint((a + b))\n",
+		"\t}\n",
+		"\n",
+		"}\n",
+	);
+	let formatted = format(source_code, Config { max_width: 30, ..Config::default() });
+	assert_eq!(formatted, expected);
+	assert_eq!(format(&formatted, Config { max_width: 30, ..Config::default() }), formatted);
+}
+
+/// Tests that a configuration file is parsed, keeping the defaults of keys that are not set.
+#[test]
+fn test_config_from_toml() {
+	assert_eq!(Config::from_toml(""), Ok(Config::default()));
+	let toml = "
+		# Formatting options
+		max_width = 80
+		hard_tabs = false # indent with spaces
+		tab_spaces = 2
+		brace_style = \"NextLine\"
+	";
+	assert_eq!(
+		Config::from_toml(toml),
+		Ok(Config { max_width: 80, indent: Indent::Spaces(2), brace_style: BraceStyle::NextLine })
+	);
+}
+
+/// Tests that unknown keys, invalid values and malformed lines are rejected with their line number.
+#[test]
+fn test_config_errors() {
+	assert_eq!(Config::from_toml("\nindent"), Err(config::Error::Syntax { line: 2 }));
+	assert_eq!(Config::from_toml("tabs = true"), Err(config::Error::UnknownKey { line: 1, key: "tabs".to_owned() }));
+	assert!(matches!(Config::from_toml("max_width = wide"), Err(config::Error::InvalidValue { line: 1, .. })));
+	assert!(matches!(Config::from_toml("brace_style = \"Kr\""), Err(config::Error::InvalidValue { .. })));
+}
+
+/// Tests that formatting keeps the AST and that formatting formatted code changes nothing, for code with tricky
+/// precedences, comments, literals and compound assignments.
+#[test]
+fn test_round_trip() {
+	let source_code = r#"
+		import math
+		/* A block
+		   comment */
+		const LIMIT: int = (1 + 2) * 3 - (4 - 5)
+		extern puts(s: str): int
+		struct Line {
+			# The start
+			start: math.Point
+			points: [ptr math.Point; 2]
+		}
+		enum Color { Red,
+			# The middle
+			Green, }
+		type Path = [ptr math.Point; 2]
+		struct Pair<A, B> {
+			first: A
+			second: ptr Pair<B, [A; 2]>
+		}
+		def first<T, U>(pair: [T; 2], other: Pair<ptr U, Pair<int, math.Point>>): T {
+			return pair[0]
+		}
+		def Line.length(self: ptr Line, factor: float): float {
+			self.start.scaled(factor).length()
+			(a + b).negated().x
+			return (1).m(f().g(), x.y) * 2
+		}
+		def main(argc: int): float {
+			# One
+			# comment
+			/* Another comment */
+			var p = (a < b = (c < d))
+			var q = -(x as float) as int
+			var r = (-x).y[(1).z]
+			var s = math.Point { x: "\"\\\n", y: '\'' }
+			var t = f((a or b and c), [1, 2.5, 1e100], Line {})
+			var u = ((a ** b) ** c ** d * -e ** 2)
+			var v = if a < b { a } else if (c) { Line {} } else { (if d { e } else { f }).length }
+			if p = (Line { start: s }) {
+				return
+			}
+			else if !p {
+				return 1.0
+			}
+			for var i = 0; i < (Line { }).length; i = (Line {}) {
+				p.x[2] = a - (b - c) / (d * e)
+				p.x[f()] *= 2 + 3
+				continue
+			}
+			x
+			math.print(x)
+			while (true) {
+				break
+			}
+			match a.b {
+				1, -2, (a + b) => {
+					x
+				}
+				# The rest
+				_ => {
+				}
+			}
+			#
+			return math.sqrt(2.0) as float
+		}
+		"#;
+	let ast_nodes = parse(source_code);
+	for config in configs() {
+		let formatted = format(source_code, config);
+		assert_eq!(without_positions(&parse(&formatted)), without_positions(&ast_nodes), "{}", formatted);
+		assert_eq!(format(&formatted, config), formatted);
+	}
+}
+
+/// Tests that formatting randomly generated ASTs and parsing the formatted code results in the same AST.
+#[test]
+fn test_generated_round_trip() {
+	for seed in 0..500 {
+		let ast_nodes = Generator::new(seed).nodes();
+		for config in configs() {
+			let formatted = format_nodes(ast_nodes.clone(), config);
+			let reparsed = parse(&formatted);
+			assert_eq!(without_positions(&reparsed), without_positions(&ast_nodes), "seed {}:\n{}", seed, formatted);
+			assert_eq!(format_nodes(reparsed, config), formatted, "seed {}", seed);
+		}
+	}
+}
+
+/// Boilerplate code for formatting source code, which has to be valid.
+fn format(source_code: &str, config: Config) -> String {
+	format_nodes(parse(source_code), config)
+}
+
+fn format_nodes(ast_nodes: Vec<ast::Node>, config: Config) -> String {
+	let mut formatted = Vec::new();
+	super::Emitter::codegen_with_config(ast_nodes.into_iter(), &mut formatted, config).unwrap();
+	String::from_utf8(formatted).unwrap()
+}
+
+fn parse(source_code: &str) -> Vec<ast::Node> {
+	let tokens = lex(source_code);
+	Parser::new(tokens.into_iter()).collect::<Result<Vec<_>, _>>().unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn lex(source_code: &str) -> Vec<crate::token::Token> {
+	let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+	Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap()
+}
+
+/// Styles to test the formatting with.
+fn configs() -> [Config; 3] {
+	[
+		Config::default(),
+		Config { indent: Indent::Spaces(4), brace_style: BraceStyle::NextLine, ..Config::default() },
+		// Wraps most expressions and lists
+		Config { max_width: 20, ..Config::default() },
+	]
+}
+
+/// Debug representation of the AST without the source positions like `testfile:1:5-1:8`, which differ after formatting.
+fn without_positions(ast_nodes: &[ast::Node]) -> String {
+	let debug = format!("{:?}", ast_nodes);
+	let mut parts = debug.split("testfile:");
+	let mut stripped = parts.next().unwrap_or_default().to_owned();
+	for part in parts {
+		stripped.push_str(part.trim_start_matches(|char: char| char.is_ascii_digit() || char == ':' || char == '-'));
+	}
+	stripped
+}
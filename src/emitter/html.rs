@@ -0,0 +1,178 @@
+//! HTML emitter, rendering source code with syntax highlighting.
+//!
+//! Unlike the other emitters, the highlighting is based on the tokens instead of the AST, so that code which cannot be
+//! parsed, like a snippet with a syntax error, is highlighted, too. Code the lexer rejects is emitted without
+//! highlighting. Each token is wrapped in a `<span>` whose CSS class is its [`Category`], the whitespace between the
+//! tokens is kept as is.
+
+use std::{io, sync::Arc};
+
+use crate::{lexer::Lexer, source::Source, token::TokenKind};
+
+/// Styles of the token categories, which are embedded in the emitted document.
+const STYLE: &str = "\
+pre.ftl { background: #fafafa; padding: 1em; }
+.keyword { color: #7928a1; font-weight: bold; }
+.constant, .number { color: #005cc5; }
+.string { color: #22863a; }
+.comment { color: #6a737d; font-style: italic; }
+.type { color: #b35900; }
+.operator { color: #d73a49; }";
+
+/// Category of a token, which determines its highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+	Keyword,
+	/// `true`, `false` and `null`.
+	Constant,
+	Number,
+	/// String and char literals.
+	String,
+	Comment,
+	/// Names of basic types like `int`, which are lexed as identifiers.
+	Type,
+	Identifier,
+	Operator,
+	/// Parentheses, braces, brackets and separators like `,`.
+	Punctuation,
+}
+
+impl Category {
+	/// The category of a token.
+	pub fn of(token: &TokenKind) -> Self {
+		match token {
+			TokenKind::Def
+			| TokenKind::Extern
+			| TokenKind::If
+			| TokenKind::Else
+			| TokenKind::While
+			| TokenKind::For
+			| TokenKind::Match
+			| TokenKind::Break
+			| TokenKind::Continue
+			| TokenKind::Pointer
+			| TokenKind::Struct
+			| TokenKind::Enum
+			| TokenKind::Type
+			| TokenKind::Var
+			| TokenKind::Const
+			| TokenKind::Return
+			| TokenKind::As
+			| TokenKind::Import
+			| TokenKind::LogicalAnd
+			| TokenKind::LogicalOr => Category::Keyword,
+			TokenKind::True | TokenKind::False | TokenKind::Null => Category::Constant,
+			TokenKind::Int(_) | TokenKind::Float(_) => Category::Number,
+			TokenKind::StringLiteral(_) | TokenKind::Char(_) => Category::String,
+			TokenKind::Comment(_) => Category::Comment,
+			TokenKind::Identifier(name) if matches!(name.as_str(), "int" | "float" | "bool" | "str" | "char") => {
+				Category::Type
+			},
+			TokenKind::Identifier(_) => Category::Identifier,
+			TokenKind::OpeningParentheses
+			| TokenKind::ClosingParentheses
+			| TokenKind::OpeningCurlyBraces
+			| TokenKind::ClosingCurlyBraces
+			| TokenKind::OpeningSquareBrackets
+			| TokenKind::ClosingSquareBrackets
+			| TokenKind::Comma
+			| TokenKind::Semicolon
+			| TokenKind::Colon
+			| TokenKind::Dot
+			| TokenKind::EndOfLine => Category::Punctuation,
+			_ => Category::Operator,
+		}
+	}
+
+	/// Name of the CSS class of the category.
+	pub fn class(self) -> &'static str {
+		match self {
+			Category::Keyword => "keyword",
+			Category::Constant => "constant",
+			Category::Number => "number",
+			Category::String => "string",
+			Category::Comment => "comment",
+			Category::Type => "type",
+			Category::Identifier => "identifier",
+			Category::Operator => "operator",
+			Category::Punctuation => "punctuation",
+		}
+	}
+}
+
+/// Writes a standalone HTML document showing the `source` with syntax highlighting.
+pub fn codegen(source: Arc<Source>, mut writer: impl io::Write) -> io::Result<()> {
+	writeln!(writer, "<!DOCTYPE html>")?;
+	writeln!(writer, "<html>")?;
+	writeln!(writer, "<head>")?;
+	writeln!(writer, "<meta charset=\"utf-8\">")?;
+	write!(writer, "<title>")?;
+	escaped(&mut writer, source.name.chars())?;
+	writeln!(writer, "</title>")?;
+	writeln!(writer, "<style>\n{}\n</style>", STYLE)?;
+	writeln!(writer, "</head>")?;
+	writeln!(writer, "<body>")?;
+	write!(writer, "<pre class=\"ftl\"><code>")?;
+	code(Arc::clone(&source), &mut writer)?;
+	writeln!(writer, "</code></pre>")?;
+	writeln!(writer, "</body>")?;
+	writeln!(writer, "</html>")
+}
+
+/// Writes the highlighted `source` only, e.g. to embed it into another document.
+pub fn code(source: Arc<Source>, mut writer: impl io::Write) -> io::Result<()> {
+	// Offset of the first char that has not been written yet
+	let mut offset = 0;
+	// The lexer skips over code it rejects, which ends up between the tokens
+	for token in Lexer::new(Arc::clone(&source).iter()).flatten() {
+		let range = token.position.position;
+		escaped(&mut writer, source.text[offset..range.start.offset].iter().copied())?;
+		write!(writer, "<span class=\"{}\">", Category::of(&token.value).class())?;
+		escaped(&mut writer, source.text[range.start.offset..=range.end.offset].iter().copied())?;
+		write!(writer, "</span>")?;
+		offset = range.end.offset + 1;
+	}
+	escaped(&mut writer, source.text[offset..].iter().copied())
+}
+
+/// Writes the `chars`, escaping those with a special meaning in HTML.
+fn escaped(writer: &mut impl io::Write, chars: impl Iterator<Item = char>) -> io::Result<()> {
+	for char in chars {
+		match char {
+			'<' => write!(writer, "&lt;")?,
+			'>' => write!(writer, "&gt;")?,
+			'&' => write!(writer, "&amp;")?,
+			'"' => write!(writer, "&quot;")?,
+			char => write!(writer, "{}", char)?,
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::source::Source;
+
+	/// Tests that tokens are wrapped in spans of their category, while whitespace and rejected code is kept.
+	#[test]
+	fn test_code() {
+		let source_code = "def f(x: int) { # a < b\n\treturn x + \"&\" ~ 1\n}";
+		let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+		let mut html = Vec::new();
+		super::code(source, &mut html).unwrap();
+		let expected = concat!(
+			r#"<span class="keyword">def</span> <span class="identifier">f</span>"#,
+			r#"<span class="punctuation">(</span><span class="identifier">x</span><span class="punctuation">:</span> "#,
+			r#"<span class="type">int</span><span class="punctuation">)</span> <span class="punctuation">{</span> "#,
+			r#"<span class="comment"># a &lt; b</span>"#,
+			"\n\t",
+			r#"<span class="keyword">return</span> <span class="identifier">x</span> <span class="operator">+</span> "#,
+			r#"<span class="string">&quot;&amp;&quot;</span> ~ <span class="number">1</span>"#,
+			"\n",
+			r#"<span class="punctuation">}</span>"#,
+		);
+		assert_eq!(String::from_utf8(html).unwrap(), expected);
+	}
+}
@@ -0,0 +1,822 @@
+//! LLVM IR emitter.
+//!
+//! Emits textual LLVM IR from the [IR](crate::ir) of a program, which can be compiled to native code with `llc`. Every
+//! argument and variable lives in a stack slot (`alloca`) that is read with `load` and written with `store`, so no phi
+//! nodes are needed. LLVM's `mem2reg` pass turns these stack slots into registers later on. Temporaries that are
+//! assigned once are registers, the other ones, like the result of `and`, get a stack slot as well.
+//!
+//! Every function and instruction carries debug info in form of a `DISubprogram` or `DILocation`, so that a debugger
+//! or a backtrace shows the FTL source position of the generated code.
+
+#[cfg(test)]
+mod test;
+
+use std::{collections::HashMap, io, io::Write};
+
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryOperator, UnaryOperator},
+		statement::{BasicDataType, DataType},
+	},
+	builtin::Builtin,
+	interpreter::Value,
+	ir::{self, BlockId, Callee, Instruction, Local, Operand, Projection, Terminator},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Implementation of the [builtin functions](crate::builtin) by calls of the C library.
+const PRELUDE: &str = include_str!("prelude.ll");
+
+/// Emits LLVM IR.
+pub struct Emitter<W> {
+	writer: W,
+	/// Signatures of all functions in the program by their index, so that calls to functions defined later can be
+	/// emitted.
+	functions: Vec<ir::Signature>,
+	/// Signatures of all extern functions in the program by their index.
+	externs: Vec<ir::Signature>,
+	/// All structs in the program by their name, so that the index of a field can be looked up.
+	structs: HashMap<Symbol, ir::Struct>,
+	/// Stack slots (`alloca`s) of the current function. They are collected separately, because they all have to be
+	/// placed in the entry block.
+	allocas: Vec<u8>,
+	/// Instructions of the current function.
+	body: Vec<u8>,
+	/// Where the value of each local of the current function is kept.
+	locals: Vec<Place>,
+	/// Counter for generating unique register names.
+	next_id: usize,
+	/// Number of string literals emitted as global constants so far, used for naming them uniquely.
+	strings: usize,
+	/// Debug info metadata nodes like `!DILocation(...)`, which are written after all functions. The id of a node is
+	/// its index, where the id 0 is reserved for the compile unit.
+	metadata: Vec<String>,
+	/// Ids of the `DIFile` metadata nodes by the name of their source.
+	files: HashMap<String, usize>,
+	/// Id of the `DISubprogram` metadata node of the current function, which is the scope of its locations.
+	subprogram: usize,
+	/// Ids of the `DILocation` metadata nodes in the current function by their position.
+	locations: HashMap<SourcePositionRange, usize>,
+}
+
+/// Where the value of a [`Local`] is kept.
+#[derive(Debug, Clone)]
+enum Place {
+	/// A stack slot like `%x.0`, which is written with `store` and read with `load`.
+	Slot(String),
+	/// The register or constant that was assigned to a temporary, or [`None`] before its assignment.
+	Value(Option<String>),
+}
+
+impl<W: io::Write> super::Emitter<W> for Emitter<W> {
+	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: W) -> io::Result<()> {
+		let program = ir::lower(ast_nodes).map_err(io::Error::other)?;
+		Self::emit(&program, writer)
+	}
+}
+
+impl<W: io::Write> Emitter<W> {
+	/// Emits the `program`, which was lowered to the IR, e.g. to run passes over it before.
+	pub fn emit(program: &ir::Program, writer: W) -> io::Result<()> {
+		let mut this = Self {
+			writer,
+			functions: program.functions.iter().map(ir::Function::signature).collect(),
+			externs: program.externs.clone(),
+			structs: program.structs.iter().map(|struct_| (struct_.name, struct_.clone())).collect(),
+			allocas: Vec::new(),
+			body: Vec::new(),
+			locals: Vec::new(),
+			next_id: 0,
+			strings: 0,
+			metadata: vec![String::new()],
+			files: HashMap::new(),
+			subprogram: 0,
+			locations: HashMap::new(),
+		};
+
+		writeln!(this.writer, "{}", PRELUDE)?;
+		for struct_ in &program.structs {
+			this.struct_(struct_)?;
+		}
+		for extern_ in &program.externs {
+			this.function_declaration(extern_)?;
+		}
+		for function in &program.functions {
+			this.function(function)?;
+		}
+		this.debug_info()
+	}
+}
+
+/// Each of the functions in this impl block is responsible for emitting the corresponding part of the IR.
+impl<W: io::Write> Emitter<W> {
+	fn struct_(&mut self, struct_: &ir::Struct) -> io::Result<()> {
+		let fields = struct_.fields.iter().map(|(_, data_type)| llvm_type(data_type)).collect::<Vec<_>>();
+		writeln!(self.writer, "{} = type {{ {} }}\n", identifier('%', &struct_.name), fields.join(", "))
+	}
+
+	fn function_declaration(&mut self, signature: &ir::Signature) -> io::Result<()> {
+		let args = signature.args.iter().map(llvm_type).collect::<Vec<_>>();
+		writeln!(
+			self.writer,
+			"declare {} {}({})\n",
+			return_type(&signature.return_type),
+			identifier('@', &signature.name),
+			args.join(", ")
+		)
+	}
+
+	fn function(&mut self, function: &ir::Function) -> io::Result<()> {
+		self.allocas.clear();
+		self.body.clear();
+		self.next_id = 0;
+		self.locations.clear();
+		self.subprogram = self.subprogram(function);
+
+		// Temporaries assigned once are used directly, all other locals get a stack slot
+		let mut assignments = vec![0; function.locals.len()];
+		for instruction in function.blocks.iter().flat_map(|block| &block.instructions) {
+			if let Some(dest) = instruction.dest() {
+				assignments[dest.0] += 1;
+			}
+		}
+		self.locals.clear();
+		for (local, assignments) in function.locals.iter().zip(assignments) {
+			let place = match (local.name, assignments) {
+				(None, 1) => Place::Value(None),
+				(Some(name), _) => Place::Slot(self.slot(&format!("{}.{}", name, self.next_id), &local.data_type)?),
+				(None, _) => {
+					let slot = self.register();
+					Place::Slot(self.slot(&slot[1..], &local.data_type)?)
+				},
+			};
+			self.locals.push(place);
+		}
+
+		// Copy each argument into its stack slot, so it can be assigned like every other variable
+		let mut args = Vec::new();
+		for (index, arg) in function.locals[..function.arity].iter().enumerate() {
+			let type_ = llvm_type(&arg.data_type);
+			let arg_name = identifier('%', &arg.name.expect("Argument without name"));
+			args.push(format!("{} {}", type_, arg_name));
+			let Place::Slot(slot) = &self.locals[index] else { unreachable!("Arguments have stack slots") };
+			writeln!(self.body, "\tstore {} {}, {}* {}", type_, arg_name, type_, slot)?;
+		}
+
+		for (id, block) in function.blocks.iter().enumerate() {
+			// The first block is the entry block, which is never jumped to
+			if id != 0 {
+				writeln!(self.body, "{}:", BlockId(id))?;
+			}
+			for (instruction, position) in block.instructions.iter().zip(&block.positions) {
+				let start = self.body.len();
+				self.instruction(function, instruction, position)?;
+				self.attach_location(start, position);
+			}
+			let start = self.body.len();
+			self.terminator(function, &block.terminator)?;
+			if let Some(position) = &block.terminator_position {
+				self.attach_location(start, position);
+			}
+		}
+
+		writeln!(
+			self.writer,
+			"define {} {}({}) !dbg !{} {{",
+			return_type(&function.return_type),
+			identifier('@', &function.name),
+			args.join(", "),
+			self.subprogram
+		)?;
+		writeln!(self.writer, "entry:")?;
+		self.writer.write_all(&self.allocas)?;
+		self.writer.write_all(&self.body)?;
+		writeln!(self.writer, "}}\n")?;
+		Ok(())
+	}
+
+	/// Emits an instruction, which was lowered from the FTL instruction at `position`.
+	fn instruction(
+		&mut self,
+		function: &ir::Function,
+		instruction: &Instruction,
+		position: &SourcePositionRange,
+	) -> io::Result<()> {
+		match instruction {
+			Instruction::Copy { dest, value } => {
+				let value = self.value(function, value)?;
+				self.assign(function, *dest, value)
+			},
+			Instruction::Binary { dest, operator, lhs, rhs } => {
+				let result = self.binary(function, operator, lhs, rhs, position)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Unary { dest, operator, operand } => {
+				let result = self.unary(function, *operator, operand)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Convert { dest, data_type, value } => {
+				let result = self.convert(function, data_type, value, position)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Call { dest, callee, args } => {
+				let result = self.call(function, *callee, args)?;
+				match (dest, result) {
+					(Some(dest), Some(result)) => self.assign(function, *dest, result),
+					_ => Ok(()),
+				}
+			},
+			Instruction::MakeArray { dest, elements } => {
+				let mut values = Vec::with_capacity(elements.len());
+				for element in elements {
+					values.push(self.value(function, element)?);
+				}
+				let data_type = &function.locals[dest.0].data_type;
+				let DataType::Array { element, .. } = data_type else {
+					return Err(io::Error::other(format!("{}: {} is no array", position, data_type)));
+				};
+				let values = values.into_iter().map(|value| (&**element, value)).enumerate();
+				let result = self.aggregate(data_type, values)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::MakeStruct { dest, name, fields } => {
+				let data_type = DataType::Struct(name.value);
+				let mut values = Vec::with_capacity(fields.len());
+				for (field, value) in fields {
+					let field = PositionContainer::new(*field, name.position.clone());
+					let (index, field_type) = self.field(&data_type, &field)?;
+					values.push((index, field_type, self.value(function, value)?));
+				}
+				let values = values.iter().map(|(index, field_type, value)| (*index, (field_type, value.clone())));
+				let result = self.aggregate(&data_type, values)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Index { dest, base, index } => {
+				let base_type = function.operand_type(base);
+				let pointer = self.pointer(function, base)?;
+				let index = self.value(function, index)?;
+				let element = self.element_pointer(&base_type, &pointer, &index)?;
+				let type_ = llvm_type(&function.locals[dest.0].data_type);
+				let result = self.register();
+				writeln!(self.body, "\t{} = load {}, {}* {}", result, type_, type_, element)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Field { dest, base, field } => {
+				let base_type = function.operand_type(base);
+				let (index, _) = self.field(&base_type, field)?;
+				let base = self.value(function, base)?;
+				let result = self.register();
+				writeln!(self.body, "\t{} = extractvalue {} {}, {}", result, llvm_type(&base_type), base, index)?;
+				self.assign(function, *dest, result)
+			},
+			Instruction::Store { target, projections, value } => {
+				let Place::Slot(pointer) = self.locals[target.0].clone() else {
+					return Err(io::Error::other(format!(
+						"{}: Assignment to a part of temporary {}",
+						position, target
+					)));
+				};
+				let data_type = function.locals[target.0].data_type.clone();
+				self.store(function, pointer, data_type, projections, value)
+			},
+			Instruction::StorePointer { pointer, projections, value } => {
+				let DataType::Pointer(pointee) = function.operand_type(pointer) else {
+					return Err(io::Error::other(format!(
+						"{}: Assignment through {}, which is no pointer",
+						position, pointer
+					)));
+				};
+				let pointer = self.value(function, pointer)?;
+				self.store(function, pointer, pointee.value, projections, value)
+			},
+		}
+	}
+
+	/// Stores the `value` into the part of the value of type `data_type` at `pointer`, which the `projections` lead to.
+	fn store(
+		&mut self,
+		function: &ir::Function,
+		mut pointer: String,
+		mut data_type: DataType,
+		projections: &[Projection],
+		value: &Operand,
+	) -> io::Result<()> {
+		for projection in projections {
+			let type_ = llvm_type(&data_type);
+			let result = self.register();
+			match projection {
+				Projection::Field(field) => {
+					let (index, field_type) = self.field(&data_type, field)?;
+					writeln!(
+						self.body,
+						"\t{} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+						result, type_, type_, pointer, index
+					)?;
+					data_type = field_type;
+				},
+				Projection::Index(index) => {
+					let index = self.value(function, index)?;
+					pointer = self.element_pointer(&data_type, &pointer, &index)?;
+					let DataType::Array { element, .. } = data_type else { unreachable!("Checked before") };
+					data_type = *element;
+					continue;
+				},
+			}
+			pointer = result;
+		}
+		let value = self.value(function, value)?;
+		let type_ = llvm_type(&data_type);
+		writeln!(self.body, "\tstore {} {}, {}* {}", type_, value, type_, pointer)
+	}
+
+	fn binary(
+		&mut self,
+		function: &ir::Function,
+		operator: &BinaryOperator,
+		lhs: &Operand,
+		rhs: &Operand,
+		position: &SourcePositionRange,
+	) -> io::Result<String> {
+		// `null` takes the pointer type of the other operand, because LLVM has no untyped null pointer
+		let data_type = match function.operand_type(lhs) {
+			DataType::Null => function.operand_type(rhs),
+			data_type => data_type,
+		};
+		let lhs = self.value(function, lhs)?;
+		let rhs = self.value(function, rhs)?;
+		let is_float = data_type == DataType::Basic(BasicDataType::Float);
+		let type_ = llvm_type(&data_type);
+		if is_float && operator.is_bitwise() {
+			return Err(io::Error::other(format!("{}: Operator {:?} cannot be applied to floats", position, operator)));
+		}
+		let result = self.register();
+		if *operator == BinaryOperator::Power {
+			let function = if is_float { "llvm.pow.f64" } else { "ftl.int_power" };
+			writeln!(self.body, "\t{} = call {} @{}({} {}, {} {})", result, type_, function, type_, lhs, type_, rhs)?;
+			return Ok(result);
+		}
+
+		let instruction = match (operator, is_float) {
+			(BinaryOperator::Add, false) => "add",
+			(BinaryOperator::Add, true) => "fadd",
+			(BinaryOperator::Subtract, false) => "sub",
+			(BinaryOperator::Subtract, true) => "fsub",
+			(BinaryOperator::Multiply, false) => "mul",
+			(BinaryOperator::Multiply, true) => "fmul",
+			(BinaryOperator::Divide, false) => "sdiv",
+			(BinaryOperator::Divide, true) => "fdiv",
+			(BinaryOperator::Power, _) => unreachable!("`**` is emitted as call"),
+			(BinaryOperator::BitOr, _) => "or",
+			(BinaryOperator::BitAnd, _) => "and",
+			(BinaryOperator::BitXor, _) => "xor",
+			(BinaryOperator::ShiftLeft, _) => "shl",
+			(BinaryOperator::ShiftRight, _) => "ashr",
+			(BinaryOperator::Less, false) => "icmp slt",
+			(BinaryOperator::Less, true) => "fcmp olt",
+			(BinaryOperator::Greater, false) => "icmp sgt",
+			(BinaryOperator::Greater, true) => "fcmp ogt",
+			(BinaryOperator::LessEqual, false) => "icmp sle",
+			(BinaryOperator::LessEqual, true) => "fcmp ole",
+			(BinaryOperator::GreaterEqual, false) => "icmp sge",
+			(BinaryOperator::GreaterEqual, true) => "fcmp oge",
+			(BinaryOperator::Equal, false) => "icmp eq",
+			(BinaryOperator::Equal, true) => "fcmp oeq",
+			(BinaryOperator::NotEqual, false) => "icmp ne",
+			(BinaryOperator::NotEqual, true) => "fcmp une",
+			(BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr, _) => {
+				unreachable!("Logical operators are lowered to branches")
+			},
+		};
+		writeln!(self.body, "\t{} = {} {} {}, {}", result, instruction, type_, lhs, rhs)?;
+		Ok(result)
+	}
+
+	fn unary(&mut self, function: &ir::Function, operator: UnaryOperator, operand: &Operand) -> io::Result<String> {
+		let data_type = function.operand_type(operand);
+		let type_ = llvm_type(&data_type);
+		let operand = self.value(function, operand)?;
+		let result = self.register();
+		match (operator, data_type) {
+			(UnaryOperator::Negate, DataType::Basic(BasicDataType::Float)) => {
+				writeln!(self.body, "\t{} = fneg {} {}", result, type_, operand)?
+			},
+			(UnaryOperator::Negate, _) => writeln!(self.body, "\t{} = sub {} 0, {}", result, type_, operand)?,
+			(UnaryOperator::Not, _) => writeln!(self.body, "\t{} = xor {} {}, true", result, type_, operand)?,
+			(UnaryOperator::Dereference, DataType::Pointer(pointee)) => {
+				let pointee = llvm_type(&pointee.value);
+				writeln!(self.body, "\t{} = load {}, {} {}", result, pointee, type_, operand)?
+			},
+			(UnaryOperator::Dereference, data_type) => {
+				return Err(io::Error::other(format!("{} is dereferenced, but is no pointer", data_type)))
+			},
+		}
+		Ok(result)
+	}
+
+	/// Emits a conversion between int and float. Converting an enum to an int or a value to its own type does not
+	/// change the value.
+	fn convert(
+		&mut self,
+		function: &ir::Function,
+		data_type: &BasicDataType,
+		value: &Operand,
+		position: &SourcePositionRange,
+	) -> io::Result<String> {
+		let from = function.operand_type(value);
+		let value = self.value(function, value)?;
+		let instruction = match (&from, data_type) {
+			(DataType::Basic(BasicDataType::Int), BasicDataType::Float) => "sitofp",
+			(DataType::Basic(BasicDataType::Float), BasicDataType::Int) => "fptosi",
+			(DataType::Basic(from), to) if from == to => return Ok(value),
+			(DataType::Enum(_), BasicDataType::Int) => return Ok(value),
+			(from, to) => return Err(io::Error::other(format!("{}: Cannot convert {} to {}", position, from, to))),
+		};
+		let to = DataType::Basic(data_type.clone());
+		let result = self.register();
+		writeln!(self.body, "\t{} = {} {} {} to {}", result, instruction, llvm_type(&from), value, llvm_type(&to))?;
+		Ok(result)
+	}
+
+	/// Emits a function call and returns its result, or [`None`] if the function has no return value.
+	fn call(&mut self, function: &ir::Function, callee: Callee, args: &[Operand]) -> io::Result<Option<String>> {
+		let signature = match callee {
+			Callee::Function(index) => self.functions[index].clone(),
+			Callee::Extern(index) => self.externs[index].clone(),
+			Callee::Builtin(builtin) => return self.builtin_call(function, builtin, args),
+		};
+		let mut params = Vec::new();
+		for (arg, data_type) in args.iter().zip(&signature.args) {
+			params.push(format!("{} {}", llvm_type(data_type), self.value(function, arg)?));
+		}
+
+		let name = identifier('@', &signature.name);
+		match signature.return_type {
+			None => {
+				writeln!(self.body, "\tcall void {}({})", name, params.join(", "))?;
+				Ok(None)
+			},
+			Some(data_type) => {
+				let result = self.register();
+				writeln!(self.body, "\t{} = call {} {}({})", result, llvm_type(&data_type), name, params.join(", "))?;
+				Ok(Some(result))
+			},
+		}
+	}
+
+	/// Emits the call of a builtin, which is implemented by the [prelude](PRELUDE).
+	fn builtin_call(
+		&mut self,
+		function: &ir::Function,
+		builtin: Builtin,
+		args: &[Operand],
+	) -> io::Result<Option<String>> {
+		match builtin {
+			Builtin::Print | Builtin::Println => {
+				let arg = args.first().expect("print without argument");
+				let data_type = function.operand_type(arg);
+				let print = match &data_type {
+					DataType::Basic(BasicDataType::Int) => "print_int",
+					DataType::Basic(BasicDataType::Float) => "print_float",
+					DataType::Basic(BasicDataType::Bool) => "print_bool",
+					DataType::Basic(BasicDataType::Str) => "print_str",
+					DataType::Basic(BasicDataType::Char) => "print_char",
+					data_type => return Err(io::Error::other(format!("Cannot print {}", data_type))),
+				};
+				let value = self.value(function, arg)?;
+				writeln!(self.body, "\tcall void @ftl.{}({} {})", print, llvm_type(&data_type), value)?;
+				if builtin == Builtin::Println {
+					writeln!(self.body, "\tcall void @ftl.println()")?;
+				}
+				Ok(None)
+			},
+			Builtin::ReadInt => {
+				let result = self.register();
+				writeln!(self.body, "\t{} = call i64 @ftl.read_int()", result)?;
+				Ok(Some(result))
+			},
+			Builtin::Sqrt | Builtin::Abs | Builtin::Pow | Builtin::Min | Builtin::Max => {
+				let intrinsic = match builtin {
+					Builtin::Sqrt => "sqrt",
+					Builtin::Abs => "fabs",
+					Builtin::Pow => "pow",
+					Builtin::Min => "minnum",
+					_ => "maxnum",
+				};
+				let mut params = Vec::new();
+				for arg in args {
+					params.push(format!("double {}", self.value(function, arg)?));
+				}
+				let result = self.register();
+				writeln!(self.body, "\t{} = call double @llvm.{}.f64({})", result, intrinsic, params.join(", "))?;
+				Ok(Some(result))
+			},
+		}
+	}
+
+	fn terminator(&mut self, function: &ir::Function, terminator: &Terminator) -> io::Result<()> {
+		match terminator {
+			Terminator::Jump(target) => writeln!(self.body, "\tbr label %{}", target),
+			Terminator::Branch { condition, if_true, if_false } => {
+				let condition = self.condition(function, condition)?;
+				writeln!(self.body, "\tbr i1 {}, label %{}, label %{}", condition, if_true, if_false)
+			},
+			Terminator::Return(Some(value)) => {
+				let data_type = function.return_type.clone().unwrap_or_else(|| function.operand_type(value));
+				let value = self.value(function, value)?;
+				writeln!(self.body, "\tret {} {}", llvm_type(&data_type), value)
+			},
+			// Falling off the end is fine for functions without return value
+			Terminator::Return(None) => match function.return_type {
+				None => writeln!(self.body, "\tret void"),
+				Some(_) => writeln!(self.body, "\tunreachable"),
+			},
+		}
+	}
+
+	/// Returns an `i1` that is true if the `condition` is true or, for a number, non-zero.
+	fn condition(&mut self, function: &ir::Function, condition: &Operand) -> io::Result<String> {
+		let data_type = function.operand_type(condition);
+		let condition = self.value(function, condition)?;
+		if data_type == DataType::Basic(BasicDataType::Bool) {
+			return Ok(condition);
+		}
+		let result = self.register();
+		match data_type {
+			DataType::Basic(BasicDataType::Float) => {
+				writeln!(self.body, "\t{} = fcmp une double {}, 0.0", result, condition)?
+			},
+			_ => writeln!(self.body, "\t{} = icmp ne i64 {}, 0", result, condition)?,
+		}
+		Ok(result)
+	}
+
+	/// Returns the value of the `operand`, which is loaded if the operand is a local with a stack slot.
+	fn value(&mut self, function: &ir::Function, operand: &Operand) -> io::Result<String> {
+		let local = match operand {
+			Operand::Constant(value) => return self.constant(value),
+			Operand::Local(local) => *local,
+		};
+		match &self.locals[local.0] {
+			Place::Slot(slot) => {
+				let slot = slot.clone();
+				let type_ = llvm_type(&function.locals[local.0].data_type);
+				let result = self.register();
+				writeln!(self.body, "\t{} = load {}, {}* {}", result, type_, type_, slot)?;
+				Ok(result)
+			},
+			Place::Value(Some(value)) => Ok(value.clone()),
+			Place::Value(None) => Err(io::Error::other(format!("Temporary {} is used before it is assigned", local))),
+		}
+	}
+
+	/// Stores the `value` in the stack slot of the local `dest`, or remembers it if the local is a temporary.
+	fn assign(&mut self, function: &ir::Function, dest: Local, value: String) -> io::Result<()> {
+		match &mut self.locals[dest.0] {
+			Place::Slot(slot) => {
+				let type_ = llvm_type(&function.locals[dest.0].data_type);
+				writeln!(self.body, "\tstore {} {}, {}* {}", type_, value, type_, slot)
+			},
+			Place::Value(assigned) => {
+				*assigned = Some(value);
+				Ok(())
+			},
+		}
+	}
+
+	/// Returns a pointer to the value of the `operand`, which is spilled into a new stack slot, unless it is a local
+	/// that already has one.
+	fn pointer(&mut self, function: &ir::Function, operand: &Operand) -> io::Result<String> {
+		if let Operand::Local(local) = operand {
+			if let Place::Slot(slot) = &self.locals[local.0] {
+				return Ok(slot.clone());
+			}
+		}
+		let data_type = function.operand_type(operand);
+		let value = self.value(function, operand)?;
+		let slot = self.register();
+		let slot = self.slot(&slot[1..], &data_type)?;
+		let type_ = llvm_type(&data_type);
+		writeln!(self.body, "\tstore {} {}, {}* {}", type_, value, type_, slot)?;
+		Ok(slot)
+	}
+
+	/// Allocates a stack slot called `name` for a value of the `data_type` and returns it.
+	fn slot(&mut self, name: &str, data_type: &DataType) -> io::Result<String> {
+		let slot = identifier('%', name);
+		self.next_id += 1;
+		writeln!(self.allocas, "\t{} = alloca {}", slot, llvm_type(data_type))?;
+		Ok(slot)
+	}
+
+	fn constant(&mut self, value: &Value) -> io::Result<String> {
+		Ok(match value {
+			Value::Int(int) => int.to_string(),
+			// Hexadecimal notation represents every double exactly
+			Value::Float(float) => format!("0x{:016X}", float.to_bits()),
+			Value::Bool(boolean) => boolean.to_string(),
+			Value::Char(char) => (*char as u32).to_string(),
+			Value::Str(string) => self.string_literal(string)?,
+			Value::Null => "null".to_owned(),
+			Value::Struct(_) | Value::Array(_) => unreachable!("Constants are literals"),
+		})
+	}
+
+	/// Emits the string as a null-terminated global constant and returns a pointer to its first character.
+	fn string_literal(&mut self, string: &str) -> io::Result<String> {
+		let mut bytes = string.as_bytes().to_vec();
+		bytes.push(0);
+		let array_type = format!("[{} x i8]", bytes.len());
+		let name = format!("@.str.{}", self.strings);
+		self.strings += 1;
+
+		// Globals may be placed anywhere outside of functions, and the current function is only written at its end
+		write!(self.writer, "{} = private unnamed_addr constant {} c\"", name, array_type)?;
+		for byte in bytes {
+			match byte {
+				b' '..=b'~' if byte != b'"' && byte != b'\\' => write!(self.writer, "{}", byte as char)?,
+				byte => write!(self.writer, "\\{:02X}", byte)?,
+			}
+		}
+		writeln!(self.writer, "\"\n")?;
+
+		Ok(format!("getelementptr inbounds ({0}, {0}* {1}, i64 0, i64 0)", array_type, name))
+	}
+
+	/// Builds a struct or an array of the `data_type` by inserting the `values` with their index and type one after
+	/// another, starting with an `undef` value.
+	fn aggregate<'t>(
+		&mut self,
+		data_type: &DataType,
+		values: impl Iterator<Item = (usize, (&'t DataType, String))>,
+	) -> io::Result<String> {
+		let type_ = llvm_type(data_type);
+		let mut aggregate = "undef".to_owned();
+		for (index, (value_type, value)) in values {
+			let result = self.register();
+			writeln!(
+				self.body,
+				"\t{} = insertvalue {} {}, {} {}, {}",
+				result,
+				type_,
+				aggregate,
+				llvm_type(value_type),
+				value,
+				index
+			)?;
+			aggregate = result;
+		}
+		Ok(aggregate)
+	}
+
+	/// Looks up the index and the type of the field `name` of the struct type `data_type`.
+	fn field(&self, data_type: &DataType, name: &PositionContainer<Symbol>) -> io::Result<(usize, DataType)> {
+		let field = match data_type {
+			DataType::Struct(struct_name) => self
+				.structs
+				.get(struct_name)
+				.and_then(|struct_| struct_.fields.iter().enumerate().find(|(_, (field, _))| *field == name.value)),
+			_ => None,
+		};
+		let Some((index, (_, field_type))) = field else {
+			return Err(io::Error::other(format!("{}: {} has no field `{}`", name.position, data_type, **name)));
+		};
+		Ok((index, field_type.clone()))
+	}
+
+	/// Computes the pointer to the element at `index` of the array of type `data_type` at `pointer`.
+	fn element_pointer(&mut self, data_type: &DataType, pointer: &str, index: &str) -> io::Result<String> {
+		if !matches!(data_type, DataType::Array { .. }) {
+			return Err(io::Error::other(format!("{} is no array", data_type)));
+		}
+		let type_ = llvm_type(data_type);
+		let result = self.register();
+		writeln!(
+			self.body,
+			"\t{} = getelementptr inbounds {}, {}* {}, i64 0, i64 {}",
+			result, type_, type_, pointer, index
+		)?;
+		Ok(result)
+	}
+
+	/// Generates a new unique register name.
+	fn register(&mut self) -> String {
+		// FTL identifiers cannot start with a dot, so this never collides with a variable name
+		self.next_id += 1;
+		format!("%.{}", self.next_id)
+	}
+}
+
+/// Debug info, which maps the generated instructions back to the FTL source code.
+impl<W: io::Write> Emitter<W> {
+	/// Adds a metadata node and returns its id.
+	fn metadata(&mut self, node: String) -> usize {
+		self.metadata.push(node);
+		self.metadata.len() - 1
+	}
+
+	/// Returns the id of the `DIFile` of the source called `name`, which is added when it is used for the first time.
+	fn file(&mut self, name: &str) -> usize {
+		if let Some(&file) = self.files.get(name) {
+			return file;
+		}
+		let file = self.metadata(format!("!DIFile(filename: \"{}\", directory: \"\")", escaped(name)));
+		self.files.insert(name.to_owned(), file);
+		file
+	}
+
+	/// Adds the `DISubprogram` of the `function` and returns its id.
+	fn subprogram(&mut self, function: &ir::Function) -> usize {
+		let file = self.file(&function.position.source.name);
+		let type_ = self.metadata("!DISubroutineType(types: !{})".to_owned());
+		let line = function.position.position.start.line;
+		self.metadata(format!(
+			"distinct !DISubprogram(name: \"{}\", scope: !{file}, file: !{file}, line: {line}, type: !{type_}, \
+			 scopeLine: {line}, spFlags: DISPFlagDefinition, unit: !0)",
+			escaped(&function.name),
+		))
+	}
+
+	/// Returns the id of the `DILocation` of the `position` in the current function, which is added when it is used
+	/// for the first time.
+	fn location(&mut self, position: &SourcePositionRange) -> usize {
+		if let Some(&location) = self.locations.get(position) {
+			return location;
+		}
+		let start = position.position.start;
+		let location = self.metadata(format!(
+			"!DILocation(line: {}, column: {}, scope: !{})",
+			start.line, start.column, self.subprogram
+		));
+		self.locations.insert(position.clone(), location);
+		location
+	}
+
+	/// Attaches the location of the `position` to the instructions emitted to the body since `start`.
+	fn attach_location(&mut self, start: usize, position: &SourcePositionRange) {
+		let location = self.location(position);
+		let emitted = self.body.split_off(start);
+		for line in String::from_utf8_lossy(&emitted).lines() {
+			let _ = writeln!(self.body, "{}, !dbg !{}", line, location);
+		}
+	}
+
+	/// Writes the metadata nodes, unless no function was defined.
+	fn debug_info(&mut self) -> io::Result<()> {
+		// The compile unit belongs to the first file, which is the one of the main module
+		let Some(&file) = self.files.values().min() else {
+			return Ok(());
+		};
+		self.metadata[0] = format!(
+			"distinct !DICompileUnit(language: DW_LANG_C, file: !{}, producer: \"fortytwolang\", isOptimized: false, \
+			 runtimeVersion: 0, emissionKind: LineTablesOnly)",
+			file
+		);
+		let version = self.metadata("!{i32 2, !\"Debug Info Version\", i32 3}".to_owned());
+		// Older assemblers don't understand the `.file` directives of DWARF 5
+		let dwarf_version = self.metadata("!{i32 7, !\"Dwarf Version\", i32 4}".to_owned());
+		writeln!(self.writer, "!llvm.dbg.cu = !{{!0}}")?;
+		writeln!(self.writer, "!llvm.module.flags = !{{!{}, !{}}}", version, dwarf_version)?;
+		for (id, node) in self.metadata.iter().enumerate() {
+			writeln!(self.writer, "!{} = {}", id, node)?;
+		}
+		Ok(())
+	}
+}
+
+/// Formats the name of a local value or type (`sigil` `%`) or of a global value (`@`). Names with other chars than
+/// ASCII letters, digits, `.`, `_`, `-` and `$`, like `%"café.0"` of a variable `café`, have to be quoted.
+fn identifier(sigil: char, name: &str) -> String {
+	let plain = name.chars().all(|char| char.is_ascii_alphanumeric() || matches!(char, '.' | '_' | '-' | '$'));
+	match plain && !name.starts_with(|char: char| char.is_ascii_digit()) {
+		true => format!("{}{}", sigil, name),
+		false => format!("{}\"{}\"", sigil, escaped(name)),
+	}
+}
+
+/// Escapes quotes and backslashes in a metadata string or a quoted name.
+fn escaped(string: &str) -> String {
+	string.replace('\\', "\\5C").replace('"', "\\22")
+}
+
+/// Converts an FTL data type into its LLVM IR counterpart.
+fn llvm_type(data_type: &DataType) -> String {
+	match data_type {
+		DataType::Basic(BasicDataType::Int) | DataType::Enum(_) => "i64".to_owned(),
+		DataType::Basic(BasicDataType::Float) => "double".to_owned(),
+		DataType::Basic(BasicDataType::Bool) => "i1".to_owned(),
+		DataType::Basic(BasicDataType::Str) => "i8*".to_owned(),
+		DataType::Basic(BasicDataType::Char) => "i32".to_owned(),
+		DataType::Struct(struct_name) => identifier('%', struct_name),
+		DataType::Pointer(pointer) => format!("{}*", llvm_type(pointer)),
+		DataType::Array { element, length } => format!("[{} x {}]", length, llvm_type(element)),
+		DataType::Null => "i8*".to_owned(),
+		DataType::Generic { .. } => unreachable!("Generic structs are specialized by the semantic analysis"),
+	}
+}
+
+/// Converts an optional return type into its LLVM IR counterpart, which is `void` if there is no return type.
+fn return_type(return_type: &Option<DataType>) -> String {
+	return_type.as_ref().map(llvm_type).unwrap_or_else(|| "void".to_owned())
+}
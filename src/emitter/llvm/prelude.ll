@@ -0,0 +1,145 @@
+; Builtin functions of FTL
+
+declare i32 @printf(i8*, ...)
+declare i32 @sscanf(i8*, i8*, ...)
+declare i8* @fgets(i8*, i32, i8*)
+@stdin = external global i8*
+declare double @llvm.sqrt.f64(double)
+declare double @llvm.fabs.f64(double)
+declare double @llvm.pow.f64(double, double)
+declare double @llvm.minnum.f64(double, double)
+declare double @llvm.maxnum.f64(double, double)
+
+@ftl.format_int = private unnamed_addr constant [5 x i8] c"%lld\00"
+@ftl.format_float = private unnamed_addr constant [3 x i8] c"%g\00"
+@ftl.format_str = private unnamed_addr constant [3 x i8] c"%s\00"
+@ftl.format_char = private unnamed_addr constant [3 x i8] c"%c\00"
+@ftl.true = private unnamed_addr constant [5 x i8] c"true\00"
+@ftl.false = private unnamed_addr constant [6 x i8] c"false\00"
+
+define internal void @ftl.print_int(i64 %value) {
+	call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([5 x i8], [5 x i8]* @ftl.format_int, i64 0, i64 0), i64 %value)
+	ret void
+}
+
+define internal void @ftl.print_float(double %value) {
+	call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([3 x i8], [3 x i8]* @ftl.format_float, i64 0, i64 0), double %value)
+	ret void
+}
+
+define internal void @ftl.print_str(i8* %value) {
+	call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([3 x i8], [3 x i8]* @ftl.format_str, i64 0, i64 0), i8* %value)
+	ret void
+}
+
+define internal void @ftl.print_bool(i1 %value) {
+	%string = select i1 %value, i8* getelementptr inbounds ([5 x i8], [5 x i8]* @ftl.true, i64 0, i64 0), i8* getelementptr inbounds ([6 x i8], [6 x i8]* @ftl.false, i64 0, i64 0)
+	call void @ftl.print_str(i8* %string)
+	ret void
+}
+
+define internal void @ftl.print_byte(i32 %byte) {
+	call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([3 x i8], [3 x i8]* @ftl.format_char, i64 0, i64 0), i32 %byte)
+	ret void
+}
+
+; Prints the 6 bits of the code point `%char` starting at `%shift` as UTF-8 continuation byte
+define internal void @ftl.print_continuation(i32 %char, i32 %shift) {
+	%shifted = lshr i32 %char, %shift
+	%bits = and i32 %shifted, 63
+	%byte = or i32 %bits, 128
+	call void @ftl.print_byte(i32 %byte)
+	ret void
+}
+
+; Prints the character encoded as UTF-8
+define internal void @ftl.print_char(i32 %char) {
+entry:
+	%is_one_byte = icmp ult i32 %char, 128
+	br i1 %is_one_byte, label %one_byte, label %check_two_bytes
+one_byte:
+	call void @ftl.print_byte(i32 %char)
+	ret void
+check_two_bytes:
+	%is_two_bytes = icmp ult i32 %char, 2048
+	br i1 %is_two_bytes, label %two_bytes, label %check_three_bytes
+two_bytes:
+	%lead_two = lshr i32 %char, 6
+	%byte_two = or i32 %lead_two, 192
+	call void @ftl.print_byte(i32 %byte_two)
+	call void @ftl.print_continuation(i32 %char, i32 0)
+	ret void
+check_three_bytes:
+	%is_three_bytes = icmp ult i32 %char, 65536
+	br i1 %is_three_bytes, label %three_bytes, label %four_bytes
+three_bytes:
+	%lead_three = lshr i32 %char, 12
+	%byte_three = or i32 %lead_three, 224
+	call void @ftl.print_byte(i32 %byte_three)
+	call void @ftl.print_continuation(i32 %char, i32 6)
+	call void @ftl.print_continuation(i32 %char, i32 0)
+	ret void
+four_bytes:
+	%lead_four = lshr i32 %char, 18
+	%byte_four = or i32 %lead_four, 240
+	call void @ftl.print_byte(i32 %byte_four)
+	call void @ftl.print_continuation(i32 %char, i32 12)
+	call void @ftl.print_continuation(i32 %char, i32 6)
+	call void @ftl.print_continuation(i32 %char, i32 0)
+	ret void
+}
+
+; `**` on integers by exponentiation by squaring, wrapping around on overflow. A negative exponent yields the truncated
+; quotient `1 / base ** -exponent`.
+define internal i64 @ftl.int_power(i64 %base, i64 %exponent) {
+entry:
+	%is_negative = icmp slt i64 %exponent, 0
+	br i1 %is_negative, label %negative, label %loop
+negative:
+	%is_one = icmp eq i64 %base, 1
+	%is_minus_one = icmp eq i64 %base, -1
+	%is_odd = trunc i64 %exponent to i1
+	%minus_one_power = select i1 %is_odd, i64 -1, i64 1
+	%one_power = select i1 %is_one, i64 1, i64 0
+	%reciprocal = select i1 %is_minus_one, i64 %minus_one_power, i64 %one_power
+	ret i64 %reciprocal
+loop:
+	%power = phi i64 [1, %entry], [%next_power, %step]
+	%factor = phi i64 [%base, %entry], [%next_factor, %step]
+	%rest = phi i64 [%exponent, %entry], [%next_rest, %step]
+	%done = icmp eq i64 %rest, 0
+	br i1 %done, label %exit, label %step
+step:
+	%bit = trunc i64 %rest to i1
+	%multiplied = mul i64 %power, %factor
+	%next_power = select i1 %bit, i64 %multiplied, i64 %power
+	%next_factor = mul i64 %factor, %factor
+	%next_rest = lshr i64 %rest, 1
+	br label %loop
+exit:
+	ret i64 %power
+}
+
+define internal void @ftl.println() {
+	call void @ftl.print_byte(i32 10)
+	ret void
+}
+
+; Reads a line from stdin and parses it as integer, which is 0 if this fails
+define internal i64 @ftl.read_int() {
+entry:
+	%line = alloca [64 x i8]
+	%value = alloca i64
+	store i64 0, i64* %value
+	%buffer = getelementptr inbounds [64 x i8], [64 x i8]* %line, i64 0, i64 0
+	%stdin = load i8*, i8** @stdin
+	%read = call i8* @fgets(i8* %buffer, i32 64, i8* %stdin)
+	%eof = icmp eq i8* %read, null
+	br i1 %eof, label %done, label %parse
+parse:
+	call i32 (i8*, i8*, ...) @sscanf(i8* %buffer, i8* getelementptr inbounds ([5 x i8], [5 x i8]* @ftl.format_int, i64 0, i64 0), i64* %value)
+	br label %done
+done:
+	%result = load i64, i64* %value
+	ret i64 %result
+}
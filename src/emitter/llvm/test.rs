@@ -0,0 +1,283 @@
+use std::{env, fs, process};
+
+use crate::{builtin, emitter::Emitter as _, error::DiagnosticSink, parser};
+
+/// Tests that names with non-ASCII chars are quoted, since LLVM only allows ASCII letters, digits and a few symbols in
+/// plain names.
+#[test]
+fn test_non_ascii_names() {
+	let source_code = "
+		struct Größe {
+			breite: int
+		}
+		def länge(café: int): int {
+			var g = Größe { breite: café }
+			return g.breite
+		}
+	";
+	let ir = emit(source_code);
+	assert!(ir.contains("%\"Größe\" = type { i64 }"));
+	assert!(ir.contains("define i64 @\"länge\"(i64 %\"café\")"));
+	assert!(ir.contains("%\"café.0\" = alloca i64"));
+	assert!(ir.contains("%g.1 = alloca %\"Größe\""));
+}
+
+/// Tests that the arguments of a function are stored in stack slots, from which they are loaded, and that the
+/// instructions carry the location of their statement.
+#[test]
+fn test_golden_functions() {
+	let source_code = "
+		def add(a: int, b: int): int {
+			return a + b
+		}
+
+		def main(): int {
+			return add(1, 2)
+		}
+	";
+	let ir = emit(source_code);
+	assert_eq!(
+		definitions(&ir),
+		concat!(
+			"define i64 @add(i64 %a, i64 %b) !dbg !3 {\n",
+			"entry:\n",
+			"\t%a.0 = alloca i64\n",
+			"\t%b.1 = alloca i64\n",
+			"\tstore i64 %a, i64* %a.0\n",
+			"\tstore i64 %b, i64* %b.1\n",
+			"\t%.3 = load i64, i64* %a.0, !dbg !4\n",
+			"\t%.4 = load i64, i64* %b.1, !dbg !4\n",
+			"\t%.5 = add i64 %.3, %.4, !dbg !4\n",
+			"\tret i64 %.5, !dbg !4\n",
+			"}\n",
+			"\n",
+			"define i64 @main() !dbg !6 {\n",
+			"entry:\n",
+			"\t%.1 = call i64 @add(i64 1, i64 2), !dbg !7\n",
+			"\tret i64 %.1, !dbg !7\n",
+			"}",
+		)
+	);
+	assert!(ir.contains("!4 = !DILocation(line: 3, column: 4, scope: !3)"));
+	assert!(ir.contains("!7 = !DILocation(line: 7, column: 4, scope: !6)"));
+}
+
+/// Tests that a `while` loop jumps back to its condition, and that `break` jumps to its end.
+#[test]
+fn test_golden_control_flow() {
+	let source_code = "
+		def main(): int {
+			var i = 0
+			while i < 10 {
+				if i = 5 {
+					break
+				}
+				i += 1
+			}
+			return i
+		}
+	";
+	assert_eq!(
+		definitions(&emit(source_code)),
+		concat!(
+			"define i64 @main() !dbg !3 {\n",
+			"entry:\n",
+			"\t%i.0 = alloca i64\n",
+			"\tstore i64 0, i64* %i.0, !dbg !4\n",
+			"\tbr label %bb1, !dbg !5\n",
+			"bb1:\n",
+			"\t%.2 = load i64, i64* %i.0, !dbg !5\n",
+			"\t%.3 = icmp slt i64 %.2, 10, !dbg !5\n",
+			"\tbr i1 %.3, label %bb2, label %bb5, !dbg !5\n",
+			"bb2:\n",
+			"\t%.4 = load i64, i64* %i.0, !dbg !6\n",
+			"\t%.5 = icmp eq i64 %.4, 5, !dbg !6\n",
+			"\tbr i1 %.5, label %bb3, label %bb4, !dbg !6\n",
+			"bb3:\n",
+			"\tbr label %bb5, !dbg !7\n",
+			"bb4:\n",
+			"\t%.6 = load i64, i64* %i.0, !dbg !8\n",
+			"\t%.7 = add i64 %.6, 1, !dbg !8\n",
+			"\tstore i64 %.7, i64* %i.0, !dbg !8\n",
+			"\tbr label %bb1, !dbg !5\n",
+			"bb5:\n",
+			"\t%.8 = load i64, i64* %i.0, !dbg !9\n",
+			"\tret i64 %.8, !dbg !9\n",
+			"}",
+		)
+	);
+}
+
+/// Tests that `**` calls the `pow` intrinsic on floats and the integer power of the prelude on ints, and that floats
+/// are written in hexadecimal, so that they are exact.
+#[test]
+fn test_golden_power() {
+	let source_code = "
+		def area(r: float): float {
+			return 3.5 * r ** 2.0
+		}
+
+		def square(n: int): int {
+			return n ** 2
+		}
+	";
+	assert_eq!(
+		definitions(&emit(source_code)),
+		concat!(
+			"define double @area(double %r) !dbg !3 {\n",
+			"entry:\n",
+			"\t%r.0 = alloca double\n",
+			"\tstore double %r, double* %r.0\n",
+			"\t%.2 = load double, double* %r.0, !dbg !4\n",
+			"\t%.3 = call double @llvm.pow.f64(double %.2, double 0x4000000000000000), !dbg !4\n",
+			"\t%.4 = fmul double 0x400C000000000000, %.3, !dbg !4\n",
+			"\tret double %.4, !dbg !4\n",
+			"}\n",
+			"\n",
+			"define i64 @square(i64 %n) !dbg !6 {\n",
+			"entry:\n",
+			"\t%n.0 = alloca i64\n",
+			"\tstore i64 %n, i64* %n.0\n",
+			"\t%.2 = load i64, i64* %n.0, !dbg !7\n",
+			"\t%.3 = call i64 @ftl.int_power(i64 %.2, i64 2), !dbg !7\n",
+			"\tret i64 %.3, !dbg !7\n",
+			"}",
+		)
+	);
+}
+
+/// Tests that `llc` accepts the IR of programs using structs, arrays, pointers, strings, chars, loops and the builtins.
+/// Skipped if `llc` is not installed.
+#[test]
+fn test_llc_accepts_ir() {
+	if process::Command::new("llc").arg("--version").output().is_err() {
+		eprintln!("Skipping test, because `llc` is not installed");
+		return;
+	}
+	let programs = [
+		(
+			"struct",
+			"
+			struct Point {
+				x: float
+				y: float
+			}
+			def main(): int {
+				var p = Point { x: 1.5, y: 2.0 }
+				p.y = p.x * p.y
+				println(p.y)
+				return p.y as int
+			}
+			",
+		),
+		(
+			"array",
+			"
+			def main(): int {
+				var a = [1, 2, 3]
+				var sum = 0
+				for var i = 0; i < 3; i += 1 {
+					sum += a[i]
+				}
+				return sum
+			}
+			",
+		),
+		(
+			"pointer",
+			"
+			extern calloc(count: int, size: int): ptr Point
+			extern malloc(size: int): ptr ptr bool
+			struct Point {
+				x: float
+				y: float
+			}
+			def main(): int {
+				var p = calloc(1, 16)
+				p.x = 1.5
+				var c = malloc(8)
+				**c = p.x > 1.0 and **c
+				return p.y as int
+			}
+			",
+		),
+		(
+			"builtins",
+			"
+			def main() {
+				println(\"größe\")
+				println('\u{1d11e}')
+				println(true)
+				println((read_int() ** 3))
+			}
+			",
+		),
+	];
+	for (name, source_code) in programs {
+		let path = env::temp_dir().join(format!("fortytwolang_llvm_{}.ll", name));
+		fs::write(&path, emit(source_code)).unwrap();
+		let output = process::Command::new("llc").arg("-filetype=null").arg(&path).output().unwrap();
+		assert!(output.status.success(), "{name}: {}", String::from_utf8_lossy(&output.stderr));
+	}
+}
+
+/// Tests that floats are printed like by the interpreters. Skipped if `llc` or the C compiler `cc` is not installed.
+#[test]
+fn test_print_float() {
+	let installed = |program: &str| process::Command::new(program).arg("--version").output().is_ok();
+	if !installed("llc") || !installed("cc") {
+		eprintln!("Skipping test, because `llc` or `cc` is not installed");
+		return;
+	}
+	let floats = [2.5, -0.0, 1.0 / 3.0, 999999.5, 123456789.0, 0.00001234, 1e100];
+	let prints: Vec<_> = floats.iter().map(|float| format!("\tprintln({:?})\n", float)).collect();
+	let directory = env::temp_dir().join("fortytwolang_llvm_print_float");
+	fs::create_dir_all(&directory).unwrap();
+	fs::write(directory.join("main.ll"), emit(&format!("def main() {{\n{}}}", prints.concat()))).unwrap();
+	let llc = process::Command::new("llc")
+		.args(["main.ll", "--relocation-model=pic", "--dwarf-directory=false", "-o", "main.s"])
+		.current_dir(&directory)
+		.output()
+		.unwrap();
+	assert!(llc.status.success(), "{}", String::from_utf8_lossy(&llc.stderr));
+	let cc = process::Command::new("cc")
+		.args(["main.s", "-o", "main", "-lm"])
+		.current_dir(&directory)
+		.output()
+		.unwrap();
+	assert!(cc.status.success(), "{}", String::from_utf8_lossy(&cc.stderr));
+	let output = process::Command::new(directory.join("main")).output().unwrap();
+	let expected: String = floats.iter().map(|float| format!("{}\n", builtin::format_float(*float))).collect();
+	assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+/// Tests that the debug info maps an instruction of the IR back to the line and column of its FTL statement.
+#[test]
+fn test_source_map() {
+	let source_code = "
+		def main(): int {
+			var x = 6
+			return x * 7
+		}
+	";
+	let ir = emit(source_code);
+	let multiplication = definitions(&ir).lines().find(|line| line.contains(" = mul i64")).unwrap();
+	let (_, location) = multiplication.rsplit_once("!dbg ").unwrap();
+	let location = ir.lines().find_map(|line| line.strip_prefix(&format!("{} = !DILocation(", location))).unwrap();
+	assert!(location.starts_with("line: 4, column: 4,"), "{}", location);
+}
+
+/// Boilerplate code for analyzing source code, which has to be valid, and emitting it as LLVM IR.
+fn emit(source_code: &str) -> String {
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	let mut ir = Vec::new();
+	super::Emitter::codegen(ast_nodes.into_iter(), &mut ir).unwrap();
+	String::from_utf8(ir).unwrap()
+}
+
+/// The function definitions of the `ir`, i.e. the part after the prelude and before the debug info.
+fn definitions(ir: &str) -> &str {
+	let definitions = ir.strip_prefix(super::PRELUDE).unwrap();
+	definitions[..definitions.find("!llvm.dbg.cu").unwrap()].trim()
+}
@@ -1,16 +1,24 @@
 //! Generating a specific target code from AST nodes.
+//!
+//! The [`html`] emitter is an exception, which highlights source code based on its tokens.
 
 mod c;
-mod ftl;
+mod dot;
+pub mod ftl;
+pub mod html;
+mod llvm;
+mod wasm;
 
 pub use c::Emitter as C;
+pub use dot::Emitter as Dot;
 pub use ftl::Emitter as Ftl;
+pub use llvm::Emitter as Llvm;
+pub use wasm::Emitter as Wasm;
 
 /// Generates (target) code from AST nodes.
-pub trait Emitter {
+///
+/// Emitters write many small pieces of code, so the `writer` should be buffered, e.g. with a [`std::io::BufWriter`].
+pub trait Emitter<W: std::io::Write> {
 	/// Generate code from the AST nodes and write it to the `writer`.
-	fn codegen(
-		ast_nodes: impl Iterator<Item = crate::ast::Node>,
-		writer: Box<dyn std::io::Write>,
-	) -> std::io::Result<()>;
+	fn codegen(ast_nodes: impl Iterator<Item = crate::ast::Node>, writer: W) -> std::io::Result<()>;
 }
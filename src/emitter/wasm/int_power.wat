@@ -0,0 +1,57 @@
+	;; `**` on integers by exponentiation by squaring, wrapping around on overflow. A negative exponent yields the
+	;; truncated quotient `1 / base ** -exponent`.
+	(func $ftl.int_power (param $base i64) (param $exponent i64) (result i64)
+		(local $power i64)
+		local.get $exponent
+		i64.const 0
+		i64.lt_s
+		if
+			local.get $base
+			i64.const -1
+			i64.eq
+			if
+				i64.const -1
+				i64.const 1
+				local.get $exponent
+				i64.const 1
+				i64.and
+				i32.wrap_i64
+				select
+				return
+			end
+			local.get $base
+			i64.const 1
+			i64.eq
+			i64.extend_i32_u
+			return
+		end
+		i64.const 1
+		local.set $power
+		block $done
+			loop $square
+				local.get $exponent
+				i64.eqz
+				br_if $done
+				local.get $exponent
+				i64.const 1
+				i64.and
+				i32.wrap_i64
+				if
+					local.get $power
+					local.get $base
+					i64.mul
+					local.set $power
+				end
+				local.get $base
+				local.get $base
+				i64.mul
+				local.set $base
+				local.get $exponent
+				i64.const 1
+				i64.shr_u
+				local.set $exponent
+				br $square
+			end
+		end
+		local.get $power
+	)
@@ -0,0 +1,667 @@
+//! WebAssembly emitter.
+//!
+//! Emits a module in the WebAssembly text format (WAT) from the [IR](crate::ir) of a program, which can be converted
+//! to a binary module with `wat2wasm`. Ints are `i64`, floats are `f64` and pointers are `i32` addresses into the
+//! linear memory. The locals of the IR are WebAssembly locals, except for temporaries which are only used right after
+//! they are computed, and thus stay on the stack. String literals are null-terminated and placed at the
+//! start of the exported linear memory. Extern functions are imported from the `env` module, all other functions are
+//! exported, so they can be called from the host.
+//!
+//! WebAssembly has no jumps, only `block`s, which can be left, and `loop`s, which can be restarted. The basic blocks
+//! of a function are nested like its dominator tree, as described in "Beyond Relooper" by Norman Ramsey: A block
+//! starting a loop is wrapped in a `loop`, and a block which is entered from several blocks before it is placed
+//! after a `block` containing them, so that they can branch to it by leaving the `block`. All other blocks are
+//! emitted where they are entered. This works for every FTL program, since loops in FTL always have a single start.
+//!
+//! The [builtin functions](crate::builtin) are imported from the `ftl` module, which the host has to provide: `print`
+//! calls `print_int(i64)`, `print_float(f64)`, `print_bool(i32)`, `print_char(i32)` with the code point or
+//! `print_str(i32)` with the address of the string, `println` additionally calls `println()` afterwards, and
+//! `read_int` calls `read_int() -> i64`. Floats should be printed like `printf("%g")` in C, see
+//! [`format_float`](crate::builtin::format_float). The math builtins are WebAssembly instructions, which propagate NaN
+//! in `math.min` and `math.max`, except for `math.pow`, which calls `pow(f64, f64) -> f64`. Only the functions used
+//! by the program are imported. `**` calls `pow` for floats and a function of the module for integers.
+
+#[cfg(test)]
+mod test;
+
+use std::{collections::BTreeSet, io, io::Write};
+
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryOperator, UnaryOperator},
+		statement::{BasicDataType, DataType},
+	},
+	builtin::Builtin,
+	interpreter::Value,
+	ir::{self, BlockId, Callee, Instruction, Operand, Projection, Terminator},
+	source::SourcePositionRange,
+};
+
+/// The function implementing `**` on integers, which is only emitted if the program uses it.
+const INT_POWER: &str = include_str!("int_power.wat");
+
+/// Emits WebAssembly text format.
+pub struct Emitter<W> {
+	writer: W,
+	/// Signatures of all functions in the program by their index, so that calls to functions defined later can be
+	/// emitted.
+	functions: Vec<ir::Signature>,
+	/// Signatures of all extern functions in the program by their index.
+	externs: Vec<ir::Signature>,
+	/// Names of the locals of the current function.
+	locals: Vec<String>,
+	/// Whether a local of the current function is a temporary that stays on the stack, see [`stacked_temporaries`].
+	stacked: Vec<bool>,
+	/// Instructions of the current function. They are collected separately, because the locals have to be declared
+	/// at the start of the function.
+	body: Vec<u8>,
+	/// Nesting depth of the current instruction, used for indentation.
+	depth: usize,
+	/// Initial contents of the linear memory, i.e. all string literals.
+	data: Vec<u8>,
+	/// Host functions implementing the used builtins, with the name and the signature of each.
+	builtin_imports: BTreeSet<(&'static str, &'static str)>,
+	/// Definitions of all functions. They are collected separately, because the imports of the used builtins have
+	/// to be placed before them.
+	definitions: Vec<u8>,
+	/// Whether `**` is applied to integers, so that [`INT_POWER`] has to be emitted.
+	uses_int_power: bool,
+}
+
+/// How the basic blocks of a function are nested.
+struct ControlFlow {
+	/// Index of each block in reverse postorder. A branch to a block with a lower or the same index jumps back to the
+	/// start of a loop.
+	numbers: Vec<usize>,
+	/// Whether a block is the start of a loop, i.e. is branched to from a block after it.
+	is_loop_start: Vec<bool>,
+	/// Whether a block is branched to from several blocks before it.
+	is_merge: Vec<bool>,
+	/// The blocks which are immediately dominated by a block and branched to from several blocks before them, with
+	/// the highest index first.
+	merge_children: Vec<Vec<BlockId>>,
+}
+
+impl<W: io::Write> super::Emitter<W> for Emitter<W> {
+	fn codegen(ast_nodes: impl Iterator<Item = ast::Node>, writer: W) -> io::Result<()> {
+		let program = ir::lower(ast_nodes).map_err(io::Error::other)?;
+		Self::emit(&program, writer)
+	}
+}
+
+impl<W: io::Write> Emitter<W> {
+	/// Emits the `program`, which was lowered to the IR, e.g. to run passes over it before.
+	pub fn emit(program: &ir::Program, writer: W) -> io::Result<()> {
+		let mut this = Self {
+			writer,
+			functions: program.functions.iter().map(ir::Function::signature).collect(),
+			externs: program.externs.clone(),
+			locals: Vec::new(),
+			stacked: Vec::new(),
+			body: Vec::new(),
+			depth: 2,
+			data: Vec::new(),
+			builtin_imports: BTreeSet::new(),
+			definitions: Vec::new(),
+			uses_int_power: false,
+		};
+
+		writeln!(this.writer, "(module")?;
+		// Imports have to be placed before all function definitions
+		for extern_ in &program.externs {
+			this.function_import(extern_)?;
+		}
+		for function in &program.functions {
+			this.function(function)?;
+		}
+		for (name, signature) in &this.builtin_imports {
+			writeln!(this.writer, "\t(import \"ftl\" \"{0}\" (func $ftl.{0}{1}))", name, signature)?;
+		}
+		this.writer.write_all(&this.definitions)?;
+		if this.uses_int_power {
+			this.writer.write_all(INT_POWER.as_bytes())?;
+		}
+		this.memory()?;
+		writeln!(this.writer, ")")?;
+		Ok(())
+	}
+}
+
+/// Each of the functions in this impl block is responsible for emitting the corresponding part of the IR.
+impl<W: io::Write> Emitter<W> {
+	/// Emits the linear memory with the string literals, if there are any.
+	fn memory(&mut self) -> io::Result<()> {
+		if self.data.is_empty() {
+			return Ok(());
+		}
+		const PAGE_SIZE: usize = 64 * 1024;
+		writeln!(self.writer, "\t(memory (export \"memory\") {})", self.data.len().div_ceil(PAGE_SIZE))?;
+		write!(self.writer, "\t(data (i32.const 0) \"")?;
+		for &byte in &self.data {
+			match byte {
+				b' '..=b'~' if byte != b'"' && byte != b'\\' => write!(self.writer, "{}", byte as char)?,
+				byte => write!(self.writer, "\\{:02x}", byte)?,
+			}
+		}
+		writeln!(self.writer, "\")")
+	}
+
+	fn function_import(&mut self, signature: &ir::Signature) -> io::Result<()> {
+		write!(self.writer, "\t(import \"env\" \"{}\" (func ${}", signature.name, mangled(&signature.name))?;
+		for arg in &signature.args {
+			write!(self.writer, " (param {})", wasm_type(arg)?)?;
+		}
+		if let Some(return_type) = &signature.return_type {
+			write!(self.writer, " (result {})", wasm_type(return_type)?)?;
+		}
+		writeln!(self.writer, "))")
+	}
+
+	fn function(&mut self, function: &ir::Function) -> io::Result<()> {
+		self.body.clear();
+		// Arguments are params, which keep their name. Other variables get their index appended, since a name can be
+		// declared multiple times in different blocks, and temporaries are only numbered.
+		self.locals = function
+			.locals
+			.iter()
+			.enumerate()
+			.map(|(index, local)| match local.name {
+				Some(name) if index < function.arity => mangled(&name),
+				Some(name) => format!("{}.{}", mangled(&name), index),
+				None => format!(".{}", index),
+			})
+			.collect();
+		self.stacked = stacked_temporaries(function);
+
+		self.tree(function, &ControlFlow::new(function), BlockId(0))?;
+		// A function with return value must not fall off its end
+		if function.return_type.is_some() {
+			self.line("unreachable")?;
+		}
+
+		let unsupported = |err: io::Error| io::Error::other(format!("{}: {}", function.position, err));
+		write!(self.definitions, "\t(func ${} (export \"{}\")", mangled(&function.name), function.name)?;
+		for (name, arg) in self.locals.iter().zip(&function.locals[..function.arity]) {
+			write!(self.definitions, " (param ${} {})", name, wasm_type(&arg.data_type).map_err(unsupported)?)?;
+		}
+		if let Some(return_type) = &function.return_type {
+			write!(self.definitions, " (result {})", wasm_type(return_type).map_err(unsupported)?)?;
+		}
+		writeln!(self.definitions)?;
+		let locals = self.locals.iter().zip(&function.locals).zip(&self.stacked).skip(function.arity);
+		for ((name, local), _) in locals.filter(|(_, &stacked)| !stacked) {
+			writeln!(self.definitions, "\t\t(local ${} {})", name, wasm_type(&local.data_type).map_err(unsupported)?)?;
+		}
+		self.definitions.write_all(&self.body)?;
+		writeln!(self.definitions, "\t)")?;
+		Ok(())
+	}
+
+	/// Emits the `block` and the blocks it dominates, wrapped in a `loop` if the block is the start of a loop.
+	fn tree(&mut self, function: &ir::Function, flow: &ControlFlow, block: BlockId) -> io::Result<()> {
+		let merge_children = &flow.merge_children[block.0];
+		if !flow.is_loop_start[block.0] {
+			return self.within(function, flow, block, merge_children);
+		}
+		self.line(&format!("loop ${}", block))?;
+		self.depth += 1;
+		self.within(function, flow, block, merge_children)?;
+		self.depth -= 1;
+		self.line("end")
+	}
+
+	/// Emits the `block` in `block`s for the `merge_children`, each of which follows the end of its `block`.
+	fn within(
+		&mut self,
+		function: &ir::Function,
+		flow: &ControlFlow,
+		block: BlockId,
+		merge_children: &[BlockId],
+	) -> io::Result<()> {
+		let Some((&merge_child, inner)) = merge_children.split_first() else {
+			return self.basic_block(function, flow, block);
+		};
+		self.line(&format!("block ${}", merge_child))?;
+		self.depth += 1;
+		self.within(function, flow, block, inner)?;
+		self.depth -= 1;
+		self.line("end")?;
+		self.tree(function, flow, merge_child)
+	}
+
+	/// Continues at the `target` after the block `source`. Leaving a `block` or restarting a `loop` needs a `br`,
+	/// other blocks are only entered from the `source` and emitted here.
+	fn branch(
+		&mut self,
+		function: &ir::Function,
+		flow: &ControlFlow,
+		source: BlockId,
+		target: BlockId,
+	) -> io::Result<()> {
+		match is_branched_to(flow, source, target) {
+			true => self.line(&format!("br ${}", target)),
+			false => self.tree(function, flow, target),
+		}
+	}
+
+	fn basic_block(&mut self, function: &ir::Function, flow: &ControlFlow, id: BlockId) -> io::Result<()> {
+		let block = &function.blocks[id.0];
+		for (instruction, position) in block.instructions.iter().zip(&block.positions) {
+			self.instruction(function, instruction, position)?;
+		}
+		match &block.terminator {
+			Terminator::Jump(target) => self.branch(function, flow, id, *target),
+			Terminator::Branch { condition, if_true, if_false } => {
+				self.condition(function, condition)?;
+				if is_branched_to(flow, id, *if_true) {
+					self.line(&format!("br_if ${}", if_true))?;
+					return self.branch(function, flow, id, *if_false);
+				}
+				if is_branched_to(flow, id, *if_false) {
+					self.line("i32.eqz")?;
+					self.line(&format!("br_if ${}", if_false))?;
+					return self.branch(function, flow, id, *if_true);
+				}
+				self.line("if")?;
+				self.depth += 1;
+				self.tree(function, flow, *if_true)?;
+				self.depth -= 1;
+				self.line("else")?;
+				self.depth += 1;
+				self.tree(function, flow, *if_false)?;
+				self.depth -= 1;
+				self.line("end")
+			},
+			Terminator::Return(Some(value)) => {
+				self.value(value)?;
+				self.line("return")
+			},
+			// Falling off the end is fine for functions without return value
+			Terminator::Return(None) => match function.return_type {
+				None => self.line("return"),
+				Some(_) => self.line("unreachable"),
+			},
+		}
+	}
+
+	/// Emits an instruction, which was lowered from the FTL instruction at `position`.
+	fn instruction(
+		&mut self,
+		function: &ir::Function,
+		instruction: &Instruction,
+		position: &SourcePositionRange,
+	) -> io::Result<()> {
+		match instruction {
+			Instruction::Copy { value, .. } => self.value(value)?,
+			Instruction::Binary { operator, lhs, rhs, .. } => self.binary(function, operator, lhs, rhs, position)?,
+			Instruction::Unary { operator, operand, .. } => self.unary(function, *operator, operand)?,
+			Instruction::Convert { data_type, value, .. } => self.convert(function, data_type, value)?,
+			Instruction::Call { dest, callee, args } => {
+				let returns_value = self.call(function, *callee, args)?;
+				match (dest, returns_value) {
+					(Some(_), true) => (),
+					// Remove the unused value from the stack
+					(None, true) => return self.line("drop"),
+					(_, false) => return Ok(()),
+				}
+			},
+			Instruction::MakeStruct { .. } | Instruction::Field { .. } => {
+				return Err(io::Error::other(format!(
+					"{}: Structs are not supported by the WebAssembly emitter",
+					position
+				)))
+			},
+			Instruction::MakeArray { .. } | Instruction::Index { .. } => {
+				return Err(io::Error::other(format!(
+					"{}: Arrays are not supported by the WebAssembly emitter",
+					position
+				)))
+			},
+			Instruction::Store { projections, .. } => {
+				let message = match projections.first() {
+					Some(Projection::Field(_)) => "Structs",
+					Some(Projection::Index(_)) => "Arrays",
+					None => unreachable!("Assignments to a whole variable are copies"),
+				};
+				return Err(io::Error::other(format!(
+					"{}: {} are not supported by the WebAssembly emitter",
+					position, message
+				)));
+			},
+			Instruction::StorePointer { pointer, projections, value } => {
+				let DataType::Pointer(pointee) = function.operand_type(pointer) else {
+					return Err(io::Error::other(format!(
+						"{}: Assignment through {}, which is no pointer",
+						position, pointer
+					)));
+				};
+				if !projections.is_empty() {
+					return Err(io::Error::other(format!(
+						"{}: Assignments to a part of {} are not supported by the WebAssembly emitter",
+						position, pointee.value
+					)));
+				}
+				self.value(pointer)?;
+				self.value(value)?;
+				let (_, store) = memory_access(&pointee.value)?;
+				return self.line(&store);
+			},
+		}
+		let dest = instruction.dest().expect("Instruction with value has a destination");
+		match self.stacked[dest.0] {
+			true => Ok(()),
+			false => self.line(&format!("local.set ${}", self.locals[dest.0])),
+		}
+	}
+
+	fn binary(
+		&mut self,
+		function: &ir::Function,
+		operator: &BinaryOperator,
+		lhs: &Operand,
+		rhs: &Operand,
+		position: &SourcePositionRange,
+	) -> io::Result<()> {
+		// `null` is compared with a pointer, which has the same representation
+		let data_type = match function.operand_type(lhs) {
+			DataType::Null => function.operand_type(rhs),
+			data_type => data_type,
+		};
+		self.value(lhs)?;
+		self.value(rhs)?;
+		let is_float = data_type == DataType::Basic(BasicDataType::Float);
+		if is_float && operator.is_bitwise() {
+			return Err(io::Error::other(format!("{}: Operator {:?} cannot be applied to floats", position, operator)));
+		}
+		if *operator == BinaryOperator::Power {
+			match is_float {
+				true => {
+					self.builtin_imports.insert(("pow", " (param f64 f64) (result f64)"));
+					return self.line("call $ftl.pow");
+				},
+				false => {
+					self.uses_int_power = true;
+					return self.line("call $ftl.int_power");
+				},
+			}
+		}
+
+		let instruction = match (operator, is_float) {
+			(BinaryOperator::Add, _) => "add",
+			(BinaryOperator::Subtract, _) => "sub",
+			(BinaryOperator::Multiply, _) => "mul",
+			(BinaryOperator::Divide, false) => "div_s",
+			(BinaryOperator::Divide, true) => "div",
+			(BinaryOperator::Power, _) => unreachable!("`**` is emitted as call"),
+			(BinaryOperator::BitOr, _) => "or",
+			(BinaryOperator::BitAnd, _) => "and",
+			(BinaryOperator::BitXor, _) => "xor",
+			(BinaryOperator::ShiftLeft, _) => "shl",
+			(BinaryOperator::ShiftRight, _) => "shr_s",
+			(BinaryOperator::Less, false) => "lt_s",
+			(BinaryOperator::Less, true) => "lt",
+			(BinaryOperator::Greater, false) => "gt_s",
+			(BinaryOperator::Greater, true) => "gt",
+			(BinaryOperator::LessEqual, false) => "le_s",
+			(BinaryOperator::LessEqual, true) => "le",
+			(BinaryOperator::GreaterEqual, false) => "ge_s",
+			(BinaryOperator::GreaterEqual, true) => "ge",
+			(BinaryOperator::Equal, _) => "eq",
+			(BinaryOperator::NotEqual, _) => "ne",
+			(BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr, _) => {
+				unreachable!("Logical operators are lowered to branches")
+			},
+		};
+		self.line(&format!("{}.{}", wasm_type(&data_type)?, instruction))
+	}
+
+	fn unary(&mut self, function: &ir::Function, operator: UnaryOperator, operand: &Operand) -> io::Result<()> {
+		self.value(operand)?;
+		match (operator, function.operand_type(operand)) {
+			(UnaryOperator::Negate, DataType::Basic(BasicDataType::Float)) => self.line("f64.neg"),
+			// There is no integer negation, and the operand is already on the stack, so it cannot be subtracted from 0
+			(UnaryOperator::Negate, _) => {
+				self.line("i64.const -1")?;
+				self.line("i64.mul")
+			},
+			(UnaryOperator::Not, _) => self.line("i32.eqz"),
+			(UnaryOperator::Dereference, DataType::Pointer(pointee)) => {
+				let (load, _) = memory_access(&pointee.value)?;
+				self.line(&load)
+			},
+			(UnaryOperator::Dereference, data_type) => {
+				Err(io::Error::other(format!("{} is dereferenced, but is no pointer", data_type)))
+			},
+		}
+	}
+
+	/// Emits a conversion between int and float. Like in the interpreter, floats saturate at the bounds of an int.
+	/// Other conversions, like of an enum to an int, do not change the value.
+	fn convert(&mut self, function: &ir::Function, data_type: &BasicDataType, value: &Operand) -> io::Result<()> {
+		self.value(value)?;
+		match (function.operand_type(value), data_type) {
+			(DataType::Basic(BasicDataType::Int), BasicDataType::Float) => self.line("f64.convert_i64_s"),
+			(DataType::Basic(BasicDataType::Float), BasicDataType::Int) => self.line("i64.trunc_sat_f64_s"),
+			_ => Ok(()),
+		}
+	}
+
+	/// Emits a function call and returns whether it pushes a return value.
+	fn call(&mut self, function: &ir::Function, callee: Callee, args: &[Operand]) -> io::Result<bool> {
+		let signature = match callee {
+			Callee::Function(index) => &self.functions[index],
+			Callee::Extern(index) => &self.externs[index],
+			Callee::Builtin(builtin) => return self.builtin_call(function, builtin, args),
+		};
+		let call = format!("call ${}", mangled(&signature.name));
+		let returns_value = signature.return_type.is_some();
+		for arg in args {
+			self.value(arg)?;
+		}
+		self.line(&call)?;
+		Ok(returns_value)
+	}
+
+	/// Emits the call of a builtin as instruction or call of the host function implementing it.
+	fn builtin_call(&mut self, function: &ir::Function, builtin: Builtin, args: &[Operand]) -> io::Result<bool> {
+		for arg in args {
+			self.value(arg)?;
+		}
+		let (name, signature) = match builtin {
+			Builtin::Sqrt => return self.line("f64.sqrt").map(|()| true),
+			Builtin::Abs => return self.line("f64.abs").map(|()| true),
+			Builtin::Min => return self.line("f64.min").map(|()| true),
+			Builtin::Max => return self.line("f64.max").map(|()| true),
+			Builtin::Pow => ("pow", " (param f64 f64) (result f64)"),
+			Builtin::Print | Builtin::Println => {
+				let arg = args.first().expect("print without argument");
+				match function.operand_type(arg) {
+					DataType::Basic(BasicDataType::Int) => ("print_int", " (param i64)"),
+					DataType::Basic(BasicDataType::Float) => ("print_float", " (param f64)"),
+					DataType::Basic(BasicDataType::Bool) => ("print_bool", " (param i32)"),
+					DataType::Basic(BasicDataType::Str) => ("print_str", " (param i32)"),
+					DataType::Basic(BasicDataType::Char) => ("print_char", " (param i32)"),
+					data_type => return Err(io::Error::other(format!("Cannot print {}", data_type))),
+				}
+			},
+			Builtin::ReadInt => ("read_int", " (result i64)"),
+		};
+		self.builtin_imports.insert((name, signature));
+		self.line(&format!("call $ftl.{}", name))?;
+		if builtin == Builtin::Println {
+			self.builtin_imports.insert(("println", ""));
+			self.line("call $ftl.println")?;
+		}
+		Ok(builtin.return_type().is_some())
+	}
+
+	/// Pushes an `i32`, which is non-zero if the `condition` is true or, for a number, non-zero.
+	fn condition(&mut self, function: &ir::Function, condition: &Operand) -> io::Result<()> {
+		self.value(condition)?;
+		match function.operand_type(condition) {
+			// Booleans already are the `i32` expected by `if` and `br_if`
+			DataType::Basic(BasicDataType::Bool) => Ok(()),
+			DataType::Basic(BasicDataType::Float) => {
+				self.line("f64.const 0")?;
+				self.line("f64.ne")
+			},
+			_ => {
+				self.line("i64.const 0")?;
+				self.line("i64.ne")
+			},
+		}
+	}
+
+	/// Pushes the value of the `operand`.
+	fn value(&mut self, operand: &Operand) -> io::Result<()> {
+		let value = match operand {
+			// The value of a stacked temporary was just pushed
+			Operand::Local(local) if self.stacked[local.0] => return Ok(()),
+			Operand::Local(local) => return self.line(&format!("local.get ${}", self.locals[local.0])),
+			Operand::Constant(value) => value,
+		};
+		match value {
+			Value::Int(int) => self.line(&format!("i64.const {}", int)),
+			// The debug representation round-trips and contains an exponent if needed, like WAT expects
+			Value::Float(float) => self.line(&format!("f64.const {:?}", float)),
+			Value::Bool(boolean) => self.line(&format!("i32.const {}", *boolean as i32)),
+			Value::Char(char) => self.line(&format!("i32.const {}", *char as u32)),
+			Value::Str(string) => {
+				// Push the address of the string in the linear memory
+				self.line(&format!("i32.const {}", self.data.len()))?;
+				self.data.extend_from_slice(string.as_bytes());
+				self.data.push(0);
+				Ok(())
+			},
+			// Pointers are addresses in the linear memory, where `null` is the address 0
+			Value::Null => self.line("i32.const 0"),
+			Value::Struct(_) | Value::Array(_) => unreachable!("Constants are literals"),
+		}
+	}
+
+	/// Writes a single instruction with the indentation of the current nesting depth.
+	fn line(&mut self, instruction: &str) -> io::Result<()> {
+		writeln!(self.body, "{}{}", "\t".repeat(self.depth), instruction)
+	}
+}
+
+impl ControlFlow {
+	fn new(function: &ir::Function) -> Self {
+		let order = function.reverse_postorder();
+		let mut numbers = vec![usize::MAX; function.blocks.len()];
+		for (number, block) in order.iter().enumerate() {
+			numbers[block.0] = number;
+		}
+
+		let mut is_loop_start = vec![false; function.blocks.len()];
+		// Edges instead of blocks are counted, because both targets of a branch can be the same block
+		let mut forward_edges = vec![0; function.blocks.len()];
+		for &block in &order {
+			for successor in function.blocks[block.0].terminator.successors() {
+				match numbers[successor.0] <= numbers[block.0] {
+					true => is_loop_start[successor.0] = true,
+					false => forward_edges[successor.0] += 1,
+				}
+			}
+		}
+		let is_merge: Vec<bool> = forward_edges.into_iter().map(|edges| edges > 1).collect();
+
+		let dominators = function.immediate_dominators();
+		let mut merge_children = vec![Vec::new(); function.blocks.len()];
+		for &block in order.iter().rev() {
+			if let (Some(dominator), true) = (dominators[block.0], is_merge[block.0]) {
+				merge_children[dominator.0].push(block);
+			}
+		}
+		Self { numbers, is_loop_start, is_merge, merge_children }
+	}
+}
+
+/// Finds the temporaries which are assigned and used once, namely as the first operand of the next instruction. Such a
+/// temporary stays on the stack between the two instructions, since each instruction pushes its first operand first.
+fn stacked_temporaries(function: &ir::Function) -> Vec<bool> {
+	let mut assignments = vec![0; function.locals.len()];
+	let mut uses = vec![0; function.locals.len()];
+	for block in &function.blocks {
+		for instruction in &block.instructions {
+			if let Some(dest) = instruction.dest() {
+				assignments[dest.0] += 1;
+			}
+		}
+		let operands = block.instructions.iter().flat_map(Instruction::operands).chain(block.terminator.operand());
+		for operand in operands {
+			if let Operand::Local(local) = operand {
+				uses[local.0] += 1;
+			}
+		}
+	}
+
+	let mut stacked = vec![false; function.locals.len()];
+	for block in &function.blocks {
+		let next_operands = block
+			.instructions
+			.iter()
+			.skip(1)
+			.map(|instruction| instruction.operands().first().copied())
+			.chain([block.terminator.operand()]);
+		for (instruction, next_operand) in block.instructions.iter().zip(next_operands) {
+			if let (Some(dest), Some(&Operand::Local(next_operand))) = (instruction.dest(), next_operand) {
+				let is_temporary = function.locals[dest.0].name.is_none();
+				stacked[dest.0] = is_temporary && dest == next_operand && assignments[dest.0] == 1 && uses[dest.0] == 1;
+			}
+		}
+	}
+	stacked
+}
+
+/// The instructions loading and storing a value of the `data_type` from and to the linear memory. Like in C, a bool
+/// takes a single byte there.
+fn memory_access(data_type: &DataType) -> io::Result<(String, String)> {
+	let type_ = wasm_type(data_type)?;
+	Ok(match data_type {
+		DataType::Basic(BasicDataType::Bool) => (format!("{}.load8_u", type_), format!("{}.store8", type_)),
+		_ => (format!("{}.load", type_), format!("{}.store", type_)),
+	})
+}
+
+/// Whether the `target` is entered from `source` with a `br`, because it is the start of a loop or follows a `block`.
+fn is_branched_to(flow: &ControlFlow, source: BlockId, target: BlockId) -> bool {
+	flow.numbers[target.0] <= flow.numbers[source.0] || flow.is_merge[target.0]
+}
+
+/// Converts a name into a WebAssembly text format identifier, which may only consist of printable ASCII chars. Each
+/// byte of a non-ASCII char is written as `%` with two hex digits, like `caf%C3%A9` for `café`, which cannot collide
+/// with other names, because FTL identifiers do not contain `%`.
+fn mangled(name: &str) -> String {
+	let mut mangled = String::with_capacity(name.len());
+	for char in name.chars() {
+		match char.is_ascii() {
+			true => mangled.push(char),
+			false => {
+				for byte in char.encode_utf8(&mut [0; 4]).bytes() {
+					mangled.push_str(&format!("%{:02X}", byte));
+				}
+			},
+		}
+	}
+	mangled
+}
+
+/// Converts an FTL data type into its WebAssembly counterpart.
+fn wasm_type(data_type: &DataType) -> io::Result<&'static str> {
+	match data_type {
+		DataType::Basic(BasicDataType::Int) | DataType::Enum(_) => Ok("i64"),
+		DataType::Basic(BasicDataType::Float) => Ok("f64"),
+		DataType::Basic(BasicDataType::Bool) => Ok("i32"),
+		DataType::Basic(BasicDataType::Str) => Ok("i32"),
+		DataType::Basic(BasicDataType::Char) => Ok("i32"),
+		DataType::Pointer(_) | DataType::Null => Ok("i32"),
+		DataType::Struct(struct_name) | DataType::Generic { name: struct_name, .. } => {
+			Err(io::Error::other(format!("Struct `{}` is not supported by the WebAssembly emitter", struct_name)))
+		},
+		DataType::Array { .. } => {
+			Err(io::Error::other(format!("Array `{}` is not supported by the WebAssembly emitter", data_type)))
+		},
+	}
+}
@@ -0,0 +1,224 @@
+use crate::{emitter::Emitter as _, error::DiagnosticSink, parser};
+
+/// Tests that names with non-ASCII chars are mangled to ASCII identifiers, while functions are exported by their
+/// original name.
+#[test]
+fn test_non_ascii_names() {
+	let source_code = "
+		def länge(café: int): int {
+			var ö = café
+			return ö
+		}
+		def main(): int {
+			return länge(3)
+		}
+	";
+	let wat = emit(source_code);
+	assert!(wat.contains("(func $l%C3%A4nge (export \"länge\") (param $caf%C3%A9 i64) (result i64)"));
+	assert!(wat.contains("(local $%C3%B6.1 i64)"));
+	assert!(wat.contains("local.set $%C3%B6.1"));
+	assert!(wat.contains("call $l%C3%A4nge"));
+	assert!(wat.lines().filter(|line| !line.contains("export")).all(|line| line.is_ascii()));
+}
+
+/// Tests that the arguments of a function are its params, and that every function is exported.
+#[test]
+fn test_golden_functions() {
+	let source_code = "
+		def add(a: int, b: int): int {
+			return a + b
+		}
+
+		def main(): int {
+			return add(1, 2)
+		}
+	";
+	assert_eq!(
+		emit(source_code),
+		concat!(
+			"(module\n",
+			"\t(func $add (export \"add\") (param $a i64) (param $b i64) (result i64)\n",
+			"\t\tlocal.get $a\n",
+			"\t\tlocal.get $b\n",
+			"\t\ti64.add\n",
+			"\t\treturn\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+			"\t(func $main (export \"main\") (result i64)\n",
+			"\t\ti64.const 1\n",
+			"\t\ti64.const 2\n",
+			"\t\tcall $add\n",
+			"\t\treturn\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+			")\n",
+		)
+	);
+}
+
+/// Tests that variables are declared as locals at the start of the function, and that a `while` loop is a `loop`,
+/// which contains the code after the loop in a `block` left by its condition and by `break`.
+#[test]
+fn test_golden_locals_and_control_flow() {
+	let source_code = "
+		def main(): int {
+			var i = 0
+			var sum = 0.5
+			while i < 10 {
+				if i = 5 {
+					break
+				}
+				sum += 1.0
+				i += 1
+			}
+			return i
+		}
+	";
+	assert_eq!(
+		emit(source_code),
+		concat!(
+			"(module\n",
+			"\t(func $main (export \"main\") (result i64)\n",
+			"\t\t(local $i.0 i64)\n",
+			"\t\t(local $sum.1 f64)\n",
+			"\t\ti64.const 0\n",
+			"\t\tlocal.set $i.0\n",
+			"\t\tf64.const 0.5\n",
+			"\t\tlocal.set $sum.1\n",
+			"\t\tloop $bb1\n",
+			"\t\t\tblock $bb5\n",
+			"\t\t\t\tlocal.get $i.0\n",
+			"\t\t\t\ti64.const 10\n",
+			"\t\t\t\ti64.lt_s\n",
+			"\t\t\t\ti32.eqz\n",
+			"\t\t\t\tbr_if $bb5\n",
+			"\t\t\t\tlocal.get $i.0\n",
+			"\t\t\t\ti64.const 5\n",
+			"\t\t\t\ti64.eq\n",
+			"\t\t\t\tif\n",
+			"\t\t\t\t\tbr $bb5\n",
+			"\t\t\t\telse\n",
+			"\t\t\t\t\tlocal.get $sum.1\n",
+			"\t\t\t\t\tf64.const 1.0\n",
+			"\t\t\t\t\tf64.add\n",
+			"\t\t\t\t\tlocal.set $sum.1\n",
+			"\t\t\t\t\tlocal.get $i.0\n",
+			"\t\t\t\t\ti64.const 1\n",
+			"\t\t\t\t\ti64.add\n",
+			"\t\t\t\t\tlocal.set $i.0\n",
+			"\t\t\t\t\tbr $bb1\n",
+			"\t\t\t\tend\n",
+			"\t\t\tend\n",
+			"\t\t\tlocal.get $i.0\n",
+			"\t\t\treturn\n",
+			"\t\tend\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+			")\n",
+		)
+	);
+}
+
+/// Tests that the code after an `if` follows a `block`, which both branches leave, and that temporaries which are
+/// used in other blocks are locals.
+#[test]
+fn test_golden_merge() {
+	let source_code = "
+		def main(a: int, b: int): int {
+			var m = a
+			if b > a {
+				m = b
+			} else {
+				println(a)
+			}
+			return m
+		}
+	";
+	let wat = emit(source_code);
+	let function = &wat[wat.find("\t(func").unwrap()..];
+	assert_eq!(
+		function,
+		concat!(
+			"\t(func $main (export \"main\") (param $a i64) (param $b i64) (result i64)\n",
+			"\t\t(local $m.2 i64)\n",
+			"\t\tblock $bb3\n",
+			"\t\t\tlocal.get $a\n",
+			"\t\t\tlocal.set $m.2\n",
+			"\t\t\tlocal.get $b\n",
+			"\t\t\tlocal.get $a\n",
+			"\t\t\ti64.gt_s\n",
+			"\t\t\tif\n",
+			"\t\t\t\tlocal.get $b\n",
+			"\t\t\t\tlocal.set $m.2\n",
+			"\t\t\t\tbr $bb3\n",
+			"\t\t\telse\n",
+			"\t\t\t\tlocal.get $a\n",
+			"\t\t\t\tcall $ftl.print_int\n",
+			"\t\t\t\tcall $ftl.println\n",
+			"\t\t\t\tbr $bb3\n",
+			"\t\t\tend\n",
+			"\t\tend\n",
+			"\t\tlocal.get $m.2\n",
+			"\t\treturn\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+			")\n",
+		)
+	);
+	let if_expression = emit("def main(a: int): int {\n\treturn if a < 0 { 0 } else { a }\n}");
+	assert!(if_expression.contains("(local $.2 i64)"), "{}", if_expression);
+}
+
+/// Tests that `**` calls the imported `pow` on floats and the integer power, which is only emitted if it is used, on
+/// ints.
+#[test]
+fn test_golden_power() {
+	let source_code = "
+		def area(r: float): float {
+			return 3.5 * r ** 2.0
+		}
+
+		def square(n: int): int {
+			return n ** 2
+		}
+	";
+	let wat = emit(source_code);
+	let (functions, int_power) = wat.split_once("\t;; `**` on integers").unwrap();
+	assert_eq!(
+		functions,
+		concat!(
+			"(module\n",
+			"\t(import \"ftl\" \"pow\" (func $ftl.pow (param f64 f64) (result f64)))\n",
+			"\t(func $area (export \"area\") (param $r f64) (result f64)\n",
+			"\t\t(local $.1 f64)\n",
+			"\t\tlocal.get $r\n",
+			"\t\tf64.const 2.0\n",
+			"\t\tcall $ftl.pow\n",
+			"\t\tlocal.set $.1\n",
+			"\t\tf64.const 3.5\n",
+			"\t\tlocal.get $.1\n",
+			"\t\tf64.mul\n",
+			"\t\treturn\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+			"\t(func $square (export \"square\") (param $n i64) (result i64)\n",
+			"\t\tlocal.get $n\n",
+			"\t\ti64.const 2\n",
+			"\t\tcall $ftl.int_power\n",
+			"\t\treturn\n",
+			"\t\tunreachable\n",
+			"\t)\n",
+		)
+	);
+	assert!(int_power.contains("(func $ftl.int_power (param $base i64) (param $exponent i64) (result i64)"));
+	assert!(!emit("def main(): float {\n\treturn 2.0 ** 3.0\n}").contains("$ftl.int_power"));
+}
+
+/// Boilerplate code for analyzing source code, which has to be valid, and emitting it as WebAssembly text format.
+fn emit(source_code: &str) -> String {
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	let mut wat = Vec::new();
+	super::Emitter::codegen(ast_nodes.into_iter(), &mut wat).unwrap();
+	String::from_utf8(wat).unwrap()
+}
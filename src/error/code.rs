@@ -0,0 +1,715 @@
+//! Stable codes for all errors and warnings, like rustc's `E0425`.
+//!
+//! The codes are grouped by the stage that reports them: `E00xx` for syntax errors of the lexer and the parser,
+//! `E01xx` for semantic errors, `E02xx` for errors at runtime, `E03xx` for features a backend does not support and
+//! `E04xx` for errors while loading imported modules.
+//! An error which can be found in several stages, like an undeclared variable, has the same code in all of them.
+//! Warnings are numbered in the same way, but start with `W` instead of `E`.
+//! Codes must never be reused for a different error, even if the old error does not exist anymore.
+
+use std::fmt;
+
+#[cfg(feature = "jit")]
+use crate::jit;
+use crate::{bytecode, interpreter, ir, lexer, module, parser, semantic_analyzer};
+
+/// A stable code for a kind of error, with a description of the error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+	/// The code itself, like `E0102`.
+	pub code: &'static str,
+	/// A short summary of the error, like `undefined variable`.
+	pub title: &'static str,
+	/// A longer description of the error and how to fix it.
+	pub explanation: &'static str,
+}
+
+impl fmt::Display for ErrorCode {
+	/// Formats the code with its title, like `E0102: undefined variable`.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.code, self.title)
+	}
+}
+
+/// Errors that have a stable [code](ErrorCode).
+pub trait Code {
+	/// The code of this error, which is one of the [`ERROR_CODES`].
+	fn code(&self) -> &'static str;
+}
+
+/// Looks up the description of an error code, ignoring its case.
+///
+/// ```
+/// use fortytwolang::error::explain;
+///
+/// assert_eq!(explain("e0102").unwrap().title, "undefined variable");
+/// assert_eq!(explain("E9999"), None);
+/// ```
+pub fn explain(code: &str) -> Option<&'static ErrorCode> {
+	ERROR_CODES.iter().find(|error_code| error_code.code.eq_ignore_ascii_case(code))
+}
+
+/// All error and warning codes, sorted by their code.
+pub const ERROR_CODES: &[ErrorCode] = &[
+	ErrorCode {
+		code: "E0001",
+		title: "unknown token",
+		explanation: "The lexer found a character which does not start any token, like `$` or `@`.\n\nRemove the \
+		              character, or put it into a string or character literal if it is part of some text.",
+	},
+	ErrorCode {
+		code: "E0002",
+		title: "illegal symbol",
+		explanation: "A symbol appeared where it is not allowed, e.g. the code ended in the middle of a token like \
+		              `<`.\n\nComplete the token or remove it.",
+	},
+	ErrorCode {
+		code: "E0003",
+		title: "invalid number literal",
+		explanation: "A number literal could not be parsed, e.g. because it is too large for its type or contains \
+		              several decimal points like `1.2.3`.\n\nIntegers are 64 bit signed integers, so they have to be \
+		              between -9223372036854775808 and 9223372036854775807. Floats are 64 bit floating point \
+		              numbers, so their magnitude has to be below about `1.8e308`.",
+	},
+	ErrorCode {
+		code: "E0004",
+		title: "invalid digit for the base",
+		explanation: "A number literal with a base prefix contains a digit that is not allowed in that base, like \
+		              `0b102` or `0o8`.\n\nBinary literals (`0b`) may only contain `0` and `1`, octal literals (`0o`) \
+		              `0` to `7` and hexadecimal literals (`0x`) `0` to `9` and `a` to `f`.",
+	},
+	ErrorCode {
+		code: "E0005",
+		title: "misplaced underscore in number",
+		explanation: "Underscores in number literals separate groups of digits, like in `1_000_000`, so they are only \
+		              allowed between two digits.\n\nRemove underscores at the start or the end of the number and \
+		              next to the decimal point, like in `1_.5` or `1._5`.",
+	},
+	ErrorCode {
+		code: "E0006",
+		title: "unterminated string literal",
+		explanation: "A string literal has no closing `\"`.\n\nAdd the missing `\"` at the end of the string. A `\"` \
+		              inside of a string has to be escaped as `\\\"`.",
+	},
+	ErrorCode {
+		code: "E0007",
+		title: "invalid character literal",
+		explanation: "A character literal has to contain exactly one character, like `'a'` or the escape sequence \
+		              `'\\n'`, and has to end with `'` in the same line.\n\nUse a string literal like `\"abc\"` for \
+		              several characters.",
+	},
+	ErrorCode {
+		code: "E0008",
+		title: "unterminated block comment",
+		explanation: "A block comment started with `/*` is not closed by `*/`. Block comments nest, so every `/*` \
+		              inside of the comment has to be closed by its own `*/`.\n\nAdd the missing `*/`.",
+	},
+	ErrorCode {
+		code: "E0009",
+		title: "identifier starts with a digit",
+		explanation: "An identifier starts with a digit, like `2nd`. Identifiers start with a letter or `_`, followed \
+		              by letters, digits and `_`. Letters of all scripts are allowed, like in `größe` or `λ`.\n\n\
+		              Rename the identifier, e.g. `2nd` to `second` or `_2nd`.",
+	},
+	ErrorCode {
+		code: "E0020",
+		title: "expected token",
+		explanation: "The parser expected a specific token, like the `)` closing the parameters of a function, but \
+		              found a different one.\n\nAdd the expected token, or check the code before for a typo.",
+	},
+	ErrorCode {
+		code: "E0021",
+		title: "illegal token",
+		explanation: "A token appeared where it cannot be used, like an operator without a second operand in \
+		              `return 1 +`.\n\nRemove the token or complete the construct it belongs to.",
+	},
+	ErrorCode {
+		code: "E0022",
+		title: "nesting too deep",
+		explanation: "Expressions or blocks are nested more than 100 levels deep, like in `((((...))))`, which the \
+		              parser does not support.\n\nMove parts of the nested code into variables or functions.",
+	},
+	ErrorCode {
+		code: "E0100",
+		title: "redeclaration",
+		explanation: "A variable, constant or parameter with the same name is already declared in this \
+		              scope.\n\nRename one of them, or assign to the existing variable with `x = ...` instead of \
+		              declaring it again with `var`.",
+	},
+	ErrorCode {
+		code: "E0101",
+		title: "assignment to constant",
+		explanation: "Constants declared with `const` cannot be changed after their declaration, neither as a whole \
+		              nor element by element.\n\nDeclare the value with `var` if it has to change.",
+	},
+	ErrorCode {
+		code: "E0102",
+		title: "undefined variable",
+		explanation: "A variable is used which is not declared, or not visible at this point. Variables are only \
+		              visible in the block they are declared in and after their declaration:\n\n    if true {\n        \
+		              var x = 1\n    }\n    return x  # `x` is not visible here\n\nDeclare the variable with `var` \
+		              before using it, or check the name for typos.",
+	},
+	ErrorCode {
+		code: "E0103",
+		title: "value of constant is not constant",
+		explanation: "The value of a constant has to be known at compile time, so it may only consist of literals, \
+		              other constants and operators, but not of variables or function calls.\n\nDeclare the value \
+		              with `var` if it has to be computed at runtime.",
+	},
+	ErrorCode {
+		code: "E0104",
+		title: "unknown struct",
+		explanation: "A data type names a struct that is not declared.\n\nDeclare the struct with `struct Name { ... \
+		              }`, or check the name for typos.",
+	},
+	ErrorCode {
+		code: "E0105",
+		title: "field access on non-struct",
+		explanation: "A field is accessed with `.` on a value which is not a struct, like an `int`.\n\nOnly structs \
+		              have fields.",
+	},
+	ErrorCode {
+		code: "E0106",
+		title: "unknown field",
+		explanation: "A struct has no field with the accessed name.\n\nCheck the declaration of the struct for the \
+		              names of its fields.",
+	},
+	ErrorCode {
+		code: "E0107",
+		title: "missing field in struct literal",
+		explanation: "A struct literal like `Point { x: 1, y: 2 }` has to give a value to every field of the \
+		              struct.\n\nAdd the missing field to the literal.",
+	},
+	ErrorCode {
+		code: "E0108",
+		title: "duplicate field in struct literal",
+		explanation: "A struct literal gives more than one value to the same field.\n\nRemove all but one of the \
+		              values.",
+	},
+	ErrorCode {
+		code: "E0109",
+		title: "index into non-array",
+		explanation: "A value is indexed with `[...]`, but it is not an array.\n\nOnly arrays can be indexed.",
+	},
+	ErrorCode {
+		code: "E0110",
+		title: "empty array literal",
+		explanation: "The type of the elements of an empty array literal `[]` cannot be inferred.\n\nArrays have to \
+		              contain at least one element.",
+	},
+	ErrorCode {
+		code: "E0111",
+		title: "type mismatch",
+		explanation: "A value has a different type than expected, e.g. a `float` is passed to a parameter of type \
+		              `int`, or the condition of an `if` is not a `bool`.\n\nThere are no implicit conversions, so \
+		              write the value with the right type, like `2.0` instead of `2` for a `float`.",
+	},
+	ErrorCode {
+		code: "E0112",
+		title: "undefined function",
+		explanation: "A function is called which is neither defined with `def` nor declared with `extern`.\n\nDefine \
+		              the function, or check the name for typos.",
+	},
+	ErrorCode {
+		code: "E0113",
+		title: "wrong number of arguments",
+		explanation: "A function is called with a different number of arguments than it has parameters.\n\nPass \
+		              exactly one argument for every parameter of the function.",
+	},
+	ErrorCode {
+		code: "E0114",
+		title: "missing return value",
+		explanation: "The value of a function call is used, but the function has no return value, or did not return \
+		              one.\n\nGive the function a return type and return a value in every case, or call it as an \
+		              instruction on its own.",
+	},
+	ErrorCode {
+		code: "E0115",
+		title: "return without value",
+		explanation: "A function with a return type has to return a value of that type, so a bare `return` is not \
+		              allowed in it.\n\nReturn a value, like `return 0`.",
+	},
+	ErrorCode {
+		code: "E0116",
+		title: "unexpected return value",
+		explanation: "A function without a return type returns a value.\n\nAdd a return type to the function, like \
+		              `def f(): int`, or use a bare `return`.",
+	},
+	ErrorCode {
+		code: "E0117",
+		title: "invalid operand",
+		explanation: "A binary operator is applied to a type it does not support, e.g. `+` to booleans, `and` to \
+		              integers or `<<` to floats.\n\nArithmetic operators work on `int` and `float`, bitwise operators \
+		              only on `int`, logical operators only on `bool`, and characters can only be compared.",
+	},
+	ErrorCode {
+		code: "E0118",
+		title: "invalid unary operand",
+		explanation: "A unary operator is applied to a type it does not support.\n\n`-` negates numbers and `!` \
+		              negates booleans.",
+	},
+	ErrorCode {
+		code: "E0119",
+		title: "missing return",
+		explanation: "A function with a return type can reach the end of its body without a `return`, so it would \
+		              not return a value in this case:\n\n    def sign(x: int): int {\n        if x < 0 {\n            \
+		              return -1\n        }\n    }  # nothing is returned for x >= 0\n\nAdd a `return` at the end of \
+		              the function, or an `else` block that returns, too. A `while true` loop without `break` never \
+		              ends, so nothing has to be returned after it.",
+	},
+	ErrorCode {
+		code: "E0120",
+		title: "duplicate function",
+		explanation: "Two functions, or a function and an `extern` declaration, have the same name. Functions cannot \
+		              be overloaded, so each name may only be defined once.\n\nRename one of the functions.",
+	},
+	ErrorCode {
+		code: "E0121",
+		title: "duplicate struct",
+		explanation: "Two structs have the same name, so it is unclear which one a type refers to.\n\nRename one of \
+		              the structs.",
+	},
+	ErrorCode {
+		code: "E0122",
+		title: "duplicate struct field",
+		explanation: "A struct definition contains two fields with the same name:\n\n    struct Point {\n        x: \
+		              int\n        x: int  # should be `y`\n    }\n\nRename or remove one of the fields.",
+	},
+	ErrorCode {
+		code: "E0123",
+		title: "invalid cast",
+		explanation: "An `as` cast converts a value to a type it cannot be converted to.\n\nOnly numbers can be \
+		              cast, i.e. an `int` to a `float` and vice versa:\n\n    var half = (n as float) / 2.0\n\nA \
+		              `float` is rounded towards zero when cast to an `int`. A variant of an enum can be cast to an \
+		              `int`, which is its index, but not the other way round.",
+	},
+	ErrorCode {
+		code: "E0124",
+		title: "untyped null",
+		explanation: "A variable without type annotation is initialized with `null`. `null` is a value of every \
+		              pointer type, so the type of the variable cannot be inferred from it.\n\nAnnotate the pointer \
+		              type of the variable:\n\n    var next: ptr Node = null",
+	},
+	ErrorCode {
+		code: "E0125",
+		title: "redefinition of builtin function",
+		explanation: "A function or extern function has the name of a builtin function like `print`, which can be \
+		              called without declaring it.\n\nRename the function.",
+	},
+	ErrorCode {
+		code: "E0126",
+		title: "invalid argument of builtin function",
+		explanation: "A builtin function is called with a value it does not support. `print` and `println` only \
+		              accept values of the basic types `int`, `float`, `bool`, `char` and `str`.\n\nPrint the fields \
+		              of a struct or the elements of an array one by one.",
+	},
+	ErrorCode {
+		code: "E0127",
+		title: "non-constant pattern",
+		explanation: "A pattern of a `match` arm is not known at compile time. Patterns may only consist of literals, \
+		              constants and operators, like `1`, `MAX` or `MAX - 1`.\n\nCompare with variables or function \
+		              calls in an `if` instead:\n\n    if x = limit() {\n        ...\n    }",
+	},
+	ErrorCode {
+		code: "E0128",
+		title: "duplicate pattern",
+		explanation: "Two patterns of a `match` have the same value, so the arm of the second one is never executed \
+		              for it:\n\n    match x {\n        1, 2 => { ... }\n        2 => { ... }  # never executed\n    \
+		              }\n\nRemove one of the patterns.",
+	},
+	ErrorCode {
+		code: "E0129",
+		title: "missing default arm",
+		explanation: "A `match` has no default arm `_`. The patterns of the other arms cannot cover all integers, so \
+		              a default arm is required, which is executed if no pattern matches:\n\n    match x {\n        \
+		              0 => { ... }\n        _ => {}\n    }\n\nThe default arm has to be the last arm.",
+	},
+	ErrorCode {
+		code: "E0130",
+		title: "duplicate enum",
+		explanation: "An enum has the same name as another enum or a struct, so it is unclear which one a type refers \
+		              to.\n\nRename one of them.",
+	},
+	ErrorCode {
+		code: "E0131",
+		title: "duplicate variant",
+		explanation: "An enum definition contains two variants with the same name:\n\n    enum Color { Red, Red \
+		              }\n\nRename or remove one of the variants.",
+	},
+	ErrorCode {
+		code: "E0132",
+		title: "unknown variant",
+		explanation: "A variant like `Color.Purple` is accessed, but the enum has no variant with this \
+		              name.\n\nCheck the definition of the enum for the names of its variants.",
+	},
+	ErrorCode {
+		code: "E0133",
+		title: "missing variants",
+		explanation: "A `match` over an enum has neither an arm for every variant nor a default arm `_`, so no arm \
+		              is executed for the missing variants:\n\n    enum Color { Red, Green, Blue }\n    match color \
+		              {\n        Color.Red, Color.Green => { ... }\n    }\n\nAdd arms for the missing variants, or \
+		              a default arm.",
+	},
+	ErrorCode {
+		code: "E0134",
+		title: "duplicate type alias",
+		explanation: "A type alias has the same name as a struct, an enum or another type alias, so it is unclear \
+		              which type the name refers to.\n\nRename one of them.",
+	},
+	ErrorCode {
+		code: "E0135",
+		title: "recursive type alias",
+		explanation: "A type alias stands for a type which contains the alias itself, directly or via other \
+		              aliases:\n\n    type List = ptr List\n\nAn alias is only another name for a type, so it \
+		              would never end. Use a struct for recursive types:\n\n    struct List {\n        next: ptr \
+		              List\n    }",
+	},
+	ErrorCode {
+		code: "E0136",
+		title: "uninferred type parameter",
+		explanation: "A generic function is called, but a type parameter appears in none of the types of its \
+		              arguments, so the type it stands for is unknown:\n\n    def zero<T>(): T { ... }\n    var x \
+		              = zero()\n\nThe type arguments of a call are inferred from its parameters, and those of a \
+		              struct literal from its fields. Use a type parameter in the types of the arguments or \
+		              fields.",
+	},
+	ErrorCode {
+		code: "E0137",
+		title: "too many specializations",
+		explanation: "A generic function or struct is specialized for each combination of type arguments it is \
+		              used with. There is a limit on their number, which is reached if the function calls itself \
+		              with a larger type than its type parameter:\n\n    def f<T>(x: T, n: int) {\n        \
+		              f([x, x], n - 1)\n    }\n\nEach specialization would require another one, so that \
+		              there would be infinitely many.",
+	},
+	ErrorCode {
+		code: "E0138",
+		title: "type argument count mismatch",
+		explanation: "A struct is used with another number of type arguments than it has type \
+		              parameters:\n\n    struct Pair<A, B> { ... }\n    var p: Pair<int> = ...\n\nGive a type \
+		              argument for each type parameter, and none for a struct which is not generic.",
+	},
+	ErrorCode {
+		code: "E0139",
+		title: "unknown method",
+		explanation: "A method is called on a value whose struct has no method of that name:\n\n    struct Point { \
+		              ... }\n    p.length()\n\nThe method `length` of `p` is the function `Point.length`. Define it \
+		              like `def Point.length(self: Point): float { ... }`, or call a method that exists.",
+	},
+	ErrorCode {
+		code: "E0140",
+		title: "invalid method receiver",
+		explanation: "A function is named like a method of a struct, as in `def Point.length(...)`, but its first \
+		              argument is no value of the struct or pointer to it. The receiver of a call like `p.length()` \
+		              is passed as first argument, so it has to have the type of the struct:\n\n    def \
+		              Point.length(self: Point): float { ... }",
+	},
+	ErrorCode {
+		code: "E0200",
+		title: "missing main function",
+		explanation: "The program has no `main()` function, where its execution starts.\n\nDefine a function `def \
+		              main()` without parameters.",
+	},
+	ErrorCode {
+		code: "E0201",
+		title: "operands of different types",
+		explanation: "A binary operator is applied at runtime to two values that it cannot combine, like an `int` and \
+		              a `float`.\n\nThe type checker reports this before the execution in most cases.",
+	},
+	ErrorCode {
+		code: "E0202",
+		title: "invalid operand at runtime",
+		explanation: "An operator is applied at runtime to a value that it does not support.\n\nThe type checker \
+		              reports this before the execution in most cases.",
+	},
+	ErrorCode {
+		code: "E0203",
+		title: "index is not an integer",
+		explanation: "An array is indexed with a value which is not an `int`.\n\nIndices have to be integers.",
+	},
+	ErrorCode {
+		code: "E0204",
+		title: "index out of bounds",
+		explanation: "An array is indexed with a negative index or one that is not smaller than the length of the \
+		              array.\n\nThe first element of an array has index 0, and the last one the length minus one.",
+	},
+	ErrorCode {
+		code: "E0205",
+		title: "division by zero",
+		explanation: "An integer is divided by zero, which has no result.\n\nCheck that the divisor is not zero \
+		              before dividing.",
+	},
+	ErrorCode {
+		code: "E0206",
+		title: "null pointer dereference",
+		explanation: "The value a pointer points to is read or assigned, but the pointer is `null`, which points to \
+		              nothing.\n\nCheck that the pointer is not `null` before dereferencing it. The interpreter \
+		              cannot create other pointers, so use a compiled target for programs working with pointers.",
+	},
+	ErrorCode {
+		code: "E0300",
+		title: "structs unsupported by backend",
+		explanation: "The bytecode compiler and the JIT do not support structs yet.\n\nUse the AST interpreter or \
+		              compile the program to an executable instead.",
+	},
+	ErrorCode {
+		code: "E0301",
+		title: "arrays unsupported by backend",
+		explanation:
+			"The JIT does not support arrays yet.\n\nUse one of the interpreters or compile the program to an \
+		              executable instead.",
+	},
+	ErrorCode {
+		code: "E0302",
+		title: "data type unsupported by backend",
+		explanation: "The JIT does not support the data type, e.g. strings.\n\nUse one of the interpreters or compile \
+		              the program to an executable instead.",
+	},
+	ErrorCode {
+		code: "E0303",
+		title: "unsupported host",
+		explanation: "The JIT cannot generate native code for the machine it runs on.\n\nUse one of the interpreters \
+		              instead.",
+	},
+	ErrorCode {
+		code: "E0304",
+		title: "native code generation failed",
+		explanation: "The JIT failed to generate or link native code for the program.\n\nThis is a bug in the JIT, so \
+		              please report it together with the program.",
+	},
+	ErrorCode {
+		code: "E0305",
+		title: "extern function unsupported by interpreter",
+		explanation: "An extern function is called, but the interpreter has no implementation of the C function with \
+		              these arguments. The interpreter implements `putchar(c: int): int`, `getchar(): int`, \
+		              `puts(s: str): int` and `abs(x: int): int`.\n\nCompile the program to an executable instead, \
+		              which can call all C functions.",
+	},
+	ErrorCode {
+		code: "E0306",
+		title: "extern function unsupported by bytecode compiler",
+		explanation: "An extern function is called, but the bytecode VM cannot call C functions.\n\nUse the AST \
+		              interpreter or compile the program to an executable instead.",
+	},
+	ErrorCode {
+		code: "E0307",
+		title: "dereference unsupported by backend",
+		explanation: "A pointer is dereferenced, but neither the bytecode VM nor the JIT has memory that pointers \
+		              could point to.\n\nCompile the program to an executable instead.",
+	},
+	ErrorCode {
+		code: "E0400",
+		title: "module not found",
+		explanation: "A module is imported, but there is no source file for it. `import math` loads the file \
+		              `math.ftl` in the same directory as the importing file.\n\nCheck the name of the module, or \
+		              create the file.",
+	},
+	ErrorCode {
+		code: "W0001",
+		title: "leading zero in decimal number",
+		explanation: "A decimal integer starts with a zero, like `0755`. In other languages, this is an octal number, \
+		              but here it is the decimal number 755.\n\nRemove the leading zeros, or write `0o755` for an \
+		              octal number.",
+	},
+	ErrorCode {
+		code: "W0020",
+		title: "unreachable code",
+		explanation: "Instructions follow a `return`, `break` or `continue` in the same block, so they are never \
+		              executed.\n\nRemove the instructions, or move them before the `return`, `break` or `continue`.",
+	},
+	ErrorCode {
+		code: "W0021",
+		title: "branch never taken",
+		explanation: "The condition of an `if` or `while` is always `false`, or the condition of an `if` with an \
+		              `else` block is always `true`, because it only consists of literals, constants and operators. \
+		              So one of the blocks is never executed:\n\n    const DEBUG = false\n    if DEBUG {\n        \
+		              print(1)  # never executed\n    }\n\nRemove the block that is never executed, or use a \
+		              variable in the condition.",
+	},
+	ErrorCode {
+		code: "W0100",
+		title: "unused variable",
+		explanation: "A local variable or constant is declared, but its value is never read. Assigning to a \
+		              variable does not count as reading it.\n\nRemove the variable, or prefix its name with `_`, like \
+		              `_x`, if it is unused on purpose.",
+	},
+	ErrorCode {
+		code: "W0101",
+		title: "unused parameter",
+		explanation: "A parameter of a function is never read in its body, so the value passed by the callers is \
+		              ignored.\n\nRemove the parameter, or prefix its name with `_`, like `_x`, if the function has to \
+		              accept it, e.g. to match the parameters of other functions.",
+	},
+];
+
+impl Code for lexer::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			lexer::Error::UnknownSymbol(_) => "E0001",
+			lexer::Error::IllegalSymbol(_) => "E0002",
+			lexer::Error::ParseNumberError(_) => "E0003",
+			lexer::Error::InvalidDigit { .. } => "E0004",
+			lexer::Error::MisplacedUnderscore(_) => "E0005",
+			lexer::Error::UnterminatedStringLiteral(_) => "E0006",
+			lexer::Error::InvalidCharLiteral(_) => "E0007",
+			lexer::Error::UnterminatedBlockComment(_) => "E0008",
+			lexer::Error::LeadingDigit(_) => "E0009",
+		}
+	}
+}
+
+impl Code for parser::Error {
+	/// The code of the error, or of the first error for [multiple errors](parser::Error::Multiple).
+	fn code(&self) -> &'static str {
+		match self {
+			parser::Error::ExpectedToken { .. } => "E0020",
+			parser::Error::IllegalToken { .. } => "E0021",
+			parser::Error::NestingTooDeep { .. } => "E0022",
+			parser::Error::Lexer(error) => error.code(),
+			parser::Error::Multiple(errors) => errors.first().map_or("E0021", Code::code),
+		}
+	}
+}
+
+impl Code for module::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			module::Error::NotFound { .. } => "E0400",
+		}
+	}
+}
+
+impl Code for semantic_analyzer::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			semantic_analyzer::Error::Redeclaration { .. } => "E0100",
+			semantic_analyzer::Error::AssignmentToConstant { .. } => "E0101",
+			semantic_analyzer::Error::UndeclaredVariable { .. } => "E0102",
+			semantic_analyzer::Error::NotConstant { .. } => "E0103",
+			semantic_analyzer::Error::UnknownStruct { .. } => "E0104",
+			semantic_analyzer::Error::NoStruct { .. } => "E0105",
+			semantic_analyzer::Error::UnknownField { .. } => "E0106",
+			semantic_analyzer::Error::MissingField { .. } => "E0107",
+			semantic_analyzer::Error::DuplicateField { .. } => "E0108",
+			semantic_analyzer::Error::NoArray { .. } => "E0109",
+			semantic_analyzer::Error::EmptyArrayLiteral { .. } => "E0110",
+			semantic_analyzer::Error::TypeMismatch { .. }
+			| semantic_analyzer::Error::ArgumentTypeMismatch { .. }
+			| semantic_analyzer::Error::AssignmentTypeMismatch { .. } => "E0111",
+			semantic_analyzer::Error::UndefinedFunctionCall { .. } => "E0112",
+			semantic_analyzer::Error::ArgumentCountMismatch { .. } => "E0113",
+			semantic_analyzer::Error::MissingReturnValue { .. } => "E0114",
+			semantic_analyzer::Error::ReturnWithoutValue { .. } => "E0115",
+			semantic_analyzer::Error::UnexpectedReturnValue { .. } => "E0116",
+			semantic_analyzer::Error::InvalidOperand { .. } => "E0117",
+			semantic_analyzer::Error::InvalidUnaryOperand { .. } => "E0118",
+			semantic_analyzer::Error::MissingReturn { .. } => "E0119",
+			semantic_analyzer::Error::DuplicateFunction { .. } => "E0120",
+			semantic_analyzer::Error::DuplicateStruct { .. } => "E0121",
+			semantic_analyzer::Error::DuplicateStructField { .. } => "E0122",
+			semantic_analyzer::Error::InvalidCast { .. } => "E0123",
+			semantic_analyzer::Error::UntypedNull { .. } => "E0124",
+			semantic_analyzer::Error::BuiltinRedefinition { .. } => "E0125",
+			semantic_analyzer::Error::InvalidBuiltinArgument { .. } => "E0126",
+			semantic_analyzer::Error::NonConstantPattern { .. } => "E0127",
+			semantic_analyzer::Error::DuplicatePattern { .. } => "E0128",
+			semantic_analyzer::Error::MissingDefaultArm { .. } => "E0129",
+			semantic_analyzer::Error::DuplicateEnum { .. } => "E0130",
+			semantic_analyzer::Error::DuplicateVariant { .. } => "E0131",
+			semantic_analyzer::Error::UnknownVariant { .. } => "E0132",
+			semantic_analyzer::Error::MissingVariants { .. } => "E0133",
+			semantic_analyzer::Error::DuplicateTypeAlias { .. } => "E0134",
+			semantic_analyzer::Error::RecursiveTypeAlias { .. } => "E0135",
+			semantic_analyzer::Error::UninferredTypeParameter { .. } => "E0136",
+			semantic_analyzer::Error::TooManySpecializations { .. } => "E0137",
+			semantic_analyzer::Error::TypeArgumentCountMismatch { .. } => "E0138",
+			semantic_analyzer::Error::UnknownMethod { .. } => "E0139",
+			semantic_analyzer::Error::InvalidReceiver { .. } => "E0140",
+			semantic_analyzer::Error::Multiple(errors) => errors.first().map_or("E0111", Code::code),
+		}
+	}
+}
+
+impl Code for interpreter::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			interpreter::Error::MissingMainFunction => "E0200",
+			interpreter::Error::UndeclaredVariable { .. } => "E0102",
+			interpreter::Error::UndefinedFunctionCall { .. } => "E0112",
+			interpreter::Error::ArgumentCountMismatch { .. } => "E0113",
+			interpreter::Error::MissingReturnValue { .. } => "E0114",
+			interpreter::Error::TypeMismatch { .. } => "E0201",
+			interpreter::Error::InvalidOperand { .. } => "E0202",
+			interpreter::Error::InvalidCast { .. } => "E0123",
+			interpreter::Error::NoStruct { .. } => "E0105",
+			interpreter::Error::UnknownField { .. } => "E0106",
+			interpreter::Error::NoArray { .. } => "E0109",
+			interpreter::Error::InvalidIndex { .. } => "E0203",
+			interpreter::Error::IndexOutOfBounds { .. } => "E0204",
+			interpreter::Error::DivisionByZero { .. } => "E0205",
+			interpreter::Error::NullDereference { .. } => "E0206",
+			interpreter::Error::UnsupportedExtern { .. } => "E0305",
+		}
+	}
+}
+
+impl Code for ir::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			ir::Error::UndeclaredVariable { .. } => "E0102",
+			ir::Error::UndefinedFunctionCall { .. } => "E0112",
+			ir::Error::ArgumentCountMismatch { .. } => "E0113",
+			ir::Error::MissingReturnValue { .. } => "E0114",
+			ir::Error::NoStruct { .. } => "E0105",
+			ir::Error::UnknownField { .. } => "E0106",
+			ir::Error::NoArray { .. } => "E0109",
+		}
+	}
+}
+
+impl Code for bytecode::CompileError {
+	fn code(&self) -> &'static str {
+		match self {
+			bytecode::CompileError::Lowering(err) => err.code(),
+			bytecode::CompileError::UnsupportedStruct { .. } => "E0300",
+			bytecode::CompileError::UnsupportedExtern { .. } => "E0306",
+			bytecode::CompileError::UnsupportedDereference { .. } => "E0307",
+		}
+	}
+}
+
+impl Code for bytecode::RuntimeError {
+	fn code(&self) -> &'static str {
+		match self {
+			bytecode::RuntimeError::MissingMainFunction => "E0200",
+			bytecode::RuntimeError::MissingReturnValue { .. } => "E0114",
+			bytecode::RuntimeError::TypeMismatch { .. } => "E0201",
+			bytecode::RuntimeError::InvalidOperand { .. } => "E0202",
+			bytecode::RuntimeError::IndexOutOfBounds { .. } => "E0204",
+			bytecode::RuntimeError::DivisionByZero => "E0205",
+		}
+	}
+}
+
+#[cfg(feature = "jit")]
+impl Code for jit::Error {
+	fn code(&self) -> &'static str {
+		match self {
+			jit::Error::UnsupportedHost(_) => "E0303",
+			jit::Error::Module(_) => "E0304",
+			jit::Error::MissingMainFunction => "E0200",
+			jit::Error::UnsupportedDataType { .. } => "E0302",
+			jit::Error::UnsupportedStruct { .. } => "E0300",
+			jit::Error::UnsupportedArray { .. } => "E0301",
+			jit::Error::UnsupportedDereference { .. } => "E0307",
+			jit::Error::UndeclaredVariable { .. } => "E0102",
+			jit::Error::UndefinedFunctionCall { .. } => "E0112",
+			jit::Error::ArgumentCountMismatch { .. } => "E0113",
+			jit::Error::MissingReturnValue { .. } => "E0114",
+			jit::Error::TypeMismatch { .. } | jit::Error::BranchTypeMismatch { .. } => "E0111",
+			jit::Error::InvalidFloatOperands { .. } => "E0117",
+			jit::Error::InvalidOperand { .. } => "E0118",
+		}
+	}
+}
@@ -0,0 +1,215 @@
+//! Presentation of errors to the user.
+//!
+//! A [`Diagnostic`] shows a message together with the affected lines of the source code, in which the position of the
+//! message is underlined, like rustc does. Its [`Severity`] tells how serious the message is, and can be highlighted
+//! with colors on terminals. Every error has a stable [code](ErrorCode), which is explained in more detail by
+//! [`explain`]. Warnings do not abort the compilation, so they are collected in a [`DiagnosticSink`] instead.
+
+use std::{error::Error, fmt, sync::Arc};
+
+pub use code::{explain, Code, ErrorCode, ERROR_CODES};
+pub use sink::DiagnosticSink;
+
+use crate::source::SourcePositionRange;
+
+mod code;
+mod sink;
+#[cfg(test)]
+mod test;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	/// The program cannot be compiled or executed.
+	Error,
+	/// The program works, but probably not as intended.
+	Warning,
+	/// Additional information, e.g. about a previous error.
+	Note,
+}
+
+impl Severity {
+	/// The ANSI escape code for the color of this severity, which is bold red, yellow or cyan like in rustc.
+	fn color(&self) -> &'static str {
+		match self {
+			Severity::Error => "1;31",
+			Severity::Warning => "1;33",
+			Severity::Note => "1;36",
+		}
+	}
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Severity::Error => write!(f, "error"),
+			Severity::Warning => write!(f, "warning"),
+			Severity::Note => write!(f, "note"),
+		}
+	}
+}
+
+/// A message with the position in the source code it refers to.
+///
+/// A diagnostic is an [`Error`] itself, whose [source](Error::source) is the error it was [created
+/// from](Diagnostic::from_error), so that it can be passed on with `?`, e.g. as [`anyhow::Error`].
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	/// How serious the diagnostic is.
+	pub severity: Severity,
+	/// The [code](ErrorCode) of the diagnostic, like `E0102`.
+	pub code: &'static str,
+	/// The message describing the problem.
+	pub message: String,
+	/// The position of the problem in the source code, if it refers to one.
+	pub position: Option<SourcePositionRange>,
+	/// The error the diagnostic was created from.
+	source: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+impl Diagnostic {
+	/// Creates an [error](Severity::Error) from its `code` and its `message`, e.g. the error itself.
+	pub fn new(code: &'static str, message: impl fmt::Display, position: Option<&SourcePositionRange>) -> Self {
+		Self {
+			severity: Severity::Error,
+			code,
+			message: message.to_string(),
+			position: position.cloned(),
+			source: None,
+		}
+	}
+
+	/// Creates an [error](Severity::Error) from an `error` with a stable [code](Code), which becomes the
+	/// [source](Error::source) of the diagnostic.
+	///
+	/// ```
+	/// use std::error::Error;
+	///
+	/// use fortytwolang::{error::Diagnostic, lexer};
+	///
+	/// let error = lexer::tokenize_str("$").unwrap_err().remove(0);
+	/// let diagnostic = Diagnostic::from_error(error.clone(), None);
+	/// assert_eq!(diagnostic.code, "E0001");
+	/// assert_eq!(diagnostic.source().unwrap().downcast_ref(), Some(&error));
+	/// ```
+	pub fn from_error(
+		error: impl Error + Code + Send + Sync + 'static,
+		position: Option<&SourcePositionRange>,
+	) -> Self {
+		let diagnostic = Self::new(error.code(), &error, position);
+		Self { source: Some(Arc::new(error)), ..diagnostic }
+	}
+
+	/// Changes the severity of the diagnostic, e.g. to turn it into a [warning](Severity::Warning).
+	pub fn with_severity(mut self, severity: Severity) -> Self {
+		self.severity = severity;
+		self
+	}
+
+	/// Renders the diagnostic like its [`Display`](fmt::Display) implementation, but highlighted with ANSI colors if
+	/// `color` is true.
+	///
+	/// ```
+	/// use fortytwolang::error::{Diagnostic, Severity};
+	///
+	/// let diagnostic = Diagnostic::new("E0100", "Shadowed variable", None).with_severity(Severity::Warning);
+	/// assert_eq!(diagnostic.render(false), "warning[E0100]: Shadowed variable");
+	/// assert_eq!(diagnostic.render(true), "\x1b[1;33mwarning[E0100]\x1b[0m\x1b[1m: Shadowed variable\x1b[0m");
+	/// ```
+	pub fn render(&self, color: bool) -> String {
+		let mut rendered = String::new();
+		self.write(&mut rendered, color).expect("writing to a String does not fail");
+		rendered
+	}
+
+	/// Writes the diagnostic like rustc, with the affected lines of the source code and `^` under the position.
+	fn write(&self, f: &mut impl fmt::Write, color: bool) -> fmt::Result {
+		let paint = |code: &str, text: &str| match color {
+			true => format!("\x1b[{}m{}\x1b[0m", code, text),
+			false => text.to_owned(),
+		};
+		let severity_color = self.severity.color();
+		// Line numbers and the separators of the gutter are bold blue
+		let gutter_color = "1;34";
+
+		write!(
+			f,
+			"{}{}",
+			paint(severity_color, &format!("{}[{}]", self.severity, self.code)),
+			paint("1", &format!(": {}", self.message))
+		)?;
+		let Some(position) = &self.position else {
+			return Ok(());
+		};
+		let (start, end) = (position.position.start, position.position.end);
+
+		// The line numbers are right-aligned in a gutter, which is as wide as the largest line number
+		let gutter = end.line.to_string().len();
+		write!(f, "\n{:gutter$}{} {}", "", paint(gutter_color, "-->"), position)?;
+		write!(f, "\n{:gutter$} {}", "", paint(gutter_color, "|"))?;
+
+		let text = position.source.text.iter().collect::<String>();
+		for (line_number, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line)) {
+			if line_number < start.line || line_number > end.line {
+				continue;
+			}
+			write!(f, "\n{} {}", paint(gutter_color, &format!("{:>gutter$} |", line_number)), line)?;
+
+			// Lines in the middle of the range are underlined from the first non-whitespace char to their end
+			let indentation = line.chars().take_while(|char| char.is_whitespace()).count();
+			let from = if line_number == start.line { start.column } else { indentation + 1 };
+			let to = if line_number == end.line { end.column } else { line.chars().count() };
+			if to < from {
+				continue;
+			}
+			// Use the same whitespace as the line, so that the underline lines up with tabs of any width
+			let underline_indentation =
+				line.chars().take(from - 1).map(|char| if char == '\t' { '\t' } else { ' ' }).collect::<String>();
+			write!(
+				f,
+				"\n{:gutter$} {} {}{}",
+				"",
+				paint(gutter_color, "|"),
+				underline_indentation,
+				paint(severity_color, &"^".repeat(to - from + 1))
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	/// Formats the diagnostic like rustc, with the affected lines of the source code and `^` under the position.
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::{
+	/// 	error::Diagnostic,
+	/// 	source::{Position, PositionRange, Source, SourcePositionRange},
+	/// };
+	///
+	/// let source = Arc::new(Source::new("file.ftl".to_owned(), "def main() {\n\treturn 1 +\n}".to_owned()));
+	/// let position = SourcePositionRange {
+	/// 	source,
+	/// 	position: PositionRange {
+	/// 		start: Position { line: 2, column: 11, offset: 23 },
+	/// 		end: Position { line: 2, column: 11, offset: 23 },
+	/// 	},
+	/// };
+	/// let diagnostic = Diagnostic::new("E0021", "Missing operand", Some(&position));
+	/// assert_eq!(
+	/// 	diagnostic.to_string(),
+	/// 	"error[E0021]: Missing operand\n --> file.ftl:2:11\n  |\n2 | \treturn 1 +\n  | \t         ^"
+	/// );
+	/// ```
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.write(f, false)
+	}
+}
+
+impl Error for Diagnostic {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		self.source.as_deref().map(|source| source as &(dyn Error + 'static))
+	}
+}
@@ -0,0 +1,49 @@
+use std::{
+	fmt,
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	error::{Diagnostic, Severity},
+	source::SourcePositionRange,
+};
+
+/// Collects [diagnostics](Diagnostic) like warnings, which do not abort the compilation, so that they can be printed
+/// after it.
+///
+/// Clones of a sink share the same diagnostics, so each stage of the compiler can get its own clone:
+///
+/// ```
+/// use fortytwolang::error::DiagnosticSink;
+///
+/// let diagnostics = DiagnosticSink::default();
+/// diagnostics.clone().warn("W0100", "Variable `x` is never used", None);
+/// assert_eq!(diagnostics.take()[0].to_string(), "warning[W0100]: Variable `x` is never used");
+/// assert!(diagnostics.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticSink {
+	diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl DiagnosticSink {
+	/// Adds a diagnostic.
+	pub fn emit(&self, diagnostic: Diagnostic) {
+		self.diagnostics.lock().unwrap().push(diagnostic);
+	}
+
+	/// Adds a [warning](Severity::Warning) with the given `code` and `message`.
+	pub fn warn(&self, code: &'static str, message: impl fmt::Display, position: Option<&SourcePositionRange>) {
+		self.emit(Diagnostic::new(code, message, position).with_severity(Severity::Warning));
+	}
+
+	/// Removes all diagnostics that were added so far and returns them in the order they were added.
+	pub fn take(&self) -> Vec<Diagnostic> {
+		std::mem::take(&mut self.diagnostics.lock().unwrap())
+	}
+
+	/// Whether no diagnostics were added.
+	pub fn is_empty(&self) -> bool {
+		self.diagnostics.lock().unwrap().is_empty()
+	}
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::{
+	error::{explain, Code, Diagnostic, ERROR_CODES},
+	lexer, semantic_analyzer,
+	source::{PositionContainer, PositionRange, Source, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Tests that the error codes are sorted, unique and look like `E0102` or `W0100`.
+#[test]
+fn test_error_codes() {
+	for pair in ERROR_CODES.windows(2) {
+		assert!(pair[0].code < pair[1].code, "{} is not before {}", pair[0].code, pair[1].code);
+	}
+	for error_code in ERROR_CODES {
+		assert_eq!(error_code.code.len(), 5);
+		assert!(
+			error_code.code.starts_with(['E', 'W']) && error_code.code[1..].chars().all(|char| char.is_ascii_digit())
+		);
+		assert!(!error_code.title.is_empty() && !error_code.explanation.is_empty());
+	}
+}
+
+/// Tests that the codes of errors can be explained.
+#[test]
+fn test_explain_error() {
+	let source = Arc::new(Source::new("testfile".to_owned(), "x".to_owned()));
+	let position = SourcePositionRange { source, position: PositionRange::default() };
+	let name = PositionContainer::new(Symbol::intern("x"), position.clone());
+	let code = semantic_analyzer::Error::UndeclaredVariable { name }.code();
+	assert_eq!(explain(code).unwrap().title, "undefined variable");
+	let literal = PositionContainer::new("x".to_owned(), position);
+	assert_eq!(explain(lexer::Error::MisplacedUnderscore(literal).code()).unwrap().code, "E0005");
+}
+
+/// Tests that a diagnostic can be passed on as error, with the error it was created from as source.
+#[test]
+fn test_diagnostic_as_error() {
+	let lexer_error = lexer::tokenize_str("$").unwrap_err().remove(0);
+	let error = anyhow::Error::from(Diagnostic::from_error(lexer_error.clone(), None));
+	assert_eq!(error.chain().count(), 2);
+	assert_eq!(error.root_cause().downcast_ref(), Some(&lexer_error));
+	assert_eq!(explain(lexer_error.code()).unwrap().to_string(), "E0001: unknown token");
+}
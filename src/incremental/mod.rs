@@ -0,0 +1,232 @@
+//! Keeping a document parsed while it is edited, e.g. in an editor, without lexing and parsing all of it again on every
+//! keystroke.
+//!
+//! A [`Document`] remembers the lines of each top-level node. An [edit](TextEdit) only re-lexes and re-parses the
+//! lines of the top-level nodes it touches. The nodes before and after them are reused, with their positions moved
+//! to the edited source code. If the re-parsed part contains an error, like an unterminated string literal, the whole
+//! document is lexed and parsed again, so that the nodes and errors are always the same as without incremental parsing.
+
+mod rebase;
+#[cfg(test)]
+mod test;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context;
+
+use crate::{
+	ast::{visit::VisitorMut, Node},
+	error::{Diagnostic, DiagnosticSink},
+	incremental::rebase::Rebase,
+	lexer, parser,
+	source::Source,
+	Lexer, Parser,
+};
+
+/// Replacement of the chars in the `range` of a document by the `text`. The range consists of char offsets, like
+/// [`Position::offset`](crate::source::Position::offset).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+	pub range: Range<usize>,
+	pub text: String,
+}
+
+/// The source code of a document, together with its top-level nodes.
+#[derive(Debug)]
+pub struct Document {
+	source: Arc<Source>,
+	/// The parsed top-level nodes, or the error why the document could not be lexed or parsed.
+	items: Result<Vec<Item>, Box<Failure>>,
+}
+
+/// A top-level node with the warnings of the lexer and parser about it.
+#[derive(Debug, Clone)]
+struct Item {
+	node: Node,
+	/// The first and last line of the node.
+	lines: (usize, usize),
+	warnings: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone)]
+enum Error {
+	Lexer(lexer::Error),
+	Parser(parser::Error),
+}
+
+/// The error of a document which could not be lexed or parsed, with the warnings of the lexer and parser before it.
+#[derive(Debug, Clone)]
+struct Failure {
+	error: Error,
+	warnings: Vec<Diagnostic>,
+}
+
+impl Document {
+	/// Lexes and parses the whole `source`.
+	pub fn new(source: Arc<Source>) -> Self {
+		let items = parse(&source, 0..source.text.len());
+		Self { source, items }
+	}
+
+	/// The current source code.
+	pub fn source(&self) -> &Arc<Source> {
+		&self.source
+	}
+
+	/// The top-level nodes, or the lexer or parser error of the current source code.
+	pub fn nodes(&self) -> anyhow::Result<Vec<Node>> {
+		match &self.items {
+			Ok(items) => Ok(items.iter().map(|item| item.node.clone()).collect()),
+			Err(failure) => match &failure.error {
+				Error::Lexer(error) => Err(error.clone()).context("Lexing error"),
+				Error::Parser(error) => Err(error.clone()).context("Parser error"),
+			},
+		}
+	}
+
+	/// The warnings of the lexer and parser, also if the document could not be lexed or parsed.
+	pub fn warnings(&self) -> Vec<Diagnostic> {
+		match &self.items {
+			Ok(items) => items.iter().flat_map(|item| item.warnings.iter().cloned()).collect(),
+			Err(failure) => failure.warnings.clone(),
+		}
+	}
+
+	/// Applies the `edit` and re-parses the top-level nodes it touches. Returns the range of the edited source code
+	/// which was lexed and parsed again.
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::{
+	/// 	incremental::{Document, TextEdit},
+	/// 	Source,
+	/// };
+	///
+	/// let source_code = "def f(): int {\n\treturn 1\n}\ndef g(): int {\n\treturn 2\n}\n";
+	/// let mut document = Document::new(Arc::new(Source::new("example.ftl".to_owned(), source_code.to_owned())));
+	/// // Only `g` is parsed again, which starts at offset 27
+	/// let reparsed = document.edit(TextEdit { range: 43..51, text: "return 3 + 4".to_owned() });
+	/// assert_eq!(reparsed, 27..58);
+	/// assert_eq!(document.nodes().unwrap().len(), 2);
+	/// ```
+	pub fn edit(&mut self, edit: TextEdit) -> Range<usize> {
+		let old = &self.source;
+		let text: String = old.text[..edit.range.start]
+			.iter()
+			.copied()
+			.chain(edit.text.chars())
+			.chain(old.text[edit.range.end..].iter().copied())
+			.collect();
+		let source = Arc::new(Source::new(old.name.clone(), text));
+		let old = std::mem::replace(&mut self.source, Arc::clone(&source));
+
+		let Ok(items) = &mut self.items else {
+			self.items = parse(&source, 0..source.text.len());
+			return 0..source.text.len();
+		};
+		let first_line = old.position(edit.range.start).line;
+		let last_line = old.position(edit.range.end).line;
+
+		let mut prefix = items.iter().take_while(|item| item.lines.1 < first_line).count();
+		let mut suffix = prefix + items[prefix..].iter().take_while(|item| item.lines.0 <= last_line).count();
+		// The reused nodes must not share a line with the re-parsed ones
+		while prefix > 0 && prefix < items.len() && items[prefix].lines.0 <= items[prefix - 1].lines.1 {
+			prefix -= 1;
+		}
+		while suffix > 0 && suffix < items.len() && items[suffix].lines.0 <= items[suffix - 1].lines.1 {
+			suffix += 1;
+		}
+
+		let start = match prefix {
+			0 => 0,
+			_ => line_start(&old, items[prefix - 1].lines.1 + 1),
+		};
+		let old_end = items.get(suffix).map_or(old.text.len(), |item| line_start(&old, item.lines.0));
+		let end = old_end + source.text.len() - old.text.len();
+		let Ok(reparsed) = parse(&source, start..end) else {
+			self.items = parse(&source, 0..source.text.len());
+			return 0..source.text.len();
+		};
+
+		let added_lines = edit.text.chars().filter(|char| *char == '\n').count();
+		let removed_lines = last_line - first_line;
+		let mut rebase = Rebase { source: Arc::clone(&source), lines: 0, chars: 0 };
+		let mut suffix_rebase = Rebase {
+			source,
+			lines: added_lines as isize - removed_lines as isize,
+			chars: end as isize - old_end as isize,
+		};
+
+		let suffix_items = items.split_off(suffix);
+		items.truncate(prefix);
+		for item in items.iter_mut() {
+			item.rebase(&mut rebase);
+		}
+		items.extend(reparsed);
+		items.extend(suffix_items.into_iter().map(|mut item| {
+			item.rebase(&mut suffix_rebase);
+			item
+		}));
+		start..end
+	}
+}
+
+impl Item {
+	fn rebase(&mut self, rebase: &mut Rebase) {
+		rebase.visit_node(&mut self.node);
+		for warning in &mut self.warnings {
+			if let Some(position) = &mut warning.position {
+				rebase.visit_position(position);
+			}
+		}
+		self.lines.0 = self.lines.0.wrapping_add_signed(rebase.lines);
+		self.lines.1 = self.lines.1.wrapping_add_signed(rebase.lines);
+	}
+}
+
+/// Lexes and parses the `range` of the `source`, which consists of whole top-level nodes.
+fn parse(source: &Arc<Source>, range: Range<usize>) -> Result<Vec<Item>, Box<Failure>> {
+	let diagnostics = DiagnosticSink::default();
+	let symbols = Arc::clone(source).iter_from(range.start).take(range.len());
+	let (tokens, lexer_errors) = Lexer::new(symbols).with_diagnostics(diagnostics.clone()).tokenize();
+	let parser = Parser::new(tokens.into_iter()).with_diagnostics(diagnostics.clone());
+	let nodes = parser.parse_with_lexer_errors(lexer_errors).map_err(|error| {
+		let error = match error {
+			parser::Error::Lexer(error) => Error::Lexer(error),
+			error => Error::Parser(error),
+		};
+		Box::new(Failure { error, warnings: diagnostics.take() })
+	})?;
+
+	let mut items: Vec<_> = nodes
+		.into_iter()
+		.map(|node| {
+			let position = node.source_position().position;
+			Item { node, lines: (position.start.line, position.end.line), warnings: Vec::new() }
+		})
+		.collect();
+	// Each warning belongs to the last node starting before it
+	for warning in diagnostics.take() {
+		let line = warning.position.as_ref().map_or(0, |position| position.position.start.line);
+		let index = items.iter().rposition(|item| item.lines.0 <= line).unwrap_or(0);
+		if let Some(item) = items.get_mut(index) {
+			item.warnings.push(warning);
+		}
+	}
+	Ok(items)
+}
+
+/// The offset of the first char of the `line` in the `source`, or the length of the source if it has less lines.
+fn line_start(source: &Source, line: usize) -> usize {
+	if line <= 1 {
+		return 0;
+	}
+	source
+		.text
+		.iter()
+		.enumerate()
+		.filter(|(_, char)| **char == '\n')
+		.nth(line - 2)
+		.map_or(source.text.len(), |(newline, _)| newline + 1)
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use crate::{
+	ast::visit::VisitorMut,
+	source::{Position, Source, SourcePositionRange},
+};
+
+/// Moves the positions of a reused node to the edited source code. The node lies on lines which were not edited, so
+/// only its lines and offsets change by the lines and chars the edit added or removed before it, not its columns.
+pub(super) struct Rebase {
+	/// The edited source code.
+	pub source: Arc<Source>,
+	/// Lines added before the node, which are negative if lines were removed.
+	pub lines: isize,
+	/// Chars added before the node, which are negative if chars were removed.
+	pub chars: isize,
+}
+
+impl Rebase {
+	fn shift(&self, position: &mut Position) {
+		position.line = position.line.wrapping_add_signed(self.lines);
+		position.offset = position.offset.wrapping_add_signed(self.chars);
+	}
+}
+
+impl VisitorMut for Rebase {
+	fn visit_position(&mut self, position: &mut SourcePositionRange) {
+		position.source = Arc::clone(&self.source);
+		self.shift(&mut position.position.start);
+		self.shift(&mut position.position.end);
+	}
+}
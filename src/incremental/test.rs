@@ -0,0 +1,141 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+	error::DiagnosticSink,
+	incremental::{Document, TextEdit},
+	parser,
+	source::Source,
+};
+
+const SOURCE_CODE: &str = "\
+struct Point {
+	x: int
+}
+
+def f(): int {
+	return 1
+}
+
+def g(p: Point): int {
+	return p.x
+}
+";
+
+/// Applies the edit replacing the first occurrence of `old` by `new` and returns the range which was parsed again.
+fn replace(document: &mut Document, old: &str, new: &str) -> Range<usize> {
+	let text: String = document.source().text.iter().collect();
+	let start = text[..text.find(old).unwrap()].chars().count();
+	edit(document, start..start + old.chars().count(), new)
+}
+
+/// Replaces the chars in the `range` by the `text` and returns the range which was parsed again. Checks that the
+/// nodes and warnings are the same as when parsing the edited source code from scratch.
+fn edit(document: &mut Document, range: Range<usize>, text: &str) -> Range<usize> {
+	let chars = &document.source().text;
+	let edited: String =
+		chars[..range.start].iter().chain(&text.chars().collect::<Vec<_>>()).chain(&chars[range.end..]).collect();
+	let reparsed = document.edit(TextEdit { range, text: text.to_owned() });
+
+	assert_eq!(document.source().text.iter().collect::<String>(), edited);
+	let diagnostics = DiagnosticSink::default();
+	let expected = crate::parse_source(Arc::new(Source::new("testfile".to_owned(), edited)), &diagnostics);
+	match expected {
+		Ok(expected) => assert_eq!(document.nodes().unwrap(), expected),
+		Err(expected) => assert_eq!(document.nodes().unwrap_err().to_string(), expected.to_string()),
+	}
+	let warnings = document.warnings();
+	let expected_warnings = diagnostics.take();
+	assert_eq!(warnings.len(), expected_warnings.len());
+	for (warning, expected) in warnings.iter().zip(&expected_warnings) {
+		assert_eq!(warning.to_string(), expected.to_string());
+		assert_eq!(warning.position, expected.position);
+	}
+	reparsed
+}
+
+fn document() -> Document {
+	Document::new(Arc::new(Source::new("testfile".to_owned(), SOURCE_CODE.to_owned())))
+}
+
+/// Tests that only the edited function is parsed again, while the nodes after it are moved to the new lines.
+#[test]
+fn test_edit_function() {
+	let mut document = document();
+	let reparsed = replace(&mut document, "return 1", "var one = 1\n\treturn one");
+	assert_eq!(reparsed, 25..69);
+	let reparsed = replace(&mut document, "x: int", "x: int\n\ty: int");
+	assert_eq!(reparsed, 0..34);
+	// Removing lines moves the following nodes up
+	let reparsed = replace(&mut document, "var one = 1\n\t", "");
+	assert_eq!(reparsed, 33..64);
+}
+
+/// Tests that code inserted between the nodes is parsed on its own.
+#[test]
+fn test_insert_node() {
+	let mut document = document();
+	// Insert at the start of the empty line before `g`
+	let reparsed = edit(&mut document, 53..53, "# helper\ndef h() {\n} # done\n");
+	assert_eq!(reparsed, 53..82);
+	// Nodes sharing a line with the edited ones are parsed again, too
+	let reparsed = replace(&mut document, "done", "finished");
+	assert_eq!(reparsed, 62..86);
+}
+
+/// Tests that errors are reported for the whole document, and that the nodes are parsed again after fixing them.
+#[test]
+fn test_edit_error() {
+	let mut document = document();
+	replace(&mut document, "return 1", "return \"1");
+	assert!(document.nodes().is_err());
+	replace(&mut document, "return \"1", "return 1 +");
+	let reparsed = replace(&mut document, "return 1 +", "return 1");
+	assert_eq!(reparsed, 0..SOURCE_CODE.len());
+	assert_eq!(replace(&mut document, "p.x", "p.x * 2"), 53..95);
+}
+
+/// Tests that the warnings of reused nodes are moved with them.
+#[test]
+fn test_edit_warnings() {
+	let mut document = document();
+	replace(&mut document, "return p.x", "return p.x\n\tp.x = 1");
+	assert_eq!(document.warnings().len(), 1);
+	replace(&mut document, "struct Point {", "\n\nstruct Point {");
+	assert_eq!(document.warnings()[0].position.as_ref().unwrap().position.start.line, 13);
+}
+
+/// Tests that deleting the `}` of a struct is an error, although the struct ends at the end of the re-parsed lines.
+#[test]
+fn test_remove_closing_brace() {
+	let mut document = document();
+	replace(&mut document, "int\n}", "int\n");
+	assert!(document.nodes().is_err());
+	replace(&mut document, "int\n", "int\n}");
+	assert_eq!(document.nodes().unwrap().len(), 3);
+}
+
+/// Tests random edits, which insert and delete braces, keywords and lines, against parsing the edited source code from
+/// scratch.
+#[test]
+fn test_random_edits() {
+	const SNIPPETS: [&str; 10] = ["", "}", "{", "\n", "def h() {\n}\n", "struct", "x: int\n", "return 1", "(", "#"];
+	// Xorshift, so that the edits are the same on every run
+	let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+	let mut random = |bound: usize| {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		state as usize % bound
+	};
+	for _ in 0..200 {
+		let mut document = document();
+		for _ in 0..4 {
+			let len = document.source().text.len();
+			let start = random(len + 1);
+			let end = (start + random(3)).min(len);
+			edit(&mut document, start..end, SNIPPETS[random(SNIPPETS.len())]);
+			let text: String = document.source().text.iter().collect();
+			assert_eq!(document.nodes().is_ok(), parser::parse_str(&text).is_ok(), "{text}");
+		}
+	}
+}
@@ -0,0 +1,63 @@
+use std::ops::Deref;
+
+use crate::{
+	ast::{
+		expression::{BinaryOperator, FunctionCall, UnaryOperator},
+		statement::DataType,
+	},
+	interpreter::Value,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Errors that occur while [interpreting](super::Interpreter) a program.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+	#[error("MissingMainFunction: The program has no `main()` function to start with.")]
+	MissingMainFunction,
+
+	#[error("{}: UndeclaredVariable: Variable `{name}` is not declared.", name.position)]
+	UndeclaredVariable { name: PositionContainer<Symbol> },
+
+	#[error("{}: UndefinedFunctionCall: Call of function `{}(...)`, but no such function is defined.", function_call.name.position, function_call.name.deref())]
+	UndefinedFunctionCall { function_call: Box<FunctionCall> },
+
+	#[error("{}: UnsupportedExtern: Extern function `{}(...)` cannot be called with these arguments by the interpreter.", function_call.name.position, function_call.name.value)]
+	UnsupportedExtern { function_call: Box<FunctionCall> },
+
+	#[error("{}: ArgumentCountMismatch: Function `{}(...)` expects {expected} arguments but {actual} parameters provided", function_call.name.position, function_call.name.value)]
+	ArgumentCountMismatch { expected: usize, actual: usize, function_call: Box<FunctionCall> },
+
+	#[error("{}: MissingReturnValue: Function `{}(...)` did not return a value.", function_call.name.position, function_call.name.value)]
+	MissingReturnValue { function_call: Box<FunctionCall> },
+
+	#[error("{}: TypeMismatch: Operator {operator:?} cannot be applied to {lhs} and {rhs}", operator.position)]
+	TypeMismatch { operator: PositionContainer<BinaryOperator>, lhs: Value, rhs: Value },
+
+	#[error("{}: InvalidOperand: Operator {operator:?} cannot be applied to {operand}", operator.position)]
+	InvalidOperand { operator: PositionContainer<UnaryOperator>, operand: Value },
+
+	#[error("{}: InvalidCast: {value} cannot be cast to {to}.", position)]
+	InvalidCast { position: SourcePositionRange, value: Value, to: DataType },
+
+	#[error("{}: NoStruct: Field `{}` accessed on {value}, which is not a struct.", field.position, field.value)]
+	NoStruct { field: PositionContainer<Symbol>, value: Value },
+
+	#[error("{}: UnknownField: {value} has no field `{}`.", field.position, field.value)]
+	UnknownField { field: PositionContainer<Symbol>, value: Value },
+
+	#[error("{}: NoArray: {value} cannot be indexed, because it is not an array.", position)]
+	NoArray { position: SourcePositionRange, value: Value },
+
+	#[error("{}: InvalidIndex: {index} is not an integer.", position)]
+	InvalidIndex { position: SourcePositionRange, index: Value },
+
+	#[error("{}: IndexOutOfBounds: Index {index} is out of bounds of an array with {length} elements.", position)]
+	IndexOutOfBounds { position: SourcePositionRange, index: i64, length: usize },
+
+	#[error("{}: DivisionByZero: Integer division by zero.", position)]
+	DivisionByZero { position: SourcePositionRange },
+
+	#[error("{}: NullDereference: The null pointer is dereferenced.", position)]
+	NullDereference { position: SourcePositionRange },
+}
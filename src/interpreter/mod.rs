@@ -0,0 +1,607 @@
+//! Executing a program by walking its [AST](crate::ast) directly, without compiling it first.
+
+mod error;
+mod native;
+#[cfg(test)]
+mod test;
+mod value;
+
+use std::{collections::HashMap, iter, sync::Arc};
+
+pub use error::Error;
+pub use value::{StructValue, Value};
+
+use crate::{
+	ast::{
+		self,
+		expression::{
+			BinaryExpression, BinaryOperator, Cast, FieldAccess, FunctionCall, IndexExpression, UnaryExpression,
+			UnaryOperator,
+		},
+		statement::{BasicDataType, DataType, LValue},
+		Block, Expression, FunctionDefinition, FunctionPrototype,
+	},
+	builtin::{self, Builtin},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Variables declared in one block, e.g. a function body or the body of an `if`/`while`.
+type Scope = HashMap<Symbol, Value>;
+
+/// The nested [`Scope`]s of one function call. The innermost scope is the last one.
+type CallStackFrame = Vec<Scope>;
+
+/// Whether execution continues with the next instruction, or leaves the current loop or function.
+enum ControlFlow {
+	/// Continue with the next instruction.
+	Next,
+	/// A `return` was executed with the given value, which is [`None`] for functions without return type.
+	Return(Option<Value>),
+	/// A `break` was executed, so the innermost loop is left.
+	Break,
+	/// A `continue` was executed, so the innermost loop continues with its next iteration.
+	Continue,
+}
+
+/// Tree-walking interpreter that evaluates [AST nodes](ast::Node).
+#[derive(Debug, Default)]
+pub struct Interpreter {
+	/// All functions defined in the program.
+	functions: HashMap<Symbol, Arc<FunctionDefinition>>,
+	/// All extern functions declared in the program, which are bound to [native implementations](native).
+	externs: HashMap<Symbol, FunctionPrototype>,
+	/// Top-level constants in the order of their declaration, which are evaluated before `main()` is called.
+	const_declarations: Vec<ast::statement::ConstDeclaration>,
+	/// Values of the top-level constants, which are visible in all functions.
+	constants: Scope,
+	/// One frame per active function call, containing the variables of this call.
+	call_stack: Vec<CallStackFrame>,
+}
+
+impl Interpreter {
+	/// Creates an [`Interpreter`] that knows all functions defined in `ast_nodes`.
+	pub fn new(ast_nodes: impl Iterator<Item = ast::Node>) -> Self {
+		let mut interpreter = Self::default();
+		for ast_node in ast_nodes {
+			interpreter.ast_node(ast_node);
+		}
+		interpreter
+	}
+
+	/// Registers the AST node, so that it can be used during execution.
+	fn ast_node(&mut self, node: ast::Node) {
+		match node {
+			ast::Node::Function(function) => {
+				self.functions.insert(function.prototype.name.value, Arc::new(function));
+			},
+			ast::Node::FunctionPrototype(prototype) => {
+				self.externs.insert(prototype.name.value, prototype);
+			},
+			ast::Node::Struct(_) | ast::Node::TypeAlias(_) => (),
+			ast::Node::Const(const_declaration) => self.const_declarations.push(const_declaration),
+			// The variants are represented by their index
+			ast::Node::Enum(enum_) => self.const_declarations.extend(enum_.constants()),
+			ast::Node::Import(_) | ast::Node::Comment(_) => (),
+		}
+	}
+
+	/// Executes the program by calling its `main()` function and returns the value returned by it.
+	#[tracing::instrument(skip_all)]
+	pub fn run(&mut self) -> Result<Option<Value>, Error> {
+		let main = self.functions.get(&Symbol::intern("main")).cloned().ok_or(Error::MissingMainFunction)?;
+		self.evaluate_constants()?;
+		self.call(&main, Vec::new())
+	}
+
+	/// Executes the `block` like the body of a function, in which the `variables` are already declared, and returns
+	/// the value returned by it. Afterwards, the `variables` also contain the variables declared in the block, even if
+	/// it failed, so that they can be used by the next block, e.g. in the REPL.
+	pub fn run_block(&mut self, block: &Block, variables: &mut HashMap<Symbol, Value>) -> Result<Option<Value>, Error> {
+		self.evaluate_constants()?;
+		self.call_stack.push(vec![std::mem::take(variables)]);
+		let control_flow = self.instructions(block);
+		let mut frame = self.call_stack.pop().expect("Call stack is empty");
+		*variables = frame.pop().unwrap_or_default();
+
+		match control_flow? {
+			ControlFlow::Next => Ok(None),
+			ControlFlow::Return(value) => Ok(value),
+			ControlFlow::Break | ControlFlow::Continue => unreachable!("break or continue outside of loop"),
+		}
+	}
+
+	/// Evaluates the top-level constants that have not been evaluated yet.
+	fn evaluate_constants(&mut self) -> Result<(), Error> {
+		// Constants may only refer to constants declared before them
+		for const_declaration in std::mem::take(&mut self.const_declarations) {
+			let value = self.expression(&const_declaration.value)?;
+			self.constants.insert(const_declaration.name.value, value);
+		}
+		Ok(())
+	}
+
+	/// Calls the `function` with the given `args` and returns the value it returned.
+	#[tracing::instrument(skip_all, fields(name = function.prototype.name.as_str()))]
+	fn call(&mut self, function: &FunctionDefinition, args: Vec<Value>) -> Result<Option<Value>, Error> {
+		let arguments = iter::zip(&function.prototype.args, args).map(|(arg, value)| (arg.name.value, value));
+		self.call_stack.push(vec![arguments.collect()]);
+		let control_flow = self.instructions(&function.body);
+		self.call_stack.pop();
+
+		match control_flow? {
+			ControlFlow::Next => Ok(None),
+			ControlFlow::Return(value) => Ok(value),
+			ControlFlow::Break | ControlFlow::Continue => unreachable!("break or continue outside of loop"),
+		}
+	}
+
+	/// Executes a block, whose variables are dropped at the end of the block.
+	fn block(&mut self, block: &Block) -> Result<ControlFlow, Error> {
+		self.frame().push(Scope::new());
+		let control_flow = self.instructions(block);
+		self.frame().pop();
+		control_flow
+	}
+
+	/// Executes the instructions one after another until one of them returns, breaks or continues.
+	fn instructions(&mut self, instructions: &[ast::Instruction]) -> Result<ControlFlow, Error> {
+		for instruction in instructions {
+			match self.instruction(instruction)? {
+				ControlFlow::Next => (),
+				control_flow => return Ok(control_flow),
+			}
+		}
+		Ok(ControlFlow::Next)
+	}
+
+	/// Executes an instruction by calling the appropriate method for the instruction type.
+	fn instruction(&mut self, instruction: &ast::Instruction) -> Result<ControlFlow, Error> {
+		match instruction {
+			ast::Instruction::Expression(expression) => self.expression_instruction(expression),
+			ast::Instruction::Statement(statement) => self.statement(statement),
+			ast::Instruction::IfElse(if_else) => self.if_else(if_else),
+			ast::Instruction::WhileLoop(while_loop) => self.while_loop(while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(for_loop),
+			ast::Instruction::Match(match_) => self.match_(match_),
+			ast::Instruction::Comment(_) => Ok(ControlFlow::Next),
+		}
+	}
+
+	/// Evaluates an expression for its side effects, e.g. a call of a function without return value.
+	fn expression_instruction(&mut self, expression: &Expression) -> Result<ControlFlow, Error> {
+		match expression {
+			Expression::FunctionCall(function_call) => {
+				self.function_call(function_call)?;
+			},
+			expression => {
+				self.expression(expression)?;
+			},
+		}
+		Ok(ControlFlow::Next)
+	}
+
+	/// Executes a statement.
+	fn statement(&mut self, statement: &ast::Statement) -> Result<ControlFlow, Error> {
+		match statement {
+			ast::Statement::VariableDeclaration(variable_declaration) => {
+				let value = self.expression(&variable_declaration.value)?;
+				self.scope().insert(variable_declaration.name.value, value);
+				Ok(ControlFlow::Next)
+			},
+			ast::Statement::ConstDeclaration(const_declaration) => {
+				let value = self.expression(&const_declaration.value)?;
+				self.scope().insert(const_declaration.name.value, value);
+				Ok(ControlFlow::Next)
+			},
+			ast::Statement::Assignment(assignment) => {
+				self.assignment(assignment)?;
+				Ok(ControlFlow::Next)
+			},
+			ast::Statement::Return(expression, _) => {
+				let value = expression.as_ref().map(|expression| self.expression(expression)).transpose()?;
+				Ok(ControlFlow::Return(value))
+			},
+			ast::Statement::Break(_) => Ok(ControlFlow::Break),
+			ast::Statement::Continue(_) => Ok(ControlFlow::Continue),
+		}
+	}
+
+	/// Assigns the value of the expression to the already declared variable, or to its field or element. The indices
+	/// of the target are evaluated before the value, and only once in a compound assignment, which reads the target
+	/// before evaluating the value.
+	fn assignment(&mut self, assignment: &ast::statement::Assignment) -> Result<(), Error> {
+		let mut indices = Vec::new();
+		self.evaluate_indices(&assignment.target, &mut indices)?;
+		let value = match &assignment.operator {
+			None => self.expression(&assignment.value)?,
+			Some(operator) => {
+				let current = self.place(&assignment.target, &mut indices.iter().cloned())?.clone();
+				let value = self.expression(&assignment.value)?;
+				binary_operation(operator, current, value)?
+			},
+		};
+		*self.place(&assignment.target, &mut indices.into_iter())? = value;
+		Ok(())
+	}
+
+	/// Evaluates the indices of the target of an assignment, starting with the outermost one, like `i` in `a[i][j]`.
+	///
+	/// A pointer that is dereferenced is evaluated, too, which fails, because the only pointer of the interpreter is
+	/// `null`.
+	fn evaluate_indices(&mut self, lvalue: &LValue, indices: &mut Vec<Value>) -> Result<(), Error> {
+		match lvalue {
+			LValue::Dereference { .. } => self.expression(&lvalue.clone().into()).map(|_| ()),
+			LValue::Variable(_) => Ok(()),
+			LValue::FieldAccess { base, .. } => self.evaluate_indices(base, indices),
+			LValue::Index { base, index } => {
+				self.evaluate_indices(base, indices)?;
+				indices.push(self.expression(index)?);
+				Ok(())
+			},
+		}
+	}
+
+	/// Looks up the value the target of an assignment refers to mutably, with the already evaluated `indices`.
+	fn place(&mut self, lvalue: &LValue, indices: &mut impl Iterator<Item = Value>) -> Result<&mut Value, Error> {
+		match lvalue {
+			LValue::Variable(name) => self.variable_mut(name),
+			LValue::FieldAccess { base, field } => match self.place(base, indices)? {
+				Value::Struct(struct_) => {
+					let Some(i) = struct_.fields.iter().position(|(name, _)| *name == field.value) else {
+						return Err(Error::UnknownField {
+							field: field.clone(),
+							value: Value::Struct(struct_.clone()),
+						});
+					};
+					Ok(&mut struct_.fields[i].1)
+				},
+				value => Err(Error::NoStruct { field: field.clone(), value: value.clone() }),
+			},
+			LValue::Dereference { position, .. } => Err(Error::NullDereference { position: position.clone() }),
+			LValue::Index { base, index } => {
+				let array = self.place(base, indices)?;
+				let index_value = indices.next().expect("Indices are evaluated before");
+				match array {
+					Value::Array(elements) => {
+						let i = array_index(index_value, elements.len(), &index.source_position())?;
+						Ok(&mut elements[i])
+					},
+					array => Err(Error::NoArray { position: base.source_position(), value: array.clone() }),
+				}
+			},
+		}
+	}
+
+	/// Executes either the `if` or the `else` block, depending on the condition.
+	fn if_else(&mut self, if_else: &ast::IfElse) -> Result<ControlFlow, Error> {
+		if self.expression(&if_else.condition)?.is_truthy() {
+			self.block(&if_else.if_true)
+		} else {
+			self.block(&if_else.if_false)
+		}
+	}
+
+	/// Executes the body of the first arm with a pattern equal to the value, or else the body of the `_` arm.
+	fn match_(&mut self, match_: &ast::Match) -> Result<ControlFlow, Error> {
+		let value = self.expression(&match_.value)?;
+		for arm in &match_.arms {
+			for pattern in &arm.patterns {
+				if self.expression(pattern)? == value {
+					return self.block(&arm.body);
+				}
+			}
+		}
+		match &match_.default {
+			Some(default) => self.block(&default.body),
+			None => Ok(ControlFlow::Next),
+		}
+	}
+
+	/// Executes the body of the while loop as long as its condition is true.
+	fn while_loop(&mut self, while_loop: &ast::WhileLoop) -> Result<ControlFlow, Error> {
+		while self.expression(&while_loop.condition)?.is_truthy() {
+			match self.block(&while_loop.body)? {
+				ControlFlow::Next | ControlFlow::Continue => (),
+				ControlFlow::Break => break,
+				ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+			}
+		}
+		Ok(ControlFlow::Next)
+	}
+
+	/// Executes a for loop, whose initialization declares variables only visible inside the loop.
+	fn for_loop(&mut self, for_loop: &ast::ForLoop) -> Result<ControlFlow, Error> {
+		self.frame().push(Scope::new());
+		let control_flow = self.for_loop_iterations(for_loop);
+		self.frame().pop();
+		control_flow
+	}
+
+	/// Executes the initialization, and then the body and step of the for loop as long as its condition is true.
+	fn for_loop_iterations(&mut self, for_loop: &ast::ForLoop) -> Result<ControlFlow, Error> {
+		self.statement(&for_loop.initialization)?;
+		while self.expression(&for_loop.condition)?.is_truthy() {
+			match self.block(&for_loop.body)? {
+				// The step is executed after `continue` too
+				ControlFlow::Next | ControlFlow::Continue => (),
+				ControlFlow::Break => break,
+				ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+			}
+			self.assignment(&for_loop.step)?;
+		}
+		Ok(ControlFlow::Next)
+	}
+
+	/// Evaluates an expression to a value.
+	fn expression(&mut self, expression: &Expression) -> Result<Value, Error> {
+		match expression {
+			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::UnaryExpression(unary_expression) => self.unary_expression(unary_expression),
+			Expression::Cast(cast) => self.cast(cast),
+			Expression::FunctionCall(function_call) => self
+				.function_call(function_call)?
+				.ok_or_else(|| Error::MissingReturnValue { function_call: Box::new(function_call.clone()) }),
+			Expression::FieldAccess(field_access) => self.field_access(field_access),
+			Expression::StructLiteral(struct_literal) => {
+				let fields = struct_literal
+					.fields
+					.iter()
+					.map(|field| Ok((field.name.value, self.expression(&field.value)?)))
+					.collect::<Result<_, _>>()?;
+				Ok(Value::Struct(Box::new(StructValue { name: struct_literal.name.value, fields })))
+			},
+			Expression::ArrayLiteral(array_literal) => Ok(Value::Array(
+				array_literal.elements.iter().map(|element| self.expression(element)).collect::<Result<_, _>>()?,
+			)),
+			Expression::IndexExpression(index_expression) => self.index_expression(index_expression),
+			Expression::If(if_expression) => match self.expression(&if_expression.condition)?.is_truthy() {
+				true => self.expression(&if_expression.if_true),
+				false => self.expression(&if_expression.if_false),
+			},
+			Expression::Number(number) => Ok(Value::from(&number.value)),
+			Expression::Bool(boolean) => Ok(Value::Bool(boolean.value)),
+			Expression::StringLiteral(string) => Ok(Value::Str(string.value.clone())),
+			Expression::Char(char) => Ok(Value::Char(char.value)),
+			Expression::Null(_) => Ok(Value::Null),
+			Expression::Variable(variable) => self.variable(variable).cloned(),
+		}
+	}
+
+	/// Evaluates both sides of the binary expression and applies the operator to them.
+	fn binary_expression(&mut self, binary_expression: &BinaryExpression) -> Result<Value, Error> {
+		if let BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr = binary_expression.operator.value {
+			return self.logical_expression(binary_expression);
+		}
+		let lhs = self.expression(&binary_expression.lhs)?;
+		let rhs = self.expression(&binary_expression.rhs)?;
+		binary_operation(&binary_expression.operator, lhs, rhs)
+	}
+
+	/// Evaluates the struct and looks up the value of the accessed field in it.
+	fn field_access(&mut self, field_access: &FieldAccess) -> Result<Value, Error> {
+		let value = self.expression(&field_access.base)?;
+		let Value::Struct(struct_) = &value else {
+			return Err(Error::NoStruct { field: field_access.field.clone(), value });
+		};
+		match struct_.fields.iter().find(|(name, _)| *name == field_access.field.value) {
+			Some((_, field)) => Ok(field.clone()),
+			None => Err(Error::UnknownField { field: field_access.field.clone(), value }),
+		}
+	}
+
+	/// Evaluates the array and the index and returns the element at the index.
+	fn index_expression(&mut self, index_expression: &IndexExpression) -> Result<Value, Error> {
+		let array = self.expression(&index_expression.base)?;
+		let index = self.expression(&index_expression.index)?;
+		let Value::Array(elements) = array else {
+			return Err(Error::NoArray { position: index_expression.base.source_position(), value: array });
+		};
+		let index = array_index(index, elements.len(), &index_expression.index.source_position())?;
+		Ok(elements.into_vec().swap_remove(index))
+	}
+
+	/// Evaluates the operand of the unary expression and applies the operator to it.
+	fn unary_expression(&mut self, unary_expression: &UnaryExpression) -> Result<Value, Error> {
+		let operator = &unary_expression.operator;
+		match (operator.value, self.expression(&unary_expression.operand)?) {
+			(UnaryOperator::Negate, Value::Int(int)) => Ok(Value::Int(int.wrapping_neg())),
+			(UnaryOperator::Negate, Value::Float(float)) => Ok(Value::Float(-float)),
+			(UnaryOperator::Not, Value::Bool(boolean)) => Ok(Value::Bool(!boolean)),
+			(UnaryOperator::Dereference, Value::Null) => {
+				Err(Error::NullDereference { position: operator.position.clone() })
+			},
+			(_, operand) => Err(Error::InvalidOperand { operator: operator.clone(), operand }),
+		}
+	}
+
+	/// Evaluates the expression of the cast and converts its value. Like in Rust, a float is rounded towards zero when
+	/// converted to an int, saturating at the bounds of the int.
+	fn cast(&mut self, cast: &Cast) -> Result<Value, Error> {
+		match (self.expression(&cast.expression)?, &cast.data_type.value) {
+			(Value::Int(int), DataType::Basic(BasicDataType::Float)) => Ok(Value::Float(int as f64)),
+			(Value::Float(float), DataType::Basic(BasicDataType::Int)) => Ok(Value::Int(float as i64)),
+			(value, DataType::Basic(BasicDataType::Int)) if matches!(value, Value::Int(_)) => Ok(value),
+			(value, DataType::Basic(BasicDataType::Float)) if matches!(value, Value::Float(_)) => Ok(value),
+			(value, to) => Err(Error::InvalidCast { position: cast.source_position(), value, to: to.clone() }),
+		}
+	}
+
+	/// Evaluates a logical `and`/`or`, whose rhs is only evaluated if the lhs does not already determine the result.
+	fn logical_expression(&mut self, binary_expression: &BinaryExpression) -> Result<Value, Error> {
+		let operator = &binary_expression.operator;
+		let lhs = self.expression(&binary_expression.lhs)?;
+		match (&operator.value, &lhs) {
+			(BinaryOperator::LogicalAnd, Value::Bool(false)) => return Ok(Value::Bool(false)),
+			(BinaryOperator::LogicalOr, Value::Bool(true)) => return Ok(Value::Bool(true)),
+			_ => (),
+		}
+		match (lhs, self.expression(&binary_expression.rhs)?) {
+			(Value::Bool(_), Value::Bool(rhs)) => Ok(Value::Bool(rhs)),
+			(lhs, rhs) => Err(Error::TypeMismatch { operator: operator.clone(), lhs, rhs }),
+		}
+	}
+
+	/// Evaluates the parameters and calls the function with them.
+	fn function_call(&mut self, function_call: &FunctionCall) -> Result<Option<Value>, Error> {
+		let function = self.functions.get(&function_call.name.value).cloned();
+		let prototype = match &function {
+			Some(function) => &function.prototype,
+			None => match self.externs.get(&function_call.name.value) {
+				Some(prototype) => prototype,
+				None => return self.builtin_call(function_call),
+			},
+		};
+
+		if function_call.params.len() != prototype.args.len() {
+			return Err(Error::ArgumentCountMismatch {
+				expected: prototype.args.len(),
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+		let returns_value = prototype.return_type.is_some();
+
+		let args: Vec<Value> =
+			function_call.params.iter().map(|param| self.expression(param)).collect::<Result<_, _>>()?;
+		match function {
+			Some(function) => self.call(&function, args),
+			None => {
+				let return_value = native::call(&function_call.name.value, &args)
+					.ok_or_else(|| Error::UnsupportedExtern { function_call: Box::new(function_call.clone()) })?;
+				// The C function returns a value even if the declaration ignores it
+				Ok(Some(return_value).filter(|_| returns_value))
+			},
+		}
+	}
+
+	/// Executes the call of a [`Builtin`].
+	fn builtin_call(&mut self, function_call: &FunctionCall) -> Result<Option<Value>, Error> {
+		let builtin = Builtin::from_name(&function_call.name)
+			.ok_or_else(|| Error::UndefinedFunctionCall { function_call: Box::new(function_call.clone()) })?;
+		if function_call.params.len() != builtin.arity() {
+			return Err(Error::ArgumentCountMismatch {
+				expected: builtin.arity(),
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+		let args: Vec<Value> =
+			function_call.params.iter().map(|param| self.expression(param)).collect::<Result<_, _>>()?;
+		Ok(builtin.call(&args))
+	}
+
+	/// Looks up the value of a variable, starting at the innermost scope and ending at the top-level constants.
+	fn variable(&self, name: &PositionContainer<Symbol>) -> Result<&Value, Error> {
+		// Top-level constants are evaluated outside of any function call
+		let local =
+			self.call_stack.last().and_then(|frame| frame.iter().rev().find_map(|scope| scope.get(&name.value)));
+		local
+			.or_else(|| self.constants.get(&name.value))
+			.ok_or_else(|| Error::UndeclaredVariable { name: name.clone() })
+	}
+
+	/// Looks up the value of a variable mutably, starting at the innermost scope.
+	fn variable_mut(&mut self, name: &PositionContainer<Symbol>) -> Result<&mut Value, Error> {
+		self.frame()
+			.iter_mut()
+			.rev()
+			.find_map(|scope| scope.get_mut(&name.value))
+			.ok_or_else(|| Error::UndeclaredVariable { name: name.clone() })
+	}
+
+	/// The call stack frame of the currently executed function.
+	fn frame(&mut self) -> &mut CallStackFrame {
+		self.call_stack.last_mut().expect("No function is being executed")
+	}
+
+	/// The innermost scope of the currently executed function.
+	fn scope(&mut self) -> &mut Scope {
+		self.frame().last_mut().expect("Function call without scope")
+	}
+}
+
+/// Checks that the `index` is an integer pointing into an array with `length` elements.
+fn array_index(index: Value, length: usize, position: &SourcePositionRange) -> Result<usize, Error> {
+	match index {
+		Value::Int(int) if usize::try_from(int).is_ok_and(|int| int < length) => Ok(int as usize),
+		Value::Int(int) => Err(Error::IndexOutOfBounds { position: position.clone(), index: int, length }),
+		index => Err(Error::InvalidIndex { position: position.clone(), index }),
+	}
+}
+
+/// Applies the `operator` to the values of its operands, except for the logical operators, which evaluate their rhs
+/// only if needed.
+fn binary_operation(operator: &PositionContainer<BinaryOperator>, lhs: Value, rhs: Value) -> Result<Value, Error> {
+	match (lhs, rhs) {
+		(Value::Int(lhs), Value::Int(rhs)) => int_operation(operator, lhs, rhs),
+		(Value::Float(lhs), Value::Float(rhs)) if !operator.is_bitwise() => Ok(float_operation(operator, lhs, rhs)),
+		// Characters are compared by their code points
+		(Value::Char(lhs), Value::Char(rhs)) if operator.is_comparison() => {
+			int_operation(operator, lhs as i64, rhs as i64)
+		},
+		(Value::Bool(lhs), Value::Bool(rhs)) if matches!(operator.value, BinaryOperator::Equal) => {
+			Ok(Value::Bool(lhs == rhs))
+		},
+		(Value::Bool(lhs), Value::Bool(rhs)) if matches!(operator.value, BinaryOperator::NotEqual) => {
+			Ok(Value::Bool(lhs != rhs))
+		},
+		(Value::Null, Value::Null) if matches!(operator.value, BinaryOperator::Equal) => Ok(Value::Bool(true)),
+		(Value::Null, Value::Null) if matches!(operator.value, BinaryOperator::NotEqual) => Ok(Value::Bool(false)),
+		(lhs, rhs) => Err(Error::TypeMismatch { operator: operator.clone(), lhs, rhs }),
+	}
+}
+
+/// Applies the `operator` to two integers. Arithmetic wraps around on overflow.
+fn int_operation(operator: &PositionContainer<BinaryOperator>, lhs: i64, rhs: i64) -> Result<Value, Error> {
+	Ok(match operator.value {
+		BinaryOperator::Add => Value::Int(lhs.wrapping_add(rhs)),
+		BinaryOperator::Subtract => Value::Int(lhs.wrapping_sub(rhs)),
+		BinaryOperator::Multiply => Value::Int(lhs.wrapping_mul(rhs)),
+		BinaryOperator::Divide if rhs == 0 => {
+			return Err(Error::DivisionByZero { position: operator.position.clone() })
+		},
+		BinaryOperator::Divide => Value::Int(lhs.wrapping_div(rhs)),
+		BinaryOperator::Power => Value::Int(builtin::int_power(lhs, rhs)),
+		BinaryOperator::BitOr => Value::Int(lhs | rhs),
+		BinaryOperator::BitAnd => Value::Int(lhs & rhs),
+		BinaryOperator::BitXor => Value::Int(lhs ^ rhs),
+		// Like WASM and Cranelift, only the lowest six bits of the shift amount are used
+		BinaryOperator::ShiftLeft => Value::Int(lhs.wrapping_shl(rhs as u32)),
+		BinaryOperator::ShiftRight => Value::Int(lhs.wrapping_shr(rhs as u32)),
+		BinaryOperator::Less => Value::Bool(lhs < rhs),
+		BinaryOperator::Greater => Value::Bool(lhs > rhs),
+		BinaryOperator::LessEqual => Value::Bool(lhs <= rhs),
+		BinaryOperator::GreaterEqual => Value::Bool(lhs >= rhs),
+		BinaryOperator::Equal => Value::Bool(lhs == rhs),
+		BinaryOperator::NotEqual => Value::Bool(lhs != rhs),
+		BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+			unreachable!("Logical operators are evaluated lazily")
+		},
+	})
+}
+
+/// Applies the `operator` to two floats.
+fn float_operation(operator: &PositionContainer<BinaryOperator>, lhs: f64, rhs: f64) -> Value {
+	match operator.value {
+		BinaryOperator::Add => Value::Float(lhs + rhs),
+		BinaryOperator::Subtract => Value::Float(lhs - rhs),
+		BinaryOperator::Multiply => Value::Float(lhs * rhs),
+		BinaryOperator::Divide => Value::Float(lhs / rhs),
+		BinaryOperator::Power => Value::Float(lhs.powf(rhs)),
+		BinaryOperator::BitOr
+		| BinaryOperator::BitAnd
+		| BinaryOperator::BitXor
+		| BinaryOperator::ShiftLeft
+		| BinaryOperator::ShiftRight => unreachable!("Bitwise operators are only applied to integers"),
+		BinaryOperator::Less => Value::Bool(lhs < rhs),
+		BinaryOperator::Greater => Value::Bool(lhs > rhs),
+		BinaryOperator::LessEqual => Value::Bool(lhs <= rhs),
+		BinaryOperator::GreaterEqual => Value::Bool(lhs >= rhs),
+		BinaryOperator::Equal => Value::Bool(lhs == rhs),
+		BinaryOperator::NotEqual => Value::Bool(lhs != rhs),
+		BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+			unreachable!("Logical operators are evaluated lazily")
+		},
+	}
+}
@@ -0,0 +1,38 @@
+//! Native implementations of C functions, which programs call after declaring them as extern functions like
+//! `extern putchar(c: int): int`.
+
+use std::io::{self, Read, Write};
+
+use crate::interpreter::Value;
+
+/// Calls the C function `name` with the `args`, like a compiled program would do.
+///
+/// Returns [`None`] if the interpreter does not implement the function, or if the `args` do not match its C signature.
+pub fn call(name: &str, args: &[Value]) -> Option<Value> {
+	match (name, args) {
+		("putchar", [Value::Int(c)]) => Some(putchar(*c as u8)),
+		// Characters are passed as their code point, like in the C emitter
+		("putchar", [Value::Char(c)]) => Some(putchar(*c as u8)),
+		("getchar", []) => {
+			let mut byte = [0];
+			Some(Value::Int(match io::stdin().read(&mut byte) {
+				Ok(1) => byte[0].into(),
+				_ => -1, // EOF
+			}))
+		},
+		("puts", [Value::Str(string)]) => Some(Value::Int(match writeln!(io::stdout(), "{}", string) {
+			Ok(()) => 0,
+			Err(_) => -1, // EOF
+		})),
+		("abs", [Value::Int(int)]) => Some(Value::Int(int.wrapping_abs())),
+		_ => None,
+	}
+}
+
+/// Writes the byte `c` to stdout and returns it, or `-1` on error.
+fn putchar(c: u8) -> Value {
+	match io::stdout().write_all(&[c]) {
+		Ok(()) => Value::Int(c.into()),
+		Err(_) => Value::Int(-1), // EOF
+	}
+}
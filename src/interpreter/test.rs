@@ -0,0 +1,581 @@
+use std::collections::HashMap;
+
+use crate::{
+	ast,
+	interpreter::{Error, Interpreter, Value},
+	parser,
+};
+
+/// Tests that arithmetic respects operator precedence.
+#[test]
+fn test_arithmetic() {
+	assert_eq!(interpret("def main(): int {\n\treturn 1 + 2 * 3 - 4 / 2\n}"), Ok(Some(Value::Int(5))));
+}
+
+/// Tests that functions can call other functions recursively.
+#[test]
+fn test_recursive_function_call() {
+	let source_code = "
+		def factorial(n: int): int {
+			if n < 2 {
+				return 1
+			}
+			var m: int = n
+			m = m - 1
+			return n * factorial(m)
+		}
+		def main(): int {
+			return factorial(5)
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(120))));
+}
+
+/// Tests that a while loop can modify variables declared outside of it.
+#[test]
+fn test_while_loop() {
+	let source_code = "
+		def main(): float {
+			var i: int = 0
+			var sum: float = 0.0
+			while i < 4 {
+				sum = sum + 0.5
+				i = i + 1
+			}
+			return sum
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Float(2.0))));
+}
+
+/// Tests that a for loop runs its step after every iteration of the body.
+#[test]
+fn test_for_loop() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 5; i = i + 1 {
+				sum = sum + i
+			}
+			return sum
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(10))));
+}
+
+/// Tests that only the first branch of an `else if` chain with a true condition is executed.
+#[test]
+fn test_else_if_chain() {
+	let source_code = "
+		def classify(x: int): int {
+			if x < 0 {
+				return 1
+			} else if x = 0 {
+				return 2
+			} else if x < 10 {
+				return 3
+			} else {
+				return 4
+			}
+		}
+		def main(): int {
+			return (classify((0 - 5)) * 1000) + (((classify(0) * 100) + (classify(5) * 10)) + classify(50))
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(1234))));
+}
+
+/// Tests that `continue` skips the rest of the loop body, but not the step, and that `break` leaves the loop.
+#[test]
+fn test_break_continue() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 100; i = i + 1 {
+				if i = 2 {
+					continue
+				}
+				if i = 5 {
+					break
+				}
+				sum = sum + i
+			}
+			var j: int = 0
+			while true {
+				j = j + 1
+				if j < 10 {
+					continue
+				}
+				break
+			}
+			return sum + j
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(18))));
+}
+
+/// Tests that compound assignments apply their operator to the whole assigned expression, also to elements of arrays
+/// at an index computed by a call.
+#[test]
+fn test_compound_assignment() {
+	let source_code = "
+		def f(): int {
+			return 1
+		}
+		def main(): int {
+			var x: int = 0
+			for var i: int = 0; i < 4; i += 1 {
+				x += i
+			}
+			x *= 1 + 2
+			x -= 2
+			x /= 4
+			var a = [1, 2, 3]
+			a[f()] *= x + 1
+			return a[1]
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(10))));
+}
+
+/// Tests that a bare `return` leaves a function without return type, also from within a loop.
+#[test]
+fn test_bare_return() {
+	let source_code = "
+		def count(n: int) {
+			var i: int = 0
+			while true {
+				if i = n {
+					return
+				}
+				i += 1
+			}
+		}
+		def main(): int {
+			count(3)
+			return 1
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(1))));
+	assert_eq!(interpret("def main() {\n\treturn;\n}"), Ok(None));
+}
+
+/// Tests that top-level constants can be used in all functions and refer to constants declared before them.
+#[test]
+fn test_const() {
+	let source_code = "
+		def main(): int {
+			const offset = 2
+			return area() + offset
+		}
+		const WIDTH = 4
+		const HEIGHT: int = WIDTH * 2 + 1
+		def area(): int {
+			return WIDTH * HEIGHT
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(38))));
+}
+
+/// Tests that comments are allowed between top-level nodes, struct fields and instructions, also after a statement.
+#[test]
+fn test_comments() {
+	let source_code = "
+		# A point
+		struct Point {
+			# The x coordinate
+			x: int
+		}
+		def main(): int {
+			# Start at one
+			var p: Point = Point { x: 1 }
+			if p.x = 1 {
+				return p.x + 1 # Two
+			}
+			return p.x
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(2))));
+}
+
+/// Tests that comparisons produce booleans, which can be compared for equality.
+#[test]
+fn test_bool() {
+	let source_code = "
+		def main(): bool {
+			var small: bool = (1 < 2)
+			return small = true
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Bool(true))));
+}
+
+/// Tests that the rhs of `and`/`or` is not evaluated if the lhs already determines the result.
+#[test]
+fn test_logical_short_circuit() {
+	let source_code = "
+		def main(): bool {
+			var zero: int = 0
+			var a: bool = (false and ((1 / zero) = 0))
+			var b: bool = (true or ((1 / zero) = 0))
+			return (a = false) and (b and ((1 < 2) or false))
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Bool(true))));
+}
+
+/// Tests that unary operators bind stronger than binary ones.
+#[test]
+fn test_unary_expression() {
+	let source_code = "
+		def main(): int {
+			var x: int = 3
+			var done: bool = !(x < 2)
+			if !done {
+				return 0
+			}
+			return -x * 2 - -x
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(-3))));
+}
+
+/// Tests that `as` converts between int and float, rounding towards zero, and binds stronger than binary operators.
+#[test]
+fn test_cast() {
+	let source_code = "
+		def main(): float {
+			var n: int = 7
+			var half: int = (-n as float / 2.0) as int
+			return half as float + n as float
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Float(4.0))));
+	assert_eq!(interpret("def main(): int {\n\treturn -2.9 as int\n}"), Ok(Some(Value::Int(-2))));
+}
+
+/// Tests that `<=` and `>=` include equality, unlike `<` and `>`.
+#[test]
+fn test_less_greater_equal() {
+	let source_code = "
+		def main(): bool {
+			var i: int = 3
+			return (i <= 3 and i >= 3) and !(i < 3 or i > 3) and (2.5 <= 2.0) = false
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Bool(true))));
+}
+
+/// Tests the bitwise operators on integers and that shifts bind stronger than `&`, `^` and `|`.
+#[test]
+fn test_bitwise_operators() {
+	let source_code = "
+		def main(): int {
+			var x: int = 12
+			var minus_sixteen: int = -16
+			return (x & 10) + (x | 3) + (x ^ 5) + (1 << 3 | 1) + (minus_sixteen >> 2)
+		}
+	";
+	// 8 + 15 + 9 + 9 - 4
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(37))));
+}
+
+/// Tests that `**` is right-associative and binds stronger than `*`, and that negative integer exponents truncate.
+#[test]
+fn test_power() {
+	let source_code = "
+		def main(): int {
+			var minus_one: int = -1
+			return 2 ** 3 ** 2 + 2 * 3 ** 2 + minus_one ** -3 + 2 ** -1 + 2 ** 64
+		}
+	";
+	// 512 + 18 - 1 + 0 + 0, since the power wraps around
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(529))));
+	assert_eq!(interpret("def main(): float {\n\treturn 4.0 ** 0.5 ** 2.0\n}"), Ok(Some(Value::Float(2.0f64.sqrt()))));
+}
+
+/// Tests that an if-expression only evaluates the taken branch, also in an `else if` chain.
+#[test]
+fn test_if_expression() {
+	let source_code = "
+		def sign(x: int): int {
+			return if x < 0 { -1 } else if x = 0 { 0 } else { 1 }
+		}
+		def main(): int {
+			var a: int = 3
+			var b: int = 5
+			var min = if (a < b) { a } else { b }
+			return min * 100 + sign(-7) * 10 + sign(0) + (if true { 1 } else { 1 / 0 })
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(291))));
+}
+
+/// Tests that the arm with a pattern equal to the value is executed, or else the default arm, and that a `break` in an
+/// arm leaves the enclosing loop.
+#[test]
+fn test_match() {
+	let source_code = "
+		const TWO: int = 2
+		def main(): int {
+			var sum: int = 0
+			var i: int = 0
+			while true {
+				i = i + 1
+				match i * 2 {
+					2, (TWO * 2) => {
+						sum = sum + 1
+					}
+					(TWO * 5) => {
+						break
+					}
+					_ => {
+						sum = sum + 10
+					}
+				}
+			}
+			return sum * 100 + i
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(2205))));
+}
+
+/// Tests that variants are compared by identity, cast to their index and matched without a default arm.
+#[test]
+fn test_enum() {
+	let source_code = "
+		enum Direction { North, East, South, West }
+		def turn(direction: Direction): Direction {
+			match direction {
+				Direction.North => {
+					return Direction.East
+				}
+				Direction.East => {
+					return Direction.South
+				}
+				Direction.South => {
+					return Direction.West
+				}
+				Direction.West => {
+					return Direction.North
+				}
+			}
+		}
+		def main(): int {
+			var direction = turn(turn(Direction.West))
+			if direction = Direction.East {
+				return direction as int * 10 + (turn(direction) as int)
+			}
+			return -1
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(12))));
+}
+
+/// Tests that fields of struct literals can be accessed, and that a struct literal in a condition needs parentheses.
+#[test]
+fn test_struct_literal() {
+	let source_code = "
+		struct Point {
+			x: int
+			y: int
+		}
+		struct Line {
+			start: Point
+			end: Point
+		}
+		def main(): int {
+			var line: Line = Line { start: Point { x: 1, y: 2 }, end: Point { y: 4, x: 3 } }
+			if line.end.y = (Point { x: 0, y: 4 }).y {
+				return line.end.x - line.start.x + line.start.y
+			}
+			return 0
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(4))));
+}
+
+/// Tests that fields of structs, also nested ones, and elements of nested arrays can be assigned to.
+#[test]
+fn test_field_and_nested_element_assignment() {
+	let source_code = "
+		struct Point {
+			x: int
+			y: int
+		}
+		struct Line {
+			start: Point
+			end: Point
+		}
+		def main(): int {
+			var line: Line = Line { start: Point { x: 1, y: 2 }, end: Point { x: 3, y: 4 } }
+			line.start.x = 5
+			line.end.y += 10
+			var grid: [[int; 2]; 2] = [[1, 2], [3, 4]]
+			grid[1][0] = line.start.x
+			return (grid[1][0] * line.end.y) + grid[0][1]
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(72))));
+}
+
+/// Tests that `null` pointers are equal to each other.
+#[test]
+fn test_null() {
+	let source_code = "
+		def main(): bool {
+			var p: ptr int = null
+			return p = null and (null =/= p) = false
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Bool(true))));
+}
+
+/// Tests that elements of arrays, also nested ones, can be read and written, and that arrays are copied on assignment.
+#[test]
+fn test_array() {
+	let source_code = "
+		def main(): int {
+			var grid: [[int; 2]; 2] = [[1, 2], [3, 4]]
+			var row: [int; 2] = grid[1]
+			row[0] = 10
+			grid[0] = row
+			row[1] *= 5
+			return grid[0][0] + grid[0][1] + grid[1][0] + grid[1][1] + row[1]
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(41))));
+}
+
+/// Tests that indexing an array out of its bounds is reported.
+#[test]
+fn test_index_out_of_bounds() {
+	assert!(matches!(
+		interpret("def main(): int {\n\tvar a: [int; 2] = [1, 2]\n\treturn a[2]\n}"),
+		Err(Error::IndexOutOfBounds { index: 2, length: 2, .. })
+	));
+}
+
+/// Tests that reading and assigning through the null pointer is reported. It is the only pointer in the interpreter.
+#[test]
+fn test_null_dereference() {
+	assert!(matches!(
+		interpret("def main(): int {\n\tvar p: ptr int = null\n\treturn *p\n}"),
+		Err(Error::NullDereference { .. })
+	));
+	assert!(matches!(
+		interpret("def main() {\n\tvar p: ptr int = null\n\t*p = 1\n}"),
+		Err(Error::NullDereference { .. })
+	));
+}
+
+/// Tests that string literals evaluate to strings with the escape sequences replaced.
+#[test]
+fn test_string_literal() {
+	assert_eq!(
+		interpret("def main(): str {\n\tvar s: str = \"a\\tb\"\n\treturn s\n}"),
+		Ok(Some(Value::Str("a\tb".to_owned())))
+	);
+}
+
+/// Tests that character literals evaluate to characters, which are compared by their code points.
+#[test]
+fn test_char() {
+	let source_code = "
+		def is_lower(c: char): bool {
+			return c >= 'a' and c <= 'z'
+		}
+		def main(): char {
+			if is_lower('q') and !is_lower('Q') and '\\n' =/= '\\t' {
+				return '\\''
+			}
+			return '?'
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Char('\''))));
+}
+
+/// Tests that integer division by zero is reported instead of panicking.
+#[test]
+fn test_division_by_zero() {
+	assert!(matches!(interpret("def main(): int {\n\treturn 1 / 0\n}"), Err(Error::DivisionByZero { .. })));
+}
+
+/// Tests that extern functions are bound to native implementations of the C functions.
+#[test]
+fn test_extern_function() {
+	let source_code = "
+		extern abs(x: int): int
+		def main(): int {
+			return abs(-42)
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(42))));
+}
+
+/// Tests that calling an extern function without native implementation is reported.
+#[test]
+fn test_unsupported_extern_function() {
+	let source_code = "
+		extern sqrt(x: float): float
+		def main(): float {
+			return sqrt(2.0)
+		}
+	";
+	assert!(matches!(interpret(source_code), Err(Error::UnsupportedExtern { .. })));
+}
+
+/// Tests that builtins are executed natively and return their value.
+#[test]
+fn test_builtin_function() {
+	let source_code = "
+		def main(): int {
+			print(\"\")
+			return 42
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Int(42))));
+}
+
+/// Tests that the math functions are mapped to the methods of `f64`.
+#[test]
+fn test_math_builtin() {
+	let source_code = "
+		def main(): float {
+			return math.max(math.abs(-2.0), math.pow(math.sqrt(9.0), 0.5))
+		}
+	";
+	assert_eq!(interpret(source_code), Ok(Some(Value::Float(2.0))));
+}
+
+/// Tests that the variables of a block are kept for the next block.
+#[test]
+fn test_run_block() {
+	let source_code = "
+		def main() {
+			var x = 3
+			x = x + 1
+		}
+		def f() {
+			var y = (x * 2)
+			return y
+		}
+	";
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	let blocks = ast_nodes.into_iter().map(|node| match node {
+		ast::Node::Function(function) => function.body,
+		_ => unreachable!(),
+	});
+
+	let mut interpreter = Interpreter::default();
+	let mut variables = HashMap::new();
+	let return_values = blocks.map(|block| interpreter.run_block(&block, &mut variables)).collect::<Vec<_>>();
+	assert_eq!(return_values, [Ok(None), Ok(Some(Value::Int(8)))]);
+	assert_eq!(variables.len(), 2);
+}
+
+/// Boilerplate code for lexing, parsing and interpreting source code.
+fn interpret(source_code: &str) -> Result<Option<Value>, Error> {
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	Interpreter::new(ast_nodes.into_iter()).run()
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use crate::{ast::expression::NumberKind, symbol::Symbol};
+
+/// A runtime value produced by evaluating an [expression](crate::ast::Expression).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	/// An integer number, like 42.
+	Int(i64),
+	/// A floating point number, like 4.2.
+	Float(f64),
+	/// A boolean, like the result of a comparison.
+	Bool(bool),
+	/// A string, like "hello".
+	Str(String),
+	/// A character, like 'a'.
+	Char(char),
+	/// A struct with the values of its fields, like `Point { x: 1, y: 2 }`. It is boxed to keep values small.
+	Struct(Box<StructValue>),
+	/// An array with its elements, like `[1, 2, 3]`. Its length is fixed, so a boxed slice suffices.
+	Array(Box<[Value]>),
+	/// The null pointer, which is the only pointer value the interpreter can create.
+	Null,
+}
+
+/// The name of a struct and the values of its fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructValue {
+	/// The name of the struct.
+	pub name: Symbol,
+	/// The fields with their values in the order they were given.
+	pub fields: Vec<(Symbol, Value)>,
+}
+
+impl Value {
+	/// Checks whether this value counts as `true` in a condition. Numbers and strings that have not been type checked
+	/// count as `true` if they are non-zero or non-empty.
+	pub fn is_truthy(&self) -> bool {
+		match self {
+			Value::Int(int) => *int != 0,
+			Value::Float(float) => *float != 0.0,
+			Value::Bool(boolean) => *boolean,
+			Value::Str(string) => !string.is_empty(),
+			Value::Char(_) => true,
+			Value::Struct(_) => true,
+			Value::Array(elements) => !elements.is_empty(),
+			Value::Null => false,
+		}
+	}
+}
+
+impl From<&NumberKind> for Value {
+	fn from(number: &NumberKind) -> Self {
+		match *number {
+			NumberKind::Int(int) => Value::Int(int),
+			NumberKind::Float(float) => Value::Float(float),
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Value::Int(int) => write!(f, "{}", int),
+			Value::Float(float) => write!(f, "{}", float),
+			Value::Bool(boolean) => write!(f, "{}", boolean),
+			Value::Str(string) => write!(f, "{}", string),
+			Value::Char(char) => write!(f, "{}", char),
+			Value::Struct(struct_) => {
+				write!(f, "{} {{ ", struct_.name)?;
+				for (i, (field, value)) in struct_.fields.iter().enumerate() {
+					if i != 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}: {}", field, value)?;
+				}
+				write!(f, " }}")
+			},
+			Value::Array(elements) => {
+				write!(f, "[")?;
+				for (i, element) in elements.iter().enumerate() {
+					if i != 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}", element)?;
+				}
+				write!(f, "]")
+			},
+			Value::Null => write!(f, "null"),
+		}
+	}
+}
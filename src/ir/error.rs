@@ -0,0 +1,30 @@
+use crate::{
+	ast::{expression::FunctionCall, statement::DataType},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Errors that occur while [lowering](super::lower) a program to the IR.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+	#[error("{}: UndeclaredVariable: Variable `{name}` is not declared.", name.position)]
+	UndeclaredVariable { name: PositionContainer<Symbol> },
+
+	#[error("{}: UndefinedFunctionCall: Call of function `{}(...)`, but no such function is defined.", function_call.name.position, function_call.name.value)]
+	UndefinedFunctionCall { function_call: Box<FunctionCall> },
+
+	#[error("{}: ArgumentCountMismatch: Function `{}(...)` expects {expected} arguments but {actual} parameters provided", function_call.name.position, function_call.name.value)]
+	ArgumentCountMismatch { expected: usize, actual: usize, function_call: Box<FunctionCall> },
+
+	#[error("{}: MissingReturnValue: Function `{}(...)` has no return value.", function_call.name.position, function_call.name.value)]
+	MissingReturnValue { function_call: Box<FunctionCall> },
+
+	#[error("{}: NoStruct: Field `{}` accessed on {data_type}, which is not a struct.", field.position, field.value)]
+	NoStruct { field: PositionContainer<Symbol>, data_type: DataType },
+
+	#[error("{}: UnknownField: Struct `{struct_name}` has no field `{}`.", field.position, field.value)]
+	UnknownField { field: PositionContainer<Symbol>, struct_name: Symbol },
+
+	#[error("{}: NoArray: {data_type} cannot be indexed, because it is not an array.", position)]
+	NoArray { position: SourcePositionRange, data_type: DataType },
+}
@@ -0,0 +1,701 @@
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{
+	BasicBlock, BlockId, Callee, Error, Function, Instruction, Local, LocalDeclaration, Operand, Program, Projection,
+	Signature, Struct, Terminator,
+};
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryExpression, BinaryOperator, Cast, FunctionCall, IfExpression, UnaryOperator},
+		statement::{BasicDataType, DataType, LValue},
+		Expression, FunctionDefinition,
+	},
+	builtin::Builtin,
+	interpreter::Value,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Lowers all function definitions in `ast_nodes` to a [`Program`] in the IR. Top-level constants are inlined
+/// wherever they are used. The types of the locals are inferred like in the semantic analysis, which should have
+/// checked the program before.
+///
+/// With the `parallel` feature, the functions are lowered in parallel. They are still collected in source order, and
+/// the error of the first function that fails is returned, so that the result is the same as without the feature.
+#[tracing::instrument(skip_all)]
+pub fn lower(ast_nodes: impl Iterator<Item = ast::Node>) -> Result<Program, Error> {
+	let mut constants = HashMap::new();
+	let mut externs = Vec::new();
+	let mut structs = Vec::new();
+	let definitions: Vec<FunctionDefinition> = ast_nodes
+		.filter_map(|ast_node| match ast_node {
+			ast::Node::Function(function) => Some(function),
+			ast::Node::FunctionPrototype(prototype) => {
+				externs.push(Signature::from(&prototype));
+				None
+			},
+			ast::Node::Struct(struct_) => {
+				structs.push(Struct::from(&struct_));
+				None
+			},
+			ast::Node::Const(const_declaration) => {
+				constants.insert(const_declaration.name.value, const_declaration.value);
+				None
+			},
+			ast::Node::Enum(enum_) => {
+				constants.extend(enum_.constants().into_iter().map(|constant| (constant.name.value, constant.value)));
+				None
+			},
+			_ => None,
+		})
+		.collect();
+
+	// Functions may be called before they are defined, so their indices have to be known up front
+	let externs_by_name = externs
+		.iter()
+		.enumerate()
+		.map(|(index, signature)| (signature.name, (Callee::Extern(index), signature.clone())));
+	let functions = definitions
+		.iter()
+		.enumerate()
+		.map(|(index, function)| {
+			(function.prototype.name.value, (Callee::Function(index), Signature::from(&function.prototype)))
+		})
+		.chain(externs_by_name)
+		.collect();
+	let program = Lowering {
+		functions: Arc::new(functions),
+		structs: Arc::new(structs.iter().map(|struct_| (struct_.name, struct_.clone())).collect()),
+		constants: Arc::new(constants),
+		..Lowering::default()
+	};
+
+	// Functions only share the prototypes and constants, so each is lowered independently of the others
+	#[cfg(feature = "parallel")]
+	let definitions = definitions.into_par_iter();
+	#[cfg(not(feature = "parallel"))]
+	let definitions = definitions.into_iter();
+	let functions: Vec<Result<Function, Error>> =
+		definitions.map(|function| program.for_function().function(function)).collect();
+	Ok(Program { functions: functions.into_iter().collect::<Result<_, _>>()?, externs, structs })
+}
+
+#[derive(Debug, Default)]
+struct Lowering {
+	/// Callee and signature of every function and extern function in the program.
+	functions: Arc<HashMap<Symbol, (Callee, Signature)>>,
+	/// All structs in the program by their name, so that the types of fields can be looked up.
+	structs: Arc<HashMap<Symbol, Struct>>,
+	/// Values of the top-level constants, which are lowered wherever a constant is used.
+	constants: Arc<HashMap<Symbol, Expression>>,
+	/// Instructions of the blocks of the function currently being lowered, with the position of each.
+	blocks: Vec<Vec<(Instruction, SourcePositionRange)>>,
+	/// Terminators of the blocks with their position, which are [`None`] until the end of the block is lowered.
+	terminators: Vec<Option<(Terminator, Option<SourcePositionRange>)>>,
+	/// The blocks in the order in which they were started, which is the order of the code they contain.
+	order: Vec<BlockId>,
+	/// The block to which instructions are added.
+	current: BlockId,
+	/// In-scope variables with their local. The innermost scope is the last one.
+	scopes: Vec<HashMap<Symbol, Local>>,
+	/// Locals of the function currently being lowered.
+	locals: Vec<LocalDeclaration>,
+	/// The blocks to which `continue` and `break` jump in the enclosing loops. The innermost loop is the last one.
+	loops: Vec<Loop>,
+	/// Position of the innermost FTL instruction being lowered, which the emitted instructions get.
+	position: Option<SourcePositionRange>,
+}
+
+#[derive(Debug)]
+struct Loop {
+	continue_to: BlockId,
+	break_to: BlockId,
+}
+
+impl Lowering {
+	/// A new lowering of a single function, which shares the functions, structs and constants of the program with
+	/// `self`.
+	fn for_function(&self) -> Self {
+		Self {
+			functions: Arc::clone(&self.functions),
+			structs: Arc::clone(&self.structs),
+			constants: Arc::clone(&self.constants),
+			..Self::default()
+		}
+	}
+
+	fn function(&mut self, function: FunctionDefinition) -> Result<Function, Error> {
+		let entry = self.new_block();
+		self.switch_to(entry);
+		self.scopes.push(HashMap::new());
+		for arg in &function.prototype.args {
+			self.declare_variable(&arg.name, arg.data_type.value.clone());
+		}
+
+		self.block(&function.body)?;
+		self.scopes.pop();
+
+		// Blocks are created before the code jumping to them, so they are brought into the order of their code
+		let mut new_ids = vec![BlockId(0); self.blocks.len()];
+		for (new_id, old_id) in self.order.iter().enumerate() {
+			new_ids[old_id.0] = BlockId(new_id);
+		}
+		let mut blocks: Vec<Option<BasicBlock>> = std::mem::take(&mut self.blocks)
+			.into_iter()
+			.zip(std::mem::take(&mut self.terminators))
+			.map(|(instructions, terminator)| {
+				// Leaving a function that has to return a value this way is caught by the backends
+				let (mut terminator, terminator_position) = terminator.unwrap_or((Terminator::Return(None), None));
+				terminator.map_successors(|target| new_ids[target.0]);
+				let (instructions, positions) = instructions.into_iter().unzip();
+				Some(BasicBlock { instructions, positions, terminator, terminator_position })
+			})
+			.collect();
+		let mut function = Function {
+			name: function.prototype.name.value.to_string(),
+			arity: function.prototype.args.len(),
+			locals: std::mem::take(&mut self.locals),
+			return_type: function.prototype.return_type.map(|return_type| return_type.value),
+			position: function.prototype.position,
+			blocks: self.order.iter().map(|id| blocks[id.0].take().expect("Block started twice")).collect(),
+		};
+		function.remove_unreachable_blocks();
+		Ok(function)
+	}
+
+	/// Lowers the instructions of a block, whose variables are only visible inside the block.
+	fn block(&mut self, block: &ast::Block) -> Result<(), Error> {
+		self.scopes.push(HashMap::new());
+		for instruction in block {
+			self.instruction(instruction)?;
+		}
+		self.scopes.pop();
+		Ok(())
+	}
+
+	fn instruction(&mut self, instruction: &ast::Instruction) -> Result<(), Error> {
+		let enclosing = self.position.replace(instruction.source_position());
+		match instruction {
+			// The value of an expression on its own is not used, so a called function does not have to return one
+			ast::Instruction::Expression(Expression::FunctionCall(function_call)) => {
+				self.function_call(function_call)?;
+			},
+			ast::Instruction::Expression(expression) => {
+				self.expression(expression)?;
+			},
+			ast::Instruction::Statement(statement) => self.statement(statement)?,
+			ast::Instruction::IfElse(if_else) => self.if_else(if_else)?,
+			ast::Instruction::WhileLoop(while_loop) => self.while_loop(while_loop)?,
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(for_loop)?,
+			ast::Instruction::Match(match_) => self.match_(match_)?,
+			ast::Instruction::Comment(_) => (),
+		}
+		self.position = enclosing;
+		Ok(())
+	}
+
+	fn statement(&mut self, statement: &ast::Statement) -> Result<(), Error> {
+		match statement {
+			ast::Statement::VariableDeclaration(variable_declaration) => self.declaration(
+				&variable_declaration.name,
+				&variable_declaration.data_type,
+				&variable_declaration.value,
+			)?,
+			ast::Statement::ConstDeclaration(const_declaration) => {
+				self.declaration(&const_declaration.name, &const_declaration.data_type, &const_declaration.value)?
+			},
+			ast::Statement::Assignment(assignment) => self.assignment(assignment)?,
+			ast::Statement::Return(value, _) => {
+				let value = value.as_ref().map(|value| self.expression(value)).transpose()?;
+				self.terminate(Terminator::Return(value));
+			},
+			ast::Statement::Break(_) => {
+				let target = self.loops.last().expect("break outside of loop").break_to;
+				self.terminate(Terminator::Jump(target));
+			},
+			ast::Statement::Continue(_) => {
+				let target = self.loops.last().expect("continue outside of loop").continue_to;
+				self.terminate(Terminator::Jump(target));
+			},
+		}
+		Ok(())
+	}
+
+	/// Lowers the declaration of a variable or a local constant, whose type is annotated or inferred from the `value`.
+	fn declaration(
+		&mut self,
+		name: &PositionContainer<Symbol>,
+		data_type: &Option<PositionContainer<DataType>>,
+		value: &Expression,
+	) -> Result<(), Error> {
+		let value = self.expression(value)?;
+		let data_type = match data_type {
+			Some(data_type) => data_type.value.clone(),
+			None => self.operand_type(&value),
+		};
+		let dest = self.declare_variable(name, data_type);
+		self.emit(Instruction::Copy { dest, value });
+		Ok(())
+	}
+
+	/// Lowers an assignment to a variable or to a part of it, or through a pointer. The indices of the target are
+	/// evaluated before the value, starting with the outermost one. A compound assignment reads the target with these
+	/// indices before evaluating the value.
+	fn assignment(&mut self, assignment: &ast::statement::Assignment) -> Result<(), Error> {
+		let mut projections = Vec::new();
+		let pointer = self.projections(&assignment.target, &mut projections)?;
+		let current = match &assignment.operator {
+			Some(operator) => {
+				let base = match &pointer {
+					Some(pointer) => self.dereference(pointer.clone()),
+					None => Operand::Local(self.lookup_variable(assignment.target.variable())?),
+				};
+				Some((operator, self.load(base, &projections, &assignment.target.source_position())?))
+			},
+			None => None,
+		};
+		let mut value = self.expression(&assignment.value)?;
+		if let Some((operator, current)) = current {
+			let dest = self.temporary(self.operand_type(&current));
+			self.emit(Instruction::Binary { dest, operator: operator.value.clone(), lhs: current, rhs: value });
+			value = Operand::Local(dest);
+		}
+		if let Some(pointer) = pointer {
+			self.emit(Instruction::StorePointer { pointer, projections, value });
+			return Ok(());
+		}
+		let target = self.lookup_variable(assignment.target.variable())?;
+		self.emit(match projections.is_empty() {
+			true => Instruction::Copy { dest: target, value },
+			false => Instruction::Store { target, projections, value },
+		});
+		Ok(())
+	}
+
+	/// Reads the part of the `base` which the `projections` lead to, e.g. for the target at `position` of a compound
+	/// assignment.
+	fn load(
+		&mut self,
+		base: Operand,
+		projections: &[Projection],
+		position: &SourcePositionRange,
+	) -> Result<Operand, Error> {
+		let mut value = base;
+		for projection in projections {
+			let dest = match projection {
+				Projection::Field(field) => {
+					let dest = self.temporary(self.field_type(&self.operand_type(&value), field)?);
+					self.emit(Instruction::Field { dest, base: value, field: field.clone() });
+					dest
+				},
+				Projection::Index(index) => {
+					let element = match self.operand_type(&value) {
+						DataType::Array { element, .. } => *element,
+						data_type => return Err(Error::NoArray { position: position.clone(), data_type }),
+					};
+					let dest = self.temporary(element);
+					self.emit(Instruction::Index { dest, base: value, index: index.clone() });
+					dest
+				},
+			};
+			value = Operand::Local(dest);
+		}
+		Ok(value)
+	}
+
+	/// Reads the value the `pointer` points to.
+	fn dereference(&mut self, pointer: Operand) -> Operand {
+		let data_type = match self.operand_type(&pointer) {
+			DataType::Pointer(pointee) => pointee.value,
+			data_type => data_type,
+		};
+		let dest = self.temporary(data_type);
+		self.emit(Instruction::Unary { dest, operator: UnaryOperator::Dereference, operand: pointer });
+		Operand::Local(dest)
+	}
+
+	/// Lowers the indices of the target of an assignment and collects the parts of the variable that lead to it. If
+	/// the target is dereferenced, the pointer is returned, and the parts lead from the value it points to instead.
+	fn projections(&mut self, lvalue: &LValue, projections: &mut Vec<Projection>) -> Result<Option<Operand>, Error> {
+		let pointer = match lvalue {
+			LValue::Variable(_) => None,
+			LValue::FieldAccess { base, field } => {
+				let pointer = self.projections(base, projections)?;
+				projections.push(Projection::Field(field.clone()));
+				pointer
+			},
+			LValue::Index { base, index } => {
+				let pointer = self.projections(base, projections)?;
+				let index = self.expression(index)?;
+				projections.push(Projection::Index(index));
+				pointer
+			},
+			// The pointer is a value like any other expression, only what it points to is assigned to
+			LValue::Dereference { base, .. } => Some(self.expression(&Expression::from((**base).clone()))?),
+		};
+		Ok(pointer)
+	}
+
+	fn if_else(&mut self, if_else: &ast::IfElse) -> Result<(), Error> {
+		let condition = self.expression(&if_else.condition)?;
+		let if_true = self.new_block();
+		let end = self.new_block();
+		let if_false = match if_else.if_false.is_empty() {
+			true => end,
+			false => self.new_block(),
+		};
+		self.terminate(Terminator::Branch { condition, if_true, if_false });
+		self.switch_to(if_true);
+		self.block(&if_else.if_true)?;
+		self.terminate(Terminator::Jump(end));
+		if if_false != end {
+			self.switch_to(if_false);
+			self.block(&if_else.if_false)?;
+			self.terminate(Terminator::Jump(end));
+		}
+		self.switch_to(end);
+		Ok(())
+	}
+
+	/// Lowers a match to comparisons of the value with the patterns in turn, which branch to the body of the arm on
+	/// equality and else to the next comparison. The last comparison continues with the body of the `_` arm.
+	fn match_(&mut self, match_: &ast::Match) -> Result<(), Error> {
+		let value = self.expression(&match_.value)?;
+		let mut bodies = Vec::new();
+		for arm in &match_.arms {
+			let body = self.new_block();
+			for pattern in &arm.patterns {
+				let pattern = self.expression(pattern)?;
+				let dest = self.temporary(DataType::Basic(BasicDataType::Bool));
+				self.emit(Instruction::Binary {
+					dest,
+					operator: BinaryOperator::Equal,
+					lhs: value.clone(),
+					rhs: pattern,
+				});
+				let next = self.new_block();
+				self.terminate(Terminator::Branch { condition: Operand::Local(dest), if_true: body, if_false: next });
+				self.switch_to(next);
+			}
+			bodies.push((body, &arm.body));
+		}
+		let end = self.new_block();
+		if let Some(default) = &match_.default {
+			self.block(&default.body)?;
+		}
+		self.terminate(Terminator::Jump(end));
+		for (block, body) in bodies {
+			self.switch_to(block);
+			self.block(body)?;
+			self.terminate(Terminator::Jump(end));
+		}
+		self.switch_to(end);
+		Ok(())
+	}
+
+	fn while_loop(&mut self, while_loop: &ast::WhileLoop) -> Result<(), Error> {
+		let header = self.new_block();
+		let body = self.new_block();
+		let end = self.new_block();
+		self.terminate(Terminator::Jump(header));
+		self.switch_to(header);
+		let condition = self.expression(&while_loop.condition)?;
+		self.terminate(Terminator::Branch { condition, if_true: body, if_false: end });
+		self.switch_to(body);
+		self.loops.push(Loop { continue_to: header, break_to: end });
+		self.block(&while_loop.body)?;
+		self.loops.pop();
+		self.terminate(Terminator::Jump(header));
+		self.switch_to(end);
+		Ok(())
+	}
+
+	fn for_loop(&mut self, for_loop: &ast::ForLoop) -> Result<(), Error> {
+		// The variables of the initialization are only visible inside the loop
+		self.scopes.push(HashMap::new());
+		self.statement(&for_loop.initialization)?;
+		let header = self.new_block();
+		let body = self.new_block();
+		let step = self.new_block();
+		let end = self.new_block();
+		self.terminate(Terminator::Jump(header));
+		self.switch_to(header);
+		let condition = self.expression(&for_loop.condition)?;
+		self.terminate(Terminator::Branch { condition, if_true: body, if_false: end });
+		self.switch_to(body);
+		// `continue` has to execute the step before checking the condition again
+		self.loops.push(Loop { continue_to: step, break_to: end });
+		self.block(&for_loop.body)?;
+		self.loops.pop();
+		self.terminate(Terminator::Jump(step));
+		self.switch_to(step);
+		self.assignment(&for_loop.step)?;
+		self.terminate(Terminator::Jump(header));
+		self.switch_to(end);
+		self.scopes.pop();
+		Ok(())
+	}
+
+	fn expression(&mut self, expression: &Expression) -> Result<Operand, Error> {
+		let dest = match expression {
+			Expression::BinaryExpression(binary_expression) => return self.binary_expression(binary_expression),
+			Expression::UnaryExpression(unary_expression) => {
+				let operand = self.expression(&unary_expression.operand)?;
+				if *unary_expression.operator == UnaryOperator::Dereference {
+					return Ok(self.dereference(operand));
+				}
+				let dest = self.temporary(self.operand_type(&operand));
+				self.emit(Instruction::Unary { dest, operator: *unary_expression.operator, operand });
+				dest
+			},
+			Expression::Cast(cast) => return self.cast(cast),
+			Expression::FunctionCall(function_call) => match self.function_call(function_call)? {
+				Some(dest) => dest,
+				None => return Err(Error::MissingReturnValue { function_call: Box::new(function_call.clone()) }),
+			},
+			Expression::FieldAccess(field_access) => {
+				let base = self.expression(&field_access.base)?;
+				let data_type = self.field_type(&self.operand_type(&base), &field_access.field)?;
+				let dest = self.temporary(data_type);
+				self.emit(Instruction::Field { dest, base, field: field_access.field.clone() });
+				dest
+			},
+			Expression::StructLiteral(struct_literal) => {
+				let fields = struct_literal
+					.fields
+					.iter()
+					.map(|field| Ok((field.name.value, self.expression(&field.value)?)))
+					.collect::<Result<_, Error>>()?;
+				let dest = self.temporary(DataType::Struct(struct_literal.name.value));
+				self.emit(Instruction::MakeStruct { dest, name: struct_literal.name.clone(), fields });
+				dest
+			},
+			Expression::ArrayLiteral(array_literal) => {
+				let elements: Vec<Operand> = array_literal
+					.elements
+					.iter()
+					.map(|element| self.expression(element))
+					.collect::<Result<_, Error>>()?;
+				// The elements of an empty array have no type, which is caught by the semantic analysis
+				let element = elements
+					.iter()
+					.map(|element| self.operand_type(element))
+					.find(|data_type| *data_type != DataType::Null)
+					.unwrap_or(DataType::Null);
+				let dest = self.temporary(DataType::Array { element: Box::new(element), length: elements.len() });
+				self.emit(Instruction::MakeArray { dest, elements });
+				dest
+			},
+			Expression::IndexExpression(index_expression) => {
+				let base = self.expression(&index_expression.base)?;
+				let index = self.expression(&index_expression.index)?;
+				let element = match self.operand_type(&base) {
+					DataType::Array { element, .. } => *element,
+					data_type => return Err(Error::NoArray { position: expression.source_position(), data_type }),
+				};
+				let dest = self.temporary(element);
+				self.emit(Instruction::Index { dest, base, index });
+				dest
+			},
+			Expression::If(if_expression) => return self.if_expression(if_expression),
+			Expression::Number(number) => return Ok(Operand::Constant(Value::from(&number.value))),
+			Expression::Bool(boolean) => return Ok(Operand::Constant(Value::Bool(boolean.value))),
+			Expression::Char(char) => return Ok(Operand::Constant(Value::Char(char.value))),
+			Expression::StringLiteral(string) => return Ok(Operand::Constant(Value::Str(string.value.clone()))),
+			Expression::Null(_) => return Ok(Operand::Constant(Value::Null)),
+			Expression::Variable(variable) => match self.lookup_variable(variable) {
+				Ok(local) => local,
+				// Local variables cannot shadow constants, so this is never a typo of a local variable
+				Err(err) => match self.constants.get(&variable.value).cloned() {
+					Some(constant) => return self.expression(&constant),
+					None => return Err(err),
+				},
+			},
+		};
+		Ok(Operand::Local(dest))
+	}
+
+	fn binary_expression(&mut self, binary_expression: &BinaryExpression) -> Result<Operand, Error> {
+		if let BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr = *binary_expression.operator {
+			return self.logical_expression(binary_expression);
+		}
+		let lhs = self.expression(&binary_expression.lhs)?;
+		let rhs = self.expression(&binary_expression.rhs)?;
+		let data_type = match *binary_expression.operator {
+			BinaryOperator::Less
+			| BinaryOperator::Greater
+			| BinaryOperator::LessEqual
+			| BinaryOperator::GreaterEqual
+			| BinaryOperator::Equal
+			| BinaryOperator::NotEqual => DataType::Basic(BasicDataType::Bool),
+			_ => self.operand_type(&lhs),
+		};
+		let dest = self.temporary(data_type);
+		self.emit(Instruction::Binary { dest, operator: binary_expression.operator.value.clone(), lhs, rhs });
+		Ok(Operand::Local(dest))
+	}
+
+	/// Lowers a logical `and`/`or` to a branch, so that the rhs is only evaluated if the lhs does not already
+	/// determine the result. The result is the lhs unless the rhs is evaluated.
+	fn logical_expression(&mut self, binary_expression: &BinaryExpression) -> Result<Operand, Error> {
+		let lhs = self.expression(&binary_expression.lhs)?;
+		let dest = self.temporary(DataType::Basic(BasicDataType::Bool));
+		self.emit(Instruction::Copy { dest, value: lhs });
+		let rhs_block = self.new_block();
+		let end = self.new_block();
+		let (if_true, if_false) = match *binary_expression.operator {
+			BinaryOperator::LogicalAnd => (rhs_block, end),
+			_ => (end, rhs_block),
+		};
+		self.terminate(Terminator::Branch { condition: Operand::Local(dest), if_true, if_false });
+		self.switch_to(rhs_block);
+		let rhs = self.expression(&binary_expression.rhs)?;
+		self.emit(Instruction::Copy { dest, value: rhs });
+		self.terminate(Terminator::Jump(end));
+		self.switch_to(end);
+		Ok(Operand::Local(dest))
+	}
+
+	/// Lowers an if-expression to blocks for both branches, which copy their value into the same temporary. Its type
+	/// is the one of the branches, unless that is `null`.
+	fn if_expression(&mut self, if_expression: &IfExpression) -> Result<Operand, Error> {
+		let condition = self.expression(&if_expression.condition)?;
+		let dest = self.temporary(DataType::Null);
+		let if_true = self.new_block();
+		let if_false = self.new_block();
+		let end = self.new_block();
+		self.terminate(Terminator::Branch { condition, if_true, if_false });
+		self.switch_to(if_true);
+		let value = self.expression(&if_expression.if_true)?;
+		self.set_type(dest, self.operand_type(&value));
+		self.emit(Instruction::Copy { dest, value });
+		self.terminate(Terminator::Jump(end));
+		self.switch_to(if_false);
+		let value = self.expression(&if_expression.if_false)?;
+		self.set_type(dest, self.operand_type(&value));
+		self.emit(Instruction::Copy { dest, value });
+		self.terminate(Terminator::Jump(end));
+		self.switch_to(end);
+		Ok(Operand::Local(dest))
+	}
+
+	/// Lowers a cast to a conversion of the value. Casts to other types than numbers do not change the value.
+	fn cast(&mut self, cast: &Cast) -> Result<Operand, Error> {
+		let value = self.expression(&cast.expression)?;
+		let data_type = match &cast.data_type.value {
+			DataType::Basic(data_type @ (BasicDataType::Int | BasicDataType::Float)) => data_type.clone(),
+			_ => return Ok(value),
+		};
+		let dest = self.temporary(DataType::Basic(data_type.clone()));
+		self.emit(Instruction::Convert { dest, data_type, value });
+		Ok(Operand::Local(dest))
+	}
+
+	/// Lowers a function call and returns the local holding the return value, if the called function returns one.
+	fn function_call(&mut self, function_call: &FunctionCall) -> Result<Option<Local>, Error> {
+		let (callee, arity, return_type) = match self.functions.get(&function_call.name.value) {
+			Some((callee, signature)) => (*callee, signature.args.len(), signature.return_type.clone()),
+			None => match Builtin::from_name(&function_call.name) {
+				Some(builtin) => (Callee::Builtin(builtin), builtin.arity(), builtin.return_type()),
+				None => return Err(Error::UndefinedFunctionCall { function_call: Box::new(function_call.clone()) }),
+			},
+		};
+
+		if function_call.params.len() != arity {
+			return Err(Error::ArgumentCountMismatch {
+				expected: arity,
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+
+		let args = function_call.params.iter().map(|param| self.expression(param)).collect::<Result<_, Error>>()?;
+		let dest = return_type.map(|return_type| self.temporary(return_type));
+		self.emit(Instruction::Call { dest, callee, args });
+		Ok(dest)
+	}
+
+	/// Looks up the type of the field `name` of the struct type `data_type`.
+	fn field_type(&self, data_type: &DataType, name: &PositionContainer<Symbol>) -> Result<DataType, Error> {
+		let DataType::Struct(struct_name) = data_type else {
+			return Err(Error::NoStruct { field: name.clone(), data_type: data_type.clone() });
+		};
+		self.structs
+			.get(struct_name)
+			.and_then(|struct_| struct_.fields.iter().find(|(field, _)| *field == name.value))
+			.map(|(_, data_type)| data_type.clone())
+			.ok_or_else(|| Error::UnknownField { field: name.clone(), struct_name: *struct_name })
+	}
+
+	/// The type of the value of the `operand`.
+	fn operand_type(&self, operand: &Operand) -> DataType {
+		match operand {
+			Operand::Local(local) => self.locals[local.0].data_type.clone(),
+			Operand::Constant(value) => super::constant_type(value),
+		}
+	}
+
+	/// Assigns a new local to a variable in the current scope.
+	fn declare_variable(&mut self, name: &PositionContainer<Symbol>, data_type: DataType) -> Local {
+		let local = self.temporary(data_type);
+		self.locals[local.0].name = Some(name.value);
+		self.scopes.last_mut().expect("Variable declaration outside of function").insert(name.value, local);
+		local
+	}
+
+	/// Looks up the local of a variable, starting at the innermost scope.
+	fn lookup_variable(&self, name: &PositionContainer<Symbol>) -> Result<Local, Error> {
+		self.scopes
+			.iter()
+			.rev()
+			.find_map(|scope| scope.get(&name.value).copied())
+			.ok_or_else(|| Error::UndeclaredVariable { name: name.clone() })
+	}
+
+	/// A new local for an intermediate result of the `data_type`.
+	fn temporary(&mut self, data_type: DataType) -> Local {
+		self.locals.push(LocalDeclaration { data_type, name: None });
+		Local(self.locals.len() - 1)
+	}
+
+	/// Sets the type of the `local`, unless the new `data_type` is the one of `null`, which converts to all pointers.
+	fn set_type(&mut self, local: Local, data_type: DataType) {
+		if data_type != DataType::Null {
+			self.locals[local.0].data_type = data_type;
+		}
+	}
+
+	/// A new empty block, which is started later by [`Lowering::switch_to`].
+	fn new_block(&mut self) -> BlockId {
+		self.blocks.push(Vec::new());
+		self.terminators.push(None);
+		BlockId(self.blocks.len() - 1)
+	}
+
+	/// Adds the following instructions to the `block`.
+	fn switch_to(&mut self, block: BlockId) {
+		self.order.push(block);
+		self.current = block;
+	}
+
+	/// Adds the `instruction` to the current block. Instructions following a terminator, e.g. after a `return`, are
+	/// added to a new block, which is unreachable.
+	fn emit(&mut self, instruction: Instruction) {
+		if self.terminators[self.current.0].is_some() {
+			let unreachable = self.new_block();
+			self.switch_to(unreachable);
+		}
+		let position = self.position.clone().expect("Instruction outside of function body");
+		self.blocks[self.current.0].push((instruction, position));
+	}
+
+	/// Ends the current block with the `terminator`, unless it already ended, e.g. with a `return` before.
+	fn terminate(&mut self, terminator: Terminator) {
+		let position = self.position.clone();
+		self.terminators[self.current.0].get_or_insert((terminator, position));
+	}
+}
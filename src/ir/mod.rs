@@ -0,0 +1,595 @@
+//! An intermediate representation (IR) of a program between the [AST](crate::ast) and the backends.
+//!
+//! [`lower`] turns the AST into three-address code: Every [`Instruction`] computes at most one operation from
+//! [`Operand`]s, which are constants or numbered [`Local`]s, and stores the result in a local. Nested expressions are
+//! split up into temporary locals, and control flow like `if`, loops and the short-circuiting `and`/`or` into
+//! [`BasicBlock`]s, which are connected by their [`Terminator`]s. Names of variables and functions are resolved, so
+//! backends and optimizations only work with indices instead of walking the AST themselves. Every local has a type,
+//! and every instruction knows the position of the FTL instruction it was lowered from, so that backends can emit
+//! typed code with debug info.
+//!
+//! Locals are mutable, i.e. the IR is not in SSA form: A variable that is assigned in a loop keeps its local.
+
+mod error;
+mod lower;
+#[cfg(test)]
+mod test;
+
+use std::{fmt, iter};
+
+pub use error::Error;
+pub use lower::lower;
+
+use crate::{
+	ast::{
+		self,
+		expression::{BinaryOperator, UnaryOperator},
+		statement::{BasicDataType, DataType},
+	},
+	builtin::Builtin,
+	interpreter::Value,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// A program in the IR, consisting of all its functions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Program {
+	/// All functions of the program. [`Callee::Function`] refers to a function by its index in this list.
+	pub functions: Vec<Function>,
+	/// The functions declared with `extern`, which are implemented outside of the program. [`Callee::Extern`] refers
+	/// to an extern function by its index in this list.
+	pub externs: Vec<Signature>,
+	/// All structs of the program, in the order of their definitions.
+	pub structs: Vec<Struct>,
+}
+
+/// A function in the IR.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+	/// The name of the function.
+	pub name: String,
+	/// The number of arguments. Arguments are the first locals.
+	pub arity: usize,
+	/// The locals including the arguments, the variables and the temporaries. [`Local`] refers to a local by its
+	/// index in this list.
+	pub locals: Vec<LocalDeclaration>,
+	/// The type of the returned value, or [`None`] if the function returns none.
+	pub return_type: Option<DataType>,
+	/// The position of the prototype of the function.
+	pub position: SourcePositionRange,
+	/// The basic blocks of the function body. Execution starts at the first one, which no terminator continues at,
+	/// and the blocks are ordered like the code they were lowered from.
+	pub blocks: Vec<BasicBlock>,
+}
+
+/// The name and the types of the arguments and the return value of a function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+	pub name: Symbol,
+	pub args: Vec<DataType>,
+	pub return_type: Option<DataType>,
+}
+
+impl From<&ast::FunctionPrototype> for Signature {
+	fn from(prototype: &ast::FunctionPrototype) -> Self {
+		Self {
+			name: prototype.name.value,
+			args: prototype.args.iter().map(|arg| arg.data_type.value.clone()).collect(),
+			return_type: prototype.return_type.as_ref().map(|return_type| return_type.value.clone()),
+		}
+	}
+}
+
+/// A struct with the names and types of its fields, in the order of its definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+	pub name: Symbol,
+	pub fields: Vec<(Symbol, DataType)>,
+}
+
+impl From<&ast::Struct> for Struct {
+	fn from(struct_: &ast::Struct) -> Self {
+		Self {
+			name: struct_.name.value,
+			fields: struct_.fields.iter().map(|field| (field.name.value, field.data_type.value.clone())).collect(),
+		}
+	}
+}
+
+/// A local of a [`Function`], which holds an argument, a variable or an intermediate result.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Local(pub usize);
+
+/// The type of a [`Local`], and the name of the argument or variable it holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalDeclaration {
+	pub data_type: DataType,
+	/// The name of the argument or variable, or [`None`] for a temporary.
+	pub name: Option<Symbol>,
+}
+
+/// The index of a [`BasicBlock`] in its [`Function`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct BlockId(pub usize);
+
+/// A sequence of instructions which is always executed from start to end, followed by a [`Terminator`] that decides
+/// where execution continues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+	pub instructions: Vec<Instruction>,
+	/// The position of the FTL instruction each of the [`Self::instructions`] was lowered from.
+	pub positions: Vec<SourcePositionRange>,
+	pub terminator: Terminator,
+	/// The position of the FTL instruction the terminator was lowered from, or [`None`] if the terminator returns at
+	/// the end of the function.
+	pub terminator_position: Option<SourcePositionRange>,
+}
+
+/// An input of an [`Instruction`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+	/// The current value of a local.
+	Local(Local),
+	/// A literal value, or the inlined value of a top-level constant.
+	Constant(Value),
+}
+
+/// A single operation in a [`BasicBlock`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+	/// `dest = value`
+	Copy { dest: Local, value: Operand },
+	/// `dest = lhs operator rhs`. The logical operators `and` and `or` are lowered to branches instead.
+	Binary { dest: Local, operator: BinaryOperator, lhs: Operand, rhs: Operand },
+	/// `dest = operator operand`, where `*operand` reads the value the pointer `operand` points to.
+	Unary { dest: Local, operator: UnaryOperator, operand: Operand },
+	/// `dest = value as data_type`, which converts between `int` and `float`. Casts to other types do not change the
+	/// value and are lowered to the value itself.
+	Convert { dest: Local, data_type: BasicDataType, value: Operand },
+	/// Calls a function with the `args`. `dest` receives the return value, if the function returns one.
+	Call { dest: Option<Local>, callee: Callee, args: Vec<Operand> },
+	/// `dest = [elements...]`
+	MakeArray { dest: Local, elements: Vec<Operand> },
+	/// `dest = base[index]`
+	Index { dest: Local, base: Operand, index: Operand },
+	/// `dest = name { fields... }`
+	MakeStruct { dest: Local, name: PositionContainer<Symbol>, fields: Vec<(Symbol, Operand)> },
+	/// `dest = base.field`
+	Field { dest: Local, base: Operand, field: PositionContainer<Symbol> },
+	/// Assigns `value` to a part of the `target`, like `target[i].x = value`. The projections start at the outermost
+	/// one and are never empty, since assigning the whole local is a [`Instruction::Copy`].
+	Store { target: Local, projections: Vec<Projection>, value: Operand },
+	/// Assigns `value` to the memory the `pointer` points to, or to a part of it, like `(*pointer).x = value`. Unlike
+	/// [`Instruction::Store`], no local is changed.
+	StorePointer { pointer: Operand, projections: Vec<Projection>, value: Operand },
+}
+
+impl Instruction {
+	/// The local which the instruction assigns to, or of which it assigns a part.
+	pub fn dest(&self) -> Option<Local> {
+		match self {
+			Instruction::Copy { dest, .. }
+			| Instruction::Binary { dest, .. }
+			| Instruction::Unary { dest, .. }
+			| Instruction::Convert { dest, .. }
+			| Instruction::MakeArray { dest, .. }
+			| Instruction::Index { dest, .. }
+			| Instruction::MakeStruct { dest, .. }
+			| Instruction::Field { dest, .. }
+			| Instruction::Store { target: dest, .. } => Some(*dest),
+			Instruction::Call { dest, .. } => *dest,
+			Instruction::StorePointer { .. } => None,
+		}
+	}
+
+	/// The operands which the instruction reads, in the order in which they are evaluated.
+	pub fn operands(&self) -> Vec<&Operand> {
+		match self {
+			Instruction::Copy { value, .. } | Instruction::Unary { operand: value, .. } => vec![value],
+			Instruction::Convert { value, .. } | Instruction::Field { base: value, .. } => vec![value],
+			Instruction::Binary { lhs, rhs, .. } => vec![lhs, rhs],
+			Instruction::Index { base, index, .. } => vec![base, index],
+			Instruction::Call { args: operands, .. } | Instruction::MakeArray { elements: operands, .. } => {
+				operands.iter().collect()
+			},
+			Instruction::MakeStruct { fields, .. } => fields.iter().map(|(_, value)| value).collect(),
+			Instruction::Store { projections, value, .. } => indices(projections).chain([value]).collect(),
+			Instruction::StorePointer { pointer, projections, value } => {
+				iter::once(pointer).chain(indices(projections)).chain([value]).collect()
+			},
+		}
+	}
+}
+
+/// The indices of the `projections`, which are operands of the instruction storing into them.
+fn indices(projections: &[Projection]) -> impl Iterator<Item = &Operand> {
+	projections.iter().filter_map(|projection| match projection {
+		Projection::Index(index) => Some(index),
+		Projection::Field(_) => None,
+	})
+}
+
+/// A function called by [`Instruction::Call`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Callee {
+	/// The function with this index in the [`Program`].
+	Function(usize),
+	/// The extern function with this index in the [`Program`].
+	Extern(usize),
+	Builtin(Builtin),
+}
+
+/// A step from a value to a part of it, which is assigned to by [`Instruction::Store`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Projection {
+	/// The element at the index of an array.
+	Index(Operand),
+	/// The field of a struct.
+	Field(PositionContainer<Symbol>),
+}
+
+/// The end of a [`BasicBlock`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Terminator {
+	/// Continue at the block.
+	Jump(BlockId),
+	/// Continue at `if_true` if the `condition` is true, otherwise at `if_false`.
+	Branch { condition: Operand, if_true: BlockId, if_false: BlockId },
+	/// Return to the caller, with a value if the function returns one.
+	Return(Option<Operand>),
+}
+
+impl Terminator {
+	/// The blocks at which execution can continue.
+	pub fn successors(&self) -> Vec<BlockId> {
+		match self {
+			Terminator::Jump(target) => vec![*target],
+			Terminator::Branch { if_true, if_false, .. } => vec![*if_true, *if_false],
+			Terminator::Return(_) => Vec::new(),
+		}
+	}
+
+	/// The operand which the terminator reads, i.e. the condition of a branch or the returned value.
+	pub fn operand(&self) -> Option<&Operand> {
+		match self {
+			Terminator::Jump(_) | Terminator::Return(None) => None,
+			Terminator::Branch { condition: operand, .. } | Terminator::Return(Some(operand)) => Some(operand),
+		}
+	}
+
+	/// Replaces the blocks at which execution continues by the result of `f`, e.g. after reordering the blocks.
+	pub fn map_successors(&mut self, f: impl Fn(BlockId) -> BlockId) {
+		match self {
+			Terminator::Jump(target) => *target = f(*target),
+			Terminator::Branch { if_true, if_false, .. } => {
+				*if_true = f(*if_true);
+				*if_false = f(*if_false);
+			},
+			Terminator::Return(_) => (),
+		}
+	}
+}
+
+impl Function {
+	/// The signature of the function, with the types of its first locals as argument types.
+	pub fn signature(&self) -> Signature {
+		Signature {
+			name: Symbol::intern(&self.name),
+			args: self.locals[..self.arity].iter().map(|local| local.data_type.clone()).collect(),
+			return_type: self.return_type.clone(),
+		}
+	}
+
+	/// The type of the value of the `operand`.
+	pub fn operand_type(&self, operand: &Operand) -> DataType {
+		match operand {
+			Operand::Local(local) => self.locals[local.0].data_type.clone(),
+			Operand::Constant(value) => constant_type(value),
+		}
+	}
+
+	/// Lets jumps and branches to a block without instructions, which only jumps on, go directly to the final target,
+	/// and removes the blocks which are no longer reached. Such blocks are left by lowering e.g. an `if` at the end of
+	/// a loop body.
+	pub fn thread_jumps(&mut self) {
+		let final_targets: Vec<BlockId> = (0..self.blocks.len())
+			.map(|id| {
+				let mut target = BlockId(id);
+				// Following at most as many jumps as there are blocks ends endless loops like `while true {}`
+				for _ in 0..self.blocks.len() {
+					let block = &self.blocks[target.0];
+					match &block.terminator {
+						Terminator::Jump(next) if block.instructions.is_empty() => target = *next,
+						_ => break,
+					}
+				}
+				target
+			})
+			.collect();
+		for block in &mut self.blocks {
+			block.terminator.map_successors(|target| final_targets[target.0]);
+		}
+		self.remove_unreachable_blocks();
+	}
+
+	/// Removes the blocks which cannot be reached from the first one, like code after a `return`.
+	pub fn remove_unreachable_blocks(&mut self) {
+		let mut reachable = vec![false; self.blocks.len()];
+		let mut pending = vec![BlockId(0)];
+		while let Some(block) = pending.pop() {
+			if std::mem::replace(&mut reachable[block.0], true) {
+				continue;
+			}
+			pending.extend(self.blocks[block.0].terminator.successors());
+		}
+		let mut new_ids = Vec::with_capacity(self.blocks.len());
+		let mut next_id = 0;
+		for &is_reachable in &reachable {
+			new_ids.push(BlockId(next_id));
+			next_id += usize::from(is_reachable);
+		}
+		let blocks = std::mem::take(&mut self.blocks);
+		self.blocks = blocks
+			.into_iter()
+			.zip(reachable)
+			.filter(|(_, is_reachable)| *is_reachable)
+			.map(|(mut block, _)| {
+				block.terminator.map_successors(|target| new_ids[target.0]);
+				block
+			})
+			.collect();
+	}
+
+	/// Orders the blocks which can be reached from the first one in reverse postorder, in which each block comes
+	/// before its successors, except for the blocks at the start of a loop, which are jumped back to.
+	pub fn reverse_postorder(&self) -> Vec<BlockId> {
+		let mut visited = vec![false; self.blocks.len()];
+		visited[0] = true;
+		let mut postorder = Vec::with_capacity(self.blocks.len());
+		// Each block on the path from the first block is paired with the index of its next successor to visit
+		let mut path = vec![(BlockId(0), 0)];
+		while let Some((block, next)) = path.last_mut() {
+			match self.blocks[block.0].terminator.successors().get(*next) {
+				Some(&successor) => {
+					*next += 1;
+					if !std::mem::replace(&mut visited[successor.0], true) {
+						path.push((successor, 0));
+					}
+				},
+				None => {
+					postorder.push(*block);
+					path.pop();
+				},
+			}
+		}
+		postorder.reverse();
+		postorder
+	}
+
+	/// Computes the immediate dominator of each block, i.e. the last block which is passed on every path from the
+	/// first block to it, with the algorithm of Cooper, Harvey and Kennedy. The first block and the blocks which
+	/// cannot be reached have none.
+	pub fn immediate_dominators(&self) -> Vec<Option<BlockId>> {
+		let order = self.reverse_postorder();
+		let mut numbers = vec![usize::MAX; self.blocks.len()];
+		let mut predecessors = vec![Vec::new(); self.blocks.len()];
+		for (number, &block) in order.iter().enumerate() {
+			numbers[block.0] = number;
+			for successor in self.blocks[block.0].terminator.successors() {
+				predecessors[successor.0].push(block);
+			}
+		}
+
+		let mut dominators = vec![None; self.blocks.len()];
+		dominators[0] = Some(BlockId(0));
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for &block in &order[1..] {
+				// The predecessors before the block in reverse postorder, like the one it was visited from, already
+				// have a dominator
+				let mut processed = predecessors[block.0].iter().filter(|block| dominators[block.0].is_some());
+				let mut dominator = *processed.next().expect("Block is reached from a preceding one");
+				for &predecessor in processed {
+					// Walk up the dominator tree from both blocks until they meet at their common dominator
+					let mut other = predecessor;
+					while dominator != other {
+						while numbers[dominator.0] > numbers[other.0] {
+							dominator = dominators[dominator.0].expect("Dominators are processed before");
+						}
+						while numbers[other.0] > numbers[dominator.0] {
+							other = dominators[other.0].expect("Dominators are processed before");
+						}
+					}
+				}
+				if dominators[block.0] != Some(dominator) {
+					dominators[block.0] = Some(dominator);
+					changed = true;
+				}
+			}
+		}
+		dominators[0] = None;
+		dominators
+	}
+}
+
+impl fmt::Display for Program {
+	/// Formats the program as human-readable listing of all blocks.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, function) in self.functions.iter().enumerate() {
+			writeln!(
+				f,
+				"function {} `{}` (arity {}, locals {}):",
+				index,
+				function.name,
+				function.arity,
+				function.locals.len()
+			)?;
+			for (id, block) in function.blocks.iter().enumerate() {
+				writeln!(f, "\t{}:", BlockId(id))?;
+				for instruction in &block.instructions {
+					writeln!(f, "\t\t{}", instruction)?;
+				}
+				writeln!(f, "\t\t{}", block.terminator)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Local {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "_{}", self.0)
+	}
+}
+
+impl fmt::Display for BlockId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "bb{}", self.0)
+	}
+}
+
+impl fmt::Display for Operand {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Operand::Local(local) => write!(f, "{}", local),
+			Operand::Constant(Value::Str(string)) => write!(f, "{:?}", string),
+			Operand::Constant(Value::Char(char)) => write!(f, "{:?}", char),
+			Operand::Constant(value) => write!(f, "{}", value),
+		}
+	}
+}
+
+impl fmt::Display for Instruction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Instruction::Copy { dest, value } => write!(f, "{} = {}", dest, value),
+			Instruction::Binary { dest, operator, lhs, rhs } => {
+				write!(f, "{} = {} {} {}", dest, lhs, binary_operator_symbol(operator), rhs)
+			},
+			Instruction::Unary { dest, operator: UnaryOperator::Negate, operand } => {
+				write!(f, "{} = -{}", dest, operand)
+			},
+			Instruction::Unary { dest, operator: UnaryOperator::Not, operand } => write!(f, "{} = !{}", dest, operand),
+			Instruction::Unary { dest, operator: UnaryOperator::Dereference, operand } => {
+				write!(f, "{} = *{}", dest, operand)
+			},
+			Instruction::Convert { dest, data_type, value } => write!(f, "{} = {} as {}", dest, value, data_type),
+			Instruction::Call { dest, callee, args } => {
+				if let Some(dest) = dest {
+					write!(f, "{} = ", dest)?;
+				}
+				write!(f, "call {}(", callee)?;
+				write_list(f, args)?;
+				write!(f, ")")
+			},
+			Instruction::MakeArray { dest, elements } => {
+				write!(f, "{} = [", dest)?;
+				write_list(f, elements)?;
+				write!(f, "]")
+			},
+			Instruction::Index { dest, base, index } => write!(f, "{} = {}[{}]", dest, base, index),
+			Instruction::MakeStruct { dest, name, fields } => {
+				write!(f, "{} = {} {{", dest, name.value)?;
+				for (i, (field, value)) in fields.iter().enumerate() {
+					write!(f, "{} {}: {}", if i == 0 { "" } else { "," }, field, value)?;
+				}
+				write!(f, " }}")
+			},
+			Instruction::Field { dest, base, field } => write!(f, "{} = {}.{}", dest, base, field.value),
+			Instruction::Store { target, projections, value } => {
+				write!(f, "{}", target)?;
+				write_projections(f, projections)?;
+				write!(f, " = {}", value)
+			},
+			Instruction::StorePointer { pointer, projections, value } if projections.is_empty() => {
+				write!(f, "*{} = {}", pointer, value)
+			},
+			Instruction::StorePointer { pointer, projections, value } => {
+				write!(f, "(*{})", pointer)?;
+				write_projections(f, projections)?;
+				write!(f, " = {}", value)
+			},
+		}
+	}
+}
+
+impl fmt::Display for Callee {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Callee::Function(index) => write!(f, "@{}", index),
+			Callee::Extern(index) => write!(f, "extern {}", index),
+			Callee::Builtin(builtin) => write!(f, "{}", builtin.name()),
+		}
+	}
+}
+
+impl fmt::Display for Terminator {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Terminator::Jump(target) => write!(f, "jump {}", target),
+			Terminator::Branch { condition, if_true, if_false } => {
+				write!(f, "branch {}, {}, {}", condition, if_true, if_false)
+			},
+			Terminator::Return(Some(value)) => write!(f, "return {}", value),
+			Terminator::Return(None) => write!(f, "return"),
+		}
+	}
+}
+
+/// The type of a `value` in an [`Operand::Constant`], which is a literal.
+fn constant_type(value: &Value) -> DataType {
+	match value {
+		Value::Int(_) => DataType::Basic(BasicDataType::Int),
+		Value::Float(_) => DataType::Basic(BasicDataType::Float),
+		Value::Bool(_) => DataType::Basic(BasicDataType::Bool),
+		Value::Str(_) => DataType::Basic(BasicDataType::Str),
+		Value::Char(_) => DataType::Basic(BasicDataType::Char),
+		Value::Null => DataType::Null,
+		Value::Struct(_) | Value::Array(_) => unreachable!("Constants are literals"),
+	}
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, operands: &[Operand]) -> fmt::Result {
+	for (i, operand) in operands.iter().enumerate() {
+		if i != 0 {
+			write!(f, ", ")?;
+		}
+		write!(f, "{}", operand)?;
+	}
+	Ok(())
+}
+
+fn write_projections(f: &mut fmt::Formatter<'_>, projections: &[Projection]) -> fmt::Result {
+	for projection in projections {
+		match projection {
+			Projection::Index(index) => write!(f, "[{}]", index)?,
+			Projection::Field(field) => write!(f, ".{}", field.value)?,
+		}
+	}
+	Ok(())
+}
+
+fn binary_operator_symbol(operator: &BinaryOperator) -> &'static str {
+	match operator {
+		BinaryOperator::Less => "<",
+		BinaryOperator::Greater => ">",
+		BinaryOperator::LessEqual => "<=",
+		BinaryOperator::GreaterEqual => ">=",
+		BinaryOperator::Add => "+",
+		BinaryOperator::Subtract => "-",
+		BinaryOperator::Multiply => "*",
+		BinaryOperator::Divide => "/",
+		BinaryOperator::Power => "**",
+		BinaryOperator::BitOr => "|",
+		BinaryOperator::BitAnd => "&",
+		BinaryOperator::BitXor => "^",
+		BinaryOperator::ShiftLeft => "<<",
+		BinaryOperator::ShiftRight => ">>",
+		BinaryOperator::Equal => "=",
+		BinaryOperator::NotEqual => "=/=",
+		BinaryOperator::LogicalAnd => "and",
+		BinaryOperator::LogicalOr => "or",
+	}
+}
@@ -0,0 +1,440 @@
+use crate::{
+	ast::statement::{BasicDataType, DataType},
+	error::DiagnosticSink,
+	ir::{self, BlockId, Error},
+	parser,
+	symbol::Symbol,
+};
+
+/// Tests that nested expressions are split up into temporaries and that constants are inlined.
+#[test]
+fn test_lower_expressions() {
+	let source_code = "
+		const offset: int = 1
+		def square(x: int): int {
+			return x * x + offset
+		}
+		def main() {
+			var y: int = square((2 - 1))
+			println((y as float))
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `square` (arity 1, locals 3):\n",
+			"\tbb0:\n",
+			"\t\t_1 = _0 * _0\n",
+			"\t\t_2 = _1 + 1\n",
+			"\t\treturn _2\n",
+			"function 1 `main` (arity 0, locals 4):\n",
+			"\tbb0:\n",
+			"\t\t_0 = 2 - 1\n",
+			"\t\t_1 = call @0(_0)\n",
+			"\t\t_2 = _1\n",
+			"\t\t_3 = _2 as float\n",
+			"\t\tcall println(_3)\n",
+			"\t\treturn\n",
+		)
+	);
+}
+
+/// Tests that loops and `if` are lowered to blocks, and that `break` and `continue` jump to the end of the loop and the
+/// step.
+#[test]
+fn test_lower_control_flow() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 10; i = i + 1 {
+				if i = 2 {
+					continue
+				}
+				if 5 < i {
+					break
+				} else {
+					sum = sum + i
+				}
+			}
+			return sum
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `main` (arity 0, locals 7):\n",
+			"\tbb0:\n",
+			"\t\t_0 = 0\n",
+			"\t\t_1 = 0\n",
+			"\t\tjump bb1\n",
+			"\tbb1:\n",
+			"\t\t_2 = _1 < 10\n",
+			"\t\tbranch _2, bb2, bb9\n",
+			"\tbb2:\n",
+			"\t\t_3 = _1 = 2\n",
+			"\t\tbranch _3, bb3, bb4\n",
+			"\tbb3:\n",
+			"\t\tjump bb8\n",
+			"\tbb4:\n",
+			"\t\t_4 = 5 < _1\n",
+			"\t\tbranch _4, bb5, bb6\n",
+			"\tbb5:\n",
+			"\t\tjump bb9\n",
+			"\tbb6:\n",
+			"\t\t_5 = _0 + _1\n",
+			"\t\t_0 = _5\n",
+			"\t\tjump bb7\n",
+			"\tbb7:\n",
+			"\t\tjump bb8\n",
+			"\tbb8:\n",
+			"\t\t_6 = _1 + 1\n",
+			"\t\t_1 = _6\n",
+			"\t\tjump bb1\n",
+			"\tbb9:\n",
+			"\t\treturn _0\n",
+		)
+	);
+}
+
+/// Tests that the rhs of `and` and `or` is only evaluated if the lhs does not determine the result.
+#[test]
+fn test_lower_logical_expression() {
+	let source_code = "
+		def main(a: bool, b: bool): bool {
+			return a and b or a
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `main` (arity 2, locals 4):\n",
+			"\tbb0:\n",
+			"\t\t_2 = _0\n",
+			"\t\tbranch _2, bb1, bb2\n",
+			"\tbb1:\n",
+			"\t\t_2 = _1\n",
+			"\t\tjump bb2\n",
+			"\tbb2:\n",
+			"\t\t_3 = _2\n",
+			"\t\tbranch _3, bb4, bb3\n",
+			"\tbb3:\n",
+			"\t\t_3 = _0\n",
+			"\t\tjump bb4\n",
+			"\tbb4:\n",
+			"\t\treturn _3\n",
+		)
+	);
+}
+
+/// Tests that both branches of an if-expression copy their value into the same temporary.
+#[test]
+fn test_lower_if_expression() {
+	let source_code = "
+		def main(a: int, b: int): int {
+			return if a < b { a } else { b * 2 }
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `main` (arity 2, locals 5):\n",
+			"\tbb0:\n",
+			"\t\t_2 = _0 < _1\n",
+			"\t\tbranch _2, bb1, bb2\n",
+			"\tbb1:\n",
+			"\t\t_3 = _0\n",
+			"\t\tjump bb3\n",
+			"\tbb2:\n",
+			"\t\t_4 = _1 * 2\n",
+			"\t\t_3 = _4\n",
+			"\t\tjump bb3\n",
+			"\tbb3:\n",
+			"\t\treturn _3\n",
+		)
+	);
+}
+
+/// Tests that the value of a match is compared with one pattern after the other, and that each arm jumps to the end.
+#[test]
+fn test_lower_match() {
+	let source_code = "
+		def main(x: int): int {
+			var y: int = 0
+			match x {
+				1, 2 => {
+					y = 10
+				}
+				3 => {
+					return 3
+				}
+				_ => {
+					y = x
+				}
+			}
+			return y
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `main` (arity 1, locals 5):\n",
+			"\tbb0:\n",
+			"\t\t_1 = 0\n",
+			"\t\t_2 = _0 = 1\n",
+			"\t\tbranch _2, bb4, bb1\n",
+			"\tbb1:\n",
+			"\t\t_3 = _0 = 2\n",
+			"\t\tbranch _3, bb4, bb2\n",
+			"\tbb2:\n",
+			"\t\t_4 = _0 = 3\n",
+			"\t\tbranch _4, bb5, bb3\n",
+			"\tbb3:\n",
+			"\t\t_1 = _0\n",
+			"\t\tjump bb6\n",
+			"\tbb4:\n",
+			"\t\t_1 = 10\n",
+			"\t\tjump bb6\n",
+			"\tbb5:\n",
+			"\t\treturn 3\n",
+			"\tbb6:\n",
+			"\t\treturn _1\n",
+		)
+	);
+}
+
+/// Tests that code after a `return` is removed, since no block jumps to it.
+#[test]
+fn test_remove_unreachable_blocks() {
+	let source_code = "
+		def main(): int {
+			while true {
+				return 1
+				println(2)
+			}
+			return 0
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `main` (arity 0, locals 0):\n",
+			"\tbb0:\n",
+			"\t\tjump bb1\n",
+			"\tbb1:\n",
+			"\t\tbranch true, bb2, bb3\n",
+			"\tbb2:\n",
+			"\t\treturn 1\n",
+			"\tbb3:\n",
+			"\t\treturn 0\n",
+		)
+	);
+}
+
+/// Tests that variables are only visible in the block they are declared in.
+#[test]
+fn test_undeclared_variable() {
+	let source_code = "
+		def main(): int {
+			if true {
+				var x: int = 1
+			}
+			return x
+		}
+	";
+	assert!(matches!(lower(source_code), Err(Error::UndeclaredVariable { name }) if name.value == "x"));
+}
+
+/// Tests that functions keep their order and indices, and that the error of the first invalid function is returned,
+/// also if the functions are lowered in parallel.
+#[test]
+fn test_function_order() {
+	let source_code: String =
+		(0..16).map(|i| format!("def f{i}(): int {{\n\treturn f{}()\n}}\n", (i + 1) % 16)).collect();
+	let program = lower_program(&source_code).unwrap();
+	for (index, function) in program.functions.iter().enumerate() {
+		assert_eq!(function.name, format!("f{index}"));
+		assert!(program.to_string().contains(&format!("call @{}()", (index + 1) % 16)));
+	}
+
+	let source_code = source_code + "def g(): int {\n\treturn x\n}\ndef h(): int {\n\treturn y\n}\n";
+	assert!(matches!(lower(&source_code), Err(Error::UndeclaredVariable { name }) if name.value == "x"));
+}
+
+/// Tests that jumps to blocks which only jump on go to the final target directly.
+#[test]
+fn test_thread_jumps() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 10; i = i + 1 {
+				if i = 2 {
+					continue
+				}
+				if 5 < i {
+					sum = sum + i
+				}
+			}
+			return sum
+		}
+	";
+	let mut program = lower_program(source_code).unwrap();
+	program.functions[0].thread_jumps();
+	assert_eq!(
+		program.to_string(),
+		concat!(
+			"function 0 `main` (arity 0, locals 7):\n",
+			"\tbb0:\n",
+			"\t\t_0 = 0\n",
+			"\t\t_1 = 0\n",
+			"\t\tjump bb1\n",
+			"\tbb1:\n",
+			"\t\t_2 = _1 < 10\n",
+			"\t\tbranch _2, bb2, bb6\n",
+			"\tbb2:\n",
+			"\t\t_3 = _1 = 2\n",
+			"\t\tbranch _3, bb5, bb3\n",
+			"\tbb3:\n",
+			"\t\t_4 = 5 < _1\n",
+			"\t\tbranch _4, bb4, bb5\n",
+			"\tbb4:\n",
+			"\t\t_5 = _0 + _1\n",
+			"\t\t_0 = _5\n",
+			"\t\tjump bb5\n",
+			"\tbb5:\n",
+			"\t\t_6 = _1 + 1\n",
+			"\t\t_1 = _6\n",
+			"\t\tjump bb1\n",
+			"\tbb6:\n",
+			"\t\treturn _0\n",
+		)
+	);
+}
+
+/// Tests that blocks come before their successors in reverse postorder except at a loop, and that the end of a loop
+/// is dominated by its condition, which both `break` and the condition leave the loop from.
+#[test]
+fn test_reverse_postorder_and_dominators() {
+	let source_code = "
+		def main(): int {
+			var i: int = 0
+			while i < 10 {
+				if i = 5 {
+					break
+				}
+				i = i + 1
+			}
+			return i
+		}
+	";
+	let program = lower_program(source_code).unwrap();
+	let function = &program.functions[0];
+	assert_eq!(function.blocks[1].terminator.successors(), [BlockId(2), BlockId(5)]);
+	assert_eq!(function.reverse_postorder(), [0, 1, 2, 4, 3, 5].map(BlockId));
+	let dominators = [None, Some(0), Some(1), Some(2), Some(2), Some(1)].map(|block| block.map(BlockId));
+	assert_eq!(function.immediate_dominators(), dominators);
+}
+
+/// Tests that locals, signatures and struct fields carry the types a backend needs, and that each instruction keeps
+/// the position of its FTL statement.
+#[test]
+fn test_types_and_positions() {
+	let source_code = "
+		extern abs(n: int): int
+		struct Point {
+			x: float
+		}
+		def main(): int {
+			var p = Point { x: 1.5 }
+			return abs(p.x as int)
+		}
+	";
+	let program = lower_program(source_code).unwrap();
+	assert_eq!(program.externs[0].args, [DataType::Basic(BasicDataType::Int)]);
+	assert_eq!(program.structs[0].fields[0].1, DataType::Basic(BasicDataType::Float));
+	let main = &program.functions[0];
+	assert_eq!(main.return_type, Some(DataType::Basic(BasicDataType::Int)));
+	assert_eq!(main.locals[0].data_type, DataType::Struct(Symbol::from("Point")));
+	assert!(program.to_string().contains("call extern 0("), "{}", program);
+
+	let block = &main.blocks[0];
+	let (conversion, position) = block
+		.instructions
+		.iter()
+		.zip(&block.positions)
+		.find(|(instruction, _)| matches!(instruction, ir::Instruction::Convert { .. }))
+		.unwrap();
+	assert_eq!(main.locals[conversion.dest().unwrap().0].data_type, DataType::Basic(BasicDataType::Int));
+	assert_eq!(position.position.start.line, 8);
+	assert_eq!(block.positions[0].position.start.line, 7);
+	assert_eq!(block.terminator_position.as_ref().map(|position| position.position.start.line), Some(8));
+}
+
+/// Tests that reading through a pointer is a unary instruction and that assigning through it stores into the memory it
+/// points to, also into a part of it.
+#[test]
+fn test_lower_dereference() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def main(p: ptr Point, n: ptr int) {
+			*n = *n + 1
+			p.x = 2
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	crate::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default()).unwrap();
+	assert_eq!(
+		ir::lower(ast_nodes.into_iter()).unwrap().to_string(),
+		concat!(
+			"function 0 `main` (arity 2, locals 4):\n",
+			"\tbb0:\n",
+			"\t\t_2 = *_1\n",
+			"\t\t_3 = _2 + 1\n",
+			"\t\t*_1 = _3\n",
+			"\t\t(*_0).x = 2\n",
+			"\t\treturn\n",
+		)
+	);
+}
+
+/// Tests that a compound assignment evaluates the index of its target only once, and reads the element at the same
+/// index it stores to.
+#[test]
+fn test_lower_compound_assignment() {
+	let source_code = "
+		def f(): int {
+			return 1
+		}
+		def main(a: [int; 3]) {
+			a[f()] *= 2 + 3
+		}
+	";
+	assert_eq!(
+		lower(source_code).unwrap(),
+		concat!(
+			"function 0 `f` (arity 0, locals 0):\n",
+			"\tbb0:\n",
+			"\t\treturn 1\n",
+			"function 1 `main` (arity 1, locals 5):\n",
+			"\tbb0:\n",
+			"\t\t_1 = call @0()\n",
+			"\t\t_2 = _0[_1]\n",
+			"\t\t_3 = 2 + 3\n",
+			"\t\t_4 = _2 * _3\n",
+			"\t\t_0[_1] = _4\n",
+			"\t\treturn\n",
+		)
+	);
+}
+
+/// Boilerplate code for lexing, parsing and lowering source code, and formatting the IR.
+fn lower(source_code: &str) -> Result<String, Error> {
+	Ok(lower_program(source_code)?.to_string())
+}
+
+/// Boilerplate code for lexing, parsing and lowering source code.
+fn lower_program(source_code: &str) -> Result<ir::Program, Error> {
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	ir::lower(ast_nodes.into_iter())
+}
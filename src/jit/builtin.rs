@@ -0,0 +1,70 @@
+//! Native implementations of the [builtins](crate::builtin), which are called by the compiled code. Like in the
+//! compiled backends, `print` is split into one function per type of the printed value, and `println` calls
+//! `println()` after it to write the newline. `math.pow` and `**` have no Cranelift instruction, so they are native
+//! functions, too.
+
+use std::ffi::{c_char, CStr};
+
+use crate::{
+	builtin::{self, Builtin},
+	interpreter::Value,
+};
+
+/// Name and address of every function, which are registered as symbols in the JIT module.
+pub(super) fn symbols() -> [(&'static str, *const u8); 9] {
+	[
+		("ftl.print_int", print_int as *const u8),
+		("ftl.print_float", print_float as *const u8),
+		("ftl.print_bool", print_bool as *const u8),
+		("ftl.print_char", print_char as *const u8),
+		("ftl.print_str", print_str as *const u8),
+		("ftl.println", println as *const u8),
+		("ftl.read_int", read_int as *const u8),
+		("ftl.pow", pow as *const u8),
+		("ftl.int_power", int_power as *const u8),
+	]
+}
+
+extern "C" fn print_int(int: i64) {
+	Builtin::Print.call(&[Value::Int(int)]);
+}
+
+extern "C" fn print_float(float: f64) {
+	Builtin::Print.call(&[Value::Float(float)]);
+}
+
+extern "C" fn print_bool(bool: u8) {
+	Builtin::Print.call(&[Value::Bool(bool != 0)]);
+}
+
+extern "C" fn print_char(char: u32) {
+	// Characters cannot be calculated with, so they are always created from valid literals
+	Builtin::Print.call(&[Value::Char(char::from_u32(char).expect("invalid character printed"))]);
+}
+
+/// # Safety
+///
+/// The `string` has to be null-terminated, which both string literals and strings from C functions are.
+unsafe extern "C" fn print_str(string: *const c_char) {
+	let string = unsafe { CStr::from_ptr(string) };
+	Builtin::Print.call(&[Value::Str(string.to_string_lossy().into_owned())]);
+}
+
+extern "C" fn println() {
+	Builtin::Println.call(&[Value::Str(String::new())]);
+}
+
+extern "C" fn read_int() -> i64 {
+	match Builtin::ReadInt.call(&[]) {
+		Some(Value::Int(int)) => int,
+		_ => unreachable!("read_int returns an int"),
+	}
+}
+
+extern "C" fn pow(x: f64, y: f64) -> f64 {
+	x.powf(y)
+}
+
+extern "C" fn int_power(base: i64, exponent: i64) -> i64 {
+	builtin::int_power(base, exponent)
+}
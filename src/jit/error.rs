@@ -0,0 +1,65 @@
+use std::ops::Deref;
+
+use crate::{
+	ast::{
+		expression::{BinaryOperator, FunctionCall, UnaryOperator},
+		statement::DataType,
+	},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// Errors that occur while [just-in-time compiling](super::Jit) a program.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("UnsupportedHost: The host machine is not supported: {0}")]
+	UnsupportedHost(String),
+
+	#[error("Module: {0}")]
+	Module(Box<cranelift_module::ModuleError>),
+
+	#[error("MissingMainFunction: The program has no `main()` function without arguments to start with.")]
+	MissingMainFunction,
+
+	#[error("{}: UnsupportedDataType: Data type `{}` is not supported by the JIT.", data_type.position, data_type.value)]
+	UnsupportedDataType { data_type: PositionContainer<DataType> },
+
+	#[error("{}: UnsupportedStruct: Structs are not supported by the JIT.", position)]
+	UnsupportedStruct { position: SourcePositionRange },
+
+	#[error("{}: UnsupportedArray: Arrays are not supported by the JIT.", position)]
+	UnsupportedArray { position: SourcePositionRange },
+
+	#[error("{}: UnsupportedDereference: Dereferences of pointers are not supported by the JIT.", position)]
+	UnsupportedDereference { position: SourcePositionRange },
+
+	#[error("{}: UndeclaredVariable: Variable `{name}` is not declared.", name.position)]
+	UndeclaredVariable { name: PositionContainer<Symbol> },
+
+	#[error("{}: UndefinedFunctionCall: Call of function `{}(...)`, but no such function is defined.", function_call.name.position, function_call.name.deref())]
+	UndefinedFunctionCall { function_call: Box<FunctionCall> },
+
+	#[error("{}: ArgumentCountMismatch: Function `{}(...)` expects {expected} arguments but {actual} parameters provided", function_call.name.position, function_call.name.value)]
+	ArgumentCountMismatch { expected: usize, actual: usize, function_call: Box<FunctionCall> },
+
+	#[error("{}: MissingReturnValue: Function `{}(...)` has no return value.", function_call.name.position, function_call.name.value)]
+	MissingReturnValue { function_call: Box<FunctionCall> },
+
+	#[error("{}: TypeMismatch: Operator {:?} cannot be applied to operands of different types", operator.position, operator.value)]
+	TypeMismatch { operator: PositionContainer<BinaryOperator> },
+
+	#[error("{}: BranchTypeMismatch: The branches of the if-expression have different types", position)]
+	BranchTypeMismatch { position: SourcePositionRange },
+
+	#[error("{}: InvalidFloatOperands: Operator {:?} cannot be applied to floats", operator.position, operator.value)]
+	InvalidFloatOperands { operator: PositionContainer<BinaryOperator> },
+
+	#[error("{}: InvalidOperand: Operator {:?} cannot be applied to this operand", operator.position, operator.value)]
+	InvalidOperand { operator: PositionContainer<UnaryOperator> },
+}
+
+impl From<cranelift_module::ModuleError> for Error {
+	fn from(err: cranelift_module::ModuleError) -> Self {
+		Self::Module(Box::new(err))
+	}
+}
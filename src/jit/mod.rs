@@ -0,0 +1,777 @@
+//! Executing a program in-process by just-in-time compiling its [AST](crate::ast) to native code with
+//! [Cranelift](https://cranelift.dev). Only available with the `jit` feature.
+//!
+//! Integers, pointers and strings are lowered to `i64`, floats to `f64` and booleans to `i8`. String literals are
+//! null-terminated data objects. Structs are not supported yet.
+//! Integer division by zero traps, which aborts the process.
+//! The [builtins](crate::builtin) call native functions of the compiler, except for the math functions, which are
+//! Cranelift instructions. Because strings and integers are both lowered to `i64`, the JIT tracks which variables hold
+//! strings to decide how a value is printed.
+
+mod builtin;
+mod error;
+#[cfg(test)]
+mod test;
+
+use std::{collections::HashMap, ffi::CStr, mem};
+
+use cranelift_codegen::{
+	entity::EntitySet,
+	ir::{
+		condcodes::{FloatCC, IntCC},
+		types, AbiParam, InstBuilder, Signature, TrapCode, Type, UserFuncName,
+	},
+	settings::{self, Configurable},
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{DataDescription, FuncId, Linkage, Module};
+pub use error::Error;
+
+use crate::{
+	ast::{
+		self,
+		expression::{
+			BinaryExpression, BinaryOperator, Cast, FieldAccess, FunctionCall, IfExpression, NumberKind, StructLiteral,
+			UnaryExpression, UnaryOperator,
+		},
+		statement::{BasicDataType, DataType, LValue},
+		Block, Expression, FunctionDefinition, FunctionPrototype,
+	},
+	builtin::Builtin,
+	interpreter::Value,
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// Trap raised when a function with return type is left without `return`.
+const MISSING_RETURN_VALUE: TrapCode = TrapCode::unwrap_user(1);
+
+/// Just-in-time compiler that turns [AST nodes](ast::Node) into native machine code.
+pub struct Jit {
+	/// The module containing the compiled functions.
+	module: JITModule,
+	/// Id and prototype of every function and extern in the program.
+	functions: HashMap<Symbol, (FuncId, FunctionPrototype)>,
+	/// Values of the top-level constants, which are translated inline wherever a constant is used.
+	constants: HashMap<Symbol, Expression>,
+}
+
+impl Jit {
+	/// Compiles all function definitions in `ast_nodes` to native code. Externs are resolved with the symbols of the
+	/// current process, e.g. the C standard library.
+	#[tracing::instrument(skip_all)]
+	pub fn compile(ast_nodes: impl Iterator<Item = ast::Node>) -> Result<Self, Error> {
+		let mut flag_builder = settings::builder();
+		flag_builder.set("use_colocated_libcalls", "false").expect("Unknown Cranelift setting");
+		flag_builder.set("is_pic", "false").expect("Unknown Cranelift setting");
+		let isa = cranelift_native::builder()
+			.map_err(|msg| Error::UnsupportedHost(msg.to_owned()))?
+			.finish(settings::Flags::new(flag_builder))
+			.map_err(|err| Error::UnsupportedHost(err.to_string()))?;
+		let mut jit_builder = JITBuilder::with_isa(isa, cranelift_module::default_libcall_names());
+		for (name, address) in builtin::symbols() {
+			jit_builder.symbol(name, address);
+		}
+		let module = JITModule::new(jit_builder);
+
+		let mut jit = Self { module, functions: HashMap::new(), constants: HashMap::new() };
+
+		// Declare all functions first, so that they can be called before being defined
+		let mut definitions = Vec::new();
+		for ast_node in ast_nodes {
+			match ast_node {
+				ast::Node::Function(function) => {
+					jit.declare(&function.prototype, Linkage::Export)?;
+					definitions.push(function);
+				},
+				ast::Node::FunctionPrototype(prototype) => jit.declare(&prototype, Linkage::Import)?,
+				ast::Node::Struct(_) | ast::Node::TypeAlias(_) => (),
+				ast::Node::Const(const_declaration) => {
+					jit.constants.insert(const_declaration.name.value, const_declaration.value);
+				},
+				ast::Node::Enum(enum_) => {
+					for constant in enum_.constants() {
+						jit.constants.insert(constant.name.value, constant.value);
+					}
+				},
+				ast::Node::Import(_) | ast::Node::Comment(_) => (),
+			}
+		}
+
+		let mut builder_context = FunctionBuilderContext::new();
+		for function in &definitions {
+			jit.define(function, &mut builder_context)?;
+		}
+		jit.module.finalize_definitions()?;
+		Ok(jit)
+	}
+
+	/// Executes the program by calling its `main()` function and returns the value returned by it.
+	#[tracing::instrument(skip_all)]
+	pub fn run(&self) -> Result<Option<Value>, Error> {
+		let (main, prototype) = self.functions.get(&Symbol::intern("main")).ok_or(Error::MissingMainFunction)?;
+		if !prototype.args.is_empty() {
+			return Err(Error::MissingMainFunction);
+		}
+		let code = self.module.get_finalized_function(*main);
+
+		// SAFETY: `main` was compiled with the default calling convention of the host, no arguments and the
+		// return type transmuted to here.
+		let return_value = match prototype.return_type.as_ref().map(|return_type| &return_type.value) {
+			None => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn()>(code) };
+				main();
+				None
+			},
+			Some(DataType::Basic(BasicDataType::Float)) => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn() -> f64>(code) };
+				Some(Value::Float(main()))
+			},
+			Some(DataType::Basic(BasicDataType::Bool)) => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn() -> u8>(code) };
+				Some(Value::Bool(main() != 0))
+			},
+			Some(DataType::Basic(BasicDataType::Char)) => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn() -> u32>(code) };
+				// Characters cannot be calculated with, so they are always created from valid literals
+				Some(Value::Char(char::from_u32(main()).expect("invalid character returned by main")))
+			},
+			Some(DataType::Basic(BasicDataType::Str)) => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn() -> *const i8>(code) };
+				// SAFETY: Strings are null-terminated, either by being a literal or by coming from a C function
+				let string = unsafe { CStr::from_ptr(main()) };
+				Some(Value::Str(string.to_string_lossy().into_owned()))
+			},
+			Some(_) => {
+				let main = unsafe { mem::transmute::<*const u8, extern "C" fn() -> i64>(code) };
+				Some(Value::Int(main()))
+			},
+		};
+		Ok(return_value)
+	}
+
+	/// Declares the function in the module with the signature given by the `prototype`.
+	fn declare(&mut self, prototype: &FunctionPrototype, linkage: Linkage) -> Result<(), Error> {
+		let signature = self.signature(prototype)?;
+		let id = self.module.declare_function(&prototype.name, linkage, &signature)?;
+		self.functions.insert(prototype.name.value, (id, prototype.clone()));
+		Ok(())
+	}
+
+	/// Translates the body of the `function` to Cranelift IR and compiles it.
+	#[tracing::instrument(skip_all, fields(name = function.prototype.name.as_str()))]
+	fn define(
+		&mut self,
+		function: &FunctionDefinition,
+		builder_context: &mut FunctionBuilderContext,
+	) -> Result<(), Error> {
+		let (id, _) = self.functions[&function.prototype.name.value];
+		let mut context = self.module.make_context();
+		context.func.signature = self.signature(&function.prototype)?;
+		context.func.name = UserFuncName::user(0, id.as_u32());
+
+		let target_config = self.module.target_config();
+		let mut translator = FunctionTranslator {
+			builder: FunctionBuilder::new(&mut context.func, builder_context),
+			module: &mut self.module,
+			functions: &self.functions,
+			constants: &self.constants,
+			scopes: Vec::new(),
+			strings: EntitySet::new(),
+			loops: Vec::new(),
+		};
+		translator.function(function)?;
+		translator.builder.finalize(target_config);
+		tracing::trace!("Cranelift IR translated:\n{}", context.func.display());
+
+		self.module.define_function(id, &mut context)?;
+		self.module.clear_context(&mut context);
+		Ok(())
+	}
+
+	/// The Cranelift signature of a function with the given `prototype`.
+	fn signature(&self, prototype: &FunctionPrototype) -> Result<Signature, Error> {
+		let mut signature = self.module.make_signature();
+		for arg in &prototype.args {
+			signature.params.push(AbiParam::new(cranelift_type(&arg.data_type)?));
+		}
+		if let Some(return_type) = return_type(&prototype.return_type)? {
+			signature.returns.push(AbiParam::new(return_type));
+		}
+		Ok(signature)
+	}
+}
+
+/// Translates a single function to Cranelift IR.
+struct FunctionTranslator<'a> {
+	builder: FunctionBuilder<'a>,
+	module: &'a mut JITModule,
+	/// Id and prototype of every function and extern in the program.
+	functions: &'a HashMap<Symbol, (FuncId, FunctionPrototype)>,
+	/// Values of the top-level constants.
+	constants: &'a HashMap<Symbol, Expression>,
+	/// In-scope variables. The innermost scope is the last one.
+	scopes: Vec<HashMap<Symbol, Variable>>,
+	/// Variables holding strings, which cannot be distinguished from integers by their Cranelift type.
+	strings: EntitySet<Variable>,
+	/// Blocks to continue and to break the enclosing loops with. The innermost loop is the last one.
+	loops: Vec<(cranelift_codegen::ir::Block, cranelift_codegen::ir::Block)>,
+}
+
+/// Each of the functions in this impl block is responsible for translating the corresponding AST node.
+impl FunctionTranslator<'_> {
+	fn function(&mut self, function: &FunctionDefinition) -> Result<(), Error> {
+		let entry_block = self.builder.create_block();
+		self.builder.append_block_params_for_function_params(entry_block);
+		self.builder.switch_to_block(entry_block);
+
+		let mut arguments = HashMap::new();
+		for (index, arg) in function.prototype.args.iter().enumerate() {
+			let value = self.builder.block_params(entry_block)[index];
+			let variable = self.builder.declare_var(cranelift_type(&arg.data_type)?);
+			self.builder.def_var(variable, value);
+			if arg.data_type.value == DataType::Basic(BasicDataType::Str) {
+				self.strings.insert(variable);
+			}
+			arguments.insert(arg.name.value, variable);
+		}
+		self.scopes.push(arguments);
+		self.instructions(&function.body)?;
+		self.scopes.pop();
+
+		// Falling off the end of the function
+		match function.prototype.return_type {
+			None => self.builder.ins().return_(&[]),
+			Some(_) => self.builder.ins().trap(MISSING_RETURN_VALUE),
+		};
+		self.builder.seal_all_blocks();
+		Ok(())
+	}
+
+	/// Translates a block, whose variables are dropped at the end of the block.
+	fn block(&mut self, block: &Block) -> Result<(), Error> {
+		self.scopes.push(HashMap::new());
+		let result = self.instructions(block);
+		self.scopes.pop();
+		result
+	}
+
+	fn instructions(&mut self, instructions: &[ast::Instruction]) -> Result<(), Error> {
+		for instruction in instructions {
+			self.instruction(instruction)?;
+		}
+		Ok(())
+	}
+
+	fn instruction(&mut self, instruction: &ast::Instruction) -> Result<(), Error> {
+		match instruction {
+			ast::Instruction::Expression(Expression::FunctionCall(function_call)) => {
+				self.function_call(function_call)?;
+			},
+			ast::Instruction::Expression(expression) => {
+				self.expression(expression)?;
+			},
+			ast::Instruction::Statement(statement) => self.statement(statement)?,
+			ast::Instruction::IfElse(if_else) => self.if_else(if_else)?,
+			ast::Instruction::WhileLoop(while_loop) => self.while_loop(while_loop)?,
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(for_loop)?,
+			ast::Instruction::Match(match_) => {
+				for instruction in match_.as_ref().clone().into_if_else() {
+					self.instruction(&instruction)?;
+				}
+			},
+			ast::Instruction::Comment(_) => (),
+		}
+		Ok(())
+	}
+
+	fn variable_declaration(
+		&mut self,
+		variable_declaration: &ast::statement::VariableDeclaration,
+	) -> Result<(), Error> {
+		if let Some(data_type) = &variable_declaration.data_type {
+			cranelift_type(data_type)?;
+		}
+		let value = self.expression(&variable_declaration.value)?;
+		let variable = self.builder.declare_var(self.builder.func.dfg.value_type(value));
+		self.builder.def_var(variable, value);
+		if self.is_string(&variable_declaration.value) {
+			self.strings.insert(variable);
+		}
+		let scope = self.scopes.last_mut().expect("Function without scope");
+		scope.insert(variable_declaration.name.value, variable);
+		Ok(())
+	}
+
+	fn statement(&mut self, statement: &ast::Statement) -> Result<(), Error> {
+		match statement {
+			ast::Statement::VariableDeclaration(variable_declaration) => {
+				self.variable_declaration(variable_declaration)?
+			},
+			ast::Statement::ConstDeclaration(const_declaration) => {
+				self.variable_declaration(&(**const_declaration).clone().into())?
+			},
+			ast::Statement::Assignment(assignment) => self.assignment(assignment)?,
+			ast::Statement::Return(expression, _) => {
+				match expression {
+					Some(expression) => {
+						let value = self.expression(expression)?;
+						self.builder.ins().return_(&[value]);
+					},
+					None => {
+						self.builder.ins().return_(&[]);
+					},
+				}
+				self.dead_block();
+			},
+			ast::Statement::Break(_) => {
+				let (_, break_block) = *self.loops.last().expect("break outside of loop");
+				self.builder.ins().jump(break_block, &[]);
+				self.dead_block();
+			},
+			ast::Statement::Continue(_) => {
+				let (continue_block, _) = *self.loops.last().expect("continue outside of loop");
+				self.builder.ins().jump(continue_block, &[]);
+				self.dead_block();
+			},
+		}
+		Ok(())
+	}
+
+	/// Switches to a new block for the instructions after a jump or return, which are unreachable, but still need a
+	/// block to be placed in.
+	fn dead_block(&mut self) {
+		let dead_block = self.builder.create_block();
+		self.builder.switch_to_block(dead_block);
+	}
+
+	fn assignment(&mut self, assignment: &ast::statement::Assignment) -> Result<(), Error> {
+		let name = match &assignment.target {
+			LValue::Variable(name) => name,
+			LValue::FieldAccess { field, .. } => {
+				return Err(Error::UnsupportedStruct { position: field.position.clone() })
+			},
+			target @ LValue::Index { .. } => {
+				return Err(Error::UnsupportedArray { position: target.source_position() })
+			},
+			LValue::Dereference { position, .. } => {
+				return Err(Error::UnsupportedDereference { position: position.clone() })
+			},
+		};
+		let value = self.expression(&assignment.assigned_value())?;
+		let variable = self.variable(name)?;
+		self.builder.def_var(variable, value);
+		Ok(())
+	}
+
+	fn if_else(&mut self, if_else: &ast::IfElse) -> Result<(), Error> {
+		let then_block = self.builder.create_block();
+		let else_block = self.builder.create_block();
+		let end_block = self.builder.create_block();
+
+		let condition = self.condition(&if_else.condition)?;
+		self.builder.ins().brif(condition, then_block, &[], else_block, &[]);
+
+		self.builder.switch_to_block(then_block);
+		self.block(&if_else.if_true)?;
+		self.builder.ins().jump(end_block, &[]);
+
+		self.builder.switch_to_block(else_block);
+		self.block(&if_else.if_false)?;
+		self.builder.ins().jump(end_block, &[]);
+
+		self.builder.switch_to_block(end_block);
+		Ok(())
+	}
+
+	fn while_loop(&mut self, while_loop: &ast::WhileLoop) -> Result<(), Error> {
+		let condition_block = self.builder.create_block();
+		let body_block = self.builder.create_block();
+		let end_block = self.builder.create_block();
+
+		self.builder.ins().jump(condition_block, &[]);
+		self.builder.switch_to_block(condition_block);
+		let condition = self.condition(&while_loop.condition)?;
+		self.builder.ins().brif(condition, body_block, &[], end_block, &[]);
+
+		self.builder.switch_to_block(body_block);
+		self.loops.push((condition_block, end_block));
+		self.block(&while_loop.body)?;
+		self.loops.pop();
+		self.builder.ins().jump(condition_block, &[]);
+
+		self.builder.switch_to_block(end_block);
+		Ok(())
+	}
+
+	fn for_loop(&mut self, for_loop: &ast::ForLoop) -> Result<(), Error> {
+		let condition_block = self.builder.create_block();
+		let body_block = self.builder.create_block();
+		let step_block = self.builder.create_block();
+		let end_block = self.builder.create_block();
+
+		// The variable of the initialization is only visible inside the loop
+		self.scopes.push(HashMap::new());
+		self.statement(&for_loop.initialization)?;
+		self.builder.ins().jump(condition_block, &[]);
+		self.builder.switch_to_block(condition_block);
+		let condition = self.condition(&for_loop.condition)?;
+		self.builder.ins().brif(condition, body_block, &[], end_block, &[]);
+
+		self.builder.switch_to_block(body_block);
+		// `continue` has to execute the step before checking the condition again
+		self.loops.push((step_block, end_block));
+		self.block(&for_loop.body)?;
+		self.loops.pop();
+		self.builder.ins().jump(step_block, &[]);
+
+		self.builder.switch_to_block(step_block);
+		self.assignment(&for_loop.step)?;
+		self.builder.ins().jump(condition_block, &[]);
+		self.scopes.pop();
+
+		self.builder.switch_to_block(end_block);
+		Ok(())
+	}
+
+	/// Translates the expression to a value that is non-zero if it is true.
+	fn condition(&mut self, expression: &Expression) -> Result<cranelift_codegen::ir::Value, Error> {
+		let value = self.expression(expression)?;
+		Ok(match self.builder.func.dfg.value_type(value) {
+			types::F64 => {
+				let zero = self.builder.ins().f64const(0.0);
+				self.builder.ins().fcmp(FloatCC::NotEqual, value, zero)
+			},
+			_ => value,
+		})
+	}
+
+	fn expression(&mut self, expression: &Expression) -> Result<cranelift_codegen::ir::Value, Error> {
+		match expression {
+			Expression::BinaryExpression(binary_expression) => self.binary_expression(binary_expression),
+			Expression::UnaryExpression(unary_expression) => self.unary_expression(unary_expression),
+			Expression::Cast(cast) => self.cast(cast),
+			Expression::FunctionCall(function_call) => self
+				.function_call(function_call)?
+				.ok_or_else(|| Error::MissingReturnValue { function_call: Box::new(function_call.clone()) }),
+			Expression::FieldAccess(FieldAccess { field: PositionContainer { position, .. }, .. })
+			| Expression::StructLiteral(StructLiteral { name: PositionContainer { position, .. }, .. }) => {
+				Err(Error::UnsupportedStruct { position: position.clone() })
+			},
+			expression @ (Expression::ArrayLiteral(_) | Expression::IndexExpression(_)) => {
+				Err(Error::UnsupportedArray { position: expression.source_position() })
+			},
+			Expression::If(if_expression) => self.if_expression(if_expression),
+			Expression::Number(number) => Ok(match number.value {
+				NumberKind::Int(int) => self.builder.ins().iconst(types::I64, int),
+				NumberKind::Float(float) => self.builder.ins().f64const(float),
+			}),
+			Expression::Bool(boolean) => Ok(self.builder.ins().iconst(types::I8, boolean.value as i64)),
+			Expression::Char(char) => Ok(self.builder.ins().iconst(types::I32, char.value as i64)),
+			Expression::StringLiteral(string) => self.string_literal(string),
+			Expression::Null(_) => Ok(self.builder.ins().iconst(types::I64, 0)),
+			// Local variables cannot shadow constants, so the name always refers to the constant
+			Expression::Variable(name) if self.constants.contains_key(&name.value) => {
+				self.expression(&self.constants[&name.value])
+			},
+			Expression::Variable(name) => {
+				let variable = self.variable(name)?;
+				Ok(self.builder.use_var(variable))
+			},
+		}
+	}
+
+	fn binary_expression(
+		&mut self,
+		binary_expression: &BinaryExpression,
+	) -> Result<cranelift_codegen::ir::Value, Error> {
+		if let BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr = binary_expression.operator.value {
+			return self.logical_expression(binary_expression);
+		}
+		let lhs = self.expression(&binary_expression.lhs)?;
+		let rhs = self.expression(&binary_expression.rhs)?;
+		let operator = &binary_expression.operator;
+
+		let data_type = self.builder.func.dfg.value_type(lhs);
+		if data_type != self.builder.func.dfg.value_type(rhs) {
+			return Err(Error::TypeMismatch { operator: operator.clone() });
+		}
+
+		if data_type == types::F64 && operator.is_bitwise() {
+			return Err(Error::InvalidFloatOperands { operator: operator.clone() });
+		}
+
+		if operator.value == BinaryOperator::Power {
+			let name = if data_type == types::F64 { "ftl.pow" } else { "ftl.int_power" };
+			let power = self.native_call(name, &[lhs, rhs], Some(data_type))?;
+			return Ok(power.expect("power has a return value"));
+		}
+
+		let ins = self.builder.ins();
+		Ok(match (data_type, &operator.value) {
+			(types::F64, BinaryOperator::Add) => ins.fadd(lhs, rhs),
+			(types::F64, BinaryOperator::Subtract) => ins.fsub(lhs, rhs),
+			(types::F64, BinaryOperator::Multiply) => ins.fmul(lhs, rhs),
+			(types::F64, BinaryOperator::Divide) => ins.fdiv(lhs, rhs),
+			(types::F64, BinaryOperator::Less) => ins.fcmp(FloatCC::LessThan, lhs, rhs),
+			(types::F64, BinaryOperator::Greater) => ins.fcmp(FloatCC::GreaterThan, lhs, rhs),
+			(types::F64, BinaryOperator::LessEqual) => ins.fcmp(FloatCC::LessThanOrEqual, lhs, rhs),
+			(types::F64, BinaryOperator::GreaterEqual) => ins.fcmp(FloatCC::GreaterThanOrEqual, lhs, rhs),
+			(types::F64, BinaryOperator::Equal) => ins.fcmp(FloatCC::Equal, lhs, rhs),
+			(types::F64, BinaryOperator::NotEqual) => ins.fcmp(FloatCC::NotEqual, lhs, rhs),
+			(_, BinaryOperator::Add) => ins.iadd(lhs, rhs),
+			(_, BinaryOperator::Subtract) => ins.isub(lhs, rhs),
+			(_, BinaryOperator::Multiply) => ins.imul(lhs, rhs),
+			(_, BinaryOperator::Divide) => ins.sdiv(lhs, rhs),
+			(_, BinaryOperator::BitOr) => ins.bor(lhs, rhs),
+			(_, BinaryOperator::BitAnd) => ins.band(lhs, rhs),
+			(_, BinaryOperator::BitXor) => ins.bxor(lhs, rhs),
+			(_, BinaryOperator::ShiftLeft) => ins.ishl(lhs, rhs),
+			(_, BinaryOperator::ShiftRight) => ins.sshr(lhs, rhs),
+			(_, BinaryOperator::Less) => ins.icmp(IntCC::SignedLessThan, lhs, rhs),
+			(_, BinaryOperator::Greater) => ins.icmp(IntCC::SignedGreaterThan, lhs, rhs),
+			(_, BinaryOperator::LessEqual) => ins.icmp(IntCC::SignedLessThanOrEqual, lhs, rhs),
+			(_, BinaryOperator::GreaterEqual) => ins.icmp(IntCC::SignedGreaterThanOrEqual, lhs, rhs),
+			(_, BinaryOperator::Equal) => ins.icmp(IntCC::Equal, lhs, rhs),
+			(_, BinaryOperator::NotEqual) => ins.icmp(IntCC::NotEqual, lhs, rhs),
+			(_, BinaryOperator::Power) => unreachable!("`**` is translated to a call"),
+			(_, BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr) => {
+				unreachable!("Logical operators are translated to branches")
+			},
+		})
+	}
+
+	fn unary_expression(&mut self, unary_expression: &UnaryExpression) -> Result<cranelift_codegen::ir::Value, Error> {
+		if unary_expression.operator.value == UnaryOperator::Dereference {
+			return Err(Error::UnsupportedDereference { position: unary_expression.operator.position.clone() });
+		}
+		let operand = self.expression(&unary_expression.operand)?;
+		let data_type = self.builder.func.dfg.value_type(operand);
+		let ins = self.builder.ins();
+		Ok(match (data_type, unary_expression.operator.value) {
+			(types::F64, UnaryOperator::Negate) => ins.fneg(operand),
+			(types::I64, UnaryOperator::Negate) => ins.ineg(operand),
+			// Booleans are either 0 or 1
+			(types::I8, UnaryOperator::Not) => ins.bxor_imm_u(operand, 1),
+			_ => return Err(Error::InvalidOperand { operator: unary_expression.operator.clone() }),
+		})
+	}
+
+	/// Translates a cast to a conversion between int and float. Floats are rounded towards zero and saturate at the
+	/// bounds of an int, like in the interpreter. Other casts do not change the value.
+	fn cast(&mut self, cast: &Cast) -> Result<cranelift_codegen::ir::Value, Error> {
+		let value = self.expression(&cast.expression)?;
+		let data_type = self.builder.func.dfg.value_type(value);
+		Ok(match (data_type, &cast.data_type.value) {
+			(types::I64, DataType::Basic(BasicDataType::Float)) => self.builder.ins().fcvt_from_sint(types::F64, value),
+			(types::F64, DataType::Basic(BasicDataType::Int)) => self.builder.ins().fcvt_to_sint_sat(types::I64, value),
+			_ => value,
+		})
+	}
+
+	/// Translates a logical `and`/`or` to branches, so that the rhs is only evaluated if the lhs does not already
+	/// determine the result.
+	fn logical_expression(
+		&mut self,
+		binary_expression: &BinaryExpression,
+	) -> Result<cranelift_codegen::ir::Value, Error> {
+		let rhs_block = self.builder.create_block();
+		let end_block = self.builder.create_block();
+		// The result is passed to the end block as parameter
+		self.builder.append_block_param(end_block, types::I8);
+
+		let lhs = self.expression(&binary_expression.lhs)?;
+		if self.builder.func.dfg.value_type(lhs) != types::I8 {
+			return Err(Error::TypeMismatch { operator: binary_expression.operator.clone() });
+		}
+		// If the rhs is skipped, the result is the value of the lhs
+		match binary_expression.operator.value {
+			BinaryOperator::LogicalAnd => self.builder.ins().brif(lhs, rhs_block, &[], end_block, &[lhs.into()]),
+			_ => self.builder.ins().brif(lhs, end_block, &[lhs.into()], rhs_block, &[]),
+		};
+
+		self.builder.switch_to_block(rhs_block);
+		let rhs = self.expression(&binary_expression.rhs)?;
+		if self.builder.func.dfg.value_type(rhs) != types::I8 {
+			return Err(Error::TypeMismatch { operator: binary_expression.operator.clone() });
+		}
+		self.builder.ins().jump(end_block, &[rhs.into()]);
+
+		self.builder.switch_to_block(end_block);
+		Ok(self.builder.block_params(end_block)[0])
+	}
+
+	fn if_expression(&mut self, if_expression: &IfExpression) -> Result<cranelift_codegen::ir::Value, Error> {
+		let then_block = self.builder.create_block();
+		let else_block = self.builder.create_block();
+		let end_block = self.builder.create_block();
+
+		let condition = self.condition(&if_expression.condition)?;
+		self.builder.ins().brif(condition, then_block, &[], else_block, &[]);
+
+		// The value of the branch is passed to the end block as parameter, whose type is the one of the first branch
+		self.builder.switch_to_block(then_block);
+		let if_true = self.expression(&if_expression.if_true)?;
+		let data_type = self.builder.func.dfg.value_type(if_true);
+		self.builder.append_block_param(end_block, data_type);
+		self.builder.ins().jump(end_block, &[if_true.into()]);
+
+		self.builder.switch_to_block(else_block);
+		let if_false = self.expression(&if_expression.if_false)?;
+		if self.builder.func.dfg.value_type(if_false) != data_type {
+			return Err(Error::BranchTypeMismatch { position: if_expression.position.clone() });
+		}
+		self.builder.ins().jump(end_block, &[if_false.into()]);
+
+		self.builder.switch_to_block(end_block);
+		Ok(self.builder.block_params(end_block)[0])
+	}
+
+	/// Defines the string as a null-terminated data object and returns its address.
+	fn string_literal(&mut self, string: &PositionContainer<String>) -> Result<cranelift_codegen::ir::Value, Error> {
+		let mut bytes = string.value.clone().into_bytes();
+		bytes.push(0);
+		let mut description = DataDescription::new();
+		description.define(bytes.into_boxed_slice());
+
+		let id = self.module.declare_anonymous_data(false, false)?;
+		self.module.define_data(id, &description)?;
+		let global_value = self.module.declare_data_in_func(id, self.builder.func);
+		Ok(self.builder.ins().symbol_value(types::I64, global_value))
+	}
+
+	/// Translates the call and returns the value returned by the function, if it has a return type.
+	fn function_call(&mut self, function_call: &FunctionCall) -> Result<Option<cranelift_codegen::ir::Value>, Error> {
+		let Some((id, prototype)) = self.functions.get(&function_call.name.value) else {
+			return match Builtin::from_name(&function_call.name) {
+				Some(builtin) => self.builtin_call(builtin, function_call),
+				None => Err(Error::UndefinedFunctionCall { function_call: Box::new(function_call.clone()) }),
+			};
+		};
+
+		if function_call.params.len() != prototype.args.len() {
+			return Err(Error::ArgumentCountMismatch {
+				expected: prototype.args.len(),
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+
+		let args = function_call.params.iter().map(|param| self.expression(param)).collect::<Result<Vec<_>, _>>()?;
+		let function = self.module.declare_func_in_func(*id, self.builder.func);
+		let call = self.builder.ins().call(function, &args);
+		Ok(self.builder.inst_results(call).first().copied())
+	}
+
+	/// Translates the call of a builtin to calls of its [native implementation](builtin).
+	fn builtin_call(
+		&mut self,
+		builtin: Builtin,
+		function_call: &FunctionCall,
+	) -> Result<Option<cranelift_codegen::ir::Value>, Error> {
+		if function_call.params.len() != builtin.arity() {
+			return Err(Error::ArgumentCountMismatch {
+				expected: builtin.arity(),
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+		if builtin == Builtin::ReadInt {
+			return self.native_call("ftl.read_int", &[], Some(types::I64));
+		}
+		if let Builtin::Sqrt | Builtin::Abs | Builtin::Pow | Builtin::Min | Builtin::Max = builtin {
+			let args =
+				function_call.params.iter().map(|param| self.expression(param)).collect::<Result<Vec<_>, _>>()?;
+			let ins = self.builder.ins();
+			return Ok(Some(match builtin {
+				Builtin::Sqrt => ins.sqrt(args[0]),
+				Builtin::Abs => ins.fabs(args[0]),
+				Builtin::Min => ins.fmin(args[0], args[1]),
+				Builtin::Max => ins.fmax(args[0], args[1]),
+				_ => return self.native_call("ftl.pow", &args, Some(types::F64)),
+			}));
+		}
+
+		let param = &function_call.params[0];
+		let value = self.expression(param)?;
+		let name = match self.builder.func.dfg.value_type(value) {
+			types::I64 if self.is_string(param) => "ftl.print_str",
+			types::I64 => "ftl.print_int",
+			types::F64 => "ftl.print_float",
+			types::I8 => "ftl.print_bool",
+			_ => "ftl.print_char",
+		};
+		self.native_call(name, &[value], None)?;
+		if builtin == Builtin::Println {
+			self.native_call("ftl.println", &[], None)?;
+		}
+		Ok(None)
+	}
+
+	/// Calls one of the native functions implementing the builtins.
+	fn native_call(
+		&mut self,
+		name: &str,
+		args: &[cranelift_codegen::ir::Value],
+		return_type: Option<Type>,
+	) -> Result<Option<cranelift_codegen::ir::Value>, Error> {
+		let mut signature = self.module.make_signature();
+		for arg in args {
+			signature.params.push(AbiParam::new(self.builder.func.dfg.value_type(*arg)));
+		}
+		signature.returns.extend(return_type.map(AbiParam::new));
+		let id = self.module.declare_function(name, Linkage::Import, &signature)?;
+		let function = self.module.declare_func_in_func(id, self.builder.func);
+		let call = self.builder.ins().call(function, args);
+		Ok(self.builder.inst_results(call).first().copied())
+	}
+
+	/// Whether the `expression` evaluates to a string.
+	fn is_string(&self, expression: &Expression) -> bool {
+		match expression {
+			Expression::StringLiteral(_) => true,
+			Expression::Variable(name) if self.constants.contains_key(&name.value) => {
+				self.is_string(&self.constants[&name.value])
+			},
+			Expression::Variable(name) => self.variable(name).is_ok_and(|variable| self.strings.contains(variable)),
+			Expression::FunctionCall(function_call) => {
+				self.functions.get(&function_call.name.value).is_some_and(|(_, prototype)| {
+					prototype.return_type.as_ref().map(|return_type| &return_type.value)
+						== Some(&DataType::Basic(BasicDataType::Str))
+				})
+			},
+			Expression::Cast(cast) => cast.data_type.value == DataType::Basic(BasicDataType::Str),
+			_ => false,
+		}
+	}
+
+	/// Looks up a variable, starting at the innermost scope.
+	fn variable(&self, name: &PositionContainer<Symbol>) -> Result<Variable, Error> {
+		self.scopes
+			.iter()
+			.rev()
+			.find_map(|scope| scope.get(&name.value).copied())
+			.ok_or_else(|| Error::UndeclaredVariable { name: name.clone() })
+	}
+}
+
+/// The Cranelift type a value of the FTL `data_type` is represented with.
+fn cranelift_type(data_type: &PositionContainer<DataType>) -> Result<Type, Error> {
+	match data_type.value {
+		DataType::Basic(BasicDataType::Int) | DataType::Enum(_) => Ok(types::I64),
+		DataType::Basic(BasicDataType::Float) => Ok(types::F64),
+		DataType::Basic(BasicDataType::Bool) => Ok(types::I8),
+		DataType::Basic(BasicDataType::Str) => Ok(types::I64),
+		DataType::Basic(BasicDataType::Char) => Ok(types::I32),
+		DataType::Pointer(_) | DataType::Null => Ok(types::I64),
+		DataType::Struct(_) | DataType::Generic { .. } | DataType::Array { .. } => {
+			Err(Error::UnsupportedDataType { data_type: data_type.clone() })
+		},
+	}
+}
+
+/// The Cranelift type of the return value, or [`None`] for functions without return type.
+fn return_type(return_type: &Option<PositionContainer<DataType>>) -> Result<Option<Type>, Error> {
+	return_type.as_ref().map(cranelift_type).transpose()
+}
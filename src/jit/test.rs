@@ -0,0 +1,236 @@
+use crate::{
+	interpreter::Value,
+	jit::{Error, Jit},
+	parser,
+};
+
+/// Tests that recursive calls and if-else are compiled to working native code.
+#[test]
+fn test_recursive_function_call() {
+	let source_code = "
+		def fibonacci(n: int): int {
+			if n < 2 {
+				return n
+			}
+			var a: int = (n - 1)
+			var b: int = (n - 2)
+			return fibonacci(a) + fibonacci(b)
+		}
+		def main(): int {
+			return fibonacci(10)
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(55)));
+}
+
+/// Tests that a while loop can modify variables declared outside of it.
+#[test]
+fn test_while_loop() {
+	let source_code = "
+		def main(): float {
+			var i: int = 0
+			var sum: float = 0.0
+			while i < 4 {
+				sum = sum + 0.5
+				i = i + 1
+			}
+			return sum
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Float(2.0)));
+}
+
+/// Tests that `continue` skips the rest of the loop body, but not the step, and that `break` leaves the loop.
+#[test]
+fn test_break_continue() {
+	let source_code = "
+		def main(): int {
+			var sum: int = 0
+			for var i: int = 0; i < 100; i = i + 1 {
+				if i = 2 {
+					continue
+				}
+				if i = 5 {
+					break
+				}
+				sum = sum + i
+			}
+			var j: int = 0
+			while true {
+				j = j + 1
+				if j < 10 {
+					continue
+				}
+				break
+			}
+			return sum + j
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(18)));
+}
+
+/// Tests that `and` binds stronger than `or` and both can be used as conditions.
+#[test]
+fn test_logical_operators() {
+	let source_code = "
+		def main(): int {
+			var count: int = 0
+			var i: int = 0
+			while i < 10 {
+				if i < 2 or i = 5 and true or false and true {
+					count = count + 1
+				}
+				i = i + 1
+			}
+			return count
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(3)));
+}
+
+/// Tests that unary operators bind stronger than binary ones.
+#[test]
+fn test_unary_expression() {
+	let source_code = "
+		def main(): int {
+			var x: int = 3
+			var done: bool = !(x < 2)
+			if !done {
+				return 0
+			}
+			return -x * 2 - -x
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(-3)));
+}
+
+/// Tests that externs are resolved with the symbols of the current process.
+#[test]
+fn test_extern_call() {
+	let source_code = "
+		extern labs(x: int): int
+		def main(): int {
+			return labs((0 - 42))
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(42)));
+}
+
+/// Tests that string literals can be passed to C functions and returned.
+#[test]
+fn test_string_literal() {
+	let source_code = "
+		extern strlen(s: str): int
+		def greeting(): str {
+			return \"hello\"
+		}
+		def main(): int {
+			return strlen(greeting())
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(5)));
+	assert_eq!(run("def main(): str {\n\treturn \"hi\"\n}").unwrap(), Some(Value::Str("hi".to_owned())));
+}
+
+/// Tests that structs are reported as unsupported.
+#[test]
+fn test_unsupported_struct() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def main(p: Point) {
+		}
+	";
+	assert!(matches!(run(source_code), Err(Error::UnsupportedDataType { .. })));
+}
+
+/// Tests that builtins are called natively, with strings being told apart from integers.
+#[test]
+fn test_builtin_function() {
+	let source_code = "
+		def main(): int {
+			var empty = \"\"
+			print(empty)
+			return 42
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(42)));
+}
+
+/// Tests that `**` calls the native functions for integers and floats.
+#[test]
+fn test_power() {
+	let source_code = "
+		def main(): int {
+			var half: float = (0.25 ** 0.5)
+			return 3 ** 4 + (2 ** -1) + (half * 2.0) as int
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(82)));
+}
+
+/// Tests that an if-expression passes the value of the taken branch on.
+#[test]
+fn test_if_expression() {
+	let source_code = "
+		def main(): int {
+			var x: float = 2.5
+			var y = if x < 0.0 { 0.0 } else { x }
+			return if y = x { 1 } else { 0 }
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(1)));
+}
+
+/// Tests that a match on the result of a call selects the arm of the matching pattern.
+#[test]
+fn test_match() {
+	let source_code = "
+		def twice(x: int): int {
+			return x * 2
+		}
+		def main(): int {
+			var result: int = 0
+			match twice(3) {
+				2, 4 => {
+					result = 1
+				}
+				6 => {
+					result = 2
+				}
+				_ => {
+					result = 3
+				}
+			}
+			return result
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(2)));
+}
+
+/// Tests that variants are compiled to their index.
+#[test]
+fn test_enum() {
+	let source_code = "
+		enum Color { Red, Green, Blue }
+		def main(): int {
+			var color: Color = Color.Blue
+			match color {
+				Color.Red, Color.Green => {
+					return 0
+				}
+				Color.Blue => {
+					return color as int
+				}
+			}
+		}
+	";
+	assert_eq!(run(source_code).unwrap(), Some(Value::Int(2)));
+}
+
+/// Boilerplate code for lexing, parsing, compiling and executing source code.
+fn run(source_code: &str) -> Result<Option<Value>, Error> {
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	Jit::compile(ast_nodes.into_iter())?.run()
+}
@@ -11,4 +11,16 @@ pub enum Error {
 	IllegalSymbol(Option<Symbol>),
 	#[error("Could not parse number {0}")]
 	ParseNumberError(PositionContainer<String>),
+	#[error("Invalid digit `{digit}` for base {base} in number {number}")]
+	InvalidDigit { number: PositionContainer<String>, digit: char, base: u32 },
+	#[error("Identifier {0} must not start with a digit")]
+	LeadingDigit(PositionContainer<String>),
+	#[error("Underscores in number {0} are only allowed between digits")]
+	MisplacedUnderscore(PositionContainer<String>),
+	#[error("Unterminated string literal {0}")]
+	UnterminatedStringLiteral(PositionContainer<String>),
+	#[error("Character literal {0} has to contain exactly one character and end with `'` in the same line")]
+	InvalidCharLiteral(PositionContainer<String>),
+	#[error("Unterminated block comment {0}")]
+	UnterminatedBlockComment(PositionContainer<String>),
 }
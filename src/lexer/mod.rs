@@ -4,18 +4,39 @@ mod error;
 #[cfg(test)]
 mod test;
 
-use std::{iter::Peekable, ops::Deref};
+use std::{iter::Peekable, sync::Arc};
 
 pub use error::Error;
 
 use crate::{
-	source::{PositionContainer, Symbol},
+	error::DiagnosticSink,
+	source::{PositionContainer, Source, SourcePositionRange, Symbol},
+	symbol,
 	token::{Token, TokenKind},
 };
 
 /// [`Token`] or [`lexer::Error`](Error).
 pub type LexResult = Result<Token, Error>;
 
+/// Lexes a snippet of source code, like in a test, without building a [`Source`] first. Unlike collecting the
+/// [`LexResult`]s of a [`Lexer`], this does not stop at the first error, but returns all errors.
+///
+/// ```
+/// use fortytwolang::{lexer, TokenKind};
+///
+/// let tokens = lexer::tokenize_str("x + 1").unwrap();
+/// assert_eq!(tokens[1].value, TokenKind::Plus);
+/// assert_eq!(lexer::tokenize_str("x $ y $").unwrap_err().len(), 2);
+/// ```
+pub fn tokenize_str(source_code: &str) -> Result<Vec<Token>, Vec<Error>> {
+	let source = Arc::new(Source::new("<string>".to_owned(), source_code.to_owned()));
+	let (tokens, errors) = Lexer::new(source.iter()).tokenize();
+	match errors.is_empty() {
+		true => Ok(tokens),
+		false => Err(errors),
+	}
+}
+
 /// Analyzes the source code char-by-char and converts it to [`Token`]s.
 ///
 /// A lexer is the first phase of a compiler. It analyses the text of the sourcecode and builds
@@ -27,6 +48,11 @@ where
 {
 	/// Iterator over [`Symbol`]s of the source code.
 	symbols: Peekable<T>,
+	/// Receives warnings about suspicious tokens.
+	diagnostics: DiagnosticSink,
+	/// The chars of the identifier or keyword being read. It is reused for all of them, so that lexing a name only
+	/// allocates when it is [interned](symbol::Symbol::intern) for the first time.
+	word: String,
 }
 
 impl<T> Lexer<T>
@@ -35,7 +61,28 @@ where
 {
 	/// Creates a [`Lexer`] from the given [`Symbol`] iterator.
 	pub fn new(symbols: T) -> Self {
-		Self { symbols: symbols.peekable() }
+		Self { symbols: symbols.peekable(), diagnostics: DiagnosticSink::default(), word: String::new() }
+	}
+
+	/// Emits warnings to `diagnostics` instead of discarding them.
+	pub fn with_diagnostics(mut self, diagnostics: DiagnosticSink) -> Self {
+		self.diagnostics = diagnostics;
+		self
+	}
+
+	/// Lexes all tokens. Unlike collecting the [`LexResult`]s, this does not stop at the first error: Invalid chars,
+	/// like an unknown symbol, are skipped and lexing continues after them, so that a stray char does not hide the
+	/// errors after it. The errors are returned in the order they occurred.
+	pub fn tokenize(self) -> (Vec<Token>, Vec<Error>) {
+		let mut tokens = Vec::new();
+		let mut errors = Vec::new();
+		for result in self {
+			match result {
+				Ok(token) => tokens.push(token),
+				Err(error) => errors.push(error),
+			}
+		}
+		(tokens, errors)
 	}
 
 	/// Checks whether [`Self::symbols`] is going to yield a whitespace next.
@@ -60,22 +107,24 @@ where
 		let symbol = self.symbols.peek()?.clone();
 
 		let token = match symbol {
-			symbol if symbol.is_alphabetic() => {
-				let read_string = self.read_string();
-				parse_string(read_string)
+			// Identifiers may start with `_`, e.g. to mark unused variables
+			symbol if is_identifier_start(*symbol) => {
+				let position = self.read_word();
+				Ok(parse_word(&self.word, position))
 			},
-			symbol if symbol.is_numeric() => {
+			symbol if symbol.is_ascii_digit() => {
 				let number = self.read_number();
+				self.warn_leading_zeros(&number);
 				parse_number(number)
 			},
 			symbol if is_comment(*symbol) => {
 				let comment = self.read_comment();
-				Ok(Token::new(TokenKind::Comment((*comment).clone()), comment.position))
-			},
-			symbol if *symbol == '"' => {
-				let string = self.read_string_literal();
-				Ok(Token::new(TokenKind::StringLiteral(string.value), string.position))
+				Ok(Token::new(TokenKind::Comment(comment.value), comment.position))
 			},
+			symbol if *symbol == '"' => self
+				.read_string_literal()
+				.map(|string| Token::new(TokenKind::StringLiteral(string.value), string.position)),
+			symbol if *symbol == '\'' => self.read_char_literal(),
 			/*symbol if symbol == '\n' => {
 				// Consume newline
 				assert_eq!(self.letters.next().map(&|(_, letter)| letter), Some('\n'));
@@ -94,8 +143,17 @@ where
 		Some(token)
 	}
 
+	/// Warns about a decimal integer with leading zeros like `0755`, which might be meant as an octal number.
+	fn warn_leading_zeros(&self, number: &PositionContainer<String>) {
+		let digits = number.replace('_', "");
+		if digits.len() > 1 && digits.starts_with('0') && digits.chars().all(|digit| digit.is_ascii_digit()) {
+			let message = format!("Leading zeros in decimal number `{}`, use `0o` for an octal number", number.value);
+			self.diagnostics.warn("W0001", message, Some(&number.position));
+		}
+	}
+
 	/// Reads a string literal, i.e. something enclosed by `"`, while also taking care of escaping.
-	fn read_string_literal(&mut self) -> PositionContainer<String> {
+	fn read_string_literal(&mut self) -> Result<PositionContainer<String>, Error> {
 		// Discard starting quotes
 		let starting_quotes = self.symbols.next().unwrap();
 		assert_eq!(starting_quotes.value, '"');
@@ -105,7 +163,10 @@ where
 
 		while let Some(mut symbol) = self.symbols.peek().cloned() {
 			if *symbol == '"' {
-				break
+				// Consume closing quotes
+				position.position.end = symbol.position.position.end;
+				self.symbols.next();
+				return Ok(PositionContainer::new(string, position));
 			}
 
 			// Escaping
@@ -117,34 +178,57 @@ where
 					None => break
 				};
 
-				symbol.value = match symbol.value {
-					'n' => '\n',
-					'r' => '\r',
-					symbol => symbol,
-				}
+				symbol.value = unescape(symbol.value);
 			}
 
 			string.push(*symbol);
 			position.position.end = symbol.position.position.end;
 			self.symbols.next();
 		}
-		PositionContainer::new(string, position)
+		// File read to end without closing quotes
+		Err(Error::UnterminatedStringLiteral(PositionContainer::new(string, position)))
 	}
 
-	/// Reads a string from [`Self::symbols`].
-	fn read_string(&mut self) -> PositionContainer<String> {
-		let mut string = String::new();
-		let mut position = self.symbols.peek().unwrap().position.clone();
-		while let Some(symbol) = self.symbols.peek().cloned() {
-			let is_string_char = symbol.is_alphanumeric() || *symbol == '_';
-			if !is_string_char {
-				break;
+	/// Reads a character literal, i.e. a single character enclosed by `'`, which may be escaped like in string literals.
+	fn read_char_literal(&mut self) -> LexResult {
+		// Discard starting quote
+		let starting_quote = self.symbols.next().unwrap();
+		let mut position = starting_quote.position.clone();
+		let mut chars = String::new();
+
+		// Read until the closing quote, but not beyond the line, so that a missing quote is reported where it belongs
+		while let Some(mut symbol) = self.symbols.next_if(|symbol| **symbol != '\n') {
+			position.position.end = symbol.position.position.end;
+			if *symbol == '\'' {
+				let mut iter = chars.chars();
+				return match (iter.next(), iter.next()) {
+					(Some(char), None) => Ok(Token::new(TokenKind::Char(char), position)),
+					_ => Err(Error::InvalidCharLiteral(PositionContainer::new(chars, position))),
+				};
 			}
-			string.push(*symbol);
+			if *symbol == '\\' {
+				symbol = match self.symbols.next_if(|symbol| **symbol != '\n') {
+					Some(symbol) => symbol,
+					None => break,
+				};
+				position.position.end = symbol.position.position.end;
+				symbol.value = unescape(symbol.value);
+			}
+			chars.push(*symbol);
+		}
+		// Line or file read to end without closing quote
+		Err(Error::InvalidCharLiteral(PositionContainer::new(chars, position)))
+	}
+
+	/// Reads an identifier or keyword from [`Self::symbols`] into [`Self::word`] and returns its position.
+	fn read_word(&mut self) -> SourcePositionRange {
+		self.word.clear();
+		let mut position = self.symbols.peek().unwrap().position.clone();
+		while let Some(symbol) = self.symbols.next_if(|symbol| is_identifier_continue(**symbol)) {
+			self.word.push(*symbol);
 			position.position.end = symbol.position.position.end;
-			self.symbols.next();
 		}
-		PositionContainer::new(string, position)
+		position
 	}
 
 	/// Reads a number from [`Self::symbols`].
@@ -152,13 +236,22 @@ where
 		let mut number = String::new();
 		let mut position = self.symbols.peek().unwrap().position.clone();
 		while let Some(symbol) = self.symbols.peek().cloned() {
-			let is_number_char = symbol.is_numeric() || *symbol == '.';
+			// Letters are read as well, so that digits of other bases and invalid digits belong to the number
+			let is_number_char = is_identifier_continue(*symbol) || *symbol == '.';
 			if !is_number_char {
 				break;
 			}
 			number.push(*symbol);
 			position.position.end = symbol.position.position.end;
 			self.symbols.next();
+			// The sign of the exponent of a float like `2E-3`, but not the subtraction in `0x1E-3`
+			let is_hexadecimal = number.starts_with("0x") || number.starts_with("0X");
+			if (*symbol == 'e' || *symbol == 'E') && !is_hexadecimal {
+				if let Some(sign) = self.symbols.next_if(|sign| **sign == '+' || **sign == '-') {
+					number.push(*sign);
+					position.position.end = sign.position.position.end;
+				}
+			}
 		}
 		PositionContainer::new(number, position)
 	}
@@ -168,26 +261,76 @@ where
 		let symbol = self.symbols.next().unwrap();
 		let position = symbol.position.clone();
 		match *symbol {
-			'+' => Ok(Token::new(TokenKind::Plus, position)),
-			'-' => Ok(Token::new(TokenKind::Minus, position)),
-			'*' => Ok(Token::new(TokenKind::Star, position)),
+			'/' if self.symbols.peek().is_some_and(|star| **star == '*') => self.read_block_comment(symbol),
+			'*' if self.symbols.peek().is_some_and(|star| **star == '*') => {
+				let mut position = position;
+				position.position.end = self.symbols.next().unwrap().position.position.end;
+				Ok(Token::new(TokenKind::Power, position))
+			},
+			'+' | '-' | '*' | '/' => {
+				// Either the arithmetic operator itself or its compound assignment, like `+=`
+				let equal = self.symbols.next_if(|equal| **equal == '=');
+				let mut position = position;
+				if let Some(equal) = &equal {
+					position.position.end = equal.position.position.end;
+				}
+				Ok(Token::new(
+					match (*symbol, equal.is_some()) {
+						('+', false) => TokenKind::Plus,
+						('+', true) => TokenKind::PlusEqual,
+						('-', false) => TokenKind::Minus,
+						('-', true) => TokenKind::MinusEqual,
+						('*', false) => TokenKind::Star,
+						('*', true) => TokenKind::StarEqual,
+						('/', false) => TokenKind::Slash,
+						_ => TokenKind::SlashEqual,
+					},
+					position,
+				))
+			},
 			',' => Ok(Token::new(TokenKind::Comma, position)),
 			'(' => Ok(Token::new(TokenKind::OpeningParentheses, position)),
 			')' => Ok(Token::new(TokenKind::ClosingParentheses, position)),
 			'{' => Ok(Token::new(TokenKind::OpeningCurlyBraces, position)),
 			'}' => Ok(Token::new(TokenKind::ClosingCurlyBraces, position)),
-			'<' => Ok(Token::new(TokenKind::Less, position)),
-			'>' => Ok(Token::new(TokenKind::Greater, position)),
+			'<' | '>' => {
+				// Either `<=`/`>=` or the shifts `<<`/`>>`
+				let second = self.symbols.next_if(|second| **second == '=' || **second == *symbol);
+				let mut position = position;
+				if let Some(second) = &second {
+					position.position.end = second.position.position.end;
+				}
+				Ok(Token::new(
+					match (*symbol, second.as_deref()) {
+						('<', None) => TokenKind::Less,
+						('<', Some('=')) => TokenKind::LessEqual,
+						('<', Some(_)) => TokenKind::ShiftLeft,
+						('>', None) => TokenKind::Greater,
+						('>', Some('=')) => TokenKind::GreaterEqual,
+						_ => TokenKind::ShiftRight,
+					},
+					position,
+				))
+			},
+			'|' => Ok(Token::new(TokenKind::BitOr, position)),
+			'&' => Ok(Token::new(TokenKind::BitAnd, position)),
+			'^' => Ok(Token::new(TokenKind::BitXor, position)),
 			'.' => Ok(Token::new(TokenKind::Dot, position)),
 			':' => Ok(Token::new(TokenKind::Colon, position)),
-			'/' => Ok(Token::new(TokenKind::Slash, position)),
 			';' => Ok(Token::new(TokenKind::Semicolon, position)),
+			'!' => Ok(Token::new(TokenKind::Not, position)),
 			'[' => Ok(Token::new(TokenKind::OpeningSquareBrackets, position)),
 			']' => Ok(Token::new(TokenKind::ClosingSquareBrackets, position)),
 			'=' => {
 				match self.symbols.peek() {
 					// Read token is `=/` so far
 					Some(symbol) if **symbol == '/' => self.symbols.next(),
+					// Read token is `=>` of a `match` arm
+					Some(symbol) if **symbol == '>' => {
+						let mut position = position;
+						position.position.end = self.symbols.next().unwrap().position.position.end;
+						return Ok(Token::new(TokenKind::FatArrow, position));
+					},
 					// Ok, only a single `=` as token
 					_ => return Ok(Token::new(TokenKind::Equal, position)),
 				};
@@ -202,6 +345,41 @@ where
 		}
 	}
 
+	/// Reads a block comment `/* ... */` after its opening `/` and returns it as [`TokenKind::Comment`].
+	///
+	/// Block comments nest, so that code containing block comments can be commented out.
+	fn read_block_comment(&mut self, slash: Symbol) -> LexResult {
+		// Skip the `*` of the opening `/*`
+		self.symbols.next();
+
+		let mut position = slash.position;
+		let mut comment = String::new();
+		let mut depth = 1;
+		while let Some(symbol) = self.symbols.next() {
+			position.position.end = symbol.position.position.end;
+			match *symbol {
+				'*' if self.symbols.peek().is_some_and(|slash| **slash == '/') => {
+					let slash = self.symbols.next().unwrap();
+					position.position.end = slash.position.position.end;
+					depth -= 1;
+					if depth == 0 {
+						trim(&mut comment);
+						return Ok(Token::new(TokenKind::Comment(comment), position));
+					}
+					comment.push_str("*/");
+				},
+				'/' if self.symbols.peek().is_some_and(|star| **star == '*') => {
+					self.symbols.next();
+					depth += 1;
+					comment.push_str("/*");
+				},
+				letter => comment.push(letter),
+			}
+		}
+		// File read to end without closing `*/`
+		Err(Error::UnterminatedBlockComment(PositionContainer::new(comment, position)))
+	}
+
 	/// Reads a comment and returns its content.
 	fn read_comment(&mut self) -> PositionContainer<String> {
 		// Skip comment symbol
@@ -232,42 +410,109 @@ where
 			}
 		}
 		// Remove potential trailing whitespaces
-		comment = comment.trim().to_owned();
+		trim(&mut comment);
 		PositionContainer::new(comment, postion)
 	}
 }
 
-/// Parses a string to a keyword (`def`, `if`, `else`, ...), or to a [`TokenKind::Identifier`] otherwise.
-fn parse_string(string: PositionContainer<String>) -> LexResult {
-	Ok(match string.as_str() {
-		"def" => Token::new(TokenKind::Def, string.position),
-		"extern" => Token::new(TokenKind::Extern, string.position),
-		"bitor" => Token::new(TokenKind::BitOr, string.position),
-		"bitand" => Token::new(TokenKind::BitAnd, string.position),
-		"mod" => Token::new(TokenKind::Modulus, string.position),
-		"if" => Token::new(TokenKind::If, string.position),
-		"else" => Token::new(TokenKind::Else, string.position),
-		"while" => Token::new(TokenKind::While, string.position),
-		"ptr" => Token::new(TokenKind::Pointer, string.position),
-		"struct" => Token::new(TokenKind::Struct, string.position),
-		"var" => Token::new(TokenKind::Var, string.position),
-		"return" => Token::new(TokenKind::Return, string.position),
-		_ => Token::new(TokenKind::Identifier(string.deref().to_owned()), string.position),
-	})
+/// Removes leading and trailing whitespace from the `string` without copying it.
+fn trim(string: &mut String) {
+	string.truncate(string.trim_end().len());
+	string.drain(..string.len() - string.trim_start().len());
+}
+
+/// Parses a word to a keyword (`def`, `if`, `else`, ...), or to a [`TokenKind::Identifier`] otherwise.
+fn parse_word(word: &str, position: SourcePositionRange) -> Token {
+	let kind = match word {
+		"def" => TokenKind::Def,
+		"extern" => TokenKind::Extern,
+		"true" => TokenKind::True,
+		"false" => TokenKind::False,
+		"null" => TokenKind::Null,
+		"mod" => TokenKind::Modulus,
+		// Keywords from before the operators `|` and `&`, which existing programs may still use
+		"bitor" => TokenKind::BitOr,
+		"bitand" => TokenKind::BitAnd,
+		"and" => TokenKind::LogicalAnd,
+		"or" => TokenKind::LogicalOr,
+		"if" => TokenKind::If,
+		"else" => TokenKind::Else,
+		"while" => TokenKind::While,
+		"for" => TokenKind::For,
+		"match" => TokenKind::Match,
+		"break" => TokenKind::Break,
+		"continue" => TokenKind::Continue,
+		"ptr" => TokenKind::Pointer,
+		"struct" => TokenKind::Struct,
+		"enum" => TokenKind::Enum,
+		"type" => TokenKind::Type,
+		"var" => TokenKind::Var,
+		"const" => TokenKind::Const,
+		"return" => TokenKind::Return,
+		"as" => TokenKind::As,
+		"import" => TokenKind::Import,
+		_ => TokenKind::Identifier(symbol::Symbol::intern(word)),
+	};
+	Token::new(kind, position)
 }
 
 /// Parses a number to a [`TokenKind::Float`].
 fn parse_number(number_str: PositionContainer<String>) -> LexResult {
-	let is_float = number_str.contains('.');
-	if is_float {
-		let float: f64 = number_str.parse().map_err(|_| Error::ParseNumberError(number_str.clone()))?;
-		Ok(Token::new(TokenKind::Float(float), number_str.position))
-	} else {
-		let int: i64 = number_str.parse().map_err(|_| Error::ParseNumberError(number_str.clone()))?;
+	let base = match number_str.get(..2) {
+		Some("0x" | "0X") => 16,
+		Some("0o" | "0O") => 8,
+		Some("0b" | "0B") => 2,
+		_ => 10,
+	};
+	let digits = if base == 10 { &number_str[..] } else { &number_str[2..] };
+	// A word like `2nd`, which is no float with an exponent like `2e3`
+	let is_letter = |symbol: char| symbol != '_' && is_identifier_start(symbol) && !matches!(symbol, 'e' | 'E');
+	if base == 10 && !digits.contains('.') && digits.chars().any(is_letter) {
+		return Err(Error::LeadingDigit(number_str.clone()));
+	}
+	// Underscores are only allowed between digits
+	if digits.starts_with('_') || digits.ends_with('_') || digits.contains("_.") || digits.contains("._") {
+		return Err(Error::MisplacedUnderscore(number_str.clone()));
+	}
+	let digits = digits.replace('_', "");
+	let is_float = base == 10 && digits.contains(['.', 'e', 'E']);
+	if base != 10 || !is_float {
+		if let Some(digit) = digits.chars().find(|digit| !digit.is_digit(base)) {
+			return Err(Error::InvalidDigit { number: number_str.clone(), digit, base });
+		}
+		let int = i64::from_str_radix(&digits, base).map_err(|_| Error::ParseNumberError(number_str.clone()))?;
 		Ok(Token::new(TokenKind::Int(int), number_str.position))
+	} else {
+		// A float too large for f64, like `1e400`, would be infinity, which has no literal
+		let float = digits.parse::<f64>().ok().filter(|float| float.is_finite());
+		let float = float.ok_or_else(|| Error::ParseNumberError(number_str.clone()))?;
+		Ok(Token::new(TokenKind::Float(float), number_str.position))
+	}
+}
+
+/// Replaces the character after a `\\` in a string or character literal with the character it stands for.
+fn unescape(symbol: char) -> char {
+	match symbol {
+		'n' => '\n',
+		'r' => '\r',
+		't' => '\t',
+		'0' => '\0',
+		symbol => symbol,
 	}
 }
 
+/// Checks whether `letter` may start an identifier, i.e. is `_` or has the Unicode property `XID_Start`, like `a`, `ö`
+/// or `λ`.
+fn is_identifier_start(letter: char) -> bool {
+	letter == '_' || unicode_ident::is_xid_start(letter)
+}
+
+/// Checks whether `letter` may follow the first letter of an identifier, i.e. has the Unicode property
+/// `XID_Continue`. These are the letters of [`is_identifier_start`], digits, `_` and combining marks like U+0301.
+fn is_identifier_continue(letter: char) -> bool {
+	unicode_ident::is_xid_continue(letter)
+}
+
 /// Checks whether `letter` is a letter that starts a comment line.
 fn is_comment(letter: char) -> bool {
 	letter == '#'
@@ -275,7 +520,7 @@ fn is_comment(letter: char) -> bool {
 
 /// Checks whether `letter` is a special character like `+`, `-`, `=`, `*`.
 fn is_special_char(letter: char) -> bool {
-	['+', '-', '=', '<', '*', '(', ')', '{', '}', '.', ':', ',', '/', ';', '[', ']'].contains(&letter)
+	['+', '-', '=', '<', '>', '*', '(', ')', '{', '}', '.', ':', ',', '/', ';', '[', ']', '!', '|', '&', '^'].contains(&letter)
 }
 
 impl<T> Iterator for Lexer<T>
@@ -1,6 +1,8 @@
 use std::sync::Arc;
+use crate::error::DiagnosticSink;
 use crate::lexer::*;
 use crate::source::Source;
+use crate::symbol::Symbol;
 use crate::token::Token;
 
 
@@ -11,11 +13,58 @@ fn test_read_string_literal() {
     assert_eq!(tokens[0].value, TokenKind::StringLiteral(r#"hello "name"!"#.to_owned()));
 }
 
+/// Tests that the closing quotes of a string literal are consumed and escape sequences are replaced.
+#[test]
+fn test_read_string_literal_escapes() {
+    let tokens = lexer(r#""a\tb\n" x"#);
+    assert_eq!(tokens[0].value, TokenKind::StringLiteral("a\tb\n".to_owned()));
+    assert_eq!(tokens[1].value, TokenKind::Identifier(Symbol::intern("x")));
+}
+
+/// Tests that a string literal without closing quotes is an error.
+#[test]
+fn test_unterminated_string_literal() {
+    let source = Arc::new(Source::new("testfile".to_owned(), r#""hello"#.to_owned()));
+    let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+    assert!(matches!(result, Err(Error::UnterminatedStringLiteral(_))));
+}
+
+/// Tests that block comments may span multiple lines and nest, and that their position covers the whole comment.
+#[test]
+fn test_read_block_comment() {
+    let tokens = lexer("a /* one\n/* two */ */ b");
+    assert_eq!(tokens[1].value, TokenKind::Comment("one\n/* two */".to_owned()));
+    assert_eq!(tokens[1].position.position.start.line, tokens[0].position.position.start.line);
+    assert_eq!(tokens[1].position.position.end.line, tokens[2].position.position.start.line);
+    assert_eq!(tokens[2].value, TokenKind::Identifier(Symbol::intern("b")));
+    assert_eq!(lexer("a / *b")[1].value, TokenKind::Slash);
+}
+
+/// Tests that a block comment without closing `*/` is an error, also if only a nested comment was closed.
+#[test]
+fn test_unterminated_block_comment() {
+    let source = Arc::new(Source::new("testfile".to_owned(), "/* a /* b */".to_owned()));
+    let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+    assert!(matches!(result, Err(Error::UnterminatedBlockComment(_))));
+}
+
+/// Tests that character literals contain exactly one, possibly escaped, character.
+#[test]
+fn test_read_char_literal() {
+    let kinds: Vec<TokenKind> = lexer(r"'a' '\n' '\'' 'ä'").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![TokenKind::Char('a'), TokenKind::Char('\n'), TokenKind::Char('\''), TokenKind::Char('ä')]);
+    for source_code in ["''", "'ab'", "'a\n'"] {
+        let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+        let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+        assert!(matches!(result, Err(Error::InvalidCharLiteral(_))), "{}", source_code);
+    }
+}
+
 /// Tests that the lexer can read an identifier.
 #[test]
 fn test_read_identifier() {
     let tokens = lexer("hello");
-    assert_eq!(tokens[0].value, TokenKind::Identifier("hello".to_owned()));
+    assert_eq!(tokens[0].value, TokenKind::Identifier(Symbol::intern("hello")));
 }
 
 /// Tests that the lexer can read a float.
@@ -25,6 +74,79 @@ fn test_read_int() {
     assert_eq!(tokens[0].value, TokenKind::Int(42));
 }
 
+/// Tests that the lexer can read hexadecimal, octal and binary integers.
+#[test]
+fn test_read_int_bases() {
+    let kinds: Vec<TokenKind> = lexer("0x1F 0XfF 0o755 0b1010").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![TokenKind::Int(31), TokenKind::Int(255), TokenKind::Int(493), TokenKind::Int(10)]);
+}
+
+/// Tests that decimal integers with leading zeros are still decimal, but cause a warning.
+#[test]
+fn test_leading_zeros_warning() {
+    let source = Arc::new(Source::new("testfile".to_owned(), "0755 0 0.5 0o755".to_owned()));
+    let diagnostics = DiagnosticSink::default();
+    let tokens = Lexer::new(source.iter()).with_diagnostics(diagnostics.clone()).collect::<Result<Vec<Token>, Error>>().unwrap();
+    assert_eq!(tokens[0].value, TokenKind::Int(755));
+    let warnings = diagnostics.take();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, "W0001");
+}
+
+/// Tests that floats can have an exponent with an optional sign, but hexadecimal integers cannot.
+#[test]
+fn test_read_float_exponent() {
+    let kinds: Vec<TokenKind> = lexer("1.5e10 2E-3 1e+2 0x1E-3").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Float(1.5e10),
+        TokenKind::Float(2E-3),
+        TokenKind::Float(1e2),
+        TokenKind::Int(0x1E),
+        TokenKind::Minus,
+        TokenKind::Int(3),
+    ]);
+}
+
+/// Tests that underscores between digits are ignored, but not allowed at the start, at the end or next to the dot.
+#[test]
+fn test_read_number_underscores() {
+    let kinds: Vec<TokenKind> = lexer("1_000_000 1.234_5 0xFF_FF").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![TokenKind::Int(1_000_000), TokenKind::Float(1.2345), TokenKind::Int(0xFF_FF)]);
+    for source_code in ["1_", "1_.5", "1._5", "0x_1"] {
+        let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+        let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+        assert!(matches!(result, Err(Error::MisplacedUnderscore(_))), "{}", source_code);
+    }
+}
+
+/// Tests that digits not belonging to the base of an integer are reported.
+#[test]
+fn test_invalid_digit() {
+    let lex = |source_code: &str| {
+        let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+        Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>()
+    };
+    assert!(matches!(lex("0b102"), Err(Error::InvalidDigit { digit: '2', base: 2, .. })));
+    assert!(matches!(lex("0o8"), Err(Error::InvalidDigit { digit: '8', base: 8, .. })));
+    assert!(matches!(lex("0x1G"), Err(Error::InvalidDigit { digit: 'G', base: 16, .. })));
+    assert!(matches!(lex("1\u{663}"), Err(Error::InvalidDigit { digit: '\u{663}', base: 10, .. })));
+    assert!(matches!(lex("0x"), Err(Error::ParseNumberError(_))));
+}
+
+/// Tests that floats too large for f64 are an error instead of infinity, like ints too large for i64.
+#[test]
+fn test_float_overflow() {
+    let lex = |source_code: &str| {
+        let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+        Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>()
+    };
+    assert!(matches!(lex("1e400"), Err(Error::ParseNumberError(number)) if number.value == "1e400"));
+    assert!(matches!(lex("9223372036854775808"), Err(Error::ParseNumberError(_))));
+    assert_eq!(lexer("1e308")[0].value, TokenKind::Float(1e308));
+    // Too small floats are rounded to zero
+    assert_eq!(lexer("1e-400")[0].value, TokenKind::Float(0.0));
+}
+
 /// Tests that the lexer can read a float.
 #[test]
 fn test_read_float() {
@@ -32,6 +154,157 @@ fn test_read_float() {
     assert_eq!(tokens[0].value, TokenKind::Float(4.2));
 }
 
+/// Tests that the lexer reads `true` and `false` as keywords.
+#[test]
+fn test_read_bool() {
+    let tokens = lexer("true false");
+    assert_eq!(tokens[0].value, TokenKind::True);
+    assert_eq!(tokens[1].value, TokenKind::False);
+}
+
+/// Tests that the lexer reads `null` as a keyword.
+#[test]
+fn test_read_null() {
+    let tokens = lexer("null nullable");
+    assert_eq!(tokens[0].value, TokenKind::Null);
+    assert_eq!(tokens[1].value, TokenKind::Identifier(Symbol::intern("nullable")));
+}
+
+/// Tests that the lexer reads `import` as a keyword and a qualified name as identifiers separated by a dot.
+#[test]
+fn test_read_import() {
+    let tokens = lexer("import math\nmath.square");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Import,
+        TokenKind::Identifier(Symbol::intern("math")),
+        TokenKind::Identifier(Symbol::intern("math")),
+        TokenKind::Dot,
+        TokenKind::Identifier(Symbol::intern("square")),
+    ]);
+}
+
+/// Tests that comparison operators with a trailing `=` are read as a single token.
+#[test]
+fn test_read_comparison_operators() {
+    let tokens = lexer("< <= > >= a<=b");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Less,
+        TokenKind::LessEqual,
+        TokenKind::Greater,
+        TokenKind::GreaterEqual,
+        TokenKind::Identifier(Symbol::intern("a")),
+        TokenKind::LessEqual,
+        TokenKind::Identifier(Symbol::intern("b")),
+    ]);
+}
+
+/// Tests that the bitwise operators are read, and that `<<`/`>>` are not confused with comparisons.
+#[test]
+fn test_read_bitwise_operators() {
+    let tokens = lexer("| & ^ << >> a<<=b");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::BitOr,
+        TokenKind::BitAnd,
+        TokenKind::BitXor,
+        TokenKind::ShiftLeft,
+        TokenKind::ShiftRight,
+        TokenKind::Identifier(Symbol::intern("a")),
+        TokenKind::ShiftLeft,
+        TokenKind::Equal,
+        TokenKind::Identifier(Symbol::intern("b")),
+    ]);
+}
+
+/// Tests that the keywords `bitor` and `bitand` are still read as the operators `|` and `&`.
+#[test]
+fn test_read_bitwise_keywords() {
+    let tokens = lexer("a bitor b bitand c");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::Identifier(Symbol::intern("a")),
+        TokenKind::BitOr,
+        TokenKind::Identifier(Symbol::intern("b")),
+        TokenKind::BitAnd,
+        TokenKind::Identifier(Symbol::intern("c")),
+    ]);
+}
+
+/// Tests that an arithmetic operator directly followed by `=` is read as compound assignment.
+#[test]
+fn test_read_compound_assignment() {
+    let tokens = lexer("+= -= *= /= - =");
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![
+        TokenKind::PlusEqual,
+        TokenKind::MinusEqual,
+        TokenKind::StarEqual,
+        TokenKind::SlashEqual,
+        TokenKind::Minus,
+        TokenKind::Equal,
+    ]);
+}
+
+/// Tests that two stars are read as power operator, but are separate multiplication tokens if separated.
+#[test]
+fn test_read_power() {
+    let kinds: Vec<TokenKind> = lexer("** * * **=").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![TokenKind::Power, TokenKind::Star, TokenKind::Star, TokenKind::Power, TokenKind::Equal]);
+}
+
+/// Tests that `=>` is read as one token, but `= >` as two.
+#[test]
+fn test_read_fat_arrow() {
+    let kinds: Vec<TokenKind> = lexer("match => = >").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, vec![TokenKind::Match, TokenKind::FatArrow, TokenKind::Equal, TokenKind::Greater]);
+}
+
+/// Tests that unknown symbols are reported with their position and skipped, so that lexing continues after them.
+#[test]
+fn test_skip_unknown_symbols() {
+    let source = Arc::new(Source::new("testfile".to_owned(), "a $ b @ c".to_owned()));
+    let (tokens, errors) = Lexer::new(source.iter()).tokenize();
+    let kinds: Vec<TokenKind> = tokens.into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, ["a", "b", "c"].map(|name| TokenKind::Identifier(Symbol::intern(name))));
+    let columns: Vec<usize> = errors.iter().map(|error| match error {
+        Error::UnknownSymbol(symbol) => symbol.position.position.start.column,
+        error => panic!("unexpected error {}", error),
+    }).collect();
+    assert_eq!(columns, [3, 7]);
+}
+
+/// Tests that identifiers may contain letters of all scripts.
+#[test]
+fn test_read_unicode_identifier() {
+    let kinds: Vec<TokenKind> = lexer("größe λ _ñ1 変数").into_iter().map(|token| token.value).collect();
+    assert_eq!(kinds, ["größe", "λ", "_ñ1", "変数"].map(|name| TokenKind::Identifier(Symbol::intern(name))));
+}
+
+/// Tests that combining marks belong to the identifier they follow, but cannot start one.
+#[test]
+fn test_read_combining_characters() {
+    // `e` followed by the combining acute accent U+0301
+    let tokens = lexer("cafe\u{301} = 1");
+    assert_eq!(tokens[0].value, TokenKind::Identifier(Symbol::intern("cafe\u{301}")));
+    assert_eq!(tokens[1].value, TokenKind::Equal);
+
+    let source = Arc::new(Source::new("testfile".to_owned(), "\u{301}a".to_owned()));
+    let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+    assert!(matches!(result, Err(Error::UnknownSymbol(symbol)) if *symbol == '\u{301}'));
+}
+
+/// Tests that identifiers starting with a digit are reported, while exponents of floats are not.
+#[test]
+fn test_leading_digit() {
+    for source_code in ["2nd", "1x_y", "3größe", "12a"] {
+        let source = Arc::new(Source::new("testfile".to_owned(), source_code.to_owned()));
+        let result = Lexer::new(source.iter()).collect::<Result<Vec<Token>, Error>>();
+        assert!(matches!(result, Err(Error::LeadingDigit(word)) if word.value == source_code), "{}", source_code);
+    }
+    assert_eq!(lexer("2e3")[0].value, TokenKind::Float(2e3));
+}
 
 /// Boilerplate code for converting source code into tokens using a lexer.
 fn lexer(source_code: &str) -> Vec<Token> {
@@ -1,34 +1,113 @@
-use std::{fs, path::Path, sync::Arc};
+//! The fortytwo-lang (FTL) compiler as a library.
+//!
+//! The compiler stages can be used on their own: The [`Lexer`] turns source code into [`Token`]s, the [`Parser`]
+//! builds [AST nodes](ast::Node) from them, the [semantic analyzer](semantic_analyzer) checks the AST, the
+//! [optimizer] simplifies it in the [passes] of the optimization level, and an [`Emitter`] generates target code from
+//! it. [`compiler_pipeline`] combines the stages up to the semantic analysis for one or more source files and the
+//! [modules](module) they import.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use fortytwolang::{ast, Lexer, Parser, Source};
+//!
+//! let source = Arc::new(Source::new("example.ftl".to_owned(), "def main() {\n\treturn 42\n}".to_owned()));
+//! let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+//! let ast_nodes = Parser::new(tokens.into_iter()).collect::<Result<Vec<_>, _>>().unwrap();
+//! assert!(matches!(ast_nodes[..], [ast::Node::Function(_)]));
+//! ```
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
 
 use anyhow::Context;
-use lexer::Lexer;
-use parser::Parser;
-use semantic_analyzer::{SymbolTable, TypeChecker};
-use source::Source;
-use token::Token;
+pub use emitter::Emitter;
+use error::DiagnosticSink;
+pub use lexer::Lexer;
+pub use parser::Parser;
+use semantic_analyzer::{monomorphize, resolve_aliases, SymbolTable, TypeChecker};
+pub use source::Source;
+pub use token::{Token, TokenKind};
 
 pub mod ast;
+pub mod builtin;
+pub mod bytecode;
 pub mod emitter;
+pub mod error;
+pub mod incremental;
+pub mod interpreter;
+pub mod ir;
+#[cfg(feature = "jit")]
+pub mod jit;
 pub mod lexer;
+pub mod module;
+pub mod optimizer;
 pub mod parser;
+pub mod passes;
 pub mod semantic_analyzer;
 pub mod source;
+pub mod symbol;
 pub mod token;
 
-/// Combines lexer, parser, and semantic analysis into a single function.
-pub fn compiler_pipeline(path: &Path) -> anyhow::Result<Vec<ast::Node>> {
-	let content = fs::read_to_string(path).context(format!("Reading FTL source file `{:?}`", path))?;
+/// Combines lexer, parser, [module loading](module::load) and semantic analysis into a single function. The nodes of
+/// all source files at `paths` are combined into a single program. Warnings of all stages are emitted to
+/// `diagnostics`.
+pub fn compiler_pipeline(paths: &[PathBuf], diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<ast::Node>> {
+	let mut ast_nodes = module::load(paths, diagnostics)?;
+	semantic_analysis(&mut ast_nodes, diagnostics)?;
+	Ok(ast_nodes)
+}
 
-	let source = Arc::new(Source::new(path.to_str().unwrap().to_string(), content));
-	let lexer = Lexer::new(source.iter());
-	let tokens = lexer.collect::<Result<Vec<Token>, lexer::Error>>().context("Lexing error")?;
+/// Runs the [type alias resolution](resolve_aliases), the global symbol scan and the type checker of
+/// the [semantic analyzer](semantic_analyzer) on a whole program. Afterwards, the generic functions and structs are
+/// replaced by their [specializations](monomorphize), method calls by plain calls, and declarations without type
+/// annotation are annotated with their inferred types.
+pub fn semantic_analysis(ast_nodes: &mut Vec<ast::Node>, diagnostics: &DiagnosticSink) -> anyhow::Result<()> {
+	resolve_aliases(ast_nodes).context("Type alias error")?;
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).context("Global symbol scan error")?;
+	let specializations = TypeChecker::type_check_with_diagnostics(symbol_table, ast_nodes.iter(), diagnostics.clone())
+		.context("Type checking error")?;
+	monomorphize(ast_nodes, specializations);
+	Ok(())
+}
 
-	let parser = Parser::new(tokens.into_iter());
-	let ast_nodes = parser.collect::<Result<Vec<_>, _>>().context("Parser error")?;
-	tracing::trace!("AST parsed: {:#?}", ast_nodes);
+/// Lexes the source file at `path`.
+pub fn lex_file(path: &Path, diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<Token>> {
+	lex_source(read_source(path)?, diagnostics)
+}
 
-	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).context("Global symbol scan error")?;
-	TypeChecker::type_check(symbol_table, ast_nodes.iter()).context("Type checking error")?;
+/// Lexes the `source`, e.g. the unsaved content of a file in an editor.
+pub fn lex_source(source: Arc<Source>, diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<Token>> {
+	let lexer = Lexer::new(source.iter()).with_diagnostics(diagnostics.clone());
+	lexer.collect::<Result<Vec<Token>, lexer::Error>>().context("Lexing error")
+}
+
+/// Lexes and parses the source file at `path`, without loading the modules it imports.
+pub fn parse_file(path: &Path, diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<ast::Node>> {
+	parse_source(read_source(path)?, diagnostics)
+}
+
+/// Lexes and parses the `source`, without loading the modules it imports.
+pub fn parse_source(source: Arc<Source>, diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<ast::Node>> {
+	// The lexer skips invalid chars, so that the parser still reports the errors after them
+	let (tokens, lexer_errors) = Lexer::new(source.iter()).with_diagnostics(diagnostics.clone()).tokenize();
 
+	let parser = Parser::new(tokens.into_iter()).with_diagnostics(diagnostics.clone());
+	let ast_nodes = match parser.parse_with_lexer_errors(lexer_errors) {
+		Err(parser::Error::Lexer(err)) => Err(err).context("Lexing error")?,
+		result => result.context("Parser error")?,
+	};
+	tracing::trace!("AST parsed: {:#?}", ast_nodes);
 	Ok(ast_nodes)
 }
+
+/// Reads the source file at `path`.
+fn read_source(path: &Path) -> anyhow::Result<Arc<Source>> {
+	let content = fs::read_to_string(path).context(format!("Reading FTL source file `{:?}`", path))?;
+	Ok(Arc::new(Source::new(path.to_str().unwrap().to_string(), content)))
+}
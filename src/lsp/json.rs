@@ -0,0 +1,280 @@
+//! Minimal JSON values for the messages of the language server, which are parsed from and written as
+//! [JSON](https://www.json.org) text.
+
+use std::{fmt, iter::Peekable, str::Chars};
+
+/// A JSON value. The members of an [object](Value::Object) keep their order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl Value {
+	/// Creates an object from its members.
+	pub fn object<const N: usize>(members: [(&str, Value); N]) -> Self {
+		Value::Object(members.into_iter().map(|(key, value)| (key.to_owned(), value)).collect())
+	}
+
+	/// The member `key` of an object, or [`Value::Null`] if there is none, so that lookups can be chained like
+	/// `message.get("params").get("textDocument")`.
+	pub fn get(&self, key: &str) -> &Value {
+		match self {
+			Value::Object(members) => members.iter().find(|(k, _)| k == key).map_or(&Value::Null, |(_, value)| value),
+			_ => &Value::Null,
+		}
+	}
+
+	/// The string, if this is one.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Value::String(string) => Some(string),
+			_ => None,
+		}
+	}
+
+	/// The number, if this is a non-negative integer like a position.
+	pub fn as_usize(&self) -> Option<usize> {
+		match self {
+			Value::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as usize),
+			_ => None,
+		}
+	}
+
+	/// The elements, if this is an array.
+	pub fn as_array(&self) -> Option<&[Value]> {
+		match self {
+			Value::Array(elements) => Some(elements),
+			_ => None,
+		}
+	}
+
+	/// Parses JSON text.
+	pub fn parse(text: &str) -> Result<Self, String> {
+		let mut parser = Parser { chars: text.chars().peekable() };
+		let value = parser.value()?;
+		parser.whitespace();
+		match parser.chars.next() {
+			None => Ok(value),
+			Some(char) => Err(format!("Unexpected `{}` after the value", char)),
+		}
+	}
+}
+
+impl From<&str> for Value {
+	fn from(string: &str) -> Self {
+		Value::String(string.to_owned())
+	}
+}
+
+impl From<String> for Value {
+	fn from(string: String) -> Self {
+		Value::String(string)
+	}
+}
+
+impl From<usize> for Value {
+	fn from(number: usize) -> Self {
+		Value::Number(number as f64)
+	}
+}
+
+impl From<bool> for Value {
+	fn from(boolean: bool) -> Self {
+		Value::Bool(boolean)
+	}
+}
+
+impl fmt::Display for Value {
+	/// Writes the value as compact JSON text.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Value::Null => write!(f, "null"),
+			Value::Bool(boolean) => write!(f, "{}", boolean),
+			// Ids and positions are integers, which must not be written as `1.0`
+			Value::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => write!(f, "{}", *number as i64),
+			Value::Number(number) if number.is_finite() => write!(f, "{}", number),
+			Value::Number(_) => write!(f, "null"),
+			Value::String(string) => string_literal(f, string),
+			Value::Array(elements) => {
+				write!(f, "[")?;
+				for (i, element) in elements.iter().enumerate() {
+					if i > 0 {
+						write!(f, ",")?;
+					}
+					write!(f, "{}", element)?;
+				}
+				write!(f, "]")
+			},
+			Value::Object(members) => {
+				write!(f, "{{")?;
+				for (i, (key, value)) in members.iter().enumerate() {
+					if i > 0 {
+						write!(f, ",")?;
+					}
+					string_literal(f, key)?;
+					write!(f, ":{}", value)?;
+				}
+				write!(f, "}}")
+			},
+		}
+	}
+}
+
+/// Writes the `string` as JSON string literal.
+fn string_literal(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+	write!(f, "\"")?;
+	for char in string.chars() {
+		match char {
+			'"' => write!(f, "\\\"")?,
+			'\\' => write!(f, "\\\\")?,
+			'\n' => write!(f, "\\n")?,
+			'\r' => write!(f, "\\r")?,
+			'\t' => write!(f, "\\t")?,
+			char if char.is_control() => write!(f, "\\u{:04x}", char as u32)?,
+			char => write!(f, "{}", char)?,
+		}
+	}
+	write!(f, "\"")
+}
+
+/// Recursive descent parser of JSON text.
+struct Parser<'a> {
+	chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+	fn value(&mut self) -> Result<Value, String> {
+		self.whitespace();
+		match self.chars.peek() {
+			Some('n') => self.keyword("null", Value::Null),
+			Some('t') => self.keyword("true", Value::Bool(true)),
+			Some('f') => self.keyword("false", Value::Bool(false)),
+			Some('"') => self.string().map(Value::String),
+			Some('[') => self.array(),
+			Some('{') => self.object(),
+			Some(char) if *char == '-' || char.is_ascii_digit() => self.number(),
+			Some(char) => Err(format!("Unexpected `{}`", char)),
+			None => Err("Unexpected end of the text".to_owned()),
+		}
+	}
+
+	fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+		for expected in keyword.chars() {
+			if self.chars.next() != Some(expected) {
+				return Err(format!("Expected `{}`", keyword));
+			}
+		}
+		Ok(value)
+	}
+
+	fn number(&mut self) -> Result<Value, String> {
+		let mut number = String::new();
+		while let Some(char) = self.chars.next_if(|char| matches!(char, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')) {
+			number.push(char);
+		}
+		number.parse().map(Value::Number).map_err(|_| format!("Invalid number `{}`", number))
+	}
+
+	fn string(&mut self) -> Result<String, String> {
+		self.expect('"')?;
+		let mut string = String::new();
+		loop {
+			match self.chars.next() {
+				Some('"') => return Ok(string),
+				Some('\\') => string.push(self.escape()?),
+				Some(char) => string.push(char),
+				None => return Err("Unterminated string".to_owned()),
+			}
+		}
+	}
+
+	/// Parses the escape sequence after a `\`.
+	fn escape(&mut self) -> Result<char, String> {
+		match self.chars.next() {
+			Some('"') => Ok('"'),
+			Some('\\') => Ok('\\'),
+			Some('/') => Ok('/'),
+			Some('b') => Ok('\u{8}'),
+			Some('f') => Ok('\u{c}'),
+			Some('n') => Ok('\n'),
+			Some('r') => Ok('\r'),
+			Some('t') => Ok('\t'),
+			Some('u') => {
+				let unit = self.code_unit()?;
+				// Chars outside of the basic multilingual plane are escaped as UTF-16 surrogate pair
+				if (0xd800..0xdc00).contains(&unit) && self.chars.next_if_eq(&'\\').is_some() {
+					self.expect('u')?;
+					let low = self.code_unit()?;
+					return char::decode_utf16([unit, low])
+						.next()
+						.unwrap()
+						.map_err(|_| "Invalid surrogate pair".to_owned());
+				}
+				char::decode_utf16([unit]).next().unwrap().map_err(|_| "Unpaired surrogate".to_owned())
+			},
+			_ => Err("Invalid escape sequence".to_owned()),
+		}
+	}
+
+	/// Parses the four hex digits of a `\u` escape sequence.
+	fn code_unit(&mut self) -> Result<u16, String> {
+		let digits: String = (0..4).filter_map(|_| self.chars.next()).collect();
+		u16::from_str_radix(&digits, 16).map_err(|_| format!("Invalid unicode escape `\\u{}`", digits))
+	}
+
+	fn array(&mut self) -> Result<Value, String> {
+		self.expect('[')?;
+		let mut elements = Vec::new();
+		self.whitespace();
+		if self.chars.next_if_eq(&']').is_some() {
+			return Ok(Value::Array(elements));
+		}
+		loop {
+			elements.push(self.value()?);
+			self.whitespace();
+			match self.chars.next() {
+				Some(',') => continue,
+				Some(']') => return Ok(Value::Array(elements)),
+				_ => return Err("Expected `,` or `]` in array".to_owned()),
+			}
+		}
+	}
+
+	fn object(&mut self) -> Result<Value, String> {
+		self.expect('{')?;
+		let mut members = Vec::new();
+		self.whitespace();
+		if self.chars.next_if_eq(&'}').is_some() {
+			return Ok(Value::Object(members));
+		}
+		loop {
+			self.whitespace();
+			let key = self.string()?;
+			self.whitespace();
+			self.expect(':')?;
+			members.push((key, self.value()?));
+			self.whitespace();
+			match self.chars.next() {
+				Some(',') => continue,
+				Some('}') => return Ok(Value::Object(members)),
+				_ => return Err("Expected `,` or `}` in object".to_owned()),
+			}
+		}
+	}
+
+	fn expect(&mut self, expected: char) -> Result<(), String> {
+		match self.chars.next() {
+			Some(char) if char == expected => Ok(()),
+			_ => Err(format!("Expected `{}`", expected)),
+		}
+	}
+
+	fn whitespace(&mut self) {
+		while self.chars.next_if(|char| matches!(char, ' ' | '\t' | '\n' | '\r')).is_some() {}
+	}
+}
@@ -0,0 +1,351 @@
+//! Language server speaking the [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! over stdin and stdout, which makes editors like VS Code show the errors of FTL code while it is edited.
+//!
+//! The server keeps the open documents [parsed](fortytwolang::incremental), so that an edit only re-parses the
+//! top-level nodes it touches. Whenever a document is opened or changed, it runs through the rest of the
+//! compiler pipeline up to the semantic analysis, and the errors and warnings are published as diagnostics. Modules
+//! are imported from the files next to the document. Documents are formatted by the FTL emitter with the style of the
+//! nearest `.ftlfmt.toml`, like with `mylang fmt`.
+
+mod json;
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::HashMap,
+	io::{self, BufRead, Write},
+	ops::ControlFlow,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use anyhow::Context;
+use fortytwolang::{
+	emitter::{self, ftl},
+	error::{Diagnostic, DiagnosticSink, Severity},
+	incremental::{Document, TextEdit},
+	module, Source,
+};
+use json::Value;
+
+/// Error code of JSON-RPC for a message which is no valid JSON.
+const PARSE_ERROR: i32 = -32700;
+/// Error code of JSON-RPC for a request which is not allowed, e.g. after the shutdown.
+const INVALID_REQUEST: i32 = -32600;
+/// Error code of JSON-RPC for a request whose method the server does not support.
+const METHOD_NOT_FOUND: i32 = -32601;
+/// Error code of LSP for a valid request which failed, like formatting code with syntax errors.
+const REQUEST_FAILED: i32 = -32803;
+
+/// Runs the language server until the client sends the `exit` notification or closes stdin.
+pub fn run() -> anyhow::Result<()> {
+	let mut server = Server { documents: HashMap::new(), shutdown: false, output: io::stdout().lock() };
+	let mut input = io::stdin().lock();
+	while let Some(content) = read_message(&mut input)? {
+		let message = match Value::parse(&content) {
+			Ok(message) => message,
+			Err(err) => {
+				server.respond(&Value::Null, Err((PARSE_ERROR, err)))?;
+				continue;
+			},
+		};
+		if server.handle(&message)?.is_break() {
+			break;
+		}
+	}
+	Ok(())
+}
+
+/// Reads the content of the next message, or returns `None` if the input ended.
+fn read_message(input: &mut impl BufRead) -> anyhow::Result<Option<String>> {
+	let mut content_length = None;
+	// The headers are separated from the content by an empty line
+	loop {
+		let mut line = String::new();
+		if input.read_line(&mut line)? == 0 {
+			return Ok(None);
+		}
+		let line = line.trim_end();
+		if line.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			if name.eq_ignore_ascii_case("Content-Length") {
+				content_length = Some(value.trim().parse::<usize>().context("Invalid `Content-Length` header")?);
+			}
+		}
+	}
+
+	let content_length = content_length.context("Message without `Content-Length` header")?;
+	let mut content = vec![0; content_length];
+	input.read_exact(&mut content).context("Reading message")?;
+	String::from_utf8(content).context("Message is no UTF-8").map(Some)
+}
+
+/// State of the language server.
+struct Server<W: Write> {
+	/// The open documents by their URI.
+	documents: HashMap<String, Document>,
+	/// Whether the client requested the shutdown, after which only the `exit` notification is allowed.
+	shutdown: bool,
+	output: W,
+}
+
+impl<W: Write> Server<W> {
+	/// Handles a request or notification of the client. Returns [`ControlFlow::Break`] if the server should exit.
+	fn handle(&mut self, message: &Value) -> anyhow::Result<ControlFlow<()>> {
+		let method = message.get("method").as_str().unwrap_or_default();
+		let params = message.get("params");
+		let id = message.get("id");
+
+		// Notifications have no id and are not responded to
+		if *id == Value::Null {
+			let document = params.get("textDocument");
+			let uri = document.get("uri").as_str().unwrap_or_default();
+			match method {
+				"exit" => {
+					anyhow::ensure!(self.shutdown, "Language client exited without shutdown request");
+					return Ok(ControlFlow::Break(()));
+				},
+				"textDocument/didOpen" => {
+					let text = document.get("text").as_str().unwrap_or_default();
+					self.documents.insert(uri.to_owned(), Document::new(source(uri, text)));
+					self.publish(uri)?;
+				},
+				"textDocument/didChange" => {
+					let Some(document) = self.documents.get_mut(uri) else {
+						return Ok(ControlFlow::Continue(()));
+					};
+					for change in params.get("contentChanges").as_array().unwrap_or_default() {
+						let text = change.get("text").as_str().unwrap_or_default();
+						// A change without range replaces the whole content
+						match change.get("range") {
+							Value::Null => *document = Document::new(source(uri, text)),
+							range => {
+								let start = char_offset(document.source(), range.get("start"));
+								let end = char_offset(document.source(), range.get("end"));
+								document.edit(TextEdit { range: start..end, text: text.to_owned() });
+							},
+						}
+					}
+					self.publish(uri)?;
+				},
+				"textDocument/didClose" => {
+					self.documents.remove(uri);
+					self.publish_diagnostics(uri, Vec::new())?;
+				},
+				_ => (),
+			}
+			return Ok(ControlFlow::Continue(()));
+		}
+
+		let result = match method {
+			_ if self.shutdown => Err((INVALID_REQUEST, "The server is shut down".to_owned())),
+			"initialize" => Ok(Value::object([
+				(
+					"capabilities",
+					Value::object([("textDocumentSync", Value::from(2)), ("documentFormattingProvider", true.into())]),
+				),
+				("serverInfo", Value::object([("name", "fortytwolang".into())])),
+			])),
+			"shutdown" => {
+				self.shutdown = true;
+				Ok(Value::Null)
+			},
+			"textDocument/formatting" => {
+				let uri = params.get("textDocument").get("uri").as_str().unwrap_or_default();
+				self.format(uri).map_err(|err| (REQUEST_FAILED, format!("{:#}", err)))
+			},
+			_ => Err((METHOD_NOT_FOUND, format!("Unsupported method `{}`", method))),
+		};
+		self.respond(id, result)?;
+		Ok(ControlFlow::Continue(()))
+	}
+
+	/// Publishes the diagnostics of the document.
+	fn publish(&mut self, uri: &str) -> anyhow::Result<()> {
+		let document = &self.documents[uri];
+		let source = document.source();
+		let diagnostics =
+			analyze(document, &path(uri)).iter().map(|diagnostic| lsp_diagnostic(source, diagnostic)).collect();
+		self.publish_diagnostics(uri, diagnostics)
+	}
+
+	/// Formats the document and returns the edits to apply to it.
+	fn format(&self, uri: &str) -> anyhow::Result<Value> {
+		let document = self.documents.get(uri).context("The document is not open")?;
+		let path = path(uri);
+		let ast_nodes = document.nodes()?;
+		// Unsaved documents have no directory to look for the configuration in
+		let config = match path.is_file() {
+			true => crate::format_config(&path)?,
+			false => ftl::Config::default(),
+		};
+
+		let mut formatted = Vec::new();
+		emitter::Ftl::codegen_with_config(ast_nodes.into_iter(), &mut formatted, config)?;
+		let formatted = String::from_utf8(formatted).context("Formatted code is no UTF-8")?;
+		let text: String = document.source().text.iter().collect();
+		if formatted == text {
+			return Ok(Value::Array(Vec::new()));
+		}
+		// Replace the whole document. Positions after its end refer to the end
+		let range = Value::object([("start", lsp_position(0, 0)), ("end", lsp_position(text.lines().count() + 1, 0))]);
+		Ok(Value::Array(vec![Value::object([("range", range), ("newText", formatted.into())])]))
+	}
+
+	fn publish_diagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> anyhow::Result<()> {
+		let params = Value::object([("uri", uri.into()), ("diagnostics", Value::Array(diagnostics))]);
+		self.send(&Value::object([
+			("jsonrpc", "2.0".into()),
+			("method", "textDocument/publishDiagnostics".into()),
+			("params", params),
+		]))
+	}
+
+	/// Responds to the request with the `id` with either its result or an error code and message.
+	fn respond(&mut self, id: &Value, result: Result<Value, (i32, String)>) -> anyhow::Result<()> {
+		let (key, value) = match result {
+			Ok(result) => ("result", result),
+			Err((code, message)) => {
+				("error", Value::object([("code", Value::Number(code.into())), ("message", message.into())]))
+			},
+		};
+		self.send(&Value::object([("jsonrpc", "2.0".into()), ("id", id.clone()), (key, value)]))
+	}
+
+	fn send(&mut self, message: &Value) -> anyhow::Result<()> {
+		let content = message.to_string();
+		write!(self.output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+		self.output.flush().context("Sending message to the language client")
+	}
+}
+
+/// The path of the file with the `uri`. URIs which are no `file://` URIs, like those of unsaved documents, are used as
+/// path as they are.
+fn path(uri: &str) -> PathBuf {
+	let Some(path) = uri.strip_prefix("file://") else {
+		return PathBuf::from(uri);
+	};
+	// Special chars like spaces are percent-encoded as `%20`
+	let mut bytes = Vec::new();
+	let mut rest = path.as_bytes();
+	while let [byte, tail @ ..] = rest {
+		let decoded = match (byte, tail) {
+			(b'%', [high, low, ..]) => {
+				std::str::from_utf8(&[*high, *low]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok())
+			},
+			_ => None,
+		};
+		match decoded {
+			Some(decoded) => {
+				bytes.push(decoded);
+				rest = &tail[2..];
+			},
+			None => {
+				bytes.push(*byte);
+				rest = tail;
+			},
+		}
+	}
+	PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The source code of the document with the `uri` and the content `text`.
+fn source(uri: &str, text: &str) -> Arc<Source> {
+	Arc::new(Source::new(path(uri).to_string_lossy().into_owned(), text.to_owned()))
+}
+
+/// Runs the parsed document at `path` through the rest of the compiler pipeline up to the semantic analysis, and
+/// returns the warnings and errors.
+fn analyze(document: &Document, path: &Path) -> Vec<Diagnostic> {
+	let diagnostics = DiagnosticSink::default();
+	for warning in document.warnings() {
+		diagnostics.emit(warning);
+	}
+	let result = document
+		.nodes()
+		.and_then(|ast_nodes| module::load_parsed(vec![ast_nodes], &[path.to_owned()], &diagnostics))
+		.and_then(|mut ast_nodes| fortytwolang::semantic_analysis(&mut ast_nodes, &diagnostics));
+
+	let mut diagnostics = diagnostics.take();
+	if let Err(err) = result {
+		match crate::error_diagnostics(&err) {
+			Some(errors) => diagnostics.extend(errors),
+			None => diagnostics.push(Diagnostic::new("", format!("{:#}", err), None)),
+		}
+	}
+	diagnostics
+}
+
+/// Converts the diagnostic into one of LSP. Diagnostics without a position in the document, e.g. in an imported
+/// module, are shown at its start.
+fn lsp_diagnostic(source: &Arc<Source>, diagnostic: &Diagnostic) -> Value {
+	let (range, message) = match &diagnostic.position {
+		Some(position) if Arc::ptr_eq(&position.source, source) => {
+			let (start, end) = (position.position.start.offset, position.position.end.offset + 1);
+			let range =
+				Value::object([("start", offset_position(source, start)), ("end", offset_position(source, end))]);
+			(range, diagnostic.message.clone())
+		},
+		Some(position) => (start_range(), format!("{}: {}", position, diagnostic.message)),
+		None => (start_range(), diagnostic.message.clone()),
+	};
+	let severity = match diagnostic.severity {
+		Severity::Error => 1,
+		Severity::Warning => 2,
+		Severity::Note => 3,
+	};
+
+	let mut members = vec![
+		("range".to_owned(), range),
+		("severity".to_owned(), Value::from(severity)),
+		("source".to_owned(), "fortytwolang".into()),
+		("message".to_owned(), message.into()),
+	];
+	if !diagnostic.code.is_empty() {
+		members.push(("code".to_owned(), diagnostic.code.into()));
+	}
+	Value::Object(members)
+}
+
+/// The empty range at the start of a document.
+fn start_range() -> Value {
+	Value::object([("start", lsp_position(0, 0)), ("end", lsp_position(0, 0))])
+}
+
+/// The LSP position of the char at `offset` in the `source`. LSP counts lines from zero and the chars in a line in
+/// UTF-16 code units.
+fn offset_position(source: &Source, offset: usize) -> Value {
+	let before = &source.text[..offset.min(source.text.len())];
+	let line_start = before.iter().rposition(|char| *char == '\n').map_or(0, |newline| newline + 1);
+	let line = before[..line_start].iter().filter(|char| **char == '\n').count();
+	let character = before[line_start..].iter().map(|char| char.len_utf16()).sum();
+	lsp_position(line, character)
+}
+
+/// The offset of the char at the LSP `position` in the `source`.
+fn char_offset(source: &Source, position: &Value) -> usize {
+	let line = position.get("line").as_usize().unwrap_or_default();
+	let character = position.get("character").as_usize().unwrap_or_default();
+	let text = &source.text;
+	let mut offset = match line {
+		0 => 0,
+		_ => text
+			.iter()
+			.enumerate()
+			.filter(|(_, char)| **char == '\n')
+			.nth(line - 1)
+			.map_or(text.len(), |(newline, _)| newline + 1),
+	};
+	let mut code_units = 0;
+	while code_units < character && offset < text.len() && text[offset] != '\n' {
+		code_units += text[offset].len_utf16();
+		offset += 1;
+	}
+	offset
+}
+
+fn lsp_position(line: usize, character: usize) -> Value {
+	Value::object([("line", line.into()), ("character", character.into())])
+}
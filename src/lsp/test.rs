@@ -0,0 +1,85 @@
+use std::{collections::HashMap, io::Cursor, sync::Arc};
+
+use fortytwolang::Source;
+
+use super::{char_offset, json::Value, lsp_position, offset_position, read_message, Server};
+
+/// Tests that messages are split by their `Content-Length` in bytes, other headers are ignored, and the end of the
+/// input ends the messages.
+#[test]
+fn test_read_message() {
+	let input = "Content-Length: 8\r\n\r\n\"größ\"Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n\
+		content-length: 2\r\n\r\n[]";
+	let mut input = Cursor::new(input);
+	assert_eq!(read_message(&mut input).unwrap().as_deref(), Some("\"größ\""));
+	assert_eq!(read_message(&mut input).unwrap().as_deref(), Some("[]"));
+	assert_eq!(read_message(&mut input).unwrap(), None);
+
+	assert!(read_message(&mut Cursor::new("Content-Type: text\r\n\r\n{}")).is_err());
+}
+
+/// Tests that an opened document with an error gets a diagnostic at the error, which is cleared by a change fixing it.
+#[test]
+fn test_diagnostics() {
+	let mut server = Server { documents: HashMap::new(), shutdown: false, output: Vec::new() };
+	let text = "def f(_s: str, n: int): int {\n\treturn n\n}\ndef main(): int {\n\treturn f(\"𝄞\", y)\n}\n";
+	let document = Value::object([("uri", "untitled:a.ftl".into()), ("text", text.into())]);
+	notify(&mut server, "textDocument/didOpen", Value::object([("textDocument", document)]));
+
+	let messages = sent(&mut server);
+	assert_eq!(messages.len(), 1);
+	let params = messages[0].get("params");
+	assert_eq!(messages[0].get("method").as_str(), Some("textDocument/publishDiagnostics"));
+	assert_eq!(params.get("uri").as_str(), Some("untitled:a.ftl"));
+	let diagnostics = params.get("diagnostics").as_array().unwrap();
+	assert_eq!(diagnostics.len(), 1);
+	// `𝄞` takes two UTF-16 code units
+	let range = Value::object([("start", lsp_position(4, 16)), ("end", lsp_position(4, 17))]);
+	assert_eq!(*diagnostics[0].get("range"), range);
+	assert_eq!(diagnostics[0].get("code").as_str(), Some("E0102"));
+
+	// Replace the undeclared `y` by `1`
+	let change = Value::object([("range", range), ("text", "1".into())]);
+	let params = Value::object([
+		("textDocument", Value::object([("uri", "untitled:a.ftl".into())])),
+		("contentChanges", Value::Array(vec![change])),
+	]);
+	notify(&mut server, "textDocument/didChange", params);
+
+	let text: String = server.documents["untitled:a.ftl"].source().text.iter().collect();
+	assert!(text.ends_with("def main(): int {\n\treturn f(\"𝄞\", 1)\n}\n"), "{}", text);
+	let messages = sent(&mut server);
+	assert_eq!(messages[0].get("params").get("diagnostics").as_array(), Some(&[][..]));
+}
+
+/// Tests that positions count the chars in a line in UTF-16 code units, in which `𝄞` takes two.
+#[test]
+fn test_utf16_positions() {
+	let source = Source::new("a.ftl".to_owned(), "x\n\"𝄞é\" y".to_owned());
+	// The offsets of `"`, `𝄞`, `é` and `y` in the second line
+	for (offset, character) in [(2, 0), (3, 1), (4, 3), (7, 6)] {
+		let position = offset_position(&source, offset);
+		assert_eq!(position, lsp_position(1, character), "{}", offset);
+		assert_eq!(char_offset(&source, &position), offset);
+	}
+	// Positions after the end of a line refer to its end
+	assert_eq!(char_offset(&source, &lsp_position(0, 5)), 1);
+	assert_eq!(offset_position(&Arc::new(source), 100), lsp_position(1, 7));
+}
+
+/// Has the `server` handle the notification with the `method` and `params`.
+fn notify(server: &mut Server<Vec<u8>>, method: &str, params: Value) {
+	let message = Value::object([("jsonrpc", "2.0".into()), ("method", method.into()), ("params", params)]);
+	assert!(server.handle(&message).unwrap().is_continue());
+}
+
+/// Takes the messages the `server` sent since the last call.
+fn sent(server: &mut Server<Vec<u8>>) -> Vec<Value> {
+	let output = String::from_utf8(std::mem::take(&mut server.output)).unwrap();
+	let mut output = Cursor::new(output);
+	let mut messages = Vec::new();
+	while let Some(content) = read_message(&mut output).unwrap() {
+		messages.push(Value::parse(&content).unwrap());
+	}
+	messages
+}
@@ -1,20 +1,38 @@
 //! Command line interface to the fortytwo-lang compiler.
 
-use std::{fs::File, io, io::Write, os::unix::process::CommandExt, path::Path, process};
+use std::{
+	fs::{self, File},
+	io::{self, BufWriter, Write},
+	os::unix::process::CommandExt,
+	path::{Path, PathBuf},
+	process::{self, ExitCode},
+	sync::Arc,
+};
 
 use anyhow::Context;
 use fortytwolang::{
-	emitter::{self, Emitter},
+	ast,
+	bytecode::{self, Vm},
+	emitter::{self, ftl, Emitter},
+	error::{self, Code, Diagnostic, DiagnosticSink, Severity},
+	interpreter::{self, Interpreter},
+	ir,
 	lexer::{self},
+	module,
 	parser::{self, Error},
+	passes::Pipeline,
 	semantic_analyzer::{self},
 	source::SourcePositionRange,
+	Source, Token,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod cli;
+mod diff;
+mod lsp;
+mod repl;
 
-fn main() {
+fn main() -> ExitCode {
 	tracing_subscriber::Registry::default()
 		.with(tracing_subscriber::EnvFilter::from_default_env())
 		/*.with(
@@ -25,151 +43,610 @@ fn main() {
 		.with(tracing_tree::HierarchicalLayer::new(2).with_targets(true).with_bracketed_fields(true))
 		.init();
 
-	let args = <cli::Args as clap::Parser>::parse();
+	let args = match <cli::Args as clap::Parser>::try_parse() {
+		Ok(args) => args,
+		// `--help` and `--version` are no errors, clap prints them to stdout and exits successfully
+		Err(err) if !err.use_stderr() => err.exit(),
+		Err(err) => {
+			let _ = err.print();
+			return ExitCode::from(ExitStatus::Usage as u8);
+		},
+	};
 
+	let reporting = Reporting { color: args.color.enabled(), deny_warnings: args.deny_warnings };
 	let result = match args.command {
-		cli::Command::Compile { file: path } => compile(&path),
-		cli::Command::Run { file: path } => run(&path),
-		cli::Command::Fmt { file: path } => format(&path),
+		cli::Command::Build { files: paths, target, optimization } => {
+			compile(&paths, target, &pipeline(optimization), reporting)
+		},
+		cli::Command::Run { files: paths, target, optimization } => {
+			run(&paths, target, &pipeline(optimization), reporting)
+		},
+		cli::Command::Fmt { file: path, check } => format(&path, check, reporting),
+		cli::Command::Check { files: paths } => compiler_pipeline(&paths, reporting).map(|_| ()),
+		cli::Command::Tokens { file: path } => tokens(&path),
+		cli::Command::Ast { file: path, format } => ast(&path, format),
+		cli::Command::Highlight { file: path } => highlight(&path),
+		cli::Command::Explain { code } => explain(&code),
+		cli::Command::Repl => repl::run(reporting),
+		cli::Command::Lsp => lsp::run(),
+		#[cfg(feature = "jit")]
+		cli::Command::Interpret { files: paths, jit: true, optimization, .. } => {
+			interpret_jit(&paths, &pipeline(optimization), reporting)
+		},
+		cli::Command::Interpret { files: paths, bytecode, optimization, .. } => {
+			interpret(&paths, bytecode, &pipeline(optimization), reporting)
+		},
 	};
 
-	if let Err(err) = result {
-		print_error(err);
-		// TODO: Use [`process::ExitCode::Failure.exit_process()`](https://doc.rust-lang.org/beta/std/process/struct.ExitCode.html#method.exit_process) when stable
-		process::exit(1);
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(err) => {
+			let status = ExitStatus::of(&err);
+			print_error(err, reporting.color);
+			ExitCode::from(status as u8)
+		},
+	}
+}
+
+/// Exit codes for the reasons why the compiler failed, so that scripts can react to them. A program that is run
+/// exits with its own exit code instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitStatus {
+	/// Any other error, e.g. of a backend or of the interpreted program at runtime.
+	Failure = 1,
+	/// The command line is invalid, like the exit code of [clap](clap::Error::exit) for usage errors.
+	Usage = 2,
+	/// The source code could not be lexed or parsed, or an imported module was not found.
+	Syntax = 3,
+	/// The program is syntactically valid, but the semantic analysis found an error, e.g. mismatching types.
+	Type = 4,
+	/// A file could not be read or written.
+	Io = 5,
+}
+
+impl ExitStatus {
+	/// The exit status for the first error in the chain of `err` whose stage is known.
+	fn of(err: &anyhow::Error) -> Self {
+		err.chain()
+			.find_map(|cause| {
+				if cause.is::<lexer::Error>() || cause.is::<parser::Error>() || cause.is::<module::Error>() {
+					Some(ExitStatus::Syntax)
+				} else if cause.is::<semantic_analyzer::Error>() {
+					Some(ExitStatus::Type)
+				} else if cause.is::<io::Error>() {
+					Some(ExitStatus::Io)
+				} else {
+					None
+				}
+			})
+			.unwrap_or(ExitStatus::Failure)
+	}
+}
+
+/// How diagnostics are reported, as chosen by command line flags.
+#[derive(Debug, Clone, Copy)]
+struct Reporting {
+	/// Whether to highlight diagnostics with colors.
+	color: bool,
+	/// Whether warnings are turned into errors.
+	deny_warnings: bool,
+}
+
+/// Runs the [compiler pipeline](fortytwolang::compiler_pipeline) and prints the warnings it found, unless the program
+/// could not even be parsed. With [`Reporting::deny_warnings`], warnings are printed as errors and fail the pipeline.
+fn compiler_pipeline(paths: &[PathBuf], reporting: Reporting) -> anyhow::Result<Vec<ast::Node>> {
+	let diagnostics = DiagnosticSink::default();
+	let result = fortytwolang::compiler_pipeline(paths, &diagnostics);
+	if let Err(err) = &result {
+		if err.is::<lexer::Error>() || err.is::<parser::Error>() || err.is::<module::Error>() {
+			return result;
+		}
+	}
+
+	let warnings = diagnostics.take();
+	let warning_count = warnings.len();
+	for warning in warnings {
+		let warning = match reporting.deny_warnings {
+			true => warning.with_severity(Severity::Error),
+			false => warning,
+		};
+		eprintln!("{}\n", warning.render(reporting.color));
 	}
+	let ast_nodes = result?;
+	anyhow::ensure!(
+		!reporting.deny_warnings || warning_count == 0,
+		"Aborting because of {} denied warning(s)",
+		warning_count
+	);
+	Ok(ast_nodes)
 }
 
-/// Formats FTL source code using the FTL emitter.
-fn format(path: &Path) -> anyhow::Result<()> {
-	let ast_nodes = fortytwolang::compiler_pipeline(path)?;
+/// Formats FTL source code using the FTL emitter. Only the file itself is formatted, not the modules it imports.
+///
+/// The style is read from the nearest `.ftlfmt.toml` next to the file or in one of its parent directories.
+///
+/// With `check`, the formatted code is compared to the file instead, and the differences are printed as diff.
+fn format(path: &Path, check: bool, reporting: Reporting) -> anyhow::Result<()> {
+	compiler_pipeline(&[path.to_owned()], reporting)?;
+	let ast_nodes = fortytwolang::parse_file(path, &DiagnosticSink::default())?;
+	let config = format_config(path)?;
 
-	emitter::Ftl::codegen(ast_nodes.into_iter(), Box::new(io::stdout()))?;
+	if !check {
+		let mut stdout = BufWriter::new(io::stdout().lock());
+		emitter::Ftl::codegen_with_config(ast_nodes.into_iter(), &mut stdout, config)?;
+		stdout.flush()?;
+		return Ok(());
+	}
+	let mut formatted = Vec::new();
+	emitter::Ftl::codegen_with_config(ast_nodes.into_iter(), &mut formatted, config)?;
+	let formatted = String::from_utf8(formatted).context("Formatted code is no UTF-8")?;
+	let original = fs::read_to_string(path).context(format!("Reading FTL source file `{:?}`", path))?;
+
+	let path = path.to_string_lossy();
+	let diff = diff::unified(&original, &formatted, &path, &format!("{} (formatted)", path));
+	print!("{}", diff);
+	anyhow::ensure!(diff.is_empty(), "`{}` is not formatted", path);
 	Ok(())
 }
 
-/// Compiles FTL source code to a C executable.
-fn compile(path: &Path) -> anyhow::Result<()> {
-	let ast_nodes = fortytwolang::compiler_pipeline(path)?;
+/// Reads the formatter configuration for the file at `path`, or returns the default one if there is no configuration file.
+fn format_config(path: &Path) -> anyhow::Result<ftl::Config> {
+	let path = fs::canonicalize(path).context(format!("Resolving path `{:?}`", path))?;
+	for directory in path.ancestors().skip(1) {
+		let config_path = directory.join(ftl::Config::FILE_NAME);
+		let toml = match fs::read_to_string(&config_path) {
+			Ok(toml) => toml,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+			Err(error) => return Err(error).context(format!("Reading formatter configuration `{:?}`", config_path)),
+		};
+		return ftl::Config::from_toml(&toml).context(format!("Invalid formatter configuration `{:?}`", config_path));
+	}
+	Ok(ftl::Config::default())
+}
 
-	// Compile to c code
-	let c_code_output_path = Path::new(&path).with_extension("c");
-	let c_code_output_file =
-		File::create(&c_code_output_path).context(format!("Creating output .c file `{:?}`", c_code_output_path))?;
+/// Prints the tokens of a source file with their positions.
+fn tokens(path: &Path) -> anyhow::Result<()> {
+	print_tokens(&fortytwolang::lex_file(path, &DiagnosticSink::default())?);
+	Ok(())
+}
 
-	emitter::C::codegen(ast_nodes.into_iter(), Box::new(c_code_output_file))?;
+/// Prints the tokens as a table with a row per token, which shows where it starts and ends, and its kind.
+fn print_tokens(tokens: &[Token]) {
+	let positions: Vec<_> = tokens.iter().map(|token| &token.position.position).collect();
+	// The columns are as wide as their widest entry, so that all rows are aligned
+	let line_width = column_width("LINE", positions.iter().map(|position| position.start.line.to_string()));
+	let col_width = column_width("COL", positions.iter().map(|position| position.start.column.to_string()));
+	let end_width = column_width("END", positions.iter().map(|position| position.end.to_string()));
+	println!("{:>line_width$}  {:>col_width$}  {:<end_width$}  KIND", "LINE", "COL", "END");
+	for (token, position) in tokens.iter().zip(positions) {
+		let (start, end) = (position.start, position.end.to_string());
+		println!("{:>line_width$}  {:>col_width$}  {:<end_width$}  {}", start.line, start.column, end, token.value);
+	}
+}
+
+/// Width of a table column with the `title` and the `entries`.
+fn column_width(title: &str, entries: impl Iterator<Item = String>) -> usize {
+	entries.map(|entry| entry.len()).max().unwrap_or(0).max(title.len())
+}
+
+/// Prints the AST of a source file in the given `format`.
+fn ast(path: &Path, format: cli::AstFormat) -> anyhow::Result<()> {
+	let ast_nodes = fortytwolang::parse_file(path, &DiagnosticSink::default())?;
+	match format {
+		cli::AstFormat::Tree => print!("{}", ast::pretty::nodes(&ast_nodes)),
+		cli::AstFormat::Debug => println!("{:#?}", ast_nodes),
+		cli::AstFormat::Dot => {
+			let mut stdout = BufWriter::new(io::stdout().lock());
+			emitter::Dot::codegen(ast_nodes.into_iter(), &mut stdout)?;
+			stdout.flush()?;
+		},
+		#[cfg(feature = "serde")]
+		cli::AstFormat::Json => println!("{}", ast::json::to_string(&ast_nodes)?),
+	}
+	Ok(())
+}
+
+/// Prints a source file as highlighted HTML.
+fn highlight(path: &Path) -> anyhow::Result<()> {
+	let content = fs::read_to_string(path).context(format!("Reading FTL source file `{:?}`", path))?;
+	let source = Arc::new(Source::new(path.to_string_lossy().into_owned(), content));
+	emitter::html::codegen(source, io::stdout().lock())?;
+	Ok(())
+}
+
+/// Prints the description of an error code.
+fn explain(code: &str) -> anyhow::Result<()> {
+	let error_code = error::explain(code).with_context(|| format!("Unknown error code `{}`", code))?;
+	println!("{}\n\n{}", error_code, error_code.explanation);
+	Ok(())
+}
+
+/// The optimization passes of the `opt_level`, printing the program after the pass `print_after`, if any.
+fn pipeline(cli::Optimization { opt_level, print_after }: cli::Optimization) -> Pipeline {
+	let pipeline = Pipeline::new(opt_level);
+	match print_after {
+		Some(pass) => pipeline.print_after(pass),
+		None => pipeline,
+	}
+}
+
+/// Warns if `--print-after` names a pass on the IR, which the `backend` does not translate, so nothing is printed.
+fn warn_without_ir(pipeline: &Pipeline, backend: &str) {
+	if let Some(pass) = pipeline.print_after_ir() {
+		eprintln!(
+			"warning: `--print-after={}` prints nothing, because the pass only runs on the IR, which {} does not use\n",
+			pass, backend
+		);
+	}
+}
+
+/// Compiles the FTL source files to an executable via the given `target`, optimized by the `pipeline`. The output files
+/// are named after the first source file.
+fn compile(paths: &[PathBuf], target: cli::Target, pipeline: &Pipeline, reporting: Reporting) -> anyhow::Result<()> {
+	let ast_nodes = pipeline.run_ast(compiler_pipeline(paths, reporting)?)?;
+	let path = &paths[0];
+
+	let c_code_output_path = match target {
+		cli::Target::C => {
+			warn_without_ir(pipeline, "the C target");
+			// Compile to c code
+			let c_code_output_path = Path::new(&path).with_extension("c");
+			let mut c_code_output_file = BufWriter::new(
+				File::create(&c_code_output_path)
+					.context(format!("Creating output .c file `{:?}`", c_code_output_path))?,
+			);
+
+			emitter::C::codegen(ast_nodes.into_iter(), &mut c_code_output_file)?;
+			c_code_output_file.flush()?;
+			c_code_output_path
+		},
+		cli::Target::Llvm => {
+			// Compile to LLVM IR
+			let llvm_ir_output_path = Path::new(&path).with_extension("ll");
+			let mut llvm_ir_output_file = BufWriter::new(
+				File::create(&llvm_ir_output_path)
+					.context(format!("Creating output .ll file `{:?}`", llvm_ir_output_path))?,
+			);
+
+			let mut program = ir::lower(ast_nodes.into_iter())?;
+			pipeline.run_ir(&mut program);
+			emitter::Llvm::emit(&program, &mut llvm_ir_output_file)?;
+			llvm_ir_output_file.flush()?;
+
+			// Compile LLVM IR to assembly, which the C compiler can turn into an executable
+			let assembly_output_path = Path::new(&path).with_extension("s");
+			let llc = process::Command::new("llc")
+				.args([
+					llvm_ir_output_path.to_string_lossy().as_ref(),
+					// The C compiler links position independent executables by default
+					"--relocation-model=pic",
+					// The assembler only understands `.file` directives without a separate directory for DWARF 4
+					"--dwarf-directory=false",
+					"-o",
+					assembly_output_path.to_string_lossy().as_ref(),
+				])
+				.output()
+				.context("Invoking LLVM static compiler")?;
+			if !llc.status.success() {
+				io::stdout().write_all(&llc.stdout)?;
+				io::stderr().write_all(&llc.stderr)?;
+				anyhow::bail!("LLVM static compiler failed with {}", llc.status);
+			}
+			assembly_output_path
+		},
+		cli::Target::Wasm => {
+			// The WebAssembly module is the final output, there is no native executable
+			let wat_output_path = Path::new(&path).with_extension("wat");
+			let mut wat_output_file = BufWriter::new(
+				File::create(&wat_output_path).context(format!("Creating output .wat file `{:?}`", wat_output_path))?,
+			);
+
+			let mut program = ir::lower(ast_nodes.into_iter())?;
+			pipeline.run_ir(&mut program);
+			emitter::Wasm::emit(&program, &mut wat_output_file)?;
+			wat_output_file.flush()?;
+			return Ok(());
+		},
+	};
 
 	// Compile to executable
 	let executable_output_path = Path::new(&path).with_extension("");
 	let c_compile = process::Command::new("cc")
-		.args([c_code_output_path.to_string_lossy().as_ref(), "-o", executable_output_path.to_string_lossy().as_ref()])
+		.args([
+			c_code_output_path.to_string_lossy().as_ref(),
+			"-o",
+			executable_output_path.to_string_lossy().as_ref(),
+			// The math builtins are implemented by libm
+			"-lm",
+		])
 		.output()
 		.context("Invoking C compiler")?;
 	if !c_compile.status.success() {
-		io::stdout().write_all(&c_compile.stdout).unwrap();
-		io::stderr().write_all(&c_compile.stderr).unwrap();
+		io::stdout().write_all(&c_compile.stdout)?;
+		io::stderr().write_all(&c_compile.stderr)?;
+		anyhow::bail!("C compiler failed with {}", c_compile.status);
 	}
 
 	Ok(())
 }
 
 /// Compiles and runs the executable.
-fn run(path: &Path) -> anyhow::Result<()> {
-	compile(path)?;
-
-	let executable = format!("./{}", Path::new(&path).with_extension("").to_string_lossy());
-	let executing_err = process::Command::new(&executable)
-		.stdin(process::Stdio::piped())
-		.stderr(process::Stdio::piped())
-		.stdout(process::Stdio::piped())
-		.exec();
+fn run(paths: &[PathBuf], target: cli::Target, pipeline: &Pipeline, reporting: Reporting) -> anyhow::Result<()> {
+	anyhow::ensure!(target != cli::Target::Wasm, "WebAssembly modules have to be run in a WebAssembly runtime");
+	compile(paths, target, pipeline, reporting)?;
+
+	// Joining keeps absolute paths, and makes relative ones start with `./`, so that they are not searched in `PATH`
+	let executable = Path::new(".").join(paths[0].with_extension(""));
+	// The program inherits stdin, stdout and stderr, so that it can interact with the terminal
+	let executing_err = process::Command::new(&executable).exec();
 	Result::Err(executing_err) // anyhow.context expects a Result
 		.context("Running executable")
 }
 
-fn print_error(err: anyhow::Error) {
-	let mut message = String::new();
+/// Interprets the program and exits with the value returned by `main()` as exit code.
+fn interpret(paths: &[PathBuf], bytecode: bool, pipeline: &Pipeline, reporting: Reporting) -> anyhow::Result<()> {
+	let ast_nodes = pipeline.run_ast(compiler_pipeline(paths, reporting)?)?;
 
-	if let Some(err) = err.downcast_ref::<lexer::Error>() {
-		message += "LexerError\n";
-		match err {
-			lexer::Error::UnknownSymbol(symbol) => {
-				message += &format!("{}\n{}", err, highlight_position_range(&symbol.position));
-			},
-			lexer::Error::IllegalSymbol(symbol) => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					symbol.as_ref().map(|s| highlight_position_range(&s.position)).unwrap_or_default()
-				);
-			},
-			lexer::Error::ParseNumberError(number_str) => {
-				message += &format!("{}\n{}", err, highlight_position_range(&number_str.position));
-			},
-		}
+	let return_value = match bytecode {
+		true => {
+			let mut program = ir::lower(ast_nodes.into_iter()).map_err(bytecode::CompileError::Lowering)?;
+			pipeline.run_ir(&mut program);
+			let program = bytecode::Compiler::compile_ir(&program)?;
+			tracing::trace!("Bytecode compiled:\n{}", program);
+			Vm::new(&program).run()?
+		},
+		false => {
+			warn_without_ir(pipeline, "the interpreter without `--bytecode`");
+			Interpreter::new(ast_nodes.into_iter()).run()?
+		},
+	};
+	exit_with_return_value(return_value);
+	Ok(())
+}
+
+/// Compiles the program just in time and exits with the value returned by `main()` as exit code.
+#[cfg(feature = "jit")]
+fn interpret_jit(paths: &[PathBuf], pipeline: &Pipeline, reporting: Reporting) -> anyhow::Result<()> {
+	let ast_nodes = pipeline.run_ast(compiler_pipeline(paths, reporting)?)?;
+
+	warn_without_ir(pipeline, "the JIT compiler");
+	let return_value = fortytwolang::jit::Jit::compile(ast_nodes.into_iter())?.run()?;
+	exit_with_return_value(return_value);
+	Ok(())
+}
+
+/// Exits with an int `return_value` as exit code, or prints any other return value.
+fn exit_with_return_value(return_value: Option<interpreter::Value>) {
+	match return_value {
+		Some(interpreter::Value::Int(exit_code)) => process::exit(exit_code as i32),
+		Some(return_value) => println!("{}", return_value),
+		None => (),
+	}
+}
+
+/// Prints the error to stderr, with the position in the source code if it has one, highlighted with colors if `color`
+/// is true.
+fn print_error(err: anyhow::Error, color: bool) {
+	let Some(diagnostics) = error_diagnostics(&err) else {
+		eprintln!("{:#}", err);
+		return;
+	};
+
+	// Like rustc, separate the diagnostics by an empty line
+	for diagnostic in diagnostics {
+		eprintln!("{}\n", diagnostic.render(color));
+	}
+}
+
+/// Converts the error into diagnostics, if it is an error of one of the compiler stages, which knows its position in
+/// the source code.
+fn error_diagnostics(err: &anyhow::Error) -> Option<Vec<Diagnostic>> {
+	let diagnostics = if let Some(err) = err.downcast_ref::<lexer::Error>() {
+		vec![Diagnostic::new(err.code(), err, lexer_error_position(err))]
 	} else if let Some(err) = err.downcast_ref::<parser::Error>() {
-		message += "ParserError\n";
-		match err {
-			Error::ExpectedToken { found, .. } => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					found.as_ref().map(|found| { highlight_position_range(&found.position) }).unwrap_or_default()
-				);
-			},
-			Error::IllegalToken { token, .. } => {
-				message += &format!(
-					"{}\n{}",
-					err,
-					token.as_ref().map(|found| { highlight_position_range(&found.position) }).unwrap_or_default()
-				);
-			},
-		}
+		err.clone()
+			.into_errors()
+			.iter()
+			.map(|err| Diagnostic::new(err.code(), err, parser_error_position(err)))
+			.collect()
+	} else if let Some(err) = err.downcast_ref::<module::Error>() {
+		let position = match err {
+			module::Error::NotFound { import, .. } => &import.position,
+		};
+		vec![Diagnostic::new(err.code(), err, Some(position))]
 	} else if let Some(err) = err.downcast_ref::<semantic_analyzer::Error>() {
-		message += "SemanticError\n";
-		match err {
-			semantic_analyzer::Error::Redeclaration { new_declaration, .. } => {
-				message += &format!("{}\n{}", err, highlight_position_range(&new_declaration.name.position))
-			},
-			semantic_analyzer::Error::UndeclaredVariable { name } => {
-				message += &format!("{}\n{}", err, highlight_position_range(&name.position))
-			},
-			semantic_analyzer::Error::TypeMismatch { position, .. } => {
-				message += &format!("{}\n{}", err, highlight_position_range(position))
-			},
-			semantic_analyzer::Error::UndefinedFunctionCall { function_call } => {
-				message += &format!("{}\n{}", err, highlight_position_range(&function_call.name.position))
-			},
-			semantic_analyzer::Error::ArgumentCountMismatch { function_call, .. } => {
-				// TODO: Highlight position of `function_call.args` instead of `function_call.name.position`
-				message += &format!("{}\n{}", err, highlight_position_range(&function_call.name.position))
+		semantic_error_diagnostics(err)
+	} else if let Some(err) = err.downcast_ref::<interpreter::Error>() {
+		let position = match err {
+			interpreter::Error::MissingMainFunction => None,
+			interpreter::Error::UndeclaredVariable { name } => Some(&name.position),
+			interpreter::Error::UndefinedFunctionCall { function_call }
+			| interpreter::Error::ArgumentCountMismatch { function_call, .. }
+			| interpreter::Error::MissingReturnValue { function_call }
+			| interpreter::Error::UnsupportedExtern { function_call } => Some(&function_call.name.position),
+			interpreter::Error::TypeMismatch { operator, .. } => Some(&operator.position),
+			interpreter::Error::InvalidOperand { operator, .. } => Some(&operator.position),
+			interpreter::Error::InvalidCast { position, .. } => Some(position),
+			interpreter::Error::NoStruct { field, .. } | interpreter::Error::UnknownField { field, .. } => {
+				Some(&field.position)
 			},
-		}
+			interpreter::Error::NoArray { position, .. }
+			| interpreter::Error::InvalidIndex { position, .. }
+			| interpreter::Error::IndexOutOfBounds { position, .. }
+			| interpreter::Error::DivisionByZero { position }
+			| interpreter::Error::NullDereference { position } => Some(position),
+		};
+		vec![Diagnostic::new(err.code(), err, position)]
+	} else if let Some(err) = err.downcast_ref::<ir::Error>() {
+		vec![Diagnostic::new(err.code(), err, Some(ir_error_position(err)))]
+	} else if let Some(err) = err.downcast_ref::<bytecode::CompileError>() {
+		let position = match err {
+			bytecode::CompileError::Lowering(err) => ir_error_position(err),
+			bytecode::CompileError::UnsupportedStruct { position }
+			| bytecode::CompileError::UnsupportedExtern { position, .. }
+			| bytecode::CompileError::UnsupportedDereference { position } => position,
+		};
+		vec![Diagnostic::new(err.code(), err, Some(position))]
+	} else if let Some(err) = err.downcast_ref::<bytecode::RuntimeError>() {
+		vec![Diagnostic::new(err.code(), err, None)]
+	} else if let Some(err) = jit_error(err) {
+		vec![err]
 	} else {
-		message = err.to_string();
-	}
+		return None;
+	};
+	Some(diagnostics)
+}
 
-	eprintln!("{}", message);
+/// Converts the error of the semantic analyzer into diagnostics, which are several ones for
+/// [multiple errors](semantic_analyzer::Error::Multiple) or errors that refer to another place in the source code.
+fn semantic_error_diagnostics(err: &semantic_analyzer::Error) -> Vec<Diagnostic> {
+	let position = match err {
+		semantic_analyzer::Error::Multiple(errors) => {
+			return errors.iter().flat_map(semantic_error_diagnostics).collect()
+		},
+		semantic_analyzer::Error::Redeclaration { new_declaration, .. } => Some(&new_declaration.name.position),
+		semantic_analyzer::Error::AssignmentToConstant { name, .. } => Some(&name.position),
+		semantic_analyzer::Error::NotConstant { position } => Some(position),
+		semantic_analyzer::Error::UndeclaredVariable { name } => Some(&name.position),
+		semantic_analyzer::Error::UnknownVariant { variant, .. } => Some(&variant.position),
+		semantic_analyzer::Error::RecursiveTypeAlias { alias } => Some(&alias.position),
+		semantic_analyzer::Error::UninferredTypeParameter { name, .. }
+		| semantic_analyzer::Error::TypeArgumentCountMismatch { name, .. } => Some(&name.position),
+		semantic_analyzer::Error::UnknownMethod { method, .. }
+		| semantic_analyzer::Error::InvalidReceiver { method, .. } => Some(&method.position),
+		semantic_analyzer::Error::TooManySpecializations { generic } => Some(&generic.position),
+		semantic_analyzer::Error::UnknownStruct { data_type } => Some(&data_type.position),
+		semantic_analyzer::Error::NoStruct { field, .. } | semantic_analyzer::Error::UnknownField { field, .. } => {
+			Some(&field.position)
+		},
+		semantic_analyzer::Error::MissingField { struct_name, .. } => Some(&struct_name.position),
+		semantic_analyzer::Error::DuplicateField { field } => Some(&field.position),
+		semantic_analyzer::Error::NoArray { position, .. }
+		| semantic_analyzer::Error::EmptyArrayLiteral { position } => Some(position),
+		semantic_analyzer::Error::TypeMismatch { position, .. }
+		| semantic_analyzer::Error::ArgumentTypeMismatch { position, .. }
+		| semantic_analyzer::Error::AssignmentTypeMismatch { position, .. } => Some(position),
+		semantic_analyzer::Error::InvalidCast { position, .. } => Some(position),
+		semantic_analyzer::Error::UntypedNull { name } | semantic_analyzer::Error::BuiltinRedefinition { name } => {
+			Some(&name.position)
+		},
+		semantic_analyzer::Error::InvalidBuiltinArgument { position, .. } => Some(position),
+		semantic_analyzer::Error::NonConstantPattern { position }
+		| semantic_analyzer::Error::MissingDefaultArm { position }
+		| semantic_analyzer::Error::MissingVariants { position, .. } => Some(position),
+		semantic_analyzer::Error::DuplicatePattern { duplicate, .. } => Some(duplicate),
+		semantic_analyzer::Error::UndefinedFunctionCall { function_call } => Some(&function_call.name.position),
+		semantic_analyzer::Error::ArgumentCountMismatch { function_call, .. } => Some(&function_call.position),
+		semantic_analyzer::Error::MissingReturnValue { function_call } => Some(&function_call.name.position),
+		semantic_analyzer::Error::ReturnWithoutValue { return_type } => Some(&return_type.position),
+		semantic_analyzer::Error::UnexpectedReturnValue { position } => Some(position),
+		semantic_analyzer::Error::MissingReturn { name, .. } => Some(&name.position),
+		semantic_analyzer::Error::DuplicateFunction { duplicate, .. }
+		| semantic_analyzer::Error::DuplicateStruct { duplicate, .. }
+		| semantic_analyzer::Error::DuplicateStructField { duplicate, .. }
+		| semantic_analyzer::Error::DuplicateEnum { duplicate, .. }
+		| semantic_analyzer::Error::DuplicateVariant { duplicate, .. }
+		| semantic_analyzer::Error::DuplicateTypeAlias { duplicate, .. } => Some(&duplicate.position),
+		semantic_analyzer::Error::InvalidOperand { operator, .. } => Some(&operator.position),
+		semantic_analyzer::Error::InvalidUnaryOperand { operator, .. } => Some(&operator.position),
+	};
+	let mut diagnostics = vec![Diagnostic::new(err.code(), err, position)];
+	// Point to the original definition, too
+	if let semantic_analyzer::Error::DuplicateFunction { previous, duplicate }
+	| semantic_analyzer::Error::DuplicateStruct { previous, duplicate }
+	| semantic_analyzer::Error::DuplicateStructField { previous, duplicate }
+	| semantic_analyzer::Error::DuplicateEnum { previous, duplicate }
+	| semantic_analyzer::Error::DuplicateVariant { previous, duplicate }
+	| semantic_analyzer::Error::DuplicateTypeAlias { previous, duplicate } = err
+	{
+		let message = format!("`{}` was first defined here", duplicate.value);
+		diagnostics.push(Diagnostic::new(err.code(), message, Some(previous)).with_severity(Severity::Note));
+	}
+	if let semantic_analyzer::Error::DuplicatePattern { previous, value, .. } = err {
+		let message = format!("{} was first matched here", value);
+		diagnostics.push(Diagnostic::new(err.code(), message, Some(previous)).with_severity(Severity::Note));
+	}
+	// Point to the declaration of the argument, whose type is expected
+	if let semantic_analyzer::Error::ArgumentTypeMismatch { argument, .. } = err {
+		let message = format!("Argument `{}` is declared here", argument.name.value);
+		diagnostics
+			.push(Diagnostic::new(err.code(), message, Some(&argument.name.position)).with_severity(Severity::Note));
+	}
+	// Point to the declaration of the variable, which determines its type
+	if let semantic_analyzer::Error::AssignmentTypeMismatch { variable, .. } = err {
+		let message = format!("`{}` is declared here with type {}", variable.name.value, variable.type_);
+		diagnostics
+			.push(Diagnostic::new(err.code(), message, Some(&variable.name.position)).with_severity(Severity::Note));
+	}
+	diagnostics
 }
 
-/// Highlights/underlines the affected position range in the source code line.
-fn highlight_position_range(position: &SourcePositionRange) -> String {
-	let affected_code = position.get_affected_lines();
+/// Converts the error into a diagnostic if it is an error of the JIT.
+#[cfg(feature = "jit")]
+fn jit_error(err: &anyhow::Error) -> Option<Diagnostic> {
+	use fortytwolang::jit;
 
-	let mut output = String::new();
+	let err = err.downcast_ref::<jit::Error>()?;
+	let position = match err {
+		jit::Error::UnsupportedHost(_) | jit::Error::Module(_) | jit::Error::MissingMainFunction => None,
+		jit::Error::UnsupportedDataType { data_type } => Some(&data_type.position),
+		jit::Error::UnsupportedStruct { position }
+		| jit::Error::UnsupportedArray { position }
+		| jit::Error::UnsupportedDereference { position }
+		| jit::Error::BranchTypeMismatch { position } => Some(position),
+		jit::Error::UndeclaredVariable { name } => Some(&name.position),
+		jit::Error::UndefinedFunctionCall { function_call }
+		| jit::Error::ArgumentCountMismatch { function_call, .. }
+		| jit::Error::MissingReturnValue { function_call } => Some(&function_call.name.position),
+		jit::Error::TypeMismatch { operator } | jit::Error::InvalidFloatOperands { operator } => {
+			Some(&operator.position)
+		},
+		jit::Error::InvalidOperand { operator } => Some(&operator.position),
+	};
+	Some(Diagnostic::new(err.code(), err, position))
+}
 
-	for line_with_whitespaces in affected_code.lines() {
-		let line = line_with_whitespaces.trim_start();
-		let spaces_removed = line_with_whitespaces.len() - line.len();
+/// Without the JIT, there are no errors of it.
+#[cfg(not(feature = "jit"))]
+fn jit_error(_err: &anyhow::Error) -> Option<Diagnostic> {
+	None
+}
 
-		// Write source code line
-		output.push_str(line);
-		output.push('\n');
+/// The position of the symbol or literal at which the lexer error occurred.
+fn lexer_error_position(err: &lexer::Error) -> Option<&SourcePositionRange> {
+	match err {
+		lexer::Error::UnknownSymbol(symbol) => Some(&symbol.position),
+		lexer::Error::IllegalSymbol(symbol) => symbol.as_ref().map(|symbol| &symbol.position),
+		lexer::Error::ParseNumberError(number_str) => Some(&number_str.position),
+		lexer::Error::InvalidDigit { number, .. }
+		| lexer::Error::MisplacedUnderscore(number)
+		| lexer::Error::LeadingDigit(number) => Some(&number.position),
+		lexer::Error::UnterminatedStringLiteral(string) | lexer::Error::InvalidCharLiteral(string) => {
+			Some(&string.position)
+		},
+		lexer::Error::UnterminatedBlockComment(comment) => Some(&comment.position),
+	}
+}
+
+/// The position of the variable, call, field or index at which lowering to the IR failed.
+fn ir_error_position(err: &ir::Error) -> &SourcePositionRange {
+	match err {
+		ir::Error::UndeclaredVariable { name } => &name.position,
+		ir::Error::UndefinedFunctionCall { function_call }
+		| ir::Error::ArgumentCountMismatch { function_call, .. }
+		| ir::Error::MissingReturnValue { function_call } => &function_call.name.position,
+		ir::Error::NoStruct { field, .. } | ir::Error::UnknownField { field, .. } => &field.position,
+		ir::Error::NoArray { position, .. } => position,
+	}
+}
 
-		// Write underline
-		output.push_str(&" ".repeat(position.position.start.column - 1 - spaces_removed));
-		let highlight_width = position.position.end.column - position.position.start.column + 1;
-		output.push_str(&"^".repeat(highlight_width));
+/// The position of the token at which the parser error occurred, if the tokens did not end before.
+fn parser_error_position(err: &parser::Error) -> Option<&SourcePositionRange> {
+	match err {
+		Error::ExpectedToken { found: token, .. }
+		| Error::IllegalToken { token, .. }
+		| Error::NestingTooDeep { token } => token.as_ref().map(|token| &token.position),
+		Error::Lexer(err) => lexer_error_position(err),
+		Error::Multiple(_) => None,
 	}
-	output
 }
@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use crate::source::PositionContainer;
+
+/// Errors that occur while [loading](super::load) the modules imported by a program.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Error {
+	#[error("{}: ModuleNotFound: Module `{}` is imported, but there is no file `{}`.", import.position, import.value, path.display())]
+	NotFound { import: PositionContainer<String>, path: PathBuf },
+}
@@ -0,0 +1,121 @@
+//! Loading the modules imported with `import`.
+//!
+//! `import math` loads the file `math.ftl` next to the importing file. The functions, structs and constants of the
+//! module are merged into the program under their qualified names like `math.square`, by which the importing file
+//! refers to them, e.g. in `math.square(3)` or `var v: math.Vector`.
+
+mod error;
+mod qualify;
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+pub use error::Error;
+
+use crate::{
+	ast::{enum_::EnumResolver, visit::VisitorMut, Node},
+	error::DiagnosticSink,
+	module::qualify::Qualifier,
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// The name of the `member` of the `module`, by which it is referred to outside of the module, like `math.square`.
+pub fn qualified_name(module: &str, member: &str) -> Symbol {
+	Symbol::from(format!("{}.{}", module, member))
+}
+
+/// Combines the name of the `module` and its `member`, as written in `math.square`, into the qualified name.
+pub fn qualify(module: PositionContainer<Symbol>, member: PositionContainer<Symbol>) -> PositionContainer<Symbol> {
+	let mut position = module.position;
+	position.position.end = member.position.position.end;
+	PositionContainer::new(qualified_name(&module.value, &member.value), position)
+}
+
+/// Parses the source files at `paths` and all modules they import, also indirectly, and returns the nodes of the
+/// modules followed by those of the files. The files share a single namespace, i.e. their members are not qualified.
+/// Like the files, each module comes after the modules it imports, so that e.g. the C emitter defines structs before
+/// they are used.
+///
+/// Each module is only loaded once, even if it is imported several times or imports are cyclic. An extern function
+/// declared by several files refers to the same C function, so only its first declaration is kept.
+pub fn load(paths: &[PathBuf], diagnostics: &DiagnosticSink) -> anyhow::Result<Vec<Node>> {
+	let files = paths.iter().map(|path| crate::parse_file(path, diagnostics)).collect::<anyhow::Result<Vec<_>>>()?;
+	load_parsed(files, paths, diagnostics)
+}
+
+/// Like [`load`], but for files which are already parsed, e.g. from the unsaved content of an editor. The modules are
+/// looked up next to the `paths` of the `files`.
+pub fn load_parsed(
+	files: Vec<Vec<Node>>,
+	paths: &[PathBuf],
+	diagnostics: &DiagnosticSink,
+) -> anyhow::Result<Vec<Node>> {
+	let mut loaded = HashSet::new();
+	let mut modules = Vec::new();
+	for (file, path) in files.iter().zip(paths) {
+		load_imports(file, path, diagnostics, &mut loaded, &mut modules)?;
+	}
+
+	let mut ast_nodes = Vec::new();
+	let mut externs = HashSet::new();
+	let mut constants = HashSet::new();
+	for node in modules.into_iter().chain(files.into_iter().flatten()) {
+		match &node {
+			Node::FunctionPrototype(prototype) if !externs.insert(prototype.name.value) => continue,
+			Node::Const(const_declaration) => {
+				constants.insert(const_declaration.name.value);
+			},
+			_ => (),
+		}
+		ast_nodes.push(node);
+	}
+
+	// Types like `math.Color` and variants like `math.Color.Red` of imported enums are only known now
+	let enums = EnumResolver::declared(&ast_nodes);
+	let mut qualifier = Qualifier::imports(constants);
+	let mut resolver = EnumResolver { enums: &enums };
+	for node in &mut ast_nodes {
+		qualifier.visit_node(node);
+		resolver.visit_node(node);
+	}
+	Ok(ast_nodes)
+}
+
+/// Loads the modules imported by the `ast_nodes` of the file at `path`, unless they are already `loaded`, and appends
+/// their qualified nodes to `modules`.
+fn load_imports(
+	ast_nodes: &[Node],
+	path: &Path,
+	diagnostics: &DiagnosticSink,
+	loaded: &mut HashSet<String>,
+	modules: &mut Vec<Node>,
+) -> anyhow::Result<()> {
+	for node in ast_nodes {
+		let Node::Import(import) = node else {
+			continue;
+		};
+		if !loaded.insert(import.value.clone()) {
+			continue;
+		}
+		let module_path = path.with_file_name(format!("{}.ftl", import.value));
+		if !module_path.is_file() {
+			return Err(Error::NotFound { import: import.clone(), path: module_path }.into());
+		}
+		let mut module = crate::parse_file(&module_path, diagnostics)
+			.with_context(|| format!("Loading module `{}`", import.value))?;
+		load_imports(&module, &module_path, diagnostics, loaded, modules)?;
+
+		let mut qualifier = Qualifier::new(&import.value, &module);
+		for node in &mut module {
+			qualifier.visit_node(node);
+		}
+		modules.extend(module);
+	}
+	Ok(())
+}
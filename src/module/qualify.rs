@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use crate::{
+	ast::{
+		self,
+		statement::{DataType, LValue},
+		visit::{self, VisitorMut},
+		Expression, Node,
+	},
+	module::qualified_name,
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// Renames the functions, structs, enums, type aliases and constants defined in a module to their qualified names, and
+/// all references to them inside the module, too. Extern functions keep their names, because they refer to C functions.
+///
+/// Calls like `math.square(3)` and types like `math.Vector` are already qualified by the parser, but `math.PI` is
+/// parsed as a field access, which refers to a constant of an imported module if `math.PI` is one of the
+/// `imported_constants`.
+pub(super) struct Qualifier<'a> {
+	/// Name of the module, like `math`.
+	module: &'a str,
+	/// Names of the functions defined in the module.
+	functions: HashSet<Symbol>,
+	/// Names of the structs, enums and type aliases defined in the module.
+	structs: HashSet<Symbol>,
+	/// Names of the constants and variants like `Color.Red` defined in the module. Local variables cannot shadow
+	/// constants, so a variable with the name of a constant always refers to the constant.
+	constants: HashSet<Symbol>,
+	/// Qualified names of the constants of all loaded modules.
+	imported_constants: HashSet<Symbol>,
+}
+
+impl<'a> Qualifier<'a> {
+	/// Qualifies the members of the `module` consisting of the `nodes`.
+	pub fn new(module: &'a str, nodes: &[Node]) -> Self {
+		let mut this = Self {
+			module,
+			functions: HashSet::new(),
+			structs: HashSet::new(),
+			constants: HashSet::new(),
+			imported_constants: HashSet::new(),
+		};
+		for node in nodes {
+			match node {
+				Node::Function(function) => this.functions.insert(function.prototype.name.value),
+				Node::Struct(struct_) => this.structs.insert(struct_.name.value),
+				Node::Enum(enum_) => {
+					this.constants.extend(enum_.variants.iter().map(|variant| enum_.variant_name(variant).value));
+					this.structs.insert(enum_.name.value)
+				},
+				Node::TypeAlias(type_alias) => this.structs.insert(type_alias.name.value),
+				Node::Const(const_declaration) => this.constants.insert(const_declaration.name.value),
+				Node::FunctionPrototype(_) | Node::Import(_) | Node::Comment(_) => continue,
+			};
+		}
+		this
+	}
+
+	/// Only resolves references to the `imported_constants`, like `math.PI`.
+	pub fn imports(imported_constants: HashSet<Symbol>) -> Self {
+		Self { imported_constants, ..Self::new("", &[]) }
+	}
+
+	fn data_type(&self, data_type: &mut DataType) {
+		match data_type {
+			DataType::Struct(name) | DataType::Enum(name) if self.structs.contains(name) => {
+				*name = qualified_name(self.module, name)
+			},
+			DataType::Generic { name, type_arguments } => {
+				if self.structs.contains(name) {
+					*name = qualified_name(self.module, name);
+				}
+				type_arguments.iter_mut().for_each(|type_argument| self.data_type(type_argument));
+			},
+			DataType::Pointer(pointee) => self.data_type(&mut pointee.value),
+			DataType::Array { element, .. } => self.data_type(element),
+			DataType::Basic(_) | DataType::Struct(_) | DataType::Enum(_) | DataType::Null => (),
+		}
+	}
+
+	/// Whether `module.member` refers to a constant of an imported module.
+	fn is_imported_constant(&self, module: &PositionContainer<Symbol>, member: &PositionContainer<Symbol>) -> bool {
+		self.imported_constants.contains(&qualified_name(module, member))
+	}
+
+	/// Qualifies the name, if it is one of the `members` of the module.
+	fn qualify(&self, members: &HashSet<Symbol>, name: &mut PositionContainer<Symbol>) {
+		if members.contains(&name.value) {
+			name.value = qualified_name(self.module, &name.value);
+		}
+	}
+}
+
+impl VisitorMut for Qualifier<'_> {
+	fn visit_node(&mut self, node: &mut Node) {
+		match node {
+			Node::Struct(struct_) => self.qualify(&self.structs, &mut struct_.name),
+			Node::Enum(enum_) => self.qualify(&self.structs, &mut enum_.name),
+			Node::TypeAlias(type_alias) => self.qualify(&self.structs, &mut type_alias.name),
+			Node::Const(const_declaration) => self.qualify(&self.constants, &mut const_declaration.name),
+			_ => (),
+		}
+		visit::walk_node_mut(self, node);
+	}
+
+	fn visit_prototype(&mut self, prototype: &mut ast::FunctionPrototype) {
+		self.qualify(&self.functions, &mut prototype.name);
+		visit::walk_prototype_mut(self, prototype);
+	}
+
+	fn visit_lvalue(&mut self, lvalue: &mut LValue) {
+		match lvalue {
+			// Assigning to a constant is an error, which should mention the qualified name, too
+			LValue::Variable(name) => self.qualify(&self.constants, name),
+			LValue::FieldAccess { base, field } => {
+				if let LValue::Variable(module) = &**base {
+					if self.is_imported_constant(module, field) {
+						*lvalue = LValue::Variable(super::qualify(module.clone(), field.clone()));
+						return;
+					}
+				}
+			},
+			LValue::Index { .. } | LValue::Dereference { .. } => (),
+		}
+		visit::walk_lvalue_mut(self, lvalue);
+	}
+
+	fn visit_expression(&mut self, expression: &mut Expression) {
+		match expression {
+			// The method of a receiver is found by the type of the receiver, whose struct is qualified already
+			Expression::FunctionCall(function_call) if function_call.receiver.is_some() => (),
+			Expression::FunctionCall(function_call) => self.qualify(&self.functions, &mut function_call.name),
+			Expression::FieldAccess(field_access) => {
+				if let Expression::Variable(module) = &*field_access.base {
+					if self.is_imported_constant(module, &field_access.field) {
+						*expression = Expression::Variable(super::qualify(module.clone(), field_access.field.clone()));
+						return;
+					}
+				}
+			},
+			Expression::StructLiteral(struct_literal) => self.qualify(&self.structs, &mut struct_literal.name),
+			Expression::Variable(name) => self.qualify(&self.constants, name),
+			_ => (),
+		}
+		visit::walk_expression_mut(self, expression);
+	}
+
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		self.data_type(&mut data_type.value);
+	}
+}
@@ -0,0 +1,267 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+	ast::Node,
+	error::DiagnosticSink,
+	interpreter::{Interpreter, Value},
+	module::{load, Error},
+};
+
+/// Tests that the members of imported modules are available under their qualified names, including constants and
+/// members of indirectly and cyclically imported modules.
+#[test]
+fn test_import() {
+	let directory = write_files(
+		"import",
+		&[
+			(
+				"main.ftl",
+				"import geo\n
+			def main(): int {
+				var v: geo.Vector = geo.make(3, 4)
+				v.x = (v.x + geo.ONE)
+				return geo.length2(v)
+			}",
+			),
+			(
+				"geo.ftl",
+				"import util\n
+			const ONE = 1
+			struct Vector {
+				x: int
+				y: int
+			}
+			def make(x: int, y: int): Vector {
+				return Vector { x: x, y: y }
+			}
+			def length2(v: Vector): int {
+				return util.add((v.x * v.x), (v.y * v.y))
+			}",
+			),
+			("util.ftl", "import geo\n\ndef add(a: int, b: int): int {\n\treturn a + b\n}"),
+		],
+	);
+	let ast_nodes = load(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	let functions: Vec<&str> = ast_nodes
+		.iter()
+		.filter_map(|node| match node {
+			Node::Function(function) => Some(function.prototype.name.as_str()),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(functions, vec!["util.add", "geo.make", "geo.length2", "main"]);
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(32)));
+}
+
+/// Tests that several files are combined into a single program, which loads a module imported by several of them only
+/// once.
+#[test]
+fn test_load_several_files() {
+	let directory = write_files(
+		"several_files",
+		&[
+			("main.ftl", "import util\n\ndef main(): int {\n\treturn util.add(helper(), 1)\n}"),
+			("helper.ftl", "import util\n\ndef helper(): int {\n\treturn util.add(40, 1)\n}"),
+			("util.ftl", "def add(a: int, b: int): int {\n\treturn a + b\n}"),
+		],
+	);
+	let paths = [directory.join("main.ftl"), directory.join("helper.ftl")];
+	let ast_nodes = crate::compiler_pipeline(&paths, &DiagnosticSink::default()).unwrap();
+	assert_eq!(ast_nodes.iter().filter(|node| matches!(node, Node::Function(_))).count(), 3);
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(42)));
+}
+
+/// Tests that enums of imported modules are referred to by qualified names, both as types and for their variants.
+#[test]
+fn test_import_enum() {
+	let directory = write_files(
+		"import_enum",
+		&[
+			(
+				"main.ftl",
+				"import geo\n
+			def main(): int {
+				var side: geo.Side = geo.opposite(geo.Side.Left)
+				return side as int
+			}",
+			),
+			(
+				"geo.ftl",
+				"enum Side { Left, Right }
+			def opposite(side: Side): Side {
+				if side = Side.Left {
+					return Side.Right
+				}
+				return Side.Left
+			}",
+			),
+		],
+	);
+	let ast_nodes = crate::compiler_pipeline(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	assert!(ast_nodes.iter().any(|node| matches!(node, Node::Enum(enum_) if enum_.name.value == "geo.Side")));
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(1)));
+}
+
+/// Tests that type aliases of imported modules are referred to by qualified names and resolved by the semantic
+/// analysis, also if they refer to other aliases of their module.
+#[test]
+fn test_import_type_alias() {
+	let directory = write_files(
+		"import_type_alias",
+		&[
+			(
+				"main.ftl",
+				"import units\n
+			def main(): int {
+				var length: units.Meters = 2
+				return units.double(length)
+			}",
+			),
+			(
+				"units.ftl",
+				"type Meters = Length
+			type Length = int
+			def double(x: Meters): int {
+				return x * 2
+			}",
+			),
+		],
+	);
+	let ast_nodes = crate::compiler_pipeline(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	assert!(ast_nodes.iter().any(|node| matches!(node, Node::TypeAlias(alias) if alias.name.value == "units.Meters")));
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(4)));
+}
+
+/// Tests that a generic function of an imported module is replaced by its specializations, whose names are qualified
+/// like the generic function.
+#[test]
+fn test_import_generic_function() {
+	let directory = write_files(
+		"import_generic_function",
+		&[
+			(
+				"main.ftl",
+				"import util\n
+			def main(): int {
+				var x: float = util.twice(1.5)
+				return util.twice(2)
+			}",
+			),
+			(
+				"util.ftl",
+				"def twice<T>(x: T): T {
+				return x + x
+			}",
+			),
+		],
+	);
+	let ast_nodes = crate::compiler_pipeline(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	let functions: Vec<_> = (ast_nodes.iter())
+		.filter_map(|node| match node {
+			Node::Function(function) => Some(function.prototype.name.as_str()),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(functions, ["util.twice.float", "util.twice.int", "main"]);
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(4)));
+}
+
+/// Tests that a generic struct of an imported module is specialized with qualified names for the types it is used with.
+#[test]
+fn test_import_generic_struct() {
+	let directory = write_files(
+		"import_generic_struct",
+		&[
+			(
+				"main.ftl",
+				"import util\n
+			def main(): int {
+				var pair: util.Pair<int> = util.pair(3)
+				return pair.first + pair.second
+			}",
+			),
+			(
+				"util.ftl",
+				"struct Pair<T> {
+				first: T
+				second: T
+			}
+			def pair<T>(x: T): Pair<T> {
+				return Pair { first: x, second: x }
+			}",
+			),
+		],
+	);
+	let ast_nodes = crate::compiler_pipeline(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	let structs: Vec<_> = (ast_nodes.iter())
+		.filter_map(|node| match node {
+			Node::Struct(struct_) => Some(struct_.name.as_str()),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(structs, ["util.Pair.int"]);
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(6)));
+}
+
+/// Tests that methods of a struct of an imported module are found by the qualified name of the struct, both in the
+/// module and in the importing module.
+#[test]
+fn test_import_method() {
+	let directory = write_files(
+		"import_method",
+		&[
+			(
+				"main.ftl",
+				"import util\n
+			def main(): int {
+				var p = util.Point { x: 1, y: 2 }
+				return p.scaled(2).sum()
+			}",
+			),
+			(
+				"util.ftl",
+				"struct Point {
+				x: int
+				y: int
+			}
+			def Point.sum(self: Point): int {
+				return self.x + self.y
+			}
+			def Point.scaled(self: Point, factor: int): Point {
+				var sum = self.sum()
+				return Point { x: (self.x * factor), y: (self.y * factor) }
+			}",
+			),
+		],
+	);
+	let ast_nodes = crate::compiler_pipeline(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap();
+	let functions: Vec<_> = (ast_nodes.iter())
+		.filter_map(|node| match node {
+			Node::Function(function) => Some(function.prototype.name.as_str()),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(functions, ["util.Point.sum", "util.Point.scaled", "main"]);
+	assert_eq!(Interpreter::new(ast_nodes.into_iter()).run().unwrap(), Some(Value::Int(6)));
+}
+
+/// Tests that importing a module without a source file is an error.
+#[test]
+fn test_import_not_found() {
+	let directory = write_files("import_not_found", &[("main.ftl", "import math\n\ndef main() {\n}")]);
+	let error = load(&[directory.join("main.ftl")], &DiagnosticSink::default()).unwrap_err();
+	let Ok(Error::NotFound { import, path }) = error.downcast::<Error>() else { panic!() };
+	assert_eq!(import.value, "math");
+	assert_eq!(path, directory.join("math.ftl"));
+}
+
+/// Writes the source `files` into a new temporary directory for the test called `name`.
+fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+	let directory = std::env::temp_dir().join(format!("fortytwolang_module_{}", name));
+	let _ = fs::remove_dir_all(&directory);
+	fs::create_dir_all(&directory).unwrap();
+	for (file_name, source_code) in files {
+		fs::write(directory.join(file_name), source_code).unwrap();
+	}
+	directory
+}
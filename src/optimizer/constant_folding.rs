@@ -0,0 +1,65 @@
+use crate::{
+	ast::{
+		expression::NumberKind,
+		fold::{self, Fold},
+		Expression,
+	},
+	semantic_analyzer::const_fold::{self, Constant},
+	source::{PositionContainer, SourcePositionRange},
+};
+
+/// Replaces unary and binary expressions with literal operands by the literal of their value, e.g. `1 + 2 * 3` by `7`
+/// and the condition `1 < 2` by `true`. The operands are folded first, so that nested expressions are folded
+/// completely.
+///
+/// Expressions whose evaluation fails, like a division by zero, are kept, so that they fail at runtime like before.
+/// So are results which cannot be written as literal, like NaN.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use fortytwolang::{ast::fold::Fold, optimizer::ConstantFolding, Lexer, Parser, Source};
+///
+/// let source = Arc::new(Source::new("example.ftl".to_owned(), "const answer: int = (6 * 7)".to_owned()));
+/// let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+/// let ast_nodes = ConstantFolding.fold_nodes(Parser::new(tokens.into_iter()).parse().unwrap());
+/// let fortytwolang::ast::Node::Const(answer) = &ast_nodes[0] else { panic!() };
+/// assert!(matches!(answer.value, fortytwolang::ast::Expression::Number(_)));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConstantFolding;
+
+impl Fold for ConstantFolding {
+	fn fold_expression(&mut self, expression: Expression) -> Expression {
+		let expression = fold::walk_expression(self, expression);
+		let literal_operands = match &expression {
+			Expression::BinaryExpression(binary_expression) => {
+				is_literal(&binary_expression.lhs) && is_literal(&binary_expression.rhs)
+			},
+			Expression::UnaryExpression(unary_expression) => is_literal(&unary_expression.operand),
+			_ => false,
+		};
+		if !literal_operands {
+			return expression;
+		}
+		let position = expression.source_position();
+		const_fold::fold(&expression, &|_| None).and_then(|value| literal(value, position)).unwrap_or(expression)
+	}
+}
+
+fn is_literal(expression: &Expression) -> bool {
+	matches!(expression, Expression::Number(_) | Expression::Bool(_) | Expression::Char(_))
+}
+
+/// The literal with the `value`, unless there is none. The smallest integer has none either, because C could only
+/// negate its absolute value, which is too large for an `int`.
+fn literal(value: Constant, position: SourcePositionRange) -> Option<Expression> {
+	Some(match value {
+		Constant::Int(i64::MIN) => return None,
+		Constant::Int(int) => Expression::Number(PositionContainer::new(NumberKind::Int(int), position)),
+		Constant::Float(float) if !float.is_finite() => return None,
+		Constant::Float(float) => Expression::Number(PositionContainer::new(NumberKind::Float(float), position)),
+		Constant::Bool(bool) => Expression::Bool(PositionContainer::new(bool, position)),
+		Constant::Char(char) => Expression::Char(PositionContainer::new(char, position)),
+	})
+}
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use crate::{
+	ast::{
+		fold::{self, Fold},
+		statement::LValue,
+		visit::{self, Visitor},
+		Block, Expression, FunctionDefinition, IfElse, Instruction, Statement,
+	},
+	optimizer::is_pure,
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// Removes code which is never executed or whose result is never used:
+///
+/// * The branch of an `if` which is not taken because its condition is `true` or `false`. The other branch replaces
+///   the `if`, unless it declares variables, which are only visible inside of it.
+/// * `while` loops whose condition is `false`.
+/// * Local variables and constants which are never referenced, if computing their value has no effects, like calling
+///   a function or dividing by zero.
+///
+/// Conditions are only recognized as literal `true` and `false`, so [`ConstantFolding`](super::ConstantFolding) should
+/// run before to fold comparisons like `1 < 2`.
+#[derive(Debug, Clone, Default)]
+pub struct DeadCodeElimination {
+	/// The names of the variables and constants referenced in the current function.
+	referenced: HashSet<Symbol>,
+}
+
+impl Fold for DeadCodeElimination {
+	fn fold_function(&mut self, function: FunctionDefinition) -> FunctionDefinition {
+		let mut references = References::default();
+		references.visit_block(&function.body);
+		self.referenced = references.0;
+		fold::walk_function(self, function)
+	}
+
+	fn fold_instruction(&mut self, instruction: Instruction) -> Block {
+		match instruction {
+			Instruction::IfElse(if_else) => match if_else.condition {
+				Expression::Bool(condition) => {
+					let branch = if condition.value { if_else.if_true } else { if_else.if_false };
+					let branch = self.fold_block(branch);
+					if !declares_variables(&branch) {
+						return branch;
+					}
+					let condition = Expression::Bool(PositionContainer::new(true, condition.position));
+					vec![Instruction::IfElse(Box::new(IfElse {
+						condition,
+						if_true: branch,
+						if_false: Vec::new(),
+						..*if_else
+					}))]
+				},
+				_ => fold::walk_instruction(self, Instruction::IfElse(if_else)),
+			},
+			Instruction::WhileLoop(while_loop)
+				if matches!(while_loop.condition, Expression::Bool(PositionContainer { value: false, .. })) =>
+			{
+				Vec::new()
+			},
+			Instruction::Statement(Statement::VariableDeclaration(variable_declaration))
+				if !self.referenced.contains(&variable_declaration.name.value)
+					&& is_pure(&variable_declaration.value) =>
+			{
+				Vec::new()
+			},
+			Instruction::Statement(Statement::ConstDeclaration(const_declaration))
+				if !self.referenced.contains(&const_declaration.name.value) =>
+			{
+				Vec::new()
+			},
+			instruction => fold::walk_instruction(self, instruction),
+		}
+	}
+}
+
+/// Collects the names of all variables which are read or assigned to.
+#[derive(Debug, Default)]
+struct References(HashSet<Symbol>);
+
+impl Visitor for References {
+	fn visit_lvalue(&mut self, lvalue: &LValue) {
+		if let LValue::Variable(variable) = lvalue {
+			self.0.insert(variable.value);
+		}
+		visit::walk_lvalue(self, lvalue);
+	}
+
+	fn visit_expression(&mut self, expression: &Expression) {
+		if let Expression::Variable(variable) = expression {
+			self.0.insert(variable.value);
+		}
+		visit::walk_expression(self, expression);
+	}
+}
+
+/// Whether the `block` declares variables or constants, which would conflict with the ones of the surrounding block
+/// if it was merged into it.
+fn declares_variables(block: &Block) -> bool {
+	block.iter().any(|instruction| {
+		matches!(
+			instruction,
+			Instruction::Statement(Statement::VariableDeclaration(_) | Statement::ConstDeclaration(_))
+		)
+	})
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use crate::{
+	ast::{
+		expression::FunctionCall,
+		fold::{self, Fold},
+		statement::{DataType, LValue, VariableDeclaration},
+		visit::{self, Visitor},
+		Block, Expression, FunctionArgument, FunctionDefinition, Instruction, Node, Statement,
+	},
+	optimizer::is_pure,
+	symbol::Symbol,
+};
+
+/// Replaces calls of small functions by their bodies, so that e.g. accessors cost no call.
+///
+/// A function can be inlined if its body only declares local variables and constants and then returns a value, its
+/// size is at most the threshold, and it does not call itself. Calls in the inlined bodies are not inlined again, so
+/// functions calling each other are inlined only once.
+///
+/// * If the function has no local variables and the arguments have no effects, the call is replaced by the returned
+///   value, with the parameters replaced by the arguments. This works in any expression.
+/// * Otherwise, if the call is the value of a variable declaration, an assignment to a variable, a `return` or an
+///   expression on its own, the parameters and local variables are declared before with unique names, like
+///   `_inline1_x`, so that the arguments are evaluated once and in order like before.
+#[derive(Debug, Clone)]
+pub struct Inlining {
+	/// The maximum number of expressions and declarations of an inlined function.
+	threshold: usize,
+	/// The functions which can be inlined by their names.
+	functions: HashMap<Symbol, Inlinable>,
+	/// The number of calls inlined with declarations, which makes the names of their variables unique.
+	inlined: usize,
+}
+
+/// The body of a function which can be inlined.
+#[derive(Debug, Clone)]
+struct Inlinable {
+	params: Vec<FunctionArgument>,
+	/// The declarations of the local variables and constants.
+	locals: Vec<VariableDeclaration>,
+	/// The returned value.
+	value: Expression,
+}
+
+impl Inlining {
+	/// The threshold of [`Inlining::default`], which inlines functions like accessors and simple arithmetic.
+	pub const DEFAULT_THRESHOLD: usize = 16;
+
+	/// Inlines the functions whose size is at most the `threshold`.
+	pub fn new(threshold: usize) -> Self {
+		Self { threshold, functions: HashMap::new(), inlined: 0 }
+	}
+
+	/// The inlined body of the `call`, if it calls a function which can be inlined: The declarations of the parameters
+	/// and local variables, and the returned value.
+	fn expand(&mut self, call: &Expression) -> Option<(Block, Expression)> {
+		let Expression::FunctionCall(call) = call else {
+			return None;
+		};
+		let function = self.functions.get(&call.name.value)?;
+		// Arrays cannot be copied into a new variable in C
+		if function.params.iter().any(|param| matches!(param.data_type.value, DataType::Array { .. })) {
+			return None;
+		}
+		self.inlined += 1;
+		let prefix = format!("_inline{}_", self.inlined);
+		let mut renames = Substitution::default();
+		let mut declarations = Vec::new();
+		let params = function.params.iter().map(|param| (&param.name, Some(param.data_type.clone())));
+		for ((name, data_type), arg) in params.zip(call.params.iter().cloned()) {
+			let declaration =
+				VariableDeclaration { name: name.clone(), data_type, position: arg.source_position(), value: arg };
+			declarations.push(renames.declare(&prefix, declaration));
+		}
+		for local in &function.locals {
+			let value = renames.fold_expression(local.value.clone());
+			declarations.push(renames.declare(&prefix, VariableDeclaration { value, ..local.clone() }));
+		}
+		let declarations = declarations
+			.into_iter()
+			.map(|declaration| Instruction::Statement(Statement::VariableDeclaration(Box::new(declaration))))
+			.collect();
+		Some((declarations, renames.fold_expression(function.value.clone())))
+	}
+}
+
+impl Default for Inlining {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_THRESHOLD)
+	}
+}
+
+impl Fold for Inlining {
+	fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+		self.functions = nodes
+			.iter()
+			.filter_map(|node| match node {
+				Node::Function(function) => Some((function.prototype.name.value, inlinable(function)?)),
+				_ => None,
+			})
+			.filter(|(_, function)| function.size() <= self.threshold)
+			.collect();
+		nodes.into_iter().map(|node| self.fold_node(node)).collect()
+	}
+
+	fn fold_instruction(&mut self, instruction: Instruction) -> Block {
+		let mut instructions = fold::walk_instruction(self, instruction);
+		let Some(instruction) = instructions.pop() else {
+			return instructions;
+		};
+		let (mut block, instruction) = match instruction {
+			Instruction::Statement(Statement::VariableDeclaration(mut declaration)) => {
+				let Some((block, value)) = self.expand(&declaration.value) else {
+					return vec![Instruction::Statement(Statement::VariableDeclaration(declaration))];
+				};
+				declaration.value = value;
+				(block, Some(Instruction::Statement(Statement::VariableDeclaration(declaration))))
+			},
+			Instruction::Statement(Statement::Assignment(mut assignment))
+				if matches!(assignment.target, LValue::Variable(_)) =>
+			{
+				let Some((block, value)) = self.expand(&assignment.value) else {
+					return vec![Instruction::Statement(Statement::Assignment(assignment))];
+				};
+				assignment.value = value;
+				(block, Some(Instruction::Statement(Statement::Assignment(assignment))))
+			},
+			Instruction::Statement(Statement::Return(Some(value), position)) => {
+				let Some((block, value)) = self.expand(&value) else {
+					return vec![Instruction::Statement(Statement::Return(Some(value), position))];
+				};
+				(block, Some(Instruction::Statement(Statement::Return(Some(value), position))))
+			},
+			Instruction::Expression(expression) => {
+				let Some((block, value)) = self.expand(&expression) else {
+					return vec![Instruction::Expression(expression)];
+				};
+				// The value of an expression on its own is not used
+				(block, (!is_pure(&value)).then_some(Instruction::Expression(value)))
+			},
+			instruction => return vec![instruction],
+		};
+		block.extend(instruction);
+		block
+	}
+
+	fn fold_expression(&mut self, expression: Expression) -> Expression {
+		let expression = fold::walk_expression(self, expression);
+		let Expression::FunctionCall(call) = &expression else {
+			return expression;
+		};
+		let Some(function) = self.functions.get(&call.name.value) else {
+			return expression;
+		};
+		if !function.locals.is_empty() || !call.params.iter().all(is_pure) {
+			return expression;
+		}
+		let mut substitution = Substitution(
+			function.params.iter().map(|param| param.name.value).zip(call.params.iter().cloned()).collect(),
+		);
+		substitution.fold_expression(function.value.clone())
+	}
+}
+
+impl Inlinable {
+	/// The number of expressions and declarations, which is compared to the threshold.
+	fn size(&self) -> usize {
+		let mut size = Size(self.locals.len());
+		for local in &self.locals {
+			size.visit_expression(&local.value);
+		}
+		size.visit_expression(&self.value);
+		size.0
+	}
+}
+
+/// The body of the `function` to inline, if it only consists of declarations and a `return` with a value, and the
+/// function does not call itself.
+fn inlinable(function: &FunctionDefinition) -> Option<Inlinable> {
+	let mut locals = Vec::new();
+	let mut instructions = function.body.iter().filter(|instruction| !matches!(instruction, Instruction::Comment(_)));
+	let value = loop {
+		match instructions.next()? {
+			Instruction::Statement(Statement::VariableDeclaration(declaration)) => locals.push((**declaration).clone()),
+			Instruction::Statement(Statement::ConstDeclaration(declaration)) => {
+				locals.push((**declaration).clone().into())
+			},
+			Instruction::Statement(Statement::Return(Some(value), _)) => break value.clone(),
+			_ => return None,
+		}
+	};
+	// The type of `null` is only known from the return type of the function
+	if instructions.next().is_some() || matches!(value, Expression::Null(_)) {
+		return None;
+	}
+	let mut calls = Calls { function: &function.prototype.name.value, recursive: false };
+	for local in &locals {
+		calls.visit_expression(&local.value);
+	}
+	calls.visit_expression(&value);
+	if calls.recursive {
+		return None;
+	}
+	Some(Inlinable { params: function.prototype.args.clone(), locals, value })
+}
+
+/// Replaces variables by expressions, like parameters by arguments.
+#[derive(Debug, Default)]
+struct Substitution(HashMap<Symbol, Expression>);
+
+impl Substitution {
+	/// Renames the variable of the `declaration` by adding the `prefix`, and replaces it in the following
+	/// expressions.
+	fn declare(&mut self, prefix: &str, mut declaration: VariableDeclaration) -> VariableDeclaration {
+		let name = declaration.name.value;
+		declaration.name.value = Symbol::from(format!("{}{}", prefix, name));
+		self.0.insert(name, Expression::Variable(declaration.name.clone()));
+		declaration
+	}
+}
+
+impl Fold for Substitution {
+	fn fold_expression(&mut self, expression: Expression) -> Expression {
+		match expression {
+			Expression::Variable(variable) => match self.0.get(&variable.value) {
+				Some(substitute) => substitute.clone(),
+				None => Expression::Variable(variable),
+			},
+			expression => fold::walk_expression(self, expression),
+		}
+	}
+}
+
+/// Counts the expressions.
+struct Size(usize);
+
+impl Visitor for Size {
+	fn visit_expression(&mut self, expression: &Expression) {
+		self.0 += 1;
+		visit::walk_expression(self, expression);
+	}
+}
+
+/// Finds calls of the `function`.
+struct Calls<'a> {
+	function: &'a str,
+	recursive: bool,
+}
+
+impl Visitor for Calls<'_> {
+	fn visit_expression(&mut self, expression: &Expression) {
+		if let Expression::FunctionCall(FunctionCall { name, .. }) = expression {
+			self.recursive |= name.value == self.function;
+		}
+		visit::walk_expression(self, expression);
+	}
+}
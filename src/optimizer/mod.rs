@@ -0,0 +1,48 @@
+//! Optimizations of the [AST](crate::ast) of a program after the [semantic analysis](crate::semantic_analyzer), before
+//! it is emitted.
+//!
+//! Each optimization is a [`Fold`](crate::ast::fold::Fold), which rewrites the nodes without changing what the program
+//! does. The optimized AST is not meant to be shown to the user, e.g. its positions may overlap.
+
+mod constant_folding;
+mod dead_code_elimination;
+mod inlining;
+#[cfg(test)]
+mod test;
+
+pub use constant_folding::ConstantFolding;
+pub use dead_code_elimination::DeadCodeElimination;
+pub use inlining::Inlining;
+
+use crate::ast::{
+	expression::{BinaryOperator, UnaryOperator},
+	Expression,
+};
+
+/// Whether evaluating the `expression` has no effects besides producing its value. Function calls may have effects,
+/// and divisions, indexing, dereferences and field accesses through pointers may fail at runtime.
+fn is_pure(expression: &Expression) -> bool {
+	match expression {
+		Expression::Number(_)
+		| Expression::Bool(_)
+		| Expression::StringLiteral(_)
+		| Expression::Char(_)
+		| Expression::Null(_)
+		| Expression::Variable(_) => true,
+		Expression::BinaryExpression(binary_expression) => {
+			binary_expression.operator.value != BinaryOperator::Divide
+				&& is_pure(&binary_expression.lhs)
+				&& is_pure(&binary_expression.rhs)
+		},
+		Expression::UnaryExpression(unary_expression) => {
+			unary_expression.operator.value != UnaryOperator::Dereference && is_pure(&unary_expression.operand)
+		},
+		Expression::Cast(cast) => is_pure(&cast.expression),
+		Expression::StructLiteral(struct_literal) => struct_literal.fields.iter().all(|field| is_pure(&field.value)),
+		Expression::ArrayLiteral(array_literal) => array_literal.elements.iter().all(is_pure),
+		Expression::If(if_expression) => {
+			is_pure(&if_expression.condition) && is_pure(&if_expression.if_true) && is_pure(&if_expression.if_false)
+		},
+		Expression::FunctionCall(_) | Expression::FieldAccess(_) | Expression::IndexExpression(_) => false,
+	}
+}
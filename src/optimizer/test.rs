@@ -0,0 +1,282 @@
+use crate::{
+	ast::fold::Fold,
+	emitter,
+	optimizer::{ConstantFolding, DeadCodeElimination, Inlining},
+	parser, Emitter,
+};
+
+/// Parses the `source_code`, optimizes it with the `passes` in order and formats the result.
+fn optimize(passes: &mut [&mut dyn Fold], source_code: &str) -> String {
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	for pass in passes {
+		ast_nodes = pass.fold_nodes(ast_nodes);
+	}
+	let mut formatted = Vec::new();
+	emitter::Ftl::codegen(ast_nodes.into_iter(), &mut formatted).unwrap();
+	String::from_utf8(formatted).unwrap()
+}
+
+/// Tests that arithmetic on literals is evaluated with the precedence of the operators.
+#[test]
+fn test_fold_arithmetic() {
+	let source_code = "
+def main(): int {
+	var f = (1.5 * 2.0 - 0.5)
+	return 1 + 2 * 3
+}";
+	let expected = concat!("def main(): int {\n", "\tvar f = 2.5\n", "\treturn 7\n", "\n", "}\n",);
+	assert_eq!(optimize(&mut [&mut ConstantFolding], source_code), expected);
+}
+
+/// Tests that comparisons in conditions are folded to booleans.
+#[test]
+fn test_fold_conditions() {
+	let source_code = "
+def main() {
+	if 1 < 2 {
+		print(1)
+	}
+	while 'a' = 'b' or !true {
+		print(2)
+	}
+}";
+	let expected = concat!(
+		"def main() {\n",
+		"\tif (true) {\n",
+		"\t\tprint(1)\n",
+		"\t}\n",
+		"\twhile (false) {\n",
+		"\t\tprint(2)\n",
+		"\t}\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut ConstantFolding], source_code), expected);
+}
+
+/// Tests that only the parts of expressions with literal operands are folded, and that failing operations are kept.
+#[test]
+fn test_keep_non_literals() {
+	let source_code = "
+def main(x: int): int {
+	print(-(2 + 3))
+	x = x + 1 + 2
+	x = x * (2 + 3)
+	return 1 / 0
+}";
+	let expected = concat!(
+		"def main(x: int): int {\n",
+		"\tprint(-5)\n",
+		"\tx = x + 1 + 2\n",
+		"\tx = x * 5\n",
+		"\treturn 1 / 0\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut ConstantFolding], source_code), expected);
+}
+
+/// Tests that branches which are never taken and loops which are never executed are removed.
+#[test]
+fn test_remove_branches() {
+	let source_code = "
+def main() {
+	if 1 > 2 {
+		print(1)
+	} else {
+		print(2)
+	}
+	if true {
+		print(3)
+	}
+	if false {
+		print(4)
+	}
+	while 1 = 2 {
+		print(5)
+	}
+}";
+	let expected = concat!("def main() {\n", "\tprint(2)\n", "\tprint(3)\n", "\n", "}\n",);
+	assert_eq!(optimize(&mut [&mut ConstantFolding, &mut DeadCodeElimination::default()], source_code), expected);
+}
+
+/// Tests that a branch declaring variables is kept in an `if true`, so that its variables stay in their own scope.
+#[test]
+fn test_keep_branch_scope() {
+	let source_code = "
+def main() {
+	if false {
+		print(1)
+	} else {
+		var x = 2
+		print(x)
+	}
+	var x = 3
+	print(x)
+}";
+	let expected = concat!(
+		"def main() {\n",
+		"\tif (true) {\n",
+		"\t\tvar x = 2\n",
+		"\t\tprint(x)\n",
+		"\t}\n",
+		"\tvar x = 3\n",
+		"\tprint(x)\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut DeadCodeElimination::default()], source_code), expected);
+}
+
+/// Tests that unreferenced variables and constants are removed, unless computing their value may have effects.
+#[test]
+fn test_remove_unreferenced_variables() {
+	let source_code = "
+def main(a: int) {
+	var unused = (a + 1)
+	const unused_constant = 42
+	var assigned = 1
+	assigned = 2
+	var called = f()
+	var divided = (a / 0)
+	var read = 3
+	print(read)
+}";
+	let expected = concat!(
+		"def main(a: int) {\n",
+		"\tvar assigned = 1\n",
+		"\tassigned = 2\n",
+		"\tvar called = f()\n",
+		"\tvar divided = (a / 0)\n",
+		"\tvar read = 3\n",
+		"\tprint(read)\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut DeadCodeElimination::default()], source_code), expected);
+}
+
+/// Tests that calls with arguments without effects are replaced by the returned value in any expression.
+#[test]
+fn test_inline_expression() {
+	let source_code = "
+struct Point {
+	x: int
+}
+def x(p: Point): int {
+	return p.x
+}
+def square(a: int): int {
+	return a * a
+}
+def main(p: Point): int {
+	print((square(2) + 1))
+	return square(x(p))
+}";
+	let expected = concat!(
+		"struct Point {\n",
+		"\tx: int\n",
+		"}\n",
+		"def x(p: Point): int {\n",
+		"\treturn p.x\n",
+		"\n",
+		"}\n",
+		"def square(a: int): int {\n",
+		"\treturn a * a\n",
+		"\n",
+		"}\n",
+		"def main(p: Point): int {\n",
+		"\tprint((2 * 2 + 1))\n",
+		"\tvar _inline1_a: int = p.x\n",
+		"\treturn _inline1_a * _inline1_a\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut Inlining::default()], source_code), expected);
+}
+
+/// Tests that the parameters and local variables are declared with unique names if the arguments have effects.
+#[test]
+fn test_inline_declarations() {
+	let source_code = "
+def norm(a: int, b: int): int {
+	const two = 2
+	var sum = (a + b)
+	return sum * two
+}
+def main(): int {
+	var x = norm(read(), 1)
+	x = norm(x, x)
+	return norm(read(), x)
+}";
+	let expected = concat!(
+		"def norm(a: int, b: int): int {\n",
+		"\tconst two = 2\n",
+		"\tvar sum = (a + b)\n",
+		"\treturn sum * two\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\tvar _inline1_a: int = read()\n",
+		"\tvar _inline1_b: int = 1\n",
+		"\tvar _inline1_two = 2\n",
+		"\tvar _inline1_sum = (_inline1_a + _inline1_b)\n",
+		"\tvar x = (_inline1_sum * _inline1_two)\n",
+		"\tvar _inline2_a: int = x\n",
+		"\tvar _inline2_b: int = x\n",
+		"\tvar _inline2_two = 2\n",
+		"\tvar _inline2_sum = (_inline2_a + _inline2_b)\n",
+		"\tx = _inline2_sum * _inline2_two\n",
+		"\tvar _inline3_a: int = read()\n",
+		"\tvar _inline3_b: int = x\n",
+		"\tvar _inline3_two = 2\n",
+		"\tvar _inline3_sum = (_inline3_a + _inline3_b)\n",
+		"\treturn _inline3_sum * _inline3_two\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut Inlining::default()], source_code), expected);
+}
+
+/// Tests that recursive functions, functions with control flow and functions above the threshold are not inlined.
+#[test]
+fn test_keep_calls() {
+	let source_code = "
+def factorial(n: int): int {
+	return n * factorial((n - 1))
+}
+def abs(a: int): int {
+	if a < 0 {
+		return -a
+	}
+	return a
+}
+def large(a: int): int {
+	return a + a + a
+}
+def main(): int {
+	return factorial(abs(large(3)))
+}";
+	let expected = concat!(
+		"def factorial(n: int): int {\n",
+		"\treturn n * factorial((n - 1))\n",
+		"\n",
+		"}\n",
+		"def abs(a: int): int {\n",
+		"\tif (a < 0) {\n",
+		"\t\treturn -a\n",
+		"\t}\n",
+		"\treturn a\n",
+		"\n",
+		"}\n",
+		"def large(a: int): int {\n",
+		"\treturn a + a + a\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\treturn factorial(abs(large(3)))\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&mut [&mut Inlining::new(4)], source_code), expected);
+}
@@ -2,21 +2,111 @@ use std::iter::Peekable;
 
 use super::Result;
 use crate::{
-	ast::Instruction,
-	parser::{helper, instruction::parse_instruction},
+	ast::{Instruction, Statement},
+	error::DiagnosticSink,
+	parser::{helper, instruction::parse_instruction, Error},
+	source::SourcePositionRange,
 	token::{Token, TokenKind},
 };
 
-pub fn parse_block(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<Vec<Instruction>> {
+/// Parses a block enclosed in curly braces and returns its instructions with the position of the closing `}`. `in_loop`
+/// tells whether the block is part of a loop body, which allows `break` and `continue` in it.
+///
+/// An invalid instruction does not abort the block. Instead, its error is collected and parsing continues with the
+/// next instruction, so that all errors of the block are reported as [`Error::Multiple`]. In this case, the block is
+/// consumed up to its closing `}`, unless a top-level keyword shows that the `}` is missing.
+///
+/// Instructions after a `return`, `break` or `continue` are never executed, which is reported as a warning to
+/// `diagnostics`.
+pub fn parse_block(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+	in_loop: bool,
+) -> Result<(Vec<Instruction>, SourcePositionRange)> {
+	let _nesting = helper::Nesting::enter(tokens.peek())?;
 	let mut block: Vec<Instruction> = Vec::new();
-	helper::parse_opening_curly_parenthesis(tokens.next())?;
+	let mut errors = Vec::new();
+	// Whether the block was already left by a `return`, `break` or `continue`
+	let mut left = false;
+	// Position of the first instruction after leaving the block, from which on the block is never executed
+	let mut unreachable: Option<SourcePositionRange> = None;
+	let mut end = helper::parse_opening_curly_parenthesis(tokens.next())?;
+	loop {
+		match tokens.peek() {
+			Some(Token { value: TokenKind::ClosingCurlyBraces, position }) => {
+				if let Some(unreachable) = unreachable.take() {
+					warn_unreachable(diagnostics, unreachable, position.position.start.offset);
+				}
+				end = position.clone();
+				tokens.next(); // Consume TokenKind::ClosingParentheses
+				break; // End of block
+			},
+			// The next top-level node starts, or the tokens end, without the block being closed
+			token @ (Some(Token {
+				value: TokenKind::Def | TokenKind::Extern | TokenKind::Struct | TokenKind::Enum | TokenKind::Type,
+				..
+			})
+			| None) => {
+				errors.push(Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, found: token.cloned() });
+				break;
+			},
+			Some(token) => {
+				if left && unreachable.is_none() && !matches!(token.value, TokenKind::Comment(_)) {
+					unreachable = Some(token.position.clone());
+				}
+				match parse_instruction(tokens, diagnostics, in_loop) {
+					Ok(instruction) => {
+						left |= matches!(
+							instruction,
+							Instruction::Statement(
+								Statement::Return(..) | Statement::Break(_) | Statement::Continue(_)
+							)
+						);
+						block.push(instruction)
+					},
+					Err(error) => {
+						synchronize(tokens, &error);
+						errors.push(error);
+					},
+				}
+			},
+		}
+	}
+	if !errors.is_empty() {
+		return Err(Error::Multiple(errors));
+	}
+	Ok((block, end))
+}
+
+/// Warns that the instructions from the `unreachable` position up to the `}` at `end_offset` are never executed.
+fn warn_unreachable(diagnostics: &DiagnosticSink, mut unreachable: SourcePositionRange, end_offset: usize) {
+	// The unreachable code ends at the last char before the `}`, which is not whitespace
+	let start_offset = unreachable.position.start.offset;
+	let text = &unreachable.source.text;
+	let last_offset = (start_offset..end_offset).rev().find(|&offset| !text[offset].is_whitespace());
+	unreachable.position.end = unreachable.source.position(last_offset.unwrap_or(start_offset));
+	diagnostics.warn("W0020", "Unreachable code", Some(&unreachable));
+}
+
+/// Skips the rest of the instruction that caused the `error`, i.e. all tokens up to the end of its line, including
+/// nested blocks starting in this line, but not the `}` closing the current block.
+///
+/// Nothing is skipped for [multiple errors](Error::Multiple), because these come from a block that was already
+/// consumed completely.
+fn synchronize(tokens: &mut Peekable<impl Iterator<Item = Token>>, error: &Error) {
+	let Some(line) = error.token().map(|token| token.position.position.end.line) else {
+		return;
+	};
+	let mut depth = 0usize;
 	while let Some(token) = tokens.peek() {
-		if let TokenKind::ClosingCurlyBraces = **token {
-			tokens.next(); // Consume TokenKind::ClosingParentheses
-			break; // End of block
+		match token.value {
+			TokenKind::ClosingCurlyBraces if depth == 0 => break,
+			TokenKind::Def | TokenKind::Extern | TokenKind::Struct | TokenKind::Enum | TokenKind::Type => break,
+			_ if depth == 0 && token.position.position.start.line > line => break,
+			TokenKind::OpeningCurlyBraces => depth += 1,
+			TokenKind::ClosingCurlyBraces => depth -= 1,
+			_ => (),
 		}
-		let instruction = parse_instruction(tokens)?;
-		block.push(instruction);
+		tokens.next();
 	}
-	Ok(block)
 }
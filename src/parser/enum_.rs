@@ -0,0 +1,32 @@
+use std::iter::Peekable;
+
+use super::Result;
+use crate::{
+	ast::{self, enum_::Variant},
+	parser::helper,
+	token::{Token, TokenKind},
+};
+
+/// Parses an enum definition like `enum Color { Red, Green, Blue }`. The variants are separated by commas, and the last
+/// one may be followed by a comma, too.
+pub(crate) fn parse_enum_definition(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Enum> {
+	let enum_ = helper::parse_enum(tokens.next())?;
+	let name = helper::parse_identifier(tokens.next())?;
+	helper::parse_opening_curly_parenthesis(tokens.next())?;
+	let mut variants = Vec::new();
+	let end = loop {
+		let comment = match tokens.peek() {
+			Some(Token { value: TokenKind::Comment(_), .. }) => Some(helper::parse_comment(tokens.next())?),
+			_ => None,
+		};
+		if comment.is_none() && tokens.peek().is_some_and(|token| token.value == TokenKind::ClosingCurlyBraces) {
+			break helper::parse_closing_curly_parenthesis(tokens.next())?;
+		}
+		let name = helper::parse_identifier(tokens.next())?;
+		variants.push(Variant { name, comment });
+		if tokens.next_if(|token| token.value == TokenKind::Comma).is_none() {
+			break helper::parse_closing_curly_parenthesis(tokens.next())?;
+		}
+	};
+	Ok(ast::Enum { name, variants, position: enum_.until(&end) })
+}
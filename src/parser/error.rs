@@ -2,13 +2,54 @@ use core::fmt;
 
 use thiserror::Error;
 
-use crate::token::{Token, TokenKind};
+use crate::{
+	lexer,
+	token::{Token, TokenKind},
+};
 
 #[derive(Debug, Clone, PartialEq, Error)]
 pub enum Error {
-	ExpectedToken { expected: TokenKind, found: Option<Token> },
+	ExpectedToken {
+		expected: TokenKind,
+		found: Option<Token>,
+	},
 
-	IllegalToken { token: Option<Token>, context: &'static str },
+	IllegalToken {
+		token: Option<Token>,
+		context: &'static str,
+	},
+
+	/// Expressions or blocks are nested deeper than [`MAX_NESTING_DEPTH`](super::helper::MAX_NESTING_DEPTH).
+	NestingTooDeep {
+		token: Option<Token>,
+	},
+
+	/// The source code could not be lexed, so there are no tokens to parse.
+	Lexer(lexer::Error),
+
+	/// Several errors the parser recovered from, in the order they occurred.
+	Multiple(Vec<Error>),
+}
+
+impl Error {
+	/// The token at which the error occurred, or [`None`] if the end of the tokens was reached, if it is a
+	/// [lexer error](Error::Lexer) or if these are [multiple errors](Error::Multiple).
+	pub(crate) fn token(&self) -> Option<&Token> {
+		match self {
+			Error::ExpectedToken { found: token, .. }
+			| Error::IllegalToken { token, .. }
+			| Error::NestingTooDeep { token } => token.as_ref(),
+			Error::Lexer(_) | Error::Multiple(_) => None,
+		}
+	}
+
+	/// Splits [multiple errors](Error::Multiple), also nested ones, into the single errors.
+	pub fn into_errors(self) -> Vec<Error> {
+		match self {
+			Error::Multiple(errors) => errors.into_iter().flat_map(Error::into_errors).collect(),
+			error => vec![error],
+		}
+	}
 }
 
 impl fmt::Display for Error {
@@ -22,6 +63,20 @@ impl fmt::Display for Error {
 				Some(token) => write!(f, "{} Illegal token '{:?}' in {}", token.position, token.value, context),
 				None => write!(f, "Illegal token in {}", context),
 			},
+			Error::NestingTooDeep { token } => match token {
+				Some(token) => write!(f, "{} Expressions or blocks are nested too deeply", token.position),
+				None => write!(f, "Expressions or blocks are nested too deeply"),
+			},
+			Error::Lexer(error) => write!(f, "{}", error),
+			Error::Multiple(errors) => {
+				for (i, error) in errors.iter().enumerate() {
+					if i != 0 {
+						writeln!(f)?;
+					}
+					write!(f, "{}", error)?;
+				}
+				Ok(())
+			},
 		}
 	}
 }
@@ -4,21 +4,203 @@ use super::Result;
 use crate::{
 	ast,
 	ast::{
-		expression::{BinaryOperator, NumberKind},
+		expression::{BinaryOperator, NumberKind, UnaryOperator},
 		Expression,
 	},
-	parser::{function::parse_function_call, helper, helper::parse_operator, Error},
-	source::PositionContainer,
+	module,
+	parser::{function::parse_function_call, helper, helper::binary_operator, variable, Error},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 	token::{Token, TokenKind},
 };
 
 pub(crate) fn parse_primary_expression(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
-	match tokens.peek() {
-		Some(Token { value: TokenKind::Identifier(_), .. }) => Ok(parse_identifier_expression(tokens)?),
+	parse_primary(tokens, true)
+}
+
+/// Parses a primary expression, including the casts like `as float` following it. If `struct_literals` is false, an
+/// identifier followed by `{` is not parsed as a struct literal, because the `{` starts the block of an `if`, `while`
+/// or `for` instead.
+fn parse_primary(tokens: &mut Peekable<impl Iterator<Item = Token>>, struct_literals: bool) -> Result<ast::Expression> {
+	let mut expression = parse_operand(tokens, struct_literals)?;
+	while tokens.next_if(|token| token.value == TokenKind::As).is_some() {
+		let data_type = variable::parse_data_type(tokens)?;
+		expression = ast::Expression::Cast(ast::expression::Cast { expression: Box::new(expression), data_type });
+	}
+	Ok(expression)
+}
+
+/// Parses a primary expression without the casts following it, which is also the operand of a unary operator.
+fn parse_operand(tokens: &mut Peekable<impl Iterator<Item = Token>>, struct_literals: bool) -> Result<ast::Expression> {
+	let _nesting = helper::Nesting::enter(tokens.peek())?;
+	let expression = match tokens.peek() {
+		Some(Token { value: TokenKind::Identifier(_), .. }) => {
+			Ok(parse_identifier_expression(tokens, struct_literals)?)
+		},
 		Some(Token { value: TokenKind::Float(_), .. }) => Ok(ast::Expression::Number(parse_float(tokens)?)),
 		Some(Token { value: TokenKind::Int(_), .. }) => Ok(ast::Expression::Number(parse_int(tokens)?)),
+		Some(Token { value: TokenKind::True | TokenKind::False, .. }) => Ok(ast::Expression::Bool(parse_bool(tokens)?)),
+		Some(Token { value: TokenKind::StringLiteral(_), .. }) => {
+			Ok(ast::Expression::StringLiteral(parse_string_literal(tokens)?))
+		},
+		Some(Token { value: TokenKind::Char(_), .. }) => Ok(ast::Expression::Char(parse_char(tokens)?)),
+		Some(Token { value: TokenKind::Null, .. }) => Ok(ast::Expression::Null(tokens.next().unwrap().position)),
 		Some(Token { value: TokenKind::OpeningParentheses, .. }) => Ok(parse_parentheses(tokens)?),
+		Some(Token { value: TokenKind::OpeningSquareBrackets, .. }) => {
+			Ok(ast::Expression::ArrayLiteral(parse_array_literal(tokens)?))
+		},
+		Some(Token { value: TokenKind::Minus | TokenKind::Not | TokenKind::Star | TokenKind::Power, .. }) => {
+			Ok(ast::Expression::UnaryExpression(parse_unary_expression(tokens, struct_literals)?))
+		},
+		// Field accesses and indexing are not applied to an if-expression, as they read like part of its else branch
+		Some(Token { value: TokenKind::If, .. }) => return Ok(ast::Expression::If(parse_if_expression(tokens)?)),
 		other => Err(Error::IllegalToken { token: other.cloned(), context: "expression" }),
+	}?;
+	parse_postfix_expressions(expression, tokens)
+}
+
+/// Parses the field accesses like `.x`, method calls like `.length()` and indexing like `[i]` following `base`, if
+/// any.
+pub(super) fn parse_postfix_expressions(
+	mut base: ast::Expression,
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<ast::Expression> {
+	loop {
+		base = match tokens.peek() {
+			Some(Token { value: TokenKind::Dot, .. }) => {
+				tokens.next(); // Consume the dot
+				let field = helper::parse_identifier(tokens.next())?;
+				match tokens.peek() {
+					Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
+						ast::Expression::FunctionCall(parse_method_call(tokens, base, field)?)
+					},
+					_ => ast::Expression::FieldAccess(ast::expression::FieldAccess { base: Box::new(base), field }),
+				}
+			},
+			Some(Token { value: TokenKind::OpeningSquareBrackets, .. }) => {
+				let index = parse_index(tokens)?;
+				ast::Expression::IndexExpression(ast::expression::IndexExpression {
+					base: Box::new(base),
+					index: Box::new(index),
+				})
+			},
+			_ => return Ok(base),
+		}
+	}
+}
+
+/// Parses the parameters of a call of the `method` of the `receiver`.
+pub(super) fn parse_method_call(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	receiver: ast::Expression,
+	method: PositionContainer<Symbol>,
+) -> Result<ast::expression::FunctionCall> {
+	let mut method_call = parse_function_call(tokens, method)?;
+	method_call.position = receiver.source_position().until(&method_call.position);
+	method_call.receiver = Some(Box::new(receiver));
+	Ok(method_call)
+}
+
+/// Parses an if-expression like `if a < b { a } else { b }`. Both branches are single expressions and the else branch
+/// is required, so that the if-expression always has a value. `else if` continues the chain with the next
+/// if-expression.
+fn parse_if_expression(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::expression::IfExpression> {
+	let if_ = helper::parse_if(tokens.next())?;
+	let condition = parse_condition(tokens)?;
+	let (if_true, _) = parse_if_expression_branch(tokens)?;
+	helper::parse_else(tokens.next())?;
+	let (if_false, end) = match tokens.peek() {
+		Some(Token { value: TokenKind::If, .. }) => {
+			let _nesting = helper::Nesting::enter(tokens.peek())?;
+			let if_expression = parse_if_expression(tokens)?;
+			let end = if_expression.position.clone();
+			(ast::Expression::If(if_expression), end)
+		},
+		_ => parse_if_expression_branch(tokens)?,
+	};
+	Ok(ast::expression::IfExpression {
+		condition: Box::new(condition),
+		if_true: Box::new(if_true),
+		if_false: Box::new(if_false),
+		position: if_.until(&end),
+	})
+}
+
+/// Parses a branch of an if-expression like `{ a }` and returns its expression and the position of the closing curly
+/// brace.
+fn parse_if_expression_branch(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<(ast::Expression, SourcePositionRange)> {
+	helper::parse_opening_curly_parenthesis(tokens.next())?;
+	let expression = parse_binary_expression(tokens)?;
+	let end = helper::parse_closing_curly_parenthesis(tokens.next())?;
+	Ok((expression, end))
+}
+
+/// Parses an index enclosed in square brackets like `[i + 1]`.
+pub fn parse_index(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
+	helper::parse_opening_square_bracket(tokens.next())?;
+	let index = parse_binary_expression(tokens)?;
+	helper::parse_closing_square_bracket(tokens.next())?;
+	Ok(index)
+}
+
+/// Parses the comma separated elements of an array literal like `[1, 2, 3]`.
+fn parse_array_literal(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::expression::ArrayLiteral> {
+	let mut position = match tokens.next() {
+		Some(Token { value: TokenKind::OpeningSquareBrackets, position }) => position,
+		other => return Err(Error::ExpectedToken { expected: TokenKind::OpeningSquareBrackets, found: other }),
+	};
+	let mut elements = Vec::new();
+	loop {
+		if let Some(closing) = tokens.next_if(|token| token.value == TokenKind::ClosingSquareBrackets) {
+			position.position.end = closing.position.position.end;
+			break;
+		}
+		elements.push(parse_binary_expression(tokens)?);
+		match tokens.peek() {
+			Some(Token { value: TokenKind::Comma, .. }) => {
+				tokens.next(); // Consume the comma
+			},
+			// No comma after this element, so the array has to end here
+			Some(Token { value: TokenKind::ClosingSquareBrackets, .. }) => (),
+			other => {
+				return Err(Error::ExpectedToken { expected: TokenKind::ClosingSquareBrackets, found: other.cloned() })
+			},
+		}
+	}
+	Ok(ast::expression::ArrayLiteral { elements, position })
+}
+
+/// Parses a unary operator and its operand. The operand is a primary expression without casts, so that unary operators
+/// bind stronger than casts and binary operators.
+pub fn parse_unary_expression(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	struct_literals: bool,
+) -> Result<ast::expression::UnaryExpression> {
+	let operator = match tokens.peek() {
+		Some(Token { value: TokenKind::Star | TokenKind::Power, .. }) => {
+			PositionContainer::new(UnaryOperator::Dereference, parse_dereference(tokens)?)
+		},
+		_ => helper::parse_unary_operator(tokens.next())?,
+	};
+	let operand = parse_operand(tokens, struct_literals)?;
+	Ok(ast::expression::UnaryExpression { operator, operand: Box::new(operand) })
+}
+
+/// Parses the `*` of a dereference and returns its position.
+pub(super) fn parse_dereference(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<SourcePositionRange> {
+	match tokens.peek_mut() {
+		// A double dereference like `**p` is lexed as a power, whose second half stays in the tokens
+		Some(token) if token.value == TokenKind::Power => {
+			let mut star = token.position.clone();
+			star.position.end = star.position.start;
+			token.value = TokenKind::Star;
+			token.position.position.start = token.position.position.end;
+			Ok(star)
+		},
+		Some(Token { value: TokenKind::Star, .. }) => Ok(tokens.next().expect("peeked TokenKind::Star").position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Star, found: tokens.next() }),
 	}
 }
 
@@ -43,16 +225,91 @@ pub fn parse_int(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<P
 	}
 }
 
-pub fn parse_identifier_expression(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
-	let identifier = helper::parse_identifier(tokens.next())?;
+pub fn parse_bool(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<bool>> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::True, position }) => Ok(PositionContainer::new(true, position)),
+		Some(Token { value: TokenKind::False, position }) => Ok(PositionContainer::new(false, position)),
+		other => Err(Error::ExpectedToken { expected: TokenKind::True, found: other }),
+	}
+}
+
+pub fn parse_string_literal(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<String>> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::StringLiteral(string), position }) => {
+			Ok(PositionContainer::new(string, position))
+		},
+		other => Err(Error::ExpectedToken { expected: TokenKind::StringLiteral(String::new()), found: other }),
+	}
+}
+
+pub fn parse_char(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<char>> {
+	match tokens.next() {
+		Some(Token { value: TokenKind::Char(char), position }) => Ok(PositionContainer::new(char, position)),
+		other => Err(Error::ExpectedToken { expected: TokenKind::Char('a'), found: other }),
+	}
+}
+
+pub fn parse_identifier_expression(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	struct_literals: bool,
+) -> Result<ast::Expression> {
+	let mut identifier = helper::parse_identifier(tokens.next())?;
+	if tokens.next_if(|token| token.value == TokenKind::Dot).is_some() {
+		let member = helper::parse_identifier(tokens.next())?;
+		match tokens.peek() {
+			// A call or struct literal like `math.square(3)` refers to a member of an imported module
+			Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
+				identifier = module::qualify(identifier, member)
+			},
+			Some(Token { value: TokenKind::OpeningCurlyBraces, .. }) if struct_literals => {
+				identifier = module::qualify(identifier, member)
+			},
+			// Otherwise, this is the access of a field, whose following postfix expressions are parsed by the caller
+			_ => {
+				let base = Box::new(ast::Expression::Variable(identifier));
+				return Ok(ast::Expression::FieldAccess(ast::expression::FieldAccess { base, field: member }));
+			},
+		}
+	}
 	match tokens.peek() {
 		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
 			Ok(ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?))
 		},
+		Some(Token { value: TokenKind::OpeningCurlyBraces, .. }) if struct_literals => {
+			Ok(ast::Expression::StructLiteral(parse_struct_literal(tokens, identifier)?))
+		},
 		_ => Ok(ast::Expression::Variable(identifier)),
 	}
 }
 
+/// Parses the fields of a struct literal like `{ x: 1, y: 2 }` after the struct name.
+fn parse_struct_literal(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	name: PositionContainer<Symbol>,
+) -> Result<ast::expression::StructLiteral> {
+	helper::parse_opening_curly_parenthesis(tokens.next())?;
+	let mut fields = Vec::new();
+	// Collect all fields until the closing curly brace
+	let end = loop {
+		if let Some(closing) = tokens.next_if(|token| token.value == TokenKind::ClosingCurlyBraces) {
+			break closing.position;
+		}
+		let field_name = helper::parse_identifier(tokens.next())?;
+		helper::parse_colon(tokens.next())?;
+		let value = parse_binary_expression(tokens)?;
+		fields.push(ast::expression::FieldValue { name: field_name, value });
+		match tokens.peek() {
+			Some(Token { value: TokenKind::Comma, .. }) => {
+				tokens.next(); // Consume the comma
+			},
+			// No comma after this field, so this is the last field
+			_ => break helper::parse_closing_curly_parenthesis(tokens.next())?,
+		}
+	};
+	let position = name.position.until(&end);
+	Ok(ast::expression::StructLiteral { name, fields, position })
+}
+
 pub fn parse_parentheses(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::expression::Expression> {
 	helper::parse_opening_parenthesis(tokens.next())?;
 	let expression = parse_binary_expression(tokens)?;
@@ -63,35 +320,68 @@ pub fn parse_parentheses(tokens: &mut Peekable<impl Iterator<Item = Token>>) ->
 pub(crate) fn parse_binary_expression(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
 ) -> Result<ast::expression::Expression> {
-	let lhs = parse_primary_expression(tokens)?;
-	parse_binary_expression_rhs(lhs, None, tokens)
+	let lhs = parse_primary(tokens, true)?;
+	parse_binary_expression_rhs(lhs, None, tokens, true)
+}
+
+/// Parses the condition of an `if`, `while` or `for`, which is directly followed by a block. Struct literals have to
+/// be put in parentheses there, like in `if p = (Point { x: 0 }) {`.
+pub(crate) fn parse_condition(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Expression> {
+	let lhs = parse_primary(tokens, false)?;
+	parse_binary_expression_rhs(lhs, None, tokens, false)
+}
+
+/// The binary operator of the `token` following the `operand`, if any.
+///
+/// A `*` or `**` at the start of a line which is directly followed by its operand, like in `*p = 1`, is no
+/// multiplication or power but starts the next instruction. Wrapped binary expressions have a space after the
+/// operators starting their lines.
+fn operator_after(operand: &Expression, token: Option<&Token>) -> Option<BinaryOperator> {
+	let token = token?;
+	let position = &token.position;
+	let starts_line = position.position.start.line > operand.source_position().position.end.line;
+	let next_char = position.source.text.get(position.position.end.offset + 1);
+	let star = matches!(token.value, TokenKind::Star | TokenKind::Power);
+	if star && starts_line && next_char.is_some_and(|char| !char.is_whitespace()) {
+		return None;
+	}
+	binary_operator(&token.value)
 }
 
 fn parse_binary_expression_rhs(
 	lhs: Expression,
 	min_operator: Option<&BinaryOperator>,
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	struct_literals: bool,
 ) -> Result<ast::expression::Expression> {
 	let mut lhs: ast::Expression = lhs;
 	loop {
 		// Read the operator after lhs and before rhs
-		let operator = match parse_operator(tokens.peek().cloned()) {
-			// Found an operator
-			Ok(operator) => operator,
+		let Some(operator) = operator_after(&lhs, tokens.peek()) else {
 			// No operator found
-			Err(_) => return Ok(lhs),
+			return Ok(lhs);
 		};
+		// The operator binds weaker than the operator of the calling recursion level, so the caller has to attach lhs
+		// as its rhs first. A right-associative operator takes lhs as its own lhs instead.
+		let right_associative = min_operator == Some(&operator) && operator.is_right_associative();
+		if min_operator.is_some_and(|min_operator| operator <= *min_operator) && !right_associative {
+			return Ok(lhs);
+		}
 		// Consume operator
-		tokens.next();
+		let position = tokens.next().expect("peeked operator token").position;
+		let operator = PositionContainer::new(operator, position);
 
 		// Parse the primary expression after the operator as rhs
-		let mut rhs = parse_primary_expression(tokens)?;
+		let mut rhs = parse_primary(tokens, struct_literals)?;
 
-		// Inspect the next operator after rhs. If it has a higher precedence than the current operator,
-		// let rhs be the result of a recursive call to parse_binary_expression_rhs with rhs as lhs.
-		if let Ok(next_operator) = parse_operator(tokens.peek().cloned()) {
-			if next_operator > operator {
-				rhs = parse_binary_expression_rhs(rhs, Some(&next_operator), tokens)?;
+		// Inspect the next operator after rhs. If it has a higher precedence than the current operator, or is the same
+		// right-associative operator, let rhs be the result of a recursive call to parse_binary_expression_rhs with rhs
+		// as lhs.
+		if let Some(next_operator) = operator_after(&rhs, tokens.peek()) {
+			if next_operator > *operator || (next_operator == *operator && operator.is_right_associative()) {
+				// A chain of right-associative operators recurses once per operator
+				let _nesting = helper::Nesting::enter(tokens.peek())?;
+				rhs = parse_binary_expression_rhs(rhs, Some(&operator), tokens, struct_literals)?;
 			}
 		}
 
@@ -102,5 +392,4 @@ fn parse_binary_expression_rhs(
 			operator,
 		});
 	}
-	Ok(lhs)
 }
@@ -4,46 +4,83 @@ use super::Result;
 use crate::{
 	ast,
 	ast::Expression,
+	error::DiagnosticSink,
+	module,
 	parser::{block::parse_block, expression::parse_primary_expression, helper, variable},
-	source::PositionContainer,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 	token::{Token, TokenKind},
 };
 
 pub fn parse_function_definition(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
 ) -> Result<ast::statement::FunctionDefinition> {
-	tokens.next(); // Consume TokenKind::FunctionDefinition
-	let prototype = parse_function_prototype(tokens)?;
-	let body = parse_block(tokens)?;
-	Ok(ast::statement::FunctionDefinition { prototype, body })
+	let def = tokens.next().expect("parse_function_definition called without TokenKind::Def");
+	let mut name = helper::parse_identifier(tokens.next())?;
+	// A method like `Point.length` is named after the struct it belongs to
+	if tokens.next_if(|token| token.value == TokenKind::Dot).is_some() {
+		name = module::qualify(name, helper::parse_identifier(tokens.next())?);
+	}
+	let type_parameters = parse_type_parameters(tokens)?;
+	let prototype = parse_function_prototype(tokens, def.position, name, type_parameters)?;
+	let (body, end) = parse_block(tokens, diagnostics, false)?;
+	let position = prototype.position.until(&end);
+	Ok(ast::statement::FunctionDefinition { prototype, body, position })
 }
 
 pub fn parse_extern_function_declaration(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
 ) -> Result<ast::statement::FunctionPrototype> {
-	tokens.next(); // Consume TokenKind::Extern
-	parse_function_prototype(tokens)
+	let extern_ = tokens.next().expect("parse_extern_function_declaration called without TokenKind::Extern");
+	let name = helper::parse_identifier(tokens.next())?;
+	parse_function_prototype(tokens, extern_.position, name, Vec::new())
 }
 
+/// Parses the type parameters in angle brackets like `<T, U>` after the name of a generic function or struct. Without
+/// angle brackets, there are no type parameters.
+pub(super) fn parse_type_parameters(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<Vec<PositionContainer<Symbol>>> {
+	let mut type_parameters = Vec::new();
+	if tokens.next_if(|token| token.value == TokenKind::Less).is_none() {
+		return Ok(type_parameters);
+	}
+	loop {
+		type_parameters.push(helper::parse_identifier(tokens.next())?);
+		if tokens.next_if(|token| token.value == TokenKind::Comma).is_none() {
+			break;
+		}
+	}
+	helper::parse_greater(tokens.next())?;
+	Ok(type_parameters)
+}
+
+/// Parses the prototype after the `name` of the function, which follows the `def` or `extern` keyword at the position
+/// `start`.
 fn parse_function_prototype(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	start: SourcePositionRange,
+	name: PositionContainer<Symbol>,
+	type_parameters: Vec<PositionContainer<Symbol>>,
 ) -> Result<ast::statement::FunctionPrototype> {
-	let name = helper::parse_identifier(tokens.next())?;
-	let args = parse_function_argument_list(tokens)?;
+	let (args, closing_parenthesis) = parse_function_argument_list(tokens)?;
 	let return_type = parse_function_prototype_return_type(tokens)?;
-	Ok(ast::statement::FunctionPrototype { name, args, return_type })
+	let end = return_type.as_ref().map_or(&closing_parenthesis, |return_type| &return_type.position);
+	let position = start.until(end);
+	Ok(ast::statement::FunctionPrototype { name, type_parameters, args, return_type, position })
 }
 
+/// Parses the arguments in parentheses and returns them with the position of the closing parenthesis.
 fn parse_function_argument_list(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
-) -> Result<Vec<ast::statement::FunctionArgument>> {
+) -> Result<(Vec<ast::statement::FunctionArgument>, SourcePositionRange)> {
 	helper::parse_opening_parenthesis(tokens.next())?;
 	let mut arguments: Vec<ast::statement::FunctionArgument> = Vec::new();
 
 	// Check whether the argument list is empty, i.e. whether the next token is a closing parenthesis
-	if let Some(Token { value: TokenKind::ClosingParentheses, .. }) = tokens.peek() {
-		tokens.next(); // Consume the closing parenthesis
-		return Ok(arguments);
+	if let Some(closing_parenthesis) = tokens.next_if(|token| token.value == TokenKind::ClosingParentheses) {
+		return Ok((arguments, closing_parenthesis.position));
 	}
 
 	// Collect all arguments until closing parentheses
@@ -59,8 +96,8 @@ fn parse_function_argument_list(
 			_ => break, // No comma after this argument, so this is the last argument
 		}
 	}
-	helper::parse_closing_parenthesis(tokens.next())?;
-	Ok(arguments)
+	let closing_parenthesis = helper::parse_closing_parenthesis(tokens.next())?;
+	Ok((arguments, closing_parenthesis))
 }
 
 fn parse_function_prototype_return_type(
@@ -81,19 +118,23 @@ fn parse_function_prototype_return_type(
 
 pub(crate) fn parse_function_call(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
-	identifier: PositionContainer<String>,
+	identifier: PositionContainer<Symbol>,
 ) -> Result<ast::expression::FunctionCall> {
-	Ok(ast::expression::FunctionCall { name: identifier, params: parse_function_parameters(tokens)? })
+	let (params, closing_parenthesis) = parse_function_parameters(tokens)?;
+	let position = identifier.position.until(&closing_parenthesis);
+	Ok(ast::expression::FunctionCall { name: identifier, receiver: None, params, position })
 }
 
-fn parse_function_parameters(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<Vec<Expression>> {
+/// Parses the parameters in parentheses and returns them with the position of the closing parenthesis.
+fn parse_function_parameters(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<(Vec<Expression>, SourcePositionRange)> {
 	helper::parse_opening_parenthesis(tokens.next())?;
 	let mut parameters: Vec<Expression> = Vec::new();
 
 	// Check whether the parameter list is empty, i.e. whether the next token is a closing parenthesis
-	if let Some(Token { value: TokenKind::ClosingParentheses, .. }) = tokens.peek() {
-		tokens.next(); // Consume the closing parenthesis
-		return Ok(parameters);
+	if let Some(closing_parenthesis) = tokens.next_if(|token| token.value == TokenKind::ClosingParentheses) {
+		return Ok((parameters, closing_parenthesis.position));
 	}
 
 	// Collect all parameters until closing parentheses
@@ -108,6 +149,6 @@ fn parse_function_parameters(tokens: &mut Peekable<impl Iterator<Item = Token>>)
 		}
 	}
 
-	helper::parse_closing_parenthesis(tokens.next())?;
-	Ok(parameters)
+	let closing_parenthesis = helper::parse_closing_parenthesis(tokens.next())?;
+	Ok((parameters, closing_parenthesis))
 }
@@ -1,119 +1,257 @@
+use std::cell::Cell;
+
 use super::{Error, Result};
 use crate::{
-	ast::expression::BinaryOperator,
-	source::PositionContainer,
+	ast::expression::{BinaryOperator, UnaryOperator},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 	token::{Token, TokenKind},
 };
 
-pub(crate) fn parse_identifier(token: Option<Token>) -> Result<PositionContainer<String>> {
+pub(crate) fn parse_identifier(token: Option<Token>) -> Result<PositionContainer<Symbol>> {
 	match token {
 		Some(Token { position, value: TokenKind::Identifier(ident) }) => Ok(PositionContainer::new(ident, position)),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: token }),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Identifier(Symbol::intern("")), found: token }),
 	}
 }
 
-pub(crate) fn parse_opening_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::OpeningParentheses) => Ok(()),
+pub(crate) fn parse_opening_parenthesis(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::OpeningParentheses, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::OpeningParentheses, found: token }),
 	}
 }
 
-pub(crate) fn parse_closing_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::ClosingParentheses) => Ok(()),
+pub(crate) fn parse_closing_parenthesis(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::ClosingParentheses, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::ClosingParentheses, found: token }),
 	}
 }
 
-pub(crate) fn parse_colon(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Colon) => Ok(()),
+pub(crate) fn parse_colon(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Colon, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::Colon, found: token }),
 	}
 }
 
-pub(crate) fn parse_comma(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Comma) => Ok(()),
-		_ => Err(Error::ExpectedToken { expected: TokenKind::Comma, found: token }),
-	}
-}
-
-pub(crate) fn parse_semicolon(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Semicolon) => Ok(()),
+pub(crate) fn parse_semicolon(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Semicolon, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::Semicolon, found: token }),
 	}
 }
 
-pub(crate) fn parse_opening_curly_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::OpeningCurlyBraces) => Ok(()),
+pub(crate) fn parse_opening_curly_parenthesis(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::OpeningCurlyBraces, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::OpeningCurlyBraces, found: token }),
 	}
 }
 
-pub(crate) fn parse_closing_curly_parenthesis(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::ClosingCurlyBraces) => Ok(()),
+pub(crate) fn parse_closing_curly_parenthesis(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::ClosingCurlyBraces, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, found: token }),
 	}
 }
 
-pub(crate) fn parse_variable_declaration(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Var) => Ok(()),
+pub(crate) fn parse_opening_square_bracket(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::OpeningSquareBrackets, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::OpeningSquareBrackets, found: token }),
+	}
+}
+
+pub(crate) fn parse_closing_square_bracket(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::ClosingSquareBrackets, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::ClosingSquareBrackets, found: token }),
+	}
+}
+
+pub(crate) fn parse_variable_declaration(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Var, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::Var, found: token }),
 	}
 }
 
-pub(crate) fn parse_equal(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Equal) => Ok(()),
+pub(crate) fn parse_comment(token: Option<Token>) -> Result<PositionContainer<String>> {
+	match token {
+		Some(Token { value: TokenKind::Comment(comment), position }) => Ok(PositionContainer::new(comment, position)),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Comment(String::new()), found: token }),
+	}
+}
+
+pub(crate) fn parse_const_declaration(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Const, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Const, found: token }),
+	}
+}
+
+pub(crate) fn parse_equal(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Equal, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::Equal, found: token }),
 	}
 }
 
-pub(crate) fn parse_if(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::If) => Ok(()),
+/// Parses `=` or a compound assignment operator like `+=`, which yields the [`BinaryOperator`] it combines the
+/// variable and the assigned value with.
+pub(crate) fn parse_assignment_operator(token: Option<Token>) -> Result<Option<PositionContainer<BinaryOperator>>> {
+	let operator = match token.as_deref() {
+		Some(TokenKind::Equal) => return Ok(None),
+		Some(TokenKind::PlusEqual) => BinaryOperator::Add,
+		Some(TokenKind::MinusEqual) => BinaryOperator::Subtract,
+		Some(TokenKind::StarEqual) => BinaryOperator::Multiply,
+		Some(TokenKind::SlashEqual) => BinaryOperator::Divide,
+		_ => return Err(Error::ExpectedToken { expected: TokenKind::Equal, found: token }),
+	};
+	Ok(token.map(|token| PositionContainer::new(operator, token.position)))
+}
+
+pub(crate) fn parse_if(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::If, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::If, found: token }),
 	}
 }
 
-pub(crate) fn parse_struct(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::Struct) => Ok(()),
+pub(crate) fn parse_else(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Else, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Else, found: token }),
+	}
+}
+
+pub(crate) fn parse_match(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Match, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Match, found: token }),
+	}
+}
+
+pub(crate) fn parse_fat_arrow(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::FatArrow, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::FatArrow, found: token }),
+	}
+}
+
+pub(crate) fn parse_import(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Import, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Import, found: token }),
+	}
+}
+
+pub(crate) fn parse_struct(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Struct, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::Struct, found: token }),
 	}
 }
 
-pub(crate) fn parse_while(token: Option<Token>) -> Result<()> {
-	match token.as_deref() {
-		Some(TokenKind::While) => Ok(()),
+pub(crate) fn parse_enum(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Enum, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Enum, found: token }),
+	}
+}
+
+pub(crate) fn parse_type(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Type, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Type, found: token }),
+	}
+}
+
+pub(crate) fn parse_greater(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::Greater, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::Greater, found: token }),
+	}
+}
+
+pub(crate) fn parse_while(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::While, position }) => Ok(position),
 		_ => Err(Error::ExpectedToken { expected: TokenKind::While, found: token }),
 	}
 }
 
-pub(crate) fn parse_operator(token: Option<Token>) -> Result<PositionContainer<BinaryOperator>> {
-	match token {
-		Some(token) => Ok(PositionContainer {
-			position: token.position.clone(),
-			value: match token.value {
-				TokenKind::Plus => BinaryOperator::Add,
-				TokenKind::Minus => BinaryOperator::Subtract,
-				TokenKind::Star => BinaryOperator::Multiply,
-				TokenKind::Slash => BinaryOperator::Divide,
-				TokenKind::Equal => BinaryOperator::Equal,
-				TokenKind::NotEqual => BinaryOperator::NotEqual,
-				TokenKind::Less => BinaryOperator::Less,
-				// TokenKind::LessEqual => BinaryOperator::LessEqual,
-				TokenKind::Greater => BinaryOperator::Greater,
-				// TokenKind::GreaterEqual => BinaryOperator::GreaterEqual,
-				_ => return Err(Error::ExpectedToken { expected: TokenKind::Plus, found: Some(token) }),
-			},
-		}),
-		None => Err(Error::IllegalToken { token, context: "operator" }),
+pub(crate) fn parse_for(token: Option<Token>) -> Result<SourcePositionRange> {
+	match token {
+		Some(Token { value: TokenKind::For, position }) => Ok(position),
+		_ => Err(Error::ExpectedToken { expected: TokenKind::For, found: token }),
+	}
+}
+
+pub(crate) fn parse_unary_operator(token: Option<Token>) -> Result<PositionContainer<UnaryOperator>> {
+	match token {
+		Some(Token { value: TokenKind::Minus, position }) => {
+			Ok(PositionContainer::new(UnaryOperator::Negate, position))
+		},
+		Some(Token { value: TokenKind::Not, position }) => Ok(PositionContainer::new(UnaryOperator::Not, position)),
+		token => Err(Error::IllegalToken { token, context: "unary operator" }),
+	}
+}
+
+/// The binary operator that the token denotes, if any. The token is only borrowed, so that the parser can look for an
+/// operator after an expression without cloning the peeked token.
+pub(crate) fn binary_operator(token: &TokenKind) -> Option<BinaryOperator> {
+	Some(match token {
+		TokenKind::Plus => BinaryOperator::Add,
+		TokenKind::Minus => BinaryOperator::Subtract,
+		TokenKind::Star => BinaryOperator::Multiply,
+		TokenKind::Slash => BinaryOperator::Divide,
+		TokenKind::Power => BinaryOperator::Power,
+		TokenKind::BitOr => BinaryOperator::BitOr,
+		TokenKind::BitAnd => BinaryOperator::BitAnd,
+		TokenKind::BitXor => BinaryOperator::BitXor,
+		TokenKind::ShiftLeft => BinaryOperator::ShiftLeft,
+		TokenKind::ShiftRight => BinaryOperator::ShiftRight,
+		TokenKind::Equal => BinaryOperator::Equal,
+		TokenKind::NotEqual => BinaryOperator::NotEqual,
+		TokenKind::Less => BinaryOperator::Less,
+		TokenKind::LessEqual => BinaryOperator::LessEqual,
+		TokenKind::Greater => BinaryOperator::Greater,
+		TokenKind::GreaterEqual => BinaryOperator::GreaterEqual,
+		TokenKind::LogicalAnd => BinaryOperator::LogicalAnd,
+		TokenKind::LogicalOr => BinaryOperator::LogicalOr,
+		_ => return None,
+	})
+}
+
+/// How deep expressions and blocks may be nested. The parser descends recursively into nested constructs, so this
+/// bounds the stack it uses, which would overflow for deeply nested input otherwise.
+pub(crate) const MAX_NESTING_DEPTH: usize = 100;
+
+thread_local! {
+	/// Number of expressions and blocks the parser of this thread is currently nested in.
+	static NESTING_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// One level of nesting, which is left when the guard is dropped.
+pub(crate) struct Nesting(());
+
+impl Nesting {
+	/// Enters a nested expression or block starting at `token`, unless that exceeds [`MAX_NESTING_DEPTH`].
+	pub(crate) fn enter(token: Option<&Token>) -> Result<Self> {
+		let depth = NESTING_DEPTH.get() + 1;
+		if depth > MAX_NESTING_DEPTH {
+			return Err(Error::NestingTooDeep { token: token.cloned() });
+		}
+		NESTING_DEPTH.set(depth);
+		Ok(Nesting(()))
+	}
+}
+
+impl Drop for Nesting {
+	fn drop(&mut self) {
+		NESTING_DEPTH.set(NESTING_DEPTH.get() - 1);
 	}
 }
@@ -0,0 +1,11 @@
+use std::iter::Peekable;
+
+use super::Result;
+use crate::{parser::helper, source::PositionContainer, token::Token};
+
+/// Parses an import of a module like `import math`.
+pub(crate) fn parse_import(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<PositionContainer<String>> {
+	helper::parse_import(tokens.next())?;
+	let name = helper::parse_identifier(tokens.next())?;
+	Ok(PositionContainer::new(name.value.to_string(), name.position))
+}
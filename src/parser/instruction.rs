@@ -3,22 +3,30 @@ use std::iter::Peekable;
 use super::Result;
 use crate::{
 	ast,
-	ast::Statement,
+	ast::{expression::BinaryOperator, statement::LValue, Statement},
+	error::DiagnosticSink,
+	module,
 	parser::{
 		block::parse_block,
 		expression,
 		expression::{parse_float, parse_int, parse_parentheses},
 		function::parse_function_call,
 		helper,
-		variable::parse_variable_declaration,
+		variable::{parse_const_declaration, parse_variable_declaration},
 		Error,
 	},
+	source::PositionContainer,
 	token::{Token, TokenKind},
 };
 
-pub fn parse_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
+pub fn parse_instruction(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+	in_loop: bool,
+) -> Result<ast::Instruction> {
 	match tokens.peek() {
 		Some(Token { value: TokenKind::Identifier(_), .. }) => Ok(parse_identifier_instruction(tokens)?),
+		Some(Token { value: TokenKind::Star | TokenKind::Power, .. }) => Ok(parse_dereference_instruction(tokens)?),
 		Some(Token { value: TokenKind::Float(_), .. }) => {
 			Ok(ast::Instruction::Expression(ast::Expression::Number(parse_float(tokens)?)))
 		},
@@ -26,58 +34,299 @@ pub fn parse_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) ->
 			Ok(ast::Instruction::Expression(ast::Expression::Number(parse_int(tokens)?)))
 		},
 		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
-			Ok(ast::Instruction::Expression(parse_parentheses(tokens)?))
+			let expression = parse_parentheses(tokens)?;
+			Ok(ast::Instruction::Expression(expression::parse_postfix_expressions(expression, tokens)?))
+		},
+		Some(Token { value: TokenKind::Comment(_), .. }) => {
+			Ok(ast::Instruction::Comment(helper::parse_comment(tokens.next())?))
+		},
+		Some(Token { value: TokenKind::If, .. }) => {
+			Ok(ast::Instruction::IfElse(Box::new(parse_if_else(tokens, diagnostics, in_loop)?)))
+		},
+		Some(Token { value: TokenKind::Match, .. }) => {
+			Ok(ast::Instruction::Match(Box::new(parse_match(tokens, diagnostics, in_loop)?)))
 		},
-		Some(Token { value: TokenKind::If, .. }) => Ok(ast::Instruction::IfElse(Box::new(parse_if_else(tokens)?))),
 		Some(Token { value: TokenKind::While, .. }) => {
-			Ok(ast::Instruction::WhileLoop(Box::new(parse_while_loop(tokens)?)))
+			Ok(ast::Instruction::WhileLoop(Box::new(parse_while_loop(tokens, diagnostics)?)))
 		},
-		Some(Token { value: TokenKind::Var, .. }) => {
-			Ok(ast::Instruction::Statement(Statement::VariableDeclaration(parse_variable_declaration(tokens)?)))
+		Some(Token { value: TokenKind::For, .. }) => {
+			Ok(ast::Instruction::ForLoop(Box::new(parse_for_loop(tokens, diagnostics)?)))
+		},
+		Some(Token { value: TokenKind::Var, .. }) => Ok(ast::Instruction::Statement(Statement::VariableDeclaration(
+			Box::new(parse_variable_declaration(tokens)?),
+		))),
+		Some(Token { value: TokenKind::Const, .. }) => {
+			Ok(ast::Instruction::Statement(Statement::ConstDeclaration(Box::new(parse_const_declaration(tokens)?))))
+		},
+		Some(Token { value: TokenKind::Return, .. }) => Ok(ast::Instruction::Statement(parse_return(tokens)?)),
+		Some(Token { value: TokenKind::Break, position }) if in_loop => {
+			let position = position.clone();
+			tokens.next(); // Consume the TokenKind::Break
+			Ok(ast::Instruction::Statement(Statement::Break(position)))
 		},
-		Some(Token { value: TokenKind::Return, .. }) => {
-			tokens.next(); // Consume the TokenKind::Return
-			Ok(ast::Instruction::Statement(Statement::Return(expression::parse_binary_expression(tokens)?)))
+		Some(Token { value: TokenKind::Continue, position }) if in_loop => {
+			let position = position.clone();
+			tokens.next(); // Consume the TokenKind::Continue
+			Ok(ast::Instruction::Statement(Statement::Continue(position)))
+		},
+		Some(token @ Token { value: TokenKind::Break | TokenKind::Continue, .. }) => {
+			Err(Error::IllegalToken { token: Some(token.clone()), context: "instruction outside of a loop" })
 		},
 		other => Err(Error::IllegalToken { token: other.cloned(), context: "instruction" }),
 	}
 }
 
-pub fn parse_if_else(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::IfElse> {
-	helper::parse_if(tokens.next())?;
-	let condition = expression::parse_binary_expression(tokens)?;
-	let if_true = parse_block(tokens)?;
+/// Parses a `return` with its optional value. The value is absent if `return` is followed by a semicolon, the end of
+/// the block or a new line.
+fn parse_return(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<Statement> {
+	let return_ = tokens.next().expect("parse_return called without TokenKind::Return");
+	let value = match tokens.peek() {
+		Some(Token { value: TokenKind::Semicolon, .. }) => {
+			tokens.next(); // Consume the TokenKind::Semicolon
+			None
+		},
+		Some(Token { value: TokenKind::ClosingCurlyBraces | TokenKind::Comment(_), .. }) | None => None,
+		Some(token) if token.position.position.start.line > return_.position.position.end.line => None,
+		Some(_) => Some(expression::parse_binary_expression(tokens)?),
+	};
+	let position = match &value {
+		Some(value) => return_.position.until(&value.source_position()),
+		None => return_.position,
+	};
+	Ok(Statement::Return(value, position))
+}
+
+pub fn parse_if_else(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+	in_loop: bool,
+) -> Result<ast::IfElse> {
+	let if_ = helper::parse_if(tokens.next())?;
+	let condition = expression::parse_condition(tokens)?;
+	// Even if the if block contains errors, it was consumed completely, so the else block can be parsed as well
+	let if_true = parse_block(tokens, diagnostics, in_loop);
 	let if_false = match tokens.peek() {
 		Some(Token { value: TokenKind::Else, .. }) => {
 			tokens.next(); // Consume the TokenKind::Else
-			parse_block(tokens)?
+			match tokens.peek() {
+				// `else if` is an else block containing only the next if-else of the chain
+				Some(Token { value: TokenKind::If, .. }) => {
+					parse_if_else(tokens, diagnostics, in_loop).map(|if_else| {
+						let end = if_else.position.clone();
+						(vec![ast::Instruction::IfElse(Box::new(if_else))], Some(end))
+					})
+				},
+				_ => parse_block(tokens, diagnostics, in_loop).map(|(block, end)| (block, Some(end))),
+			}
 		},
-		_ => Vec::new(),
+		_ => Ok((Vec::new(), None)),
 	};
 
-	Ok(ast::IfElse { condition, if_true, if_false })
+	match (if_true, if_false) {
+		(Ok((if_true, if_true_end)), Ok((if_false, if_false_end))) => {
+			let position = if_.until(if_false_end.as_ref().unwrap_or(&if_true_end));
+			Ok(ast::IfElse { condition, if_true, if_false, position })
+		},
+		(Err(if_true), Err(if_false)) => Err(Error::Multiple(vec![if_true, if_false])),
+		(Err(error), _) | (_, Err(error)) => Err(error),
+	}
+}
+
+/// Parses a `match` like `match x { 1, 2 => { ... } _ => { ... } }`. The patterns of an arm are primary expressions
+/// separated by commas, and the `_` arm has to be the last one.
+pub fn parse_match(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+	in_loop: bool,
+) -> Result<ast::Match> {
+	let match_ = helper::parse_match(tokens.next())?;
+	// Like the condition of an if-else, the value is directly followed by a curly brace
+	let value = expression::parse_condition(tokens)?;
+	helper::parse_opening_curly_parenthesis(tokens.next())?;
+	let mut arms = Vec::new();
+	let mut default = None;
+	let end = loop {
+		let comment = match tokens.peek() {
+			Some(Token { value: TokenKind::Comment(_), .. }) => Some(helper::parse_comment(tokens.next())?),
+			_ => None,
+		};
+		match tokens.peek() {
+			Some(Token { value: TokenKind::ClosingCurlyBraces, .. }) if comment.is_none() => {
+				break helper::parse_closing_curly_parenthesis(tokens.next())?;
+			},
+			Some(Token { value: TokenKind::Identifier(name), .. }) if *name == "_" => {
+				tokens.next(); // Consume the `_`
+				helper::parse_fat_arrow(tokens.next())?;
+				let (body, _) = parse_block(tokens, diagnostics, in_loop)?;
+				default = Some(ast::MatchArm { patterns: Vec::new(), body, comment });
+				// Arms after the `_` arm would never be executed
+				break helper::parse_closing_curly_parenthesis(tokens.next())?;
+			},
+			_ => {
+				let mut patterns = vec![expression::parse_primary_expression(tokens)?];
+				while tokens.next_if(|token| token.value == TokenKind::Comma).is_some() {
+					patterns.push(expression::parse_primary_expression(tokens)?);
+				}
+				helper::parse_fat_arrow(tokens.next())?;
+				let (body, _) = parse_block(tokens, diagnostics, in_loop)?;
+				arms.push(ast::MatchArm { patterns, body, comment });
+			},
+		}
+	};
+	Ok(ast::Match { value, value_type: None, arms, default, position: match_.until(&end) })
 }
 
-pub fn parse_while_loop(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::WhileLoop> {
-	helper::parse_while(tokens.next())?;
-	let condition = expression::parse_binary_expression(tokens)?;
-	let body = parse_block(tokens)?;
-	Ok(ast::WhileLoop { condition, body })
+pub fn parse_while_loop(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+) -> Result<ast::WhileLoop> {
+	let while_ = helper::parse_while(tokens.next())?;
+	let condition = expression::parse_condition(tokens)?;
+	let (body, end) = parse_block(tokens, diagnostics, true)?;
+	let position = while_.until(&end);
+	Ok(ast::WhileLoop { condition, body, position })
 }
 
-pub fn parse_identifier_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
-	let identifier = helper::parse_identifier(tokens.next())?;
+pub fn parse_for_loop(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+) -> Result<ast::ForLoop> {
+	let for_ = helper::parse_for(tokens.next())?;
+	let initialization = match tokens.peek() {
+		Some(Token { value: TokenKind::Var, .. }) => {
+			Statement::VariableDeclaration(Box::new(parse_variable_declaration(tokens)?))
+		},
+		_ => Statement::Assignment(parse_assignment(tokens, true)?),
+	};
+	helper::parse_semicolon(tokens.next())?;
+	let condition = expression::parse_condition(tokens)?;
+	helper::parse_semicolon(tokens.next())?;
+	// Like the condition, the step is directly followed by the block
+	let step = parse_assignment(tokens, false)?;
+	let (body, end) = parse_block(tokens, diagnostics, true)?;
+	let position = for_.until(&end);
+	Ok(ast::ForLoop { initialization, condition, step, body, position })
+}
+
+/// Parses an assignment like `x = 42`, `point.x += 1`, `a[i] = 3` or `*p = 1`. If `struct_literals` is false, struct
+/// literals in the value have to be put in parentheses, see [`expression::parse_condition`].
+fn parse_assignment(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	struct_literals: bool,
+) -> Result<ast::statement::Assignment> {
+	let target = parse_target(tokens)?;
+	let (operator, value) = parse_assigned_value(tokens, struct_literals)?;
+	Ok(ast::statement::Assignment { target, operator, value })
+}
+
+/// Parses the left side of an assignment, which is a variable followed by field accesses and indexing, or such a
+/// target after a `*`. Like in expressions, the `*` applies to the whole target, so `*p.next` dereferences `p.next`.
+fn parse_target(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<LValue> {
 	match tokens.peek() {
-		Some(Token { value: TokenKind::OpeningParentheses, .. }) => {
-			Ok(ast::Instruction::Expression(ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?)))
+		Some(Token { value: TokenKind::Star | TokenKind::Power, .. }) => {
+			let _nesting = helper::Nesting::enter(tokens.peek())?;
+			let position = expression::parse_dereference(tokens)?;
+			Ok(LValue::Dereference { position, base: Box::new(parse_target(tokens)?) })
+		},
+		_ => {
+			let variable = helper::parse_identifier(tokens.next())?;
+			parse_lvalue(LValue::Variable(variable), tokens)
 		},
-		Some(Token { value: TokenKind::Equal, .. }) => {
-			tokens.next(); // Consume the TokenKind::Equal
-			Ok(ast::Instruction::Statement(ast::Statement::VariableAssignment(ast::statement::VariableAssignment {
-				name: identifier,
-				value: expression::parse_binary_expression(tokens)?,
-			})))
+	}
+}
+
+/// Parses the field accesses like `.x` and indexing like `[i]` following the `lvalue` on the left side of an
+/// assignment, if any.
+fn parse_lvalue(mut lvalue: LValue, tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<LValue> {
+	loop {
+		lvalue = match tokens.peek() {
+			Some(Token { value: TokenKind::Dot, .. }) => {
+				tokens.next(); // Consume the dot
+				let field = helper::parse_identifier(tokens.next())?;
+				LValue::FieldAccess { base: Box::new(lvalue), field }
+			},
+			Some(Token { value: TokenKind::OpeningSquareBrackets, .. }) => {
+				let index = expression::parse_index(tokens)?;
+				LValue::Index { base: Box::new(lvalue), index: Box::new(index) }
+			},
+			_ => return Ok(lvalue),
+		}
+	}
+}
+
+/// Parses the `=` or compound assignment operator like `+=`, and the value after it.
+fn parse_assigned_value(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	struct_literals: bool,
+) -> Result<(Option<PositionContainer<BinaryOperator>>, ast::Expression)> {
+	let operator = helper::parse_assignment_operator(tokens.next())?;
+	let value = match struct_literals {
+		true => expression::parse_binary_expression(tokens)?,
+		false => expression::parse_condition(tokens)?,
+	};
+	Ok((operator, value))
+}
+
+/// Parses an instruction starting with an identifier, which is a function call like `print(x)` or `math.print(x)`, a
+/// method call like `line.start.move(1)`, an assignment or a plain expression like `point.x`.
+pub fn parse_identifier_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
+	let mut identifier = helper::parse_identifier(tokens.next())?;
+	let mut member = None;
+	if tokens.next_if(|token| token.value == TokenKind::Dot).is_some() {
+		member = Some(helper::parse_identifier(tokens.next())?);
+	}
+	if let Some(Token { value: TokenKind::OpeningParentheses, .. }) = tokens.peek() {
+		if let Some(member) = member.take() {
+			identifier = module::qualify(identifier, member);
+		}
+		let function_call = ast::Expression::FunctionCall(parse_function_call(tokens, identifier)?);
+		return Ok(ast::Instruction::Expression(expression::parse_postfix_expressions(function_call, tokens)?));
+	}
+	let mut target = LValue::Variable(identifier);
+	if let Some(field) = member {
+		target = LValue::FieldAccess { base: Box::new(target), field };
+	}
+	let target = parse_lvalue(target, tokens)?;
+	let is_call = tokens.peek().is_some_and(|token| token.value == TokenKind::OpeningParentheses);
+	let target = match target {
+		// The last field access is the name of a method
+		LValue::FieldAccess { base, field } if is_call => {
+			let method_call = expression::parse_method_call(tokens, (*base).into(), field)?;
+			let method_call = ast::Expression::FunctionCall(method_call);
+			return Ok(ast::Instruction::Expression(expression::parse_postfix_expressions(method_call, tokens)?));
+		},
+		target => target,
+	};
+	parse_target_instruction(target, tokens)
+}
+
+/// Parses an instruction starting with `*`, which is an assignment through a pointer like `*p = 1`, or a plain
+/// expression like `*p`.
+fn parse_dereference_instruction(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::Instruction> {
+	let target = parse_target(tokens)?;
+	parse_target_instruction(target, tokens)
+}
+
+/// Parses the assignment to the `target`, if an assignment operator follows it. Otherwise, the target is a plain
+/// expression.
+fn parse_target_instruction(
+	target: LValue,
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<ast::Instruction> {
+	match tokens.peek() {
+		Some(Token {
+			value:
+				TokenKind::Equal
+				| TokenKind::PlusEqual
+				| TokenKind::MinusEqual
+				| TokenKind::StarEqual
+				| TokenKind::SlashEqual,
+			..
+		}) => {
+			let (operator, value) = parse_assigned_value(tokens, true)?;
+			let assignment = ast::statement::Assignment { target, operator, value };
+			Ok(ast::Instruction::Statement(ast::Statement::Assignment(assignment)))
 		},
-		_ => Ok(ast::Instruction::Expression(ast::Expression::Variable(identifier))),
+		_ => Ok(ast::Instruction::Expression(target.into())),
 	}
 }
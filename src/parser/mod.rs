@@ -1,35 +1,65 @@
 //! Building an [`AST`](crate::ast) from a [`Token`] stream.
 
 mod block;
+mod enum_;
 mod error;
 mod expression;
 mod function;
 mod helper;
+mod import;
 mod instruction;
 mod struct_;
+#[cfg(test)]
+mod test;
+mod type_alias;
 mod variable;
 
-use std::iter::Peekable;
+use std::{iter::Peekable, sync::Arc};
 
 pub use error::Error;
 
 use crate::{
-	ast::Node,
+	ast::{enum_::EnumResolver, visit::VisitorMut, Node},
+	error::DiagnosticSink,
+	lexer::{self, Lexer},
 	parser::{
+		enum_::parse_enum_definition,
 		function::{parse_extern_function_declaration, parse_function_definition},
+		import::parse_import,
 		struct_::parse_struct_definition,
+		type_alias::parse_type_alias,
+		variable::parse_const_declaration,
 	},
+	source::Source,
 	token::{Token, TokenKind},
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Lexes and parses a snippet of source code, like in a test, without driving the [`Lexer`] and the [`Parser`]
+/// manually. All errors are returned, the [lexer errors](Error::Lexer) before those of the parser.
+///
+/// ```
+/// use fortytwolang::{ast::Node, parser};
+///
+/// let ast_nodes = parser::parse_str("def main(): int {\n\treturn 42\n}").unwrap();
+/// assert!(matches!(&ast_nodes[..], [Node::Function(_)]));
+/// assert_eq!(parser::parse_str("def main() {\n\tvar x = )\n\tx = 1 +\n}").unwrap_err().len(), 2);
+/// ```
+pub fn parse_str(source_code: &str) -> std::result::Result<Vec<Node>, Vec<Error>> {
+	let source = Arc::new(Source::new("<string>".to_owned(), source_code.to_owned()));
+	let (tokens, lexer_errors) = Lexer::new(source.iter()).tokenize();
+	Parser::new(tokens.into_iter()).parse_with_lexer_errors(lexer_errors).map_err(Error::into_errors)
+}
+
 /// Analyzes [`Token`]s and builds an [AST](crate::ast).
 pub struct Parser<T>
 where
 	T: Iterator<Item = Token>,
 {
 	tokens: Peekable<T>,
+	/// Receives warnings about valid, but suspicious code.
+	diagnostics: DiagnosticSink,
 }
 
 impl<T> Parser<T>
@@ -37,21 +67,99 @@ where
 	T: Iterator<Item = Token>,
 {
 	pub fn new(tokens: T) -> Self {
-		Self { tokens: tokens.peekable() }
+		Self { tokens: tokens.peekable(), diagnostics: DiagnosticSink::default() }
+	}
+
+	/// Emits warnings to `diagnostics` instead of discarding them.
+	pub fn with_diagnostics(mut self, diagnostics: DiagnosticSink) -> Self {
+		self.diagnostics = diagnostics;
+		self
+	}
+
+	/// Parses all top-level nodes. Unlike collecting the [`Result`]s of the iterator, this does not stop at the first
+	/// error, but returns all errors combined to [`Error::Multiple`] if there is more than one.
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::{parser::Error, Lexer, Parser, Source};
+	///
+	/// let source_code = "def main() {\n\tvar x = )\n\tx = 1 +\n}\ndef f( {\n}";
+	/// let source = Arc::new(Source::new("example.ftl".to_owned(), source_code.to_owned()));
+	/// let tokens = Lexer::new(source.iter()).collect::<Result<Vec<_>, _>>().unwrap();
+	/// let Err(Error::Multiple(errors)) = Parser::new(tokens.into_iter()).parse() else { panic!() };
+	/// assert_eq!(errors.len(), 3);
+	/// ```
+	pub fn parse(self) -> Result<Vec<Node>> {
+		self.parse_with_lexer_errors(Vec::new())
+	}
+
+	/// Parses all top-level nodes like [`Self::parse`], from tokens of which the lexer skipped invalid chars with the
+	/// `lexer_errors`. These are reported as [`Error::Lexer`] before the errors of the parser, which may be caused by
+	/// the skipped chars, too.
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::{parser::Error, Lexer, Parser, Source};
+	///
+	/// let source_code = "def main() {\n\tvar x = 1 $\n\tx = 1 +\n}";
+	/// let source = Arc::new(Source::new("example.ftl".to_owned(), source_code.to_owned()));
+	/// let (tokens, lexer_errors) = Lexer::new(source.iter()).tokenize();
+	/// let Err(Error::Multiple(errors)) = Parser::new(tokens.into_iter()).parse_with_lexer_errors(lexer_errors) else {
+	/// 	panic!()
+	/// };
+	/// assert!(matches!(errors[..], [Error::Lexer(_), Error::IllegalToken { .. }]));
+	/// ```
+	pub fn parse_with_lexer_errors(self, lexer_errors: Vec<lexer::Error>) -> Result<Vec<Node>> {
+		let mut errors: Vec<Error> = lexer_errors.into_iter().map(Error::Lexer).collect();
+		let mut ast_nodes: Vec<Node> =
+			self.filter_map(|node| node.map_err(|error| errors.extend(error.into_errors())).ok()).collect();
+		// The enums may be declared after they are used, so references to them are only resolved now
+		let enums = EnumResolver::declared(&ast_nodes);
+		if !enums.is_empty() {
+			let mut resolver = EnumResolver { enums: &enums };
+			ast_nodes.iter_mut().for_each(|node| resolver.visit_node(node));
+		}
+		match errors.len() {
+			0 => Ok(ast_nodes),
+			1 => Err(errors.remove(0)),
+			_ => Err(Error::Multiple(errors)),
+		}
+	}
+}
+
+/// Skips tokens after an erroneous top-level node until the next function, extern function, struct, enum, type alias or
+/// import starts.
+fn synchronize(tokens: &mut Peekable<impl Iterator<Item = Token>>) {
+	while let Some(token) = tokens.peek() {
+		if let TokenKind::Def
+		| TokenKind::Extern
+		| TokenKind::Struct
+		| TokenKind::Enum
+		| TokenKind::Type
+		| TokenKind::Import = **token
+		{
+			break;
+		}
+		tokens.next();
 	}
 }
 
-fn parse_top_level_node(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Option<Result<Node>> {
+fn parse_top_level_node(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	diagnostics: &DiagnosticSink,
+) -> Option<Result<Node>> {
 	let token = tokens.peek()?;
 	match **token {
-		TokenKind::Def => Some(parse_function_definition(tokens).map(Node::Function)),
+		TokenKind::Def => Some(parse_function_definition(tokens, diagnostics).map(Node::Function)),
 		TokenKind::Extern => Some(parse_extern_function_declaration(tokens).map(Node::FunctionPrototype)),
 		TokenKind::Struct => Some(parse_struct_definition(tokens).map(Node::Struct)),
-		TokenKind::Comment(ref comment) => {
-			tracing::warn!("Skipping {}", token);
-			tokens.next();
-			parse_top_level_node(tokens)
-		},
+		TokenKind::Enum => Some(parse_enum_definition(tokens).map(Node::Enum)),
+		TokenKind::Type => Some(parse_type_alias(tokens).map(Node::TypeAlias)),
+		TokenKind::Const => Some(parse_const_declaration(tokens).map(Node::Const)),
+		TokenKind::Import => Some(parse_import(tokens).map(Node::Import)),
+		TokenKind::Comment(_) => Some(helper::parse_comment(tokens.next()).map(Node::Comment)),
 		_ => Some(Err(Error::IllegalToken { token: Some(tokens.next()?), context: "top level node" })),
 	}
 }
@@ -62,7 +170,16 @@ where
 {
 	type Item = Result<Node>;
 
+	/// Parses the next top-level node. After an error, the tokens are skipped up to the next top-level node, so that
+	/// parsing can continue with it.
 	fn next(&mut self) -> Option<Self::Item> {
-		parse_top_level_node(&mut self.tokens)
+		let node = parse_top_level_node(&mut self.tokens, &self.diagnostics)?;
+		if let Err(error) = &node {
+			// A node with multiple errors was consumed completely
+			if !matches!(error, Error::Multiple(_)) {
+				synchronize(&mut self.tokens);
+			}
+		}
+		Some(node)
 	}
 }
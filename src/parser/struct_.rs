@@ -1,33 +1,41 @@
 use std::iter::Peekable;
 
-use super::Result;
+use super::{Error, Result};
 use crate::{
 	ast,
-	parser::{helper, variable::parse_data_type},
+	parser::{function::parse_type_parameters, helper, variable::parse_data_type},
 	token::{Token, TokenKind},
 };
 
 pub(crate) fn parse_struct_definition(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
 ) -> Result<ast::struct_::Struct> {
-	helper::parse_struct(tokens.next())?;
+	let mut position = helper::parse_struct(tokens.next())?;
 	let name = helper::parse_identifier(tokens.next())?;
+	let type_parameters = parse_type_parameters(tokens)?;
 	helper::parse_opening_curly_parenthesis(tokens.next())?;
 	let mut fields: Vec<ast::struct_::Field> = Vec::new();
-	while let Some(token) = tokens.peek() {
-		if let TokenKind::ClosingCurlyBraces = **token {
-			tokens.next(); // Consume TokenKind::ClosingParentheses
-			break; // End of block
+	loop {
+		match tokens.peek() {
+			Some(Token { value: TokenKind::ClosingCurlyBraces, .. }) => {
+				position = position.until(&helper::parse_closing_curly_parenthesis(tokens.next())?);
+				break; // End of block
+			},
+			// The tokens end without the struct being closed
+			None => return Err(Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, found: None }),
+			Some(_) => fields.push(parse_field(tokens)?),
 		}
-		let field = parse_field(tokens)?;
-		fields.push(field);
 	}
-	Ok(ast::struct_::Struct { name, fields })
+	Ok(ast::struct_::Struct { name, type_parameters, fields, position })
 }
 
 pub(crate) fn parse_field(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::struct_::Field> {
+	let comment = match tokens.peek() {
+		Some(Token { value: TokenKind::Comment(_), .. }) => Some(helper::parse_comment(tokens.next())?),
+		_ => None,
+	};
 	let name = helper::parse_identifier(tokens.next())?;
 	helper::parse_colon(tokens.next())?;
 	let data_type = parse_data_type(tokens)?;
-	Ok(ast::struct_::Field { name, data_type })
+	Ok(ast::struct_::Field { name, data_type, comment })
 }
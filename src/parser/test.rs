@@ -0,0 +1,161 @@
+use crate::{
+	ast::{expression::UnaryOperator, statement::LValue, Expression, Instruction, Node, Statement},
+	parser::{parse_str, Error},
+	token::TokenKind,
+};
+
+/// Tests that chains of right-associative operators count towards the nesting depth, since each operator recurses,
+/// while chains of left-associative operators do not.
+#[test]
+fn test_nesting_right_associative_chain() {
+	let chain = |operator: &str, count: usize| {
+		let operands = vec!["2"; count + 1].join(&format!(" {operator} "));
+		format!("def main(): int {{\n\treturn {operands}\n}}")
+	};
+	let errors = parse_str(&chain("**", 5000)).unwrap_err();
+	assert!(matches!(errors[..], [Error::NestingTooDeep { .. }]));
+	assert!(parse_str(&chain("**", 50)).is_ok());
+	assert!(parse_str(&chain("+", 2000)).is_ok());
+}
+
+/// Tests that an erroneous instruction only skips the rest of its line, so that the errors of the following lines are
+/// reported as well, also inside of nested blocks.
+#[test]
+fn test_errors_in_block() {
+	let source_code = "
+		def main() {
+			var x = )
+			x = 1 + )
+			var y: int = 2
+			if y > 1 {
+				y = / 3
+				y = 4
+			}
+			print((y x))
+		}
+	";
+	assert_eq!(error_lines(source_code), [3, 4, 7, 10]);
+}
+
+/// Tests that an erroneous top-level node skips the tokens up to the next top-level node, after which parsing
+/// continues.
+#[test]
+fn test_errors_in_top_level_nodes() {
+	let source_code = "
+		x = 1
+		struct Point {
+			x: int
+			y
+		}
+		def f(a: int, ): int {
+			return a
+		}
+		extern g(
+		def main() {
+			var p = 1 $
+		}
+		import
+	";
+	let errors = parse_str(source_code).unwrap_err();
+	assert!(matches!(&errors[0], Error::Lexer(_)), "{:?}", errors[0]);
+	assert_eq!(error_lines(source_code), [0, 2, 6, 7, 11, 0]);
+}
+
+/// Tests that a block which is not closed before the next top-level node is reported, and that the next node is parsed
+/// anyway.
+#[test]
+fn test_unclosed_block() {
+	let source_code = "
+		def f() {
+			var x = 1
+		def main() {
+			var y = )
+		}
+	";
+	assert_eq!(error_lines(source_code), [4, 5]);
+	let errors = parse_str(source_code).unwrap_err();
+	assert!(matches!(&errors[0], Error::ExpectedToken { expected: TokenKind::ClosingCurlyBraces, found: Some(_) }));
+}
+
+/// Tests that an operator binding weaker than the operator before it ends the right hand side of that one, e.g. that
+/// `a - b * c + d` is `(a - (b * c)) + d` and not `a - ((b * c) + d)`, while `**` is right-associative.
+#[test]
+fn test_precedence() {
+	let expressions = [
+		("a - b * c + d", "((a Subtract (b Multiply c)) Add d)"),
+		("a * b - c / d + a", "(((a Multiply b) Subtract (c Divide d)) Add a)"),
+		("a + b * c ** d ** a - b", "((a Add (b Multiply (c Power (d Power a)))) Subtract b)"),
+		("a < b + c * d", "(a Less (b Add (c Multiply d)))"),
+		("a - b - c", "((a Subtract b) Subtract c)"),
+	];
+	for (expression, expected) in expressions {
+		let source_code = format!("def f(a: int, b: int, c: int, d: int): int {{\n\treturn {expression}\n}}");
+		let ast_nodes = parse_str(&source_code).unwrap();
+		let [Node::Function(function)] = &ast_nodes[..] else { panic!("{:?}", ast_nodes) };
+		let [Instruction::Statement(Statement::Return(Some(expression), _))] = &function.body[..] else {
+			panic!("{:?}", function.body)
+		};
+		assert_eq!(parenthesized(expression), expected);
+	}
+}
+
+/// Tests that a `*` dereferences the whole target of an assignment, that `**` is split into two dereferences, and that
+/// a `*` at the start of a line starts a new instruction unless a space follows it, like in wrapped expressions.
+#[test]
+fn test_dereference() {
+	let source_code = "
+		def main(p: ptr int, q: ptr ptr int, l: ptr List) {
+			*p = 1
+			*l.next = null
+			**q = p
+			var x = 2
+			*p += x
+			x = x
+				* x
+			*p
+		}
+	";
+	let ast_nodes = parse_str(source_code).unwrap();
+	let [Node::Function(function)] = &ast_nodes[..] else { panic!("{:?}", ast_nodes) };
+	let targets: Vec<_> = (function.body.iter())
+		.filter_map(|instruction| match instruction {
+			Instruction::Statement(Statement::Assignment(assignment)) => Some(&assignment.target),
+			_ => None,
+		})
+		.collect();
+	let [p, l, q, compound, x] = targets[..] else { panic!("{:?}", targets) };
+	assert!(matches!(p, LValue::Dereference { base, .. } if matches!(**base, LValue::Variable(_))));
+	assert!(matches!(l, LValue::Dereference { base, .. } if matches!(**base, LValue::FieldAccess { .. })));
+	let LValue::Dereference { position: outer, base } = q else { panic!("{:?}", q) };
+	let LValue::Dereference { position: inner, .. } = &**base else { panic!("{:?}", base) };
+	assert_eq!((outer.position.start.column, outer.position.end.column), (4, 4));
+	assert_eq!((inner.position.start.column, inner.position.end.column), (5, 5));
+	assert!(matches!(compound, LValue::Dereference { .. }));
+	assert!(matches!(x, LValue::Variable(_)));
+	let Instruction::Statement(Statement::Assignment(multiplication)) = &function.body[5] else { unreachable!() };
+	assert_eq!(parenthesized(&multiplication.value), "(x Multiply x)");
+	assert!(matches!(
+		function.body.last(),
+		Some(Instruction::Expression(Expression::UnaryExpression(unary)))
+			if unary.operator.value == UnaryOperator::Dereference
+	));
+}
+
+/// Boilerplate code for parsing source code, which has to be erroneous, and returning the lines of the errors. An error
+/// without token, e.g. at the end of the tokens or from the lexer, has line 0.
+fn error_lines(source_code: &str) -> Vec<usize> {
+	let errors = parse_str(source_code).unwrap_err();
+	errors.iter().map(|error| error.token().map_or(0, |token| token.position.position.start.line)).collect()
+}
+
+/// Shows the binary expressions of the `expression`, which consists of variables and binary expressions, in
+/// parentheses.
+fn parenthesized(expression: &Expression) -> String {
+	match expression {
+		Expression::BinaryExpression(binary) => {
+			format!("({} {:?} {})", parenthesized(&binary.lhs), binary.operator.value, parenthesized(&binary.rhs))
+		},
+		Expression::Variable(variable) => variable.value.to_string(),
+		expression => panic!("Unexpected expression {:?}", expression),
+	}
+}
@@ -0,0 +1,18 @@
+use std::iter::Peekable;
+
+use super::Result;
+use crate::{
+	ast,
+	parser::{helper, variable},
+	token::Token,
+};
+
+/// Parses a type alias like `type Meters = float`.
+pub(crate) fn parse_type_alias(tokens: &mut Peekable<impl Iterator<Item = Token>>) -> Result<ast::TypeAlias> {
+	let type_ = helper::parse_type(tokens.next())?;
+	let name = helper::parse_identifier(tokens.next())?;
+	helper::parse_equal(tokens.next())?;
+	let data_type = variable::parse_data_type(tokens)?;
+	let position = type_.until(&data_type.position);
+	Ok(ast::TypeAlias { name, data_type, position })
+}
@@ -2,22 +2,45 @@ use std::iter::Peekable;
 
 use super::Result;
 use crate::{
-	ast,
+	ast, module,
 	parser::{expression, helper, variable, Error},
 	source::PositionContainer,
+	symbol::Symbol,
 	token::{Token, TokenKind},
 };
 
 pub fn parse_variable_declaration(
 	tokens: &mut Peekable<impl Iterator<Item = Token>>,
 ) -> Result<ast::statement::VariableDeclaration> {
-	helper::parse_variable_declaration(tokens.next())?;
+	let var = helper::parse_variable_declaration(tokens.next())?;
 	let name = helper::parse_identifier(tokens.next())?;
-	helper::parse_colon(tokens.next())?;
-	let data_type = variable::parse_data_type(tokens)?;
+	let data_type = parse_type_annotation(tokens)?;
 	helper::parse_equal(tokens.next())?;
 	let value = expression::parse_primary_expression(tokens)?;
-	Ok(ast::statement::VariableDeclaration { name, data_type, value })
+	let position = var.until(&value.source_position());
+	Ok(ast::statement::VariableDeclaration { name, data_type, value, position })
+}
+
+pub fn parse_const_declaration(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<ast::statement::ConstDeclaration> {
+	let const_ = helper::parse_const_declaration(tokens.next())?;
+	let name = helper::parse_identifier(tokens.next())?;
+	let data_type = parse_type_annotation(tokens)?;
+	helper::parse_equal(tokens.next())?;
+	let value = expression::parse_binary_expression(tokens)?;
+	let position = const_.until(&value.source_position());
+	Ok(ast::statement::ConstDeclaration { name, data_type, value, position })
+}
+
+/// Parses the optional `: type` after the name of a declaration.
+fn parse_type_annotation(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+) -> Result<Option<PositionContainer<ast::statement::DataType>>> {
+	match tokens.next_if(|token| token.value == TokenKind::Colon) {
+		Some(_colon) => Ok(Some(variable::parse_data_type(tokens)?)),
+		None => Ok(None),
+	}
 }
 
 pub(crate) fn parse_data_type(
@@ -31,6 +54,32 @@ pub(crate) fn parse_data_type(
 			let type_to_point_to = parse_data_type(tokens)?;
 			Ok(PositionContainer { value: ast::statement::DataType::Pointer(Box::new(type_to_point_to)), position })
 		},
+		// Array type like `[int; 3]`
+		Some(Token { value: TokenKind::OpeningSquareBrackets, mut position }) => {
+			let element = parse_data_type(tokens)?;
+			helper::parse_semicolon(tokens.next())?;
+			let length = match tokens.next() {
+				Some(Token { value: TokenKind::Int(length), .. }) if length >= 0 => length as usize,
+				other => return Err(Error::ExpectedToken { expected: TokenKind::Int(0), found: other }),
+			};
+			match tokens.next() {
+				Some(Token { value: TokenKind::ClosingSquareBrackets, position: closing_position }) => {
+					position.position.end = closing_position.position.end;
+				},
+				other => return Err(Error::ExpectedToken { expected: TokenKind::ClosingSquareBrackets, found: other }),
+			}
+			let array = ast::statement::DataType::Array { element: Box::new(element.value), length };
+			Ok(PositionContainer { value: array, position })
+		},
+		// Struct of an imported module like `math.Vector`
+		Some(Token { value: TokenKind::Identifier(module), position })
+			if tokens.peek().is_some_and(|token| token.value == TokenKind::Dot) =>
+		{
+			tokens.next(); // Consume the dot
+			let name =
+				module::qualify(PositionContainer::new(module, position), helper::parse_identifier(tokens.next())?);
+			parse_struct_type(tokens, name)
+		},
 		// Normal type
 		Some(Token { value: TokenKind::Identifier(type_str), position }) => {
 			match ast::statement::BasicDataType::try_from(type_str.as_str()) {
@@ -40,10 +89,42 @@ pub(crate) fn parse_data_type(
 				},
 				Err(_) => {
 					// User-defined data type (struct)
-					Ok(PositionContainer { value: ast::statement::DataType::Struct(type_str), position })
+					parse_struct_type(tokens, PositionContainer::new(type_str, position))
 				},
 			}
 		},
-		other => Err(Error::ExpectedToken { expected: TokenKind::Identifier(String::new()), found: other }),
+		other => Err(Error::ExpectedToken { expected: TokenKind::Identifier(Symbol::intern("")), found: other }),
+	}
+}
+
+/// Parses the type arguments in angle brackets like `<int, float>` after the `name` of a generic struct. Without angle
+/// brackets, the type is a normal struct.
+fn parse_struct_type(
+	tokens: &mut Peekable<impl Iterator<Item = Token>>,
+	name: PositionContainer<Symbol>,
+) -> Result<PositionContainer<ast::statement::DataType>> {
+	if tokens.next_if(|token| token.value == TokenKind::Less).is_none() {
+		return Ok(PositionContainer { value: ast::statement::DataType::Struct(name.value), position: name.position });
 	}
+	let mut type_arguments = Vec::new();
+	loop {
+		type_arguments.push(parse_data_type(tokens)?.value);
+		if tokens.next_if(|token| token.value == TokenKind::Comma).is_none() {
+			break;
+		}
+	}
+	let closing_angle_bracket = match tokens.peek_mut() {
+		// The closing angle brackets of nested type arguments like in `Pair<List<int>>` are lexed as a right shift,
+		// whose second half stays in the tokens
+		Some(token) if token.value == TokenKind::ShiftRight => {
+			let mut closing_angle_bracket = token.position.clone();
+			closing_angle_bracket.position.end = closing_angle_bracket.position.start;
+			token.value = TokenKind::Greater;
+			token.position.position.start = token.position.position.end;
+			closing_angle_bracket
+		},
+		_ => helper::parse_greater(tokens.next())?,
+	};
+	let generic = ast::statement::DataType::Generic { name: name.value, type_arguments };
+	Ok(PositionContainer { value: generic, position: name.position.until(&closing_angle_bracket) })
 }
@@ -0,0 +1,191 @@
+//! Selecting and ordering the optimization passes that run between the [semantic analysis](crate::semantic_analyzer)
+//! and the backends.
+//!
+//! A [`Pipeline`] runs the [`Pass`]es of an [`OptLevel`] in order. Passes over the AST run before any backend, passes
+//! over the [IR](crate::ir) only for backends that translate the IR, i.e. the [bytecode compiler](crate::bytecode),
+//! the [LLVM emitter](crate::emitter::Llvm) and the [WebAssembly emitter](crate::emitter::Wasm).
+
+#[cfg(test)]
+mod test;
+
+use std::{fmt, io, str::FromStr};
+
+use crate::{
+	ast::{fold::Fold, Node},
+	emitter::{self, Emitter},
+	ir,
+	optimizer::{ConstantFolding, DeadCodeElimination, Inlining},
+};
+
+/// How much a program is optimized, which selects the [`Pass`]es of a [`Pipeline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+	/// No optimizations, so that the emitted code corresponds to the source code.
+	O0,
+	/// Cheap optimizations which do not change the structure of the program, like constant folding.
+	#[default]
+	O1,
+	/// All optimizations, including inlining and dead code elimination.
+	O2,
+}
+
+impl OptLevel {
+	/// The passes that run at this level, in order.
+	pub fn passes(self) -> Vec<Pass> {
+		match self {
+			OptLevel::O0 => Vec::new(),
+			OptLevel::O1 => vec![Pass::ConstantFolding, Pass::ThreadJumps],
+			OptLevel::O2 => vec![Pass::Inlining, Pass::ConstantFolding, Pass::DeadCodeElimination, Pass::ThreadJumps],
+		}
+	}
+}
+
+impl FromStr for OptLevel {
+	type Err = Error;
+
+	/// Parses the number of a level, like `2` for [`OptLevel::O2`].
+	fn from_str(level: &str) -> Result<Self, Self::Err> {
+		match level {
+			"0" => Ok(OptLevel::O0),
+			"1" => Ok(OptLevel::O1),
+			"2" => Ok(OptLevel::O2),
+			_ => Err(Error::UnknownOptLevel(level.to_owned())),
+		}
+	}
+}
+
+/// A single optimization of a [`Pipeline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+	/// [`Inlining`] on the AST.
+	Inlining,
+	/// [`ConstantFolding`] on the AST.
+	ConstantFolding,
+	/// [`DeadCodeElimination`] on the AST.
+	DeadCodeElimination,
+	/// [Jump threading](ir::Function::thread_jumps) on the IR.
+	ThreadJumps,
+}
+
+impl Pass {
+	/// All passes in the order in which they run at [`OptLevel::O2`].
+	pub const ALL: [Pass; 4] = [Pass::Inlining, Pass::ConstantFolding, Pass::DeadCodeElimination, Pass::ThreadJumps];
+
+	/// The name by which the pass is selected, e.g. for `--print-after`.
+	pub fn name(self) -> &'static str {
+		match self {
+			Pass::Inlining => "inline",
+			Pass::ConstantFolding => "const-fold",
+			Pass::DeadCodeElimination => "dce",
+			Pass::ThreadJumps => "thread-jumps",
+		}
+	}
+
+	/// Whether the pass runs on the [IR](crate::ir) instead of the AST, so that it is skipped by backends which
+	/// translate the AST, like the [C emitter](crate::emitter::C) and the [interpreter](crate::interpreter).
+	pub fn runs_on_ir(self) -> bool {
+		matches!(self, Pass::ThreadJumps)
+	}
+}
+
+impl fmt::Display for Pass {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+impl FromStr for Pass {
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Pass::ALL.into_iter().find(|pass| pass.name() == name).ok_or_else(|| Error::UnknownPass(name.to_owned()))
+	}
+}
+
+/// Errors of selecting passes by name.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+	#[error("Unknown optimization level `{0}`, expected 0, 1 or 2")]
+	UnknownOptLevel(String),
+
+	#[error("Unknown pass `{0}`, expected one of inline, const-fold, dce and thread-jumps")]
+	UnknownPass(String),
+}
+
+/// Runs a sequence of [`Pass`]es over a program.
+///
+/// ```
+/// use fortytwolang::passes::{OptLevel, Pass, Pipeline};
+///
+/// let pipeline = Pipeline::new(OptLevel::O2).print_after(Pass::DeadCodeElimination);
+/// assert_eq!(pipeline.passes()[0], Pass::Inlining);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+	passes: Vec<Pass>,
+	/// The pass after which the program is printed to stderr.
+	print_after: Option<Pass>,
+}
+
+impl Pipeline {
+	/// Runs the passes of the `level`.
+	pub fn new(level: OptLevel) -> Self {
+		Self::with_passes(level.passes())
+	}
+
+	/// Runs the `passes` in the given order. A pass may occur more than once, e.g. to fold constants again after
+	/// inlining.
+	pub fn with_passes(passes: Vec<Pass>) -> Self {
+		Self { passes, print_after: None }
+	}
+
+	/// Prints the program to stderr after each run of the `pass`: The AST as FTL code, the IR as listing. Nothing is
+	/// printed if the pass does not run, e.g. because of the optimization level.
+	pub fn print_after(mut self, pass: Pass) -> Self {
+		self.print_after = Some(pass);
+		self
+	}
+
+	/// The passes that run, in order.
+	pub fn passes(&self) -> &[Pass] {
+		&self.passes
+	}
+
+	/// The pass after which the program is printed, if it [runs on the IR](Pass::runs_on_ir), so that nothing is
+	/// printed for backends which translate the AST.
+	pub fn print_after_ir(&self) -> Option<Pass> {
+		self.print_after.filter(|pass| pass.runs_on_ir())
+	}
+
+	/// Runs the passes over the AST.
+	pub fn run_ast(&self, mut ast_nodes: Vec<Node>) -> io::Result<Vec<Node>> {
+		for &pass in &self.passes {
+			ast_nodes = match pass {
+				Pass::Inlining => Inlining::default().fold_nodes(ast_nodes),
+				Pass::ConstantFolding => ConstantFolding.fold_nodes(ast_nodes),
+				Pass::DeadCodeElimination => DeadCodeElimination::default().fold_nodes(ast_nodes),
+				Pass::ThreadJumps => continue,
+			};
+			tracing::debug!("Ran pass `{}` on the AST", pass);
+			if self.print_after == Some(pass) {
+				eprintln!("# After pass `{}`:", pass);
+				emitter::Ftl::codegen(ast_nodes.iter().cloned(), io::stderr().lock())?;
+			}
+		}
+		Ok(ast_nodes)
+	}
+
+	/// Runs the passes over the IR.
+	pub fn run_ir(&self, program: &mut ir::Program) {
+		for &pass in &self.passes {
+			match pass {
+				Pass::ThreadJumps => program.functions.iter_mut().for_each(ir::Function::thread_jumps),
+				Pass::Inlining | Pass::ConstantFolding | Pass::DeadCodeElimination => continue,
+			}
+			tracing::debug!("Ran pass `{}` on the IR", pass);
+			if self.print_after == Some(pass) {
+				eprint!("# After pass `{}`:\n{}", pass, program);
+			}
+		}
+	}
+}
@@ -0,0 +1,110 @@
+use crate::{
+	emitter, parser,
+	passes::{Error, OptLevel, Pass, Pipeline},
+	Emitter,
+};
+
+const SOURCE_CODE: &str = "
+def square(x: int): int {
+	return x * x
+}
+
+def main(): int {
+	var unused = (2 * 3)
+	return square(4)
+}";
+
+/// Tests that no pass runs at `-O0`.
+#[test]
+fn test_opt_level_0() {
+	let expected = concat!(
+		"def square(x: int): int {\n",
+		"\treturn x * x\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\tvar unused = (2 * 3)\n",
+		"\treturn square(4)\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&Pipeline::new(OptLevel::O0), SOURCE_CODE), expected);
+}
+
+/// Tests that `-O1` only folds constants.
+#[test]
+fn test_opt_level_1() {
+	let expected = concat!(
+		"def square(x: int): int {\n",
+		"\treturn x * x\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\tvar unused = 6\n",
+		"\treturn square(4)\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&Pipeline::new(OptLevel::O1), SOURCE_CODE), expected);
+}
+
+/// Tests that `-O2` inlines the call, folds the result and removes the unused variable.
+#[test]
+fn test_opt_level_2() {
+	let expected = concat!(
+		"def square(x: int): int {\n",
+		"\treturn x * x\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\treturn 16\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&Pipeline::new(OptLevel::O2), SOURCE_CODE), expected);
+}
+
+/// Tests that passes run in the given order, so that constant folding before inlining misses the inlined product.
+#[test]
+fn test_pass_order() {
+	let pipeline = Pipeline::with_passes(vec![Pass::ConstantFolding, Pass::Inlining, Pass::DeadCodeElimination]);
+	let expected = concat!(
+		"def square(x: int): int {\n",
+		"\treturn x * x\n",
+		"\n",
+		"}\n",
+		"def main(): int {\n",
+		"\treturn 4 * 4\n",
+		"\n",
+		"}\n",
+	);
+	assert_eq!(optimize(&pipeline, SOURCE_CODE), expected);
+}
+
+/// Tests that levels and passes are parsed from their names on the command line.
+#[test]
+fn test_parse_names() {
+	assert_eq!("2".parse(), Ok(OptLevel::O2));
+	assert_eq!("3".parse::<OptLevel>(), Err(Error::UnknownOptLevel("3".to_owned())));
+	for pass in Pass::ALL {
+		assert_eq!(pass.name().parse(), Ok(pass));
+	}
+	assert_eq!("fold".parse::<Pass>(), Err(Error::UnknownPass("fold".to_owned())));
+}
+
+/// Tests that only printing after a pass on the IR is reported, which backends translating the AST warn about.
+#[test]
+fn test_print_after_ir() {
+	assert_eq!(Pipeline::new(OptLevel::O1).print_after_ir(), None);
+	assert_eq!(Pipeline::new(OptLevel::O1).print_after(Pass::ConstantFolding).print_after_ir(), None);
+	let pipeline = Pipeline::new(OptLevel::O1).print_after(Pass::ThreadJumps);
+	assert_eq!(pipeline.print_after_ir(), Some(Pass::ThreadJumps));
+}
+
+/// Parses the `source_code`, runs the AST passes of the `pipeline` and formats the result.
+fn optimize(pipeline: &Pipeline, source_code: &str) -> String {
+	let ast_nodes = pipeline.run_ast(parser::parse_str(source_code).unwrap()).unwrap();
+	let mut formatted = Vec::new();
+	emitter::Ftl::codegen(ast_nodes.into_iter(), &mut formatted).unwrap();
+	String::from_utf8(formatted).unwrap()
+}
@@ -0,0 +1,265 @@
+//! Reading lines in the terminal with editing: The cursor is moved with the arrow keys, Home and End, the lines of the
+//! [history](super::history) are recalled with the up and down arrow keys, Ctrl-C cancels the line and Ctrl-D on an
+//! empty line ends the input.
+
+use std::{
+	io::{self, BufRead, IsTerminal, Read, Write},
+	mem::MaybeUninit,
+};
+
+/// A line read by [`read_line`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Line {
+	/// The line was entered with Enter.
+	Entered(String),
+	/// The line was cancelled with Ctrl-C.
+	Interrupted,
+	/// The input ended, because Ctrl-D was pressed on an empty line or stdin was closed.
+	End,
+}
+
+/// A key pressed in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Key {
+	Char(char),
+	Enter,
+	Backspace,
+	Delete,
+	Left,
+	Right,
+	Up,
+	Down,
+	Home,
+	End,
+	/// Ctrl-C
+	Interrupt,
+	/// Ctrl-D
+	EndOfInput,
+}
+
+/// Reads a line from stdin after showing the `prompt`. If stdin is a terminal, the line can be edited and the
+/// `history` recalled. Otherwise, e.g. if the input is piped, the line is read as it is.
+pub(super) fn read_line(prompt: &str, history: &[String]) -> io::Result<Line> {
+	let mut stdin = io::stdin().lock();
+	let mut stdout = io::stdout().lock();
+	if !stdin.is_terminal() {
+		write!(stdout, "{}", prompt)?;
+		stdout.flush()?;
+		let mut line = String::new();
+		if stdin.read_line(&mut line)? == 0 {
+			// Leave the prompt on its own line
+			writeln!(stdout)?;
+			return Ok(Line::End);
+		}
+		line.truncate(line.trim_end_matches(['\n', '\r']).len());
+		return Ok(Line::Entered(line));
+	}
+
+	let _raw_mode = RawMode::enable()?;
+	let mut editor = Editor::new(history);
+	let mut bytes = stdin.bytes();
+	loop {
+		render(&mut stdout, prompt, &editor)?;
+		let line = match read_key(&mut bytes)? {
+			Some(key) => editor.press(key),
+			None => Some(Line::End),
+		};
+		if let Some(line) = line {
+			if line == Line::Interrupted {
+				write!(stdout, "^C")?;
+			}
+			// The terminal does not translate `\n` to a new line in raw mode
+			write!(stdout, "\r\n")?;
+			stdout.flush()?;
+			return Ok(line);
+		}
+	}
+}
+
+/// Shows the `prompt` and the line of the `editor` in place of the current line of the terminal, and moves the cursor
+/// to the cursor of the `editor`. Each char is assumed to take one column.
+fn render(stdout: &mut impl Write, prompt: &str, editor: &Editor) -> io::Result<()> {
+	let line: String = editor.chars.iter().collect();
+	// Return to the start of the line and clear the rest of the previous line after writing the new one
+	write!(stdout, "\r{}{}\x1b[K", prompt, line)?;
+	let behind_cursor = editor.chars.len() - editor.cursor;
+	if behind_cursor > 0 {
+		write!(stdout, "\x1b[{}D", behind_cursor)?;
+	}
+	stdout.flush()
+}
+
+/// Reads the next key from the `bytes` of a terminal in raw mode, or returns `None` if they end. Control chars and
+/// escape sequences of other keys are skipped.
+pub(super) fn read_key(bytes: &mut impl Iterator<Item = io::Result<u8>>) -> io::Result<Option<Key>> {
+	while let Some(byte) = bytes.next().transpose()? {
+		let key = match byte {
+			b'\r' | b'\n' => Key::Enter,
+			0x01 => Key::Home,
+			0x03 => Key::Interrupt,
+			0x04 => Key::EndOfInput,
+			0x05 => Key::End,
+			0x08 | 0x7f => Key::Backspace,
+			0x1b => match read_escape_sequence(bytes)? {
+				Some(key) => key,
+				None => continue,
+			},
+			byte if byte.is_ascii_control() => continue,
+			byte => match read_char(byte, bytes)? {
+				Some(char) => Key::Char(char),
+				None => continue,
+			},
+		};
+		return Ok(Some(key));
+	}
+	Ok(None)
+}
+
+/// Reads an escape sequence like `\x1b[A` after its `\x1b`, and returns its key if it is known.
+fn read_escape_sequence(bytes: &mut impl Iterator<Item = io::Result<u8>>) -> io::Result<Option<Key>> {
+	// Sequences start either with `[` or, in application mode, with `O`
+	if !matches!(bytes.next().transpose()?, Some(b'[' | b'O')) {
+		return Ok(None);
+	}
+	// Numeric parameters, like the `3` of Delete `\x1b[3~`, are followed by a final byte like `~`
+	let mut parameters = Vec::new();
+	while let Some(byte) = bytes.next().transpose()? {
+		let key = match (byte, parameters.as_slice()) {
+			(b'0'..=b'9' | b';', _) => {
+				parameters.push(byte);
+				continue;
+			},
+			(b'A', _) => Key::Up,
+			(b'B', _) => Key::Down,
+			(b'C', _) => Key::Right,
+			(b'D', _) => Key::Left,
+			(b'H', _) | (b'~', b"1" | b"7") => Key::Home,
+			(b'F', _) | (b'~', b"4" | b"8") => Key::End,
+			(b'~', b"3") => Key::Delete,
+			_ => return Ok(None),
+		};
+		return Ok(Some(key));
+	}
+	Ok(None)
+}
+
+/// Reads the char starting with the `first` byte of its UTF-8 encoding, or returns `None` if the encoding is invalid.
+fn read_char(first: u8, bytes: &mut impl Iterator<Item = io::Result<u8>>) -> io::Result<Option<char>> {
+	let len = match first.leading_ones() {
+		0 => 1,
+		len @ 2..=4 => len as usize,
+		_ => return Ok(None),
+	};
+	let mut encoding = vec![first];
+	for _ in 1..len {
+		match bytes.next().transpose()? {
+			Some(byte) => encoding.push(byte),
+			None => return Ok(None),
+		}
+	}
+	Ok(std::str::from_utf8(&encoding).ok().and_then(|char| char.chars().next()))
+}
+
+/// The line being edited, independent of the terminal.
+pub(super) struct Editor<'h> {
+	/// The lines which can be recalled, oldest first.
+	history: &'h [String],
+	/// The chars of the line.
+	chars: Vec<char>,
+	/// Index of the char in [`Self::chars`] before which chars are inserted.
+	cursor: usize,
+	/// Index of the recalled line in the [`Self::history`], which is the length of the history for a new line.
+	recalled: usize,
+	/// The new line, while a line of the history is recalled.
+	draft: Vec<char>,
+}
+
+impl<'h> Editor<'h> {
+	pub(super) fn new(history: &'h [String]) -> Self {
+		Self { history, chars: Vec::new(), cursor: 0, recalled: history.len(), draft: Vec::new() }
+	}
+
+	/// Applies the `key` to the line. Returns the [`Line`] if it is finished.
+	pub(super) fn press(&mut self, key: Key) -> Option<Line> {
+		match key {
+			Key::Char(char) => {
+				self.chars.insert(self.cursor, char);
+				self.cursor += 1;
+			},
+			Key::Enter => return Some(Line::Entered(self.chars.iter().collect())),
+			Key::Backspace if self.cursor > 0 => {
+				self.cursor -= 1;
+				self.chars.remove(self.cursor);
+			},
+			Key::Delete if self.cursor < self.chars.len() => {
+				self.chars.remove(self.cursor);
+			},
+			Key::Left => self.cursor = self.cursor.saturating_sub(1),
+			Key::Right => self.cursor = (self.cursor + 1).min(self.chars.len()),
+			Key::Up if self.recalled > 0 => {
+				if self.recalled == self.history.len() {
+					self.draft = self.chars.clone();
+				}
+				self.recall(self.recalled - 1);
+			},
+			Key::Down if self.recalled < self.history.len() => self.recall(self.recalled + 1),
+			Key::Home => self.cursor = 0,
+			Key::End => self.cursor = self.chars.len(),
+			Key::Interrupt => return Some(Line::Interrupted),
+			Key::EndOfInput if self.chars.is_empty() => return Some(Line::End),
+			// Like in a shell, Ctrl-D deletes the char under the cursor if the line is not empty
+			Key::EndOfInput => return self.press(Key::Delete),
+			Key::Backspace | Key::Delete | Key::Up | Key::Down => (),
+		}
+		None
+	}
+
+	/// Replaces the line with the line at `index` in the history, or with the draft after the last line.
+	fn recall(&mut self, index: usize) {
+		self.recalled = index;
+		self.chars = match self.history.get(index) {
+			Some(line) => line.chars().collect(),
+			None => std::mem::take(&mut self.draft),
+		};
+		self.cursor = self.chars.len();
+	}
+}
+
+/// Switches the terminal of stdin to raw mode, in which the keys are read one by one without being shown, until it is
+/// dropped.
+struct RawMode {
+	/// The settings of the terminal before, which are restored.
+	original: libc::termios,
+}
+
+impl RawMode {
+	fn enable() -> io::Result<Self> {
+		let mut original = MaybeUninit::uninit();
+		// SAFETY: `tcgetattr` initializes the `termios` if it succeeds.
+		let original = unsafe {
+			if libc::tcgetattr(libc::STDIN_FILENO, original.as_mut_ptr()) != 0 {
+				return Err(io::Error::last_os_error());
+			}
+			original.assume_init()
+		};
+		let mut raw = original;
+		// Read the keys one by one without echo, and Ctrl-C as key instead of as signal
+		raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+		// Read Enter as `\r`, and Ctrl-S and Ctrl-Q as keys instead of pausing the output
+		raw.c_iflag &= !(libc::ICRNL | libc::IXON);
+		raw.c_cc[libc::VMIN] = 1;
+		raw.c_cc[libc::VTIME] = 0;
+		// SAFETY: `raw` is a valid `termios`, since it was read by `tcgetattr`.
+		if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSADRAIN, &raw) } != 0 {
+			return Err(io::Error::last_os_error());
+		}
+		Ok(Self { original })
+	}
+}
+
+impl Drop for RawMode {
+	fn drop(&mut self) {
+		// SAFETY: `original` is a valid `termios`, since it was read by `tcgetattr`.
+		unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSADRAIN, &self.original) };
+	}
+}
@@ -0,0 +1,50 @@
+use std::{
+	env,
+	fs::{self, File, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+/// Name of the history file in the home directory.
+const HISTORY_FILE: &str = ".mylang_history";
+
+/// The lines entered in the REPL, which are kept in a file in the home directory, one per line.
+pub(super) struct History {
+	/// The lines of the previous sessions and of this one, oldest first.
+	lines: Vec<String>,
+	/// The history file, opened for appending. Without home directory, the history is only kept for this session.
+	file: Option<File>,
+}
+
+impl History {
+	/// Opens the history file `~/.mylang_history`.
+	pub(super) fn open() -> Self {
+		match env::var_os("HOME") {
+			Some(home) => Self::load(&PathBuf::from(home).join(HISTORY_FILE)),
+			None => Self { lines: Vec::new(), file: None },
+		}
+	}
+
+	/// Reads the lines of the history file at `path`, which is created if it does not exist yet.
+	pub(super) fn load(path: &Path) -> Self {
+		let lines = fs::read_to_string(path).unwrap_or_default().lines().map(str::to_owned).collect();
+		Self { lines, file: OpenOptions::new().create(true).append(true).open(path).ok() }
+	}
+
+	/// The lines, oldest first.
+	pub(super) fn lines(&self) -> &[String] {
+		&self.lines
+	}
+
+	/// Appends the `line` to the history file, unless it repeats the previous line. Failing to write the history does
+	/// not stop the REPL.
+	pub(super) fn append(&mut self, line: &str) {
+		if self.lines.last().is_some_and(|last| last == line) {
+			return;
+		}
+		self.lines.push(line.to_owned());
+		if let Some(file) = &mut self.file {
+			let _ = writeln!(file, "{}", line);
+		}
+	}
+}
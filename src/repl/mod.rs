@@ -0,0 +1,448 @@
+//! Interactive read-eval-print loop, which executes each entry with the interpreter.
+//!
+//! An entry consists of either top-level definitions or instructions, which are executed as body of a function. If
+//! the entry is a single expression with a value, like `1 + 2`, its value is printed. The definitions and variables of
+//! an entry stay visible in the following entries of the [session](Session), so `var x = 3` can be followed by
+//! `x + 1`. As long as an entry has unclosed braces, brackets or parentheses, like after `if x {`, it is continued on
+//! the next line. In a terminal, the lines are [edited](editor) and the lines of the history file `~/.mylang_history`
+//! can be recalled with the arrow keys. Ctrl-C cancels the current entry, and Ctrl-D exits the REPL.
+//!
+//! Lines starting with `:` are [commands](HELP) of the REPL, e.g. to show the tokens of a snippet.
+
+mod editor;
+mod history;
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::{HashMap, HashSet},
+	io, mem,
+	ops::ControlFlow,
+	sync::Arc,
+};
+
+use anyhow::Context;
+use fortytwolang::{
+	ast::{
+		self,
+		expression::FunctionCall,
+		statement::{BasicDataType, DataType},
+		Expression, FunctionArgument, FunctionDefinition, FunctionPrototype, Instruction, Statement,
+	},
+	builtin::Builtin,
+	emitter,
+	error::DiagnosticSink,
+	interpreter::{Interpreter, StructValue, Value},
+	semantic_analyzer,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+	Emitter, Lexer, Parser, Source, Token, TokenKind,
+};
+
+use editor::Line;
+use history::History;
+
+use crate::Reporting;
+
+/// Name of the function whose body are the instructions of an entry. It cannot be called, since it is no identifier.
+const ENTRY_FUNCTION: &str = "<entry>";
+
+/// Prompt for the first line of an entry.
+const PROMPT: &str = ">>> ";
+
+/// Prompt for the following lines of an incomplete entry.
+const CONTINUATION_PROMPT: &str = "....: ";
+
+/// Description of the commands, which is shown by `:help`.
+const HELP: &str = "\
+:tokens <code>  Show the tokens of the code
+:ast <code>     Show the abstract syntax tree of the code
+:fmt            Show the previous entry formatted
+:help           Show this help
+:quit           Exit the REPL, like Ctrl-D";
+
+/// Reads entries from stdin and executes them until the end of the input, i.e. until Ctrl-D is pressed.
+pub fn run(reporting: Reporting) -> anyhow::Result<()> {
+	let mut history = History::open();
+	let mut repl = Repl::default();
+	loop {
+		let line = match editor::read_line(repl.prompt(), history.lines())? {
+			Line::Entered(line) => line,
+			// The cancelled line discards the incomplete entry as well
+			Line::Interrupted => {
+				repl.entry.clear();
+				continue;
+			},
+			// An incomplete entry is discarded
+			Line::End => return Ok(()),
+		};
+		if !line.trim().is_empty() {
+			history.append(line.trim_end());
+		}
+		match repl.line(&line) {
+			Ok(ControlFlow::Continue(())) => (),
+			Ok(ControlFlow::Break(())) => return Ok(()),
+			Err(err) => crate::print_error(err, reporting.color),
+		}
+	}
+}
+
+/// State of the REPL between the lines it reads.
+#[derive(Default)]
+struct Repl {
+	/// The lines of the entry so far, which is incomplete if it is not empty.
+	entry: String,
+	session: Session,
+	/// The last entry that was executed, which can be formatted.
+	previous_entry: Option<String>,
+}
+
+impl Repl {
+	/// The prompt for the next line, which shows whether it continues an incomplete entry.
+	fn prompt(&self) -> &'static str {
+		match self.entry.is_empty() {
+			true => PROMPT,
+			false => CONTINUATION_PROMPT,
+		}
+	}
+
+	/// Executes the `line` if it is a command, or adds it to the entry, which is executed once it is complete.
+	/// Returns [`ControlFlow::Break`] if the REPL should be exited.
+	fn line(&mut self, line: &str) -> anyhow::Result<ControlFlow<()>> {
+		if self.entry.is_empty() {
+			if line.trim().is_empty() {
+				return Ok(ControlFlow::Continue(()));
+			}
+			if line.starts_with(':') {
+				return command(line.trim(), self.previous_entry.as_deref());
+			}
+		}
+		self.entry.push_str(line);
+		self.entry.push('\n');
+		if !is_complete(&self.entry) {
+			return Ok(ControlFlow::Continue(()));
+		}
+
+		let entry = mem::take(&mut self.entry);
+		let entry = entry.trim_end();
+		self.previous_entry = Some(entry.to_owned());
+		self.session.evaluate(entry)?;
+		Ok(ControlFlow::Continue(()))
+	}
+}
+
+/// Whether all braces, brackets and parentheses opened in the `entry` are closed. An entry that cannot be lexed is
+/// complete, so that the error is reported right away.
+fn is_complete(entry: &str) -> bool {
+	let source = Arc::new(Source::new("<repl>".to_owned(), entry.to_owned()));
+	let Ok(tokens) = Lexer::new(source.iter()).collect::<Result<Vec<Token>, _>>() else {
+		return true;
+	};
+	let mut depth = 0;
+	for token in tokens {
+		match token.value {
+			TokenKind::OpeningCurlyBraces | TokenKind::OpeningSquareBrackets | TokenKind::OpeningParentheses => {
+				depth += 1
+			},
+			TokenKind::ClosingCurlyBraces | TokenKind::ClosingSquareBrackets | TokenKind::ClosingParentheses => {
+				depth -= 1
+			},
+			_ => (),
+		}
+	}
+	// Superfluous closing tokens are reported as error by the parser
+	depth <= 0
+}
+
+/// Executes a command like `:tokens 1 + 2`. Returns [`ControlFlow::Break`] if the REPL should be exited.
+fn command(line: &str, previous_entry: Option<&str>) -> anyhow::Result<ControlFlow<()>> {
+	let (name, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+	match name {
+		":tokens" => crate::print_tokens(&lex(argument)?),
+		":ast" => match parse(argument)? {
+			Entry::Definitions(definitions) => print!("{}", ast::pretty::nodes(&definitions)),
+			Entry::Instructions(function) => print!("{}", ast::pretty::block(&function.body)),
+		},
+		":fmt" => {
+			let entry = previous_entry.context("There is no previous entry to format")?;
+			match parse(entry)? {
+				Entry::Definitions(definitions) => emitter::Ftl::codegen(definitions.into_iter(), io::stdout().lock())?,
+				Entry::Instructions(function) => {
+					emitter::Ftl::codegen_block(function.body, io::stdout().lock())?;
+					println!();
+				},
+			}
+		},
+		":help" => println!("{}", HELP),
+		":quit" => return Ok(ControlFlow::Break(())),
+		_ => anyhow::bail!("Unknown command `{}`, see `:help`", name),
+	}
+	Ok(ControlFlow::Continue(()))
+}
+
+/// Definitions and variables of the previous entries, which are visible in the following entries.
+#[derive(Default)]
+struct Session {
+	/// The top-level definitions, like functions and structs. A definition replaces an earlier one with the same name.
+	definitions: Vec<ast::Node>,
+	/// The variables declared by the instructions, with their current values.
+	variables: HashMap<Symbol, Value>,
+}
+
+impl Session {
+	/// Lexes, parses, checks and executes a single entry.
+	fn evaluate(&mut self, entry: &str) -> anyhow::Result<()> {
+		match parse(entry)? {
+			Entry::Definitions(definitions) => self.define(definitions),
+			Entry::Instructions(mut function) => {
+				self.print_value(&mut function);
+				self.execute(*function)
+			},
+		}
+	}
+
+	/// Checks the `definitions` together with the earlier ones and adds them to the session.
+	fn define(&mut self, definitions: Vec<ast::Node>) -> anyhow::Result<()> {
+		let names = definitions.iter().filter_map(definition_name).collect::<HashSet<_>>();
+		let mut all_definitions = (self.definitions.iter())
+			.filter(|definition| definition_name(definition).is_none_or(|name| !names.contains(name)))
+			.cloned()
+			.collect::<Vec<_>>();
+		all_definitions.extend(definitions);
+		// Warnings like unused variables are expected for single lines, so they are not shown. A copy is analyzed,
+		// because the analysis replaces generic functions by their specializations for the calls so far
+		fortytwolang::semantic_analysis(&mut all_definitions.clone(), &DiagnosticSink::default())?;
+		self.definitions = all_definitions;
+		Ok(())
+	}
+
+	/// Checks and executes the instructions of the `function`, in which the variables of the session are declared.
+	fn execute(&mut self, mut function: FunctionDefinition) -> anyhow::Result<()> {
+		// The variables are declared as arguments for the type checker, unless the instructions declare them again
+		let redeclared = (function.body.iter())
+			.filter_map(|instruction| match instruction {
+				Instruction::Statement(Statement::VariableDeclaration(declaration)) => Some(&declaration.name.value),
+				Instruction::Statement(Statement::ConstDeclaration(declaration)) => Some(&declaration.name.value),
+				_ => None,
+			})
+			.collect::<HashSet<_>>();
+		let position = &function.prototype.name.position;
+		for (name, value) in &self.variables {
+			let Some(data_type) = self.data_type(value).filter(|_| !redeclared.contains(name)) else {
+				continue;
+			};
+			function.prototype.args.push(FunctionArgument {
+				name: PositionContainer::new(*name, position.clone()),
+				data_type: PositionContainer::new(data_type, position.clone()),
+			});
+		}
+
+		let mut ast_nodes = self.definitions.clone();
+		ast_nodes.push(ast::Node::Function(function));
+		fortytwolang::semantic_analysis(&mut ast_nodes, &DiagnosticSink::default())?;
+		let Some(ast::Node::Function(function)) = ast_nodes.pop() else {
+			unreachable!("The function of the entry was pushed last")
+		};
+		Interpreter::new(ast_nodes.into_iter()).run_block(&function.body, &mut self.variables)?;
+		Ok(())
+	}
+
+	/// Replaces an entry consisting of a single expression with a value by a call of `println()` with this
+	/// expression.
+	fn print_value(&self, function: &mut FunctionDefinition) {
+		let [Instruction::Expression(expression)] = &function.body[..] else {
+			return;
+		};
+		let has_value = match expression {
+			Expression::FunctionCall(function_call) if function_call.receiver.is_some() => {
+				self.method_prototype(&function_call.name).is_none_or(|prototype| prototype.return_type.is_some())
+			},
+			Expression::FunctionCall(function_call) => match self.prototype(&function_call.name) {
+				Some(prototype) => prototype.return_type.is_some(),
+				None => match Builtin::from_name(&function_call.name) {
+					Some(builtin) => builtin.return_type().is_some(),
+					// A method call on a variable like `p.length()`
+					None => function_call
+						.name
+						.rsplit_once('.')
+						.and_then(|(_, method)| self.method_prototype(method))
+						.is_none_or(|prototype| prototype.return_type.is_some()),
+				},
+			},
+			_ => true,
+		};
+		if has_value {
+			let Some(Instruction::Expression(expression)) = function.body.pop() else { unreachable!() };
+			let position = expression.source_position();
+			let name = PositionContainer::new(Symbol::intern(Builtin::Println.name()), position.clone());
+			function.body.push(Instruction::Expression(Expression::FunctionCall(FunctionCall {
+				name,
+				receiver: None,
+				params: vec![expression],
+				position,
+			})));
+		}
+	}
+
+	/// The type of a variable holding the `value`. An empty array has no type, but cannot be declared anyway.
+	fn data_type(&self, value: &Value) -> Option<DataType> {
+		Some(match value {
+			Value::Int(_) => DataType::Basic(BasicDataType::Int),
+			Value::Float(_) => DataType::Basic(BasicDataType::Float),
+			Value::Bool(_) => DataType::Basic(BasicDataType::Bool),
+			Value::Str(_) => DataType::Basic(BasicDataType::Str),
+			Value::Char(_) => DataType::Basic(BasicDataType::Char),
+			Value::Struct(struct_value) => self.struct_type(struct_value),
+			Value::Array(elements) => {
+				DataType::Array { element: Box::new(self.data_type(elements.first()?)?), length: elements.len() }
+			},
+			Value::Null => DataType::Null,
+		})
+	}
+
+	/// The type of the `struct_value`. The session only declares the generic structs, so the type arguments of a
+	/// specialization like `Pair.int.float` are inferred from the fields again.
+	fn struct_type(&self, struct_value: &StructValue) -> DataType {
+		for definition in &self.definitions {
+			let ast::Node::Struct(struct_) = definition else {
+				continue;
+			};
+			let mut bindings = HashMap::new();
+			for (name, value) in &struct_value.fields {
+				let field = struct_.fields.iter().find(|field| field.name.value == *name);
+				if let (Some(field), Some(data_type)) = (field, self.data_type(value)) {
+					semantic_analyzer::infer_type_arguments(
+						&struct_.type_parameters,
+						&field.data_type.value,
+						&data_type,
+						&mut bindings,
+					);
+				}
+			}
+			let type_arguments = (struct_.type_parameters.iter())
+				.map(|type_parameter| bindings.get(&type_parameter.value).cloned())
+				.collect::<Option<Vec<_>>>();
+			match type_arguments {
+				Some(type_arguments)
+					if struct_.is_generic()
+						&& semantic_analyzer::specialized_name(struct_.name.value, &type_arguments)
+							== struct_value.name =>
+				{
+					return DataType::Generic { name: struct_.name.value, type_arguments };
+				},
+				_ => (),
+			}
+		}
+		DataType::Struct(struct_value.name)
+	}
+
+	/// Looks up the prototype of a function or extern function defined in the session.
+	fn prototype(&self, name: &str) -> Option<&FunctionPrototype> {
+		self.definitions.iter().find_map(|definition| match definition {
+			ast::Node::Function(function) if *function.prototype.name == name => Some(&function.prototype),
+			ast::Node::FunctionPrototype(prototype) if *prototype.name == name => Some(prototype),
+			_ => None,
+		})
+	}
+
+	/// Looks up the prototype of a method like `Point.length` by the name of the `method` only, since the type of the
+	/// receiver is not known before the type check.
+	fn method_prototype(&self, method: &str) -> Option<&FunctionPrototype> {
+		let suffix = format!(".{method}");
+		self.definitions.iter().find_map(|definition| match definition {
+			ast::Node::Function(function) if function.prototype.name.ends_with(&suffix) => Some(&function.prototype),
+			_ => None,
+		})
+	}
+}
+
+/// The code of an entry.
+enum Entry {
+	/// Top-level definitions, which start with a keyword like `def` or `struct`.
+	Definitions(Vec<ast::Node>),
+	/// Instructions, which are executed as body of a function without arguments.
+	Instructions(Box<FunctionDefinition>),
+}
+
+/// Lexes the `code` of an entry.
+fn lex(code: &str) -> anyhow::Result<Vec<Token>> {
+	let source = Arc::new(Source::new("<repl>".to_owned(), code.to_owned()));
+	Lexer::new(source.iter()).collect::<Result<Vec<Token>, _>>().context("Lexing error")
+}
+
+/// Lexes and parses the `code` of an entry.
+fn parse(code: &str) -> anyhow::Result<Entry> {
+	let tokens = lex(code)?;
+	if let Some(Token { value: TokenKind::Def | TokenKind::Extern | TokenKind::Struct | TokenKind::Import, .. }) =
+		tokens.first()
+	{
+		let definitions = Parser::new(tokens.into_iter()).parse().context("Parser error")?;
+		anyhow::ensure!(
+			!definitions.iter().any(|definition| matches!(definition, ast::Node::Import(_))),
+			"Modules cannot be imported in the REPL"
+		);
+		return Ok(Entry::Definitions(definitions));
+	}
+
+	// The definition of the function and the call of `println()` are located at the start of the entry
+	let Some(position) = tokens.first().map(|token| token.position.clone()) else {
+		anyhow::bail!("There is no code to parse");
+	};
+	match wrap_in_function(tokens.clone(), &position) {
+		Ok(function) => Ok(Entry::Instructions(Box::new(function))),
+		// Expressions like `1 + 2` are no valid instructions, but can be parsed as parameter. Otherwise, the entry is
+		// invalid
+		Err(err) => {
+			let mut function = wrap_in_function(wrap_in_println(tokens, &position), &position).map_err(|_| err)?;
+			let Some(Instruction::Expression(Expression::FunctionCall(mut println))) = function.body.pop() else {
+				unreachable!("Parsed as call of `println()`")
+			};
+			function.body.push(Instruction::Expression(println.params.remove(0)));
+			Ok(Entry::Instructions(Box::new(function)))
+		},
+	}
+}
+
+/// Parses the `tokens` as body of a function, by surrounding them with the tokens of its definition.
+fn wrap_in_function(tokens: Vec<Token>, position: &SourcePositionRange) -> anyhow::Result<FunctionDefinition> {
+	let head = [
+		TokenKind::Def,
+		TokenKind::Identifier(Symbol::intern(ENTRY_FUNCTION)),
+		TokenKind::OpeningParentheses,
+		TokenKind::ClosingParentheses,
+		TokenKind::OpeningCurlyBraces,
+	];
+	let token = |kind| PositionContainer::new(kind, position.clone());
+	let tokens = head.into_iter().map(token).chain(tokens).chain([token(TokenKind::ClosingCurlyBraces)]);
+
+	let mut ast_nodes = Parser::new(tokens).parse().context("Parser error")?;
+	match ast_nodes.pop() {
+		Some(ast::Node::Function(main)) if ast_nodes.is_empty() => Ok(main),
+		_ => anyhow::bail!("Only instructions can be entered"),
+	}
+}
+
+/// Surrounds the tokens of an expression with the tokens of a call like `println((expression))`.
+fn wrap_in_println(tokens: Vec<Token>, position: &SourcePositionRange) -> Vec<Token> {
+	let token = |kind| PositionContainer::new(kind, position.clone());
+	let head = [
+		TokenKind::Identifier(Symbol::intern(Builtin::Println.name())),
+		TokenKind::OpeningParentheses,
+		TokenKind::OpeningParentheses,
+	];
+	let tail = [TokenKind::ClosingParentheses, TokenKind::ClosingParentheses];
+	head.into_iter().map(token).chain(tokens).chain(tail.into_iter().map(token)).collect()
+}
+
+/// The name of a top-level definition, if it has one.
+fn definition_name(definition: &ast::Node) -> Option<&str> {
+	match definition {
+		ast::Node::Function(function) => Some(&function.prototype.name),
+		ast::Node::FunctionPrototype(prototype) => Some(&prototype.name),
+		ast::Node::Struct(struct_) => Some(&struct_.name),
+		ast::Node::Enum(enum_) => Some(&enum_.name),
+		ast::Node::TypeAlias(type_alias) => Some(&type_alias.name),
+		ast::Node::Const(const_declaration) => Some(&const_declaration.name),
+		ast::Node::Import(_) | ast::Node::Comment(_) => None,
+	}
+}
@@ -0,0 +1,189 @@
+use std::{env, fs, io, ops::ControlFlow};
+
+use fortytwolang::{interpreter::Value, symbol::Symbol};
+
+use super::{
+	editor::{self, Editor, Key, Line},
+	history::History,
+	Repl, CONTINUATION_PROMPT, PROMPT,
+};
+
+/// Tests that the appended lines are read again from the history file, without repeating the same line.
+#[test]
+fn test_history_round_trip() {
+	let path = env::temp_dir().join(format!("fortytwolang_history_{}", std::process::id()));
+	let _ = fs::remove_file(&path);
+
+	let mut history = History::load(&path);
+	assert!(history.lines().is_empty());
+	for line in ["var x = 1", "x + 1", "x + 1", "def f() {", "}"] {
+		history.append(line);
+	}
+	drop(history);
+
+	let mut history = History::load(&path);
+	assert_eq!(history.lines(), ["var x = 1", "x + 1", "def f() {", "}"]);
+	history.append("f()");
+	assert_eq!(History::load(&path).lines().last().map(String::as_str), Some("f()"));
+	fs::remove_file(&path).unwrap();
+}
+
+/// Tests that the lines of the history are recalled from the newest, and that going down again restores the line
+/// being written.
+#[test]
+fn test_recall() {
+	let history = ["var x = 1".to_owned(), "x + 1".to_owned()];
+	let mut editor = Editor::new(&history);
+	type_text(&mut editor, "x");
+	assert_eq!(editor.press(Key::Up), None);
+	assert_eq!(editor.press(Key::Up), None);
+	// The oldest line stays recalled
+	assert_eq!(editor.press(Key::Up), None);
+	assert_eq!(enter(&mut editor), "var x = 1");
+
+	let mut editor = Editor::new(&history);
+	type_text(&mut editor, "x");
+	editor.press(Key::Up);
+	editor.press(Key::Down);
+	// There is no newer line
+	editor.press(Key::Down);
+	assert_eq!(enter(&mut editor), "x");
+}
+
+/// Tests that chars are inserted and deleted at the cursor.
+#[test]
+fn test_edit() {
+	let mut editor = Editor::new(&[]);
+	type_text(&mut editor, "größe");
+	editor.press(Key::Left);
+	editor.press(Key::Backspace);
+	editor.press(Key::Home);
+	editor.press(Key::Delete);
+	type_text(&mut editor, "G");
+	editor.press(Key::End);
+	editor.press(Key::Right);
+	type_text(&mut editor, "!");
+	assert_eq!(enter(&mut editor), "Gröe!");
+}
+
+/// Tests that Ctrl-C cancels the line, while Ctrl-D ends the input only on an empty line.
+#[test]
+fn test_interrupt_and_end() {
+	let mut editor = Editor::new(&[]);
+	type_text(&mut editor, "1 +");
+	assert_eq!(editor.press(Key::Interrupt), Some(Line::Interrupted));
+
+	let mut editor = Editor::new(&[]);
+	type_text(&mut editor, "ab");
+	editor.press(Key::Left);
+	assert_eq!(editor.press(Key::EndOfInput), None);
+	assert_eq!(editor.press(Key::Backspace), None);
+	assert_eq!(editor.press(Key::EndOfInput), Some(Line::End));
+}
+
+/// Tests that escape sequences, control chars and multi-byte chars are read as keys, and unknown sequences are
+/// skipped.
+#[test]
+fn test_read_key() {
+	let input = "\x1b[A\x1b[3~\x1bOH\x1b[5~ü\x03\r\x7f".bytes();
+	let mut bytes = input.map(Ok::<u8, io::Error>);
+	let mut keys = Vec::new();
+	while let Some(key) = editor::read_key(&mut bytes).unwrap() {
+		keys.push(key);
+	}
+	let expected = [Key::Up, Key::Delete, Key::Home, Key::Char('ü'), Key::Interrupt, Key::Enter, Key::Backspace];
+	assert_eq!(keys, expected);
+}
+
+/// Tests that an entry with unclosed braces, brackets or parentheses is continued on the next lines, and that an entry
+/// which cannot be lexed is complete.
+#[test]
+fn test_continuation() {
+	assert!(super::is_complete("1 + 2\n"));
+	assert!(!super::is_complete("def f(): int {\n"));
+	assert!(!super::is_complete("f([1,\n2]\n"));
+	assert!(super::is_complete("\"{\n"));
+	// Too many closing braces are reported by the parser
+	assert!(super::is_complete("}\n"));
+
+	let mut repl = Repl::default();
+	assert_eq!(repl.prompt(), PROMPT);
+	enter_lines(&mut repl, &["def f(): int {", "", "\treturn 2"]);
+	assert_eq!(repl.prompt(), CONTINUATION_PROMPT);
+	// A line starting with `:` in an incomplete entry is no command, so the entry cannot be parsed
+	assert_eq!(repl.line(":quit").unwrap(), ControlFlow::Continue(()));
+	assert_eq!(repl.prompt(), CONTINUATION_PROMPT);
+	assert!(repl.line("}").is_err());
+	assert_eq!(repl.prompt(), PROMPT);
+	enter_lines(&mut repl, &["def f(): int {", "\treturn 2", "}"]);
+	assert_eq!(repl.prompt(), PROMPT);
+	assert_eq!(repl.previous_entry.as_deref(), Some("def f(): int {\n\treturn 2\n}"));
+}
+
+/// Tests that lines starting with `:` are commands, which do not change the session.
+#[test]
+fn test_commands() {
+	let mut repl = Repl::default();
+	assert!(repl.line(":fmt").is_err(), "There is no previous entry");
+	for line in [":help", ":tokens var x = 1", ":ast var x = 1", "  "] {
+		assert_eq!(repl.line(line).unwrap(), ControlFlow::Continue(()), "{}", line);
+	}
+	assert!(repl.session.variables.is_empty());
+	enter_lines(&mut repl, &["var x = 1"]);
+	assert_eq!(repl.line(":fmt").unwrap(), ControlFlow::Continue(()));
+	assert!(repl.line(":unknown").is_err());
+	assert!(repl.line(":ast def").is_err());
+	assert!(repl.line(" :quit").is_err(), "Only a line starting with `:` is a command");
+	assert_eq!(repl.line(":quit").unwrap(), ControlFlow::Break(()));
+}
+
+/// Tests that the variables and definitions of an entry are visible in the following entries, that a definition
+/// replaces an earlier one with the same name, and that an erroneous entry keeps the session.
+#[test]
+fn test_session() {
+	let mut repl = Repl::default();
+	enter_lines(&mut repl, &["var x = 3", "x = x + 1"]);
+	assert_eq!(variable(&repl, "x"), Some(&Value::Int(4)));
+	// Functions cannot see the variables of the session, since they are no entries
+	assert!(repl.line("def g(): int {\n\treturn x\n}").is_err());
+	assert!(repl.session.definitions.is_empty());
+
+	enter_lines(&mut repl, &["def f(): int {", "\treturn 2", "}", "var y = f()", "y = y + x"]);
+	assert_eq!(variable(&repl, "y"), Some(&Value::Int(6)));
+	enter_lines(&mut repl, &["def f(): int {", "\treturn 5", "}", "y = f()"]);
+	assert_eq!(repl.session.definitions.len(), 1);
+	assert_eq!(variable(&repl, "y"), Some(&Value::Int(5)));
+
+	assert!(repl.line("y = undefined").is_err());
+	assert!(repl.line("var z = 1 / 0").is_err());
+	assert_eq!(variable(&repl, "y"), Some(&Value::Int(5)));
+	assert_eq!(variable(&repl, "z"), None);
+	assert_eq!(repl.prompt(), PROMPT);
+}
+
+/// Enters the `lines` into the REPL, expecting that none of them fails.
+fn enter_lines(repl: &mut Repl, lines: &[&str]) {
+	for line in lines {
+		assert_eq!(repl.line(line).unwrap(), ControlFlow::Continue(()), "{}", line);
+	}
+}
+
+/// The value of the variable `name` in the session of the REPL.
+fn variable<'a>(repl: &'a Repl, name: &str) -> Option<&'a Value> {
+	repl.session.variables.get(&Symbol::intern(name))
+}
+
+/// Presses the keys of the chars of the `text`.
+fn type_text(editor: &mut Editor, text: &str) {
+	for char in text.chars() {
+		assert_eq!(editor.press(Key::Char(char)), None);
+	}
+}
+
+/// Presses Enter and returns the entered line.
+fn enter(editor: &mut Editor) -> String {
+	match editor.press(Key::Enter) {
+		Some(Line::Entered(line)) => line,
+		line => panic!("Expected an entered line, found {:?}", line),
+	}
+}
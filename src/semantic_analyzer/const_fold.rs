@@ -0,0 +1,116 @@
+use std::cmp::Ordering;
+
+use crate::{
+	ast::{
+		expression::{BinaryOperator, NumberKind, UnaryOperator},
+		statement::{BasicDataType, DataType},
+		Expression,
+	},
+	builtin,
+	symbol::Symbol,
+};
+
+/// A value that is known at compile time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+	Int(i64),
+	Float(f64),
+	Bool(bool),
+	Char(char),
+}
+
+/// Evaluates the `expression` at compile time, if it only consists of literals, operators and constants, whose values
+/// are looked up by `constant`.
+///
+/// Returns [`None`] if the value is not known at compile time, or if the evaluation fails, e.g. because of a division by
+/// zero, which is reported at runtime instead. Like in the interpreter, integer arithmetic wraps around on overflow.
+pub fn fold(expression: &Expression, constant: &impl Fn(Symbol) -> Option<Constant>) -> Option<Constant> {
+	match expression {
+		Expression::Number(number) => Some(match number.value {
+			NumberKind::Int(int) => Constant::Int(int),
+			NumberKind::Float(float) => Constant::Float(float),
+		}),
+		Expression::Bool(bool) => Some(Constant::Bool(bool.value)),
+		Expression::Char(char) => Some(Constant::Char(char.value)),
+		Expression::Variable(name) => constant(name.value),
+		Expression::UnaryExpression(unary_expression) => {
+			match (&unary_expression.operator.value, fold(&unary_expression.operand, constant)?) {
+				(UnaryOperator::Negate, Constant::Int(int)) => Some(Constant::Int(int.wrapping_neg())),
+				(UnaryOperator::Negate, Constant::Float(float)) => Some(Constant::Float(-float)),
+				(UnaryOperator::Not, Constant::Bool(bool)) => Some(Constant::Bool(!bool)),
+				_ => None,
+			}
+		},
+		Expression::Cast(cast) => match (fold(&cast.expression, constant)?, &cast.data_type.value) {
+			(Constant::Int(int), DataType::Basic(BasicDataType::Float)) => Some(Constant::Float(int as f64)),
+			(Constant::Float(float), DataType::Basic(BasicDataType::Int)) => Some(Constant::Int(float as i64)),
+			(constant, _) => Some(constant),
+		},
+		Expression::BinaryExpression(binary_expression) => {
+			let lhs = fold(&binary_expression.lhs, constant)?;
+			let rhs = fold(&binary_expression.rhs, constant)?;
+			fold_binary(&binary_expression.operator.value, lhs, rhs)
+		},
+		Expression::If(if_expression) => match fold(&if_expression.condition, constant)? {
+			Constant::Bool(true) => fold(&if_expression.if_true, constant),
+			Constant::Bool(false) => fold(&if_expression.if_false, constant),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Applies the `operator` to two constants.
+fn fold_binary(operator: &BinaryOperator, lhs: Constant, rhs: Constant) -> Option<Constant> {
+	let ordering = match (lhs, rhs) {
+		(Constant::Int(lhs), Constant::Int(rhs)) => Some(lhs.cmp(&rhs)),
+		(Constant::Float(lhs), Constant::Float(rhs)) => lhs.partial_cmp(&rhs),
+		(Constant::Bool(lhs), Constant::Bool(rhs)) => Some(lhs.cmp(&rhs)),
+		(Constant::Char(lhs), Constant::Char(rhs)) => Some(lhs.cmp(&rhs)),
+		_ => return None,
+	};
+	if operator.is_comparison() {
+		// Comparisons with NaN are false, except for `=/=`
+		let Some(ordering) = ordering else {
+			return Some(Constant::Bool(*operator == BinaryOperator::NotEqual));
+		};
+		return Some(Constant::Bool(match operator {
+			BinaryOperator::Less => ordering == Ordering::Less,
+			BinaryOperator::Greater => ordering == Ordering::Greater,
+			BinaryOperator::LessEqual => ordering != Ordering::Greater,
+			BinaryOperator::GreaterEqual => ordering != Ordering::Less,
+			BinaryOperator::Equal => ordering == Ordering::Equal,
+			_ => ordering != Ordering::Equal,
+		}));
+	}
+	Some(match (lhs, rhs) {
+		(Constant::Int(lhs), Constant::Int(rhs)) => Constant::Int(match operator {
+			BinaryOperator::Add => lhs.wrapping_add(rhs),
+			BinaryOperator::Subtract => lhs.wrapping_sub(rhs),
+			BinaryOperator::Multiply => lhs.wrapping_mul(rhs),
+			BinaryOperator::Divide if rhs == 0 => return None,
+			BinaryOperator::Divide => lhs.wrapping_div(rhs),
+			BinaryOperator::Power => builtin::int_power(lhs, rhs),
+			BinaryOperator::BitOr => lhs | rhs,
+			BinaryOperator::BitAnd => lhs & rhs,
+			BinaryOperator::BitXor => lhs ^ rhs,
+			BinaryOperator::ShiftLeft => lhs.wrapping_shl(rhs as u32),
+			BinaryOperator::ShiftRight => lhs.wrapping_shr(rhs as u32),
+			_ => return None,
+		}),
+		(Constant::Float(lhs), Constant::Float(rhs)) => Constant::Float(match operator {
+			BinaryOperator::Add => lhs + rhs,
+			BinaryOperator::Subtract => lhs - rhs,
+			BinaryOperator::Multiply => lhs * rhs,
+			BinaryOperator::Divide => lhs / rhs,
+			BinaryOperator::Power => lhs.powf(rhs),
+			_ => return None,
+		}),
+		(Constant::Bool(lhs), Constant::Bool(rhs)) => Constant::Bool(match operator {
+			BinaryOperator::LogicalAnd => lhs && rhs,
+			BinaryOperator::LogicalOr => lhs || rhs,
+			_ => return None,
+		}),
+		_ => return None,
+	})
+}
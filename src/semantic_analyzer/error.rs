@@ -1,9 +1,14 @@
 use std::{ops::Deref, sync::Arc};
 
 use crate::{
-	ast::{expression::FunctionCall, statement::DataType},
+	ast::{
+		expression::{BinaryOperator, FunctionCall, UnaryOperator},
+		statement::{DataType, FunctionArgument},
+	},
+	builtin::Builtin,
 	semantic_analyzer::variable::Variable,
 	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 };
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
@@ -11,15 +16,142 @@ pub enum Error {
 	#[error("{}: Redeclaration: Variable `{new_declaration}` was previously declared as `{previous_declaration}`.", new_declaration.name.position)]
 	Redeclaration { previous_declaration: Arc<Variable>, new_declaration: Arc<Variable> },
 
+	#[error("{}: AssignmentToConstant: `{constant}` is a constant and cannot be assigned to.", name.position)]
+	AssignmentToConstant { name: PositionContainer<Symbol>, constant: Arc<Variable> },
+
+	#[error("{}: AssignmentTypeMismatch: {actual} cannot be assigned to `{}`, which has type {}.", position, variable.name.value, variable.type_)]
+	AssignmentTypeMismatch { variable: Arc<Variable>, position: SourcePositionRange, actual: DataType },
+
+	#[error(
+		"{}: NotConstant: The value of a constant may only consist of literals, constants and operators.",
+		position
+	)]
+	NotConstant { position: SourcePositionRange },
+
 	#[error("{}: UndeclaredVariable: Variable `{name}` is not declared.", name.position)]
-	UndeclaredVariable { name: PositionContainer<String> },
+	UndeclaredVariable { name: PositionContainer<Symbol> },
+
+	#[error("{}: UnknownStruct: Struct `{}` is not declared.", data_type.position, data_type.value)]
+	UnknownStruct { data_type: PositionContainer<DataType> },
+
+	#[error("{}: NoStruct: Field `{}` accessed on {data_type}, which is not a struct.", field.position, field.value)]
+	NoStruct { field: PositionContainer<Symbol>, data_type: DataType },
+
+	#[error("{}: UnknownField: Struct `{struct_name}` has no field `{}`.", field.position, field.value)]
+	UnknownField { field: PositionContainer<Symbol>, struct_name: Symbol },
+
+	#[error("{}: MissingField: Field `{field}` of struct `{}` has no value.", struct_name.position, struct_name.value)]
+	MissingField { struct_name: PositionContainer<Symbol>, field: Symbol },
+
+	#[error("{}: DuplicateField: Field `{}` has more than one value.", field.position, field.value)]
+	DuplicateField { field: PositionContainer<Symbol> },
+
+	#[error("{}: DuplicateFunction: Function `{}(...)` is defined more than once.", duplicate.position, duplicate.value)]
+	DuplicateFunction { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: DuplicateStruct: Struct `{}` is defined more than once.", duplicate.position, duplicate.value)]
+	DuplicateStruct { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: DuplicateEnum: A struct or enum named `{}` is already defined.", duplicate.position, duplicate.value)]
+	DuplicateEnum { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: DuplicateVariant: Enum has more than one variant named `{}`.", duplicate.position, duplicate.value)]
+	DuplicateVariant { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: DuplicateTypeAlias: A struct, enum or type alias named `{}` is already defined.", duplicate.position, duplicate.value)]
+	DuplicateTypeAlias { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: RecursiveTypeAlias: Type alias `{}` refers to itself.", alias.position, alias.value)]
+	RecursiveTypeAlias { alias: PositionContainer<Symbol> },
+
+	#[error("{}: UninferredTypeParameter: The type parameter `{}` of `{}` cannot be inferred from the given values.", name.position, type_parameter, name.value)]
+	UninferredTypeParameter { type_parameter: Symbol, name: PositionContainer<Symbol> },
+
+	#[error("{}: TooManySpecializations: Generic `{}` is specialized for more than {} different type arguments.", generic.position, generic.value, super::generic::MAX_SPECIALIZATIONS)]
+	TooManySpecializations { generic: PositionContainer<Symbol> },
+
+	#[error("{}: TypeArgumentCountMismatch: Struct `{}` expects {expected} type arguments, but {actual} are given.", name.position, name.value)]
+	TypeArgumentCountMismatch { name: PositionContainer<Symbol>, expected: usize, actual: usize },
+
+	#[error("{}: UnknownMethod: Method `{}` called on {data_type}, which has no such method.", method.position, method.value)]
+	UnknownMethod { method: PositionContainer<Symbol>, data_type: DataType },
+
+	#[error("{}: InvalidReceiver: Method `{}` has to take a value of the struct `{struct_name}` or a pointer to it as first argument.", method.position, method.value)]
+	InvalidReceiver { method: PositionContainer<Symbol>, struct_name: Symbol },
+
+	#[error("{}: UnknownVariant: Enum `{enum_name}` has no variant `{}`.", variant.position, variant.value)]
+	UnknownVariant { variant: PositionContainer<Symbol>, enum_name: Symbol },
+
+	#[error("{}: DuplicateStructField: Struct has more than one field named `{}`.", duplicate.position, duplicate.value)]
+	DuplicateStructField { previous: Box<SourcePositionRange>, duplicate: PositionContainer<Symbol> },
+
+	#[error("{}: NoArray: {data_type} cannot be indexed, because it is not an array.", position)]
+	NoArray { position: SourcePositionRange, data_type: DataType },
+
+	#[error("{}: EmptyArrayLiteral: The type of the elements of an empty array literal is unknown.", position)]
+	EmptyArrayLiteral { position: SourcePositionRange },
 
 	#[error("{}: TypeMismatch: expected {}, got {}", position, expected, actual)]
 	TypeMismatch { expected: DataType, position: SourcePositionRange, actual: DataType },
 
 	#[error("{}: UndefinedFunctionCall: Call of function `{}(...)`, but no such function is defined.", function_call.name.position, function_call.name.deref())]
-	UndefinedFunctionCall { function_call: FunctionCall },
+	UndefinedFunctionCall { function_call: Box<FunctionCall> },
 
 	#[error("{}: ArgumentCountMismatch: Function `{}(...)` expects {expected} arguments but {actual} parameters provided", function_call.name.position, function_call.name.value)]
-	ArgumentCountMismatch { expected: usize, actual: usize, function_call: FunctionCall },
+	ArgumentCountMismatch { expected: usize, actual: usize, function_call: Box<FunctionCall> },
+
+	#[error("{}: ArgumentTypeMismatch: Argument `{}` expects {}, but {actual} is provided.", position, argument.name.value, argument.data_type.value)]
+	ArgumentTypeMismatch { argument: Box<FunctionArgument>, position: SourcePositionRange, actual: DataType },
+
+	#[error("{}: MissingReturnValue: Function `{}(...)` has no return value, but its value is used.", function_call.name.position, function_call.name.value)]
+	MissingReturnValue { function_call: Box<FunctionCall> },
+
+	#[error("{}: ReturnWithoutValue: Function with return type {} has to return a value.", return_type.position, return_type.value)]
+	ReturnWithoutValue { return_type: PositionContainer<DataType> },
+
+	#[error("{}: UnexpectedReturnValue: Function without return type cannot return a value.", position)]
+	UnexpectedReturnValue { position: SourcePositionRange },
+
+	#[error("{}: MissingReturn: Function `{}(...)` with return type {} does not return a value on every path.", name.position, name.value, return_type)]
+	MissingReturn { name: PositionContainer<Symbol>, return_type: DataType },
+
+	#[error("{}: InvalidOperand: Operator {:?} cannot be applied to {data_type}.", operator.position, operator.value)]
+	InvalidOperand { operator: PositionContainer<BinaryOperator>, data_type: DataType },
+
+	#[error("{}: InvalidUnaryOperand: Operator {:?} cannot be applied to {data_type}.", operator.position, operator.value)]
+	InvalidUnaryOperand { operator: PositionContainer<UnaryOperator>, data_type: DataType },
+
+	#[error("{}: InvalidCast: {from} cannot be cast to {to}.", position)]
+	InvalidCast { position: SourcePositionRange, from: DataType, to: DataType },
+
+	#[error("{}: UntypedNull: The type of `{}` cannot be inferred from `null`, because it is a value of every pointer type.", name.position, name.value)]
+	UntypedNull { name: PositionContainer<Symbol> },
+
+	#[error("{}: BuiltinRedefinition: Function `{}` cannot be declared, because it is a builtin function.", name.position, name.value)]
+	BuiltinRedefinition { name: PositionContainer<Symbol> },
+
+	#[error("{}: InvalidBuiltinArgument: Builtin function `{}` cannot be called with {actual}.", position, builtin.name())]
+	InvalidBuiltinArgument { builtin: Builtin, position: SourcePositionRange, actual: DataType },
+
+	#[error(
+		"{}: NonConstantPattern: The pattern of a match arm may only consist of literals, constants and operators.",
+		position
+	)]
+	NonConstantPattern { position: SourcePositionRange },
+
+	#[error("{}: DuplicatePattern: The value {value} is already matched by a previous pattern.", duplicate)]
+	DuplicatePattern { previous: Box<SourcePositionRange>, duplicate: SourcePositionRange, value: String },
+
+	#[error(
+		"{}: MissingDefaultArm: The match has no default arm `_`, which is executed if no pattern matches.",
+		position
+	)]
+	MissingDefaultArm { position: SourcePositionRange },
+
+	#[error("{}: MissingVariants: The match has no default arm `_` and no arm for {}.", position, missing.iter().map(|variant| format!("`{}`", variant)).collect::<Vec<_>>().join(", "))]
+	MissingVariants { position: SourcePositionRange, missing: Vec<Symbol> },
+
+	/// Several errors, e.g. of different arguments of a function call, in the order they occurred.
+	#[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+	Multiple(Vec<Error>),
 }
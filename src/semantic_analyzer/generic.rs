@@ -0,0 +1,377 @@
+use std::{
+	collections::{HashMap, HashSet},
+	mem,
+};
+
+use crate::{
+	ast::{
+		expression::{UnaryExpression, UnaryOperator},
+		statement::{ConstDeclaration, DataType, LValue, VariableDeclaration},
+		visit::{self, VisitorMut},
+		Expression, FunctionDefinition, Instruction, Node, Struct,
+	},
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
+};
+
+/// The maximum number of specializations of a generic function or struct, which is only reached if it uses itself with
+/// ever larger type arguments.
+pub const MAX_SPECIALIZATIONS: usize = 64;
+
+/// A call of a generic function or method, or a literal of a generic struct, identified by the name of the function
+/// containing it, if any, and the position of the called function, method or struct name.
+pub(super) type Use = (Option<Symbol>, SourcePositionRange);
+
+/// The type arguments a generic function is called with or a generic struct is used with.
+#[derive(Debug, Clone)]
+pub(super) struct Instance {
+	/// The name of the generic function or struct.
+	pub generic: Symbol,
+	/// The types for the type parameters of the generic function or struct, in their order.
+	pub type_arguments: Vec<DataType>,
+}
+
+impl Instance {
+	/// The name of the specialization for the type arguments, like `max.int` or `swap.ptr_Point`.
+	pub fn specialized_name(&self) -> Symbol {
+		specialized_name(self.generic, &self.type_arguments)
+	}
+}
+
+/// The uses of generic functions and structs that the type checker found, whose specializations are not checked yet.
+#[derive(Debug, Default)]
+pub(super) struct Instances {
+	/// The calls of generic functions.
+	pub calls: HashMap<Use, Instance>,
+	/// The types of generic structs by the names of their specializations.
+	pub structs: HashMap<Symbol, Instance>,
+	/// The name of the specialization that each literal of a generic struct creates.
+	pub literals: HashMap<Use, Symbol>,
+	/// The name of the method that each method call calls, like `Point.length` for `point.length()`.
+	pub methods: HashMap<Use, Symbol>,
+	/// The inferred type of each variable or constant declared without type annotation, by the position of its name,
+	/// and of the value of each match, by the position of the match.
+	pub variables: HashMap<Use, DataType>,
+	/// The field accesses like `p.x` through a pointer to a struct, by the position of the field.
+	pub dereferences: HashSet<Use>,
+}
+
+/// The specializations of the generic functions and structs, which the type checker creates for the calls and types it
+/// checks.
+#[derive(Debug, Default)]
+pub struct Specializations {
+	/// The specialized copies of each generic function by the name of the generic function.
+	pub(super) functions: HashMap<Symbol, Vec<FunctionDefinition>>,
+	/// The specialized copies of each generic struct by the name of the generic struct.
+	pub(super) structs: HashMap<Symbol, Vec<Struct>>,
+	/// The name of the specialization that each call of a generic function calls, or that each literal of a generic
+	/// struct creates.
+	pub(super) uses: HashMap<Use, Symbol>,
+	/// The name of the method that each method call calls.
+	pub(super) methods: HashMap<Use, Symbol>,
+	/// The inferred type of each variable or constant declared without type annotation, and of the value of each match.
+	pub(super) variables: HashMap<Use, DataType>,
+	/// The field accesses through a pointer to a struct.
+	pub(super) dereferences: HashSet<Use>,
+}
+
+/// Replaces each generic function and struct in the `ast_nodes` by its `specializations`, and renames the calls, struct
+/// literals and types to the specializations they refer to, so that the backends see no type parameters anymore.
+/// Generic functions and structs which are never used are removed.
+///
+/// Method calls are replaced by calls of the methods with the receiver as first parameter, too, so that the backends
+/// only see plain calls. Variables and constants declared without type annotation get their inferred type as
+/// annotation, for backends like C which cannot infer types themselves. Field accesses through pointers like `p.x`
+/// get their implicit dereference, i.e. become `(*p).x`.
+#[tracing::instrument(skip_all)]
+pub fn monomorphize(ast_nodes: &mut Vec<Node>, specializations: Specializations) {
+	let Specializations { mut functions, structs, uses, methods, variables, dereferences } = specializations;
+	let mut structs = sort_by_containment(structs);
+	let mut renamer =
+		Renamer { uses: &uses, methods: &methods, variables: &variables, dereferences: &dereferences, function: None };
+	let mut monomorphized = Vec::with_capacity(ast_nodes.len());
+	for node in ast_nodes.drain(..) {
+		match node {
+			// The specializations replace the generic function at its place, so that the order of the other nodes
+			// stays the same, e.g. that `main` stays the last function of a REPL entry
+			Node::Function(function) if function.prototype.is_generic() => {
+				let mut specialized = functions.remove(&function.prototype.name.value).unwrap_or_default();
+				specialized.sort_by(|lhs, rhs| lhs.prototype.name.as_str().cmp(rhs.prototype.name.as_str()));
+				monomorphized.extend(specialized.into_iter().map(Node::Function));
+			},
+			Node::Struct(struct_) if struct_.is_generic() => {
+				let specialized = structs.remove(&struct_.name.value).unwrap_or_default();
+				monomorphized.extend(specialized.into_iter().map(Node::Struct));
+			},
+			node => monomorphized.push(node),
+		}
+	}
+	for node in &mut monomorphized {
+		renamer.visit_node(node);
+	}
+	*ast_nodes = monomorphized;
+}
+
+/// The name of the specialization of the `generic` function or struct for the `type_arguments`.
+pub fn specialized_name(generic: Symbol, type_arguments: &[DataType]) -> Symbol {
+	let type_arguments: Vec<_> = type_arguments.iter().map(mangle).collect();
+	Symbol::from(format!("{}.{}", generic, type_arguments.join(".")))
+}
+
+/// Sorts the specializations of each generic struct so that a specialization comes after the specializations it
+/// contains by value, which e.g. the C emitter has to define first. Otherwise, they are sorted by name.
+fn sort_by_containment(structs: HashMap<Symbol, Vec<Struct>>) -> HashMap<Symbol, Vec<Struct>> {
+	let mut all: Vec<_> =
+		structs.into_iter().flat_map(|(generic, structs)| structs.into_iter().map(move |s| (generic, s))).collect();
+	all.sort_by(|(_, lhs), (_, rhs)| lhs.name.as_str().cmp(rhs.name.as_str()));
+	let by_name: HashMap<_, _> =
+		all.iter().enumerate().map(|(index, (_, struct_))| (struct_.name.value, index)).collect();
+
+	// Depth-first search, which adds a specialization after the ones its fields contain
+	fn visit(
+		index: usize,
+		all: &[(Symbol, Struct)],
+		by_name: &HashMap<Symbol, usize>,
+		visited: &mut HashSet<usize>,
+		order: &mut Vec<usize>,
+	) {
+		if !visited.insert(index) {
+			return;
+		}
+		for field in &all[index].1.fields {
+			for contained in contained_structs(&field.data_type.value) {
+				if let Some(&contained) = by_name.get(&contained) {
+					visit(contained, all, by_name, visited, order);
+				}
+			}
+		}
+		order.push(index);
+	}
+	let mut visited = HashSet::new();
+	let mut order = Vec::new();
+	for index in 0..all.len() {
+		visit(index, &all, &by_name, &mut visited, &mut order);
+	}
+
+	let mut all: Vec<_> = all.into_iter().map(Some).collect();
+	let mut sorted: HashMap<Symbol, Vec<Struct>> = HashMap::new();
+	for index in order {
+		let (generic, struct_) = all[index].take().unwrap();
+		sorted.entry(generic).or_default().push(struct_);
+	}
+	sorted
+}
+
+/// The names of the specializations that a value of the `data_type` contains, i.e. not behind pointers.
+fn contained_structs(data_type: &DataType) -> Vec<Symbol> {
+	match data_type {
+		DataType::Generic { name, type_arguments } => vec![specialized_name(*name, type_arguments)],
+		DataType::Array { element, .. } => contained_structs(element),
+		_ => Vec::new(),
+	}
+}
+
+/// A copy of the generic `function` with the name `name`, whose type parameters are replaced by the `type_arguments`.
+pub(super) fn specialize(
+	function: &FunctionDefinition,
+	name: Symbol,
+	type_arguments: &[DataType],
+) -> FunctionDefinition {
+	let mut specialized = function.clone();
+	let bindings = function.prototype.type_parameters.iter().map(|type_parameter| type_parameter.value);
+	let mut substitution = Substitution(bindings.zip(type_arguments.iter().cloned()).collect());
+	substitution.visit_function(&mut specialized);
+	specialized.prototype.name.value = name;
+	specialized.prototype.type_parameters.clear();
+	specialized
+}
+
+/// A copy of the generic `struct_` with the name `name`, whose type parameters are replaced by the `type_arguments`.
+pub(super) fn specialize_struct(struct_: &Struct, name: Symbol, type_arguments: &[DataType]) -> Struct {
+	let mut specialized = struct_.clone();
+	let bindings = struct_.type_parameters.iter().map(|type_parameter| type_parameter.value);
+	let mut substitution = Substitution(bindings.zip(type_arguments.iter().cloned()).collect());
+	substitution.visit_struct(&mut specialized);
+	specialized.name.value = name;
+	specialized.type_parameters.clear();
+	specialized
+}
+
+/// Binds the type parameters in the type of an `argument` to the corresponding parts of the type of the `parameter`
+/// that is passed for it, e.g. `T` to `int` for the argument type `ptr T` and the parameter type `ptr int`. A type
+/// parameter keeps the type it is bound to first, so that a mismatch is reported for the later arguments.
+pub fn infer_type_arguments(
+	type_parameters: &[PositionContainer<Symbol>],
+	argument: &DataType,
+	parameter: &DataType,
+	bindings: &mut HashMap<Symbol, DataType>,
+) {
+	match (argument, parameter) {
+		// `null` is a value of every pointer type, so it does not determine the type parameter
+		(_, DataType::Null) => (),
+		(DataType::Struct(name), _) if type_parameters.iter().any(|type_parameter| **type_parameter == *name) => {
+			bindings.entry(*name).or_insert_with(|| parameter.clone());
+		},
+		(
+			DataType::Generic { name: argument_name, type_arguments: arguments },
+			DataType::Generic { name: parameter_name, type_arguments: parameters },
+		) if argument_name == parameter_name => {
+			for (argument, parameter) in arguments.iter().zip(parameters) {
+				infer_type_arguments(type_parameters, argument, parameter, bindings);
+			}
+		},
+		(DataType::Pointer(argument), DataType::Pointer(parameter)) => {
+			infer_type_arguments(type_parameters, &argument.value, &parameter.value, bindings)
+		},
+		(DataType::Array { element: argument, .. }, DataType::Array { element: parameter, .. }) => {
+			infer_type_arguments(type_parameters, argument, parameter, bindings)
+		},
+		_ => (),
+	}
+}
+
+/// Replaces the type parameters in the `data_type`, possibly behind pointers or in arrays, by the types they are bound
+/// to in the `bindings`.
+pub(super) fn substitute(data_type: &mut DataType, bindings: &HashMap<Symbol, DataType>) {
+	match data_type {
+		DataType::Struct(name) => {
+			if let Some(type_argument) = bindings.get(name) {
+				*data_type = type_argument.clone();
+			}
+		},
+		DataType::Generic { type_arguments, .. } => {
+			type_arguments.iter_mut().for_each(|type_argument| substitute(type_argument, bindings))
+		},
+		DataType::Pointer(pointee) => substitute(&mut pointee.value, bindings),
+		DataType::Array { element, .. } => substitute(element, bindings),
+		DataType::Basic(_) | DataType::Enum(_) | DataType::Null => (),
+	}
+}
+
+/// The part of a specialized name for the `data_type`, which contains no spaces, unlike its [`Display`] output.
+///
+/// [`Display`]: std::fmt::Display
+fn mangle(data_type: &DataType) -> String {
+	match data_type {
+		DataType::Basic(basic_data_type) => basic_data_type.to_string(),
+		DataType::Struct(name) | DataType::Enum(name) => name.to_string(),
+		DataType::Generic { name, type_arguments } => specialized_name(*name, type_arguments).to_string(),
+		DataType::Pointer(pointee) => format!("ptr_{}", mangle(&pointee.value)),
+		DataType::Array { element, length } => format!("arr{}_{}", length, mangle(element)),
+		DataType::Null => "null".to_owned(),
+	}
+}
+
+/// Replaces the type parameters in all types of a function by the types they are bound to.
+struct Substitution(HashMap<Symbol, DataType>);
+
+impl VisitorMut for Substitution {
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		substitute(&mut data_type.value, &self.0);
+	}
+}
+
+/// Renames the uses of generic functions and structs to the specializations they refer to, after desugaring method
+/// calls and field accesses through pointers, and annotating the inferred types of declarations.
+struct Renamer<'a> {
+	uses: &'a HashMap<Use, Symbol>,
+	methods: &'a HashMap<Use, Symbol>,
+	variables: &'a HashMap<Use, DataType>,
+	dereferences: &'a HashSet<Use>,
+	/// The name of the function whose uses are renamed, or [`None`] outside of functions.
+	function: Option<Symbol>,
+}
+
+impl VisitorMut for Renamer<'_> {
+	fn visit_function(&mut self, function: &mut FunctionDefinition) {
+		self.function = Some(function.prototype.name.value);
+		visit::walk_function_mut(self, function);
+		self.function = None;
+	}
+
+	fn visit_expression(&mut self, expression: &mut Expression) {
+		if let Expression::FunctionCall(function_call) = expression {
+			if let Some(method) = self.methods.get(&(self.function, function_call.name.position.clone())) {
+				function_call.desugar_method(*method);
+			}
+		}
+		if let Expression::FieldAccess(field_access) = expression {
+			if self.dereferences.contains(&(self.function, field_access.field.position.clone())) {
+				let position = field_access.base.source_position();
+				let placeholder = Expression::Null(position.clone());
+				let base = mem::replace(&mut *field_access.base, placeholder);
+				*field_access.base = Expression::UnaryExpression(UnaryExpression {
+					operator: PositionContainer::new(UnaryOperator::Dereference, position),
+					operand: Box::new(base),
+				});
+			}
+		}
+		let name = match expression {
+			Expression::FunctionCall(function_call) => Some(&mut function_call.name),
+			Expression::StructLiteral(struct_literal) => Some(&mut struct_literal.name),
+			_ => None,
+		};
+		if let Some(name) = name {
+			if let Some(specialized) = self.uses.get(&(self.function, name.position.clone())) {
+				name.value = *specialized;
+			}
+		}
+		visit::walk_expression_mut(self, expression);
+	}
+
+	fn visit_instruction(&mut self, instruction: &mut Instruction) {
+		if let Instruction::Match(match_) = instruction {
+			self.annotate(&match_.position, &mut match_.value_type);
+		}
+		visit::walk_instruction_mut(self, instruction);
+	}
+
+	fn visit_lvalue(&mut self, lvalue: &mut LValue) {
+		if let LValue::FieldAccess { base, field } = lvalue {
+			if self.dereferences.contains(&(self.function, field.position.clone())) {
+				let position = base.source_position();
+				let placeholder = LValue::Variable(base.variable().clone());
+				let pointer = mem::replace(&mut **base, placeholder);
+				**base = LValue::Dereference { position, base: Box::new(pointer) };
+			}
+		}
+		visit::walk_lvalue_mut(self, lvalue);
+	}
+
+	fn visit_variable_declaration(&mut self, variable_declaration: &mut VariableDeclaration) {
+		self.annotate(&variable_declaration.name.position, &mut variable_declaration.data_type);
+		visit::walk_variable_declaration_mut(self, variable_declaration);
+	}
+
+	fn visit_const_declaration(&mut self, const_declaration: &mut ConstDeclaration) {
+		self.annotate(&const_declaration.name.position, &mut const_declaration.data_type);
+		visit::walk_const_declaration_mut(self, const_declaration);
+	}
+
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		specialize_data_type(&mut data_type.value);
+	}
+}
+
+impl Renamer<'_> {
+	/// Sets the `data_type` of the declaration or match at `position` to the inferred type, unless it is annotated.
+	fn annotate(&self, position: &SourcePositionRange, data_type: &mut Option<PositionContainer<DataType>>) {
+		if data_type.is_none() {
+			let inferred_type = self.variables.get(&(self.function, position.clone()));
+			*data_type =
+				inferred_type.map(|inferred_type| PositionContainer::new(inferred_type.clone(), position.clone()));
+		}
+	}
+}
+
+/// Replaces the generic struct types in the `data_type` by the specializations for their type arguments.
+fn specialize_data_type(data_type: &mut DataType) {
+	match data_type {
+		DataType::Generic { name, type_arguments } => {
+			*data_type = DataType::Struct(specialized_name(*name, type_arguments))
+		},
+		DataType::Pointer(pointee) => specialize_data_type(&mut pointee.value),
+		DataType::Array { element, .. } => specialize_data_type(element),
+		DataType::Basic(_) | DataType::Struct(_) | DataType::Enum(_) | DataType::Null => (),
+	}
+}
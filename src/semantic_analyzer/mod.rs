@@ -1,11 +1,18 @@
 //! Creation of a [`SymbolTable`] and [type checking](TypeChecker).
 
+pub(crate) mod const_fold;
 mod error;
+mod generic;
 mod symbol_table;
+#[cfg(test)]
+mod test;
+mod type_alias;
 mod type_check;
 mod variable;
 
 pub use error::Error;
+pub use generic::{infer_type_arguments, monomorphize, specialized_name, Specializations};
 pub use symbol_table::SymbolTable;
+pub use type_alias::resolve_aliases;
 pub use type_check::TypeChecker;
 pub use variable::Variable;
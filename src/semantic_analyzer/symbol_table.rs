@@ -1,23 +1,35 @@
-use std::{collections::HashMap, convert::Infallible, ops::Deref};
+use std::collections::{hash_map::Entry, HashMap};
 
 use crate::{
 	ast,
-	ast::{FunctionPrototype, Struct},
+	ast::{Enum, FunctionPrototype, Struct, TypeAlias},
+	builtin::Builtin,
+	semantic_analyzer::Error,
+	source::PositionContainer,
+	symbol::Symbol,
 };
 
-/// Contains all globally declared [functions](Self::functions) and [structs](Self::structs).
+/// Contains all globally declared [functions](Self::functions), [structs](Self::structs), [enums](Self::enums) and
+/// [type aliases](Self::type_aliases).
 #[derive(Debug, Default, Clone)]
 pub struct SymbolTable {
 	/// All declared functions in the program, as discovered by the [global symbol scan](Self::global_symbol_scan).
-	pub functions: HashMap<String, FunctionPrototype>,
+	pub functions: HashMap<Symbol, FunctionPrototype>,
 	/// All declared structs in the program, as discovered by the [global symbol scan](Self::global_symbol_scan).
-	pub structs: HashMap<String, Struct>,
+	pub structs: HashMap<Symbol, Struct>,
+	/// All declared enums in the program, as discovered by the [global symbol scan](Self::global_symbol_scan).
+	pub enums: HashMap<Symbol, Enum>,
+	/// All declared type aliases in the program, whose uses are already [resolved](super::resolve_aliases).
+	pub type_aliases: HashMap<Symbol, TypeAlias>,
 }
 
 impl SymbolTable {
 	/// Generates a [`SymbolTable`] by scanning the program for global symbols like [struct](crate::ast::struct_) and [function definitions](crate::ast::FunctionDefinition).
+	///
+	/// Fails if two functions or two structs, enums or type aliases have the same name, or if a struct has two fields or
+	/// an enum has two variants with the same name.
 	#[tracing::instrument(skip_all)]
-	pub fn global_symbol_scan<'a>(ast_nodes: impl Iterator<Item = &'a ast::Node>) -> Result<Self, Infallible> {
+	pub fn global_symbol_scan<'a>(ast_nodes: impl Iterator<Item = &'a ast::Node>) -> Result<Self, Error> {
 		let mut symbol_table = SymbolTable::default();
 		for ast_node in ast_nodes {
 			symbol_table.ast_node(ast_node)?;
@@ -26,24 +38,119 @@ impl SymbolTable {
 	}
 
 	/// Scans one AST node for global symbols, i.e. functions and structs.
-	fn ast_node(&mut self, node: &ast::Node) -> Result<(), Infallible> {
+	fn ast_node(&mut self, node: &ast::Node) -> Result<(), Error> {
 		match node {
 			ast::Node::Function(function) => self.function(&function.prototype),
 			ast::Node::Struct(struct_) => self.struct_(struct_),
+			ast::Node::Enum(enum_) => self.enum_(enum_),
+			ast::Node::TypeAlias(type_alias) => self.type_alias(type_alias),
 			ast::Node::FunctionPrototype(function_prototype) => self.function(function_prototype),
-			_ => todo!(),
+			// Constants are declared by the type checker, because their type may have to be inferred
+			ast::Node::Const(_) => Ok(()),
+			ast::Node::Import(_) | ast::Node::Comment(_) => Ok(()),
 		}
 	}
 
-	/// Adds a function to the [functions symbol table](Self::functions).
-	fn function(&mut self, function_prototype: &FunctionPrototype) -> Result<(), Infallible> {
-		self.functions.insert(function_prototype.name.deref().clone(), function_prototype.clone());
-		Ok(())
+	/// Adds a function to the [functions symbol table](Self::functions). Its name must not be one of a [`Builtin`].
+	fn function(&mut self, function_prototype: &FunctionPrototype) -> Result<(), Error> {
+		if Builtin::from_name(&function_prototype.name).is_some() {
+			return Err(Error::BuiltinRedefinition { name: function_prototype.name.clone() });
+		}
+		match self.functions.entry(function_prototype.name.value) {
+			Entry::Occupied(previous) => Err(Error::DuplicateFunction {
+				previous: Box::new(previous.get().name.position.clone()),
+				duplicate: function_prototype.name.clone(),
+			}),
+			Entry::Vacant(entry) => {
+				entry.insert(function_prototype.clone());
+				Ok(())
+			},
+		}
 	}
 
 	/// Adds a struct to the [structs symbol table](Self::structs).
-	fn struct_(&mut self, struct_: &Struct) -> Result<(), Infallible> {
-		self.structs.insert(struct_.name.deref().clone(), struct_.clone());
+	fn struct_(&mut self, struct_: &Struct) -> Result<(), Error> {
+		for (i, field) in struct_.fields.iter().enumerate() {
+			if let Some(previous) = struct_.fields[..i].iter().find(|previous| previous.name.value == field.name.value)
+			{
+				return Err(Error::DuplicateStructField {
+					previous: Box::new(previous.name.position.clone()),
+					duplicate: field.name.clone(),
+				});
+			}
+		}
+		if let Some(enum_) = self.enums.get(&struct_.name.value) {
+			return Err(Error::DuplicateEnum {
+				previous: Box::new(enum_.name.position.clone()),
+				duplicate: struct_.name.clone(),
+			});
+		}
+		self.check_type_alias_name(&struct_.name)?;
+		match self.structs.entry(struct_.name.value) {
+			Entry::Occupied(previous) => Err(Error::DuplicateStruct {
+				previous: Box::new(previous.get().name.position.clone()),
+				duplicate: struct_.name.clone(),
+			}),
+			Entry::Vacant(entry) => {
+				entry.insert(struct_.clone());
+				Ok(())
+			},
+		}
+	}
+
+	/// Adds an enum to the [enums symbol table](Self::enums). Structs and enums share one namespace, since both are
+	/// referred to by their name in types.
+	fn enum_(&mut self, enum_: &Enum) -> Result<(), Error> {
+		for (i, variant) in enum_.variants.iter().enumerate() {
+			if let Some(previous) =
+				enum_.variants[..i].iter().find(|previous| previous.name.value == variant.name.value)
+			{
+				return Err(Error::DuplicateVariant {
+					previous: Box::new(previous.name.position.clone()),
+					duplicate: variant.name.clone(),
+				});
+			}
+		}
+		let previous = match (self.structs.get(&enum_.name.value), self.enums.get(&enum_.name.value)) {
+			(Some(struct_), _) => Some(&struct_.name),
+			(_, Some(previous)) => Some(&previous.name),
+			(None, None) => None,
+		};
+		if let Some(previous) = previous {
+			return Err(Error::DuplicateEnum {
+				previous: Box::new(previous.position.clone()),
+				duplicate: enum_.name.clone(),
+			});
+		}
+		self.check_type_alias_name(&enum_.name)?;
+		self.enums.insert(enum_.name.value, enum_.clone());
+		Ok(())
+	}
+
+	/// Adds a type alias to the [type aliases symbol table](Self::type_aliases). Like structs and enums, it is referred
+	/// to by its name in types, so it shares their namespace.
+	fn type_alias(&mut self, type_alias: &TypeAlias) -> Result<(), Error> {
+		let previous = (self.structs.get(&type_alias.name.value).map(|struct_| &struct_.name))
+			.or_else(|| self.enums.get(&type_alias.name.value).map(|enum_| &enum_.name));
+		if let Some(previous) = previous {
+			return Err(Error::DuplicateTypeAlias {
+				previous: Box::new(previous.position.clone()),
+				duplicate: type_alias.name.clone(),
+			});
+		}
+		self.check_type_alias_name(&type_alias.name)?;
+		self.type_aliases.insert(type_alias.name.value, type_alias.clone());
 		Ok(())
 	}
+
+	/// Fails if a type alias with the `name` of a new struct, enum or type alias is already declared.
+	fn check_type_alias_name(&self, name: &PositionContainer<Symbol>) -> Result<(), Error> {
+		match self.type_aliases.get(&name.value) {
+			Some(previous) => Err(Error::DuplicateTypeAlias {
+				previous: Box::new(previous.name.position.clone()),
+				duplicate: name.clone(),
+			}),
+			None => Ok(()),
+		}
+	}
 }
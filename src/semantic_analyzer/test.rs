@@ -0,0 +1,979 @@
+use crate::{
+	ast::{
+		expression::UnaryOperator,
+		statement::{BasicDataType, DataType, LValue},
+		Expression, Instruction, Node, Statement,
+	},
+	error::DiagnosticSink,
+	parser,
+	semantic_analyzer::{monomorphize, resolve_aliases, Error, SymbolTable, TypeChecker},
+	symbol::Symbol,
+};
+
+/// Tests that a valid program with loops, calls and functions without return value passes.
+#[test]
+fn test_valid_program() {
+	let source_code = "
+		def square(x: int): int {
+			return x * x
+		}
+		def main(): int {
+			var i: int = 0
+			var sum: int = 0
+			while i < 4 {
+				sum = sum + square(i)
+				i = i + 1
+			}
+			print(sum)
+			return sum
+		}
+	";
+	assert_eq!(type_check(source_code), Ok(()));
+}
+
+/// Tests that arithmetic on structs is rejected.
+#[test]
+fn test_struct_operand() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def add(p: Point): int {
+			return p + 1
+		}
+	";
+	assert!(matches!(type_check(source_code), Err(Error::InvalidOperand { .. })));
+}
+
+/// Tests that a parameter of the wrong type is rejected.
+#[test]
+fn test_argument_type_mismatch() {
+	let source_code = "
+		def half(x: float): float {
+			return x / 2.0
+		}
+		def main(): float {
+			return half(3)
+		}
+	";
+	assert!(matches!(
+		type_check(source_code),
+		Err(Error::ArgumentTypeMismatch { actual: DataType::Basic(BasicDataType::Int), .. })
+	));
+}
+
+/// Tests that all wrong parameters of a function call are reported, in addition to a wrong number of parameters.
+#[test]
+fn test_argument_errors_per_parameter() {
+	let source_code = "
+		def add(x: float, y: float): float {
+			return x + y
+		}
+		def main(): float {
+			return add(1, true, 2.0)
+		}
+	";
+	let Err(Error::Multiple(errors)) = type_check(source_code) else {
+		panic!("expected multiple errors");
+	};
+	assert!(matches!(
+		errors.as_slice(),
+		[
+			Error::ArgumentCountMismatch { expected: 2, actual: 3, .. },
+			Error::ArgumentTypeMismatch { actual: DataType::Basic(BasicDataType::Int), .. },
+			Error::ArgumentTypeMismatch { actual: DataType::Basic(BasicDataType::Bool), .. },
+		]
+	));
+}
+
+/// Tests that the returned value has to match the return type of the function.
+#[test]
+fn test_return_type_mismatch() {
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn 1.5\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(type_check("def main() {\n\treturn 1\n}"), Err(Error::UnexpectedReturnValue { .. })));
+}
+
+/// Tests that a bare `return` is only allowed in functions without return type.
+#[test]
+fn test_bare_return() {
+	assert_eq!(type_check("def main() {\n\tif true {\n\t\treturn\n\t}\n\treturn;\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn\n}"),
+		Err(Error::ReturnWithoutValue { return_type }) if return_type.value == DataType::Basic(BasicDataType::Int)
+	));
+}
+
+/// Tests that functions with return type have to return on every path through `if`/`else` and loops.
+#[test]
+fn test_missing_return() {
+	assert_eq!(
+		type_check("def f(x: int): int {\n\tif x < 0 {\n\t\treturn 0\n\t} else {\n\t\treturn 1\n\t}\n}"),
+		Ok(())
+	);
+	assert_eq!(type_check("def f(): int {\n\twhile true {\n\t\treturn 1\n\t}\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def sign(x: int): int {\n\tif x < 0 {\n\t\treturn 0 - 1\n\t}\n}"),
+		Err(Error::MissingReturn { name, .. }) if name.value == "sign"
+	));
+	assert!(matches!(
+		type_check("def f(x: int): int {\n\twhile true {\n\t\tif x > 0 {\n\t\t\tbreak\n\t\t}\n\t}\n}"),
+		Err(Error::MissingReturn { .. })
+	));
+	assert!(matches!(
+		type_check("def f(x: int): int {\n\twhile x > 0 {\n\t\treturn 1\n\t}\n}"),
+		Err(Error::MissingReturn { .. })
+	));
+}
+
+/// Tests that the value of a function without return value cannot be used.
+#[test]
+fn test_missing_return_value() {
+	let source_code = "
+		def main(): int {
+			return print(1)
+		}
+	";
+	assert!(matches!(type_check(source_code), Err(Error::MissingReturnValue { .. })));
+}
+
+/// Tests that a variable without type annotation gets the type of its initial value.
+#[test]
+fn test_inferred_variable_type() {
+	assert_eq!(type_check("def main(): float {\n\tvar x = 1.5\n\treturn x * 2.0\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): int {\n\tvar x = 1.5\n\treturn x\n}"),
+		Err(Error::TypeMismatch {
+			expected: DataType::Basic(BasicDataType::Int),
+			actual: DataType::Basic(BasicDataType::Float),
+			..
+		})
+	));
+	assert!(matches!(
+		type_check("def main() {\n\tvar x: bool = 1\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Bool), .. })
+	));
+}
+
+/// Tests that a later assignment has to match the type inferred for the variable, and that the error refers to both
+/// the declaration and the assignment.
+#[test]
+fn test_assignment_to_inferred_variable() {
+	assert_eq!(type_check("def main(): float {\n\tvar x = 3.5\n\tx = 1.0\n\treturn x\n}"), Ok(()));
+	let source_code = "struct P {\n\tx: int\n}\ndef main() {\n\tvar x = 3.5\n\tx = P { x: 1 }\n}";
+	let Err(Error::AssignmentTypeMismatch { variable, position, actual }) = type_check(source_code) else {
+		panic!("conflicting assignment not detected");
+	};
+	assert_eq!(variable.type_, DataType::Basic(BasicDataType::Float));
+	assert_eq!(actual, DataType::Struct(Symbol::intern("P")));
+	assert_eq!((variable.name.position.position.start.line, position.position.start.line), (5, 6));
+}
+
+/// Tests that an assigned value has to match the type of the field or element, and that only fields of structs can be
+/// assigned to.
+#[test]
+fn test_field_assignment() {
+	let struct_ = "struct P {\n\tx: int\n}\n";
+	assert_eq!(type_check(&format!("{}def main() {{\n\tvar p = P {{ x: 1 }}\n\tp.x += 2\n}}", struct_)), Ok(()));
+	assert!(matches!(
+		type_check(&format!("{}def main() {{\n\tvar p = P {{ x: 1 }}\n\tp.x = 2.0\n}}", struct_)),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check(&format!("{}def main() {{\n\tvar p = P {{ x: 1 }}\n\tp.y = 2\n}}", struct_)),
+		Err(Error::UnknownField { field, .. }) if field.value == "y"
+	));
+	assert!(matches!(type_check("def main() {\n\tvar x = 1\n\tx.y = 2\n}"), Err(Error::NoStruct { .. })));
+	assert!(matches!(
+		type_check("def main() {\n\tvar a = [[1, 2], [3, 4]]\n\ta[0][1] = true\n}"),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Bool), .. })
+	));
+}
+
+/// Tests that `null` can be used wherever a pointer is expected, but not as a value of other types, and that it does
+/// not determine the type of a variable.
+#[test]
+fn test_null() {
+	let source_code = "
+		struct Node {
+			next: ptr Node
+		}
+		def last(node: ptr Node): ptr Node {
+			return null
+		}
+		def main() {
+			var node: Node = Node { next: null }
+			var next: ptr Node = last(null)
+			if next = null or null =/= node.next {
+				next = null
+			}
+		}
+	";
+	assert_eq!(type_check(source_code), Ok(()));
+	assert!(matches!(
+		type_check("def main() {\n\tvar x: int = null\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Int), actual: DataType::Null, .. })
+	));
+	assert!(matches!(
+		type_check("def main(x: float): bool {\n\treturn x = null\n}"),
+		Err(Error::TypeMismatch { actual: DataType::Null, .. })
+	));
+	assert!(matches!(
+		type_check("def main(p: ptr int): bool {\n\treturn p < null\n}"),
+		Err(Error::InvalidOperand { .. })
+	));
+	assert!(matches!(
+		type_check("def main() {\n\tvar p = null\n}"),
+		Err(Error::UntypedNull { name }) if name.value == "p"
+	));
+}
+
+/// Tests that constants are visible before their declaration, but cannot be assigned to or computed at runtime.
+#[test]
+fn test_const() {
+	assert_eq!(type_check("def main(): int {\n\treturn B\n}\nconst A = 2\nconst B: int = A * 3\n"), Ok(()));
+	assert!(matches!(
+		type_check("const A = 1\ndef main() {\n\tA = 2\n}"),
+		Err(Error::AssignmentToConstant { name, .. }) if name.value == "A"
+	));
+	assert!(matches!(
+		type_check("def main() {\n\tconst a = [1, 2]\n\ta[0] = 3\n}"),
+		Err(Error::AssignmentToConstant { .. })
+	));
+	assert!(matches!(type_check("def main(x: int) {\n\tconst y = x + 1\n}"), Err(Error::NotConstant { .. })));
+	assert!(matches!(type_check("const A = 1\ndef main(A: int) {\n}"), Err(Error::Redeclaration { .. })));
+}
+
+/// Tests that conditions have to be booleans and that booleans cannot be used in arithmetic.
+#[test]
+fn test_bool() {
+	assert!(matches!(
+		type_check("def main() {\n\tif 1 {\n\t}\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Bool), .. })
+	));
+	assert!(matches!(type_check("def main(): bool {\n\treturn true + false\n}"), Err(Error::InvalidOperand { .. })));
+	assert_eq!(type_check("def main(): bool {\n\treturn 1.5 < 2.5 =/= false\n}"), Ok(()));
+}
+
+/// Tests that strings have their own type, which cannot be used in arithmetic.
+#[test]
+fn test_string() {
+	assert_eq!(type_check("def main(): str {\n\treturn \"hello\"\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): str {\n\treturn \"a\" + \"b\"\n}"),
+		Err(Error::InvalidOperand { data_type: DataType::Basic(BasicDataType::Str), .. })
+	));
+}
+
+/// Tests that characters have their own type and can be compared, but not calculated with.
+#[test]
+fn test_char() {
+	assert_eq!(type_check("def main(c: char): bool {\n\treturn 'a' <= c and c =/= '_'\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): char {\n\treturn 'a' + 'b'\n}"),
+		Err(Error::InvalidOperand { data_type: DataType::Basic(BasicDataType::Char), .. })
+	));
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn 'a'\n}"),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Char), .. })
+	));
+}
+
+/// Tests that `and`/`or` only accept booleans.
+#[test]
+fn test_logical_operand() {
+	assert!(matches!(
+		type_check("def main(): bool {\n\treturn 1 and true\n}"),
+		Err(Error::InvalidOperand { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+}
+
+/// Tests that bitwise operators only accept integers.
+#[test]
+fn test_bitwise_operand() {
+	assert!(matches!(
+		type_check("def main(): float {\n\treturn 1.0 << 2.0\n}"),
+		Err(Error::InvalidOperand { data_type: DataType::Basic(BasicDataType::Float), .. })
+	));
+	assert_eq!(type_check("def main(): int {\n\treturn 1 << 2 | 1\n}"), Ok(()));
+}
+
+/// Tests that numbers cannot be negated logically, and booleans not arithmetically.
+#[test]
+fn test_unary_operand() {
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn !1\n}"),
+		Err(Error::InvalidUnaryOperand { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check("def main(): bool {\n\treturn -true\n}"),
+		Err(Error::InvalidUnaryOperand { data_type: DataType::Basic(BasicDataType::Bool), .. })
+	));
+}
+
+/// Tests that only numbers can be cast, and that the cast has the type it casts to.
+#[test]
+fn test_cast() {
+	assert_eq!(type_check("def main(): float {\n\treturn 1 as float / 2.0\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn 1.5 as float\n}"),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Float), .. })
+	));
+	assert!(matches!(
+		type_check("def main(): int {\n\treturn true as int\n}"),
+		Err(Error::InvalidCast { from: DataType::Basic(BasicDataType::Bool), .. })
+	));
+}
+
+/// Tests that a variable declared in a block is not visible after the block.
+#[test]
+fn test_variable_out_of_scope() {
+	let source_code = "
+		def main(): int {
+			if true {
+				var x: int = 1
+			}
+			return x
+		}
+	";
+	assert!(matches!(type_check(source_code), Err(Error::UndeclaredVariable { name }) if name.value == "x"));
+}
+
+/// Tests that the variable declared in the initialization of a for loop is not visible after the loop.
+#[test]
+fn test_for_loop_variable_out_of_scope() {
+	let source_code = "
+		def main(): int {
+			for var i: int = 0; i < 3; i = i + 1 {
+			}
+			return i
+		}
+	";
+	assert!(matches!(type_check(source_code), Err(Error::UndeclaredVariable { name }) if name.value == "i"));
+}
+
+/// Tests that unknown struct names are reported, also behind pointers.
+#[test]
+fn test_unknown_struct() {
+	assert!(matches!(
+		type_check("def main(p: ptr Point) {\n}"),
+		Err(Error::UnknownStruct { data_type }) if data_type.position.position.start.column == 17
+	));
+	assert!(matches!(type_check("struct Line {\n\tstart: Point\n}"), Err(Error::UnknownStruct { .. })));
+}
+
+/// Tests that the type of a field access is the type of the field, also in chains.
+#[test]
+fn test_field_access() {
+	let source_code = "
+		struct Point {
+			x: int
+			y: float
+		}
+		struct Line {
+			start: Point
+			end: Point
+		}
+		def height(line: Line): float {
+			return line.end.y - line.start.y
+		}
+	";
+	assert_eq!(type_check(source_code), Ok(()));
+}
+
+/// Tests that only existing fields of structs can be accessed.
+#[test]
+fn test_invalid_field_access() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def z(p: Point): int {
+			return p.z
+		}
+	";
+	assert!(matches!(
+		type_check(source_code),
+		Err(Error::UnknownField { field, struct_name }) if field.value == "z" && struct_name == "Point"
+	));
+	assert!(matches!(
+		type_check("def x(i: int): int {\n\treturn i.x\n}"),
+		Err(Error::NoStruct { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+}
+
+/// Tests that a struct literal has to give every field exactly one value of the right type.
+#[test]
+fn test_struct_literal() {
+	let struct_ = "struct Point {\n\tx: int\n\ty: float\n}\n";
+	let check = |literal: &str| type_check(&format!("{}def main(): Point {{\n\treturn {}\n}}", struct_, literal));
+	assert_eq!(check("Point { y: 2.0, x: 1 }"), Ok(()));
+	assert!(matches!(check("Point { x: 1 }"), Err(Error::MissingField { field, .. }) if field == "y"));
+	assert!(matches!(check("Point { x: 1, x: 2, y: 2.0 }"), Err(Error::DuplicateField { .. })));
+	assert!(matches!(check("Point { x: 1, y: 2 }"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(check("Point { x: 1, y: 2.0, z: 3 }"), Err(Error::UnknownField { .. })));
+}
+
+/// Tests that array literals and indexing are typed by their elements, and that only arrays can be indexed.
+#[test]
+fn test_array() {
+	assert_eq!(
+		type_check("def main(): float {\n\tvar a: [[float; 1]; 2] = [[1.0], [2.0]]\n\treturn a[1][0]\n}"),
+		Ok(())
+	);
+	assert!(matches!(
+		type_check("def main() {\n\tvar a: [int; 2] = [1, 2, 3]\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Array { length: 2, .. }, .. })
+	));
+	assert!(matches!(
+		type_check("def main() {\n\tvar a: [int; 2] = [1, 2]\n\ta[0] = true\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check("def main() {\n\tvar a: [int; 2] = [1, 2]\n\ta[1.0] = 1\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(type_check("def main(i: int): int {\n\treturn i[0]\n}"), Err(Error::NoArray { .. })));
+	assert!(matches!(type_check("def main() {\n\tvar a: [int; 0] = []\n}"), Err(Error::EmptyArrayLiteral { .. })));
+}
+
+/// Tests that variables, constants and parameters which are never read are reported as warnings, unless they start
+/// with `_`.
+#[test]
+fn test_unused_variable_warning() {
+	let source_code = "
+		def main(unused: int, _ignored: int): int {
+			var written = 1
+			written = 2
+			var read = 3
+			const LIMIT = 4
+			return read
+		}
+	";
+	assert_eq!(
+		warnings(source_code),
+		[
+			("W0101", "Parameter `unused` is never used".to_owned()),
+			("W0100", "Variable `written` is never used".to_owned()),
+			("W0100", "Constant `LIMIT` is never used".to_owned()),
+		]
+	);
+}
+
+/// Tests that the warnings of different functions are reported in source order, and that the error of the first
+/// invalid function is returned, also if the functions are checked in parallel.
+#[test]
+fn test_source_order() {
+	let source_code: String = (0..16).map(|i| format!("def f{i}() {{\n\tvar unused{i} = {i}\n}}\n")).collect();
+	let expected: Vec<_> = (0..16).map(|i| ("W0100", format!("Variable `unused{i}` is never used"))).collect();
+	assert_eq!(warnings(&source_code), expected);
+
+	let source_code = source_code + "def g(): int {\n\treturn true\n}\ndef h(): int {\n\treturn 1.0\n}\n";
+	assert!(matches!(
+		type_check(&source_code),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Bool), .. })
+	));
+}
+
+/// Tests that branches whose condition is known at compile time to never be true are reported as warnings.
+#[test]
+fn test_never_taken_branch_warning() {
+	let source_code = "
+		const DEBUG = 1 > 2
+		def main(): int {
+			if DEBUG or false {
+				return 1
+			}
+			while true {
+				return 2
+			}
+			if true {
+				return 3
+			} else {
+				return 4
+			}
+		}
+	";
+	assert_eq!(
+		warnings(source_code),
+		[
+			("W0021", "The condition is always false, so the `if` block is never executed".to_owned()),
+			("W0021", "The condition is always true, so the `else` block is never executed".to_owned()),
+		]
+	);
+}
+
+/// Tests that calls of undefined functions are reported.
+#[test]
+fn test_undefined_function_call() {
+	assert!(matches!(type_check("def main(): int {\n\treturn answer()\n}"), Err(Error::UndefinedFunctionCall { .. })));
+}
+
+/// Tests that functions and structs with the same name, and struct fields with the same name, are rejected.
+#[test]
+fn test_duplicate_definitions() {
+	let Err(Error::DuplicateFunction { previous, duplicate }) =
+		type_check("def f() {\n}\ndef f() {\n}\ndef main(): int {\n\treturn 0\n}")
+	else {
+		panic!("duplicate function not detected");
+	};
+	assert_eq!((previous.position.start.line, duplicate.position.position.start.line), (1, 3));
+	assert!(matches!(
+		type_check("struct P {\n\tx: int\n}\nstruct P {\n\ty: int\n}"),
+		Err(Error::DuplicateStruct { .. })
+	));
+	assert!(matches!(type_check("struct P {\n\tx: int\n\tx: float\n}"), Err(Error::DuplicateStructField { .. })));
+	// A function and a struct may have the same name
+	assert!(type_check("struct P {\n\tx: int\n}\ndef P() {\n}").is_ok());
+}
+
+/// Boilerplate code for lexing, parsing and type checking source code.
+/// Tests that the arguments of builtins are checked and that the names of builtins are reserved.
+/// Tests that the condition of an if-expression has to be a boolean and that both branches have to have the same type,
+/// except for `null` in one branch.
+#[test]
+fn test_if_expression() {
+	assert_eq!(type_check("def main(a: int, b: int): int {\n\treturn if a < b { a } else { b }\n}"), Ok(()));
+	assert_eq!(type_check("def main(p: ptr int): ptr int {\n\treturn if true { null } else { p }\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(a: int): int {\n\treturn if a { a } else { 0 }\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Bool), .. })
+	));
+	assert!(matches!(
+		type_check("def main(a: int): int {\n\treturn if a < 0 { 0 } else { 1.5 }\n}"),
+		Err(Error::TypeMismatch {
+			expected: DataType::Basic(BasicDataType::Int),
+			actual: DataType::Basic(BasicDataType::Float),
+			..
+		})
+	));
+}
+
+/// Tests that a match needs an integer value, distinct constant patterns and a default arm.
+#[test]
+fn test_match() {
+	let match_ = |value: &str, patterns: &str, default: &str| {
+		type_check(&format!(
+			"const ONE = 1\ndef main(x: int, y: float) {{\n\tmatch {} {{\n\t\t{} => {{\n\t\t}}\n{}\t}}\n}}",
+			value, patterns, default
+		))
+	};
+	let default = "\t\t_ => {\n\t\t}\n";
+	assert_eq!(match_("x", "0, ONE, (ONE + 1)", default), Ok(()));
+	assert!(matches!(
+		match_("y", "0", default),
+		Err(Error::TypeMismatch { actual: DataType::Basic(BasicDataType::Float), .. })
+	));
+	assert!(matches!(match_("x", "0, 1.5", default), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(match_("x", "0, x", default), Err(Error::NonConstantPattern { .. })));
+	assert!(matches!(match_("x", "1, ONE", default), Err(Error::DuplicatePattern { value, .. }) if value == "1"));
+	assert!(matches!(match_("x", "0", ""), Err(Error::MissingDefaultArm { .. })));
+}
+
+/// Tests that the variants of an enum are constants of its type, which are only compared with each other.
+#[test]
+fn test_enum() {
+	let enum_ = |body: &str| type_check(&format!("enum Color {{ Red, Green }}\ndef main(c: Color) {{\n\t{}\n}}", body));
+	assert_eq!(enum_("var same: bool = (c = Color.Red)"), Ok(()));
+	assert_eq!(enum_("var index: int = (Color.Green as int)"), Ok(()));
+	assert!(matches!(
+		enum_("var index: int = Color.Green"),
+		Err(Error::TypeMismatch { actual: DataType::Enum(name), .. }) if name == "Color"
+	));
+	assert!(matches!(enum_("var same = (c = 0)"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(enum_("var next = (c + 1)"), Err(Error::InvalidOperand { .. })));
+	assert!(matches!(enum_("var c2 = (1 as Color)"), Err(Error::InvalidCast { .. })));
+	assert!(matches!(enum_("Color.Red = c"), Err(Error::AssignmentToConstant { .. })));
+	assert!(matches!(
+		enum_("var c2 = Color.Blue"),
+		Err(Error::UnknownVariant { variant, enum_name }) if variant.value == "Blue" && enum_name == "Color"
+	));
+	assert!(matches!(type_check("enum Color { Red, Red }"), Err(Error::DuplicateVariant { .. })));
+	assert!(matches!(type_check("struct Color {\n}\nenum Color { Red }"), Err(Error::DuplicateEnum { .. })));
+}
+
+/// Tests that a match over an enum needs no default arm if it covers all variants.
+#[test]
+fn test_match_enum() {
+	let match_ = |patterns: &str| {
+		type_check(&format!(
+			"enum Color {{ Red, Green, Blue }}\ndef main(c: Color): int {{\n\tmatch c {{\n\t\t{} => {{\n{}\t}}\n}}",
+			patterns, "\t\t\treturn 1\n\t\t}\n"
+		))
+	};
+	assert_eq!(match_("Color.Red, Color.Green, Color.Blue"), Ok(()));
+	assert!(matches!(
+		match_("Color.Red, Color.Blue"),
+		Err(Error::MissingVariants { missing, .. }) if missing == vec![Symbol::intern("Color.Green")]
+	));
+	assert!(matches!(match_("Color.Red, 1"), Err(Error::TypeMismatch { .. })));
+	assert!(matches!(
+		match_("Color.Red, Color.Green, Color.Blue, Color.Red"),
+		Err(Error::DuplicatePattern { value, .. }) if value == "`Color.Red`"
+	));
+}
+
+/// Tests that a type alias is the same type as the type it stands for, also via other aliases.
+#[test]
+fn test_type_alias() {
+	let source_code = "
+		type Distance = Meters
+		type Meters = float
+		type Path = [Distance; 2]
+		def length(path: Path): Meters {
+			var first: float = path[0]
+			return first + path[1] as Meters
+		}
+	";
+	assert_eq!(type_check(source_code), Ok(()));
+	assert!(matches!(
+		type_check("type Meters = float\ndef main(): Meters {\n\treturn 1\n}"),
+		Err(Error::TypeMismatch { expected: DataType::Basic(BasicDataType::Float), .. })
+	));
+	assert!(matches!(
+		type_check("type A = ptr B\ntype B = [A; 2]"),
+		Err(Error::RecursiveTypeAlias { alias }) if alias.value == "A"
+	));
+	assert!(matches!(type_check("type A = B"), Err(Error::UnknownStruct { .. })));
+	assert!(matches!(type_check("type A = int\ntype A = int"), Err(Error::DuplicateTypeAlias { .. })));
+	assert!(matches!(type_check("type A = int\nstruct A {\n}"), Err(Error::DuplicateTypeAlias { .. })));
+}
+
+/// Tests that the type arguments of generic functions are inferred from the parameters, and that each generic
+/// function is replaced by a specialization for each of its type arguments, also for calls in specializations.
+#[test]
+fn test_generic_function() {
+	let source_code = "
+		def max<T>(a: T, b: T): T {
+			if a > b {
+				return a
+			}
+			return b
+		}
+		def first<T>(values: [T; 2]): T {
+			var value: T = values[0]
+			return max(value, values[1])
+		}
+		def unused<T>(x: T) {
+		}
+		def main(): float {
+			var x: int = (max(1, 2) + first([3, 4]))
+			return first([1.5, 2.5])
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let specializations = TypeChecker::type_check(symbol_table, ast_nodes.iter()).unwrap();
+	monomorphize(&mut ast_nodes, specializations);
+	let functions: Vec<_> = (ast_nodes.iter())
+		.map(|node| match node {
+			Node::Function(function) => function.prototype.name.as_str(),
+			_ => panic!("Unexpected node {:?}", node),
+		})
+		.collect();
+	assert_eq!(functions, ["max.float", "max.int", "first.float", "first.int", "main"]);
+	let Node::Function(main) = &ast_nodes[4] else { unreachable!() };
+	let Instruction::Statement(Statement::Return(Some(Expression::FunctionCall(function_call)), _)) = &main.body[1]
+	else {
+		panic!("Unexpected instruction {:?}", main.body[1]);
+	};
+	assert_eq!(function_call.name.value, "first.float");
+
+	assert!(matches!(
+		type_check("def max<T>(a: T, b: T): T {\n\treturn a\n}\ndef main() {\n\tmax(1, 2.5)\n}"),
+		Err(Error::ArgumentTypeMismatch { argument, actual: DataType::Basic(BasicDataType::Float), .. })
+			if argument.data_type.value == DataType::Basic(BasicDataType::Int)
+	));
+	assert!(matches!(
+		type_check("def zero<T>(): T {\n\treturn 0\n}\ndef main() {\n\tvar x: int = zero()\n}"),
+		Err(Error::UninferredTypeParameter { type_parameter, .. }) if type_parameter == "T"
+	));
+	// The body of a generic function is only checked for the type arguments it is called with
+	assert_eq!(type_check("def neg<T>(x: T): T {\n\treturn -x\n}\ndef main() {\n\tneg(1)\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def neg<T>(x: T): T {\n\treturn -x\n}\ndef main() {\n\tneg(true)\n}"),
+		Err(Error::InvalidUnaryOperand { .. })
+	));
+	assert!(matches!(
+		type_check("def f<T>(x: T, n: int) {\n\tf([x, x], n)\n}\ndef main() {\n\tf(1, 2)\n}"),
+		Err(Error::TooManySpecializations { generic }) if generic.value == "f"
+	));
+}
+
+/// Tests that the type arguments of generic structs are inferred from the fields of literals, and that each generic
+/// struct is replaced by a specialization for each of its type arguments, also for types in specializations.
+#[test]
+fn test_generic_struct() {
+	let source_code = "
+		struct Pair<A, B> {
+			first: A
+			second: B
+		}
+		def swap<A, B>(pair: Pair<A, B>): Pair<B, A> {
+			return Pair { first: pair.second, second: pair.first }
+		}
+		def main(): float {
+			var pair: Pair<int, float> = Pair { first: 1, second: 2.5 }
+			var nested = Pair { first: swap(pair), second: true }
+			return nested.first.first
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let specializations = TypeChecker::type_check(symbol_table, ast_nodes.iter()).unwrap();
+	monomorphize(&mut ast_nodes, specializations);
+	let structs: Vec<_> = (ast_nodes.iter())
+		.filter_map(|node| match node {
+			Node::Struct(struct_) => Some(struct_.name.as_str()),
+			_ => None,
+		})
+		.collect();
+	// A specialization comes after the specializations it contains
+	assert_eq!(structs, ["Pair.float.int", "Pair.Pair.float.int.bool", "Pair.int.float"]);
+	let Node::Function(main) = ast_nodes.last().unwrap() else { unreachable!() };
+	let Instruction::Statement(Statement::VariableDeclaration(declaration)) = &main.body[0] else {
+		panic!("Unexpected instruction {:?}", main.body[0]);
+	};
+	assert_eq!(declaration.data_type.as_ref().unwrap().value, DataType::Struct(Symbol::from("Pair.int.float")));
+	let Expression::StructLiteral(struct_literal) = &declaration.value else { unreachable!() };
+	assert_eq!(struct_literal.name.value, "Pair.int.float");
+
+	let pair = "struct Pair<A, B> {\n\tfirst: A\n\tsecond: B\n}\n";
+	assert!(matches!(
+		type_check(&format!("{pair}def main() {{\n\tvar p: Pair<int> = null\n}}")),
+		Err(Error::TypeArgumentCountMismatch { expected: 2, actual: 1, .. })
+	));
+	assert!(matches!(
+		type_check(&format!("{pair}def main(p: ptr Pair) {{\n}}")),
+		Err(Error::TypeArgumentCountMismatch { expected: 2, actual: 0, .. })
+	));
+	assert!(matches!(
+		type_check(&format!(
+			"{pair}def main(): int {{\n\tvar p = Pair {{ first: 1, second: 2 }}\n\treturn p.second.x\n}}"
+		)),
+		Err(Error::NoStruct { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check("struct Box<T> {\n\tn: int\n}\ndef main() {\n\tvar b = Box { n: 1 }\n}"),
+		Err(Error::UninferredTypeParameter { type_parameter, name }) if type_parameter == "T" && name.value == "Box"
+	));
+	assert!(matches!(
+		type_check("struct L<T> {\n\tnext: ptr L<[T; 2]>\n}\ndef main(l: ptr L<int>) {\n}"),
+		Err(Error::TooManySpecializations { generic }) if generic.value == "L"
+	));
+}
+
+/// Tests that method calls are resolved by the type of the receiver and replaced by plain calls with the receiver as
+/// first argument, also for calls on variables, which are parsed as calls of a qualified name. Fields of a receiver
+/// which is a pointer are accessed through it.
+#[test]
+fn test_method() {
+	let source_code = "
+		struct Point {
+			x: float
+		}
+		struct Pair<A, B> {
+			first: A
+			second: B
+		}
+		def Point.scaled(self: Point, factor: float): Point {
+			return Point { x: (self.x * factor) }
+		}
+		def Point.reset(self: ptr Point) {
+			self.x = 0.0
+		}
+		def Point.length(self: ptr Point): float {
+			return self.x
+		}
+		def Pair.swap<A, B>(self: Pair<A, B>): Pair<B, A> {
+			return Pair { first: self.second, second: self.first }
+		}
+		def main(p: ptr Point, q: Point): float {
+			var pair = Pair { first: 1, second: 2.5 }
+			p.reset()
+			var swapped = pair.swap()
+			return q.scaled(2.0).x
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let specializations = TypeChecker::type_check(symbol_table, ast_nodes.iter()).unwrap();
+	monomorphize(&mut ast_nodes, specializations);
+	let reset_body = ast_nodes.iter().find_map(|node| match node {
+		Node::Function(function) if function.prototype.name.value == "Point.reset" => Some(&function.body),
+		_ => None,
+	});
+	let Some([Instruction::Statement(Statement::Assignment(assignment))]) = reset_body.map(Vec::as_slice) else {
+		panic!("Unexpected body {:?}", reset_body);
+	};
+	assert!(matches!(
+		&assignment.target,
+		LValue::FieldAccess { base, .. } if matches!(**base, LValue::Dereference { .. })
+	));
+	let Node::Function(main) = ast_nodes.last().unwrap() else { unreachable!() };
+	let Instruction::Expression(Expression::FunctionCall(reset)) = &main.body[1] else {
+		panic!("Unexpected instruction {:?}", main.body[1]);
+	};
+	assert_eq!(reset.name.value, "Point.reset");
+	assert!(matches!(&reset.params[..], [Expression::Variable(name)] if name.value == "p"));
+	let Instruction::Statement(Statement::VariableDeclaration(declaration)) = &main.body[2] else {
+		panic!("Unexpected instruction {:?}", main.body[2]);
+	};
+	let Expression::FunctionCall(swap) = &declaration.value else { unreachable!() };
+	assert_eq!(swap.name.value, "Pair.swap.int.float");
+	let Instruction::Statement(Statement::Return(Some(Expression::FieldAccess(field_access)), _)) = &main.body[3]
+	else {
+		panic!("Unexpected instruction {:?}", main.body[3]);
+	};
+	let Expression::FunctionCall(scaled) = &*field_access.base else { unreachable!() };
+	assert_eq!(scaled.name.value, "Point.scaled");
+	assert!(scaled.receiver.is_none());
+	assert_eq!(scaled.params.len(), 2);
+
+	let point = "struct Point {\n\tx: float\n}\n";
+	// A method can also be called like a function
+	assert_eq!(
+		type_check(&format!(
+			"{point}def Point.get(self: Point): float {{\n\treturn self.x\n}}\n\
+			def main(): float {{\n\treturn Point.get(Point {{ x: 1.0 }})\n}}"
+		)),
+		Ok(())
+	);
+	assert!(matches!(
+		type_check(&format!("{point}def main(p: Point) {{\n\tp.length()\n}}")),
+		Err(Error::UnknownMethod { method, data_type: DataType::Struct(_) }) if method.value == "length"
+	));
+	assert!(matches!(
+		type_check("def main(n: int) {\n\tn.length()\n}"),
+		Err(Error::UnknownMethod { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check(&format!("{point}def Point.get(x: int): int {{\n\treturn x\n}}")),
+		Err(Error::InvalidReceiver { method, struct_name }) if method.value == "Point.get" && struct_name == "Point"
+	));
+}
+
+/// Tests that dereferences have the type the pointer points to, that a constant pointer can be assigned through, and
+/// that field accesses through pointers are desugared to field accesses of dereferences.
+#[test]
+fn test_dereference() {
+	let source_code = "
+		struct Point {
+			x: int
+		}
+		def main(p: ptr Point, n: ptr int): int {
+			const c: ptr int = null
+			*c = *n + 1
+			p.x = *n
+			return p.x
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let specializations = TypeChecker::type_check(symbol_table, ast_nodes.iter()).unwrap();
+	monomorphize(&mut ast_nodes, specializations);
+	let Node::Function(main) = ast_nodes.last().unwrap() else { unreachable!() };
+	let Instruction::Statement(Statement::Assignment(assignment)) = &main.body[2] else {
+		panic!("Unexpected instruction {:?}", main.body[2]);
+	};
+	let LValue::FieldAccess { base, .. } = &assignment.target else { panic!("{:?}", assignment.target) };
+	assert!(matches!(&**base, LValue::Dereference { base, .. } if matches!(**base, LValue::Variable(_))));
+	let Instruction::Statement(Statement::Return(Some(Expression::FieldAccess(field_access)), _)) = &main.body[3]
+	else {
+		panic!("Unexpected instruction {:?}", main.body[3]);
+	};
+	assert!(matches!(
+		&*field_access.base,
+		Expression::UnaryExpression(unary) if unary.operator.value == UnaryOperator::Dereference
+	));
+
+	assert!(matches!(
+		type_check("def main(x: int): int {\n\treturn *x\n}"),
+		Err(Error::InvalidUnaryOperand { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check("def main(x: int) {\n\t*x = 1\n}"),
+		Err(Error::InvalidUnaryOperand { data_type: DataType::Basic(BasicDataType::Int), .. })
+	));
+	assert!(matches!(
+		type_check("def main(p: ptr int) {\n\t*p = 1.5\n}"),
+		Err(Error::TypeMismatch { .. })
+	));
+}
+
+/// Tests that variables and constants declared without type annotation get their inferred type as annotation, with
+/// the specialized names of generic structs.
+#[test]
+fn test_annotate_inferred_types() {
+	let source_code = "
+		struct Box<T> {
+			value: T
+		}
+		def main() {
+			var b = Box { value: [1.5, 2.5] }
+			const c = 1
+			var x: float = 2.0
+		}
+	";
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let specializations = TypeChecker::type_check(symbol_table, ast_nodes.iter()).unwrap();
+	monomorphize(&mut ast_nodes, specializations);
+	let Node::Function(main) = ast_nodes.last().unwrap() else { unreachable!() };
+	let data_types: Vec<_> = (main.body.iter())
+		.map(|instruction| match instruction {
+			Instruction::Statement(Statement::VariableDeclaration(declaration)) => &declaration.data_type,
+			Instruction::Statement(Statement::ConstDeclaration(declaration)) => &declaration.data_type,
+			_ => panic!("Unexpected instruction {:?}", instruction),
+		})
+		.map(|data_type| data_type.as_ref().unwrap().value.clone())
+		.collect();
+	assert_eq!(
+		data_types,
+		[
+			DataType::Struct(Symbol::from("Box.arr2_float")),
+			DataType::Basic(BasicDataType::Int),
+			DataType::Basic(BasicDataType::Float)
+		]
+	);
+}
+
+#[test]
+fn test_builtin() {
+	assert_eq!(type_check("def main(): int {\n\tprintln(\"answer\")\n\treturn read_int()\n}"), Ok(()));
+	assert!(matches!(type_check("def main() {\n\tprint(1, 2)\n}"), Err(Error::ArgumentCountMismatch { .. })));
+	assert!(matches!(
+		type_check("struct P {\n\tx: int\n}\ndef main() {\n\tprint(P { x: 1 })\n}"),
+		Err(Error::InvalidBuiltinArgument { .. })
+	));
+	assert!(matches!(type_check("def print(x: int) {\n}"), Err(Error::BuiltinRedefinition { .. })));
+}
+
+/// Tests that the math functions are available without import and only accept floats.
+#[test]
+fn test_math_builtin() {
+	assert_eq!(type_check("def main(): float {\n\treturn math.pow(math.sqrt(2.0), 2.0)\n}"), Ok(()));
+	assert!(matches!(
+		type_check("def main(): float {\n\treturn math.sqrt(2)\n}"),
+		Err(Error::InvalidBuiltinArgument { .. })
+	));
+}
+
+fn type_check(source_code: &str) -> Result<(), Error> {
+	let mut ast_nodes = parser::parse_str(source_code).unwrap();
+	resolve_aliases(&mut ast_nodes)?;
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter())?;
+	TypeChecker::type_check(symbol_table, ast_nodes.iter()).map(|_| ())
+}
+
+/// Boilerplate code for type checking source code, which has to be valid, and returning the codes and messages of the
+/// warnings.
+fn warnings(source_code: &str) -> Vec<(&'static str, String)> {
+	let ast_nodes = parser::parse_str(source_code).unwrap();
+	let symbol_table = SymbolTable::global_symbol_scan(ast_nodes.iter()).unwrap();
+	let diagnostics = DiagnosticSink::default();
+	TypeChecker::type_check_with_diagnostics(symbol_table, ast_nodes.iter(), diagnostics.clone()).unwrap();
+	diagnostics.take().into_iter().map(|warning| (warning.code, warning.message)).collect()
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::{
+	ast::{statement::DataType, visit::VisitorMut, Node, TypeAlias},
+	semantic_analyzer::Error,
+	source::PositionContainer,
+	symbol::Symbol,
+};
+
+/// Replaces all uses of the type aliases declared in the `ast_nodes` by the types they stand for, so that `Meters` and
+/// `float` are the same type for the type checker and the backends. The type of an alias may be another alias, but
+/// must not contain the alias itself.
+///
+/// The aliases stay in the AST as [`Node::TypeAlias`], with the types they stand for resolved, too.
+#[tracing::instrument(skip_all)]
+pub fn resolve_aliases(ast_nodes: &mut [Node]) -> Result<(), Error> {
+	let mut resolver = AliasResolver { aliases: HashMap::new(), resolved: HashMap::new() };
+	for node in ast_nodes.iter() {
+		if let Node::TypeAlias(type_alias) = node {
+			// A duplicate is reported by the global symbol scan, which sees the same nodes
+			resolver.aliases.entry(type_alias.name.value).or_insert_with(|| type_alias.clone());
+		}
+	}
+	if resolver.aliases.is_empty() {
+		return Ok(());
+	}
+
+	// Resolve in the order of declaration, so that the error is the same on every run
+	for node in ast_nodes.iter() {
+		if let Node::TypeAlias(type_alias) = node {
+			resolver.resolve(type_alias.name.value, &mut Vec::new())?;
+		}
+	}
+	for node in ast_nodes {
+		resolver.visit_node(node);
+	}
+	Ok(())
+}
+
+struct AliasResolver {
+	/// The declared aliases with the types as written.
+	aliases: HashMap<Symbol, TypeAlias>,
+	/// The types the aliases stand for, which contain no aliases anymore.
+	resolved: HashMap<Symbol, DataType>,
+}
+
+impl AliasResolver {
+	/// The type the alias `name` stands for. The `path` contains the aliases whose types are currently resolved, which
+	/// would be resolved again if the alias refers to itself.
+	fn resolve(&mut self, name: Symbol, path: &mut Vec<Symbol>) -> Result<DataType, Error> {
+		if let Some(data_type) = self.resolved.get(&name) {
+			return Ok(data_type.clone());
+		}
+		let alias = &self.aliases[&name];
+		if path.contains(&name) {
+			return Err(Error::RecursiveTypeAlias { alias: alias.name.clone() });
+		}
+		let mut data_type = alias.data_type.value.clone();
+		path.push(name);
+		self.substitute(&mut data_type, path)?;
+		path.pop();
+		self.resolved.insert(name, data_type.clone());
+		Ok(data_type)
+	}
+
+	/// Replaces the aliases in the `data_type`, possibly behind pointers or in arrays, by the types they stand for.
+	fn substitute(&mut self, data_type: &mut DataType, path: &mut Vec<Symbol>) -> Result<(), Error> {
+		match data_type {
+			DataType::Struct(name) if self.aliases.contains_key(name) => *data_type = self.resolve(*name, path)?,
+			DataType::Generic { type_arguments, .. } => {
+				for type_argument in type_arguments {
+					self.substitute(type_argument, path)?;
+				}
+			},
+			DataType::Pointer(pointee) => self.substitute(&mut pointee.value, path)?,
+			DataType::Array { element, .. } => self.substitute(element, path)?,
+			DataType::Basic(_) | DataType::Struct(_) | DataType::Enum(_) | DataType::Null => (),
+		}
+		Ok(())
+	}
+}
+
+impl VisitorMut for AliasResolver {
+	fn visit_data_type(&mut self, data_type: &mut PositionContainer<DataType>) {
+		// All aliases are resolved already, so there is no error anymore
+		self.substitute(&mut data_type.value, &mut Vec::new()).expect("Unresolved type alias");
+	}
+}
@@ -1,80 +1,347 @@
 use std::{
-	collections::{HashMap, HashSet},
-	iter,
+	collections::HashMap,
+	iter, mem,
 	ops::Deref,
-	sync::Arc,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
 };
 
-use super::{Error, SymbolTable, Variable};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{
+	const_fold::{self, Constant},
+	generic::{self, Instance, Instances, Specializations, MAX_SPECIALIZATIONS},
+	Error, SymbolTable, Variable,
+};
 use crate::{
 	ast::{
 		self,
-		expression::{BinaryExpression, FunctionCall, Number, NumberKind},
+		expression::{
+			ArrayLiteral, BinaryExpression, BinaryOperator, Cast, FieldAccess, FunctionCall, IfExpression,
+			IndexExpression, Number, NumberKind, StructLiteral, UnaryExpression, UnaryOperator,
+		},
 		statement::{BasicDataType, DataType},
-		Expression, FunctionDefinition,
+		Enum, Expression, FunctionDefinition, FunctionPrototype, Struct,
 	},
-	source::PositionContainer,
+	builtin::Builtin,
+	error::DiagnosticSink,
+	module::qualified_name,
+	source::{PositionContainer, SourcePositionRange},
+	symbol::Symbol,
 };
 
-/// Stores all variables declared in this call stack frame.
-type CallStackFrame = HashSet<Arc<Variable>>;
+/// Stores all variables declared in one scope, e.g. a function body or the body of an `if`/`while`, by their name.
+type CallStackFrame = HashMap<Symbol, Arc<Variable>>;
 
 /// Verifies that all types in the program match the expected types (e.g. in function calls and expressions) and that variables are declared before usage.
 #[derive(Debug, Clone)]
 pub struct TypeChecker {
-	/// Globally defined [structs](SymbolTable::structs) and [functions](SymbolTable::functions).
-	symbol_table: SymbolTable,
-	/// Currently declared in-scope variables.
-	pub variables: HashMap<String, Arc<Variable>>,
-	/// List of stack frames, each containing the variables declared in that scope.
+	/// Globally defined [structs](SymbolTable::structs) and [functions](SymbolTable::functions). It is shared with the
+	/// copies of the type checker that check nodes in parallel.
+	symbol_table: Arc<SymbolTable>,
+	/// List of stack frames, each containing the variables declared in that scope. The innermost scope is the last one.
 	pub call_stack: Vec<CallStackFrame>,
+	/// Return type of the function currently being checked, or [`None`] if it has no return type.
+	return_type: Option<PositionContainer<DataType>>,
+	/// Name of the function currently being checked, which identifies the uses of generic functions and structs in it.
+	function_name: Option<Symbol>,
+	/// The type parameters of the generic function or struct whose prototype or fields are checked, which are valid
+	/// types in it.
+	type_parameters: Vec<Symbol>,
+	/// The uses of generic functions and structs with their type arguments, which are not specialized yet. It is shared
+	/// with the copies of the type checker that check nodes in parallel.
+	instances: Arc<Mutex<Instances>>,
+	/// Receives warnings, e.g. about unused variables.
+	diagnostics: DiagnosticSink,
 }
 
 impl TypeChecker {
-	/// Checks that all types in statements and expressions match.
+	/// Checks that all types in statements and expressions match, and returns the specializations of the generic
+	/// functions and structs for the type arguments they are used with, which [`generic::monomorphize`] puts into the
+	/// AST.
 	#[tracing::instrument(skip_all)]
 	pub fn type_check<'a>(
 		symbol_table: SymbolTable,
 		ast_nodes: impl Iterator<Item = &'a ast::Node>,
-	) -> Result<(), Error> {
-		let mut type_check = Self { symbol_table, variables: HashMap::new(), call_stack: Vec::new() };
+	) -> Result<Specializations, Error> {
+		Self::type_check_with_diagnostics(symbol_table, ast_nodes, DiagnosticSink::default())
+	}
+
+	/// Checks that all types in statements and expressions match like [`Self::type_check`], and emits warnings to
+	/// `diagnostics`.
+	///
+	/// With the `parallel` feature, the nodes other than constants are checked in parallel. Their warnings are emitted
+	/// in source order up to the first node with an error, whose error is returned, so that the result is the same as
+	/// without the feature.
+	///
+	/// The body of a generic function is only checked in its specializations, after all other nodes.
+	pub fn type_check_with_diagnostics<'a>(
+		symbol_table: SymbolTable,
+		ast_nodes: impl Iterator<Item = &'a ast::Node>,
+		diagnostics: DiagnosticSink,
+	) -> Result<Specializations, Error> {
+		let symbol_table = Arc::new(symbol_table);
+		let mut type_check = Self {
+			symbol_table,
+			call_stack: Vec::new(),
+			return_type: None,
+			function_name: None,
+			type_parameters: Vec::new(),
+			instances: Arc::default(),
+			diagnostics,
+		};
 
 		type_check.call_stack.push(CallStackFrame::new());
 
-		for ast_node in ast_nodes {
+		// Constants and variants are visible in all functions, even in those defined before them, so they are declared
+		// first
+		let (constants, ast_nodes): (Vec<_>, Vec<_>) =
+			ast_nodes.partition(|ast_node| matches!(ast_node, ast::Node::Const(_) | ast::Node::Enum(_)));
+		for ast_node in constants {
 			type_check.ast_node(ast_node)?;
 		}
+		let generics: HashMap<_, _> = ast_nodes
+			.iter()
+			.copied()
+			.filter_map(|ast_node| match ast_node {
+				ast::Node::Function(function) if function.prototype.is_generic() => {
+					Some((function.prototype.name.value, function))
+				},
+				_ => None,
+			})
+			.collect();
+		type_check.ast_nodes(ast_nodes)?;
+		type_check.specialize(&generics)
+	}
+
+	/// Creates and type checks the specializations of the `generics` and the generic structs for the uses recorded in
+	/// [`Self::instances`], until the specializations use no generic function or struct with other type arguments
+	/// anymore.
+	fn specialize(&mut self, generics: &HashMap<Symbol, &FunctionDefinition>) -> Result<Specializations, Error> {
+		let symbol_table = Arc::clone(&self.symbol_table);
+		let mut specializations = Specializations::default();
+		loop {
+			let Instances { calls, structs, literals, methods, variables, dereferences } =
+				mem::take(&mut *self.instances.lock().unwrap());
+			specializations.uses.extend(literals);
+			specializations.methods.extend(methods);
+			specializations.variables.extend(variables);
+			specializations.dereferences.extend(dereferences);
+			if calls.is_empty() && structs.is_empty() {
+				return Ok(specializations);
+			}
+
+			// The uses are recorded in parallel, so they are sorted to report the same error on every run
+			let mut structs: Vec<_> = structs.into_iter().collect();
+			structs.sort_by_key(|(name, _)| name.as_str());
+			for (name, instance) in structs {
+				let specialized = specializations.structs.entry(instance.generic).or_default();
+				if specialized.iter().any(|struct_| struct_.name.value == name) {
+					continue;
+				}
+				let generic = &symbol_table.structs[&instance.generic];
+				if specialized.len() == MAX_SPECIALIZATIONS {
+					return Err(Error::TooManySpecializations { generic: generic.name.clone() });
+				}
+				let struct_ = generic::specialize_struct(generic, name, &instance.type_arguments);
+				self.struct_(&struct_)?;
+				specializations.structs.get_mut(&instance.generic).unwrap().push(struct_);
+			}
+
+			let mut calls: Vec<_> = calls.into_iter().collect();
+			calls.sort_by_key(|((function_name, position), _)| {
+				(function_name.map(|function_name| function_name.as_str()), position.position.start.offset)
+			});
+			for (call, instance) in calls {
+				let name = instance.specialized_name();
+				specializations.uses.insert(call, name);
+				let specialized = specializations.functions.entry(instance.generic).or_default();
+				if specialized.iter().any(|function| function.prototype.name.value == name) {
+					continue;
+				}
+				let generic = generics[&instance.generic];
+				if specialized.len() == MAX_SPECIALIZATIONS {
+					return Err(Error::TooManySpecializations { generic: generic.prototype.name.clone() });
+				}
+				let function = generic::specialize(generic, name, &instance.type_arguments);
+				// Warnings like unused variables are the same for all specializations, so they are only emitted for the
+				// first one
+				let diagnostics = self.diagnostics.clone();
+				if !specialized.is_empty() {
+					self.diagnostics = DiagnosticSink::default();
+				}
+				let result = self.function(&function);
+				self.diagnostics = diagnostics;
+				result?;
+				specializations.functions.get_mut(&instance.generic).unwrap().push(function);
+			}
+		}
+	}
+
+	/// Type checks the nodes one after another.
+	#[cfg(not(feature = "parallel"))]
+	fn ast_nodes(&mut self, ast_nodes: Vec<&ast::Node>) -> Result<(), Error> {
+		ast_nodes.into_iter().try_for_each(|ast_node| self.ast_node(ast_node))
+	}
+
+	/// Type checks the nodes in parallel. After the constants, the nodes do not depend on each other, so each is
+	/// checked by its own copy of the type checker, which collects its warnings separately.
+	#[cfg(feature = "parallel")]
+	fn ast_nodes(&mut self, ast_nodes: Vec<&ast::Node>) -> Result<(), Error> {
+		let results: Vec<_> = ast_nodes
+			.into_par_iter()
+			.map(|ast_node| {
+				let mut type_check = Self { diagnostics: DiagnosticSink::default(), ..self.clone() };
+				let result = type_check.ast_node(ast_node);
+				(result, type_check.diagnostics.take())
+			})
+			.collect();
+		for (result, diagnostics) in results {
+			diagnostics.into_iter().for_each(|diagnostic| self.diagnostics.emit(diagnostic));
+			result?;
+		}
 		Ok(())
 	}
 
 	/// Type checks an AST node by calling the appropriate method for the node type.
 	fn ast_node(&mut self, node: &ast::Node) -> Result<(), Error> {
 		match node {
+			ast::Node::Function(function) if function.prototype.is_generic() => self.generic_function(function),
 			ast::Node::Function(function) => self.function(function),
-			ast::Node::Struct(struct_) => Ok(()),
-			ast::Node::FunctionPrototype(_) => Ok(()),
-			_ => todo!(),
+			ast::Node::Struct(struct_) => self.struct_(struct_),
+			ast::Node::Enum(enum_) => self.enum_(enum_),
+			// The uses of the alias are resolved already, but the type it stands for has to exist
+			ast::Node::TypeAlias(type_alias) => self.resolve_data_type(&type_alias.data_type),
+			ast::Node::FunctionPrototype(function_prototype) => self.function_prototype(function_prototype),
+			ast::Node::Const(const_declaration) => self.const_declaration(const_declaration),
+			ast::Node::Import(_) | ast::Node::Comment(_) => Ok(()),
+		}
+	}
+
+	/// Checks that the types of all fields of the struct exist. The type parameters of a generic struct are valid types
+	/// in its fields.
+	fn struct_(&mut self, struct_: &Struct) -> Result<(), Error> {
+		self.type_parameters = struct_.type_parameters.iter().map(|type_parameter| type_parameter.value).collect();
+		let result = struct_.fields.iter().try_for_each(|field| self.resolve_data_type(&field.data_type));
+		self.type_parameters.clear();
+		result
+	}
+
+	/// Declares the variants of the enum as constants of its type, whose values are their indices.
+	fn enum_(&mut self, enum_: &Enum) -> Result<(), Error> {
+		for (index, variant) in enum_.variants.iter().enumerate() {
+			self.add_variable(Arc::new(Variable {
+				name: enum_.variant_name(variant),
+				type_: DataType::Enum(enum_.name.value),
+				constant: true,
+				parameter: false,
+				value: Some(Constant::Int(index as i64)),
+				used: AtomicBool::new(false),
+			}))?;
+		}
+		Ok(())
+	}
+
+	/// Checks that the types of all arguments and the return type exist, and that a method takes its struct as first
+	/// argument.
+	fn function_prototype(&mut self, function_prototype: &FunctionPrototype) -> Result<(), Error> {
+		if let Some((struct_name, _)) = function_prototype.name.rsplit_once('.') {
+			let struct_name = Symbol::intern(struct_name);
+			let receiver = function_prototype.args.first().map(|arg| match &arg.data_type.value {
+				DataType::Pointer(pointee) => &pointee.value,
+				data_type => data_type,
+			});
+			let is_receiver = match receiver {
+				Some(DataType::Struct(name) | DataType::Generic { name, .. }) => *name == struct_name,
+				_ => false,
+			};
+			if self.symbol_table.structs.contains_key(&struct_name) && !is_receiver {
+				return Err(Error::InvalidReceiver { method: function_prototype.name.clone(), struct_name });
+			}
 		}
+		for arg in &function_prototype.args {
+			self.resolve_data_type(&arg.data_type)?;
+		}
+		if let Some(return_type) = &function_prototype.return_type {
+			self.resolve_data_type(return_type)?;
+		}
+		Ok(())
+	}
+
+	/// Checks the prototype of a generic function, whose type parameters are valid types in it. The body is checked in
+	/// the specializations of the function instead.
+	fn generic_function(&mut self, function: &FunctionDefinition) -> Result<(), Error> {
+		self.type_parameters =
+			function.prototype.type_parameters.iter().map(|type_parameter| type_parameter.value).collect();
+		let result = self.function_prototype(&function.prototype);
+		self.type_parameters.clear();
+		result
 	}
 
 	/// Type checks each instruction in the given function.
-	#[tracing::instrument(skip_all, fields(name = function.prototype.name.deref()))]
+	#[tracing::instrument(skip_all, fields(name = function.prototype.name.as_str()))]
 	fn function(&mut self, function: &FunctionDefinition) -> Result<(), Error> {
+		self.function_prototype(&function.prototype)?;
+		self.return_type = function.prototype.return_type.clone();
+		self.function_name = Some(function.prototype.name.value);
+
 		// Add the function's arguments to the symbol table
 		self.call_stack.push(CallStackFrame::new());
 		for arg in &function.prototype.args {
-			self.add_variable(Arc::new(Variable { name: arg.name.clone(), type_: arg.data_type.value.clone() }))?;
+			self.add_variable(Arc::new(Variable {
+				name: arg.name.clone(),
+				type_: arg.data_type.value.clone(),
+				constant: false,
+				parameter: true,
+				value: None,
+				used: AtomicBool::new(false),
+			}))?;
 		}
 
 		// Type check the function's body
 		for instruction in &function.body {
 			self.instruction(instruction)?;
 		}
+		if let Some(return_type) = &function.prototype.return_type {
+			if !self.always_returns(&function.body) {
+				return Err(Error::MissingReturn {
+					name: function.prototype.name.clone(),
+					return_type: return_type.value.clone(),
+				});
+			}
+		}
 
 		self.drop_call_stack_frame();
 		Ok(())
 	}
 
+	/// Whether every path through the block ends in a `return`, so that its end is never reached. Loops that never end
+	/// also count, i.e. `while true` loops without a `break`.
+	fn always_returns(&self, block: &[ast::Instruction]) -> bool {
+		block.iter().any(|instruction| match instruction {
+			ast::Instruction::Statement(ast::Statement::Return(..)) => true,
+			ast::Instruction::IfElse(if_else) => match self.constant_condition(&if_else.condition) {
+				Some(true) => self.always_returns(&if_else.if_true),
+				Some(false) => self.always_returns(&if_else.if_false),
+				None => self.always_returns(&if_else.if_true) && self.always_returns(&if_else.if_false),
+			},
+			ast::Instruction::WhileLoop(while_loop) => {
+				self.constant_condition(&while_loop.condition) == Some(true) && !breaks(&while_loop.body)
+			},
+			ast::Instruction::ForLoop(for_loop) => {
+				self.constant_condition(&for_loop.condition) == Some(true) && !breaks(&for_loop.body)
+			},
+			// Without a default arm, the match has been checked to cover all variants of an enum
+			ast::Instruction::Match(match_) => {
+				match_.arms.iter().chain(&match_.default).all(|arm| self.always_returns(&arm.body))
+			},
+			_ => false,
+		})
+	}
+
 	/// Type checks an instruction by calling the appropriate method for the instruction type.
 	fn instruction(&mut self, instruction: &ast::Instruction) -> Result<(), Error> {
 		match instruction {
@@ -82,20 +349,19 @@ impl TypeChecker {
 			ast::Instruction::Statement(statement) => self.statement(statement),
 			ast::Instruction::IfElse(if_else) => self.if_else(if_else),
 			ast::Instruction::WhileLoop(while_loop) => self.while_loop(while_loop),
+			ast::Instruction::ForLoop(for_loop) => self.for_loop(for_loop),
+			ast::Instruction::Match(match_) => self.match_(match_),
+			ast::Instruction::Comment(_) => Ok(()),
 		}
 	}
 
-	/// Type checks an expression by calling the appropriate method for the expression type.
+	/// Type checks an expression whose value is discarded. Only here, functions without return value may be called.
 	fn expression(&mut self, expression: &ast::Expression) -> Result<(), Error> {
 		match expression {
-			ast::Expression::BinaryExpression(binary_expression) => {
-				self.infer_binary_expression_type(binary_expression).map(|_expression_type| ())
-			},
 			ast::Expression::FunctionCall(function_call) => {
-				self.infer_function_call_return_type(function_call).map(|return_type| ())
+				self.infer_function_call_return_type(function_call).map(|_return_type| ())
 			},
-			ast::Expression::Number(_) => Ok(()),
-			ast::Expression::Variable(_) => Ok(()),
+			expression => self.infer_expression_type(expression).map(|_expression_type| ()),
 		}
 	}
 
@@ -105,8 +371,11 @@ impl TypeChecker {
 			ast::statement::Statement::VariableDeclaration(variable_declaration) => {
 				self.variable_declaration(variable_declaration)
 			},
-			ast::statement::Statement::VariableAssignment(assignment) => self.variable_assignment(assignment),
-			ast::Statement::Return(expression) => self.return_(expression),
+			ast::Statement::ConstDeclaration(const_declaration) => self.const_declaration(const_declaration),
+			ast::Statement::Assignment(assignment) => self.assignment(assignment),
+			ast::Statement::Return(expression, _) => self.return_(expression),
+			// The parser already ensures that these only occur inside loops
+			ast::Statement::Break(_) | ast::Statement::Continue(_) => Ok(()),
 		}
 	}
 
@@ -115,9 +384,61 @@ impl TypeChecker {
 		&mut self,
 		variable_declaration: &ast::statement::VariableDeclaration,
 	) -> Result<(), Error> {
+		self.declare_variable(
+			&variable_declaration.name,
+			&variable_declaration.data_type,
+			&variable_declaration.value,
+			false,
+		)
+	}
+
+	/// Checks that the value of the constant is known at compile time and that its type matches.
+	fn const_declaration(&mut self, const_declaration: &ast::statement::ConstDeclaration) -> Result<(), Error> {
+		if !self.is_constant(&const_declaration.value) {
+			return Err(Error::NotConstant { position: const_declaration.value.source_position() });
+		}
+		self.declare_variable(&const_declaration.name, &const_declaration.data_type, &const_declaration.value, true)
+	}
+
+	/// Declares a variable or constant in the innermost scope, after checking that the type of its initial value
+	/// matches the annotated type.
+	fn declare_variable(
+		&mut self,
+		name: &PositionContainer<Symbol>,
+		data_type: &Option<PositionContainer<DataType>>,
+		value: &Expression,
+		constant: bool,
+	) -> Result<(), Error> {
+		if let Some(data_type) = data_type {
+			self.resolve_data_type(data_type)?;
+		}
+
+		// Without type annotation, the variable gets the type of its initial value
+		let inferred_type = self.infer_expression_type(value)?;
+		let type_ = match data_type {
+			Some(data_type) if !data_type.value.accepts(&inferred_type) => {
+				return Err(Error::TypeMismatch {
+					expected: data_type.value.clone(),
+					position: name.position.clone(),
+					actual: inferred_type,
+				})
+			},
+			Some(data_type) => data_type.value.clone(),
+			// `null` fits every pointer type, so the type of the variable is unknown
+			None if inferred_type == DataType::Null => return Err(Error::UntypedNull { name: name.clone() }),
+			None => {
+				let declaration = (self.function_name, name.position.clone());
+				self.instances.lock().unwrap().variables.insert(declaration, inferred_type.clone());
+				inferred_type
+			},
+		};
 		let variable = Arc::new(Variable {
-			name: variable_declaration.name.clone(),
-			type_: variable_declaration.data_type.deref().clone(),
+			name: name.clone(),
+			type_,
+			constant,
+			parameter: false,
+			value: if constant { const_fold::fold(value, &|name| self.constant_value(name)) } else { None },
+			used: AtomicBool::new(false),
 		});
 		tracing::debug!(
 			var = variable.to_string(),
@@ -125,83 +446,251 @@ impl TypeChecker {
 			"variable declaration"
 		);
 
-		let inferred_type = self.infer_expression_type(&variable_declaration.value)?;
-		if inferred_type != variable.type_ {
-			return Err(Error::TypeMismatch {
-				expected: variable.type_.clone(),
-				position: variable.name.position.clone(),
-				actual: inferred_type,
-			});
+		self.add_variable(variable)
+	}
+
+	/// Whether the value of the expression is known at compile time, i.e. it only consists of literals, constants and
+	/// operators.
+	fn is_constant(&self, expression: &Expression) -> bool {
+		match expression {
+			Expression::Number(_)
+			| Expression::Bool(_)
+			| Expression::StringLiteral(_)
+			| Expression::Char(_)
+			| Expression::Null(_) => true,
+			Expression::Variable(variable) => {
+				self.lookup_variable(variable.value).is_some_and(|variable| variable.constant)
+			},
+			Expression::BinaryExpression(binary_expression) => {
+				self.is_constant(&binary_expression.lhs) && self.is_constant(&binary_expression.rhs)
+			},
+			Expression::UnaryExpression(unary_expression) => self.is_constant(&unary_expression.operand),
+			Expression::Cast(cast) => self.is_constant(&cast.expression),
+			Expression::FieldAccess(field_access) => self.is_constant(&field_access.base),
+			Expression::StructLiteral(struct_literal) => {
+				struct_literal.fields.iter().all(|field| self.is_constant(&field.value))
+			},
+			Expression::ArrayLiteral(array_literal) => {
+				array_literal.elements.iter().all(|element| self.is_constant(element))
+			},
+			Expression::IndexExpression(index_expression) => {
+				self.is_constant(&index_expression.base) && self.is_constant(&index_expression.index)
+			},
+			Expression::If(if_expression) => {
+				self.is_constant(&if_expression.condition)
+					&& self.is_constant(&if_expression.if_true)
+					&& self.is_constant(&if_expression.if_false)
+			},
+			Expression::FunctionCall(_) => false,
 		}
+	}
 
-		// If there is a previous declaration of this variable, there is a name conflict.
-		let previous_declaration = self.variables.get(&variable.name.value);
-		if let Some(previous_declaration) = previous_declaration {
+	/// Adds a variable to the innermost scope of [`Self::call_stack`].
+	fn add_variable(&mut self, var: Arc<Variable>) -> Result<(), Error> {
+		// If there is a previous declaration of this variable in this or an enclosing scope, e.g. a constant, there is
+		// a name conflict.
+		if let Some(previous_declaration) = self.lookup_variable(var.name.value) {
 			return Err(Error::Redeclaration {
 				previous_declaration: Arc::clone(previous_declaration),
-				new_declaration: Arc::clone(&variable),
+				new_declaration: var,
 			});
 		}
-
-		self.add_variable(variable)?;
-		// Type check the expression itself
-		// TODO: Should already be covered by the type inference of the expression, i.e. by calling `self.infer_expression_type`
-		self.expression(&variable_declaration.value)?;
-		Ok(())
-	}
-
-	/// Adds a variable to [`Self::variables`] and [`Self::call_stack`].
-	fn add_variable(&mut self, var: Arc<Variable>) -> Result<(), Error> {
-		self.variables.insert(var.name.value.clone(), Arc::clone(&var));
-		self.call_stack.last_mut().unwrap().insert(var);
+		self.call_stack.last_mut().unwrap().insert(var.name.value, var);
 		Ok(())
 	}
 
-	/// Removes one frame from the call stack and deletes all of its variables from the symbol table ([`Self::variables`]).
+	/// Removes one frame from the call stack, so that its variables are no longer in scope. Variables of the frame
+	/// that were never read are reported as unused, unless their name starts with `_`.
 	fn drop_call_stack_frame(&mut self) {
 		let frame = self.call_stack.pop().unwrap();
-		for variable in frame {
-			self.variables.remove(&variable.name.value);
+		let mut unused = frame
+			.into_values()
+			.filter(|variable| !variable.used.load(Ordering::Relaxed) && !variable.name.starts_with('_'))
+			.collect::<Vec<_>>();
+		// The frame is a hash map, so sort the variables to report them in the order of their declarations
+		unused.sort_by_key(|variable| variable.name.position.position.start.offset);
+		for variable in unused {
+			let (code, kind) = match (variable.parameter, variable.constant) {
+				(true, _) => ("W0101", "Parameter"),
+				(false, true) => ("W0100", "Constant"),
+				(false, false) => ("W0100", "Variable"),
+			};
+			let message = format!("{} `{}` is never used", kind, variable.name.value);
+			self.diagnostics.warn(code, message, Some(&variable.name.position));
 		}
 	}
 
-	/// Checks that the type of the expression matches that of the variable.
-	fn variable_assignment(&mut self, variable_assignment: &ast::statement::VariableAssignment) -> Result<(), Error> {
-		// Infer the type of the expression on the right-hand side of the assignment
-		let expression_type = self.infer_expression_type(&variable_assignment.value)?;
-		let var = Arc::new(Variable { name: variable_assignment.name.clone(), type_: expression_type.clone() });
-		tracing::debug!(var = var.to_string(), position = var.name.position.to_string(), "variable assignment");
-
-		// Look up the type of the variable in the symbol table
-		let variable_type =
-			self.variables.get(&var.name.value).ok_or(Error::UndeclaredVariable { name: var.name.clone() })?;
-
-		if expression_type != variable_type.type_ {
-			// Cannot assign an expression to a variable of different type
+	/// Looks up the value of a constant by its name, if it is known at compile time.
+	fn constant_value(&self, name: Symbol) -> Option<Constant> {
+		self.lookup_variable(name).and_then(|variable| variable.value)
+	}
+
+	/// Looks up a variable by its name, starting at the innermost scope.
+	fn lookup_variable(&self, name: Symbol) -> Option<&Arc<Variable>> {
+		self.call_stack.iter().rev().find_map(|frame| frame.get(&name))
+	}
+
+	/// Checks that the structs and enums used in the data type, possibly behind pointers or in arrays, are declared,
+	/// and that generic structs get a type argument for each type parameter.
+	///
+	/// Outside of generic prototypes and structs, the types of generic structs are recorded in [`Self::instances`], so
+	/// that they are specialized.
+	fn resolve_data_type(&self, data_type: &PositionContainer<DataType>) -> Result<(), Error> {
+		match &data_type.value {
+			DataType::Basic(_) | DataType::Null => Ok(()),
+			DataType::Struct(name) if self.symbol_table.structs.get(name).is_some_and(Struct::is_generic) => {
+				Err(self.type_argument_count_mismatch(&self.symbol_table.structs[name], &[], data_type))
+			},
+			DataType::Struct(name) if self.symbol_table.structs.contains_key(name) => Ok(()),
+			DataType::Struct(name) if self.type_parameters.contains(name) => Ok(()),
+			DataType::Enum(name) if self.symbol_table.enums.contains_key(name) => Ok(()),
+			DataType::Struct(_) | DataType::Enum(_) => Err(Error::UnknownStruct { data_type: data_type.clone() }),
+			DataType::Generic { name, type_arguments } => {
+				let struct_ = (self.symbol_table.structs.get(name))
+					.ok_or_else(|| Error::UnknownStruct { data_type: data_type.clone() })?;
+				if struct_.type_parameters.len() != type_arguments.len() {
+					return Err(self.type_argument_count_mismatch(struct_, type_arguments, data_type));
+				}
+				// Like the element type of an array, the type arguments have no positions of their own
+				for type_argument in type_arguments {
+					self.resolve_data_type(&PositionContainer::new(type_argument.clone(), data_type.position.clone()))?;
+				}
+				if self.type_parameters.is_empty() {
+					let instance = Instance { generic: *name, type_arguments: type_arguments.clone() };
+					self.instances.lock().unwrap().structs.insert(instance.specialized_name(), instance);
+				}
+				Ok(())
+			},
+			DataType::Pointer(pointee) => self.resolve_data_type(pointee),
+			// The element type has no position of its own, so the position of the array type is used instead
+			DataType::Array { element, .. } => {
+				self.resolve_data_type(&PositionContainer::new(element.deref().clone(), data_type.position.clone()))
+			},
+		}
+	}
+
+	/// The error for the `data_type`, which uses the `struct_` with the `type_arguments`, whose number differs from
+	/// that of its type parameters.
+	fn type_argument_count_mismatch(
+		&self,
+		struct_: &Struct,
+		type_arguments: &[DataType],
+		data_type: &PositionContainer<DataType>,
+	) -> Error {
+		Error::TypeArgumentCountMismatch {
+			name: PositionContainer::new(struct_.name.value, data_type.position.clone()),
+			expected: struct_.type_parameters.len(),
+			actual: type_arguments.len(),
+		}
+	}
+
+	/// Checks that the type of the expression matches that of the variable, or of its field or element that is
+	/// assigned to.
+	fn assignment(&mut self, assignment: &ast::statement::Assignment) -> Result<(), Error> {
+		// Infer the type of the expression on the right-hand side of the assignment. A compound assignment like
+		// `x += 1` also reads the target.
+		let expression_type = self.infer_expression_type(&assignment.assigned_value())?;
+		let name = assignment.target.variable();
+		tracing::debug!(var = name.as_str(), position = name.position.to_string(), "assignment");
+
+		// Look up the type of the variable in the symbol table. The assignment does not declare a new variable, so the
+		// variable stays in the scope it was declared in.
+		let variable = self.lookup_variable(name.value).ok_or(Error::UndeclaredVariable { name: name.clone() })?;
+		// Assigning through a pointer reads the variable holding the pointer, but does not change it
+		if assignment.target.is_dereference() {
+			variable.used.store(true, Ordering::Relaxed);
+		} else if variable.constant {
+			return Err(Error::AssignmentToConstant { name: name.clone(), constant: Arc::clone(variable) });
+		}
+
+		let target_type = self.lvalue_type(&assignment.target, variable)?;
+		if target_type.accepts(&expression_type) {
+			return Ok(());
+		}
+		match &assignment.target {
+			// Cannot assign an expression to a variable of different type, which may also have been inferred from the
+			// initial value of the variable
+			ast::statement::LValue::Variable(_) => Err(Error::AssignmentTypeMismatch {
+				variable: Arc::clone(variable),
+				position: assignment.value.source_position(),
+				actual: expression_type,
+			}),
+			_ => Err(Error::TypeMismatch {
+				expected: target_type,
+				position: assignment.value.source_position(),
+				actual: expression_type,
+			}),
+		}
+	}
+
+	/// Infers the type of the target of an assignment, whose base is the `variable`. Unlike in
+	/// [`Self::infer_expression_type`], the variable is not marked as used, because it is only written to.
+	fn lvalue_type(&self, lvalue: &ast::statement::LValue, variable: &Variable) -> Result<DataType, Error> {
+		match lvalue {
+			ast::statement::LValue::Variable(_) => Ok(variable.type_.clone()),
+			ast::statement::LValue::FieldAccess { base, field } => {
+				let base_type = self.lvalue_type(base, variable)?;
+				self.field_type(self.field_base(base_type, field), field, base.source_position())
+			},
+			ast::statement::LValue::Index { base, index } => {
+				let base_type = self.lvalue_type(base, variable)?;
+				let DataType::Array { element, .. } = base_type else {
+					return Err(Error::NoArray { position: base.source_position(), data_type: base_type });
+				};
+				self.check_index(index)?;
+				Ok(*element)
+			},
+			ast::statement::LValue::Dereference { position, base } => match self.lvalue_type(base, variable)? {
+				DataType::Pointer(pointee) => Ok(pointee.value),
+				data_type => Err(Error::InvalidUnaryOperand {
+					operator: PositionContainer::new(UnaryOperator::Dereference, position.clone()),
+					data_type,
+				}),
+			},
+		}
+	}
+
+	/// Checks that an index into an array is an integer.
+	fn check_index(&self, index: &Expression) -> Result<(), Error> {
+		let index_type = self.infer_expression_type(index)?;
+		if index_type != DataType::Basic(BasicDataType::Int) {
 			return Err(Error::TypeMismatch {
-				expected: variable_type.type_.clone(),
-				position: variable_assignment.name.position.clone(),
-				actual: expression_type.clone(),
+				expected: DataType::Basic(BasicDataType::Int),
+				position: index.source_position(),
+				actual: index_type,
 			});
 		}
-
-		self.add_variable(var)?;
-		self.expression(&variable_assignment.value)?;
 		Ok(())
 	}
 
 	/// Checks that the return type of the function matches the type of the return expression.
-	fn return_(&mut self, expression: &Expression) -> Result<(), Error> {
-		let return_type = self.infer_expression_type(expression)?;
-		// TODO: Check that the return type matches the function's return type
-		tracing::warn!("TODO: Check that the return type {:?} matches the function's return type", return_type);
+	fn return_(&mut self, expression: &Option<Expression>) -> Result<(), Error> {
+		let (expression, expected) = match (expression, &self.return_type) {
+			(None, None) => return Ok(()),
+			(None, Some(return_type)) => return Err(Error::ReturnWithoutValue { return_type: return_type.clone() }),
+			(Some(expression), None) => {
+				return Err(Error::UnexpectedReturnValue { position: expression.source_position() })
+			},
+			(Some(expression), Some(return_type)) => (expression, return_type.value.clone()),
+		};
+		let actual = self.infer_expression_type(expression)?;
+		if !expected.accepts(&actual) {
+			return Err(Error::TypeMismatch { expected, position: expression.source_position(), actual });
+		}
 		Ok(())
 	}
 
 	/// Type checks an if-else block.
 	fn if_else(&mut self, if_else: &ast::IfElse) -> Result<(), Error> {
 		// if block, always present
-		self.expression(&if_else.condition)?;
+		self.condition(&if_else.condition)?;
+		match self.constant_condition(&if_else.condition) {
+			Some(false) => self.warn_never_taken(&if_else.condition, false, "the `if` block is"),
+			Some(true) if !if_else.if_false.is_empty() => {
+				self.warn_never_taken(&if_else.condition, true, "the `else` block is")
+			},
+			_ => (),
+		}
 
 		self.call_stack.push(CallStackFrame::new());
 		for instruction in &if_else.if_true {
@@ -224,7 +713,10 @@ impl TypeChecker {
 
 	/// Type checks a while loop.
 	fn while_loop(&mut self, while_loop: &ast::WhileLoop) -> Result<(), Error> {
-		self.expression(&while_loop.condition)?;
+		self.condition(&while_loop.condition)?;
+		if self.constant_condition(&while_loop.condition) == Some(false) {
+			self.warn_never_taken(&while_loop.condition, false, "the loop body is");
+		}
 
 		self.call_stack.push(CallStackFrame::new());
 		for instruction in &while_loop.body {
@@ -235,12 +727,145 @@ impl TypeChecker {
 		Ok(())
 	}
 
+	/// Type checks a for loop. The variables declared in the initialization are only visible inside the loop.
+	fn for_loop(&mut self, for_loop: &ast::ForLoop) -> Result<(), Error> {
+		self.call_stack.push(CallStackFrame::new());
+		self.statement(&for_loop.initialization)?;
+		self.condition(&for_loop.condition)?;
+
+		self.call_stack.push(CallStackFrame::new());
+		for instruction in &for_loop.body {
+			self.instruction(instruction)?;
+		}
+		self.drop_call_stack_frame();
+
+		self.assignment(&for_loop.step)?;
+		self.drop_call_stack_frame();
+
+		Ok(())
+	}
+
+	/// Type checks a match. The value has to be an integer or an enum, and the patterns distinct constants of its type.
+	///
+	/// Unless the arms cover all variants of an enum, the match needs a default arm.
+	fn match_(&mut self, match_: &ast::Match) -> Result<(), Error> {
+		let value_type = self.infer_expression_type(&match_.value)?;
+		let match_use = (self.function_name, match_.position.clone());
+		self.instances.lock().unwrap().variables.insert(match_use, value_type.clone());
+		let enum_ = match &value_type {
+			DataType::Basic(BasicDataType::Int) => None,
+			DataType::Enum(name) => self.symbol_table.enums.get(name),
+			_ => {
+				return Err(Error::TypeMismatch {
+					expected: DataType::Basic(BasicDataType::Int),
+					position: match_.value.source_position(),
+					actual: value_type,
+				})
+			},
+		};
+
+		// The values of the previous patterns with their positions, to detect duplicates
+		let mut matched: Vec<(i64, SourcePositionRange)> = Vec::new();
+		for arm in &match_.arms {
+			for pattern in &arm.patterns {
+				let position = pattern.source_position();
+				let pattern_type = self.infer_expression_type(pattern)?;
+				if pattern_type != value_type {
+					return Err(Error::TypeMismatch { expected: value_type, position, actual: pattern_type });
+				}
+				if !self.is_constant(pattern) {
+					return Err(Error::NonConstantPattern { position });
+				}
+				// The evaluation fails e.g. for a division by zero, which is reported at runtime instead
+				let Some(Constant::Int(value)) = const_fold::fold(pattern, &|name| self.constant_value(name)) else {
+					continue;
+				};
+				if let Some((_, previous)) = matched.iter().find(|(previous, _)| *previous == value) {
+					let value = match enum_ {
+						Some(enum_) => format!("`{}`", enum_.variant_name(&enum_.variants[value as usize]).value),
+						None => value.to_string(),
+					};
+					let previous = Box::new(previous.clone());
+					return Err(Error::DuplicatePattern { previous, duplicate: position, value });
+				}
+				matched.push((value, position));
+			}
+		}
+		match enum_ {
+			_ if match_.default.is_some() => (),
+			Some(enum_) => {
+				let missing: Vec<Symbol> = enum_
+					.variants
+					.iter()
+					.enumerate()
+					.filter(|(index, _)| !matched.iter().any(|(value, _)| *value == *index as i64))
+					.map(|(_, variant)| enum_.variant_name(variant).value)
+					.collect();
+				if !missing.is_empty() {
+					return Err(Error::MissingVariants { position: match_.position.clone(), missing });
+				}
+			},
+			None => return Err(Error::MissingDefaultArm { position: match_.position.clone() }),
+		}
+
+		for arm in match_.arms.iter().chain(&match_.default) {
+			self.call_stack.push(CallStackFrame::new());
+			for instruction in &arm.body {
+				self.instruction(instruction)?;
+			}
+			self.drop_call_stack_frame();
+		}
+
+		Ok(())
+	}
+
+	/// The value of the condition, if it is known at compile time.
+	fn constant_condition(&self, condition: &Expression) -> Option<bool> {
+		match const_fold::fold(condition, &|name| self.constant_value(name))? {
+			Constant::Bool(bool) => Some(bool),
+			_ => None,
+		}
+	}
+
+	/// Warns that a branch is never taken, because the `condition` is always `value`. `branch` names the branch in the
+	/// message.
+	fn warn_never_taken(&self, condition: &Expression, value: bool, branch: &str) {
+		let message = format!("The condition is always {}, so {} never executed", value, branch);
+		self.diagnostics.warn("W0021", message, Some(&condition.source_position()));
+	}
+
+	/// Checks that the condition of an if-else or loop is a boolean.
+	fn condition(&self, condition: &Expression) -> Result<(), Error> {
+		let condition_type = self.infer_expression_type(condition)?;
+		if condition_type != DataType::Basic(BasicDataType::Bool) {
+			return Err(Error::TypeMismatch {
+				expected: DataType::Basic(BasicDataType::Bool),
+				position: condition.source_position(),
+				actual: condition_type,
+			});
+		}
+		Ok(())
+	}
+
 	/// Infers the type of an expression, which can consist of binary expressions, numbers, function calls and variables.
 	pub fn infer_expression_type(&self, expression: &Expression) -> Result<DataType, Error> {
 		match expression {
 			Expression::BinaryExpression(binary_expression) => self.infer_binary_expression_type(binary_expression),
-			Expression::FunctionCall(function_call) => self.infer_function_call_return_type(function_call),
+			Expression::UnaryExpression(unary_expression) => self.infer_unary_expression_type(unary_expression),
+			Expression::Cast(cast) => self.infer_cast_type(cast),
+			Expression::FunctionCall(function_call) => self
+				.infer_function_call_return_type(function_call)?
+				.ok_or_else(|| Error::MissingReturnValue { function_call: Box::new(function_call.clone()) }),
+			Expression::FieldAccess(field_access) => self.infer_field_access_type(field_access),
+			Expression::StructLiteral(struct_literal) => self.infer_struct_literal_type(struct_literal),
+			Expression::ArrayLiteral(array_literal) => self.infer_array_literal_type(array_literal),
+			Expression::IndexExpression(index_expression) => self.infer_index_expression_type(index_expression),
+			Expression::If(if_expression) => self.infer_if_expression_type(if_expression),
 			Expression::Number(number) => Self::number_type_inference(number),
+			Expression::Bool(_) => Ok(DataType::Basic(BasicDataType::Bool)),
+			Expression::StringLiteral(_) => Ok(DataType::Basic(BasicDataType::Str)),
+			Expression::Char(_) => Ok(DataType::Basic(BasicDataType::Char)),
+			Expression::Null(_) => Ok(DataType::Null),
 			Expression::Variable(variable) => {
 				// Here, a variables is used inside an expression. This is not about a variable declaration.
 				self.infer_variable_type(variable)
@@ -249,64 +874,475 @@ impl TypeChecker {
 	}
 
 	/// Infers the type of the left-hand and right-hand side of a binary expression,
-	/// verifies that they are equal and returns this common type.
+	/// verifies that they are equal basic types and returns the type of the result.
+	/// Infers the type of an if-expression, whose condition has to be a boolean and whose branches have to have the
+	/// same type. If one branch is `null` and the other a pointer, the if-expression is the pointer.
+	fn infer_if_expression_type(&self, if_expression: &IfExpression) -> Result<DataType, Error> {
+		self.condition(&if_expression.condition)?;
+		let if_true = self.infer_expression_type(&if_expression.if_true)?;
+		let if_false = self.infer_expression_type(&if_expression.if_false)?;
+		if if_true.accepts(&if_false) {
+			Ok(if_true)
+		} else if if_false.accepts(&if_true) {
+			Ok(if_false)
+		} else {
+			Err(Error::TypeMismatch {
+				expected: if_true,
+				position: if_expression.if_false.source_position(),
+				actual: if_false,
+			})
+		}
+	}
+
 	fn infer_binary_expression_type(&self, binary_expression: &BinaryExpression) -> Result<DataType, Error> {
 		let lhs = self.infer_expression_type(&binary_expression.lhs)?;
 		let rhs = self.infer_expression_type(&binary_expression.rhs)?;
-		if lhs != rhs {
+		let operator = &binary_expression.operator;
+		let valid_operand = match (&lhs, &operator.value) {
+			// Booleans can only be compared for equality and combined logically
+			(
+				DataType::Basic(BasicDataType::Bool),
+				BinaryOperator::Equal
+				| BinaryOperator::NotEqual
+				| BinaryOperator::LogicalAnd
+				| BinaryOperator::LogicalOr,
+			) => true,
+			(DataType::Basic(BasicDataType::Bool), _) => false,
+			(_, BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr) => false,
+			// Bits can only be manipulated in integers
+			(data_type, operator) if operator.is_bitwise() => *data_type == DataType::Basic(BasicDataType::Int),
+			// Not every backend can compare strings yet
+			(DataType::Basic(BasicDataType::Str), _) => false,
+			// Characters can be compared, e.g. to check for a range like `'a' <= c and c <= 'z'`, but not calculated with
+			(DataType::Basic(BasicDataType::Char), operator) => operator.is_comparison(),
+			(DataType::Basic(_), _) => true,
+			// Pointers can only be compared for equality, e.g. to check whether they are `null`, and so can variants
+			(
+				DataType::Pointer(_) | DataType::Null | DataType::Enum(_),
+				BinaryOperator::Equal | BinaryOperator::NotEqual,
+			) => true,
+			_ => false,
+		};
+		if !valid_operand {
+			return Err(Error::InvalidOperand { operator: operator.clone(), data_type: lhs });
+		}
+		if !lhs.accepts(&rhs) && !rhs.accepts(&lhs) {
 			return Err(Error::TypeMismatch {
 				expected: lhs,
 				position: binary_expression.operator.position.clone(),
 				actual: rhs,
 			});
 		}
-		Ok(lhs)
+
+		Ok(match binary_expression.operator.value {
+			BinaryOperator::Add
+			| BinaryOperator::Subtract
+			| BinaryOperator::Multiply
+			| BinaryOperator::Divide
+			| BinaryOperator::Power
+			| BinaryOperator::BitOr
+			| BinaryOperator::BitAnd
+			| BinaryOperator::BitXor
+			| BinaryOperator::ShiftLeft
+			| BinaryOperator::ShiftRight => lhs,
+			BinaryOperator::Less
+			| BinaryOperator::Greater
+			| BinaryOperator::LessEqual
+			| BinaryOperator::GreaterEqual
+			| BinaryOperator::Equal
+			| BinaryOperator::NotEqual
+			| BinaryOperator::LogicalAnd
+			| BinaryOperator::LogicalOr => DataType::Basic(BasicDataType::Bool),
+		})
+	}
+
+	/// Infers the type of the operand of a unary expression, which is also the type of the result, and verifies that
+	/// the operator can be applied to it. Only the dereference of a pointer has the type the pointer points to.
+	fn infer_unary_expression_type(&self, unary_expression: &UnaryExpression) -> Result<DataType, Error> {
+		let operand = self.infer_expression_type(&unary_expression.operand)?;
+		if let (UnaryOperator::Dereference, DataType::Pointer(pointee)) = (&unary_expression.operator.value, &operand) {
+			return Ok(pointee.value.clone());
+		}
+		let valid_operand = matches!(
+			(&unary_expression.operator.value, &operand),
+			(UnaryOperator::Negate, DataType::Basic(BasicDataType::Int | BasicDataType::Float))
+				| (UnaryOperator::Not, DataType::Basic(BasicDataType::Bool))
+		);
+		if !valid_operand {
+			return Err(Error::InvalidUnaryOperand { operator: unary_expression.operator.clone(), data_type: operand });
+		}
+		Ok(operand)
+	}
+
+	/// Checks that the value of the cast expression can be converted to the type it is cast to, which is the type of
+	/// the result. Only numbers can be converted, i.e. `int` to `float` and vice versa, an enum to the `int` index of
+	/// its variant, and every type to itself.
+	fn infer_cast_type(&self, cast: &Cast) -> Result<DataType, Error> {
+		self.resolve_data_type(&cast.data_type)?;
+		let from = self.infer_expression_type(&cast.expression)?;
+		let number =
+			|data_type: &DataType| matches!(data_type, DataType::Basic(BasicDataType::Int | BasicDataType::Float));
+		let index = matches!(from, DataType::Enum(_)) && cast.data_type.value == DataType::Basic(BasicDataType::Int);
+		if from != cast.data_type.value && !(number(&from) && number(&cast.data_type.value)) && !index {
+			return Err(Error::InvalidCast {
+				position: cast.source_position(),
+				from,
+				to: cast.data_type.value.clone(),
+			});
+		}
+		Ok(cast.data_type.value.clone())
+	}
+
+	/// Infers the type of the accessed field by looking up the struct of the base in the
+	/// [symbol table](Self::symbol_table).
+	fn infer_field_access_type(&self, field_access: &FieldAccess) -> Result<DataType, Error> {
+		let base = self.infer_expression_type(&field_access.base)?;
+		self.field_type(
+			self.field_base(base, &field_access.field),
+			&field_access.field,
+			field_access.base.source_position(),
+		)
+	}
+
+	/// The type whose `field` is accessed on a `base`: The struct a pointer points to, or the `base` itself. A field
+	/// access through a pointer is recorded, so that [`generic::monomorphize`] desugars it for the backends.
+	fn field_base(&self, base: DataType, field: &PositionContainer<Symbol>) -> DataType {
+		match base {
+			DataType::Pointer(pointee) if matches!(pointee.value, DataType::Struct(_) | DataType::Generic { .. }) => {
+				let field_access = (self.function_name, field.position.clone());
+				self.instances.lock().unwrap().dereferences.insert(field_access);
+				pointee.value
+			},
+			base => base,
+		}
+	}
+
+	/// Looks up the type of the `field` of the struct type `base`, which is at `base_position`.
+	fn field_type(
+		&self,
+		base: DataType,
+		field: &PositionContainer<Symbol>,
+		base_position: SourcePositionRange,
+	) -> Result<DataType, Error> {
+		let (struct_name, type_arguments) = match base {
+			DataType::Struct(struct_name) => (struct_name, Vec::new()),
+			DataType::Generic { name, type_arguments } => (name, type_arguments),
+			base => return Err(Error::NoStruct { field: field.clone(), data_type: base }),
+		};
+		let Some(struct_) = self.symbol_table.structs.get(&struct_name) else {
+			let data_type = PositionContainer::new(DataType::Struct(struct_name), base_position);
+			return Err(Error::UnknownStruct { data_type });
+		};
+		// The type parameters in the field type of a generic struct stand for the type arguments of the base
+		let bindings = struct_.type_parameters.iter().map(|type_parameter| type_parameter.value).zip(type_arguments);
+		let bindings: HashMap<_, _> = bindings.collect();
+		struct_
+			.fields
+			.iter()
+			.find(|struct_field| struct_field.name.value == field.value)
+			.map(|struct_field| {
+				let mut data_type = struct_field.data_type.value.clone();
+				generic::substitute(&mut data_type, &bindings);
+				data_type
+			})
+			.ok_or_else(|| Error::UnknownField { field: field.clone(), struct_name })
+	}
+
+	/// Checks that every field of the struct gets exactly one value of the type of the field.
+	///
+	/// The type arguments of a generic struct are inferred from the values of the fields, like those of a generic
+	/// function from the parameters of a call.
+	fn infer_struct_literal_type(&self, struct_literal: &StructLiteral) -> Result<DataType, Error> {
+		let name = &struct_literal.name;
+		let Some(struct_) = self.symbol_table.structs.get(&name.value) else {
+			let data_type = PositionContainer::new(DataType::Struct(name.value), name.position.clone());
+			return Err(Error::UnknownStruct { data_type });
+		};
+
+		let mut fields = Vec::with_capacity(struct_literal.fields.len());
+		let mut type_arguments = HashMap::new();
+		for (i, field_value) in struct_literal.fields.iter().enumerate() {
+			if struct_literal.fields[..i].iter().any(|previous| previous.name.value == field_value.name.value) {
+				return Err(Error::DuplicateField { field: field_value.name.clone() });
+			}
+			let Some(field) = struct_.fields.iter().find(|field| field.name.value == field_value.name.value) else {
+				return Err(Error::UnknownField { field: field_value.name.clone(), struct_name: name.value });
+			};
+			let actual = self.infer_expression_type(&field_value.value)?;
+			generic::infer_type_arguments(
+				&struct_.type_parameters,
+				&field.data_type.value,
+				&actual,
+				&mut type_arguments,
+			);
+			fields.push((field, field_value, actual));
+		}
+		for type_parameter in &struct_.type_parameters {
+			if !type_arguments.contains_key(&type_parameter.value) {
+				return Err(Error::UninferredTypeParameter {
+					type_parameter: type_parameter.value,
+					name: name.clone(),
+				});
+			}
+		}
+		for (field, field_value, actual) in fields {
+			let mut expected = field.data_type.value.clone();
+			generic::substitute(&mut expected, &type_arguments);
+			if !expected.accepts(&actual) {
+				return Err(Error::TypeMismatch { expected, position: field_value.value.source_position(), actual });
+			}
+		}
+
+		let missing_field = struct_
+			.fields
+			.iter()
+			.find(|field| !struct_literal.fields.iter().any(|field_value| field_value.name.value == field.name.value));
+		if let Some(missing_field) = missing_field {
+			return Err(Error::MissingField { struct_name: name.clone(), field: missing_field.name.value });
+		}
+		if !struct_.is_generic() {
+			return Ok(DataType::Struct(name.value));
+		}
+		let instance = Instance {
+			generic: name.value,
+			type_arguments: struct_
+				.type_parameters
+				.iter()
+				.map(|type_parameter| type_arguments[&type_parameter.value].clone())
+				.collect(),
+		};
+		let data_type = DataType::Generic { name: name.value, type_arguments: instance.type_arguments.clone() };
+		let mut instances = self.instances.lock().unwrap();
+		instances.literals.insert((self.function_name, name.position.clone()), instance.specialized_name());
+		instances.structs.insert(instance.specialized_name(), instance);
+		Ok(data_type)
+	}
+
+	/// Infers the type of the array from its first element and checks that all other elements have the same type.
+	fn infer_array_literal_type(&self, array_literal: &ArrayLiteral) -> Result<DataType, Error> {
+		let Some((first, rest)) = array_literal.elements.split_first() else {
+			return Err(Error::EmptyArrayLiteral { position: array_literal.position.clone() });
+		};
+		let element = self.infer_expression_type(first)?;
+		for other in rest {
+			let actual = self.infer_expression_type(other)?;
+			if actual != element {
+				return Err(Error::TypeMismatch { expected: element, position: other.source_position(), actual });
+			}
+		}
+		Ok(DataType::Array { element: Box::new(element), length: array_literal.elements.len() })
 	}
 
-	/// Infers the type of a variable by looking it up in [`Self::variables`].
-	fn infer_variable_type(&self, variable: &PositionContainer<String>) -> Result<DataType, Error> {
-		self.variables
-			.get(&variable.value)
+	/// Infers the element type of the indexed array and checks that the index is an integer.
+	fn infer_index_expression_type(&self, index_expression: &IndexExpression) -> Result<DataType, Error> {
+		let base = self.infer_expression_type(&index_expression.base)?;
+		let DataType::Array { element, .. } = base else {
+			return Err(Error::NoArray { position: index_expression.base.source_position(), data_type: base });
+		};
+		self.check_index(&index_expression.index)?;
+		Ok(*element)
+	}
+
+	/// Infers the type of a variable by looking it up in [`Self::call_stack`].
+	fn infer_variable_type(&self, variable: &PositionContainer<Symbol>) -> Result<DataType, Error> {
+		self.lookup_variable(variable.value)
+			.inspect(|v| v.used.store(true, Ordering::Relaxed))
 			.map(|v| v.type_.clone())
-			.ok_or(Error::UndeclaredVariable { name: variable.clone() })
+			.ok_or_else(|| self.undeclared_variable(variable))
 	}
 
-	/// Looks up the return type of the function and thereby checks that the types of the parameters supplied in the `function_call`
+	/// The error for the undeclared `variable`, which is an unknown variant if it is qualified by an enum like
+	/// `Color.Purple`.
+	fn undeclared_variable(&self, variable: &PositionContainer<Symbol>) -> Error {
+		match variable.rsplit_once('.') {
+			Some((enum_name, variant)) if self.symbol_table.enums.contains_key(&Symbol::intern(enum_name)) => {
+				Error::UnknownVariant {
+					variant: PositionContainer::new(Symbol::intern(variant), variable.position.clone()),
+					enum_name: Symbol::intern(enum_name),
+				}
+			},
+			_ => Error::UndeclaredVariable { name: variable.clone() },
+		}
+	}
+
+	/// Looks up the return type of the function, which is [`None`] if it has no return value, and thereby checks that the types of the parameters supplied in the `function_call`
 	/// match the types of the arguments of the defined function in the [symbol table](Self::symbol_table).
-	fn infer_function_call_return_type(&self, function_call: &FunctionCall) -> Result<DataType, Error> {
+	fn infer_function_call_return_type(&self, function_call: &FunctionCall) -> Result<Option<DataType>, Error> {
+		if let Some(receiver) = &function_call.receiver {
+			return self.infer_method_call_return_type(receiver, function_call.name.clone(), function_call);
+		}
+
 		// Get function definition
 		let function_definition = self.symbol_table.functions.get(&function_call.name.value);
 		let Some(function_definition) = function_definition else {
-			return Err(Error::UndefinedFunctionCall { function_call: function_call.clone() });
+			return match Builtin::from_name(&function_call.name) {
+				Some(builtin) => self.infer_builtin_call_return_type(builtin, function_call),
+				None => match self.variable_receiver(&function_call.name) {
+					Some((receiver, method)) => self.infer_method_call_return_type(&receiver, method, function_call),
+					None => Err(Error::UndefinedFunctionCall { function_call: Box::new(function_call.clone()) }),
+				},
+			};
 		};
 
-		// Check that the number of supplied parameters matches the number of expected arguments.
-		// Since the later used `iter::zip` returns None if one of the iterators is shorter than the other, we need to check the lengths first.
+		// Check the number of supplied parameters and the type of each of them, collecting all errors of the call
+		let mut errors = Vec::new();
 		if function_call.params.len() != function_definition.args.len() {
-			return Err(Error::ArgumentCountMismatch {
+			errors.push(Error::ArgumentCountMismatch {
 				expected: function_definition.args.len(),
 				actual: function_call.params.len(),
-				function_call: function_call.clone(),
+				function_call: Box::new(function_call.clone()),
 			});
 		}
+		let param_types: Vec<_> = function_call
+			.params
+			.iter()
+			.map(|param| self.infer_expression_type(param).map_err(|error| errors.push(error)).ok())
+			.collect();
 
-		// Check that the types of supplied parameters and expected arguments match.
-		for (param, arg) in iter::zip(&function_call.params, &function_definition.args) {
-			let param_type = self.infer_expression_type(param)?;
-			if param_type != arg.data_type.value {
-				return Err(Error::TypeMismatch {
-					expected: arg.data_type.value.clone(),
+		// The type arguments of a generic function are inferred from the parameters, and replace the type parameters in
+		// the types of the arguments
+		let mut type_arguments = HashMap::new();
+		for (arg, param_type) in function_definition.args.iter().zip(&param_types) {
+			if let Some(param_type) = param_type {
+				generic::infer_type_arguments(
+					&function_definition.type_parameters,
+					&arg.data_type.value,
+					param_type,
+					&mut type_arguments,
+				);
+			}
+		}
+		if errors.is_empty() {
+			for type_parameter in &function_definition.type_parameters {
+				if !type_arguments.contains_key(&type_parameter.value) {
+					errors.push(Error::UninferredTypeParameter {
+						type_parameter: type_parameter.value,
+						name: function_call.name.clone(),
+					});
+				}
+			}
+		}
+
+		// Parameters without a matching argument are only checked for errors in themselves
+		for (i, (param, param_type)) in function_call.params.iter().zip(param_types).enumerate() {
+			let (Some(param_type), Some(arg)) = (param_type, function_definition.args.get(i)) else {
+				continue;
+			};
+			let mut arg = arg.clone();
+			generic::substitute(&mut arg.data_type.value, &type_arguments);
+			if !arg.data_type.value.accepts(&param_type) {
+				errors.push(Error::ArgumentTypeMismatch {
+					argument: Box::new(arg),
 					position: param.source_position(),
 					actual: param_type,
 				});
 			}
 		}
+		match errors.len() {
+			0 => (),
+			1 => return Err(errors.remove(0)),
+			_ => return Err(Error::Multiple(errors)),
+		}
 
-		Ok(function_definition
-			.return_type
-			.as_ref()
-			.map(|return_type| return_type.value.clone())
-			.expect("Function without return value not supported yet"))
+		let return_type = function_definition.return_type.as_ref().map(|return_type| {
+			let mut return_type = return_type.value.clone();
+			generic::substitute(&mut return_type, &type_arguments);
+			return_type
+		});
+		if function_definition.is_generic() {
+			let instance = Instance {
+				generic: function_call.name.value,
+				type_arguments: function_definition
+					.type_parameters
+					.iter()
+					.map(|type_parameter| type_arguments[&type_parameter.value].clone())
+					.collect(),
+			};
+			let call = (self.function_name, function_call.name.position.clone());
+			self.instances.lock().unwrap().calls.insert(call, instance);
+		}
+		Ok(return_type)
+	}
+
+	/// The receiver and the method of a call like `point.length()`, which is parsed as the call of a function
+	/// `point.length`, if `point` is a variable.
+	fn variable_receiver(&self, name: &PositionContainer<Symbol>) -> Option<(Expression, PositionContainer<Symbol>)> {
+		let (variable, method) = name.split_once('.')?;
+		self.lookup_variable(Symbol::intern(variable))?;
+		Some((
+			Expression::Variable(PositionContainer::new(Symbol::intern(variable), name.position.clone())),
+			PositionContainer::new(Symbol::intern(method), name.position.clone()),
+		))
+	}
+
+	/// Like [`Self::infer_function_call_return_type`], but for the call of the `method` of the `receiver`. The method
+	/// like `Point.length` belongs to the struct of the receiver or of the value it points to, and is called with the
+	/// receiver as first parameter.
+	fn infer_method_call_return_type(
+		&self,
+		receiver: &Expression,
+		method: PositionContainer<Symbol>,
+		method_call: &FunctionCall,
+	) -> Result<Option<DataType>, Error> {
+		let receiver_type = self.infer_expression_type(receiver)?;
+		let struct_name = match &receiver_type {
+			DataType::Pointer(pointee) => match &pointee.value {
+				DataType::Struct(name) | DataType::Generic { name, .. } => Some(*name),
+				_ => None,
+			},
+			DataType::Struct(name) | DataType::Generic { name, .. } => Some(*name),
+			_ => None,
+		};
+		let name = struct_name.map(|struct_name| qualified_name(&struct_name, &method.value));
+		let Some(name) = name.filter(|name| self.symbol_table.functions.contains_key(name)) else {
+			return Err(Error::UnknownMethod { method, data_type: receiver_type });
+		};
+
+		let function_call = FunctionCall {
+			name: PositionContainer::new(name, method_call.name.position.clone()),
+			receiver: None,
+			params: iter::once(receiver.clone()).chain(method_call.params.iter().cloned()).collect(),
+			position: method_call.position.clone(),
+		};
+		let return_type = self.infer_function_call_return_type(&function_call)?;
+		let call = (self.function_name, method_call.name.position.clone());
+		self.instances.lock().unwrap().methods.insert(call, name);
+		Ok(return_type)
+	}
+
+	/// Like [`Self::infer_function_call_return_type`], but for a call of a [`Builtin`].
+	fn infer_builtin_call_return_type(
+		&self,
+		builtin: Builtin,
+		function_call: &FunctionCall,
+	) -> Result<Option<DataType>, Error> {
+		let mut errors = Vec::new();
+		if function_call.params.len() != builtin.arity() {
+			errors.push(Error::ArgumentCountMismatch {
+				expected: builtin.arity(),
+				actual: function_call.params.len(),
+				function_call: Box::new(function_call.clone()),
+			});
+		}
+		// Like for functions, parameters without a matching argument are only checked for errors in themselves
+		for (i, param) in function_call.params.iter().enumerate() {
+			match self.infer_expression_type(param) {
+				Ok(param_type) if i < builtin.arity() && !builtin.accepts(&param_type) => {
+					errors.push(Error::InvalidBuiltinArgument {
+						builtin,
+						position: param.source_position(),
+						actual: param_type,
+					})
+				},
+				Ok(_) => (),
+				Err(error) => errors.push(error),
+			}
+		}
+		match errors.len() {
+			0 => Ok(builtin.return_type()),
+			1 => Err(errors.remove(0)),
+			_ => Err(Error::Multiple(errors)),
+		}
 	}
 
 	/// Infers the type of a number expression.
@@ -317,3 +1353,13 @@ impl TypeChecker {
 		}
 	}
 }
+
+/// Whether the loop body contains a `break` that leaves the loop, i.e. one that is not inside a nested loop.
+fn breaks(body: &[ast::Instruction]) -> bool {
+	body.iter().any(|instruction| match instruction {
+		ast::Instruction::Statement(ast::Statement::Break(_)) => true,
+		ast::Instruction::IfElse(if_else) => breaks(&if_else.if_true) || breaks(&if_else.if_false),
+		ast::Instruction::Match(match_) => match_.arms.iter().chain(&match_.default).any(|arm| breaks(&arm.body)),
+		_ => false,
+	})
+}
@@ -3,9 +3,12 @@ use std::{
 	fmt::Formatter,
 	hash::{Hash, Hasher},
 	ops::Deref,
+	sync::atomic::AtomicBool,
 };
 
-use crate::{ast::statement::DataType, source::PositionContainer};
+use crate::{
+	ast::statement::DataType, semantic_analyzer::const_fold::Constant, source::PositionContainer, symbol::Symbol,
+};
 
 /// Stores the name and type of a currently in-scope variable in the call stack.
 ///
@@ -14,9 +17,17 @@ use crate::{ast::statement::DataType, source::PositionContainer};
 #[derive(Debug)]
 pub struct Variable {
 	/// The name of the variable and the position of the declaration.
-	pub name: PositionContainer<String>,
+	pub name: PositionContainer<Symbol>,
 	/// The type of the variable.
 	pub type_: DataType,
+	/// Whether the variable was declared with `const`, so that it cannot be assigned to.
+	pub constant: bool,
+	/// Whether the variable is a parameter of the function, which is reported differently if it is unused.
+	pub parameter: bool,
+	/// The value of a constant, if it is known at compile time.
+	pub value: Option<Constant>,
+	/// Whether the value of the variable is read anywhere, which is tracked to warn about unused variables.
+	pub used: AtomicBool,
 }
 
 impl fmt::Display for Variable {
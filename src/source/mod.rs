@@ -43,6 +43,34 @@ impl Source {
 		Self { name, text: text.chars().collect() }
 	}
 
+	/// Calculates the line and column of the char at `offset`.
+	///
+	/// ```
+	/// use fortytwolang::source::{Position, Source};
+	///
+	/// let source = Source::new("file.name".to_owned(), "ab\ncd".to_owned());
+	/// assert_eq!(source.position(4), Position { line: 2, column: 2, offset: 4 });
+	/// ```
+	pub fn position(&self, offset: usize) -> Position {
+		let before = &self.text[..offset];
+		let line = before.iter().filter(|char| **char == '\n').count() + 1;
+		let line_start = before.iter().rposition(|char| *char == '\n').map_or(0, |newline| newline + 1);
+		Position { line, column: offset - line_start + 1, offset }
+	}
+
+	/// Calculates the byte offset of the char at `offset` in the UTF-8 encoded text, e.g. for editors addressing the
+	/// text by bytes.
+	///
+	/// ```
+	/// use fortytwolang::source::Source;
+	///
+	/// let source = Source::new("file.name".to_owned(), "\"Größe\"".to_owned());
+	/// assert_eq!(source.byte_offset(4), 5);
+	/// ```
+	pub fn byte_offset(&self, offset: usize) -> usize {
+		self.text[..offset].iter().map(|char| char.len_utf8()).sum()
+	}
+
 	/// Creates an iterator over the [`Symbol`]s of the source code.
 	///
 	/// # Example
@@ -69,7 +97,23 @@ impl Source {
 	/// assert_eq!(iter.next(), expected);
 	/// ```
 	pub fn iter(self: Arc<Self>) -> impl Iterator<Item = Symbol> {
-		SourceIter { source: self, position: Position::default() }
+		SourceIter::new(self)
+	}
+
+	/// Like [`iter`](Self::iter), but starts at the char at `offset`, e.g. to lex only a part of the source code.
+	///
+	/// ```
+	/// use std::sync::Arc;
+	///
+	/// use fortytwolang::source::{Position, Source};
+	///
+	/// let source = Arc::new(Source::new("file.name".to_owned(), "ab\ncd".to_owned()));
+	/// let symbol = source.iter_from(4).next().unwrap();
+	/// assert_eq!((symbol.value, symbol.position.position.start), ('d', Position { line: 2, column: 2, offset: 4 }));
+	/// ```
+	pub fn iter_from(self: Arc<Self>, offset: usize) -> impl Iterator<Item = Symbol> {
+		let position = self.position(offset);
+		SourceIter { source: self, position }
 	}
 }
 
@@ -83,11 +127,19 @@ impl fmt::Debug for Source {
 pub type Symbol = PositionContainer<char>;
 
 /// Iterator over the chars of a source code.
-struct SourceIter {
+pub(crate) struct SourceIter {
 	source: Arc<Source>,
 	position: Position,
 }
 
+impl SourceIter {
+	/// Creates an iterator starting at the first char of the `source`, like [`Source::iter`], but whose type can be
+	/// named, e.g. in the field of a struct.
+	pub(crate) fn new(source: Arc<Source>) -> Self {
+		Self { source, position: Position::default() }
+	}
+}
+
 impl Iterator for SourceIter {
 	type Item = Symbol;
 
@@ -2,12 +2,15 @@ use std::fmt;
 
 /// Line and column in source code.
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
 	/// Line in the source code file.
 	pub line: usize,
 	/// Column in the [line](Self::line) in the source code file.
 	pub column: usize,
-	/// Byte offset from the start of the source code file.
+	/// Offset of the char from the start of the source code file, i.e. its index in
+	/// [`Source::text`](super::Source::text). The offset in bytes is calculated by
+	/// [`Source::byte_offset`](super::Source::byte_offset).
 	pub offset: usize,
 }
 
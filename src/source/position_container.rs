@@ -4,6 +4,7 @@ use crate::source::source_position::SourcePositionRange;
 
 /// Wrapper for values inside source code with position information.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PositionContainer<T> {
 	/// Where the [`value`](Self::value) is located in the source code.
 	pub position: SourcePositionRange,
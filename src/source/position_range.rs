@@ -4,6 +4,7 @@ use crate::source::Position;
 
 /// Position in the source code ranging from start to end (both inclusive).
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PositionRange {
 	pub start: Position,
 	pub end: Position,
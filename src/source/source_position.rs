@@ -3,9 +3,11 @@ use std::{fmt, sync::Arc};
 use crate::source::{position_range::PositionRange, Source};
 
 /// Position in the source code ranging from start to end (both inclusive).
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourcePositionRange {
-	/// Source code name and text.
+	/// Source code name and text. Only the name is serialized, so a deserialized source has no text.
+	#[cfg_attr(feature = "serde", serde(with = "source_name"))]
 	pub source: Arc<Source>,
 	/// Position range in the [source code](Self::source).
 	pub position: PositionRange,
@@ -23,6 +25,14 @@ impl SourcePositionRange {
 	pub fn get_affected_code(&self) -> String {
 		self.source.text[self.position.start.offset..=self.position.end.offset].iter().collect::<String>()
 	}
+
+	/// Returns the position range from the start of this range to the end of `end`, e.g. to span a node from its first
+	/// to its last token.
+	pub fn until(&self, end: &SourcePositionRange) -> SourcePositionRange {
+		let mut position = self.clone();
+		position.position.end = end.position.end;
+		position
+	}
 }
 
 impl fmt::Display for SourcePositionRange {
@@ -31,6 +41,30 @@ impl fmt::Display for SourcePositionRange {
 	}
 }
 
+/// Only shows the name of the source and the range, since the AST contains lots of positions.
+impl fmt::Debug for SourcePositionRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}", self.source.name, self.position)
+	}
+}
+
+/// Serializes a source as its name, since its text would be repeated for every position.
+#[cfg(feature = "serde")]
+mod source_name {
+	use std::sync::Arc;
+
+	use crate::source::Source;
+
+	pub fn serialize<S: serde::Serializer>(source: &Arc<Source>, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&source.name)
+	}
+
+	pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Arc<Source>, D::Error> {
+		let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Ok(Arc::new(Source::new(name, String::new())))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
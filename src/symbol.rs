@@ -0,0 +1,204 @@
+//! Interned identifiers.
+//!
+//! Names of variables, functions, structs and fields occur many times in the tokens and the AST. Each distinct name is
+//! stored once in a global interner, and a [`Symbol`] refers to it by its index, so that it is cheap to copy and to
+//! compare.
+
+use std::{
+	cmp::Ordering,
+	collections::HashMap,
+	fmt, ops,
+	sync::{Mutex, OnceLock},
+};
+
+/// An interned string, usually an identifier. Symbols are equal if their strings are, and they are ordered like
+/// their strings.
+///
+/// ```
+/// use fortytwolang::symbol::Symbol;
+///
+/// let name = Symbol::intern("main");
+/// assert_eq!(name, Symbol::from("main"));
+/// assert_eq!(name.as_str(), "main");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+/// The symbols of the strings which were interned. Interning locks it, reading the string of a symbol does not.
+#[derive(Default)]
+struct Interner {
+	symbols: HashMap<&'static str, Symbol>,
+}
+
+fn interner() -> &'static Mutex<Interner> {
+	static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+	INTERNER.get_or_init(Default::default)
+}
+
+/// A bucket of [`STRINGS`], whose slots are filled once.
+type Bucket = [OnceLock<&'static str>];
+
+/// The strings of all symbols, which live as long as the program, so that [`Symbol::as_str`] can return them. They
+/// are only appended, into buckets of doubling size which are never moved, so that they can be read without locking.
+static STRINGS: [OnceLock<Box<Bucket>>; BUCKETS] = [const { OnceLock::new() }; BUCKETS];
+
+/// Number of buckets needed for all `u32` indices.
+const BUCKETS: usize = u32::BITS as usize + 1;
+
+/// The slot of the string of the symbol with the `index` in [`STRINGS`], allocating its bucket if it is new.
+fn slot(index: u32) -> &'static OnceLock<&'static str> {
+	// Bucket `b` holds the 2^b indices from 2^b - 1 on
+	let position = u64::from(index) + 1;
+	let bucket = position.ilog2();
+	let slots = STRINGS[bucket as usize].get_or_init(|| (0..1u64 << bucket).map(|_| OnceLock::new()).collect());
+	&slots[(position - (1 << bucket)) as usize]
+}
+
+impl Symbol {
+	/// The symbol of the `string`, which is stored in the interner if it is new.
+	pub fn intern(string: &str) -> Self {
+		let mut interner = interner().lock().expect("Interner poisoned");
+		if let Some(&symbol) = interner.symbols.get(string) {
+			return symbol;
+		}
+		let string: &'static str = Box::leak(string.to_owned().into_boxed_str());
+		let symbol = Symbol(u32::try_from(interner.symbols.len()).expect("Too many symbols"));
+		// The string is stored before the symbol exists, so that it is found by `as_str`
+		slot(symbol.0).get_or_init(|| string);
+		interner.symbols.insert(string, symbol);
+		symbol
+	}
+
+	/// The interned string.
+	pub fn as_str(self) -> &'static str {
+		slot(self.0).get().expect("Symbol not interned")
+	}
+}
+
+impl ops::Deref for Symbol {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl From<&str> for Symbol {
+	fn from(string: &str) -> Self {
+		Symbol::intern(string)
+	}
+}
+
+impl From<String> for Symbol {
+	fn from(string: String) -> Self {
+		Symbol::intern(&string)
+	}
+}
+
+impl From<Symbol> for String {
+	fn from(symbol: Symbol) -> Self {
+		symbol.as_str().to_owned()
+	}
+}
+
+impl PartialEq<str> for Symbol {
+	fn eq(&self, other: &str) -> bool {
+		self.as_str() == other
+	}
+}
+
+impl PartialEq<&str> for Symbol {
+	fn eq(&self, other: &&str) -> bool {
+		self.as_str() == *other
+	}
+}
+
+impl PartialEq<String> for Symbol {
+	fn eq(&self, other: &String) -> bool {
+		self.as_str() == other
+	}
+}
+
+impl PartialOrd for Symbol {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Symbol {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match self == other {
+			true => Ordering::Equal,
+			false => self.as_str().cmp(other.as_str()),
+		}
+	}
+}
+
+impl fmt::Display for Symbol {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl fmt::Debug for Symbol {
+	/// Formats the symbol like its string, so that e.g. the AST is printed with the names.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(self.as_str(), f)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Symbol {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer).map(Symbol::from)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Symbol;
+
+	/// Tests that the same string is interned once and that different strings get different symbols.
+	#[test]
+	fn test_intern() {
+		let a = Symbol::intern("symbol_test_a");
+		assert_eq!(a, Symbol::intern(&String::from("symbol_test_a")));
+		assert_ne!(a, Symbol::intern("symbol_test_b"));
+		assert_eq!(a.as_str(), "symbol_test_a");
+		assert_eq!(format!("{} {:?}", a, a), "symbol_test_a \"symbol_test_a\"");
+	}
+
+	/// Tests that symbols are ordered like their strings, not like the order in which they were interned.
+	#[test]
+	fn test_order() {
+		let z = Symbol::intern("symbol_test_z");
+		let y = Symbol::intern("symbol_test_y");
+		assert!(y < z);
+	}
+
+	/// Tests that the strings of symbols interned by several threads at once are read back, across several buckets.
+	#[test]
+	fn test_concurrent_intern() {
+		let threads: Vec<_> = (0..4)
+			.map(|thread| {
+				std::thread::spawn(move || {
+					let names: Vec<_> = (0..300).map(|i| format!("symbol_test_{}_{}", thread, i)).collect();
+					let symbols: Vec<_> = names.iter().map(|name| Symbol::intern(name)).collect();
+					for (name, symbol) in names.iter().zip(symbols) {
+						assert_eq!(symbol.as_str(), name);
+					}
+				})
+			})
+			.collect();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+	}
+}
@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use crate::source::PositionContainer;
+use crate::{source::PositionContainer, symbol::Symbol};
 
 /// A [`TokenKind`] with its position in the source code.
 pub type Token = PositionContainer<TokenKind>;
@@ -14,8 +14,14 @@ pub enum TokenKind {
 	Def,
 	/// Keyword: Extern function declaration.
 	Extern,
+	/// Keyword: Boolean literal `true`.
+	True,
+	/// Keyword: Boolean literal `false`.
+	False,
+	/// Keyword: Null pointer literal `null`.
+	Null,
 	/// Function, variable name or data type.
-	Identifier(String),
+	Identifier(Symbol),
 	/// Floating point number.
 	Float(f64),
 	/// Integer number.
@@ -24,16 +30,24 @@ pub enum TokenKind {
 	Comment(String),
 	/// String literal, enclosed by `"`.
 	StringLiteral(String),
+	/// Character literal, enclosed by `'`.
+	Char(char),
 	/// `+`
 	Plus,
 	/// `*`
 	Star,
+	/// `**`
+	Power,
 	/// `-`
 	Minus,
 	/// `<`
 	Less,
 	/// `>`
 	Greater,
+	/// `<=`
+	LessEqual,
+	/// `>=`
+	GreaterEqual,
 	/// `(`
 	OpeningParentheses,
 	/// `)`
@@ -54,22 +68,52 @@ pub enum TokenKind {
 	Colon,
 	/// `/`
 	Slash,
+	/// `+=`
+	PlusEqual,
+	/// `-=`
+	MinusEqual,
+	/// `*=`
+	StarEqual,
+	/// `/=`
+	SlashEqual,
 	/// `=`
 	Equal,
 	/// `=/=`
 	NotEqual,
-	/// Bitwise OR
+	/// `=>` between the patterns and the body of a `match` arm
+	FatArrow,
+	/// `!`
+	Not,
+	/// Bitwise OR `|`
 	BitOr,
-	/// Bitwise AND
+	/// Bitwise AND `&`
 	BitAnd,
+	/// Bitwise XOR `^`
+	BitXor,
+	/// Shift left `<<`
+	ShiftLeft,
+	/// Arithmetic shift right `>>`
+	ShiftRight,
 	/// Modulus %
 	Modulus,
+	/// Logical AND `and`
+	LogicalAnd,
+	/// Logical OR `or`
+	LogicalOr,
 	/// If
 	If,
 	/// Else
 	Else,
 	/// `while` loop
 	While,
+	/// `for` loop
+	For,
+	/// `match`
+	Match,
+	/// `break` out of a loop
+	Break,
+	/// `continue` with the next iteration of a loop
+	Continue,
 	/// `.`
 	Dot,
 	/// End of line, i.e. `\n`.
@@ -78,10 +122,20 @@ pub enum TokenKind {
 	Pointer,
 	/// `struct`
 	Struct,
+	/// `enum`
+	Enum,
+	/// `type`
+	Type,
 	/// `var`
 	Var,
+	/// `const`
+	Const,
 	/// `return`
 	Return,
+	/// `as` in a cast like `x as float`
+	As,
+	/// `import`
+	Import,
 }
 
 impl fmt::Display for TokenKind {